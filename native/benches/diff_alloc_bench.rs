@@ -0,0 +1,92 @@
+//! Diff allocation benchmark.
+//!
+//! Repaints a full 80×24 screen every frame and counts heap allocations
+//! through a counting global allocator. The cell-update vector is kept on
+//! the context, so after the first frame has grown it, later frames no
+//! longer reallocate it; the allocation counts for the first frame and the
+//! steady state are printed before the timed run.
+//!
+//! Run with: cargo bench --manifest-path native/Cargo.toml --bench diff_alloc_bench
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use kraken_tui::types::NodeType;
+use kraken_tui::{
+    tui_create_node, tui_init_headless, tui_invalidate, tui_render, tui_set_content,
+    tui_set_layout_dimension, tui_set_root, tui_shutdown,
+};
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+const FRAMES: u64 = 100;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// A full-screen Text node so that every cell of a repaint is non-blank.
+fn build_screen() {
+    assert_eq!(tui_init_headless(WIDTH, HEIGHT), 0);
+    let root = tui_create_node(NodeType::Text as u8);
+    tui_set_layout_dimension(root, 0, 100.0, 2);
+    tui_set_layout_dimension(root, 1, 100.0, 2);
+    let text = "diff ".repeat(WIDTH as usize * HEIGHT as usize / 5);
+    tui_set_content(root, text.as_ptr(), text.len() as u32);
+    tui_set_root(root);
+}
+
+/// Force a full repaint and render it.
+fn full_frame() {
+    tui_invalidate();
+    tui_render();
+}
+
+fn bench_diff_allocations(c: &mut Criterion) {
+    build_screen();
+
+    let before = allocations();
+    full_frame();
+    let first = allocations() - before;
+    let before = allocations();
+    for _ in 0..FRAMES {
+        full_frame();
+    }
+    let steady = (allocations() - before) as f64 / FRAMES as f64;
+    println!(
+        "diff allocations: first frame {first}, steady state {steady:.1} per frame \
+         ({} cells each)",
+        WIDTH as u32 * HEIGHT as u32
+    );
+
+    c.bench_function("render_full_repaint_80x24", |b| b.iter(full_frame));
+
+    tui_shutdown();
+}
+
+criterion_group!(benches, bench_diff_allocations);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+//! Incremental layout benchmark.
+//!
+//! Builds a 100-row × 20-leaf tree and compares the layout cost of a frame
+//! where one leaf is resized (Taffy re-solves that leaf and its ancestors)
+//! against one where the root's padding changes (every row is re-solved).
+//! Only the layout stage is timed, via the `perf_layout_us` counter.
+//!
+//! Run with: cargo bench --manifest-path native/Cargo.toml --bench layout_bench
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use kraken_tui::types::NodeType;
+use kraken_tui::{
+    tui_append_child, tui_create_node, tui_get_perf_counter, tui_init_headless, tui_render,
+    tui_set_layout_dimension, tui_set_layout_edges, tui_set_layout_flex, tui_set_root,
+    tui_shutdown,
+};
+
+const ROWS: usize = 100;
+const LEAVES_PER_ROW: usize = 20;
+const PERF_LAYOUT_US: u32 = 0;
+
+/// Build the tree and return (root, one leaf in the middle of it).
+fn build_tree() -> (u32, u32) {
+    assert_eq!(tui_init_headless(200, 60), 0);
+    let root = tui_create_node(NodeType::Box as u8);
+    tui_set_layout_flex(root, 0, 1); // column
+    let mut middle_leaf = 0;
+    for row_index in 0..ROWS {
+        let row = tui_create_node(NodeType::Box as u8);
+        tui_set_layout_dimension(row, 1, 1.0, 1);
+        for leaf_index in 0..LEAVES_PER_ROW {
+            let leaf = tui_create_node(NodeType::Box as u8);
+            tui_set_layout_dimension(leaf, 0, 3.0, 1);
+            tui_append_child(row, leaf);
+            if row_index == ROWS / 2 && leaf_index == LEAVES_PER_ROW / 2 {
+                middle_leaf = leaf;
+            }
+        }
+        tui_append_child(root, row);
+    }
+    tui_set_root(root);
+    tui_render();
+    (root, middle_leaf)
+}
+
+/// Time only the layout stage of `iters` renders, each preceded by `mutate`.
+fn layout_time(iters: u64, mut mutate: impl FnMut(u64)) -> Duration {
+    let mut total = Duration::ZERO;
+    for i in 0..iters {
+        mutate(i);
+        tui_render();
+        total += Duration::from_micros(tui_get_perf_counter(PERF_LAYOUT_US));
+    }
+    total
+}
+
+fn bench_incremental_layout(c: &mut Criterion) {
+    let (root, leaf) = build_tree();
+
+    c.bench_function("layout_one_leaf_changed", |b| {
+        b.iter_custom(|iters| {
+            layout_time(iters, |i| {
+                tui_set_layout_dimension(leaf, 0, 3.0 + (i % 2) as f32, 1);
+            })
+        });
+    });
+
+    c.bench_function("layout_root_padding_changed", |b| {
+        b.iter_custom(|iters| {
+            layout_time(iters, |i| {
+                let pad = (i % 2) as f32;
+                tui_set_layout_edges(root, 0, pad, pad, pad, pad);
+            })
+        });
+    });
+
+    tui_shutdown();
+}
+
+criterion_group!(benches, bench_incremental_layout);
+criterion_main!(benches);
@@ -8,15 +8,38 @@
 //! - Delta-time advancement per ADR-T13
 //! - Built-in primitives: spinner, progress, pulse (TASK-H1)
 //! - Animation chaining: B starts when A completes (TASK-H2)
+//! - Global time scale for slow-motion debugging
+//! - Multi-stop keyframe animations
+//! - AnimationEnd / opt-in AnimationStart events into the event buffer
+//! - Damped-spring animations for f32 properties
+//! - FLIP position transitions (capture positions, then play from the delta)
 
 use crate::context::TuiContext;
-use crate::types::{color_tag, AnimProp, Easing, TuiNode, VisualStyle};
+use crate::types::{
+    color_tag, AnimProp, Easing, ErrorCode, KeyframeStop, NodeType, TuiError, TuiEvent, TuiNode,
+    VisualStyle,
+};
 use std::collections::{HashMap, HashSet};
 
+/// Upper bound for the global animation time scale.
+const MAX_TIME_SCALE: f32 = 100.0;
+
+/// Largest spring integration step (seconds); smaller for stiff or heavily
+/// damped springs.
+const SPRING_MAX_STEP_S: f32 = 1.0 / 120.0;
+/// Upper bound on integration steps per advance, so a huge delta cannot
+/// stall. A delta needing more snaps the spring to its target.
+const SPRING_MAX_STEPS: u32 = 10_000;
+/// A spring settles once displacement and velocity both fall below this.
+const SPRING_REST_EPSILON: f32 = 0.001;
+
 /// Spinner frame cycling state for the built-in spinner primitive.
 #[derive(Debug, Clone)]
 pub struct SpinnerState {
     pub frames: Vec<String>,
+    /// Non-empty → color spinner: cycles the node's fg color through these
+    /// and leaves its content alone (`frames` is empty).
+    pub colors: Vec<u32>,
     pub frame_idx: usize,
     pub interval_ms: u32,
     pub frame_elapsed: f32,
@@ -39,6 +62,82 @@ pub struct Animation {
     pub pending: bool,
     /// Some → spinner mode; cycles text content of the target node
     pub spinner: Option<SpinnerState>,
+    /// Some → keyframe mode; `start_bits` is the implicit value at t=0 and
+    /// `end_bits` mirrors the last stop
+    pub keyframes: Option<Vec<Keyframe>>,
+    /// Some → spring mode; physics replaces duration/easing
+    pub spring: Option<SpringState>,
+    /// Some → typewriter mode; reveals the target's content grapheme by
+    /// grapheme through `TuiNode::typewriter_reveal`
+    pub typewriter: Option<TypewriterState>,
+    /// Some → marquee mode; scrolls the target's text through
+    /// `TuiNode::text_marquee`, looping until cancelled
+    pub marquee: Option<MarqueeState>,
+}
+
+/// Marquee scroll position in cells; fractional so slow speeds accumulate.
+#[derive(Debug, Clone, Copy)]
+pub struct MarqueeState {
+    pub speed: f32,
+    pub gap: u32,
+    pub offset: f32,
+}
+
+/// Typewriter reveal progress: `revealed` is fractional so slow rates still
+/// accumulate across short frames.
+#[derive(Debug, Clone, Copy)]
+pub struct TypewriterState {
+    pub chars_per_sec: f32,
+    pub revealed: f32,
+}
+
+/// Damped-spring integration state. `value`/`velocity` are per-second units
+/// of the animated f32 property.
+#[derive(Debug, Clone, Copy)]
+pub struct SpringState {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub value: f32,
+    pub velocity: f32,
+}
+
+impl SpringState {
+    /// Integrate toward `target` (semi-implicit Euler). Returns true at rest.
+    /// The step stays inside the integrator's stability bound for both the
+    /// stiffness and the damping; a delta too long to cover in
+    /// `SPRING_MAX_STEPS` such steps lands on the target instead.
+    fn step(&mut self, target: f32, elapsed_ms: f32) -> bool {
+        let total_s = elapsed_ms / 1000.0;
+        let max_step = SPRING_MAX_STEP_S
+            .min(1.0 / self.stiffness.sqrt())
+            .min(1.0 / self.damping.max(1.0));
+        let steps = (total_s / max_step).ceil();
+        if steps > SPRING_MAX_STEPS as f32 {
+            self.value = target;
+            self.velocity = 0.0;
+            return true;
+        }
+        let steps = (steps as u32).max(1);
+        let dt = total_s / steps as f32;
+        // Integrate the displacement, which keeps full f32 precision near
+        // rest where tiny per-step moves would vanish against `target`.
+        let mut offset = self.value - target;
+        for _ in 0..steps {
+            let accel = -self.stiffness * offset - self.damping * self.velocity;
+            self.velocity += accel * dt;
+            offset += self.velocity * dt;
+        }
+        self.value = target + offset;
+        offset.abs() < SPRING_REST_EPSILON && self.velocity.abs() < SPRING_REST_EPSILON
+    }
+}
+
+/// A validated keyframe stop. `easing` shapes the segment ending at this stop.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f32,
+    pub bits: u32,
+    pub easing: Easing,
 }
 
 /// A choreography member links an animation handle to a group timeline offset.
@@ -155,6 +254,52 @@ fn interpolate(property: AnimProp, start_bits: u32, end_bits: u32, alpha: f32) -
     }
 }
 
+/// Sample a keyframe track at normalized time `t`. Before the first stop the
+/// value runs from `start_bits`; after the last stop it holds that stop.
+fn sample_keyframes(property: AnimProp, start_bits: u32, stops: &[Keyframe], t: f32) -> u32 {
+    let (mut prev_time, mut prev_bits) = (0.0, start_bits);
+    for stop in stops {
+        if t <= stop.time {
+            let span = stop.time - prev_time;
+            let local = if span <= 0.0 {
+                1.0
+            } else {
+                ((t - prev_time) / span).clamp(0.0, 1.0)
+            };
+            return interpolate(
+                property,
+                prev_bits,
+                stop.bits,
+                apply_easing(stop.easing, local),
+            );
+        }
+        prev_time = stop.time;
+        prev_bits = stop.bits;
+    }
+    prev_bits
+}
+
+/// Current value of a property animation at its elapsed time.
+fn sample(anim: &Animation) -> u32 {
+    if let Some(spring) = anim.spring {
+        return spring.value.to_bits();
+    }
+    let t = if anim.duration_ms == 0 {
+        1.0
+    } else {
+        (anim.elapsed_ms / anim.duration_ms as f32).clamp(0.0, 1.0)
+    };
+    match anim.keyframes {
+        Some(ref stops) => sample_keyframes(anim.property, anim.start_bits, stops, t),
+        None => interpolate(
+            anim.property,
+            anim.start_bits,
+            anim.end_bits,
+            apply_easing(anim.easing, t),
+        ),
+    }
+}
+
 // ============================================================================
 // Property Read/Write Helpers
 // ============================================================================
@@ -217,7 +362,7 @@ pub(crate) fn start_animation(
     target_bits: u32,
     duration_ms: u32,
     easing: Easing,
-) -> Result<u32, String> {
+) -> Result<u32, TuiError> {
     ctx.validate_handle(target)?;
 
     // Capture start value — if conflicting non-spinner animation exists, use its
@@ -228,13 +373,7 @@ pub(crate) fn start_animation(
         .position(|a| a.target == target && a.property == property && a.spinner.is_none())
     {
         let existing = &ctx.animations[idx];
-        let t = if existing.duration_ms == 0 {
-            1.0
-        } else {
-            (existing.elapsed_ms / existing.duration_ms as f32).clamp(0.0, 1.0)
-        };
-        let alpha = apply_easing(existing.easing, t);
-        let current = interpolate(property, existing.start_bits, existing.end_bits, alpha);
+        let current = sample(existing);
         let existing_id = existing.id;
         ctx.animations.remove(idx);
         ctx.animation_chains.remove(&existing_id);
@@ -245,7 +384,7 @@ pub(crate) fn start_animation(
         let node = ctx
             .nodes
             .get(&target)
-            .ok_or_else(|| format!("Invalid handle: {target}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {target}")))?;
         read_property(node, property)
     };
 
@@ -264,11 +403,213 @@ pub(crate) fn start_animation(
         looping: false,
         pending: false,
         spinner: None,
+        keyframes: None,
+        spring: None,
+        typewriter: None,
+        marquee: None,
     });
 
     Ok(id)
 }
 
+/// Start a multi-stop keyframe animation. Returns the animation handle.
+///
+/// Stops must have finite, non-decreasing times in [0, 1]. The property's
+/// current value is the implicit start, so a track whose first stop is above
+/// 0 eases in from wherever the property is now. Replaces any existing
+/// animation on the same (target, property) like `start_animation`.
+pub(crate) fn start_keyframe_animation(
+    ctx: &mut TuiContext,
+    target: u32,
+    property: AnimProp,
+    stops: &[KeyframeStop],
+    duration_ms: u32,
+) -> Result<u32, TuiError> {
+    if stops.is_empty() {
+        return Err(ErrorCode::InvalidArgument.raise("Keyframe animation needs at least one stop"));
+    }
+    let mut keyframes = Vec::with_capacity(stops.len());
+    let mut prev_time = 0.0f32;
+    for (i, stop) in stops.iter().enumerate() {
+        if !stop.time.is_finite() || !(0.0..=1.0).contains(&stop.time) {
+            return Err(ErrorCode::IndexOutOfBounds.raise(format!(
+                "Keyframe {i} time out of range [0, 1]: {}",
+                stop.time
+            )));
+        }
+        if stop.time < prev_time {
+            return Err(ErrorCode::InvalidArgument.raise(format!(
+                "Keyframe {i} time {} precedes previous stop",
+                stop.time
+            )));
+        }
+        let easing = Easing::from_u8(stop.easing).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid easing function: {}", stop.easing))
+        })?;
+        keyframes.push(Keyframe {
+            time: stop.time,
+            bits: stop.value,
+            easing,
+        });
+        prev_time = stop.time;
+    }
+
+    let last_bits = keyframes[keyframes.len() - 1].bits;
+    let id = start_animation(
+        ctx,
+        target,
+        property,
+        last_bits,
+        duration_ms,
+        Easing::Linear,
+    )?;
+    if let Some(anim) = ctx.animations.iter_mut().find(|a| a.id == id) {
+        anim.keyframes = Some(keyframes);
+    }
+    Ok(id)
+}
+
+/// Start a damped-spring animation toward `target_bits`. Returns the handle.
+///
+/// Only f32 properties (opacity, position) can spring. Replacing a running
+/// spring on the same property keeps its velocity so motion stays continuous.
+/// Completes (and emits AnimationEnd) once the spring comes to rest.
+pub(crate) fn start_spring(
+    ctx: &mut TuiContext,
+    target: u32,
+    property: AnimProp,
+    target_bits: u32,
+    stiffness: f32,
+    damping: f32,
+) -> Result<u32, TuiError> {
+    if !matches!(
+        property,
+        AnimProp::Opacity | AnimProp::PositionX | AnimProp::PositionY
+    ) {
+        return Err(ErrorCode::InvalidArgument.raise(format!(
+            "Spring animation requires an f32 property, got {property:?}"
+        )));
+    }
+    if !stiffness.is_finite() || stiffness <= 0.0 {
+        return Err(
+            ErrorCode::InvalidArgument.raise(format!("Invalid spring stiffness: {stiffness}"))
+        );
+    }
+    if !damping.is_finite() || damping < 0.0 {
+        return Err(ErrorCode::InvalidArgument.raise(format!("Invalid spring damping: {damping}")));
+    }
+
+    let velocity = ctx
+        .animations
+        .iter()
+        .find(|a| a.target == target && a.property == property)
+        .and_then(|a| a.spring)
+        .map_or(0.0, |spring| spring.velocity);
+
+    let id = start_animation(ctx, target, property, target_bits, 0, Easing::Linear)?;
+    if let Some(anim) = ctx.animations.iter_mut().find(|a| a.id == id) {
+        anim.spring = Some(SpringState {
+            stiffness,
+            damping,
+            value: f32::from_bits(anim.start_bits),
+            velocity,
+        });
+    }
+    Ok(id)
+}
+
+/// Handles of every descendant of `root` (excluding `root`), depth-first.
+fn descendants(ctx: &TuiContext, root: u32) -> Vec<u32> {
+    let mut out = Vec::new();
+    let mut stack: Vec<u32> = ctx
+        .nodes
+        .get(&root)
+        .map(|n| n.children.iter().rev().copied().collect())
+        .unwrap_or_default();
+    while let Some(h) = stack.pop() {
+        out.push(h);
+        if let Some(node) = ctx.nodes.get(&h) {
+            stack.extend(node.children.iter().rev());
+        }
+    }
+    out
+}
+
+/// Record the on-screen position (layout + render offset) of every
+/// descendant of `root`, replacing any previous capture. Returns the count.
+pub(crate) fn capture_positions(ctx: &mut TuiContext, root: u32) -> Result<usize, TuiError> {
+    ctx.validate_handle(root)?;
+    crate::layout::compute_layout(ctx)?;
+
+    let mut captured = HashMap::new();
+    for handle in descendants(ctx, root) {
+        if let Some((x, y)) = crate::layout::absolute_position(ctx, handle) {
+            let (ox, oy) = ctx.nodes[&handle].render_offset;
+            captured.insert(handle, (x + ox, y + oy));
+        }
+    }
+    let count = captured.len();
+    ctx.captured_positions = captured;
+    Ok(count)
+}
+
+/// Play FLIP transitions for descendants of `root` that moved since
+/// `capture_positions`: each starts at `render_offset = old - new` and
+/// animates back to zero. Nodes created after the capture are skipped.
+/// Consumes the capture. Returns the number of nodes transitioned.
+pub(crate) fn play_position_transitions(
+    ctx: &mut TuiContext,
+    root: u32,
+    duration_ms: u32,
+    easing: Easing,
+) -> Result<usize, TuiError> {
+    ctx.validate_handle(root)?;
+    crate::layout::compute_layout(ctx)?;
+
+    let captured = std::mem::take(&mut ctx.captured_positions);
+    let mut moved = 0;
+    for handle in descendants(ctx, root) {
+        let Some(&(old_x, old_y)) = captured.get(&handle) else {
+            continue;
+        };
+        let Some((new_x, new_y)) = crate::layout::absolute_position(ctx, handle) else {
+            continue;
+        };
+        let (dx, dy) = (old_x - new_x, old_y - new_y);
+        if dx == 0.0 && dy == 0.0 {
+            continue;
+        }
+
+        // Drop in-flight position animations so the new ones start from the
+        // inverted offset rather than the old interpolated value.
+        let stale: Vec<u32> = ctx
+            .animations
+            .iter()
+            .filter(|a| {
+                a.target == handle
+                    && matches!(a.property, AnimProp::PositionX | AnimProp::PositionY)
+            })
+            .map(|a| a.id)
+            .collect();
+        for id in stale {
+            cancel_animation(ctx, id)?;
+        }
+
+        if let Some(node) = ctx.nodes.get_mut(&handle) {
+            node.render_offset = (dx, dy);
+        }
+        for property in [AnimProp::PositionX, AnimProp::PositionY] {
+            start_animation(ctx, handle, property, 0.0f32.to_bits(), duration_ms, easing)?;
+        }
+        crate::tree::mark_dirty(ctx, handle);
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Braille frames used by `start_spinner`.
+const DEFAULT_SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 /// Start a built-in spinner animation on a node.
 ///
 /// Cycles through braille spinner frames at the given interval, setting the
@@ -278,8 +619,27 @@ pub(crate) fn start_spinner(
     ctx: &mut TuiContext,
     target: u32,
     interval_ms: u32,
-) -> Result<u32, String> {
+) -> Result<u32, TuiError> {
+    let frames = DEFAULT_SPINNER_FRAMES
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    start_spinner_frames(ctx, target, frames, interval_ms)
+}
+
+/// Start a spinner that cycles through caller-supplied frames (e.g. an ASCII
+/// `| / - \` set for terminals that mangle braille). At least one frame is
+/// required.
+pub(crate) fn start_spinner_frames(
+    ctx: &mut TuiContext,
+    target: u32,
+    frames: Vec<String>,
+    interval_ms: u32,
+) -> Result<u32, TuiError> {
     ctx.validate_handle(target)?;
+    if frames.is_empty() {
+        return Err(ErrorCode::InvalidArgument.raise("Spinner needs at least one frame"));
+    }
 
     // Guard: at least 1ms interval to prevent infinite loops in advance
     let interval_ms = interval_ms.max(1);
@@ -287,11 +647,6 @@ pub(crate) fn start_spinner(
     let id = ctx.next_anim_handle;
     ctx.next_anim_handle += 1;
 
-    let frames: Vec<String> = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
-
     ctx.animations.push(Animation {
         id,
         target,
@@ -305,10 +660,163 @@ pub(crate) fn start_spinner(
         pending: false,
         spinner: Some(SpinnerState {
             frames,
+            colors: Vec::new(),
+            frame_idx: 0,
+            interval_ms,
+            frame_elapsed: 0.0,
+        }),
+        keyframes: None,
+        spring: None,
+        typewriter: None,
+        marquee: None,
+    });
+
+    Ok(id)
+}
+
+/// Start a color spinner: the node keeps its content while its fg color
+/// steps through `colors` every `interval_ms`. Runs until cancelled like the
+/// glyph spinner. At least one color is required.
+pub(crate) fn start_color_spinner(
+    ctx: &mut TuiContext,
+    target: u32,
+    colors: Vec<u32>,
+    interval_ms: u32,
+) -> Result<u32, TuiError> {
+    ctx.validate_handle(target)?;
+    if colors.is_empty() {
+        return Err(ErrorCode::InvalidArgument.raise("Color spinner needs at least one color"));
+    }
+    let interval_ms = interval_ms.max(1);
+
+    let id = ctx.next_anim_handle;
+    ctx.next_anim_handle += 1;
+
+    ctx.animations.push(Animation {
+        id,
+        target,
+        property: AnimProp::FgColor,
+        start_bits: 0,
+        end_bits: 0,
+        duration_ms: 0,
+        elapsed_ms: 0.0,
+        easing: Easing::Linear,
+        looping: false,
+        pending: false,
+        spinner: Some(SpinnerState {
+            frames: Vec::new(),
+            colors,
             frame_idx: 0,
             interval_ms,
             frame_elapsed: 0.0,
         }),
+        keyframes: None,
+        spring: None,
+        typewriter: None,
+        marquee: None,
+    });
+
+    Ok(id)
+}
+
+/// Start a typewriter reveal on a Text node: its content stays whole (so
+/// `tui_get_content` is unaffected) while rendering shows a prefix that
+/// grows by `chars_per_sec` graphemes per second. Completes with
+/// AnimationEnd once everything is shown. Replaces any running typewriter
+/// on the node. Returns the animation handle.
+pub(crate) fn start_typewriter(
+    ctx: &mut TuiContext,
+    target: u32,
+    chars_per_sec: f32,
+) -> Result<u32, TuiError> {
+    ctx.validate_handle(target)?;
+    if !chars_per_sec.is_finite() || chars_per_sec <= 0.0 {
+        return Err(
+            ErrorCode::InvalidArgument.raise(format!("Invalid typewriter rate: {chars_per_sec}"))
+        );
+    }
+    let node = ctx.nodes.get_mut(&target).unwrap(); // safe: just validated
+    if node.node_type != NodeType::Text {
+        return Err(ErrorCode::WrongNodeType.raise(format!("Handle {target} is not a Text widget")));
+    }
+    node.typewriter_reveal = Some(0);
+    ctx.animations
+        .retain(|a| !(a.target == target && a.typewriter.is_some()));
+    crate::tree::mark_dirty(ctx, target);
+
+    let id = ctx.next_anim_handle;
+    ctx.next_anim_handle += 1;
+
+    ctx.animations.push(Animation {
+        id,
+        target,
+        property: AnimProp::Opacity, // placeholder; not used for interpolation
+        start_bits: 0,
+        end_bits: 0,
+        duration_ms: 0,
+        elapsed_ms: 0.0,
+        easing: Easing::Linear,
+        looping: false,
+        pending: false,
+        spinner: None,
+        keyframes: None,
+        spring: None,
+        typewriter: Some(TypewriterState {
+            chars_per_sec,
+            revealed: 0.0,
+        }),
+        marquee: None,
+    });
+
+    Ok(id)
+}
+
+/// Start a marquee on a Text node: text wider than the node scrolls left
+/// `speed` cells per second and wraps around with `gap` blank cells between
+/// repetitions. Text that fits is drawn as usual. Loops until cancelled;
+/// replaces any running marquee on the node. Returns the animation handle.
+pub(crate) fn start_marquee(
+    ctx: &mut TuiContext,
+    target: u32,
+    speed: f32,
+    gap: u32,
+) -> Result<u32, TuiError> {
+    ctx.validate_handle(target)?;
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(ErrorCode::InvalidArgument.raise(format!("Invalid marquee speed: {speed}")));
+    }
+    let node = ctx.nodes.get_mut(&target).unwrap(); // safe: just validated
+    if node.node_type != NodeType::Text {
+        return Err(ErrorCode::WrongNodeType.raise(format!("Handle {target} is not a Text widget")));
+    }
+    node.text_marquee = Some((0, gap));
+    ctx.animations
+        .retain(|a| !(a.target == target && a.marquee.is_some()));
+    crate::tree::mark_dirty(ctx, target);
+
+    let id = ctx.next_anim_handle;
+    ctx.next_anim_handle += 1;
+
+    ctx.animations.push(Animation {
+        id,
+        target,
+        property: AnimProp::Opacity, // placeholder; not used for interpolation
+        start_bits: 0,
+        end_bits: 0,
+        duration_ms: 0,
+        elapsed_ms: 0.0,
+        easing: Easing::Linear,
+        looping: false,
+        pending: false,
+        spinner: None,
+        keyframes: None,
+        spring: None,
+        typewriter: None,
+        marquee: Some(MarqueeState {
+            speed,
+            gap,
+            offset: 0.0,
+        }),
     });
 
     Ok(id)
@@ -324,7 +832,7 @@ pub(crate) fn start_progress(
     target: u32,
     duration_ms: u32,
     easing: Easing,
-) -> Result<u32, String> {
+) -> Result<u32, TuiError> {
     ctx.validate_handle(target)?;
 
     // Cancel any existing opacity animation so we can force-reset to 0.0
@@ -361,7 +869,7 @@ pub(crate) fn start_pulse(
     target: u32,
     duration_ms: u32,
     easing: Easing,
-) -> Result<u32, String> {
+) -> Result<u32, TuiError> {
     ctx.validate_handle(target)?;
 
     let id = start_animation(
@@ -390,12 +898,12 @@ pub(crate) fn chain_animation(
     ctx: &mut TuiContext,
     after_anim: u32,
     next_anim: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     if !ctx.animations.iter().any(|a| a.id == after_anim) {
-        return Err(format!("Animation not found: {after_anim}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Animation not found: {after_anim}")));
     }
     if !ctx.animations.iter().any(|a| a.id == next_anim) {
-        return Err(format!("Animation not found: {next_anim}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Animation not found: {next_anim}")));
     }
 
     // Mark the successor as pending and reset its progress
@@ -409,7 +917,7 @@ pub(crate) fn chain_animation(
 }
 
 /// Create an empty choreography group.
-pub(crate) fn create_choreography_group(ctx: &mut TuiContext) -> Result<u32, String> {
+pub(crate) fn create_choreography_group(ctx: &mut TuiContext) -> Result<u32, TuiError> {
     let id = ctx.next_choreo_group_handle;
     ctx.next_choreo_group_handle += 1;
     ctx.choreo_groups.insert(
@@ -432,25 +940,24 @@ pub(crate) fn choreography_add(
     group_id: u32,
     anim_id: u32,
     start_at_ms: u32,
-) -> Result<(), String> {
-    let group = ctx
-        .choreo_groups
-        .get_mut(&group_id)
-        .ok_or_else(|| format!("Choreography group not found: {group_id}"))?;
+) -> Result<(), TuiError> {
+    let group = ctx.choreo_groups.get_mut(&group_id).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Choreography group not found: {group_id}"))
+    })?;
     if group.running {
-        return Err("Cannot mutate a running choreography group".to_string());
+        return Err("Cannot mutate a running choreography group".into());
     }
     if group.members.iter().any(|m| m.anim_id == anim_id) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Animation {anim_id} is already part of group {group_id}"
-        ));
+        )));
     }
 
     let anim = ctx
         .animations
         .iter_mut()
         .find(|a| a.id == anim_id)
-        .ok_or_else(|| format!("Animation not found: {anim_id}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Animation not found: {anim_id}")))?;
     anim.pending = true;
     anim.elapsed_ms = 0.0;
 
@@ -464,11 +971,10 @@ pub(crate) fn choreography_add(
 }
 
 /// Start a choreography group timeline from t=0.
-pub(crate) fn choreography_start(ctx: &mut TuiContext, group_id: u32) -> Result<(), String> {
-    let group = ctx
-        .choreo_groups
-        .get_mut(&group_id)
-        .ok_or_else(|| format!("Choreography group not found: {group_id}"))?;
+pub(crate) fn choreography_start(ctx: &mut TuiContext, group_id: u32) -> Result<(), TuiError> {
+    let group = ctx.choreo_groups.get_mut(&group_id).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Choreography group not found: {group_id}"))
+    })?;
     if group.running {
         return Ok(());
     }
@@ -495,12 +1001,11 @@ pub(crate) fn choreography_start(ctx: &mut TuiContext, group_id: u32) -> Result<
 ///
 /// Already-started animations continue. Not-yet-started members are cancelled
 /// to guarantee they cannot start later.
-pub(crate) fn choreography_cancel(ctx: &mut TuiContext, group_id: u32) -> Result<(), String> {
+pub(crate) fn choreography_cancel(ctx: &mut TuiContext, group_id: u32) -> Result<(), TuiError> {
     let pending_ids: Vec<u32> = {
-        let group = ctx
-            .choreo_groups
-            .get(&group_id)
-            .ok_or_else(|| format!("Choreography group not found: {group_id}"))?;
+        let group = ctx.choreo_groups.get(&group_id).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Choreography group not found: {group_id}"))
+        })?;
         group
             .members
             .iter()
@@ -519,11 +1024,10 @@ pub(crate) fn choreography_cancel(ctx: &mut TuiContext, group_id: u32) -> Result
 pub(crate) fn destroy_choreography_group(
     ctx: &mut TuiContext,
     group_id: u32,
-) -> Result<(), String> {
-    let group = ctx
-        .choreo_groups
-        .remove(&group_id)
-        .ok_or_else(|| format!("Choreography group not found: {group_id}"))?;
+) -> Result<(), TuiError> {
+    let group = ctx.choreo_groups.remove(&group_id).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Choreography group not found: {group_id}"))
+    })?;
 
     let pending_ids: Vec<u32> = group
         .members
@@ -597,6 +1101,10 @@ fn advance_choreography(ctx: &mut TuiContext, elapsed_ms: f32) -> HashMap<u32, f
     for anim in &mut ctx.animations {
         if activation_elapsed_by_anim.contains_key(&anim.id) {
             anim.pending = false;
+            if ctx.animation_start_events {
+                ctx.event_buffer
+                    .push(TuiEvent::animation_start(anim.target, anim.id));
+            }
         }
     }
 
@@ -607,21 +1115,36 @@ fn advance_choreography(ctx: &mut TuiContext, elapsed_ms: f32) -> HashMap<u32, f
 ///
 /// For each non-pending animation:
 /// - Spinner: advance frame timer, cycle content
-/// - Property (one-shot): interpolate, remove when complete, activate any chain
+/// - Property (one-shot): interpolate, remove when complete, emit AnimationEnd,
+///   activate any chain
 /// - Property (looping): interpolate, reverse direction on completion
 pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
+    let start = std::time::Instant::now();
+    ctx.perf_anim_advanced = advance_animations_inner(ctx, elapsed_ms);
+    ctx.perf_anim_advance_us = start.elapsed().as_micros() as u64;
+}
+
+/// Body of `advance_animations`. Returns how many animations were stepped.
+fn advance_animations_inner(ctx: &mut TuiContext, elapsed_ms: f32) -> u32 {
+    // Scaled before everything else so choreography offsets, spinners and
+    // property animations all see the same slowed (or frozen) clock.
+    let elapsed_ms = elapsed_ms * ctx.animation_time_scale;
     if elapsed_ms <= 0.0 {
-        return;
+        return 0;
     }
 
     let activation_elapsed_by_anim = advance_choreography(ctx, elapsed_ms);
 
     if ctx.animations.is_empty() {
-        return;
+        return 0;
     }
 
+    let mut advanced = 0u32;
+
     let mut updates: Vec<(u32, AnimProp, u32)> = Vec::new();
     let mut content_updates: Vec<(u32, String)> = Vec::new();
+    let mut reveal_updates: Vec<(u32, Option<u32>)> = Vec::new();
+    let mut marquee_updates: Vec<(u32, (u32, u32))> = Vec::new();
     let mut dirty_nodes: Vec<u32> = Vec::new();
     let mut completed_ids: Vec<u32> = Vec::new();
 
@@ -634,15 +1157,66 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
             .get(&anim.id)
             .copied()
             .unwrap_or(elapsed_ms);
-
-        if let Some(ref mut spinner) = anim.spinner {
+        advanced += 1;
+
+        if let Some(ref mut spring) = anim.spring {
+            // Spring mode: integrate physics; no duration, looping ignored
+            let target = f32::from_bits(anim.end_bits);
+            if spring.step(target, anim_elapsed_ms) {
+                updates.push((anim.target, anim.property, anim.end_bits));
+                completed_ids.push(anim.id);
+                ctx.event_buffer
+                    .push(TuiEvent::animation_end(anim.target, anim.id));
+            } else {
+                updates.push((anim.target, anim.property, spring.value.to_bits()));
+            }
+            dirty_nodes.push(anim.target);
+        } else if let Some(ref mut typewriter) = anim.typewriter {
+            // Typewriter mode: grow the revealed prefix; done once it
+            // covers the node's current content
+            typewriter.revealed += typewriter.chars_per_sec * anim_elapsed_ms / 1000.0;
+            let total = ctx
+                .nodes
+                .get(&anim.target)
+                .map_or(0, |n| crate::text_utils::grapheme_count(&n.content));
+            let shown = typewriter.revealed as usize;
+            if shown >= total {
+                reveal_updates.push((anim.target, None));
+                completed_ids.push(anim.id);
+                ctx.event_buffer
+                    .push(TuiEvent::animation_end(anim.target, anim.id));
+            } else {
+                reveal_updates.push((anim.target, Some(shown as u32)));
+            }
+            dirty_nodes.push(anim.target);
+        } else if let Some(ref mut marquee) = anim.marquee {
+            // Marquee mode: advance the offset, wrapping once a whole
+            // repetition (text plus gap) has scrolled by
+            let period = ctx.nodes.get(&anim.target).map_or(0, |n| {
+                crate::text_utils::display_width(&n.content.replace('\n', " "))
+            }) as f32
+                + marquee.gap as f32;
+            marquee.offset += marquee.speed * anim_elapsed_ms / 1000.0;
+            if period > 0.0 {
+                marquee.offset %= period;
+            }
+            marquee_updates.push((anim.target, (marquee.offset as u32, marquee.gap)));
+            dirty_nodes.push(anim.target);
+        } else if let Some(ref mut spinner) = anim.spinner {
             // Spinner mode: advance frame timer and cycle through frames
+            // (or colors, for a color spinner)
+            let cycle_len = spinner.frames.len().max(spinner.colors.len());
             spinner.frame_elapsed += anim_elapsed_ms;
             while spinner.frame_elapsed >= spinner.interval_ms as f32 {
                 spinner.frame_elapsed -= spinner.interval_ms as f32;
-                spinner.frame_idx = (spinner.frame_idx + 1) % spinner.frames.len();
+                spinner.frame_idx = (spinner.frame_idx + 1) % cycle_len;
+            }
+            if spinner.colors.is_empty() {
+                content_updates.push((anim.target, spinner.frames[spinner.frame_idx].clone()));
+            } else {
+                let color = spinner.colors[spinner.frame_idx];
+                updates.push((anim.target, AnimProp::FgColor, color));
             }
-            content_updates.push((anim.target, spinner.frames[spinner.frame_idx].clone()));
             dirty_nodes.push(anim.target);
         } else {
             // Property animation (standard or looping)
@@ -652,28 +1226,22 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
 
             if completed {
                 if anim.looping {
-                    // Looping (pulse): reverse direction and reset timer
                     anim.elapsed_ms -= anim.duration_ms as f32;
-                    std::mem::swap(&mut anim.start_bits, &mut anim.end_bits);
-                    // Compute value with new direction (guard against zero duration)
-                    let t = if anim.duration_ms == 0 {
-                        1.0
-                    } else {
-                        (anim.elapsed_ms / anim.duration_ms as f32).clamp(0.0, 1.0)
-                    };
-                    let alpha = apply_easing(anim.easing, t);
-                    let bits = interpolate(anim.property, anim.start_bits, anim.end_bits, alpha);
-                    updates.push((anim.target, anim.property, bits));
+                    // Keyframe tracks restart from the first stop; two-point
+                    // animations (pulse) reverse direction instead.
+                    if anim.keyframes.is_none() {
+                        std::mem::swap(&mut anim.start_bits, &mut anim.end_bits);
+                    }
+                    updates.push((anim.target, anim.property, sample(anim)));
                 } else {
                     // One-shot: apply exact end value, mark for removal
                     updates.push((anim.target, anim.property, anim.end_bits));
                     completed_ids.push(anim.id);
+                    ctx.event_buffer
+                        .push(TuiEvent::animation_end(anim.target, anim.id));
                 }
             } else {
-                let t = (anim.elapsed_ms / anim.duration_ms as f32).clamp(0.0, 1.0);
-                let alpha = apply_easing(anim.easing, t);
-                let bits = interpolate(anim.property, anim.start_bits, anim.end_bits, alpha);
-                updates.push((anim.target, anim.property, bits));
+                updates.push((anim.target, anim.property, sample(anim)));
             }
 
             dirty_nodes.push(anim.target);
@@ -691,7 +1259,23 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
     // Apply spinner content updates to nodes
     for (target, content) in content_updates {
         if let Some(node) = ctx.nodes.get_mut(&target) {
-            node.content = content;
+            node.set_content(content);
+            node.dirty = true;
+        }
+    }
+
+    // Apply typewriter reveal updates to nodes
+    for (target, reveal) in reveal_updates {
+        if let Some(node) = ctx.nodes.get_mut(&target) {
+            node.typewriter_reveal = reveal;
+            node.dirty = true;
+        }
+    }
+
+    // Apply marquee offsets to nodes
+    for (target, marquee) in marquee_updates {
+        if let Some(node) = ctx.nodes.get_mut(&target) {
+            node.text_marquee = Some(marquee);
             node.dirty = true;
         }
     }
@@ -707,6 +1291,10 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
         if let Some(next_id) = next_id_opt {
             if let Some(next_anim) = ctx.animations.iter_mut().find(|a| a.id == next_id) {
                 next_anim.pending = false;
+                if ctx.animation_start_events {
+                    ctx.event_buffer
+                        .push(TuiEvent::animation_start(next_anim.target, next_id));
+                }
             }
             ctx.animation_chains.remove(&completed_id);
         }
@@ -717,6 +1305,17 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
     for completed_id in completed_ids {
         remove_animation_from_choreography(ctx, completed_id);
     }
+    advanced
+}
+
+/// Set the global animation time scale, clamped to `[0, MAX_TIME_SCALE]`.
+/// 0 freezes every animation in place without removing it.
+pub(crate) fn set_time_scale(ctx: &mut TuiContext, scale: f32) -> Result<(), TuiError> {
+    if scale.is_nan() {
+        return Err(ErrorCode::InvalidArgument.raise("Invalid animation time scale: NaN"));
+    }
+    ctx.animation_time_scale = scale.clamp(0.0, MAX_TIME_SCALE);
+    Ok(())
 }
 
 /// Mark a running animation as looping (bidirectional oscillation).
@@ -724,12 +1323,12 @@ pub(crate) fn advance_animations(ctx: &mut TuiContext, elapsed_ms: f32) {
 /// When looping is true, the animation reverses direction and repeats on
 /// completion instead of being removed. Works for any property animation;
 /// used to make color transitions and opacity animations oscillate.
-pub(crate) fn set_animation_looping(ctx: &mut TuiContext, anim_id: u32) -> Result<(), String> {
+pub(crate) fn set_animation_looping(ctx: &mut TuiContext, anim_id: u32) -> Result<(), TuiError> {
     if let Some(anim) = ctx.animations.iter_mut().find(|a| a.id == anim_id) {
         anim.looping = true;
         Ok(())
     } else {
-        Err(format!("Animation not found: {anim_id}"))
+        Err(ErrorCode::InvalidHandle.raise(format!("Animation not found: {anim_id}")))
     }
 }
 
@@ -739,13 +1338,25 @@ pub(crate) fn set_animation_looping(ctx: &mut TuiContext, anim_id: u32) -> Resul
 /// The node is NOT marked dirty (per TechSpec).
 /// Removes any chain where this animation was the predecessor so the chained
 /// successor is not auto-started.
-pub(crate) fn cancel_animation(ctx: &mut TuiContext, anim_id: u32) -> Result<(), String> {
+pub(crate) fn cancel_animation(ctx: &mut TuiContext, anim_id: u32) -> Result<(), TuiError> {
     let idx = ctx
         .animations
         .iter()
         .position(|a| a.id == anim_id)
-        .ok_or_else(|| format!("Animation not found: {anim_id}"))?;
-    ctx.animations.remove(idx);
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Animation not found: {anim_id}")))?;
+    let anim = ctx.animations.remove(idx);
+    // A cancelled typewriter leaves its text fully shown; a cancelled
+    // marquee puts it back in place.
+    if anim.typewriter.is_some() || anim.marquee.is_some() {
+        if let Some(node) = ctx.nodes.get_mut(&anim.target) {
+            if anim.typewriter.is_some() {
+                node.typewriter_reveal = None;
+            } else {
+                node.text_marquee = None;
+            }
+        }
+        crate::tree::mark_dirty(ctx, anim.target);
+    }
     // Prevent the chained successor from auto-starting
     ctx.animation_chains.remove(&anim_id);
     remove_animation_from_choreography(ctx, anim_id);
@@ -865,28 +1476,489 @@ mod tests {
         assert!((x - 5.0).abs() < 0.2);
     }
 
-    // ── Interpolation tests ──────────────────────────────────────────────
-
     #[test]
-    fn test_linear_interpolation_opacity() {
-        // Opacity 1.0 -> 0.0
-        let start = 1.0f32.to_bits();
-        let end = 0.0f32.to_bits();
-
-        let at_0 = f32::from_bits(interpolate_f32(start, end, 0.0));
-        assert!((at_0 - 1.0).abs() < 0.001);
-
-        let at_quarter = f32::from_bits(interpolate_f32(start, end, 0.25));
-        assert!((at_quarter - 0.75).abs() < 0.001);
+    fn test_render_skips_advance_when_host_drives_clock() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(h);
+        start_animation(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            1000,
+            Easing::Linear,
+        )
+        .unwrap();
 
-        let at_half = f32::from_bits(interpolate_f32(start, end, 0.5));
-        assert!((at_half - 0.5).abs() < 0.001);
+        ctx.auto_advance_animations = false;
+        ctx.last_render_time =
+            Some(std::time::Instant::now() - std::time::Duration::from_millis(500));
+        crate::render::render(&mut ctx).unwrap();
+        assert_eq!(ctx.animations[0].elapsed_ms, 0.0);
 
-        let at_three_quarter = f32::from_bits(interpolate_f32(start, end, 0.75));
-        assert!((at_three_quarter - 0.25).abs() < 0.001);
+        // Host-driven delta advances without a render.
+        advance_animations(&mut ctx, 250.0);
+        assert_eq!(ctx.animations[0].elapsed_ms, 250.0);
 
-        let at_1 = f32::from_bits(interpolate_f32(start, end, 1.0));
-        assert!((at_1 - 0.0).abs() < 0.001);
+        // Re-enabling resumes wall-clock advance from the last render only.
+        ctx.auto_advance_animations = true;
+        crate::render::render(&mut ctx).unwrap();
+        assert!(ctx.animations[0].elapsed_ms < 500.0);
+    }
+
+    #[test]
+    fn test_time_scale_slows_and_freezes_animations() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        start_animation(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            1000,
+            Easing::Linear,
+        )
+        .unwrap();
+
+        set_time_scale(&mut ctx, 0.5).unwrap();
+        advance_animations(&mut ctx, 400.0);
+        assert_eq!(ctx.animations[0].elapsed_ms, 200.0);
+
+        set_time_scale(&mut ctx, 0.0).unwrap();
+        advance_animations(&mut ctx, 400.0);
+        assert_eq!(ctx.animations.len(), 1);
+        assert_eq!(ctx.animations[0].elapsed_ms, 200.0);
+
+        set_time_scale(&mut ctx, 2.0).unwrap();
+        advance_animations(&mut ctx, 100.0);
+        assert_eq!(ctx.animations[0].elapsed_ms, 400.0);
+    }
+
+    #[test]
+    fn test_time_scale_clamps_and_rejects_nan() {
+        let mut ctx = test_ctx();
+        set_time_scale(&mut ctx, -3.0).unwrap();
+        assert_eq!(ctx.animation_time_scale, 0.0);
+        set_time_scale(&mut ctx, f32::INFINITY).unwrap();
+        assert_eq!(ctx.animation_time_scale, MAX_TIME_SCALE);
+        assert!(set_time_scale(&mut ctx, f32::NAN).is_err());
+        assert_eq!(ctx.animation_time_scale, MAX_TIME_SCALE);
+    }
+
+    #[test]
+    fn test_time_scale_applies_to_choreography_offsets() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let anim = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            1000,
+            Easing::Linear,
+        )
+        .unwrap();
+        let group = create_choreography_group(&mut ctx).unwrap();
+        choreography_add(&mut ctx, group, anim, 100).unwrap();
+        choreography_start(&mut ctx, group).unwrap();
+
+        set_time_scale(&mut ctx, 0.5).unwrap();
+        // 150ms wall → 75ms scaled: member offset (100ms) not yet reached.
+        advance_animations(&mut ctx, 150.0);
+        assert_eq!(ctx.choreo_groups[&group].elapsed_ms, 75.0);
+        assert!(ctx.animations[0].pending);
+    }
+
+    fn stop(time: f32, value: f32, easing: Easing) -> KeyframeStop {
+        KeyframeStop {
+            time,
+            value: value.to_bits(),
+            easing: easing as u8,
+        }
+    }
+
+    #[test]
+    fn test_keyframes_fade_in_hold_fade_out() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.nodes.get_mut(&h).unwrap().visual_style.opacity = 0.0;
+        let stops = [
+            stop(0.25, 1.0, Easing::Linear),
+            stop(0.75, 1.0, Easing::Linear),
+            stop(1.0, 0.0, Easing::Linear),
+        ];
+        start_keyframe_animation(&mut ctx, h, AnimProp::Opacity, &stops, 1000).unwrap();
+
+        advance_animations(&mut ctx, 125.0);
+        assert!((ctx.nodes[&h].visual_style.opacity - 0.5).abs() < 0.01);
+        advance_animations(&mut ctx, 375.0);
+        assert!((ctx.nodes[&h].visual_style.opacity - 1.0).abs() < 0.01);
+        advance_animations(&mut ctx, 375.0);
+        assert!((ctx.nodes[&h].visual_style.opacity - 0.5).abs() < 0.01);
+        advance_animations(&mut ctx, 200.0);
+        assert_eq!(ctx.nodes[&h].visual_style.opacity, 0.0);
+        assert!(ctx.animations.is_empty());
+    }
+
+    #[test]
+    fn test_keyframes_segment_easing_applies_to_segment_end() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let stops = [
+            stop(0.0, 0.0, Easing::Linear),
+            stop(1.0, 10.0, Easing::EaseIn),
+        ];
+        start_keyframe_animation(&mut ctx, h, AnimProp::PositionX, &stops, 1000).unwrap();
+        advance_animations(&mut ctx, 500.0);
+        // EaseIn at local t=0.5 → 0.25
+        assert!((ctx.nodes[&h].render_offset.0 - 2.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_keyframes_loop_cycles_through_stops() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let stops = [
+            stop(0.0, 0.0, Easing::Linear),
+            stop(0.5, 10.0, Easing::Linear),
+            stop(1.0, 20.0, Easing::Linear),
+        ];
+        let id = start_keyframe_animation(&mut ctx, h, AnimProp::PositionX, &stops, 1000).unwrap();
+        set_animation_looping(&mut ctx, id).unwrap();
+
+        advance_animations(&mut ctx, 1250.0);
+        // Wrapped to t=0.25 of the same track, not reversed.
+        assert!((ctx.nodes[&h].render_offset.0 - 5.0).abs() < 0.01);
+        assert_eq!(ctx.animations.len(), 1);
+    }
+
+    #[test]
+    fn test_keyframes_reject_invalid_stops() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let prop = AnimProp::Opacity;
+        assert!(start_keyframe_animation(&mut ctx, h, prop, &[], 100).is_err());
+        let out_of_range = [stop(1.5, 1.0, Easing::Linear)];
+        assert!(start_keyframe_animation(&mut ctx, h, prop, &out_of_range, 100).is_err());
+        let unordered = [
+            stop(0.6, 1.0, Easing::Linear),
+            stop(0.4, 0.0, Easing::Linear),
+        ];
+        assert!(start_keyframe_animation(&mut ctx, h, prop, &unordered, 100).is_err());
+        let mut bad_easing = stop(1.0, 1.0, Easing::Linear);
+        bad_easing.easing = 99;
+        assert!(start_keyframe_animation(&mut ctx, h, prop, &[bad_easing], 100).is_err());
+        assert!(ctx.animations.is_empty());
+    }
+
+    fn drain_types(ctx: &mut TuiContext) -> Vec<(u32, u32, u32)> {
+        ctx.event_buffer
+            .drain(..)
+            .map(|e| (e.event_type, e.target, e.data[0]))
+            .collect()
+    }
+
+    #[test]
+    fn test_one_shot_completion_emits_animation_end() {
+        use crate::types::TuiEventType;
+
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let id = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::Opacity,
+            0.0f32.to_bits(),
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+
+        advance_animations(&mut ctx, 50.0);
+        assert!(ctx.event_buffer.is_empty());
+        advance_animations(&mut ctx, 60.0);
+        assert_eq!(
+            drain_types(&mut ctx),
+            vec![(TuiEventType::AnimationEnd as u32, h, id)]
+        );
+    }
+
+    #[test]
+    fn test_looping_spinner_and_cancelled_emit_no_end() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        start_pulse(&mut ctx, h, 100, Easing::Linear).unwrap();
+        start_spinner(&mut ctx, h, 10).unwrap();
+        let cancelled = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::FgColor,
+            0x01FF0000,
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+        cancel_animation(&mut ctx, cancelled).unwrap();
+
+        advance_animations(&mut ctx, 1000.0);
+        assert!(ctx.event_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_chain_start_event_is_opt_in() {
+        use crate::types::TuiEventType;
+
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::Opacity,
+            0.0f32.to_bits(),
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+        let b = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::FgColor,
+            0x01FF0000,
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+        chain_animation(&mut ctx, a, b).unwrap();
+
+        ctx.animation_start_events = true;
+        advance_animations(&mut ctx, 150.0);
+        assert_eq!(
+            drain_types(&mut ctx),
+            vec![
+                (TuiEventType::AnimationEnd as u32, h, a),
+                (TuiEventType::AnimationStart as u32, h, b),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spring_settles_on_target_and_is_removed() {
+        use crate::types::TuiEventType;
+
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let id = start_spring(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            170.0,
+            26.0,
+        )
+        .unwrap();
+
+        advance_animations(&mut ctx, 100.0);
+        let x = ctx.nodes[&h].render_offset.0;
+        assert!(x > 0.0 && x < 10.0, "spring should be in motion, got {x}");
+
+        for _ in 0..200 {
+            if ctx.animations.is_empty() {
+                break;
+            }
+            advance_animations(&mut ctx, 16.0);
+        }
+        assert!(ctx.animations.is_empty(), "spring never came to rest");
+        assert_eq!(ctx.nodes[&h].render_offset.0, 10.0);
+        let end = ctx.event_buffer.pop().unwrap();
+        assert_eq!(end.event_type, TuiEventType::AnimationEnd as u32);
+        assert_eq!(end.data[0], id);
+    }
+
+    #[test]
+    fn test_heavily_damped_spring_stays_finite_and_settles() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        start_spring(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            100.0,
+            500.0,
+        )
+        .unwrap();
+
+        for _ in 0..12 {
+            advance_animations(&mut ctx, 16.0);
+        }
+        let x = ctx.nodes[&h].render_offset.0;
+        assert!(
+            x.is_finite() && (0.0..=10.0).contains(&x),
+            "overdamped spring left [0, 10]: {x}"
+        );
+
+        for _ in 0..200 {
+            if ctx.animations.is_empty() {
+                break;
+            }
+            advance_animations(&mut ctx, 1000.0);
+        }
+        assert!(ctx.animations.is_empty(), "spring never came to rest");
+        assert_eq!(ctx.nodes[&h].render_offset.0, 10.0);
+    }
+
+    #[test]
+    fn test_spring_snaps_to_target_on_huge_delta() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        start_spring(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            170.0,
+            26.0,
+        )
+        .unwrap();
+
+        // Far more than SPRING_MAX_STEPS steps of the stable step size.
+        advance_animations(&mut ctx, 3_600_000.0);
+        assert!(ctx.animations.is_empty(), "spring should settle at once");
+        assert_eq!(ctx.nodes[&h].render_offset.0, 10.0);
+    }
+
+    #[test]
+    fn test_spring_replacement_keeps_velocity() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        start_spring(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            10.0f32.to_bits(),
+            170.0,
+            26.0,
+        )
+        .unwrap();
+        advance_animations(&mut ctx, 50.0);
+        let before = ctx.animations[0].spring.unwrap();
+
+        start_spring(
+            &mut ctx,
+            h,
+            AnimProp::PositionX,
+            20.0f32.to_bits(),
+            170.0,
+            26.0,
+        )
+        .unwrap();
+        assert_eq!(ctx.animations.len(), 1);
+        let after = ctx.animations[0].spring.unwrap();
+        assert_eq!(after.velocity, before.velocity);
+        assert_eq!(after.value, before.value);
+    }
+
+    #[test]
+    fn test_spring_rejects_color_and_bad_params() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let one = 1.0f32.to_bits();
+        assert!(start_spring(&mut ctx, h, AnimProp::FgColor, 0x01FF0000, 100.0, 10.0).is_err());
+        assert!(start_spring(&mut ctx, h, AnimProp::Opacity, one, 0.0, 10.0).is_err());
+        assert!(start_spring(&mut ctx, h, AnimProp::Opacity, one, 100.0, -1.0).is_err());
+        assert!(start_spring(&mut ctx, h, AnimProp::Opacity, one, f32::NAN, 1.0).is_err());
+        assert!(ctx.animations.is_empty());
+    }
+
+    #[test]
+    fn test_flip_transition_animates_from_old_position() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let b = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_flex(&mut ctx, root, 0, 1).unwrap(); // column
+        for h in [a, b] {
+            crate::layout::set_dimension(&mut ctx, h, 1, 2.0, 1).unwrap(); // height 2
+            tree::append_child(&mut ctx, root, h).unwrap();
+        }
+
+        assert_eq!(capture_positions(&mut ctx, root).unwrap(), 2);
+
+        // Insert a new item at the top: a and b shift down by 3 rows.
+        let inserted = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        crate::layout::set_dimension(&mut ctx, inserted, 1, 3.0, 1).unwrap();
+        tree::insert_child(&mut ctx, root, inserted, 0).unwrap();
+
+        let moved = play_position_transitions(&mut ctx, root, 100, Easing::Linear).unwrap();
+        assert_eq!(moved, 2);
+        assert!(ctx.captured_positions.is_empty());
+        assert_eq!(ctx.nodes[&a].render_offset, (0.0, -3.0));
+        assert_eq!(ctx.nodes[&b].render_offset, (0.0, -3.0));
+        assert_eq!(ctx.nodes[&inserted].render_offset, (0.0, 0.0));
+
+        advance_animations(&mut ctx, 50.0);
+        assert!((ctx.nodes[&a].render_offset.1 + 1.5).abs() < 0.01);
+        advance_animations(&mut ctx, 60.0);
+        assert_eq!(ctx.nodes[&a].render_offset, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_advance_records_perf_counters() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::Opacity,
+            0.0f32.to_bits(),
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+        let b = start_animation(
+            &mut ctx,
+            h,
+            AnimProp::FgColor,
+            0x01FF0000,
+            100,
+            Easing::Linear,
+        )
+        .unwrap();
+        chain_animation(&mut ctx, a, b).unwrap();
+
+        // Pending successor is not counted as advanced.
+        advance_animations(&mut ctx, 10.0);
+        assert_eq!(ctx.perf_anim_advanced, 1);
+
+        ctx.animations.clear();
+        advance_animations(&mut ctx, 10.0);
+        assert_eq!(ctx.perf_anim_advanced, 0);
+    }
+
+    // ── Interpolation tests ──────────────────────────────────────────────
+
+    #[test]
+    fn test_linear_interpolation_opacity() {
+        // Opacity 1.0 -> 0.0
+        let start = 1.0f32.to_bits();
+        let end = 0.0f32.to_bits();
+
+        let at_0 = f32::from_bits(interpolate_f32(start, end, 0.0));
+        assert!((at_0 - 1.0).abs() < 0.001);
+
+        let at_quarter = f32::from_bits(interpolate_f32(start, end, 0.25));
+        assert!((at_quarter - 0.75).abs() < 0.001);
+
+        let at_half = f32::from_bits(interpolate_f32(start, end, 0.5));
+        assert!((at_half - 0.5).abs() < 0.001);
+
+        let at_three_quarter = f32::from_bits(interpolate_f32(start, end, 0.75));
+        assert!((at_three_quarter - 0.25).abs() < 0.001);
+
+        let at_1 = f32::from_bits(interpolate_f32(start, end, 1.0));
+        assert!((at_1 - 0.0).abs() < 0.001);
     }
 
     #[test]
@@ -1258,6 +2330,99 @@ mod tests {
         assert!(ctx.animations[0].spinner.is_some());
     }
 
+    #[test]
+    fn test_spinner_with_custom_frames() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+
+        assert!(start_spinner_frames(&mut ctx, h, Vec::new(), 100).is_err());
+        let frames = ["|", "/", "-", "\\"].map(String::from).to_vec();
+        start_spinner_frames(&mut ctx, h, frames, 100).unwrap();
+
+        advance_animations(&mut ctx, 1.0);
+        assert_eq!(ctx.nodes[&h].content, "|");
+        advance_animations(&mut ctx, 300.0);
+        assert_eq!(ctx.nodes[&h].content, "\\");
+        advance_animations(&mut ctx, 100.0);
+        assert_eq!(ctx.nodes[&h].content, "|");
+    }
+
+    #[test]
+    fn test_color_spinner_cycles_fg_and_keeps_content() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.nodes.get_mut(&h).unwrap().content = "●".into();
+
+        assert!(start_color_spinner(&mut ctx, h, Vec::new(), 100).is_err());
+        let colors = vec![0x01FF0000, 0x0100FF00, 0x010000FF];
+        start_color_spinner(&mut ctx, h, colors, 100).unwrap();
+
+        advance_animations(&mut ctx, 1.0);
+        assert_eq!(ctx.nodes[&h].visual_style.fg_color, 0x01FF0000);
+        advance_animations(&mut ctx, 100.0);
+        assert_eq!(ctx.nodes[&h].visual_style.fg_color, 0x0100FF00);
+        advance_animations(&mut ctx, 200.0);
+        assert_eq!(ctx.nodes[&h].visual_style.fg_color, 0x01FF0000);
+        assert_eq!(ctx.nodes[&h].content, "●");
+
+        advance_animations(&mut ctx, 10_000.0);
+        assert_eq!(ctx.animations.len(), 1);
+    }
+
+    #[test]
+    fn test_typewriter_reveals_prefix_then_ends() {
+        use crate::types::TuiEventType;
+
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.nodes.get_mut(&h).unwrap().content = "héllo".into();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        assert!(start_typewriter(&mut ctx, input, 10.0).is_err());
+        assert!(start_typewriter(&mut ctx, h, 0.0).is_err());
+
+        let id = start_typewriter(&mut ctx, h, 10.0).unwrap();
+        assert_eq!(ctx.nodes[&h].typewriter_reveal, Some(0));
+        advance_animations(&mut ctx, 250.0);
+        assert_eq!(ctx.nodes[&h].typewriter_reveal, Some(2));
+        assert_eq!(ctx.nodes[&h].content, "héllo");
+        assert!(ctx.event_buffer.is_empty());
+
+        advance_animations(&mut ctx, 300.0);
+        assert_eq!(ctx.nodes[&h].typewriter_reveal, None);
+        assert!(ctx.animations.is_empty());
+        let end = ctx.event_buffer.pop().unwrap();
+        assert_eq!(end.event_type, TuiEventType::AnimationEnd as u32);
+        assert_eq!(end.data[0], id);
+
+        // Cancelling mid-reveal shows the whole text.
+        let id = start_typewriter(&mut ctx, h, 1.0).unwrap();
+        advance_animations(&mut ctx, 1500.0);
+        assert_eq!(ctx.nodes[&h].typewriter_reveal, Some(1));
+        cancel_animation(&mut ctx, id).unwrap();
+        assert_eq!(ctx.nodes[&h].typewriter_reveal, None);
+    }
+
+    #[test]
+    fn test_marquee_offset_wraps_until_cancelled() {
+        let mut ctx = test_ctx();
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.nodes.get_mut(&h).unwrap().content = "ticker".into();
+        assert!(start_marquee(&mut ctx, h, -1.0, 2).is_err());
+
+        let id = start_marquee(&mut ctx, h, 10.0, 2).unwrap();
+        assert_eq!(ctx.nodes[&h].text_marquee, Some((0, 2)));
+        advance_animations(&mut ctx, 500.0);
+        assert_eq!(ctx.nodes[&h].text_marquee, Some((5, 2)));
+        // Six cells of text plus a two-cell gap repeat every 800ms.
+        advance_animations(&mut ctx, 500.0);
+        assert_eq!(ctx.nodes[&h].text_marquee, Some((2, 2)));
+        advance_animations(&mut ctx, 60_000.0);
+        assert_eq!(ctx.animations.len(), 1);
+
+        cancel_animation(&mut ctx, id).unwrap();
+        assert_eq!(ctx.nodes[&h].text_marquee, None);
+    }
+
     // ── H1: Progress primitive tests ─────────────────────────────────────
 
     #[test]
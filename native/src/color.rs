@@ -0,0 +1,454 @@
+//! Color Module — helpers over the packed u32 color encoding.
+//!
+//! Responsibilities:
+//! - Packing and unpacking RGB truecolor values (tag 0x01)
+//! - Hex string parsing and formatting for host convenience APIs
+//! - HSL/HSV construction and lightness adjustment for palette derivation
+//! - xterm 256-color palette resolution and WCAG luminance/contrast picks
+//! - Nearest-palette quantization for terminals without truecolor
+//!
+//! The encoding itself is defined in `types.rs` (TechSpec §3.2). This module
+//! keeps the host-facing conversions next to each other so every host
+//! language shares one implementation instead of re-deriving the tag math.
+
+use crate::types::{color_tag, ColorMode, ErrorCode, TuiError};
+
+pub(crate) const TAG_DEFAULT: u8 = 0x00;
+pub(crate) const TAG_RGB: u8 = 0x01;
+pub(crate) const TAG_INDEXED: u8 = 0x02;
+
+/// Pack 8-bit RGB channels into a truecolor value (tag 0x01).
+pub(crate) fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    ((TAG_RGB as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
+
+/// Unpack an RGB truecolor value. Returns `None` for default and indexed colors.
+pub(crate) fn rgb_components(color: u32) -> Option<(u8, u8, u8)> {
+    if color_tag(color) != TAG_RGB {
+        return None;
+    }
+    Some((
+        ((color >> 16) & 0xFF) as u8,
+        ((color >> 8) & 0xFF) as u8,
+        (color & 0xFF) as u8,
+    ))
+}
+
+/// Parse a host color string into the packed encoding.
+///
+/// Accepted forms:
+/// - `#rgb`, `#rrggbb`, `#rrggbbaa` → RGB truecolor (alpha is ignored; the
+///   cell model has no per-color alpha, use opacity instead)
+/// - `default` → terminal default (tag 0x00)
+/// - `idx:N` with `N` in 0..=255 → indexed palette color (tag 0x02)
+pub(crate) fn parse_color(text: &str) -> Result<u32, TuiError> {
+    let trimmed = text.trim();
+    if trimmed.eq_ignore_ascii_case("default") {
+        return Ok(0);
+    }
+    if let Some(index) = trimmed.strip_prefix("idx:") {
+        let index: u8 = index.parse().map_err(|_| {
+            ErrorCode::InvalidArgument.raise(format!(
+                "Invalid indexed color: {trimmed:?} (expected idx:0..255)"
+            ))
+        })?;
+        return Ok(((TAG_INDEXED as u32) << 24) | index as u32);
+    }
+
+    let hex = trimmed.strip_prefix('#').ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!(
+            "Invalid color: {trimmed:?} (expected #rgb, #rrggbb, or #rrggbbaa)"
+        ))
+    })?;
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ErrorCode::InvalidArgument.raise(format!("Invalid hex color: {trimmed:?}")));
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).unwrap_or(0);
+    match hex.len() {
+        3 => {
+            let r = channel(&hex[0..1]);
+            let g = channel(&hex[1..2]);
+            let b = channel(&hex[2..3]);
+            Ok(rgb(r * 17, g * 17, b * 17))
+        }
+        6 | 8 => Ok(rgb(
+            channel(&hex[0..2]),
+            channel(&hex[2..4]),
+            channel(&hex[4..6]),
+        )),
+        _ => Err(ErrorCode::InvalidArgument.raise(format!(
+            "Invalid hex color: {trimmed:?} (expected 3, 6, or 8 hex digits)"
+        ))),
+    }
+}
+
+/// Format a packed color as a host string. Inverse of [`parse_color`].
+pub(crate) fn format_color(color: u32) -> Result<String, TuiError> {
+    match color_tag(color) {
+        TAG_DEFAULT => Ok("default".to_string()),
+        TAG_RGB => {
+            let (r, g, b) = rgb_components(color).unwrap_or((0, 0, 0));
+            Ok(format!("#{r:02x}{g:02x}{b:02x}"))
+        }
+        TAG_INDEXED => Ok(format!("idx:{}", color & 0xFF)),
+        tag => Err(ErrorCode::InvalidArgument.raise(format!("Invalid color tag: 0x{tag:02X}"))),
+    }
+}
+
+// ============================================================================
+// HSL / HSV
+// ============================================================================
+
+fn validate_components(a: f32, b: f32, c: f32) -> Result<(), TuiError> {
+    if !(a.is_finite() && b.is_finite() && c.is_finite()) {
+        return Err(ErrorCode::InvalidArgument.raise("Color components must be finite numbers"));
+    }
+    Ok(())
+}
+
+fn unit_to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Map a hue sector plus chroma/intermediate values onto RGB in 0..=1.
+fn hue_to_rgb(h: f32, c: f32, x: f32) -> (f32, f32, f32) {
+    match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+/// Build an RGB color from hue (degrees, wrapped), saturation and lightness
+/// (both 0..=1, clamped).
+pub(crate) fn from_hsl(h: f32, s: f32, l: f32) -> Result<u32, TuiError> {
+    validate_components(h, s, l)?;
+    let h = h.rem_euclid(360.0);
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = hue_to_rgb(h, c, x);
+    Ok(rgb(unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m)))
+}
+
+/// Build an RGB color from hue (degrees, wrapped), saturation and value
+/// (both 0..=1, clamped).
+pub(crate) fn from_hsv(h: f32, s: f32, v: f32) -> Result<u32, TuiError> {
+    validate_components(h, s, v)?;
+    let h = h.rem_euclid(360.0);
+    let s = s.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = hue_to_rgb(h, c, x);
+    Ok(rgb(unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m)))
+}
+
+/// Decompose an RGB color into (hue degrees, saturation, lightness).
+pub(crate) fn to_hsl(color: u32) -> Option<(f32, f32, f32)> {
+    let (r, g, b) = rgb_components(color)?;
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return Some((0.0, 0.0, l));
+    }
+    let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    Some((h, s, l))
+}
+
+/// Shift the HSL lightness of an RGB color by `amount` (-1..=1). Default and
+/// indexed colors pass through unchanged because their RGB value is unknown.
+pub(crate) fn adjust_lightness(color: u32, amount: f32) -> Result<u32, TuiError> {
+    if !amount.is_finite() {
+        return Err(ErrorCode::InvalidArgument.raise("Lightness amount must be a finite number"));
+    }
+    match to_hsl(color) {
+        Some((h, s, l)) => from_hsl(h, s, l + amount),
+        None => Ok(color),
+    }
+}
+
+// ============================================================================
+// Palette Resolution and Contrast
+// ============================================================================
+
+/// The 16 base ANSI colors as rendered by xterm's default palette.
+const ANSI_16: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xCD, 0x00, 0x00),
+    (0x00, 0xCD, 0x00),
+    (0xCD, 0xCD, 0x00),
+    (0x00, 0x00, 0xEE),
+    (0xCD, 0x00, 0xCD),
+    (0x00, 0xCD, 0xCD),
+    (0xE5, 0xE5, 0xE5),
+    (0x7F, 0x7F, 0x7F),
+    (0xFF, 0x00, 0x00),
+    (0x00, 0xFF, 0x00),
+    (0xFF, 0xFF, 0x00),
+    (0x5C, 0x5C, 0xFF),
+    (0xFF, 0x00, 0xFF),
+    (0x00, 0xFF, 0xFF),
+    (0xFF, 0xFF, 0xFF),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16..=231).
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+
+/// Resolve a 256-color palette index to the xterm default RGB value.
+pub(crate) fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_16[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[((i / 6) % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Resolve RGB and indexed colors to concrete channels. Default colors have
+/// no known RGB value and return `None`.
+pub(crate) fn resolve_rgb(color: u32) -> Option<(u8, u8, u8)> {
+    match color_tag(color) {
+        TAG_RGB => rgb_components(color),
+        TAG_INDEXED => Some(indexed_to_rgb((color & 0xFF) as u8)),
+        _ => None,
+    }
+}
+
+/// WCAG 2.x relative luminance of an sRGB color.
+pub(crate) fn relative_luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// Pick packed white or black, whichever has the higher WCAG contrast ratio
+/// against `bg`. A default background has no known luminance, so the terminal
+/// default foreground (0) is returned and the terminal keeps its own pairing.
+pub(crate) fn contrast_color(bg: u32) -> Result<u32, TuiError> {
+    match color_tag(bg) {
+        TAG_DEFAULT => return Ok(0),
+        TAG_RGB | TAG_INDEXED => {}
+        tag => {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid color tag: 0x{tag:02X}")))
+        }
+    }
+    let luminance = relative_luminance(resolve_rgb(bg).unwrap_or((0, 0, 0)));
+    // Contrast against white is 1.05 / (L + 0.05), against black
+    // (L + 0.05) / 0.05; they are equal at L ≈ 0.179.
+    let white_ratio = 1.05 / (luminance + 0.05);
+    let black_ratio = (luminance + 0.05) / 0.05;
+    Ok(if white_ratio >= black_ratio {
+        rgb(0xFF, 0xFF, 0xFF)
+    } else {
+        rgb(0x00, 0x00, 0x00)
+    })
+}
+
+/// Index of the palette entry nearest to `target` by squared RGB distance,
+/// searching the first `palette_len` xterm entries (16 or 256).
+fn nearest_palette_index(target: (u8, u8, u8), palette_len: usize) -> u8 {
+    let distance = |(r, g, b): (u8, u8, u8)| {
+        let dr = i32::from(r) - i32::from(target.0);
+        let dg = i32::from(g) - i32::from(target.1);
+        let db = i32::from(b) - i32::from(target.2);
+        dr * dr + dg * dg + db * db
+    };
+    (0..palette_len)
+        .map(|i| i as u8)
+        .min_by_key(|&i| distance(indexed_to_rgb(i)))
+        .unwrap_or(0)
+}
+
+/// Map a color to one the given output mode can display. RGB becomes the
+/// nearest xterm palette entry; in 16-color mode indexed colors above 15 are
+/// folded down too. Default colors and truecolor mode pass through.
+pub(crate) fn quantize(color: u32, mode: ColorMode) -> u32 {
+    let palette_len = match mode {
+        ColorMode::TrueColor => return color,
+        ColorMode::Color256 => 256,
+        ColorMode::Color16 => 16,
+    };
+    match color_tag(color) {
+        TAG_RGB => {
+            let channels = rgb_components(color).unwrap_or((0, 0, 0));
+            indexed(nearest_palette_index(channels, palette_len))
+        }
+        TAG_INDEXED if mode == ColorMode::Color16 && (color & 0xFF) > 15 => {
+            let index = (color & 0xFF) as u8;
+            indexed(nearest_palette_index(indexed_to_rgb(index), 16))
+        }
+        _ => color,
+    }
+}
+
+fn indexed(index: u8) -> u32 {
+    (u32::from(TAG_INDEXED) << 24) | u32::from(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_forms() {
+        assert_eq!(parse_color("#58a6ff").unwrap(), 0x0158A6FF);
+        assert_eq!(parse_color("#58A6FF").unwrap(), 0x0158A6FF);
+        assert_eq!(parse_color("#f00").unwrap(), 0x01FF0000);
+        assert_eq!(parse_color("#58a6ff80").unwrap(), 0x0158A6FF);
+    }
+
+    #[test]
+    fn test_parse_named_forms() {
+        assert_eq!(parse_color("default").unwrap(), 0);
+        assert_eq!(parse_color("idx:123").unwrap(), 0x0200007B);
+        assert!(parse_color("idx:256").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(parse_color("58a6ff").is_err());
+        assert!(parse_color("#58a6f").is_err());
+        assert!(parse_color("#zzzzzz").is_err());
+        assert!(parse_color("").is_err());
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        for input in ["#58a6ff", "default", "idx:123", "#000000"] {
+            let packed = parse_color(input).unwrap();
+            assert_eq!(format_color(packed).unwrap(), input);
+        }
+        assert!(format_color(0x03000000).is_err());
+    }
+
+    #[test]
+    fn test_from_hsl_primaries() {
+        assert_eq!(from_hsl(0.0, 1.0, 0.5).unwrap(), 0x01FF0000);
+        assert_eq!(from_hsl(120.0, 1.0, 0.5).unwrap(), 0x0100FF00);
+        assert_eq!(from_hsl(240.0, 1.0, 0.5).unwrap(), 0x010000FF);
+        assert_eq!(from_hsl(-120.0, 1.0, 0.5).unwrap(), 0x010000FF);
+        assert_eq!(from_hsl(0.0, 0.0, 1.0).unwrap(), 0x01FFFFFF);
+        assert!(from_hsl(f32::NAN, 1.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_from_hsv_primaries() {
+        assert_eq!(from_hsv(0.0, 1.0, 1.0).unwrap(), 0x01FF0000);
+        assert_eq!(from_hsv(60.0, 1.0, 1.0).unwrap(), 0x01FFFF00);
+        assert_eq!(from_hsv(0.0, 0.0, 0.0).unwrap(), 0x01000000);
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        let accent = 0x0158A6FF;
+        let (h, s, l) = to_hsl(accent).unwrap();
+        assert_eq!(from_hsl(h, s, l).unwrap(), accent);
+    }
+
+    #[test]
+    fn test_adjust_lightness() {
+        let base = 0x01808080;
+        let lighter = adjust_lightness(base, 0.1).unwrap();
+        let darker = adjust_lightness(base, -0.1).unwrap();
+        assert!(rgb_components(lighter).unwrap().0 > 0x80);
+        assert!(rgb_components(darker).unwrap().0 < 0x80);
+        assert_eq!(adjust_lightness(base, 1.0).unwrap(), 0x01FFFFFF);
+
+        // Non-RGB colors pass through unchanged.
+        assert_eq!(adjust_lightness(0, 0.2).unwrap(), 0);
+        assert_eq!(adjust_lightness(0x02000007, 0.2).unwrap(), 0x02000007);
+    }
+
+    #[test]
+    fn test_indexed_to_rgb_palette_regions() {
+        assert_eq!(indexed_to_rgb(1), (0xCD, 0x00, 0x00));
+        assert_eq!(indexed_to_rgb(16), (0x00, 0x00, 0x00));
+        assert_eq!(indexed_to_rgb(196), (0xFF, 0x00, 0x00));
+        assert_eq!(indexed_to_rgb(231), (0xFF, 0xFF, 0xFF));
+        assert_eq!(indexed_to_rgb(232), (0x08, 0x08, 0x08));
+        assert_eq!(indexed_to_rgb(255), (0xEE, 0xEE, 0xEE));
+    }
+
+    #[test]
+    fn test_contrast_color_picks_readable_foreground() {
+        let white = 0x01FFFFFF;
+        let black = 0x01000000;
+        assert_eq!(contrast_color(0x01000000).unwrap(), white);
+        assert_eq!(contrast_color(0x011E1E2E).unwrap(), white);
+        assert_eq!(contrast_color(0x01FFFFFF).unwrap(), black);
+        assert_eq!(contrast_color(0x01FFD700).unwrap(), black);
+        // Indexed colors resolve through the 256-color table.
+        assert_eq!(contrast_color(0x02000004).unwrap(), white); // blue
+        assert_eq!(contrast_color(0x020000E2).unwrap(), black); // bright yellow cube
+        assert_eq!(contrast_color(0).unwrap(), 0);
+        assert!(contrast_color(0x03000000).is_err());
+    }
+
+    #[test]
+    fn test_quantize_to_256_palette() {
+        // Exact cube and grayscale entries map to themselves.
+        assert_eq!(
+            quantize(rgb(0xFF, 0x00, 0x00), ColorMode::Color256),
+            0x02000009
+        );
+        assert_eq!(
+            quantize(rgb(0x5F, 0x87, 0xAF), ColorMode::Color256),
+            0x02000043
+        );
+        assert_eq!(
+            quantize(rgb(0x80, 0x80, 0x80), ColorMode::Color256),
+            0x020000F4
+        );
+        // Default and indexed colors pass through; truecolor is untouched.
+        assert_eq!(quantize(0, ColorMode::Color256), 0);
+        assert_eq!(quantize(0x020000C8, ColorMode::Color256), 0x020000C8);
+        assert_eq!(quantize(rgb(1, 2, 3), ColorMode::TrueColor), rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn test_quantize_to_16_palette() {
+        assert_eq!(
+            quantize(rgb(0xF0, 0x10, 0x10), ColorMode::Color16),
+            0x02000009
+        );
+        assert_eq!(
+            quantize(rgb(0x10, 0x10, 0x10), ColorMode::Color16),
+            0x02000000
+        );
+        // High indexed colors fold into the base 16.
+        assert_eq!(quantize(0x020000E7, ColorMode::Color16), 0x0200000F);
+        assert_eq!(quantize(0x02000004, ColorMode::Color16), 0x02000004);
+    }
+}
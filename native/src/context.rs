@@ -3,37 +3,92 @@
 //! The context owns all mutable state for the TUI system.
 //! A single global instance is managed via `tui_init()` / `tui_shutdown()`.
 
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
 use std::ops::{Deref, DerefMut};
-use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 #[cfg(not(test))]
 use std::thread::ThreadId;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::animation::{Animation, ChoreographyGroup};
 use crate::edit_buffer::EditBuffer;
+use crate::interner::StringInterner;
+use crate::node_store::NodeStore;
 use crate::terminal::TerminalBackend;
 use crate::terminal_capabilities::TerminalCapabilityState;
 use crate::text_buffer::TextBuffer;
 use crate::text_view::TextView;
 use crate::theme::Theme;
-use crate::types::{Buffer, DebugFrameSnapshot, DebugTraceEntry, TextCache, TuiEvent, TuiNode};
+use crate::types::{
+    log_level, Buffer, CaretStyle, DebugFrameSnapshot, DebugTraceEntry, ErrorCode, FrameLogEntry,
+    NodeText, TextCache, TuiError, TuiEvent, TuiStats,
+};
 use crate::writer::WriterState;
 
+/// Virtual clock installed by `tui_test_set_clock`. It reads the real time
+/// of the first call, `anchor`, when the host's milliseconds were
+/// `anchor_ms`, and advances only as the host's `ms` does.
+#[derive(Debug, Clone, Copy)]
+pub struct TestClock {
+    pub anchor: Instant,
+    pub anchor_ms: u64,
+    pub ms: u64,
+}
+
 pub struct TuiContext {
     // Tree Module
-    pub tree: taffy::TaffyTree<()>,
-    pub nodes: HashMap<u32, TuiNode>,
+    /// Text and Input leaves carry their handle as node context so layout can measure them.
+    pub tree: taffy::TaffyTree<u32>,
+    pub nodes: NodeStore,
     pub next_handle: u32,
     pub root: Option<u32>,
+    /// Maximum number of live nodes. 0 means unlimited.
+    pub max_nodes: u32,
 
     // Event Module
     pub event_buffer: Vec<TuiEvent>,
     pub focused: Option<u32>,
+    /// Idle heartbeat interval for `read_input`. 0 disables Tick events.
+    pub tick_interval_ms: u32,
+    /// Time of the last Tick or real input; the idle clock restarts here.
+    pub last_tick_time: Option<Instant>,
+    /// Idle window after which a Select's typeahead buffer starts over.
+    pub typeahead_ms: u32,
+    /// When set, an unconsumed Escape moves focus to the nearest focusable
+    /// ancestor (or clears it) instead of being delivered as a Key event.
+    pub escape_blurs: bool,
+    /// Focus target (0 = none) the host hid the caret for; the caret shows
+    /// again as soon as focus moves elsewhere. None = caret visible.
+    pub caret_hidden_for: Option<u32>,
+    pub caret_style: CaretStyle,
+    /// Characters that break words for Ctrl+Arrow / Ctrl+Backspace, in
+    /// addition to whitespace. Empty = whitespace only.
+    pub word_break_chars: String,
+    /// Set once any node enables opacity cascade; until then style
+    /// resolution skips the ancestor walk.
+    pub opacity_cascade_used: bool,
+    /// Image nodes placed this frame as (handle, x, y, w, h); their payloads
+    /// are written after the frame's runs.
+    pub image_placements: Vec<(u32, u16, u16, u16, u16)>,
+    /// Emit ASCII stand-ins for box, block and braille glyphs. None = on
+    /// exactly when the terminal lacks the UTF8 capability.
+    pub ascii_fallback: Option<bool>,
+    /// Screen rows drawn this frame by scaled Text, with their scale.
+    pub line_scale_rows: Vec<(u16, crate::types::LineScale)>,
+    /// DEC line attribute last written for each screen row; None = unknown,
+    /// so the next frame writes it.
+    pub emitted_line_scales: Vec<Option<crate::types::LineScale>>,
+    /// Popup menu capturing input and drawn above the tree, if any.
+    pub open_menu: Option<crate::menu::OpenMenu>,
 
     // Render Module
     pub front_buffer: Buffer,
     pub back_buffer: Buffer,
+    /// Cell updates of the current frame; cleared and refilled every frame
+    /// so its allocation is reused.
+    pub diff_updates: Vec<crate::types::CellUpdate>,
     pub backend: Box<dyn TerminalBackend>,
     pub terminal_capabilities: TerminalCapabilityState,
 
@@ -63,9 +118,33 @@ pub struct TuiContext {
     pub next_anim_handle: u32,
     pub next_choreo_group_handle: u32,
     pub last_render_time: Option<Instant>,
+    /// Virtual clock set by `tui_test_set_clock`. `None` reads the real
+    /// clock.
+    pub test_clock: Option<TestClock>,
+    /// `tui_render` is skipped while less than this has passed since the
+    /// last frame and nothing changed. 0 = never skipped.
+    pub min_render_interval_ms: u32,
+    /// Set by `invalidate` so a throttled `tui_render` still repaints.
+    pub screen_invalidated: bool,
+    /// Set by mutations that affect layout (Taffy styles, tree structure).
+    /// While clear, `compute_layout` keeps the previous Taffy layout.
+    pub layout_dirty: bool,
+    /// Root handle and terminal size the current layout was computed for.
+    pub layout_basis: Option<(u32, u16, u16)>,
+    /// Global paint state of the last full frame; `render` is skipped while
+    /// it still matches and nothing is dirty.
+    pub(crate) last_frame_key: Option<crate::render::FrameKey>,
+    /// When false, `render` leaves animation timing to the host
+    /// (`tui_advance_animations`).
+    pub auto_advance_animations: bool,
+    /// Global multiplier on animation time (0 freezes, 1 is real time).
+    pub animation_time_scale: f32,
+    /// Emit AnimationStart when a chained/choreographed animation activates.
+    pub animation_start_events: bool,
+    /// Visual positions recorded by `capture_positions` for FLIP transitions.
+    pub captured_positions: HashMap<u32, (f32, f32)>,
 
     // Diagnostics
-    pub last_error: String,
     pub debug_mode: bool,
     pub perf_layout_us: u64,
     pub perf_render_us: u64,
@@ -77,6 +156,10 @@ pub struct TuiContext {
     pub perf_text_wrap_us: u64,
     pub perf_text_cache_hits: u32,
     pub perf_text_cache_misses: u32,
+    pub perf_anim_advance_us: u64,
+    pub perf_anim_advanced: u32,
+    /// Wall-clock gap between the last two `render` calls.
+    pub perf_frame_interval_us: u64,
 
     // Dev Mode (ADR-T34)
     pub debug_overlay_flags: u32,
@@ -86,12 +169,23 @@ pub struct TuiContext {
     pub debug_frames: VecDeque<DebugFrameSnapshot>,
     pub next_debug_seq: u64,
     pub frame_seq: u64,
+    /// Post-mortem frame log ring; recording is off while capacity is 0.
+    pub frame_log: VecDeque<FrameLogEntry>,
+    pub frame_log_capacity: usize,
+    /// Set between `tui_suspend` and `tui_resume`; rendering is a no-op while
+    /// the host owns the terminal.
+    pub suspended: bool,
+    /// Strip control characters from `tui_set_content` text (default on).
+    pub sanitize_content: bool,
+    /// Shares repeated content and option text while set
+    /// (`tui_set_string_interning`).
+    pub string_interner: Option<StringInterner>,
 }
 
-// SAFETY: ADR-T16 preserves Kraken TUI's single-threaded execution model.
-// The lock is used for aliasing safety at the FFI boundary, not to introduce
-// concurrent access. We never intentionally share mutable context access across
-// threads in production code paths.
+// SAFETY: every field is Send + Sync except Taffy's `CompactLength`, which
+// may hold a pointer to a host-provided calc() resolver. Kraken TUI never
+// creates calc() lengths, so the pointer is always a plain tagged value.
+// Mutable access is serialized by the global RwLock (ADR-T16).
 unsafe impl Send for TuiContext {}
 unsafe impl Sync for TuiContext {}
 
@@ -99,21 +193,39 @@ impl TuiContext {
     pub fn new(mut backend: Box<dyn TerminalBackend>) -> Self {
         let (w, h) = backend.size();
         let terminal_capabilities = backend.capabilities();
+        let mut writer_state = WriterState::new();
+        writer_state.color_mode =
+            crate::types::ColorMode::from_capabilities(&terminal_capabilities);
         Self {
             tree: taffy::TaffyTree::new(),
-            nodes: HashMap::new(),
+            nodes: NodeStore::new(),
             next_handle: 1, // Handle(0) is permanently invalid
             root: None,
+            max_nodes: 0,
 
             event_buffer: Vec::new(),
+            tick_interval_ms: 0,
+            last_tick_time: None,
+            typeahead_ms: 1000,
+            escape_blurs: false,
+            caret_hidden_for: None,
+            caret_style: CaretStyle::default(),
+            word_break_chars: String::new(),
+            opacity_cascade_used: false,
+            image_placements: Vec::new(),
+            ascii_fallback: None,
+            line_scale_rows: Vec::new(),
+            emitted_line_scales: Vec::new(),
+            open_menu: None,
             focused: None,
 
             front_buffer: Buffer::new(w, h),
             back_buffer: Buffer::new(w, h),
+            diff_updates: Vec::new(),
             backend,
             terminal_capabilities,
 
-            writer_state: WriterState::new(),
+            writer_state,
 
             syntax_set: syntect::parsing::SyntaxSet::load_defaults_newlines(),
             theme_set: syntect::highlighting::ThemeSet::load_defaults(),
@@ -138,8 +250,17 @@ impl TuiContext {
             next_anim_handle: 1,
             next_choreo_group_handle: 1,
             last_render_time: None,
+            test_clock: None,
+            min_render_interval_ms: 0,
+            screen_invalidated: false,
+            layout_dirty: true,
+            layout_basis: None,
+            last_frame_key: None,
+            auto_advance_animations: true,
+            animation_time_scale: 1.0,
+            animation_start_events: false,
+            captured_positions: HashMap::new(),
 
-            last_error: String::new(),
             debug_mode: false,
             perf_layout_us: 0,
             perf_render_us: 0,
@@ -151,6 +272,9 @@ impl TuiContext {
             perf_text_wrap_us: 0,
             perf_text_cache_hits: 0,
             perf_text_cache_misses: 0,
+            perf_anim_advance_us: 0,
+            perf_anim_advanced: 0,
+            perf_frame_interval_us: 0,
 
             debug_overlay_flags: 0,
             debug_trace_flags: 0,
@@ -163,22 +287,85 @@ impl TuiContext {
             debug_frames: VecDeque::new(),
             next_debug_seq: 0,
             frame_seq: 0,
+            frame_log: VecDeque::new(),
+            frame_log_capacity: 0,
+            suspended: false,
+            sanitize_content: true,
+            string_interner: None,
         }
     }
 
     /// Validate that a handle refers to an existing node.
-    pub fn validate_handle(&self, handle: u32) -> Result<(), String> {
+    ///
+    /// Node handles are allocated sequentially and never recycled (ADR-003),
+    /// so any missing handle below `next_handle` was allocated by this context
+    /// and later destroyed. The error says so, which separates use-after-destroy
+    /// bugs in the host from handles that were never valid at all.
+    pub fn validate_handle(&self, handle: u32) -> Result<(), TuiError> {
         if handle == 0 {
-            return Err("Handle(0) is the invalid sentinel".to_string());
+            return Err(ErrorCode::InvalidHandle.raise("Handle(0) is the invalid sentinel"));
         }
         if !self.nodes.contains_key(&handle) {
-            return Err(format!("Invalid handle: {handle}"));
+            if handle < self.next_handle {
+                return Err(ErrorCode::InvalidHandle.raise(format!(
+                    "Invalid handle: {handle} (node was destroyed; handles are never reused)"
+                )));
+            }
+            return Err(ErrorCode::InvalidHandle
+                .raise(format!("Invalid handle: {handle} (never allocated)")));
         }
         Ok(())
     }
 
+    /// `text` as node text, shared with earlier copies while string
+    /// interning is on.
+    pub fn node_text(&mut self, text: String) -> NodeText {
+        match self.string_interner.as_mut() {
+            Some(interner) if !text.is_empty() => interner.intern(&text),
+            _ => text.into(),
+        }
+    }
+
+    /// Whether the focused widget's caret is drawn. Hiding it (for a
+    /// host-driven blink) only lasts while focus stays where it was.
+    pub fn caret_visible(&self) -> bool {
+        self.caret_hidden_for != Some(self.focused.unwrap_or(0))
+    }
+
+    /// Collect the per-frame statistics returned by `tui_get_stats`.
+    pub fn stats(&self) -> TuiStats {
+        TuiStats {
+            layout_us: self.perf_layout_us,
+            render_us: self.perf_render_us,
+            diff_cells: self.perf_diff_cells,
+            node_count: self.nodes.len() as u32,
+            dirty_count: self.nodes.values().filter(|n| n.dirty).count() as u32,
+            animation_count: self.animations.len() as u32,
+            event_buffer_len: self.event_buffer.len() as u32,
+        }
+    }
+
+    /// Current time for animations, ticks, render throttling and typeahead:
+    /// the host's virtual clock once `tui_test_set_clock` set one.
+    pub fn now(&self) -> Instant {
+        match self.test_clock {
+            Some(clock) => clock.anchor + Duration::from_millis(clock.ms - clock.anchor_ms),
+            None => Instant::now(),
+        }
+    }
+
     pub fn debug_log(&self, msg: &str) {
-        if self.debug_mode {
+        self.log(log_level::DEBUG, msg);
+    }
+
+    /// Send `msg` to the host logger if one is installed, otherwise to
+    /// stderr in debug mode. Debug messages are dropped outside debug mode
+    /// either way.
+    pub fn log(&self, level: u8, msg: &str) {
+        if level == log_level::DEBUG && !self.debug_mode {
+            return;
+        }
+        if !forward_log(level, msg) && self.debug_mode {
             eprintln!("[kraken-tui] {msg}");
         }
     }
@@ -186,12 +373,12 @@ impl TuiContext {
     /// Allocate a fresh handle for a substrate object (`TextBuffer`,
     /// `TextView`, `EditBuffer`). Substrate handles share a counter so a
     /// handle from one map is never confused with one from another.
-    pub fn alloc_substrate_handle(&mut self) -> Result<u32, String> {
+    pub fn alloc_substrate_handle(&mut self) -> Result<u32, TuiError> {
         let h = self.next_substrate_handle;
         self.next_substrate_handle = self
             .next_substrate_handle
             .checked_add(1)
-            .ok_or_else(|| "Substrate handle counter overflow".to_string())?;
+            .ok_or_else(|| ErrorCode::LimitExceeded.raise("Substrate handle counter overflow"))?;
         Ok(h)
     }
 }
@@ -203,6 +390,40 @@ impl TuiContext {
 static CONTEXT: OnceLock<RwLock<Option<TuiContext>>> = OnceLock::new();
 #[cfg(not(test))]
 static OWNER_THREAD: OnceLock<RwLock<Option<ThreadId>>> = OnceLock::new();
+/// While set (the default), only the thread that initialized the context may
+/// use it. While clear, any thread may, one call at a time.
+static THREAD_AFFINITY: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    /// Error of the calling thread's last failed FFI call. Per thread, so a
+    /// call on another thread can neither clear nor replace it.
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+    /// Kind of `LAST_ERROR`.
+    static LAST_ERROR_CODE: Cell<ErrorCode> = const { Cell::new(ErrorCode::None) };
+    /// Set while this thread holds the context lock. A nested acquisition,
+    /// e.g. an FFI call from inside a host callback, would deadlock on the
+    /// RwLock, so it fails instead.
+    static CONTEXT_HELD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the context as held by the current thread until dropped.
+struct HeldMarker;
+
+impl Drop for HeldMarker {
+    fn drop(&mut self) {
+        CONTEXT_HELD.with(|held| held.set(false));
+    }
+}
+
+fn enter_context() -> Result<HeldMarker, TuiError> {
+    if CONTEXT_HELD.with(|held| held.replace(true)) {
+        return Err(ErrorCode::ThreadAccess.raise(
+            "Reentrant context access: FFI called while this thread holds the context \
+             (e.g. from a host callback)",
+        ));
+    }
+    Ok(HeldMarker)
+}
 
 fn context_lock() -> &'static RwLock<Option<TuiContext>> {
     CONTEXT.get_or_init(|| RwLock::new(None))
@@ -217,7 +438,7 @@ fn lock_poisoned(name: &str, detail: impl std::fmt::Display) -> String {
     format!("{name} lock poisoned after panic: {detail}")
 }
 
-fn ensure_thread_affinity() -> Result<(), String> {
+fn ensure_thread_affinity() -> Result<(), TuiError> {
     #[cfg(test)]
     {
         return Ok(());
@@ -225,13 +446,17 @@ fn ensure_thread_affinity() -> Result<(), String> {
 
     #[cfg(not(test))]
     {
+        if !THREAD_AFFINITY.load(Ordering::Relaxed) {
+            return Ok(());
+        }
         let current = std::thread::current().id();
         let owner = owner_thread_lock()
             .read()
             .map_err(|e| lock_poisoned("owner_thread", e))?;
         if let Some(owner_id) = *owner {
             if owner_id != current {
-                return Err("Context access from non-owner thread is unsupported".to_string());
+                return Err(ErrorCode::ThreadAccess
+                    .raise("Context access from non-owner thread is unsupported"));
             }
         }
         Ok(())
@@ -239,14 +464,15 @@ fn ensure_thread_affinity() -> Result<(), String> {
 }
 
 #[cfg(not(test))]
-fn bind_owner_thread_current() -> Result<(), String> {
+fn bind_owner_thread_current() -> Result<(), TuiError> {
     let current = std::thread::current().id();
     let mut owner = owner_thread_lock()
         .write()
         .map_err(|e| lock_poisoned("owner_thread", e))?;
     if let Some(owner_id) = *owner {
         if owner_id != current {
-            return Err("Context access from non-owner thread is unsupported".to_string());
+            return Err(ErrorCode::ThreadAccess
+                .raise("Context access from non-owner thread is unsupported"));
         }
     }
     *owner = Some(current);
@@ -254,12 +480,12 @@ fn bind_owner_thread_current() -> Result<(), String> {
 }
 
 #[cfg(test)]
-fn bind_owner_thread_current() -> Result<(), String> {
+fn bind_owner_thread_current() -> Result<(), TuiError> {
     Ok(())
 }
 
 #[cfg(not(test))]
-fn clear_owner_thread() -> Result<(), String> {
+fn clear_owner_thread() -> Result<(), TuiError> {
     let mut owner = owner_thread_lock()
         .write()
         .map_err(|e| lock_poisoned("owner_thread", e))?;
@@ -268,7 +494,7 @@ fn clear_owner_thread() -> Result<(), String> {
 }
 
 #[cfg(test)]
-fn clear_owner_thread() -> Result<(), String> {
+fn clear_owner_thread() -> Result<(), TuiError> {
     Ok(())
 }
 
@@ -287,6 +513,7 @@ pub fn ffi_test_guard() -> std::sync::MutexGuard<'static, ()> {
 
 pub struct ContextReadGuard<'a> {
     guard: RwLockReadGuard<'a, Option<TuiContext>>,
+    _held: HeldMarker,
 }
 
 impl Deref for ContextReadGuard<'_> {
@@ -301,6 +528,7 @@ impl Deref for ContextReadGuard<'_> {
 
 pub struct ContextWriteGuard<'a> {
     guard: RwLockWriteGuard<'a, Option<TuiContext>>,
+    _held: HeldMarker,
 }
 
 impl Deref for ContextWriteGuard<'_> {
@@ -322,47 +550,56 @@ impl DerefMut for ContextWriteGuard<'_> {
 }
 
 /// Acquire a read lock for the global context.
-pub fn context_read() -> Result<ContextReadGuard<'static>, String> {
+pub fn context_read() -> Result<ContextReadGuard<'static>, TuiError> {
     ensure_thread_affinity()?;
+    let held = enter_context()?;
     let guard = context_lock()
         .read()
         .map_err(|e| lock_poisoned("context", e))?;
     if guard.is_none() {
-        return Err("Context not initialized. Call tui_init() first.".to_string());
+        return Err(
+            ErrorCode::NotInitialized.raise("Context not initialized. Call tui_init() first.")
+        );
     }
-    Ok(ContextReadGuard { guard })
+    Ok(ContextReadGuard { guard, _held: held })
 }
 
 /// Acquire a write lock for the global context.
-pub fn context_write() -> Result<ContextWriteGuard<'static>, String> {
+pub fn context_write() -> Result<ContextWriteGuard<'static>, TuiError> {
     ensure_thread_affinity()?;
+    let held = enter_context()?;
     let guard = context_lock()
         .write()
         .map_err(|e| lock_poisoned("context", e))?;
     if guard.is_none() {
-        return Err("Context not initialized. Call tui_init() first.".to_string());
+        return Err(
+            ErrorCode::NotInitialized.raise("Context not initialized. Call tui_init() first.")
+        );
     }
-    Ok(ContextWriteGuard { guard })
+    Ok(ContextWriteGuard { guard, _held: held })
 }
 
 /// Initialize the global context with the given backend.
-pub fn init_context(backend: Box<dyn TerminalBackend>) -> Result<(), String> {
+pub fn init_context(backend: Box<dyn TerminalBackend>) -> Result<(), TuiError> {
     ensure_thread_affinity()?;
+    let _held = enter_context()?;
     bind_owner_thread_current()?;
 
     let mut guard = context_lock()
         .write()
         .map_err(|e| lock_poisoned("context", e))?;
     if guard.is_some() {
-        return Err("Context already initialized. Call tui_shutdown() first.".to_string());
+        return Err(ErrorCode::AlreadyInitialized
+            .raise("Context already initialized. Call tui_shutdown() first."));
     }
     *guard = Some(TuiContext::new(backend));
     Ok(())
 }
 
 /// Check whether a context is currently initialized.
-pub fn is_context_initialized() -> Result<bool, String> {
+pub fn is_context_initialized() -> Result<bool, TuiError> {
     ensure_thread_affinity()?;
+    let _held = enter_context()?;
     let guard = context_lock()
         .read()
         .map_err(|e| lock_poisoned("context", e))?;
@@ -370,68 +607,92 @@ pub fn is_context_initialized() -> Result<bool, String> {
 }
 
 /// Destroy the global context and return the backend for shutdown.
-pub fn destroy_context() -> Result<Option<Box<dyn TerminalBackend>>, String> {
+pub fn destroy_context() -> Result<Option<Box<dyn TerminalBackend>>, TuiError> {
     ensure_thread_affinity()?;
+    let _held = enter_context()?;
     let mut guard = context_lock()
         .write()
         .map_err(|e| lock_poisoned("context", e))?;
     let backend = guard.take().map(|ctx| ctx.backend);
     drop(guard);
     clear_owner_thread()?;
+    set_thread_affinity(true);
     Ok(backend)
 }
 
-/// Store an error message in the global context (best-effort; ignores if no context).
-pub fn set_last_error(msg: String) {
-    if ensure_thread_affinity().is_err() {
-        return;
-    }
-    if let Ok(mut guard) = context_lock().write() {
-        if let Some(ctx) = guard.as_mut() {
-            ctx.last_error = msg;
-        }
-    }
+/// Require (the default) or lift the owner-thread check on context access.
+/// Either way, calls are serialized through the context lock.
+pub fn set_thread_affinity(enabled: bool) {
+    THREAD_AFFINITY.store(enabled, Ordering::Relaxed);
+}
+
+/// Record the calling thread's last error and its kind, and report it to the
+/// host logger.
+pub fn set_last_error(error: TuiError) {
+    forward_log(log_level::ERROR, &error.message);
+    LAST_ERROR_CODE.with(|slot| slot.set(error.code));
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = error.message);
 }
 
-/// Clear the context-bound error message.
-///
-/// Fast path: peek under a read lock first and skip the write lock when
-/// `last_error` is already empty. Every successful FFI call (substrate
-/// getters, mutators, handle constructors) calls this, so avoiding the
-/// write-lock acquisition on the common case (clean slate) reduces
-/// contention and per-call cost on hot getters polled in a host render
-/// loop. The read-lock check plus dropped guard is far cheaper than
-/// reacquiring the write lock.
+/// Clear the calling thread's last error. Every successful FFI call does
+/// this, so it takes no lock.
 pub fn clear_last_error() {
-    if ensure_thread_affinity().is_err() {
-        return;
-    }
-    if let Ok(guard) = context_lock().read() {
-        match guard.as_ref() {
-            Some(ctx) if ctx.last_error.is_empty() => return,
-            None => return,
-            _ => {}
-        }
-    }
-    if let Ok(mut guard) = context_lock().write() {
-        if let Some(ctx) = guard.as_mut() {
-            ctx.last_error.clear();
-        }
-    }
+    LAST_ERROR_CODE.with(|code| code.set(ErrorCode::None));
+    LAST_ERROR.with(|slot| slot.borrow_mut().clear());
 }
 
-/// Snapshot the last error into owned memory.
-pub fn get_last_error_snapshot() -> Option<String> {
-    if ensure_thread_affinity().is_err() {
-        return None;
-    }
-    if let Ok(guard) = context_lock().read() {
-        if let Some(ctx) = guard.as_ref() {
-            if !ctx.last_error.is_empty() {
-                return Some(ctx.last_error.clone());
-            }
-        }
-    }
+/// Kind of the calling thread's last error; `None` after a success.
+pub fn get_last_error_code() -> ErrorCode {
+    LAST_ERROR_CODE.with(Cell::get)
+}
 
-    None
+// ============================================================================
+// Host Logger
+// ============================================================================
+
+/// Host logger installed with `tui_set_log_callback`. `msg` is UTF-8, not
+/// NUL-terminated, and only valid for the duration of the call.
+pub type LogCallback =
+    extern "C" fn(level: u8, msg: *const u8, len: u32, user_data: *mut std::ffi::c_void);
+
+#[derive(Clone, Copy)]
+struct LogSink {
+    cb: LogCallback,
+    user_data: *mut std::ffi::c_void,
+}
+
+// SAFETY: `user_data` is never dereferenced here, only handed back to the
+// host's callback. Errors are logged from whichever thread made the failing
+// call, so with thread affinity off the host's logger must be thread-safe.
+unsafe impl Send for LogSink {}
+unsafe impl Sync for LogSink {}
+
+/// Process-wide rather than per context, so init failures and calls made
+/// before init are logged too.
+static LOG_SINK: RwLock<Option<LogSink>> = RwLock::new(None);
+
+/// Install (`Some`) or remove (`None`) the host logger.
+pub fn set_log_callback(cb: Option<LogCallback>, user_data: *mut std::ffi::c_void) {
+    let sink = cb.map(|cb| LogSink { cb, user_data });
+    *LOG_SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+}
+
+/// Hand `msg` to the host logger. Returns false when none is installed.
+/// The sink lock is released before the call, so the logger may replace
+/// itself; FFI calls from inside it fail as reentrant context access.
+fn forward_log(level: u8, msg: &str) -> bool {
+    let sink = *LOG_SINK.read().unwrap_or_else(PoisonError::into_inner);
+    let Some(sink) = sink else {
+        return false;
+    };
+    (sink.cb)(level, msg.as_ptr(), msg.len() as u32, sink.user_data);
+    true
+}
+
+/// Snapshot the calling thread's last error into owned memory.
+pub fn get_last_error_snapshot() -> Option<String> {
+    LAST_ERROR.with(|slot| {
+        let error = slot.borrow();
+        (!error.is_empty()).then(|| error.clone())
+    })
 }
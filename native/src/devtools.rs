@@ -4,7 +4,9 @@
 //! - Push bounded trace entries per kind (event, focus, dirty, viewport)
 //! - Take frame snapshots after render
 //! - Serialize debug snapshot and trace streams to JSON
+//! - Explain which size constraints decided a node's computed layout
 //! - Render debug overlays into the back buffer (without mutating layout)
+//! - Opt-in frame log ring for post-mortem timing (independent of debug_mode)
 //!
 //! ADR-T34: Dev Mode Is Core Product Work
 //!
@@ -15,8 +17,8 @@ use serde::Serialize;
 
 use crate::context::TuiContext;
 use crate::types::{
-    overlay_flags, trace_kind, Cell, CellAttrs, DebugFrameSnapshot, DebugTraceEntry,
-    DEBUG_TRACE_MAX,
+    overlay_flags, trace_kind, Cell, CellAttrs, DebugFrameSnapshot, DebugTraceEntry, ErrorCode,
+    FrameLogEntry, TuiError, DEBUG_TRACE_MAX, FRAME_LOG_MAX,
 };
 
 // ============================================================================
@@ -100,6 +102,58 @@ pub(crate) fn take_frame_snapshot(ctx: &mut TuiContext) {
     });
 }
 
+// ============================================================================
+// Frame Log
+// ============================================================================
+
+/// Set the frame log capacity (clamped to `FRAME_LOG_MAX`). 0 disables
+/// recording and drops the log; shrinking keeps the newest entries.
+pub(crate) fn set_frame_log_capacity(ctx: &mut TuiContext, capacity: u32) {
+    let capacity = (capacity as usize).min(FRAME_LOG_MAX);
+    ctx.frame_log_capacity = capacity;
+    while ctx.frame_log.len() > capacity {
+        ctx.frame_log.pop_front();
+    }
+    if capacity == 0 {
+        ctx.frame_log.shrink_to_fit();
+    }
+}
+
+/// Append the just-finished render to the frame log. No-op when disabled.
+/// Must run before dirty flags are cleared.
+pub(crate) fn record_frame(ctx: &mut TuiContext) {
+    if ctx.frame_log_capacity == 0 {
+        return;
+    }
+    let timestamp_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    if ctx.frame_log.len() >= ctx.frame_log_capacity {
+        ctx.frame_log.pop_front();
+    }
+    ctx.frame_log.push_back(FrameLogEntry {
+        timestamp_ms,
+        dirty_nodes: ctx.nodes.values().filter(|n| n.dirty).count() as u32,
+        diff_cells: ctx.perf_diff_cells,
+        render_us: ctx.perf_render_us,
+    });
+}
+
+/// Serialize the frame log oldest-first, one line per render:
+/// `<unix_ms> dirty=<n> diff=<n> render_us=<n>`.
+pub(crate) fn build_frame_log_text(ctx: &TuiContext) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    for entry in &ctx.frame_log {
+        let _ = writeln!(
+            out,
+            "{} dirty={} diff={} render_us={}",
+            entry.timestamp_ms, entry.dirty_nodes, entry.diff_cells, entry.render_us
+        );
+    }
+    out
+}
+
 // ============================================================================
 // Snapshot Serialization
 // ============================================================================
@@ -200,7 +254,7 @@ fn build_widget_tree(
 }
 
 /// Build and serialize the full debug snapshot to a JSON string.
-pub(crate) fn build_snapshot_json(ctx: &TuiContext) -> Result<String, String> {
+pub(crate) fn build_snapshot_json(ctx: &TuiContext) -> Result<String, TuiError> {
     let latest_frame = ctx.debug_frames.back();
     let frame_id = latest_frame.map_or(ctx.frame_seq, |f| f.frame_id);
     let focused = ctx.focused.unwrap_or(0);
@@ -265,17 +319,189 @@ pub(crate) fn build_snapshot_json(ctx: &TuiContext) -> Result<String, String> {
         _phantom: std::marker::PhantomData,
     };
 
-    serde_json::to_string(&snap).map_err(|e| format!("Snapshot serialization failed: {e}"))
+    serde_json::to_string(&snap).map_err(|e| format!("Snapshot serialization failed: {e}").into())
 }
 
 /// Build and serialize trace entries of a given kind to JSON.
-pub(crate) fn build_trace_json(ctx: &TuiContext, kind: u8) -> Result<String, String> {
+pub(crate) fn build_trace_json(ctx: &TuiContext, kind: u8) -> Result<String, TuiError> {
     let idx = kind as usize;
     if idx >= trace_kind::COUNT {
         return Ok("[]".to_string());
     }
     let entries: Vec<&DebugTraceEntry> = ctx.debug_traces[idx].iter().collect();
-    serde_json::to_string(&entries).map_err(|e| format!("Trace serialization failed: {e}"))
+    serde_json::to_string(&entries).map_err(|e| format!("Trace serialization failed: {e}").into())
+}
+
+// ============================================================================
+// Layout Explanation
+// ============================================================================
+
+#[derive(Serialize)]
+struct AxisExplainJson {
+    requested: String,
+    min: String,
+    max: String,
+    /// Parent content-box size on this axis (terminal size for the root).
+    available: f32,
+    resolved: f32,
+}
+
+#[derive(Serialize)]
+struct LayoutExplainJson {
+    handle: u32,
+    width: AxisExplainJson,
+    height: AxisExplainJson,
+    flex_grow: f32,
+    flex_shrink: f32,
+    flex_basis: String,
+    /// Constraints that decided the resolved size, e.g. `max_width`, `flex_shrink`.
+    active: Vec<&'static str>,
+}
+
+fn dimension_label(dim: taffy::Dimension) -> String {
+    match dim.tag() {
+        taffy::CompactLength::LENGTH_TAG => format!("{}", dim.value()),
+        taffy::CompactLength::PERCENT_TAG => format!("{}%", dim.value() * 100.0),
+        _ => "auto".to_string(),
+    }
+}
+
+fn resolve_dimension(dim: taffy::Dimension, available: f32) -> Option<f32> {
+    match dim.tag() {
+        taffy::CompactLength::LENGTH_TAG => Some(dim.value()),
+        taffy::CompactLength::PERCENT_TAG => Some(dim.value() * available),
+        _ => None,
+    }
+}
+
+/// Whether two cell sizes round to the same cell.
+fn same_cells(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.5
+}
+
+/// Report a node's requested vs resolved size and which constraints won,
+/// read from its Taffy style and last computed layout (no layout re-run).
+pub(crate) fn build_layout_explain_json(ctx: &TuiContext, handle: u32) -> Result<String, TuiError> {
+    let node = ctx
+        .nodes
+        .get(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let style = ctx
+        .tree
+        .style(node.taffy_node)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?;
+    let layout = ctx
+        .tree
+        .layout(node.taffy_node)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to get layout: {e:?}")))?;
+
+    let parent_taffy = node
+        .parent
+        .and_then(|parent| ctx.nodes.get(&parent))
+        .map(|parent| parent.taffy_node);
+    let available = match parent_taffy.and_then(|tn| ctx.tree.layout(tn).ok()) {
+        Some(parent) => (
+            parent.size.width
+                - parent.padding.left
+                - parent.padding.right
+                - parent.border.left
+                - parent.border.right,
+            parent.size.height
+                - parent.padding.top
+                - parent.padding.bottom
+                - parent.border.top
+                - parent.border.bottom,
+        ),
+        None => {
+            let (w, h) = ctx.backend.size();
+            (w as f32, h as f32)
+        }
+    };
+
+    let mut active = Vec::new();
+    let axes = [
+        (
+            style.size.width,
+            style.min_size.width,
+            style.max_size.width,
+            available.0,
+            layout.size.width,
+            ["min_width", "max_width", "available_width"],
+        ),
+        (
+            style.size.height,
+            style.min_size.height,
+            style.max_size.height,
+            available.1,
+            layout.size.height,
+            ["min_height", "max_height", "available_height"],
+        ),
+    ];
+    let [width, height] = axes.map(|(size, min, max, avail, resolved, names)| {
+        // A bound only counts as active when the request alone would not
+        // have produced the resolved size.
+        let requested = resolve_dimension(size, avail);
+        if requested.is_none_or(|r| !same_cells(r, resolved)) {
+            if resolve_dimension(min, avail).is_some_and(|m| m > 0.0 && same_cells(m, resolved)) {
+                active.push(names[0]);
+            } else if resolve_dimension(max, avail).is_some_and(|m| same_cells(m, resolved)) {
+                active.push(names[1]);
+            } else if same_cells(avail, resolved) {
+                active.push(names[2]);
+            }
+        }
+        AxisExplainJson {
+            requested: dimension_label(size),
+            min: dimension_label(min),
+            max: dimension_label(max),
+            available: avail,
+            resolved,
+        }
+    });
+
+    let parent_style = parent_taffy.and_then(|tn| ctx.tree.style(tn).ok());
+    if let Some(parent_style) = parent_style.filter(|ps| ps.display == taffy::Display::Flex) {
+        let row = matches!(
+            parent_style.flex_direction,
+            taffy::FlexDirection::Row | taffy::FlexDirection::RowReverse
+        );
+        let (size, avail, resolved, content) = if row {
+            (
+                style.size.width,
+                available.0,
+                layout.size.width,
+                layout.content_size.width,
+            )
+        } else {
+            (
+                style.size.height,
+                available.1,
+                layout.size.height,
+                layout.content_size.height,
+            )
+        };
+        let base = resolve_dimension(style.flex_basis, avail)
+            .or_else(|| resolve_dimension(size, avail))
+            .unwrap_or(content);
+        if style.flex_grow > 0.0 && resolved > base + 0.5 {
+            active.push("flex_grow");
+        }
+        if style.flex_shrink > 0.0 && resolved < base - 0.5 {
+            active.push("flex_shrink");
+        }
+    }
+
+    let explain = LayoutExplainJson {
+        handle,
+        width,
+        height,
+        flex_grow: style.flex_grow,
+        flex_shrink: style.flex_shrink,
+        flex_basis: dimension_label(style.flex_basis),
+        active,
+    };
+    serde_json::to_string(&explain)
+        .map_err(|e| format!("Layout explain serialization failed: {e}").into())
 }
 
 // ============================================================================
@@ -507,6 +733,86 @@ mod tests {
         ctx
     }
 
+    #[test]
+    fn test_layout_explain_reports_winning_constraint() {
+        use crate::layout::{compute_layout, set_dimension, set_flex_factor};
+        use crate::types::NodeType;
+        let mut ctx = TuiContext::new(Box::new(HeadlessBackend::new(80, 24)));
+        let row = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let capped = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let squeezed = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        crate::tree::append_child(&mut ctx, row, capped).unwrap();
+        crate::tree::append_child(&mut ctx, row, squeezed).unwrap();
+        ctx.root = Some(row);
+        set_dimension(&mut ctx, row, 0, 40.0, 1).unwrap();
+        set_dimension(&mut ctx, row, 1, 5.0, 1).unwrap();
+        set_dimension(&mut ctx, capped, 0, 30.0, 1).unwrap();
+        set_dimension(&mut ctx, capped, 4, 10.0, 1).unwrap();
+        set_flex_factor(&mut ctx, capped, 1, 0.0).unwrap();
+        set_dimension(&mut ctx, squeezed, 0, 50.0, 1).unwrap();
+        compute_layout(&mut ctx).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&build_layout_explain_json(&ctx, capped).unwrap()).unwrap();
+        assert_eq!(json["width"]["requested"], "30");
+        assert_eq!(json["width"]["resolved"], 10.0);
+        // Stretched to the row's height.
+        assert_eq!(
+            json["active"],
+            serde_json::json!(["max_width", "available_height"])
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&build_layout_explain_json(&ctx, squeezed).unwrap()).unwrap();
+        assert_eq!(json["width"]["available"], 40.0);
+        assert_eq!(json["width"]["resolved"], 30.0);
+        assert_eq!(
+            json["active"],
+            serde_json::json!(["available_height", "flex_shrink"])
+        );
+
+        assert!(build_layout_explain_json(&ctx, 9999).is_err());
+    }
+
+    #[test]
+    fn test_frame_log_off_by_default_and_bounded() {
+        let mut ctx = TuiContext::new(Box::new(HeadlessBackend::new(80, 24)));
+        let root = crate::tree::create_node(&mut ctx, crate::types::NodeType::Box).unwrap();
+        ctx.root = Some(root);
+
+        crate::render::render(&mut ctx).unwrap();
+        assert!(ctx.frame_log.is_empty());
+
+        set_frame_log_capacity(&mut ctx, 2);
+        for _ in 0..3 {
+            crate::tree::mark_dirty(&mut ctx, root);
+            crate::render::render(&mut ctx).unwrap();
+        }
+        assert_eq!(ctx.frame_log.len(), 2);
+
+        let text = build_frame_log_text(&ctx);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(" dirty=") && lines[0].contains(" render_us="));
+
+        set_frame_log_capacity(&mut ctx, 0);
+        assert!(ctx.frame_log.is_empty());
+    }
+
+    #[test]
+    fn test_frame_log_records_dirty_count_before_clear() {
+        let mut ctx = TuiContext::new(Box::new(HeadlessBackend::new(80, 24)));
+        let root = crate::tree::create_node(&mut ctx, crate::types::NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        set_frame_log_capacity(&mut ctx, 4);
+
+        crate::render::render(&mut ctx).unwrap();
+        assert_eq!(ctx.frame_log[0].dirty_nodes, 1);
+        crate::render::invalidate(&mut ctx);
+        crate::render::render(&mut ctx).unwrap();
+        assert_eq!(ctx.frame_log[1].dirty_nodes, 0);
+    }
+
     #[test]
     fn test_trace_buffer_bounded() {
         let mut ctx = make_ctx();
@@ -6,6 +6,7 @@
 
 use crate::context::TuiContext;
 use crate::text_buffer;
+use crate::types::{ErrorCode, TuiError};
 use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, Clone)]
@@ -94,30 +95,34 @@ fn try_coalesce(previous: &mut EditOp, next: &EditOp) -> bool {
     false
 }
 
-fn current_buffer_epoch(ctx: &TuiContext, buffer_handle: u32) -> Result<u64, String> {
+fn current_buffer_epoch(ctx: &TuiContext, buffer_handle: u32) -> Result<u64, TuiError> {
     ctx.text_buffers
         .get(&buffer_handle)
         .map(|buffer| buffer.epoch())
-        .ok_or_else(|| format!("EditBuffer references missing TextBuffer {buffer_handle}"))
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!(
+                "EditBuffer references missing TextBuffer {buffer_handle}"
+            ))
+        })
 }
 
-fn ensure_buffer_epoch_current(ctx: &TuiContext, handle: u32) -> Result<u32, String> {
-    let edit_buffer = ctx
-        .edit_buffers
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+fn ensure_buffer_epoch_current(ctx: &TuiContext, handle: u32) -> Result<u32, TuiError> {
+    let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     let current_epoch = current_buffer_epoch(ctx, edit_buffer.buffer)?;
     if current_epoch != edit_buffer.buffer_epoch {
         return Err(format!(
             "EditBuffer {handle} history is stale after external TextBuffer mutation; destroy and recreate the EditBuffer before using undo/redo"
-        ));
+        )
+        .into());
     }
     Ok(edit_buffer.buffer)
 }
 
-pub(crate) fn create(ctx: &mut TuiContext, buffer: u32) -> Result<u32, String> {
+pub(crate) fn create(ctx: &mut TuiContext, buffer: u32) -> Result<u32, TuiError> {
     if buffer == 0 || !ctx.text_buffers.contains_key(&buffer) {
-        return Err(format!("Invalid TextBuffer handle: {buffer}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {buffer}")));
     }
     let handle = ctx.alloc_substrate_handle()?;
     let buffer_epoch = current_buffer_epoch(ctx, buffer)?;
@@ -126,9 +131,9 @@ pub(crate) fn create(ctx: &mut TuiContext, buffer: u32) -> Result<u32, String> {
     Ok(handle)
 }
 
-pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     if ctx.edit_buffers.remove(&handle).is_none() {
-        return Err(format!("Invalid EditBuffer handle: {handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}")));
     }
     Ok(())
 }
@@ -138,7 +143,7 @@ pub(crate) fn apply_insert(
     handle: u32,
     start: usize,
     payload: &str,
-) -> Result<bool, String> {
+) -> Result<bool, TuiError> {
     apply_replace(ctx, handle, start, start, payload)
 }
 
@@ -147,7 +152,7 @@ pub(crate) fn apply_delete(
     handle: u32,
     start: usize,
     end: usize,
-) -> Result<bool, String> {
+) -> Result<bool, TuiError> {
     apply_replace(ctx, handle, start, end, "")
 }
 
@@ -157,24 +162,26 @@ pub(crate) fn apply_replace(
     start: usize,
     end: usize,
     payload: &str,
-) -> Result<bool, String> {
+) -> Result<bool, TuiError> {
     let buffer_handle = ensure_buffer_epoch_current(ctx, handle)?;
     let deleted_text = {
         let buffer = ctx.text_buffers.get(&buffer_handle).ok_or_else(|| {
             format!("EditBuffer {handle} references missing buffer {buffer_handle}")
         })?;
         if start > end || end > buffer.byte_len() {
-            return Err(format!(
+            return Err(ErrorCode::IndexOutOfBounds.raise(format!(
                 "Byte range end={end} out of bounds (byte_len={})",
                 buffer.byte_len()
-            ));
+            )));
         }
         let content = buffer.content();
         if !content.is_char_boundary(start) {
-            return Err(format!("Byte offset {start} is not a UTF-8 boundary"));
+            return Err(ErrorCode::InvalidArgument
+                .raise(format!("Byte offset {start} is not a UTF-8 boundary")));
         }
         if !content.is_char_boundary(end) {
-            return Err(format!("Byte offset {end} is not a UTF-8 boundary"));
+            return Err(ErrorCode::InvalidArgument
+                .raise(format!("Byte offset {end} is not a UTF-8 boundary")));
         }
         content[start..end].to_string()
     };
@@ -182,10 +189,9 @@ pub(crate) fn apply_replace(
     text_buffer::replace_range(ctx, buffer_handle, start, end, payload)?;
     let next_buffer_epoch = current_buffer_epoch(ctx, buffer_handle)?;
 
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     edit_buffer.buffer_epoch = next_buffer_epoch;
     if edit_buffer.undo_cursor < edit_buffer.history.len() {
         edit_buffer.history.truncate(edit_buffer.undo_cursor);
@@ -207,17 +213,16 @@ pub(crate) fn apply_replace(
     Ok(false)
 }
 
-pub(crate) fn break_coalescing(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn break_coalescing(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     ensure_buffer_epoch_current(ctx, handle)?;
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     edit_buffer.generation = edit_buffer.generation.saturating_add(1);
     Ok(())
 }
 
-pub(crate) fn undo(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
+pub(crate) fn undo(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
     let (buffer_handle, op) = {
         let buffer_handle = ensure_buffer_epoch_current(ctx, handle)?;
         let edit_buffer = ctx.edit_buffers.get(&handle).unwrap();
@@ -234,17 +239,16 @@ pub(crate) fn undo(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
     text_buffer::replace_range(ctx, buffer_handle, op.start, inserted_end, &op.deleted_text)?;
     let next_buffer_epoch = current_buffer_epoch(ctx, buffer_handle)?;
 
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     edit_buffer.buffer_epoch = next_buffer_epoch;
     edit_buffer.undo_cursor -= 1;
     edit_buffer.generation = edit_buffer.generation.saturating_add(1);
     Ok(true)
 }
 
-pub(crate) fn redo(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
+pub(crate) fn redo(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
     let (buffer_handle, op) = {
         let buffer_handle = ensure_buffer_epoch_current(ctx, handle)?;
         let edit_buffer = ctx.edit_buffers.get(&handle).unwrap();
@@ -261,48 +265,47 @@ pub(crate) fn redo(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
     text_buffer::replace_range(ctx, buffer_handle, op.start, deleted_end, &op.inserted_text)?;
     let next_buffer_epoch = current_buffer_epoch(ctx, buffer_handle)?;
 
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     edit_buffer.buffer_epoch = next_buffer_epoch;
     edit_buffer.undo_cursor += 1;
     edit_buffer.generation = edit_buffer.generation.saturating_add(1);
     Ok(true)
 }
 
-pub(crate) fn can_undo(ctx: &TuiContext, handle: u32) -> Result<bool, String> {
+pub(crate) fn can_undo(ctx: &TuiContext, handle: u32) -> Result<bool, TuiError> {
     ensure_buffer_epoch_current(ctx, handle)?;
-    let edit_buffer = ctx
-        .edit_buffers
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     Ok(edit_buffer.can_undo())
 }
 
-pub(crate) fn can_redo(ctx: &TuiContext, handle: u32) -> Result<bool, String> {
+pub(crate) fn can_redo(ctx: &TuiContext, handle: u32) -> Result<bool, TuiError> {
     ensure_buffer_epoch_current(ctx, handle)?;
-    let edit_buffer = ctx
-        .edit_buffers
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     Ok(edit_buffer.can_redo())
 }
 
-pub(crate) fn history_len(ctx: &TuiContext, handle: u32) -> Result<usize, String> {
+pub(crate) fn history_len(ctx: &TuiContext, handle: u32) -> Result<usize, TuiError> {
     ensure_buffer_epoch_current(ctx, handle)?;
-    let edit_buffer = ctx
-        .edit_buffers
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     Ok(edit_buffer.history_len())
 }
 
-pub(crate) fn trim_history(ctx: &mut TuiContext, handle: u32, limit: usize) -> Result<(), String> {
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+pub(crate) fn trim_history(
+    ctx: &mut TuiContext,
+    handle: u32,
+    limit: usize,
+) -> Result<(), TuiError> {
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     if limit == 0 || edit_buffer.history.len() <= limit {
         return Ok(());
     }
@@ -312,11 +315,10 @@ pub(crate) fn trim_history(ctx: &mut TuiContext, handle: u32, limit: usize) -> R
     Ok(())
 }
 
-pub(crate) fn discard_redo(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+pub(crate) fn discard_redo(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     if edit_buffer.undo_cursor < edit_buffer.history.len() {
         edit_buffer.history.truncate(edit_buffer.undo_cursor);
         edit_buffer.generation = edit_buffer.generation.saturating_add(1);
@@ -324,19 +326,17 @@ pub(crate) fn discard_redo(ctx: &mut TuiContext, handle: u32) -> Result<(), Stri
     Ok(())
 }
 
-pub(crate) fn clear_history(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn clear_history(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let buffer_handle = {
-        let edit_buffer = ctx
-            .edit_buffers
-            .get(&handle)
-            .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+        let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+        })?;
         edit_buffer.buffer
     };
     let buffer_epoch = current_buffer_epoch(ctx, buffer_handle)?;
-    let edit_buffer = ctx
-        .edit_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+    let edit_buffer = ctx.edit_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     edit_buffer.buffer_epoch = buffer_epoch;
     edit_buffer.history.clear();
     edit_buffer.undo_cursor = 0;
@@ -344,11 +344,10 @@ pub(crate) fn clear_history(ctx: &mut TuiContext, handle: u32) -> Result<(), Str
     Ok(())
 }
 
-pub(crate) fn buffer_handle(ctx: &TuiContext, handle: u32) -> Result<u32, String> {
-    let edit_buffer = ctx
-        .edit_buffers
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid EditBuffer handle: {handle}"))?;
+pub(crate) fn buffer_handle(ctx: &TuiContext, handle: u32) -> Result<u32, TuiError> {
+    let edit_buffer = ctx.edit_buffers.get(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid EditBuffer handle: {handle}"))
+    })?;
     Ok(edit_buffer.buffer())
 }
 
@@ -462,9 +461,9 @@ mod tests {
             text_buffer::append(ctx, buf, "!").unwrap();
 
             let err = undo(ctx, edit).unwrap_err();
-            assert!(err.contains("history is stale"), "{err}");
+            assert!(err.message.contains("history is stale"), "{err}");
             let err = can_undo(ctx, edit).unwrap_err();
-            assert!(err.contains("history is stale"), "{err}");
+            assert!(err.message.contains("history is stale"), "{err}");
         });
     }
 }
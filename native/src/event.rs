@@ -11,18 +11,35 @@ use crate::context::TuiContext;
 use crate::edit_buffer;
 use crate::text_buffer;
 use crate::text_utils::{
-    clamp_textarea_cursor_lines, grapheme_count, grapheme_to_byte_idx, split_textarea_lines_owned,
+    clamp_textarea_cursor_lines, grapheme_count, grapheme_to_byte_idx, is_partial_number,
+    matches_input_pattern, next_word_end, pattern_insertion, prev_word_start,
+    split_textarea_lines_owned,
 };
 use crate::text_view;
 use crate::textarea;
-use crate::types::{key, NodeType, TerminalInputEvent, TextAreaEdit, TuiEvent};
+use crate::types::{
+    key, log_level, modifier, ErrorCode, NodeType, TerminalInputEvent, TextAreaEdit, TuiError,
+    TuiEvent, TuiEventType, TuiNode,
+};
 
 /// Read terminal input, classify events, store in buffer.
 /// Returns the number of events captured.
-pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize, String> {
+pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize, TuiError> {
     let raw_events = ctx.backend.read_events(timeout_ms);
     let mut count = 0;
 
+    if ctx.tick_interval_ms > 0 {
+        if !raw_events.is_empty() {
+            // Real input restarts the idle clock, so a steady input stream
+            // faster than the interval never produces ticks.
+            ctx.last_tick_time = Some(ctx.now());
+        } else if let Some(elapsed_ms) = tick_due(ctx) {
+            ctx.last_tick_time = Some(ctx.now());
+            ctx.event_buffer.push(TuiEvent::tick(elapsed_ms));
+            count += 1;
+        }
+    }
+
     for raw in raw_events {
         match raw {
             TerminalInputEvent::Key {
@@ -30,6 +47,12 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                 modifiers,
                 character,
             } => {
+                // An open popup menu captures every key.
+                if crate::menu::handle_key(ctx, code) {
+                    count += 1;
+                    continue;
+                }
+
                 // Tab / BackTab → focus traversal
                 if code == key::TAB {
                     focus_next(ctx);
@@ -51,23 +74,14 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                                 .get_mut(&overlay_handle)
                                 .and_then(|node| node.overlay_state.as_mut())
                                 .and_then(|overlay| overlay.restore_focus.take());
-                            let taffy_node = if let Some(node) = ctx.nodes.get_mut(&overlay_handle)
+                            if let Some(ov) = ctx
+                                .nodes
+                                .get_mut(&overlay_handle)
+                                .and_then(|node| node.overlay_state.as_mut())
                             {
-                                if let Some(ref mut ov) = node.overlay_state {
-                                    ov.open = false;
-                                    node.dirty = true;
-                                }
-                                Some(node.taffy_node)
-                            } else {
-                                None
-                            };
-                            if let Some(tn) = taffy_node {
-                                if let Ok(s) = ctx.tree.style(tn) {
-                                    let mut style = s.clone();
-                                    style.display = taffy::Display::None;
-                                    let _ = ctx.tree.set_style(tn, style);
-                                }
+                                ov.open = false;
                             }
+                            let _ = crate::layout::sync_display(ctx, overlay_handle);
                             crate::tree::clear_focus_if_under(ctx, overlay_handle);
                             if ctx.focused.is_none() {
                                 if let Some(restore_handle) = restore_focus {
@@ -88,19 +102,37 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                     let focused_type = ctx.nodes.get(&focused_handle).map(|n| n.node_type);
                     match focused_type {
                         Some(crate::types::NodeType::Input)
-                            if handle_input_key(ctx, focused_handle, code, character) =>
+                            if handle_input_key(
+                                ctx,
+                                focused_handle,
+                                code,
+                                modifiers,
+                                character,
+                            ) =>
                         {
                             count += 1;
                             continue;
                         }
                         Some(crate::types::NodeType::TextArea)
-                            if handle_textarea_key(ctx, focused_handle, code, character) =>
+                            if handle_textarea_key(
+                                ctx,
+                                focused_handle,
+                                code,
+                                modifiers,
+                                character,
+                            ) =>
                         {
                             count += 1;
                             continue;
                         }
                         Some(crate::types::NodeType::Select)
-                            if handle_select_key(ctx, focused_handle, code) =>
+                            if handle_select_key(ctx, focused_handle, code, character) =>
+                        {
+                            count += 1;
+                            continue;
+                        }
+                        Some(crate::types::NodeType::ComboBox)
+                            if handle_combobox_key(ctx, focused_handle, code, character) =>
                         {
                             count += 1;
                             continue;
@@ -140,6 +172,16 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                     }
                 }
 
+                // With escape_blurs on, an Escape no widget consumed
+                // releases focus instead of reaching the host as a key.
+                if code == key::ESCAPE && ctx.escape_blurs {
+                    if let Some(focused_handle) = ctx.focused {
+                        blur_to_focusable_ancestor(ctx, focused_handle);
+                        count += 1;
+                        continue;
+                    }
+                }
+
                 let codepoint = if character != '\0' {
                     character as u32
                 } else {
@@ -170,6 +212,13 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                 button,
                 modifiers,
             } => {
+                // An open popup menu takes clicks: inside selects, outside
+                // dismisses without reaching the tree.
+                if crate::menu::handle_mouse(ctx, x, y, button) {
+                    count += 1;
+                    continue;
+                }
+
                 let target = crate::layout::hit_test(ctx, x, y).unwrap_or(0);
 
                 // Click events (buttons 0-2) can change focus
@@ -248,6 +297,9 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
                 // diagnostics can change, so refresh the cached capability
                 // state before devtools or host copy-out APIs read it.
                 ctx.terminal_capabilities = ctx.backend.capabilities();
+                // Reflow everything on the next render, even if the host
+                // only re-renders when something is dirty.
+                crate::tree::mark_all_paint_dirty(ctx);
                 ctx.event_buffer
                     .push(TuiEvent::resize(width as u32, height as u32));
                 count += 1;
@@ -261,6 +313,27 @@ pub(crate) fn read_input(ctx: &mut TuiContext, timeout_ms: u32) -> Result<usize,
     Ok(count)
 }
 
+/// Set the idle heartbeat interval. 0 disables Tick events.
+pub(crate) fn set_tick_interval(ctx: &mut TuiContext, interval_ms: u32) {
+    ctx.tick_interval_ms = interval_ms;
+    ctx.last_tick_time = if interval_ms > 0 {
+        Some(ctx.now())
+    } else {
+        None
+    };
+}
+
+/// Milliseconds since the last tick/input if the interval has elapsed.
+fn tick_due(ctx: &TuiContext) -> Option<u32> {
+    let last = ctx.last_tick_time?;
+    let elapsed_ms = ctx
+        .now()
+        .duration_since(last)
+        .as_millis()
+        .min(u32::MAX as u128) as u32;
+    (elapsed_ms >= ctx.tick_interval_ms).then_some(elapsed_ms)
+}
+
 /// Drain one event from the buffer. Returns None if empty.
 pub(crate) fn next_event(ctx: &mut TuiContext) -> Option<TuiEvent> {
     if ctx.event_buffer.is_empty() {
@@ -270,30 +343,119 @@ pub(crate) fn next_event(ctx: &mut TuiContext) -> Option<TuiEvent> {
     }
 }
 
+/// Host tag of the node an event is about: its target, or the newly focused
+/// node for FocusChange, which has no target. 0 when untagged or gone.
+pub(crate) fn event_tag(ctx: &TuiContext, event: &TuiEvent) -> u64 {
+    let node = if event.event_type == TuiEventType::FocusChange as u32 {
+        event.data[1]
+    } else {
+        event.target
+    };
+    ctx.nodes.get(&node).map_or(0, |n| n.tag)
+}
+
 /// Handle a key press on a focused Input widget. Returns true if consumed.
-fn handle_input_key(ctx: &mut TuiContext, handle: u32, code: u32, character: char) -> bool {
+/// The node's `submit_key` binding emits Submit. Shift with
+/// Left/Right/Home/End extends the selection from its anchor; unshifted
+/// movement collapses it, and edits replace the selected range. Ctrl with
+/// Left/Right/Backspace works word-wise (see `word_break_chars`).
+fn handle_input_key(
+    ctx: &mut TuiContext,
+    handle: u32,
+    code: u32,
+    modifiers: u32,
+    character: char,
+) -> bool {
     let node = match ctx.nodes.get_mut(&handle) {
         Some(n) => n,
         None => return false,
     };
-
     let content_len = grapheme_count(&node.content) as u32;
     if node.cursor_position > content_len {
         node.cursor_position = content_len;
     }
+    if node.input_selection_anchor.is_some_and(|a| a > content_len) {
+        node.input_selection_anchor = Some(content_len);
+    }
+
+    if (code, modifiers) == node.submit_key {
+        ctx.event_buffer.push(TuiEvent::submit(handle));
+        return true;
+    }
+
+    let by_word = modifiers & modifier::CTRL != 0;
+    if matches!(code, key::LEFT | key::RIGHT | key::HOME | key::END) {
+        let cursor = node.cursor_position;
+        let breaks = ctx.word_break_chars.as_str();
+        let next = match code {
+            key::LEFT if by_word => prev_word_start(&node.content, cursor as usize, breaks) as u32,
+            key::RIGHT if by_word => next_word_end(&node.content, cursor as usize, breaks) as u32,
+            key::LEFT => cursor.saturating_sub(1),
+            key::RIGHT => (cursor + 1).min(content_len),
+            key::HOME => 0,
+            _ => content_len,
+        };
+        let anchor_before = node.input_selection_anchor;
+        if modifiers & modifier::SHIFT != 0 {
+            node.input_selection_anchor = Some(anchor_before.unwrap_or(cursor));
+        } else {
+            node.input_selection_anchor = None;
+        }
+        if next != cursor || anchor_before != node.input_selection_anchor {
+            node.cursor_position = next;
+            node.dirty = true;
+        }
+        return true;
+    }
+
+    let is_insert = character != '\0' && !character.is_control();
+    if matches!(code, key::BACKSPACE | key::DELETE) || is_insert {
+        if let Some((start, end)) = node.input_selection() {
+            let replacement = if is_insert {
+                input_insertion(node, start as usize, character)
+            } else {
+                String::new()
+            };
+            let start_byte = grapheme_to_byte_idx(&node.content, start as usize);
+            let end_byte = grapheme_to_byte_idx(&node.content, end as usize);
+            let mut next = node.content.to_string();
+            next.replace_range(start_byte..end_byte, &replacement);
+            if !fits_input_constraints(node, &next) {
+                return true;
+            }
+            node.content = next.into();
+            node.cursor_position = start + grapheme_count(&replacement) as u32;
+            node.input_selection_anchor = None;
+            node.dirty = true;
+            ctx.event_buffer.push(TuiEvent::change(handle, 0));
+            return true;
+        }
+        node.input_selection_anchor = None;
+    }
 
     match code {
-        key::ENTER => {
-            ctx.event_buffer.push(TuiEvent::submit(handle));
+        // Enter that is not the submit binding does nothing in a single line.
+        key::ENTER => return true,
+        key::UP | key::DOWN if node.numeric.is_some() => {
+            let direction = if code == key::UP { 1.0 } else { -1.0 };
+            if step_numeric_input(node, direction) {
+                ctx.event_buffer.push(TuiEvent::change(handle, 0));
+            }
             return true;
         }
         key::BACKSPACE => {
             let cursor = node.cursor_position as usize;
             if cursor > 0 {
-                let start = grapheme_to_byte_idx(&node.content, cursor - 1);
+                // Ctrl+Backspace deletes back to the start of the word.
+                let from = if by_word {
+                    prev_word_start(&node.content, cursor, &ctx.word_break_chars)
+                } else {
+                    cursor - 1
+                };
+                let start = grapheme_to_byte_idx(&node.content, from);
                 let end = grapheme_to_byte_idx(&node.content, cursor);
-                node.content.replace_range(start..end, "");
-                node.cursor_position -= 1;
+                node.content.to_mut().replace_range(start..end, "");
+                node.cursor_position = from as u32;
                 node.dirty = true;
                 ctx.event_buffer.push(TuiEvent::change(handle, 0));
             }
@@ -305,49 +467,31 @@ fn handle_input_key(ctx: &mut TuiContext, handle: u32, code: u32, character: cha
             if cursor < len {
                 let start = grapheme_to_byte_idx(&node.content, cursor);
                 let end = grapheme_to_byte_idx(&node.content, cursor + 1);
-                node.content.replace_range(start..end, "");
+                node.content.to_mut().replace_range(start..end, "");
                 node.dirty = true;
                 ctx.event_buffer.push(TuiEvent::change(handle, 0));
             }
             return true;
         }
-        key::LEFT => {
-            if node.cursor_position > 0 {
-                node.cursor_position -= 1;
-                node.dirty = true;
-            }
-            return true;
-        }
-        key::RIGHT => {
-            let len = grapheme_count(&node.content) as u32;
-            if node.cursor_position < len {
-                node.cursor_position += 1;
-                node.dirty = true;
-            }
-            return true;
-        }
-        key::HOME => {
-            node.cursor_position = 0;
-            node.dirty = true;
-            return true;
-        }
-        key::END => {
-            node.cursor_position = grapheme_count(&node.content) as u32;
-            node.dirty = true;
-            return true;
-        }
         _ => {}
     }
 
     // Printable character insertion
-    if character != '\0' && !character.is_control() {
+    if is_insert {
+        let cursor = node.cursor_position as usize;
+        let inserted = input_insertion(node, cursor, character);
+        let inserted_len = grapheme_count(&inserted) as u32;
         let max_len = node.max_length;
         let current_len = grapheme_count(&node.content) as u32;
-        if max_len == 0 || current_len < max_len {
-            let cursor = node.cursor_position as usize;
-            let byte_idx = grapheme_to_byte_idx(&node.content, cursor);
-            node.content.insert(byte_idx, character);
-            node.cursor_position += 1;
+        if max_len == 0 || current_len + inserted_len <= max_len {
+            let mut next = node.content.to_string();
+            next.insert_str(grapheme_to_byte_idx(&node.content, cursor), &inserted);
+            // A character that breaks the pattern or numeric mode is swallowed.
+            if !fits_input_constraints(node, &next) {
+                return true;
+            }
+            node.set_content(next);
+            node.cursor_position += inserted_len;
             node.dirty = true;
             ctx.event_buffer.push(TuiEvent::change(handle, 0));
             return true;
@@ -357,12 +501,52 @@ fn handle_input_key(ctx: &mut TuiContext, handle: u32, code: u32, character: cha
     false
 }
 
-fn ensure_textarea_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+/// What typing `character` at grapheme `at` inserts into an Input: with a
+/// pattern, any literal separators owed before the next slot come first.
+fn input_insertion(node: &TuiNode, at: usize, character: char) -> String {
+    match node.input_pattern.as_deref() {
+        Some(pattern) => pattern_insertion(pattern, at, character),
+        None => character.to_string(),
+    }
+}
+
+/// Whether `text` still satisfies the Input's pattern and numeric mode, if
+/// any, as a value being typed.
+fn fits_input_constraints(node: &TuiNode, text: &str) -> bool {
+    node.input_pattern
+        .as_deref()
+        .is_none_or(|pattern| matches_input_pattern(text, pattern, false))
+        && (node.numeric.is_none() || is_partial_number(text))
+}
+
+/// Step a numeric Input's value by `direction` steps, clamped to its range,
+/// and rewrite the content. Unparsable content steps from the range's
+/// value closest to zero. Returns whether the content changed.
+fn step_numeric_input(node: &mut TuiNode, direction: f64) -> bool {
+    let Some(numeric) = node.numeric else {
+        return false;
+    };
+    let current = node
+        .content
+        .parse::<f64>()
+        .unwrap_or_else(|_| 0f64.clamp(numeric.min, numeric.max));
+    let next = (current + direction * numeric.step).clamp(numeric.min, numeric.max);
+    let text = numeric.format(next);
+    if node.content == text {
+        return false;
+    }
+    node.cursor_position = grapheme_count(&text) as u32;
+    node.set_content(text);
+    node.dirty = true;
+    true
+}
+
+fn ensure_textarea_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let (existing_buffer, existing_view, existing_edit, content) = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         (
             node.text_buffer_handle,
             node.text_view_handle,
@@ -397,7 +581,7 @@ fn ensure_textarea_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), St
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     node.text_buffer_handle = Some(buffer_handle);
     node.text_view_handle = Some(view_handle);
     node.edit_buffer_handle = Some(edit_handle);
@@ -408,26 +592,35 @@ fn sync_textarea_content_from_buffer(
     ctx: &mut TuiContext,
     handle: u32,
     buffer_handle: u32,
-) -> Result<String, String> {
+) -> Result<String, TuiError> {
     let content = ctx
         .text_buffers
         .get(&buffer_handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+        })?
         .content()
         .to_string();
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    node.content = content.clone();
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    node.set_content(content.clone());
     Ok(content)
 }
 
 /// Handle a key press on a focused TextArea widget. Returns true if consumed.
-fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character: char) -> bool {
+fn handle_textarea_key(
+    ctx: &mut TuiContext,
+    handle: u32,
+    code: u32,
+    modifiers: u32,
+    character: char,
+) -> bool {
     if ensure_textarea_substrate(ctx, handle).is_err() {
         return false;
     }
+    let was_at_bottom = textarea::view_at_bottom(ctx, handle);
 
     let mut emit_change = false;
     let mut consumed = false;
@@ -561,15 +754,21 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
                 if cursor_col > 0 {
                     let row = cursor_row as usize;
                     let col = cursor_col as usize;
+                    // Ctrl+Backspace deletes back to the start of the word.
+                    let from = if modifiers & modifier::CTRL != 0 {
+                        prev_word_start(&lines[row], col, &ctx.word_break_chars)
+                    } else {
+                        col - 1
+                    };
                     let start = textarea::position_to_byte_offset(&content, cursor_row, cursor_col)
                         - (grapheme_to_byte_idx(&lines[row], col)
-                            - grapheme_to_byte_idx(&lines[row], col - 1));
+                            - grapheme_to_byte_idx(&lines[row], from));
                     let end = textarea::position_to_byte_offset(&content, cursor_row, cursor_col);
                     coalesced = match edit_buffer::apply_delete(ctx, edit_handle, start, end) {
                         Ok(coalesced) => coalesced,
                         Err(_) => return false,
                     };
-                    cursor_col -= 1;
+                    cursor_col = from as u32;
                     emit_change = true;
                 } else if cursor_row > 0 {
                     let prev_row = cursor_row - 1;
@@ -632,7 +831,13 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
             }
             key::LEFT => {
                 let _ = edit_buffer::break_coalescing(ctx, edit_handle);
-                if cursor_col > 0 {
+                if cursor_col > 0 && modifiers & modifier::CTRL != 0 {
+                    cursor_col = prev_word_start(
+                        &lines[cursor_row as usize],
+                        cursor_col as usize,
+                        &ctx.word_break_chars,
+                    ) as u32;
+                } else if cursor_col > 0 {
                     cursor_col -= 1;
                 } else if cursor_row > 0 {
                     cursor_row -= 1;
@@ -644,7 +849,11 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
                 let _ = edit_buffer::break_coalescing(ctx, edit_handle);
                 let row = cursor_row as usize;
                 let line_len = grapheme_count(&lines[row]) as u32;
-                if cursor_col < line_len {
+                if cursor_col < line_len && modifiers & modifier::CTRL != 0 {
+                    cursor_col =
+                        next_word_end(&lines[row], cursor_col as usize, &ctx.word_break_chars)
+                            as u32;
+                } else if cursor_col < line_len {
                     cursor_col += 1;
                 } else if (cursor_row as usize) + 1 < lines.len() {
                     cursor_row += 1;
@@ -670,11 +879,19 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
             }
             key::HOME => {
                 let _ = edit_buffer::break_coalescing(ctx, edit_handle);
+                // Ctrl+Home jumps to the start of the document.
+                if modifiers & modifier::CTRL != 0 {
+                    cursor_row = 0;
+                }
                 cursor_col = 0;
                 consumed = true;
             }
             key::END => {
                 let _ = edit_buffer::break_coalescing(ctx, edit_handle);
+                // Ctrl+End jumps to the end of the document.
+                if modifiers & modifier::CTRL != 0 {
+                    cursor_row = lines.len().saturating_sub(1) as u32;
+                }
                 let row = cursor_row as usize;
                 cursor_col = grapheme_count(&lines[row]) as u32;
                 consumed = true;
@@ -716,7 +933,7 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
             Some(node) => node,
             None => return false,
         };
-        node.content = content;
+        node.set_content(content);
         node.cursor_row = cursor_row;
         node.cursor_col = cursor_col;
         node.textarea_view_row = textarea_view_row.min(cursor_row);
@@ -730,7 +947,22 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
             Some(state) => state,
             None => return false,
         };
-        state.clear_selection();
+        // Shift+movement extends the selection from its anchor (or from
+        // where the cursor started); anything else collapses it.
+        let extends_selection = modifiers & modifier::SHIFT != 0
+            && matches!(
+                code,
+                key::LEFT | key::RIGHT | key::UP | key::DOWN | key::HOME | key::END
+            );
+        if extends_selection {
+            let anchor = selection_anchor_before
+                .filter(|_| has_selection)
+                .unwrap_or((cursor_row_before, cursor_col_before));
+            state.selection_anchor = Some(anchor);
+            state.selection_focus = Some((cursor_row, cursor_col));
+        } else {
+            state.clear_selection();
+        }
         edit_buffer_handle_for_trim = node.edit_buffer_handle;
 
         if emit_change {
@@ -761,16 +993,27 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
                 edit_buffer::trim_history(ctx, edit_buffer_handle, history_limit as usize)
             {
                 // History trimming is best-effort during typing so a trim
-                // failure does not drop the input event, but debug mode still
-                // surfaces the mismatch to keep native/host state drift visible.
-                ctx.debug_log(&format!(
-                    "handle_textarea_key: trim_history failed for edit buffer {edit_buffer_handle}: {err}"
-                ));
+                // failure does not drop the input event, but it is reported
+                // as a warning to keep native/host state drift visible.
+                ctx.log(
+                    log_level::WARN,
+                    &format!(
+                        "handle_textarea_key: trim_history failed for edit buffer {edit_buffer_handle}: {err}"
+                    ),
+                );
             }
         }
     }
 
     if emit_change {
+        if let Err(err) = textarea::enforce_max_lines(ctx, handle, was_at_bottom) {
+            ctx.log(
+                log_level::WARN,
+                &format!(
+                    "handle_textarea_key: enforce_max_lines failed for handle {handle}: {err}"
+                ),
+            );
+        }
         ctx.event_buffer.push(TuiEvent::change(handle, 0));
     }
 
@@ -778,33 +1021,74 @@ fn handle_textarea_key(ctx: &mut TuiContext, handle: u32, code: u32, character:
 }
 
 /// Handle a key press on a focused Select widget. Returns true if consumed.
-fn handle_select_key(ctx: &mut TuiContext, handle: u32, code: u32) -> bool {
+fn handle_select_key(ctx: &mut TuiContext, handle: u32, code: u32, character: char) -> bool {
+    let typeahead_ms = ctx.typeahead_ms;
+    let now = ctx.now();
     let node = match ctx.nodes.get_mut(&handle) {
         Some(n) => n,
         None => return false,
     };
 
-    let option_count = node.options.len() as u32;
-    if option_count == 0 {
+    if node.options.is_empty() {
         return false;
     }
+    // Navigation walks only the options the filter leaves visible; the
+    // selection itself stays an index into the full list.
+    let visible = node.visible_option_indices();
+
+    // Typeahead: printable characters extend a prefix that jumps to the
+    // first visible option starting with it. The prefix restarts after
+    // `typeahead_ms` of idle time; Backspace trims it.
+    if node
+        .typeahead_at
+        .is_some_and(|at| now.duration_since(at).as_millis() > typeahead_ms as u128)
+    {
+        node.typeahead.clear();
+    }
+    let typed = if character != '\0' && !character.is_control() {
+        node.typeahead.push(character);
+        true
+    } else if code == key::BACKSPACE && !node.typeahead.is_empty() {
+        node.typeahead.pop();
+        true
+    } else {
+        false
+    };
+    if typed {
+        node.typeahead_at = Some(now);
+        let prefix = node.typeahead.to_lowercase();
+        let target = visible
+            .iter()
+            .copied()
+            .find(|&i| node.options[i as usize].to_lowercase().starts_with(&prefix));
+        if let Some(target) = target.filter(|_| !prefix.is_empty()) {
+            if node.selected_index != Some(target) {
+                node.selected_index = Some(target);
+                node.dirty = true;
+                ctx.event_buffer.push(TuiEvent::change(handle, target));
+            }
+        }
+        return true;
+    }
 
     match code {
         key::UP => {
+            node.typeahead.clear();
             let current = node.selected_index.unwrap_or(0);
-            if current > 0 {
-                node.selected_index = Some(current - 1);
+            if let Some(&prev) = visible.iter().rev().find(|&&i| i < current) {
+                node.selected_index = Some(prev);
                 node.dirty = true;
-                ctx.event_buffer.push(TuiEvent::change(handle, current - 1));
+                ctx.event_buffer.push(TuiEvent::change(handle, prev));
             }
             return true;
         }
         key::DOWN => {
+            node.typeahead.clear();
             let current = node.selected_index.unwrap_or(0);
-            if current + 1 < option_count {
-                node.selected_index = Some(current + 1);
+            if let Some(&next) = visible.iter().find(|&&i| i > current) {
+                node.selected_index = Some(next);
                 node.dirty = true;
-                ctx.event_buffer.push(TuiEvent::change(handle, current + 1));
+                ctx.event_buffer.push(TuiEvent::change(handle, next));
             }
             return true;
         }
@@ -818,6 +1102,59 @@ fn handle_select_key(ctx: &mut TuiContext, handle: u32, code: u32) -> bool {
     false
 }
 
+/// Handle a key press on a focused ComboBox. Up/Down move the highlight
+/// through the options matching the typed text, Enter commits the
+/// highlighted option (or the typed text when none is highlighted), and
+/// everything else edits the text line like an Input. Returns true if
+/// consumed.
+fn handle_combobox_key(ctx: &mut TuiContext, handle: u32, code: u32, character: char) -> bool {
+    let node = match ctx.nodes.get_mut(&handle) {
+        Some(n) => n,
+        None => return false,
+    };
+
+    match code {
+        key::UP | key::DOWN => {
+            let visible = node.visible_option_indices();
+            let next = match (code, node.selected_index) {
+                (key::DOWN, None) => visible.first().copied(),
+                (key::DOWN, Some(current)) => visible.iter().copied().find(|&i| i > current),
+                (_, None) => None,
+                (_, Some(current)) => visible.iter().rev().copied().find(|&i| i < current),
+            };
+            if next.is_some() || code == key::UP {
+                // Up past the first option returns to the typed text.
+                node.selected_index = next;
+                node.dirty = true;
+            }
+            return true;
+        }
+        key::ENTER => {
+            if let Some(sel) = node.selected_index {
+                if let Some(label) = node.options.get(sel as usize).cloned() {
+                    node.cursor_position = crate::text_utils::grapheme_count(&label) as u32;
+                    node.set_content(label);
+                    node.dirty = true;
+                }
+            }
+            ctx.event_buffer.push(TuiEvent::submit(handle));
+            return true;
+        }
+        _ => {}
+    }
+
+    let before = node.content.clone();
+    let consumed = handle_input_key(ctx, handle, code, 0, character);
+    if let Some(node) = ctx.nodes.get_mut(&handle) {
+        if node.content != before {
+            // The option list re-filters; drop a highlight it may hide.
+            node.selected_index = None;
+            node.marquee = None;
+        }
+    }
+    consumed
+}
+
 /// Handle a key press on a focused Table widget. Returns true if consumed.
 fn handle_table_key(ctx: &mut TuiContext, handle: u32, code: u32) -> bool {
     let node = match ctx.nodes.get_mut(&handle) {
@@ -1018,6 +1355,37 @@ pub(crate) fn refocus_after_loss(ctx: &mut TuiContext, old_focus: u32) {
     }
 }
 
+/// Move focus off `handle`'s subtree after it stopped taking part in layout,
+/// falling back to the first remaining focusable node.
+pub(crate) fn release_focus_under(ctx: &mut TuiContext, handle: u32) {
+    let Some(old_focus) = ctx.focused else {
+        return;
+    };
+    if !crate::tree::is_self_or_descendant(ctx, old_focus, handle) {
+        return;
+    }
+    ctx.focused = None;
+    refocus_after_loss(ctx, old_focus);
+}
+
+/// Move focus from `old_focus` to its nearest focusable, visible ancestor,
+/// or clear it when there is none. Emits the FocusChange either way.
+fn blur_to_focusable_ancestor(ctx: &mut TuiContext, old_focus: u32) {
+    let mut current = ctx.nodes.get(&old_focus).and_then(|n| n.parent);
+    while let Some(handle) = current {
+        if restore_focus_handle(ctx, handle) {
+            return;
+        }
+        current = ctx.nodes.get(&handle).and_then(|n| n.parent);
+    }
+    ctx.focused = None;
+    ctx.event_buffer.push(TuiEvent::focus_change(old_focus, 0));
+    if ctx.debug_mode && (ctx.debug_trace_flags & 0x2) != 0 {
+        let detail = format!("Focus({old_focus}->0)");
+        crate::devtools::push_trace(ctx, crate::types::trace_kind::FOCUS, 0, detail);
+    }
+}
+
 /// Advance focus to the next focusable node (depth-first tree order).
 pub(crate) fn focus_next(ctx: &mut TuiContext) {
     let focusable_order = collect_focusable_order(ctx);
@@ -1081,48 +1449,47 @@ pub(crate) fn focus_prev(ctx: &mut TuiContext) {
 /// If any modal open overlay is active, only nodes within that overlay's
 /// subtree are returned (focus trapping).
 fn collect_focusable_order(ctx: &TuiContext) -> Vec<u32> {
-    if let Some(modal_root) = find_active_modal_root(ctx) {
-        let mut result = Vec::new();
-        collect_focusable_recursive(ctx, modal_root, &mut result);
-        return result;
-    }
-
-    let mut result = Vec::new();
-    if let Some(root) = ctx.root {
-        collect_focusable_recursive(ctx, root, &mut result);
-    }
-    result
+    let Some(root) = find_active_modal_root(ctx).or(ctx.root) else {
+        return Vec::new();
+    };
+    // After at least one render, skip nodes whose computed layout has
+    // collapsed to zero area so focus cannot land in invisible panes.
+    let has_area = |handle| {
+        ctx.frame_seq == 0
+            || crate::layout::get_layout(ctx, handle)
+                .map_or(true, |(_, _, width, height)| width > 0 && height > 0)
+    };
+    reachable_pre_order(ctx, root, has_area)
+        .into_iter()
+        .filter(|handle| ctx.nodes[handle].focusable)
+        .collect()
 }
 
-fn collect_focusable_recursive(ctx: &TuiContext, handle: u32, result: &mut Vec<u32>) {
-    if let Some(node) = ctx.nodes.get(&handle) {
-        if !node.visible {
-            return;
+/// Nodes under `root` in depth-first pre-order, skipping hidden subtrees,
+/// closed overlays and subtrees whose root fails `keep`. Iterative, so any
+/// tree depth is fine.
+fn reachable_pre_order(ctx: &TuiContext, root: u32, keep: impl Fn(u32) -> bool) -> Vec<u32> {
+    let mut order = Vec::new();
+    let mut stack = vec![root];
+    while let Some(handle) = stack.pop() {
+        let Some(node) = ctx.nodes.get(&handle) else {
+            continue;
+        };
+        if !node.visible || node.display_none || !keep(handle) {
+            continue;
         }
         // Skip closed overlays — their children are not reachable.
         if node.node_type == NodeType::Overlay {
             if let Some(ref ov) = node.overlay_state {
                 if !ov.open {
-                    return;
-                }
-            }
-        }
-        // After at least one render, skip nodes whose computed layout has
-        // collapsed to zero area so focus cannot land in invisible panes.
-        if ctx.frame_seq > 0 {
-            if let Ok((_, _, width, height)) = crate::layout::get_layout(ctx, handle) {
-                if width <= 0 || height <= 0 {
-                    return;
+                    continue;
                 }
             }
         }
-        if node.focusable {
-            result.push(handle);
-        }
-        for &child in &node.children {
-            collect_focusable_recursive(ctx, child, result);
-        }
+        order.push(handle);
+        stack.extend(node.children.iter().rev());
     }
+    order
 }
 
 /// Walk ancestors to find the nearest open Overlay with `dismiss_on_escape` enabled.
@@ -1157,44 +1524,21 @@ fn find_modal_overlay_ancestor(ctx: &TuiContext, handle: u32) -> Option<u32> {
     }
 }
 
-/// Find the top-most active modal overlay in tree/render order.
+/// Find the top-most active modal overlay in tree/render order: the last
+/// one in pre-order, so a later sibling or a nested modal wins.
 fn find_active_modal_root(ctx: &TuiContext) -> Option<u32> {
     let root = ctx.root?;
-    find_active_modal_root_recursive(ctx, root)
-}
-
-fn find_active_modal_root_recursive(ctx: &TuiContext, handle: u32) -> Option<u32> {
-    let node = ctx.nodes.get(&handle)?;
-    if !node.visible {
-        return None;
-    }
-    if node.node_type == NodeType::Overlay {
-        if let Some(ref ov) = node.overlay_state {
-            if !ov.open {
-                return None;
-            }
-        }
-    }
-
-    let mut active = None;
-    for &child in &node.children {
-        if let Some(child_modal) = find_active_modal_root_recursive(ctx, child) {
-            active = Some(child_modal);
-        }
-    }
-    if active.is_some() {
-        return active;
-    }
-
-    if node.node_type == NodeType::Overlay {
-        if let Some(ref ov) = node.overlay_state {
-            if ov.modal && ov.open {
-                return Some(handle);
-            }
-        }
-    }
-
-    None
+    reachable_pre_order(ctx, root, |_| true)
+        .into_iter()
+        .filter(|handle| {
+            let node = &ctx.nodes[handle];
+            node.node_type == NodeType::Overlay
+                && node
+                    .overlay_state
+                    .as_ref()
+                    .is_some_and(|ov| ov.modal && ov.open)
+        })
+        .last()
 }
 
 /// Check if `handle` is a descendant of `ancestor`.
@@ -1257,7 +1601,7 @@ fn find_splitpane_divider_hit(ctx: &TuiContext, x: u16, y: u16) -> Option<(u32,
     // We check from root down so we find the outermost matching SplitPane first.
     fn check_node(ctx: &TuiContext, handle: u32, x: f32, y: f32) -> Option<(u32, u16, u16)> {
         let node = ctx.nodes.get(&handle)?;
-        if !node.visible {
+        if !node.visible || node.display_none {
             return None;
         }
 
@@ -1373,13 +1717,14 @@ mod tests {
     use crate::terminal::MockBackend;
     use crate::tree;
     use crate::types::{NodeType, TuiEventType};
+    use std::time::Instant;
     use taffy::style_helpers::{length, percent};
 
     fn test_ctx() -> TuiContext {
         TuiContext::new(Box::new(MockBackend::new(80, 24)))
     }
 
-    fn textarea_undo_for_test(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
+    fn textarea_undo_for_test(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
         let (edit_handle, edit) = {
             let node = ctx
                 .nodes
@@ -1405,7 +1750,7 @@ mod tests {
             .content()
             .to_string();
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.content = content;
+        node.set_content(content);
         node.cursor_row = edit.cursor_row_before;
         node.cursor_col = edit.cursor_col_before;
         if let Some(state) = node.textarea_state.as_mut() {
@@ -1418,7 +1763,7 @@ mod tests {
         Ok(true)
     }
 
-    fn textarea_redo_for_test(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
+    fn textarea_redo_for_test(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
         let (edit_handle, edit) = {
             let node = ctx
                 .nodes
@@ -1444,7 +1789,7 @@ mod tests {
             .content()
             .to_string();
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.content = content;
+        node.set_content(content);
         node.cursor_row = edit.cursor_row_after;
         node.cursor_col = edit.cursor_col_after;
         if let Some(state) = node.textarea_state.as_mut() {
@@ -1532,8 +1877,10 @@ mod tests {
         let (_, _, width, _) = crate::layout::get_layout(&ctx, left_input).unwrap();
         assert_eq!(width, 0);
 
+        // The inputs size the split pane to one row, so the expanded right
+        // side stays reachable while the collapsed left side is skipped.
         let order = collect_focusable_order(&ctx);
-        assert_eq!(order, vec![outside_input]);
+        assert_eq!(order, vec![splitpane, right_input, outside_input]);
     }
 
     #[test]
@@ -1547,29 +1894,199 @@ mod tests {
         ctx.focused = Some(input);
 
         // Type "hi"
-        handle_input_key(&mut ctx, input, 'h' as u32, 'h');
-        handle_input_key(&mut ctx, input, 'i' as u32, 'i');
+        handle_input_key(&mut ctx, input, 'h' as u32, 0, 'h');
+        handle_input_key(&mut ctx, input, 'i' as u32, 0, 'i');
 
         assert_eq!(ctx.nodes[&input].content, "hi");
         assert_eq!(ctx.nodes[&input].cursor_position, 2);
 
         // Backspace
-        handle_input_key(&mut ctx, input, key::BACKSPACE, '\0');
+        handle_input_key(&mut ctx, input, key::BACKSPACE, 0, '\0');
         assert_eq!(ctx.nodes[&input].content, "h");
         assert_eq!(ctx.nodes[&input].cursor_position, 1);
     }
 
+    #[test]
+    fn test_input_shift_arrows_select_and_typing_replaces() {
+        let mut ctx = test_ctx();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&input).unwrap();
+            node.content = "hello world".into();
+            node.cursor_position = 5;
+        }
+
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::HOME,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert_eq!(ctx.nodes[&input].input_selection(), Some((0, 5)));
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::RIGHT,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert_eq!(ctx.nodes[&input].input_selection(), Some((1, 5)));
+        assert!(
+            ctx.event_buffer.is_empty(),
+            "selecting does not change content"
+        );
+
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            'J' as u32,
+            modifier::SHIFT,
+            'J'
+        ));
+        let node = &ctx.nodes[&input];
+        assert_eq!(node.content, "hJ world");
+        assert_eq!(node.cursor_position, 2);
+        assert_eq!(node.input_selection(), None);
+        assert_eq!(ctx.event_buffer.len(), 1);
+
+        // An unshifted movement collapses the selection without editing.
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::END,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert!(handle_input_key(&mut ctx, input, key::LEFT, 0, '\0'));
+        assert_eq!(ctx.nodes[&input].input_selection(), None);
+        assert_eq!(ctx.nodes[&input].cursor_position, 7);
+
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::HOME,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert!(handle_input_key(&mut ctx, input, key::BACKSPACE, 0, '\0'));
+        assert_eq!(ctx.nodes[&input].content, "d");
+        assert_eq!(ctx.nodes[&input].cursor_position, 0);
+    }
+
+    #[test]
+    fn test_textarea_shift_arrows_extend_selection() {
+        let mut ctx = test_ctx();
+        let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&textarea).unwrap();
+            node.content = "abc\ndef".into();
+            node.cursor_row = 0;
+            node.cursor_col = 1;
+        }
+
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::RIGHT,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::DOWN,
+            modifier::SHIFT,
+            '\0'
+        ));
+        let state = ctx.nodes[&textarea].textarea_state.as_ref().unwrap();
+        assert_eq!(state.selection_anchor, Some((0, 1)));
+        assert_eq!(state.selection_focus, Some((1, 2)));
+        assert!(ctx.event_buffer.is_empty());
+
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            'X' as u32,
+            modifier::SHIFT,
+            'X'
+        ));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!(node.content, "aXf");
+        assert_eq!((node.cursor_row, node.cursor_col), (0, 2));
+        assert_eq!(node.textarea_state.as_ref().unwrap().selection_anchor, None);
+
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::LEFT,
+            modifier::SHIFT,
+            '\0'
+        ));
+        assert!(handle_textarea_key(&mut ctx, textarea, key::LEFT, 0, '\0'));
+        let state = ctx.nodes[&textarea].textarea_state.as_ref().unwrap();
+        assert_eq!(state.selection_anchor, None);
+    }
+
+    #[test]
+    fn test_input_custom_submit_key() {
+        let mut ctx = test_ctx();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+
+        assert!(handle_input_key(&mut ctx, input, key::ENTER, 0, '\0'));
+        assert_eq!(ctx.event_buffer.len(), 1, "plain Enter submits by default");
+        ctx.event_buffer.clear();
+
+        ctx.nodes.get_mut(&input).unwrap().submit_key = (key::ENTER, modifier::CTRL);
+        assert!(handle_input_key(&mut ctx, input, key::ENTER, 0, '\0'));
+        assert!(ctx.event_buffer.is_empty(), "unbound Enter is swallowed");
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::ENTER,
+            modifier::CTRL,
+            '\0'
+        ));
+        let event = ctx.event_buffer.pop().unwrap();
+        assert_eq!(event.event_type, TuiEventType::Submit as u32);
+        assert_eq!(event.target, input);
+        assert_eq!(ctx.nodes[&input].content, "");
+    }
+
+    #[test]
+    fn test_input_pattern_inserts_separators_and_rejects_misfits() {
+        let mut ctx = test_ctx();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        ctx.nodes.get_mut(&input).unwrap().input_pattern = Some("##-##".to_string());
+
+        for ch in ['1', '2', '3'] {
+            assert!(handle_input_key(&mut ctx, input, ch as u32, 0, ch));
+        }
+        assert_eq!(ctx.nodes[&input].content, "12-3");
+        assert_eq!(ctx.nodes[&input].cursor_position, 4);
+        assert_eq!(ctx.event_buffer.len(), 3);
+        ctx.event_buffer.clear();
+
+        assert!(handle_input_key(&mut ctx, input, 'x' as u32, 0, 'x'));
+        assert_eq!(ctx.nodes[&input].content, "12-3");
+        assert!(ctx.event_buffer.is_empty(), "rejected keys emit no Change");
+
+        assert!(handle_input_key(&mut ctx, input, '4' as u32, 0, '4'));
+        assert!(handle_input_key(&mut ctx, input, '5' as u32, 0, '5'));
+        assert_eq!(ctx.nodes[&input].content, "12-34");
+    }
+
     #[test]
     fn test_input_backspace_removes_whole_grapheme_cluster() {
         let mut ctx = test_ctx();
         let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
         {
             let node = ctx.nodes.get_mut(&input).unwrap();
-            node.content = "e\u{301}".to_string();
+            node.content = "e\u{301}".into();
             node.cursor_position = 1;
         }
 
-        assert!(handle_input_key(&mut ctx, input, key::BACKSPACE, '\0'));
+        assert!(handle_input_key(&mut ctx, input, key::BACKSPACE, 0, '\0'));
         let node = &ctx.nodes[&input];
         assert_eq!(node.content, "");
         assert_eq!(node.cursor_position, 0);
@@ -1586,7 +2103,7 @@ mod tests {
 
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abc\ndef".to_string();
+            node.content = "abc\ndef".into();
             node.cursor_row = 1;
             node.cursor_col = 0;
         }
@@ -1595,6 +2112,7 @@ mod tests {
             &mut ctx,
             textarea,
             key::BACKSPACE,
+            0,
             '\0'
         ));
         let node = &ctx.nodes[&textarea];
@@ -1609,7 +2127,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "e\u{301}x".to_string();
+            node.content = "e\u{301}x".into();
             node.cursor_row = 0;
             node.cursor_col = 1;
         }
@@ -1618,6 +2136,7 @@ mod tests {
             &mut ctx,
             textarea,
             key::BACKSPACE,
+            0,
             '\0'
         ));
         let node = &ctx.nodes[&textarea];
@@ -1632,35 +2151,142 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello".to_string();
+            node.content = "hello".into();
             node.cursor_row = 0;
             node.cursor_col = 2;
         }
 
-        assert!(handle_textarea_key(&mut ctx, textarea, key::ENTER, '\0'));
+        assert!(handle_textarea_key(&mut ctx, textarea, key::ENTER, 0, '\0'));
         let node = &ctx.nodes[&textarea];
         assert_eq!(node.content, "he\nllo");
         assert_eq!(node.cursor_row, 1);
         assert_eq!(node.cursor_col, 0);
     }
 
+    #[test]
+    fn test_textarea_ctrl_home_end_jump_to_document_bounds() {
+        let mut ctx = test_ctx();
+        let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&textarea).unwrap();
+            node.content = "first\nsecond line\nend!".into();
+            node.cursor_row = 1;
+            node.cursor_col = 3;
+        }
+
+        assert!(handle_textarea_key(&mut ctx, textarea, key::END, 0, '\0'));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!((node.cursor_row, node.cursor_col), (1, 11));
+
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::HOME,
+            modifier::CTRL,
+            '\0'
+        ));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!((node.cursor_row, node.cursor_col), (0, 0));
+
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::END,
+            modifier::CTRL,
+            '\0'
+        ));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!((node.cursor_row, node.cursor_col), (2, 4));
+
+        assert!(handle_textarea_key(&mut ctx, textarea, key::HOME, 0, '\0'));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!((node.cursor_row, node.cursor_col), (2, 0));
+    }
+
+    #[test]
+    fn test_ctrl_word_movement_respects_word_break_chars() {
+        let mut ctx = test_ctx();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&input).unwrap();
+            node.content = "foo.bar baz".into();
+            node.cursor_position = 11;
+        }
+        let ctrl = modifier::CTRL;
+        assert!(handle_input_key(&mut ctx, input, key::LEFT, ctrl, '\0'));
+        assert_eq!(ctx.nodes[&input].cursor_position, 8);
+        assert!(handle_input_key(&mut ctx, input, key::LEFT, ctrl, '\0'));
+        assert_eq!(ctx.nodes[&input].cursor_position, 0);
+
+        ctx.word_break_chars = ".".to_string();
+        assert!(handle_input_key(&mut ctx, input, key::RIGHT, ctrl, '\0'));
+        assert_eq!(ctx.nodes[&input].cursor_position, 3);
+        assert!(handle_input_key(&mut ctx, input, key::RIGHT, ctrl, '\0'));
+        assert!(handle_input_key(&mut ctx, input, key::RIGHT, ctrl, '\0'));
+        assert_eq!(ctx.nodes[&input].cursor_position, 7);
+        assert!(handle_input_key(
+            &mut ctx,
+            input,
+            key::BACKSPACE,
+            ctrl,
+            '\0'
+        ));
+        assert_eq!(ctx.nodes[&input].content, "foo. baz");
+        assert_eq!(ctx.nodes[&input].cursor_position, 4);
+
+        let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&textarea).unwrap();
+            node.content = "a.b\nx.y".into();
+            node.cursor_row = 1;
+            node.cursor_col = 0;
+        }
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::RIGHT,
+            ctrl,
+            '\0'
+        ));
+        assert_eq!(ctx.nodes[&textarea].cursor_col, 1);
+        assert!(handle_textarea_key(&mut ctx, textarea, key::END, 0, '\0'));
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::BACKSPACE,
+            ctrl,
+            '\0'
+        ));
+        let node = &ctx.nodes[&textarea];
+        assert_eq!(node.content, "a.b\nx.");
+        assert_eq!((node.cursor_row, node.cursor_col), (1, 2));
+        assert!(handle_textarea_key(
+            &mut ctx,
+            textarea,
+            key::LEFT,
+            ctrl,
+            '\0'
+        ));
+        assert_eq!(ctx.nodes[&textarea].cursor_col, 1);
+    }
+
     #[test]
     fn test_textarea_up_down_clamps_column() {
         let mut ctx = test_ctx();
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abcd\nxy".to_string();
+            node.content = "abcd\nxy".into();
             node.cursor_row = 0;
             node.cursor_col = 4;
         }
 
-        assert!(handle_textarea_key(&mut ctx, textarea, key::DOWN, '\0'));
+        assert!(handle_textarea_key(&mut ctx, textarea, key::DOWN, 0, '\0'));
         let node = &ctx.nodes[&textarea];
         assert_eq!(node.cursor_row, 1);
         assert_eq!(node.cursor_col, 2);
 
-        assert!(handle_textarea_key(&mut ctx, textarea, key::UP, '\0'));
+        assert!(handle_textarea_key(&mut ctx, textarea, key::UP, 0, '\0'));
         let node = &ctx.nodes[&textarea];
         assert_eq!(node.cursor_row, 0);
         assert_eq!(node.cursor_col, 2);
@@ -1672,14 +2298,14 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello".to_string();
+            node.content = "hello".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
         }
 
         // Type " world" as one contiguous insert run.
         for ch in [' ', 'w', 'o', 'r', 'l', 'd'] {
-            handle_textarea_key(&mut ctx, textarea, 0, ch);
+            handle_textarea_key(&mut ctx, textarea, 0, 0, ch);
         }
         assert_eq!(ctx.nodes[&textarea].content, "hello world");
 
@@ -1709,13 +2335,13 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "ab".to_string();
+            node.content = "ab".into();
             node.cursor_row = 0;
             node.cursor_col = 1;
         }
 
         // Insert newline between 'a' and 'b'
-        handle_textarea_key(&mut ctx, textarea, key::ENTER, '\0');
+        handle_textarea_key(&mut ctx, textarea, key::ENTER, 0, '\0');
         assert_eq!(ctx.nodes[&textarea].content, "a\nb");
         assert_eq!(ctx.nodes[&textarea].cursor_row, 1);
         assert_eq!(ctx.nodes[&textarea].cursor_col, 0);
@@ -1731,7 +2357,7 @@ mod tests {
         assert_eq!(ctx.nodes[&textarea].content, "a\nb");
 
         // Now backspace at (1, 0) → joins lines back to "ab"
-        handle_textarea_key(&mut ctx, textarea, key::BACKSPACE, '\0');
+        handle_textarea_key(&mut ctx, textarea, key::BACKSPACE, 0, '\0');
         assert_eq!(ctx.nodes[&textarea].content, "ab");
 
         // Undo the backspace → "a\nb"
@@ -1745,13 +2371,13 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abc".to_string();
+            node.content = "abc".into();
             node.cursor_row = 0;
             node.cursor_col = 3;
         }
 
         // Type 'x'
-        handle_textarea_key(&mut ctx, textarea, 0, 'x');
+        handle_textarea_key(&mut ctx, textarea, 0, 0, 'x');
         assert_eq!(ctx.nodes[&textarea].content, "abcx");
 
         // Undo → "abc"
@@ -1759,7 +2385,7 @@ mod tests {
         assert_eq!(ctx.nodes[&textarea].content, "abc");
 
         // Type 'y' (diverge from redo history)
-        handle_textarea_key(&mut ctx, textarea, 0, 'y');
+        handle_textarea_key(&mut ctx, textarea, 0, 0, 'y');
         assert_eq!(ctx.nodes[&textarea].content, "abcy");
 
         // Redo should now return false (redo stack was cleared)
@@ -1774,7 +2400,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "start".to_string();
+            node.content = "start".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
             node.textarea_state.as_mut().unwrap().history_limit = 3;
@@ -1782,9 +2408,9 @@ mod tests {
 
         // Create five distinct edit groups by breaking coalescing with cursor movement.
         for ch in ['a', 'b', 'c', 'd', 'e'] {
-            handle_textarea_key(&mut ctx, textarea, 0, ch);
-            handle_textarea_key(&mut ctx, textarea, key::LEFT, '\0');
-            handle_textarea_key(&mut ctx, textarea, key::RIGHT, '\0');
+            handle_textarea_key(&mut ctx, textarea, 0, 0, ch);
+            handle_textarea_key(&mut ctx, textarea, key::LEFT, 0, '\0');
+            handle_textarea_key(&mut ctx, textarea, key::RIGHT, 0, '\0');
         }
         assert_eq!(ctx.nodes[&textarea].content, "startabcde");
 
@@ -1808,16 +2434,16 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "x".to_string();
+            node.content = "x".into();
             node.cursor_row = 0;
             node.cursor_col = 1;
         }
 
         // Type 5 characters with explicit coalescing breaks between groups.
         for ch in ['a', 'b', 'c', 'd', 'e'] {
-            handle_textarea_key(&mut ctx, textarea, 0, ch);
-            handle_textarea_key(&mut ctx, textarea, key::LEFT, '\0');
-            handle_textarea_key(&mut ctx, textarea, key::RIGHT, '\0');
+            handle_textarea_key(&mut ctx, textarea, 0, 0, ch);
+            handle_textarea_key(&mut ctx, textarea, key::LEFT, 0, '\0');
+            handle_textarea_key(&mut ctx, textarea, key::RIGHT, 0, '\0');
         }
         assert_eq!(ctx.nodes[&textarea].content, "xabcde");
 
@@ -1851,7 +2477,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello world".to_string();
+            node.content = "hello world".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
             let state = node.textarea_state.as_mut().unwrap();
@@ -1859,7 +2485,7 @@ mod tests {
             state.selection_focus = Some((0, 5));
         }
 
-        assert!(handle_textarea_key(&mut ctx, textarea, 0, 'X'));
+        assert!(handle_textarea_key(&mut ctx, textarea, 0, 0, 'X'));
         assert_eq!(ctx.nodes[&textarea].content, "X world");
 
         let result = textarea_undo_for_test(&mut ctx, textarea);
@@ -1951,6 +2577,103 @@ mod tests {
         assert_eq!(event.target, 0); // no focus
     }
 
+    #[test]
+    fn test_e2e_escape_blurs_to_focusable_ancestor() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let panel = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let input = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        tree::append_child(&mut ctx, root, panel).unwrap();
+        tree::append_child(&mut ctx, panel, input).unwrap();
+        ctx.root = Some(root);
+        ctx.nodes.get_mut(&panel).unwrap().focusable = true;
+        ctx.escape_blurs = true;
+        let escape = || TerminalInputEvent::Key {
+            code: key::ESCAPE,
+            modifiers: 0,
+            character: '\0',
+        };
+
+        ctx.focused = Some(input);
+        inject_events(&mut ctx, vec![escape()]);
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 1);
+        assert_eq!(ctx.focused, Some(panel));
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::FocusChange as u32);
+        assert_eq!((event.data[0], event.data[1]), (input, panel));
+        assert!(
+            next_event(&mut ctx).is_none(),
+            "Escape must not also arrive as a key"
+        );
+
+        // No focusable ancestor left: focus clears.
+        inject_events(&mut ctx, vec![escape()]);
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 1);
+        assert_eq!(ctx.focused, None);
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!((event.data[0], event.data[1]), (panel, 0));
+        assert!(next_event(&mut ctx).is_none());
+
+        // With nothing focused, Escape is delivered to the host as usual.
+        inject_events(&mut ctx, vec![escape()]);
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 1);
+        assert_eq!(
+            next_event(&mut ctx).unwrap().event_type,
+            TuiEventType::Key as u32
+        );
+    }
+
+    #[test]
+    fn test_e2e_idle_tick_event() {
+        let mut ctx = test_ctx();
+
+        // Disabled by default: an idle read produces nothing.
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 0);
+
+        set_tick_interval(&mut ctx, 50);
+        // Interval not yet elapsed.
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 0);
+
+        ctx.last_tick_time = Some(Instant::now() - std::time::Duration::from_millis(80));
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 1);
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::Tick as u32);
+        assert_eq!(event.target, 0);
+        assert!(event.data[0] >= 80);
+
+        // The idle clock restarted with the tick.
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 0);
+
+        set_tick_interval(&mut ctx, 0);
+        assert!(ctx.last_tick_time.is_none());
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_e2e_input_suppresses_tick() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        set_tick_interval(&mut ctx, 50);
+        ctx.last_tick_time = Some(Instant::now() - std::time::Duration::from_millis(80));
+
+        inject_events(
+            &mut ctx,
+            vec![TerminalInputEvent::Key {
+                code: key::ESCAPE,
+                modifiers: 0,
+                character: '\0',
+            }],
+        );
+
+        // Input arrived: only the key is delivered and the idle clock resets.
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 1);
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::Key as u32);
+        assert!(next_event(&mut ctx).is_none());
+        assert_eq!(read_input(&mut ctx, 0).unwrap(), 0);
+    }
+
     #[test]
     fn test_e2e_tab_focus_change() {
         let mut ctx = test_ctx();
@@ -2025,7 +2748,7 @@ mod tests {
         tree::append_child(&mut ctx, root, input).unwrap();
         ctx.root = Some(root);
         ctx.focused = Some(input);
-        ctx.nodes.get_mut(&input).unwrap().content = "hello".to_string();
+        ctx.nodes.get_mut(&input).unwrap().content = "hello".into();
 
         inject_events(
             &mut ctx,
@@ -2055,7 +2778,7 @@ mod tests {
         ctx.focused = Some(select);
 
         let node = ctx.nodes.get_mut(&select).unwrap();
-        node.options = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        node.options = vec!["A".into(), "B".into(), "C".into()];
         node.selected_index = Some(0);
 
         inject_events(
@@ -2079,6 +2802,116 @@ mod tests {
         assert_eq!(event.data[0], 1); // new selected index
     }
 
+    #[test]
+    fn test_select_arrows_skip_filtered_options() {
+        let mut ctx = test_ctx();
+        let select = tree::create_node(&mut ctx, NodeType::Select).unwrap();
+        ctx.root = Some(select);
+        ctx.focused = Some(select);
+
+        let node = ctx.nodes.get_mut(&select).unwrap();
+        node.options = ["Apple", "Banana", "Pineapple", "Cherry", "Grape"]
+            .iter()
+            .map(|&s| s.into())
+            .collect();
+        node.selected_index = Some(0);
+        node.select_filter = "AP".to_string();
+
+        assert!(handle_select_key(&mut ctx, select, key::DOWN, '\0'));
+        assert_eq!(ctx.nodes[&select].selected_index, Some(2));
+        assert!(handle_select_key(&mut ctx, select, key::DOWN, '\0'));
+        assert_eq!(ctx.nodes[&select].selected_index, Some(4));
+        assert!(handle_select_key(&mut ctx, select, key::DOWN, '\0'));
+        assert_eq!(ctx.nodes[&select].selected_index, Some(4));
+        assert!(handle_select_key(&mut ctx, select, key::UP, '\0'));
+        assert_eq!(ctx.nodes[&select].selected_index, Some(2));
+
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.data[0], 2); // full-list index, not filtered position
+    }
+
+    #[test]
+    fn test_select_typeahead_jumps_and_resets() {
+        let mut ctx = test_ctx();
+        let select = tree::create_node(&mut ctx, NodeType::Select).unwrap();
+        ctx.root = Some(select);
+        ctx.focused = Some(select);
+        ctx.nodes.get_mut(&select).unwrap().options = ["Blue", "Gray", "Green", "Grey"]
+            .iter()
+            .map(|&s| s.into())
+            .collect();
+        let selected = |ctx: &TuiContext| ctx.nodes[&select].selected_index;
+
+        assert!(handle_select_key(&mut ctx, select, 0, 'g'));
+        assert_eq!(selected(&ctx), Some(1));
+        assert!(handle_select_key(&mut ctx, select, 0, 'R'));
+        assert!(handle_select_key(&mut ctx, select, 0, 'e'));
+        assert_eq!(selected(&ctx), Some(2));
+        assert!(handle_select_key(&mut ctx, select, 0, 'y'));
+        assert_eq!(selected(&ctx), Some(3));
+
+        // Backspace trims the prefix back to "gre".
+        assert!(handle_select_key(&mut ctx, select, key::BACKSPACE, '\0'));
+        assert_eq!(ctx.nodes[&select].typeahead, "gRe");
+        assert_eq!(selected(&ctx), Some(2));
+
+        // Arrow navigation resets the buffer.
+        assert!(handle_select_key(&mut ctx, select, key::UP, '\0'));
+        assert!(ctx.nodes[&select].typeahead.is_empty());
+        assert_eq!(selected(&ctx), Some(1));
+
+        // After the idle window the next keystroke starts a new prefix.
+        handle_select_key(&mut ctx, select, 0, 'g');
+        ctx.nodes.get_mut(&select).unwrap().typeahead_at =
+            Some(Instant::now() - std::time::Duration::from_millis(1500));
+        assert!(handle_select_key(&mut ctx, select, 0, 'b'));
+        assert_eq!(ctx.nodes[&select].typeahead, "b");
+        assert_eq!(selected(&ctx), Some(0));
+    }
+
+    #[test]
+    fn test_combobox_filters_navigates_and_commits() {
+        let mut ctx = test_ctx();
+        let combo = tree::create_node(&mut ctx, NodeType::ComboBox).unwrap();
+        ctx.root = Some(combo);
+        ctx.focused = Some(combo);
+        ctx.nodes.get_mut(&combo).unwrap().options = ["Berlin", "Bern", "Boston", "Oslo"]
+            .iter()
+            .map(|&s| s.into())
+            .collect();
+
+        for ch in ['b', 'e', 'r'] {
+            assert!(handle_combobox_key(&mut ctx, combo, 0, ch));
+        }
+        assert_eq!(ctx.nodes[&combo].content, "ber");
+        assert_eq!(ctx.nodes[&combo].visible_option_indices(), vec![0, 1]);
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::Change as u32);
+        ctx.event_buffer.clear();
+
+        // Arrows walk only the matching options.
+        assert!(handle_combobox_key(&mut ctx, combo, key::DOWN, '\0'));
+        assert!(handle_combobox_key(&mut ctx, combo, key::DOWN, '\0'));
+        assert!(handle_combobox_key(&mut ctx, combo, key::DOWN, '\0'));
+        assert_eq!(ctx.nodes[&combo].selected_index, Some(1));
+
+        // Enter commits the highlighted option.
+        assert!(handle_combobox_key(&mut ctx, combo, key::ENTER, '\0'));
+        assert_eq!(ctx.nodes[&combo].content, "Bern");
+        assert_eq!(ctx.nodes[&combo].cursor_position, 4);
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::Submit as u32);
+
+        // Editing drops the highlight, so Enter commits the typed text.
+        assert!(handle_combobox_key(&mut ctx, combo, 0, 'x'));
+        assert_eq!(ctx.nodes[&combo].selected_index, None);
+        ctx.event_buffer.clear();
+        assert!(handle_combobox_key(&mut ctx, combo, key::ENTER, '\0'));
+        assert_eq!(ctx.nodes[&combo].content, "Bernx");
+        let event = next_event(&mut ctx).unwrap();
+        assert_eq!(event.event_type, TuiEventType::Submit as u32);
+    }
+
     #[test]
     fn test_e2e_backtab_focus_backward() {
         let mut ctx = test_ctx();
@@ -2523,7 +3356,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello world".to_string();
+            node.content = "hello world".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
             // Select "hello" (cols 0-5)
@@ -2533,7 +3366,7 @@ mod tests {
         }
 
         // Type 'X' — should replace "hello" with "X"
-        handle_textarea_key(&mut ctx, textarea, 0, 'X');
+        handle_textarea_key(&mut ctx, textarea, 0, 0, 'X');
         assert_eq!(ctx.nodes[&textarea].content, "X world");
         assert_eq!(ctx.nodes[&textarea].cursor_row, 0);
         assert_eq!(ctx.nodes[&textarea].cursor_col, 1);
@@ -2546,7 +3379,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello world".to_string();
+            node.content = "hello world".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
             // Select "hello" (cols 0-5)
@@ -2556,7 +3389,7 @@ mod tests {
         }
 
         // Press ENTER — should replace "hello" with newline
-        handle_textarea_key(&mut ctx, textarea, key::ENTER, '\0');
+        handle_textarea_key(&mut ctx, textarea, key::ENTER, 0, '\0');
         assert_eq!(ctx.nodes[&textarea].content, "\n world");
         assert_eq!(ctx.nodes[&textarea].cursor_row, 1);
         assert_eq!(ctx.nodes[&textarea].cursor_col, 0);
@@ -2569,7 +3402,7 @@ mod tests {
         let textarea = tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "hello world".to_string();
+            node.content = "hello world".into();
             node.cursor_row = 0;
             node.cursor_col = 5;
             // Select "hello" (cols 0-5)
@@ -2579,7 +3412,7 @@ mod tests {
         }
 
         // Press BACKSPACE — should delete "hello" only
-        handle_textarea_key(&mut ctx, textarea, key::BACKSPACE, '\0');
+        handle_textarea_key(&mut ctx, textarea, key::BACKSPACE, 0, '\0');
         assert_eq!(ctx.nodes[&textarea].content, " world");
         assert_eq!(ctx.nodes[&textarea].cursor_row, 0);
         assert_eq!(ctx.nodes[&textarea].cursor_col, 0);
@@ -0,0 +1,89 @@
+//! String interner for repeated node content and option labels.
+//!
+//! Enabled with `tui_set_string_interning`. Large tables and Selects repeat
+//! the same labels thousands of times; interned text is a `NodeText::Shared`
+//! pointing at one allocation per distinct string instead of one per node.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::types::NodeText;
+
+/// Table size below which unused strings are never pruned.
+const MIN_PRUNE_LEN: usize = 1024;
+
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+    /// Unused strings are dropped once the table grows to this size.
+    prune_at: usize,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        Self {
+            strings: HashSet::new(),
+            prune_at: MIN_PRUNE_LEN,
+        }
+    }
+
+    /// Number of distinct strings currently held.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// `text` sharing storage with every earlier interned copy of it.
+    pub fn intern(&mut self, text: &str) -> NodeText {
+        if let Some(shared) = self.strings.get(text) {
+            return NodeText::Shared(Arc::clone(shared));
+        }
+        if self.strings.len() >= self.prune_at {
+            self.prune();
+        }
+        let shared: Arc<str> = Arc::from(text);
+        self.strings.insert(Arc::clone(&shared));
+        NodeText::Shared(shared)
+    }
+
+    /// Drop strings that no node holds any more. The threshold doubles with
+    /// what survives so pruning stays amortized O(1) per insert.
+    fn prune(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+        self.prune_at = (self.strings.len() * 2).max(MIN_PRUNE_LEN);
+    }
+}
+
+impl Default for StringInterner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_text_shares_one_allocation() {
+        let mut interner = StringInterner::new();
+        let (NodeText::Shared(a), NodeText::Shared(b)) =
+            (interner.intern("label"), interner.intern("label"))
+        else {
+            panic!("interned text must be shared");
+        };
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn prune_drops_only_unused_strings() {
+        let mut interner = StringInterner::new();
+        let kept = interner.intern("kept");
+        for i in 1..MIN_PRUNE_LEN {
+            interner.intern(&format!("tmp {i}"));
+        }
+        interner.intern("trigger");
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.intern("kept"), kept);
+        assert_eq!(interner.len(), 2);
+    }
+}
@@ -2,10 +2,11 @@
 //!
 //! Responsibilities:
 //! - Translate tui_set_layout_* calls into Taffy Style mutations (read-modify-write per ADR-T04)
-//! - Compute layout from root
+//! - Compute layout from root, measuring Text/Input leaves from their content
 //! - Provide hit-test geometry for mouse events
 
 use crate::context::TuiContext;
+use crate::types::{BorderStyle, ErrorCode, NodeType, TuiError, TuiGridTrack, TuiNodeLayout};
 use taffy::prelude::*;
 use taffy::style_helpers::{auto, length, percent};
 
@@ -17,25 +18,25 @@ pub(crate) fn set_dimension(
     prop: u32,
     value: f32,
     unit: u8,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     // Read current style
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
 
     let dimension = match unit {
         0 => auto(),
         1 => length(value),
         2 => percent(value / 100.0),
-        _ => return Err(format!("Invalid unit: {unit}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid unit: {unit}"))),
     };
 
     match prop {
@@ -45,15 +46,19 @@ pub(crate) fn set_dimension(
         3 => style.min_size.height = dimension,
         4 => style.max_size.width = dimension,
         5 => style.max_size.height = dimension,
-        _ => return Err(format!("Invalid dimension property: {prop}")),
+        _ => {
+            return Err(
+                ErrorCode::InvalidArgument.raise(format!("Invalid dimension property: {prop}"))
+            )
+        }
     }
 
     // Write back
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
 
-    crate::tree::mark_dirty(ctx, handle);
+    crate::tree::mark_layout_dirty(ctx, handle);
     Ok(())
 }
 
@@ -64,17 +69,17 @@ pub(crate) fn set_flex(
     handle: u32,
     prop: u32,
     value: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
 
     match prop {
@@ -84,7 +89,10 @@ pub(crate) fn set_flex(
                 1 => FlexDirection::Column,
                 2 => FlexDirection::RowReverse,
                 3 => FlexDirection::ColumnReverse,
-                _ => return Err(format!("Invalid flex_direction: {value}")),
+                _ => {
+                    return Err(ErrorCode::InvalidArgument
+                        .raise(format!("Invalid flex_direction: {value}")))
+                }
             };
         }
         1 => {
@@ -92,7 +100,11 @@ pub(crate) fn set_flex(
                 0 => FlexWrap::NoWrap,
                 1 => FlexWrap::Wrap,
                 2 => FlexWrap::WrapReverse,
-                _ => return Err(format!("Invalid flex_wrap: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid flex_wrap: {value}"))
+                    )
+                }
             };
         }
         2 => {
@@ -103,7 +115,10 @@ pub(crate) fn set_flex(
                 3 => JustifyContent::SpaceBetween,
                 4 => JustifyContent::SpaceAround,
                 5 => JustifyContent::SpaceEvenly,
-                _ => return Err(format!("Invalid justify_content: {value}")),
+                _ => {
+                    return Err(ErrorCode::InvalidArgument
+                        .raise(format!("Invalid justify_content: {value}")))
+                }
             });
         }
         3 => {
@@ -113,7 +128,11 @@ pub(crate) fn set_flex(
                 2 => AlignItems::End,
                 3 => AlignItems::Center,
                 4 => AlignItems::Baseline,
-                _ => return Err(format!("Invalid align_items: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid align_items: {value}"))
+                    )
+                }
             });
         }
         4 => {
@@ -124,7 +143,11 @@ pub(crate) fn set_flex(
                 3 => Some(AlignSelf::End),
                 4 => Some(AlignSelf::Center),
                 5 => Some(AlignSelf::Baseline),
-                _ => return Err(format!("Invalid align_self: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid align_self: {value}"))
+                    )
+                }
             };
         }
         5 => {
@@ -135,14 +158,22 @@ pub(crate) fn set_flex(
                 3 => AlignContent::SpaceBetween,
                 4 => AlignContent::SpaceAround,
                 5 => AlignContent::SpaceEvenly,
-                _ => return Err(format!("Invalid align_content: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid align_content: {value}"))
+                    )
+                }
             });
         }
         6 => {
             style.position = match value {
                 0 => Position::Relative,
                 1 => Position::Absolute,
-                _ => return Err(format!("Invalid position: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid position: {value}"))
+                    )
+                }
             };
         }
         7 => {
@@ -150,22 +181,40 @@ pub(crate) fn set_flex(
                 0 => taffy::Overflow::Visible,
                 1 => taffy::Overflow::Hidden,
                 2 => taffy::Overflow::Scroll,
-                _ => return Err(format!("Invalid overflow: {value}")),
+                _ => {
+                    return Err(
+                        ErrorCode::InvalidArgument.raise(format!("Invalid overflow: {value}"))
+                    )
+                }
             };
             style.overflow = taffy::Point { x: ov, y: ov };
         }
-        _ => return Err(format!("Invalid flex property: {prop}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid flex property: {prop}"))),
     }
 
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
 
-    crate::tree::mark_dirty(ctx, handle);
+    crate::tree::mark_layout_dirty(ctx, handle);
     Ok(())
 }
 
-/// Set edge properties (padding, margin) on all four sides.
+/// Resolve an edge/gap value in `unit` (1 = cells, 2 = percent of the
+/// containing block), matching `set_dimension`'s unit codes.
+fn length_percentage(value: f32, unit: u8) -> Result<LengthPercentage, TuiError> {
+    match unit {
+        1 => Ok(length(value)),
+        2 => Ok(percent(value / 100.0)),
+        _ => Err(ErrorCode::InvalidArgument.raise(format!("Invalid unit: {unit}"))),
+    }
+}
+
+/// Set edge properties (0 = padding, 1 = margin, 2 = inset) on all four sides,
+/// in `unit` (0 = auto, margin/inset only; 1 = cells; 2 = percent).
+/// Inset offsets a `position: absolute` node from its containing block; a
+/// NaN side is left `auto`.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn set_edges(
     ctx: &mut TuiContext,
     handle: u32,
@@ -174,76 +223,91 @@ pub(crate) fn set_edges(
     right: f32,
     bottom: f32,
     left: f32,
-) -> Result<(), String> {
+    unit: u8,
+) -> Result<(), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
 
+    let auto_side = |value: f32| -> Result<LengthPercentageAuto, TuiError> {
+        if unit == 0 || (prop == 2 && value.is_nan()) {
+            Ok(LengthPercentageAuto::auto())
+        } else {
+            length_percentage(value, unit).map(Into::into)
+        }
+    };
+
     match prop {
         0 => {
             style.padding = taffy::geometry::Rect {
-                top: length(top),
-                right: length(right),
-                bottom: length(bottom),
-                left: length(left),
+                top: length_percentage(top, unit)?,
+                right: length_percentage(right, unit)?,
+                bottom: length_percentage(bottom, unit)?,
+                left: length_percentage(left, unit)?,
             };
         }
-        1 => {
-            style.margin = taffy::geometry::Rect {
-                top: length(top),
-                right: length(right),
-                bottom: length(bottom),
-                left: length(left),
+        1 | 2 => {
+            let edges = taffy::geometry::Rect {
+                top: auto_side(top)?,
+                right: auto_side(right)?,
+                bottom: auto_side(bottom)?,
+                left: auto_side(left)?,
             };
+            if prop == 1 {
+                style.margin = edges;
+            } else {
+                style.inset = edges;
+            }
         }
-        _ => return Err(format!("Invalid edge property: {prop}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid edge property: {prop}"))),
     }
 
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
 
-    crate::tree::mark_dirty(ctx, handle);
+    crate::tree::mark_layout_dirty(ctx, handle);
     Ok(())
 }
 
-/// Set row and column gap.
+/// Set row and column gap in `unit` (1 = cells, 2 = percent of the container).
 pub(crate) fn set_gap(
     ctx: &mut TuiContext,
     handle: u32,
     row_gap: f32,
     column_gap: f32,
-) -> Result<(), String> {
+    unit: u8,
+) -> Result<(), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
 
     style.gap = Size {
-        width: length(column_gap),
-        height: length(row_gap),
+        width: length_percentage(column_gap, unit)?,
+        height: length_percentage(row_gap, unit)?,
     };
 
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
 
-    crate::tree::mark_dirty(ctx, handle);
+    crate::tree::mark_layout_dirty(ctx, handle);
     Ok(())
 }
 
@@ -254,41 +318,223 @@ pub(crate) fn set_flex_factor(
     handle: u32,
     prop: u32,
     value: f32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
 
     match prop {
         0 => style.flex_grow = value,
         1 => style.flex_shrink = value,
         2 => style.flex_basis = taffy::Dimension::length(value),
-        _ => return Err(format!("Invalid flex_factor property: {prop}")),
+        _ => {
+            return Err(
+                ErrorCode::InvalidArgument.raise(format!("Invalid flex_factor property: {prop}"))
+            )
+        }
+    }
+
+    ctx.tree
+        .set_style(taffy_node, style)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
+
+    crate::tree::mark_layout_dirty(ctx, handle);
+    Ok(())
+}
+
+/// Set the width / height aspect ratio used when only one axis is sized.
+/// A ratio of 0 clears it.
+pub(crate) fn set_aspect_ratio(
+    ctx: &mut TuiContext,
+    handle: u32,
+    ratio: f32,
+) -> Result<(), TuiError> {
+    if !ratio.is_finite() || ratio < 0.0 {
+        return Err(ErrorCode::InvalidArgument.raise(format!("Invalid aspect ratio: {ratio}")));
     }
+    let taffy_node = ctx
+        .nodes
+        .get(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
+        .taffy_node;
+
+    let mut style = ctx
+        .tree
+        .style(taffy_node)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
+        .clone();
+
+    style.aspect_ratio = if ratio == 0.0 { None } else { Some(ratio) };
 
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
 
-    crate::tree::mark_dirty(ctx, handle);
+    crate::tree::mark_layout_dirty(ctx, handle);
+    Ok(())
+}
+
+/// Set how a node lays out its children: 0 = flex, 1 = grid, 2 = none.
+/// A `none` node and its subtree take no layout space; unlike `visible`,
+/// siblings reflow into the gap.
+pub(crate) fn set_display(ctx: &mut TuiContext, handle: u32, display: u8) -> Result<(), TuiError> {
+    let node = ctx
+        .nodes
+        .get_mut(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    match display {
+        0 | 1 => {
+            node.display_grid = display == 1;
+            node.display_none = false;
+        }
+        2 => node.display_none = true,
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid display: {display}"))),
+    }
+    sync_display(ctx, handle)
+}
+
+/// Toggle `display: none` without touching the node's flex/grid mode.
+pub(crate) fn set_display_none(
+    ctx: &mut TuiContext,
+    handle: u32,
+    enabled: bool,
+) -> Result<(), TuiError> {
+    ctx.nodes
+        .get_mut(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
+        .display_none = enabled;
+    sync_display(ctx, handle)
+}
+
+/// The Taffy display a node should have. Hidden nodes, closed overlays and
+/// display-none nodes all collapse out of layout.
+pub(crate) fn node_display(node: &crate::types::TuiNode) -> Display {
+    let overlay_open = node.overlay_state.as_ref().is_none_or(|ov| ov.open);
+    if !node.visible || !overlay_open || node.display_none {
+        Display::None
+    } else if node.display_grid {
+        Display::Grid
+    } else {
+        Display::Flex
+    }
+}
+
+/// Push the node's visibility/display flags into its Taffy style.
+pub(crate) fn sync_display(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let display = node_display(
+        ctx.nodes
+            .get(&handle)
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?,
+    );
+    update_style(ctx, handle, |style| style.display = display)
+}
+
+/// Replace a grid container's column (axis 0) or row (axis 1) track list.
+pub(crate) fn set_grid_template(
+    ctx: &mut TuiContext,
+    handle: u32,
+    axis: u8,
+    tracks: &[TuiGridTrack],
+) -> Result<(), TuiError> {
+    let mut template = Vec::with_capacity(tracks.len());
+    for track in tracks {
+        if !track.value.is_finite() || track.value < 0.0 {
+            return Err(ErrorCode::InvalidArgument
+                .raise(format!("Invalid grid track value: {}", track.value)));
+        }
+        template.push(match track.kind {
+            0 => auto(),
+            1 => length(track.value),
+            2 => fr(track.value),
+            3 => percent(track.value / 100.0),
+            kind => {
+                return Err(
+                    ErrorCode::InvalidArgument.raise(format!("Invalid grid track kind: {kind}"))
+                )
+            }
+        });
+    }
+    match axis {
+        0 => update_style(ctx, handle, |style| style.grid_template_columns = template),
+        1 => update_style(ctx, handle, |style| style.grid_template_rows = template),
+        _ => Err(ErrorCode::InvalidArgument.raise(format!("Invalid grid axis: {axis}"))),
+    }
+}
+
+/// Place a grid item between 1-based grid lines (negative counts from the
+/// end, as in CSS). A line of 0 leaves that edge to auto-placement.
+pub(crate) fn set_grid_placement(
+    ctx: &mut TuiContext,
+    handle: u32,
+    col_start: i16,
+    col_end: i16,
+    row_start: i16,
+    row_end: i16,
+) -> Result<(), TuiError> {
+    fn placement(index: i16) -> GridPlacement {
+        if index == 0 {
+            GridPlacement::Auto
+        } else {
+            line(index)
+        }
+    }
+    update_style(ctx, handle, |style| {
+        style.grid_column = Line {
+            start: placement(col_start),
+            end: placement(col_end),
+        };
+        style.grid_row = Line {
+            start: placement(row_start),
+            end: placement(row_end),
+        };
+    })
+}
+
+/// Read-modify-write a node's Taffy style (ADR-T04) and mark it dirty.
+fn update_style(
+    ctx: &mut TuiContext,
+    handle: u32,
+    apply: impl FnOnce(&mut Style),
+) -> Result<(), TuiError> {
+    let taffy_node = ctx
+        .nodes
+        .get(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
+        .taffy_node;
+
+    let mut style = ctx
+        .tree
+        .style(taffy_node)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
+        .clone();
+    apply(&mut style);
+    ctx.tree
+        .set_style(taffy_node, style)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
+
+    crate::tree::mark_layout_dirty(ctx, handle);
     Ok(())
 }
 
 /// Compute layout from root with the given available space.
-pub(crate) fn compute_layout(ctx: &mut TuiContext) -> Result<(), String> {
-    let root_handle = ctx.root.ok_or("No root set. Call tui_set_root() first.")?;
+pub(crate) fn compute_layout(ctx: &mut TuiContext) -> Result<(), TuiError> {
+    let root_handle = ctx.root.ok_or_else(|| {
+        ErrorCode::NotInitialized.raise("No root set. Call tui_set_root() first.")
+    })?;
     let root_taffy = ctx
         .nodes
         .get(&root_handle)
-        .ok_or_else(|| format!("Root handle {root_handle} not found"))?
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Root handle {root_handle} not found"))
+        })?
         .taffy_node;
 
     let (w, h) = ctx.backend.size();
@@ -300,9 +546,17 @@ pub(crate) fn compute_layout(ctx: &mut TuiContext) -> Result<(), String> {
         height: AvailableSpace::Definite(h as f32),
     };
 
-    ctx.tree
-        .compute_layout(root_taffy, avail)
-        .map_err(|e| format!("Layout computation failed: {e:?}"))?;
+    flag_remeasured_text(ctx);
+
+    // Paint-only changes leave the previous layout valid.
+    let basis = Some((root_handle, w, h));
+    if !ctx.layout_dirty && ctx.layout_basis == basis {
+        ctx.perf_layout_us = 0;
+        return Ok(());
+    }
+    let depth = tree_depth(ctx, root_handle);
+    run_taffy(ctx, root_taffy, avail, depth)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Layout computation failed: {e}")))?;
 
     // After the first layout pass, re-sync SplitPane children using the real
     // computed container sizes. This locks ratios to exact cell counts instead
@@ -325,20 +579,192 @@ pub(crate) fn compute_layout(ctx: &mut TuiContext) -> Result<(), String> {
         if !needs_relayout {
             break;
         }
-        ctx.tree
-            .compute_layout(root_taffy, avail)
-            .map_err(|e| format!("Layout re-computation failed: {e:?}"))?;
+        run_taffy(ctx, root_taffy, avail, depth)
+            .map_err(|e| ErrorCode::Layout.raise(format!("Layout re-computation failed: {e}")))?;
     }
 
+    ctx.layout_dirty = false;
+    ctx.layout_basis = basis;
     ctx.perf_layout_us = start.elapsed().as_micros() as u64;
     ctx.debug_log(&format!("compute_layout: {}μs", ctx.perf_layout_us));
 
     Ok(())
 }
 
+/// Deepest tree Taffy lays out on the calling thread. Its layout recursion
+/// takes a few KiB of stack per level, so deeper trees are laid out on a
+/// scoped thread whose stack grows with the depth.
+const INLINE_LAYOUT_DEPTH: usize = 128;
+/// Layout thread stack per tree level: about 2 KiB is used in release builds
+/// and 8 KiB in debug builds.
+const LAYOUT_STACK_PER_LEVEL: usize = 16 * 1024;
+
+/// One Taffy pass, movable onto the layout thread.
+struct TaffyPass<'a> {
+    tree: &'a mut TaffyTree<u32>,
+    nodes: &'a crate::node_store::NodeStore,
+    root: NodeId,
+    avail: Size<AvailableSpace>,
+}
+
+// SAFETY: as for `TuiContext`, the only non-Send data is Taffy's
+// `CompactLength`, which never holds a calc() pointer here. The pass borrows
+// the context exclusively and is joined before the borrow ends.
+unsafe impl Send for TaffyPass<'_> {}
+
+impl TaffyPass<'_> {
+    fn run(self) -> Result<(), TuiError> {
+        let nodes = self.nodes;
+        self.tree
+            .compute_layout_with_measure(self.root, self.avail, |known, available, _, handle, _| {
+                measure_leaf(nodes, known, available, handle.copied())
+            })
+            .map_err(|e| format!("{e:?}").into())
+    }
+}
+
+/// Run Taffy over the tree under `root`, which is `depth` levels deep.
+fn run_taffy(
+    ctx: &mut TuiContext,
+    root: NodeId,
+    avail: Size<AvailableSpace>,
+    depth: usize,
+) -> Result<(), TuiError> {
+    let pass = TaffyPass {
+        tree: &mut ctx.tree,
+        nodes: &ctx.nodes,
+        root,
+        avail,
+    };
+    if depth <= INLINE_LAYOUT_DEPTH {
+        return pass.run();
+    }
+    let stack_size = (depth + 1).saturating_mul(LAYOUT_STACK_PER_LEVEL);
+    std::thread::scope(|scope| {
+        let worker = std::thread::Builder::new()
+            .name("kraken-layout".to_string())
+            .stack_size(stack_size)
+            .spawn_scoped(scope, move || pass.run())
+            .map_err(|e| format!("cannot start layout thread for {depth} levels: {e}"))?;
+        worker
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Number of levels below `root`, counted without recursing.
+fn tree_depth(ctx: &TuiContext, root: u32) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(root, 0)];
+    while let Some((handle, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        if let Some(node) = ctx.nodes.get(&handle) {
+            stack.extend(node.children.iter().map(|&child| (child, depth + 1)));
+        }
+    }
+    deepest
+}
+
+/// Invalidate Taffy's cached measurement of dirty Text and Input leaves whose
+/// `measure_key` changed, and request a layout pass for them. Other dirty
+/// leaves (a color, a caret move) keep the cache and the previous layout.
+fn flag_remeasured_text(ctx: &mut TuiContext) {
+    for node in ctx.nodes.values_mut() {
+        if node.dirty && matches!(node.node_type, NodeType::Text | NodeType::Input) {
+            let key = measure_key(node);
+            if node.measure_key != Some(key) {
+                node.measure_key = Some(key);
+                let _ = ctx.tree.mark_dirty(node.taffy_node);
+                ctx.layout_dirty = true;
+            }
+        }
+    }
+}
+
+/// Hash of everything `measure_leaf` reads from a node.
+fn measure_key(node: &crate::types::TuiNode) -> u64 {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    (
+        node.node_type as u8,
+        &*node.content,
+        node.content_format as u8,
+        node.text_transform as u8,
+        node.shown_mask(),
+        node.letter_spacing,
+        node.fixed_advance,
+        node.line_scale as u8,
+        node.line_spacing,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Intrinsic content size of a Text or Input leaf. Plain Text wraps to the
+/// width it is offered; Markdown/Code Text keep their explicit sizing, and
+/// Input is one row wide enough for its content plus the cursor cell (it
+/// scrolls horizontally, so its min-content width is 0).
+fn measure_leaf(
+    nodes: &crate::node_store::NodeStore,
+    known: Size<Option<f32>>,
+    available: Size<AvailableSpace>,
+    handle: Option<u32>,
+) -> Size<f32> {
+    let Some(node) = handle.and_then(|h| nodes.get(&h)) else {
+        return Size::ZERO;
+    };
+    let content = crate::text_utils::transform_case(&node.content, node.text_transform);
+    let (width, height) = match node.node_type {
+        NodeType::Input if available.width == AvailableSpace::MinContent => (0, 1),
+        NodeType::Input => {
+            // Masked input shows one mask character per source grapheme.
+            let shown = if node.shown_mask() != 0 {
+                &node.content
+            } else {
+                &*content
+            };
+            let graphemes = crate::text_utils::grapheme_count(shown) as u32;
+            let width = if node.fixed_advance != 0 {
+                graphemes * node.fixed_advance as u32
+            } else if node.shown_mask() != 0 {
+                graphemes
+            } else {
+                crate::text::measure_text(&content)
+            };
+            (width + graphemes * node.letter_spacing + 1, 1)
+        }
+        NodeType::Text if node.content_format == crate::types::ContentFormat::Plain => {
+            let max_w = known.width.or(match available.width {
+                AvailableSpace::Definite(w) => Some(w),
+                AvailableSpace::MinContent => Some(0.0),
+                AvailableSpace::MaxContent => None,
+            });
+            let (letter_spacing, fixed_advance) = node.laid_out_spacing();
+            let (width, rows) = crate::text::measure_wrapped(
+                &content,
+                max_w.map(|w| w.max(0.0) as u32),
+                letter_spacing,
+                fixed_advance,
+            );
+            // The last glyph on a scaled line still covers two columns.
+            let width = if node.line_scale != crate::types::LineScale::Normal && width > 0 {
+                width + 1
+            } else {
+                width
+            };
+            (width, crate::text::spaced_height(rows, node.line_spacing))
+        }
+        _ => return Size::ZERO,
+    };
+    Size {
+        width: known.width.unwrap_or(width as f32),
+        height: known.height.unwrap_or(height as f32),
+    }
+}
+
 /// Re-sync a SplitPane's child styles using its computed size.
-/// Returns true when a second layout pass should be run.
-fn resync_splitpane_if_needed(ctx: &mut TuiContext, handle: u32) -> Result<bool, String> {
+/// Returns true when a style changed and another layout pass should run.
+fn resync_splitpane_if_needed(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
     let has_two_children = {
         let node = match ctx.nodes.get(&handle) {
             Some(n) => n,
@@ -350,22 +776,21 @@ fn resync_splitpane_if_needed(ctx: &mut TuiContext, handle: u32) -> Result<bool,
         return Ok(false);
     }
 
-    crate::splitpane::sync_children_layout(ctx, handle)?;
-    Ok(true)
+    crate::splitpane::sync_children_layout(ctx, handle)
 }
 
 /// Get the computed layout for a node (x, y, width, height).
-pub(crate) fn get_layout(ctx: &TuiContext, handle: u32) -> Result<(i32, i32, i32, i32), String> {
+pub(crate) fn get_layout(ctx: &TuiContext, handle: u32) -> Result<(i32, i32, i32, i32), TuiError> {
     let taffy_node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .taffy_node;
 
     let layout = ctx
         .tree
         .layout(taffy_node)
-        .map_err(|e| format!("Failed to get layout: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to get layout: {e:?}")))?;
 
     Ok((
         layout.location.x as i32,
@@ -375,46 +800,117 @@ pub(crate) fn get_layout(ctx: &TuiContext, handle: u32) -> Result<(i32, i32, i32
     ))
 }
 
-/// Hit-test: find the deepest widget containing the given coordinates.
-/// Traverses back-to-front to match visual stacking order.
-pub(crate) fn hit_test(ctx: &TuiContext, x: u16, y: u16) -> Option<u32> {
-    let root = ctx.root?;
-    hit_test_recursive(ctx, root, x as f32, y as f32, 0.0, 0.0)
+/// Absolute layout position of a node: the sum of computed layout locations
+/// up the parent chain. Render offsets are not included.
+pub(crate) fn absolute_position(ctx: &TuiContext, handle: u32) -> Option<(f32, f32)> {
+    let (mut x, mut y) = (0.0, 0.0);
+    let mut current = Some(handle);
+    while let Some(h) = current {
+        let node = ctx.nodes.get(&h)?;
+        let layout = ctx.tree.layout(node.taffy_node).ok()?;
+        x += layout.location.x;
+        y += layout.location.y;
+        current = node.parent;
+    }
+    Some((x, y))
+}
+
+/// Screen rects for every node, positioned the way `render_node` draws
+/// them: parent offsets, rounded render offsets, and ScrollBox border insets
+/// and scroll. The root tree comes first in pre-order; detached subtrees
+/// follow in handle order, placed as if drawn at the origin. Nodes whose
+/// layout has not been computed are skipped.
+pub(crate) fn screen_layouts(ctx: &TuiContext) -> Vec<TuiNodeLayout> {
+    let mut out = Vec::with_capacity(ctx.nodes.len());
+    if let Some(root) = ctx.root {
+        collect_screen_layouts(ctx, root, 0, 0, &mut out);
+    }
+    let mut detached: Vec<u32> = ctx
+        .nodes
+        .iter()
+        .filter(|(&h, n)| n.parent.is_none() && Some(h) != ctx.root)
+        .map(|(&h, _)| h)
+        .collect();
+    detached.sort_unstable();
+    for handle in detached {
+        collect_screen_layouts(ctx, handle, 0, 0, &mut out);
+    }
+    out
 }
 
-fn hit_test_recursive(
+fn collect_screen_layouts(
     ctx: &TuiContext,
     handle: u32,
-    x: f32,
-    y: f32,
-    offset_x: f32,
-    offset_y: f32,
-) -> Option<u32> {
-    let node = ctx.nodes.get(&handle)?;
-    if !node.visible {
-        return None;
-    }
-
-    let layout = ctx.tree.layout(node.taffy_node).ok()?;
-    // Keep hit-testing aligned with render-space coordinates, including
-    // per-node render offset.
-    let abs_x = offset_x + layout.location.x + node.render_offset.0.round();
-    let abs_y = offset_y + layout.location.y + node.render_offset.1.round();
-
-    // Check if point is within this node's bounds
-    if x < abs_x || y < abs_y || x >= abs_x + layout.size.width || y >= abs_y + layout.size.height {
-        return None;
-    }
-
-    // Check children in reverse order (back-to-front = last child is visually on top)
-    for &child_handle in node.children.iter().rev() {
-        if let Some(hit) = hit_test_recursive(ctx, child_handle, x, y, abs_x, abs_y) {
-            return Some(hit);
-        }
+    parent_x: i32,
+    parent_y: i32,
+    out: &mut Vec<TuiNodeLayout>,
+) {
+    let Some(node) = ctx.nodes.get(&handle) else {
+        return;
+    };
+    let Ok(layout) = ctx.tree.layout(node.taffy_node) else {
+        return;
+    };
+    let x = parent_x + layout.location.x as i32 + node.render_offset.0.round() as i32;
+    let y = parent_y + layout.location.y as i32 + node.render_offset.1.round() as i32;
+    out.push(TuiNodeLayout {
+        handle,
+        x,
+        y,
+        w: layout.size.width as i32,
+        h: layout.size.height as i32,
+    });
+
+    let (child_x, child_y) = if node.node_type == NodeType::ScrollBox {
+        let inset = if crate::style::resolve_style(handle, ctx).border_style != BorderStyle::None {
+            1
+        } else {
+            0
+        };
+        (x + inset - node.scroll_x, y + inset - node.scroll_y)
+    } else {
+        (x, y)
+    };
+    for &child in &node.children {
+        collect_screen_layouts(ctx, child, child_x, child_y, out);
     }
+}
 
-    // No child hit — this node is the target
-    Some(handle)
+/// Hit-test: find the deepest widget containing the given coordinates.
+/// Traverses back-to-front to match visual stacking order.
+pub(crate) fn hit_test(ctx: &TuiContext, x: u16, y: u16) -> Option<u32> {
+    let (x, y) = (x as f32, y as f32);
+    let contains = |handle: u32, offset_x: f32, offset_y: f32| -> Option<(f32, f32)> {
+        let node = ctx.nodes.get(&handle)?;
+        if !node.visible || node.display_none {
+            return None;
+        }
+        let layout = ctx.tree.layout(node.taffy_node).ok()?;
+        // Keep hit-testing aligned with render-space coordinates, including
+        // per-node render offset.
+        let abs_x = offset_x + layout.location.x + node.render_offset.0.round();
+        let abs_y = offset_y + layout.location.y + node.render_offset.1.round();
+        let inside = x >= abs_x
+            && y >= abs_y
+            && x < abs_x + layout.size.width
+            && y < abs_y + layout.size.height;
+        inside.then_some((abs_x, abs_y))
+    };
+
+    let mut hit = ctx.root?;
+    let mut origin = contains(hit, 0.0, 0.0)?;
+    // Descend into the last child containing the point (back-to-front = last
+    // child is visually on top) until no child does.
+    'descend: loop {
+        for &child in ctx.nodes[&hit].children.iter().rev() {
+            if let Some(child_origin) = contains(child, origin.0, origin.1) {
+                hit = child;
+                origin = child_origin;
+                continue 'descend;
+            }
+        }
+        return Some(hit);
+    }
 }
 
 #[cfg(test)]
@@ -429,6 +925,198 @@ mod tests {
         TuiContext::new(Box::new(MockBackend::new(80, 24)))
     }
 
+    #[test]
+    fn test_screen_layouts_follow_render_offsets_and_scroll() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let sb = tree::create_node(&mut ctx, NodeType::ScrollBox).unwrap();
+        let inner = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let detached = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        tree::append_child(&mut ctx, root, sb).unwrap();
+        tree::append_child(&mut ctx, sb, inner).unwrap();
+        ctx.root = Some(root);
+        set_edges(&mut ctx, root, 0, 2.0, 0.0, 0.0, 3.0, 1).unwrap();
+        set_dimension(&mut ctx, sb, 0, 10.0, 1).unwrap();
+        set_dimension(&mut ctx, sb, 1, 4.0, 1).unwrap();
+        set_dimension(&mut ctx, inner, 0, 10.0, 1).unwrap();
+        set_dimension(&mut ctx, inner, 1, 20.0, 1).unwrap();
+        crate::style::set_border(&mut ctx, sb, 1).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        ctx.nodes.get_mut(&sb).unwrap().scroll_y = 5;
+        ctx.nodes.get_mut(&sb).unwrap().render_offset = (1.0, 0.0);
+
+        let rects = screen_layouts(&ctx);
+        let handles: Vec<u32> = rects.iter().map(|r| r.handle).collect();
+        assert_eq!(handles, vec![root, sb, inner, detached]);
+        assert_eq!(
+            (rects[1].x, rects[1].y, rects[1].w, rects[1].h),
+            (4, 2, 10, 4)
+        );
+        // Same origin render_node uses: the content area plus the child's
+        // own layout location, shifted up by the scroll offset.
+        assert_eq!((rects[2].x, rects[2].y), (6, -1));
+    }
+
+    #[test]
+    fn test_aspect_ratio_derives_height_from_width() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let tile = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        tree::append_child(&mut ctx, root, tile).unwrap();
+        ctx.root = Some(root);
+        set_flex(&mut ctx, root, 3, 1).unwrap(); // align_items: start
+        set_dimension(&mut ctx, tile, 0, 20.0, 1).unwrap();
+        set_aspect_ratio(&mut ctx, tile, 2.0).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, tile).unwrap(), (0, 0, 20, 10));
+
+        set_aspect_ratio(&mut ctx, tile, 0.0).unwrap();
+        assert_eq!(
+            ctx.tree
+                .style(ctx.nodes[&tile].taffy_node)
+                .unwrap()
+                .aspect_ratio,
+            None
+        );
+        assert!(set_aspect_ratio(&mut ctx, tile, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_grid_template_and_placement() {
+        let mut ctx = test_ctx();
+        let grid = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let cells: Vec<u32> = (0..3)
+            .map(|_| {
+                let cell = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+                tree::append_child(&mut ctx, grid, cell).unwrap();
+                cell
+            })
+            .collect();
+        ctx.root = Some(grid);
+        set_dimension(&mut ctx, grid, 0, 40.0, 1).unwrap();
+        set_display(&mut ctx, grid, 1).unwrap();
+        let columns = [
+            TuiGridTrack {
+                kind: 1,
+                value: 10.0,
+            },
+            TuiGridTrack {
+                kind: 2,
+                value: 1.0,
+            },
+            TuiGridTrack {
+                kind: 2,
+                value: 2.0,
+            },
+        ];
+        set_grid_template(&mut ctx, grid, 0, &columns).unwrap();
+        let rows = [TuiGridTrack {
+            kind: 1,
+            value: 3.0,
+        }; 2];
+        set_grid_template(&mut ctx, grid, 1, &rows).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, cells[0]).unwrap(), (0, 0, 10, 3));
+        assert_eq!(get_layout(&ctx, cells[1]).unwrap(), (10, 0, 10, 3));
+        assert_eq!(get_layout(&ctx, cells[2]).unwrap(), (20, 0, 20, 3));
+
+        // Span the last two columns of the second row.
+        set_grid_placement(&mut ctx, cells[0], 2, 4, 2, 3).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, cells[0]).unwrap(), (10, 3, 30, 3));
+
+        let bad = [TuiGridTrack {
+            kind: 9,
+            value: 1.0,
+        }];
+        assert!(set_grid_template(&mut ctx, grid, 0, &bad).is_err());
+        assert!(set_display(&mut ctx, grid, 7).is_err());
+    }
+
+    #[test]
+    fn test_display_none_collapses_gap() {
+        let mut ctx = test_ctx();
+        let column = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        set_flex(&mut ctx, column, 0, 1).unwrap();
+        let rows: Vec<u32> = (0..3)
+            .map(|_| {
+                let row = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+                set_dimension(&mut ctx, row, 1, 3.0, 1).unwrap();
+                tree::append_child(&mut ctx, column, row).unwrap();
+                row
+            })
+            .collect();
+        ctx.root = Some(column);
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, rows[2]).unwrap().1, 6);
+
+        set_display_none(&mut ctx, rows[1], true).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, rows[2]).unwrap().1, 3);
+        assert!(ctx.nodes.contains_key(&rows[1]));
+
+        // Display-none does not forget the grid mode underneath it.
+        set_display(&mut ctx, rows[1], 1).unwrap();
+        set_display_none(&mut ctx, rows[1], true).unwrap();
+        set_display_none(&mut ctx, rows[1], false).unwrap();
+        let taffy_node = ctx.nodes[&rows[1]].taffy_node;
+        assert_eq!(ctx.tree.style(taffy_node).unwrap().display, Display::Grid);
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, rows[2]).unwrap().1, 6);
+    }
+
+    #[test]
+    fn test_percent_gap_and_edges() {
+        let mut ctx = test_ctx();
+        let row = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let b = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        tree::append_child(&mut ctx, row, a).unwrap();
+        tree::append_child(&mut ctx, row, b).unwrap();
+        ctx.root = Some(row);
+        set_dimension(&mut ctx, row, 0, 80.0, 1).unwrap();
+        set_dimension(&mut ctx, a, 0, 10.0, 1).unwrap();
+        set_dimension(&mut ctx, b, 0, 10.0, 1).unwrap();
+        set_gap(&mut ctx, row, 0.0, 10.0, 2).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, b).unwrap().0, 18);
+
+        // Percent margins resolve against the containing block's width.
+        set_edges(&mut ctx, a, 1, 0.0, 0.0, 0.0, 5.0, 2).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, a).unwrap().0, 4);
+
+        assert!(set_gap(&mut ctx, row, 1.0, 1.0, 0).is_err());
+        assert!(set_edges(&mut ctx, a, 0, 1.0, 1.0, 1.0, 1.0, 0).is_err());
+    }
+
+    #[test]
+    fn test_text_measures_content() {
+        let mut ctx = test_ctx();
+        let column = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let row = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let label = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let para = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        set_flex(&mut ctx, column, 0, 1).unwrap();
+        set_flex(&mut ctx, column, 3, 1).unwrap(); // align_items: start
+        tree::append_child(&mut ctx, column, row).unwrap();
+        tree::append_child(&mut ctx, row, label).unwrap();
+        tree::append_child(&mut ctx, column, para).unwrap();
+        ctx.root = Some(column);
+        ctx.nodes.get_mut(&label).unwrap().content = "Name:".into();
+        ctx.nodes.get_mut(&para).unwrap().content = "abcdefghij\nk".into();
+        set_dimension(&mut ctx, para, 0, 4.0, 1).unwrap();
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, label).unwrap(), (0, 0, 5, 1));
+        // Wrapped to the fixed width: 3 rows for the first line, 1 for "k".
+        assert_eq!(get_layout(&ctx, para).unwrap(), (0, 1, 4, 4));
+
+        ctx.nodes.get_mut(&label).unwrap().content = "Nickname:".into();
+        tree::mark_dirty(&mut ctx, label);
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(get_layout(&ctx, label).unwrap().2, 9);
+    }
+
     #[test]
     fn test_read_modify_write_preserves_properties() {
         let mut ctx = test_ctx();
@@ -466,6 +1154,134 @@ mod tests {
         assert_eq!((x, y, w, h), (0, 0, 20, 5));
     }
 
+    #[test]
+    fn test_reused_layout_matches_full_recompute() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let b = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let c = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        tree::append_child(&mut ctx, root, a).unwrap();
+        tree::append_child(&mut ctx, root, b).unwrap();
+        ctx.root = Some(root);
+        set_dimension(&mut ctx, c, 0, 4.0, 1).unwrap();
+        set_dimension(&mut ctx, c, 1, 2.0, 1).unwrap();
+
+        let layouts = |ctx: &TuiContext| -> Vec<(i32, i32, i32, i32)> {
+            [root, a, b, c]
+                .iter()
+                .map(|&h| get_layout(ctx, h).unwrap())
+                .collect()
+        };
+        type Step = Box<dyn Fn(&mut TuiContext) -> Result<(), TuiError>>;
+        let steps: Vec<Step> = vec![
+            Box::new(move |ctx| set_dimension(ctx, a, 0, 12.0, 1)),
+            Box::new(move |ctx| crate::style::set_color(ctx, a, 1, 0x01ff0000)),
+            Box::new(move |ctx| set_flex(ctx, root, 0, 1)),
+            Box::new(move |ctx| set_gap(ctx, root, 1.0, 2.0, 1)),
+            Box::new(move |ctx| set_edges(ctx, a, 0, 1.0, 2.0, 1.0, 2.0, 1)),
+            Box::new(move |ctx| tree::append_child(ctx, a, c)),
+            Box::new(move |ctx| {
+                ctx.nodes
+                    .get_mut(&b)
+                    .unwrap()
+                    .set_content("hello layout".to_string());
+                tree::mark_dirty(ctx, b);
+                Ok(())
+            }),
+            Box::new(move |ctx| crate::style::set_border(ctx, root, 1)),
+            Box::new(move |ctx| tree::remove_child(ctx, a, c)),
+            Box::new(move |ctx| set_display(ctx, a, 2)),
+        ];
+        compute_layout(&mut ctx).unwrap();
+        for step in steps {
+            tree::clear_dirty_flags(&mut ctx);
+            step(&mut ctx).unwrap();
+            compute_layout(&mut ctx).unwrap();
+            let reused = layouts(&ctx);
+            tree::mark_all_dirty(&mut ctx);
+            compute_layout(&mut ctx).unwrap();
+            assert_eq!(reused, layouts(&ctx));
+        }
+
+        // A paint-only change leaves the layout clean.
+        tree::clear_dirty_flags(&mut ctx);
+        crate::style::set_color(&mut ctx, root, 0, 0x0100ff00).unwrap();
+        assert!(!ctx.layout_dirty);
+
+        // So does recoloring a Text node: it is dirty, but measures the same.
+        compute_layout(&mut ctx).unwrap();
+        tree::clear_dirty_flags(&mut ctx);
+        crate::style::set_color(&mut ctx, b, 0, 0x01ff0000).unwrap();
+        assert!(ctx.nodes[&b].dirty);
+        flag_remeasured_text(&mut ctx);
+        assert!(!ctx.layout_dirty);
+        ctx.nodes
+            .get_mut(&b)
+            .unwrap()
+            .set_content("hello relayout".to_string());
+        flag_remeasured_text(&mut ctx);
+        assert!(ctx.layout_dirty);
+    }
+
+    #[test]
+    fn test_leaf_change_re_solves_only_its_ancestors() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        set_flex(&mut ctx, root, 0, 1).unwrap();
+        set_dimension(&mut ctx, root, 0, 100.0, 2).unwrap();
+        let mut rows = Vec::new();
+        for _ in 0..4 {
+            let row = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+            set_dimension(&mut ctx, row, 0, 50.0, 2).unwrap();
+            for _ in 0..5 {
+                let leaf = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+                set_dimension(&mut ctx, leaf, 0, 3.0, 1).unwrap();
+                set_dimension(&mut ctx, leaf, 1, 1.0, 1).unwrap();
+                tree::append_child(&mut ctx, row, leaf).unwrap();
+            }
+            tree::append_child(&mut ctx, root, row).unwrap();
+            rows.push(row);
+        }
+        ctx.root = Some(root);
+        compute_layout(&mut ctx).unwrap();
+
+        let layouts = |ctx: &TuiContext| -> Vec<(u32, (i32, i32, i32, i32))> {
+            let mut handles: Vec<u32> = ctx.nodes.keys().copied().collect();
+            handles.sort_unstable();
+            handles
+                .into_iter()
+                .map(|h| (h, get_layout(ctx, h).unwrap()))
+                .collect()
+        };
+        let taffy_dirty = |ctx: &TuiContext, h: u32| ctx.tree.dirty(ctx.nodes[&h].taffy_node);
+
+        let leaf = ctx.nodes[&rows[1]].children[2];
+        set_dimension(&mut ctx, leaf, 0, 7.0, 1).unwrap();
+        assert!(taffy_dirty(&ctx, rows[1]).unwrap());
+        assert!(!taffy_dirty(&ctx, rows[2]).unwrap());
+        compute_layout(&mut ctx).unwrap();
+        let incremental = layouts(&ctx);
+        assert_eq!(get_layout(&ctx, leaf).unwrap().2, 7);
+        tree::mark_all_dirty(&mut ctx);
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(incremental, layouts(&ctx));
+
+        // A resize re-solves against the new space from Taffy's caches.
+        ctx.backend
+            .as_any_mut()
+            .downcast_mut::<MockBackend>()
+            .unwrap()
+            .width = 40;
+        tree::mark_all_paint_dirty(&mut ctx);
+        compute_layout(&mut ctx).unwrap();
+        let resized = layouts(&ctx);
+        assert_eq!(get_layout(&ctx, rows[0]).unwrap().2, 20);
+        tree::mark_all_dirty(&mut ctx);
+        compute_layout(&mut ctx).unwrap();
+        assert_eq!(resized, layouts(&ctx));
+    }
+
     #[test]
     fn test_hit_test_accounts_for_render_offset() {
         let mut ctx = test_ctx();
@@ -17,13 +17,17 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 
 mod animation;
+mod color;
 mod context;
 pub mod devtools;
 mod edit_buffer;
 mod event;
 #[cfg(test)]
 mod golden;
+mod interner;
 mod layout;
+mod menu;
+mod node_store;
 mod render;
 mod scroll;
 mod splitpane;
@@ -47,17 +51,18 @@ mod tree;
 pub mod types;
 pub mod writer;
 
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::ffi::CString;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 
 use context::{
-    clear_last_error, context_read, context_write, destroy_context, get_last_error_snapshot,
-    init_context, is_context_initialized, set_last_error, TuiContext,
+    clear_last_error, context_read, context_write, destroy_context, get_last_error_code,
+    get_last_error_snapshot, init_context, is_context_initialized, set_last_error, TuiContext,
 };
 use terminal::{CrosstermBackend, TerminalBackend};
 use text_utils::{clamp_textarea_cursor_lines, grapheme_count, split_textarea_lines_owned};
-use types::{NodeType, TuiEvent};
+use types::{ErrorCode, NodeType, TuiError, TuiEvent, TuiTaggedEvent};
 
 fn refresh_terminal_capabilities(ctx: &mut TuiContext) {
     // Capability diagnostics include terminal pixel/cell geometry, so copy-out
@@ -69,10 +74,9 @@ fn refresh_terminal_capabilities(ctx: &mut TuiContext) {
 // function call without borrowing the context lock guard. We keep it in TLS so
 // each caller thread gets stable ownership of its latest snapshot.
 //
-// ADR-T16 keeps Kraken TUI on a single-threaded execution model. In a
-// multi-threaded host calling into FFI from different threads, errors set on
-// one thread are not guaranteed to be visible via `tui_get_last_error()` on
-// another thread.
+// Errors themselves are per thread too (ADR-T16): with thread affinity off
+// (`tui_set_thread_affinity`), `tui_get_last_error()` reports the calling
+// thread's last failure, never one set by a call on another thread.
 thread_local! {
     static LAST_ERROR_SNAPSHOT: RefCell<Option<CString>> = const { RefCell::new(None) };
 }
@@ -86,18 +90,18 @@ thread_local! {
 /// Success paths clear `last_error` so callers that disambiguate a returned
 /// `0` via `tui_get_last_error()` (notably substrate value-returning getters,
 /// per TechSpec §4.4) cannot observe a stale diagnostic from a prior call.
-fn ffi_wrap(f: impl FnOnce() -> Result<i32, String>) -> i32 {
+fn ffi_wrap(f: impl FnOnce() -> Result<i32, TuiError>) -> i32 {
     match catch_unwind(AssertUnwindSafe(f)) {
         Ok(Ok(code)) => {
             clear_last_error();
             code
         }
-        Ok(Err(msg)) => {
-            set_last_error(msg);
+        Ok(Err(error)) => {
+            set_last_error(error);
             -1
         }
         Err(_) => {
-            set_last_error("internal panic".to_string());
+            set_last_error(ErrorCode::Panic.raise("internal panic"));
             -2
         }
     }
@@ -108,18 +112,18 @@ fn ffi_wrap(f: impl FnOnce() -> Result<i32, String>) -> i32 {
 /// state and errors are surfaced through `tui_get_last_error()`. Success
 /// paths clear `last_error` so callers can disambiguate a real `0` from
 /// a stale failure.
-fn ffi_wrap_u64(f: impl FnOnce() -> Result<u64, String>) -> u64 {
+fn ffi_wrap_u64(f: impl FnOnce() -> Result<u64, TuiError>) -> u64 {
     match catch_unwind(AssertUnwindSafe(f)) {
         Ok(Ok(v)) => {
             clear_last_error();
             v
         }
-        Ok(Err(msg)) => {
-            set_last_error(msg);
+        Ok(Err(error)) => {
+            set_last_error(error);
             0
         }
         Err(_) => {
-            set_last_error("internal panic".to_string());
+            set_last_error(ErrorCode::Panic.raise("internal panic"));
             0
         }
     }
@@ -128,18 +132,38 @@ fn ffi_wrap_u64(f: impl FnOnce() -> Result<u64, String>) -> u64 {
 /// Wrap an FFI function that returns a small status-like `u8`.
 /// Returns 0 on error and surfaces the diagnostic through
 /// `tui_get_last_error()`.
-fn ffi_wrap_u8(f: impl FnOnce() -> Result<u8, String>) -> u8 {
+fn ffi_wrap_u8(f: impl FnOnce() -> Result<u8, TuiError>) -> u8 {
     match catch_unwind(AssertUnwindSafe(f)) {
         Ok(Ok(value)) => {
             clear_last_error();
             value
         }
-        Ok(Err(msg)) => {
-            set_last_error(msg);
+        Ok(Err(error)) => {
+            set_last_error(error);
             0
         }
         Err(_) => {
-            set_last_error("internal panic".to_string());
+            set_last_error(ErrorCode::Panic.raise("internal panic"));
+            0
+        }
+    }
+}
+
+/// Wrap an FFI function that returns a packed `u32` value (for example a
+/// color). Returns 0 on error; because 0 can also be a valid value, callers
+/// disambiguate through `tui_get_last_error()`, which success paths clear.
+fn ffi_wrap_u32(f: impl FnOnce() -> Result<u32, TuiError>) -> u32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            clear_last_error();
+            value
+        }
+        Ok(Err(error)) => {
+            set_last_error(error);
+            0
+        }
+        Err(_) => {
+            set_last_error(ErrorCode::Panic.raise("internal panic"));
             0
         }
     }
@@ -153,30 +177,31 @@ fn ffi_wrap_u8(f: impl FnOnce() -> Result<u8, String>) -> u8 {
 /// on wrapped numbers and quietly corrupt downstream state. Return the
 /// error as a normal `Err`; callers route it through `set_last_error`
 /// in the standard way.
-fn usize_to_u32_or_err(value: usize, label: &str) -> Result<u32, String> {
+fn usize_to_u32_or_err(value: usize, label: &str) -> Result<u32, TuiError> {
     u32::try_from(value).map_err(|_| {
         format!(
             "{label} ({value}) exceeds u32::MAX; substrate ABI cannot represent it. \
              Adopt a u64 ABI shape or trim content before crossing the 32-bit ceiling."
         )
+        .into()
     })
 }
 
 /// Wrap an FFI function that returns a u32 handle. Returns 0 on error.
 /// Success paths clear `last_error` so callers consulting it after a
 /// successful zero-sentinel return see a clean slate.
-fn ffi_wrap_handle(f: impl FnOnce() -> Result<u32, String>) -> u32 {
+fn ffi_wrap_handle(f: impl FnOnce() -> Result<u32, TuiError>) -> u32 {
     match catch_unwind(AssertUnwindSafe(f)) {
         Ok(Ok(handle)) => {
             clear_last_error();
             handle
         }
-        Ok(Err(msg)) => {
-            set_last_error(msg);
+        Ok(Err(error)) => {
+            set_last_error(error);
             0
         }
         Err(_) => {
-            set_last_error("internal panic".to_string());
+            set_last_error(ErrorCode::Panic.raise("internal panic"));
             0
         }
     }
@@ -193,15 +218,18 @@ fn clamp_textarea_cursor(node: &mut types::TuiNode) {
     }
 }
 
-fn textarea_content_snapshot(ctx: &TuiContext, node: &types::TuiNode) -> Result<String, String> {
+fn textarea_content_snapshot(ctx: &TuiContext, node: &types::TuiNode) -> Result<String, TuiError> {
     if let Some(buffer_handle) = node.text_buffer_handle {
         return ctx
             .text_buffers
             .get(&buffer_handle)
             .map(|buffer| buffer.content().to_string())
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"));
+            .ok_or_else(|| {
+                ErrorCode::InvalidHandle
+                    .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+            });
     }
-    Ok(node.content.clone())
+    Ok(node.content.to_string())
 }
 
 // ============================================================================
@@ -212,7 +240,8 @@ fn textarea_content_snapshot(ctx: &TuiContext, node: &types::TuiNode) -> Result<
 pub extern "C" fn tui_init() -> i32 {
     ffi_wrap(|| {
         if is_context_initialized()? {
-            return Err("Context already initialized. Call tui_shutdown() first.".to_string());
+            return Err(ErrorCode::AlreadyInitialized
+                .raise("Context already initialized. Call tui_shutdown() first."));
         }
 
         let mut backend = Box::new(CrosstermBackend::new());
@@ -228,7 +257,8 @@ pub extern "C" fn tui_init() -> i32 {
 pub extern "C" fn tui_init_headless(width: u16, height: u16) -> i32 {
     ffi_wrap(|| {
         if is_context_initialized()? {
-            return Err("Context already initialized. Call tui_shutdown() first.".to_string());
+            return Err(ErrorCode::AlreadyInitialized
+                .raise("Context already initialized. Call tui_shutdown() first."));
         }
 
         let backend = Box::new(terminal::HeadlessBackend::new(width, height));
@@ -237,6 +267,86 @@ pub extern "C" fn tui_init_headless(width: u16, height: u16) -> i32 {
     })
 }
 
+/// Callback init — creates the context with a backend that hands each frame's
+/// cell diff to `write_diff_cb` (and then calls `flush_cb`, if non-null)
+/// instead of writing to a terminal. `user_data` is passed through untouched.
+/// Size and input are driven with `tui_headless_resize` and the
+/// `tui_headless_inject_*` functions.
+#[no_mangle]
+pub extern "C" fn tui_init_with_callback(
+    width: u16,
+    height: u16,
+    write_diff_cb: Option<terminal::WriteDiffCallback>,
+    flush_cb: Option<terminal::FlushCallback>,
+    user_data: *mut std::ffi::c_void,
+) -> i32 {
+    ffi_wrap(|| {
+        let write_diff_cb = write_diff_cb
+            .ok_or_else(|| ErrorCode::InvalidArgument.raise("Null write_diff callback"))?;
+        if is_context_initialized()? {
+            return Err(ErrorCode::AlreadyInitialized
+                .raise("Context already initialized. Call tui_shutdown() first."));
+        }
+
+        let backend = Box::new(terminal::CallbackBackend::new(
+            width,
+            height,
+            write_diff_cb,
+            flush_cb,
+            user_data,
+        ));
+        init_context(backend)?;
+        Ok(0)
+    })
+}
+
+/// Simulate a terminal resize on the headless backend: updates the reported
+/// size and queues a `Resize` event for the next `tui_read_input`.
+/// Errors if the active backend is a real terminal.
+#[no_mangle]
+pub extern "C" fn tui_headless_resize(width: u16, height: u16) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.backend.simulate_resize(width, height)?;
+        Ok(0)
+    })
+}
+
+/// Queue a synthetic key event on the headless backend, delivered by the
+/// next `tui_read_input`. `codepoint` is the Unicode scalar for character
+/// keys (0 for none). Errors if the active backend is a real terminal.
+#[no_mangle]
+pub extern "C" fn tui_headless_inject_key(code: u32, modifiers: u32, codepoint: u32) -> i32 {
+    ffi_wrap(|| {
+        let character = char::from_u32(codepoint).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid codepoint: {codepoint}"))
+        })?;
+        let mut ctx = context_write()?;
+        ctx.backend.inject_event(types::TerminalInputEvent::Key {
+            code,
+            modifiers,
+            character,
+        })?;
+        Ok(0)
+    })
+}
+
+/// Queue a synthetic mouse event on the headless backend, delivered by the
+/// next `tui_read_input`. Errors if the active backend is a real terminal.
+#[no_mangle]
+pub extern "C" fn tui_headless_inject_mouse(x: u16, y: u16, button: u8, modifiers: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.backend.inject_event(types::TerminalInputEvent::Mouse {
+            x,
+            y,
+            button,
+            modifiers,
+        })?;
+        Ok(0)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn tui_shutdown() -> i32 {
     ffi_wrap(|| {
@@ -248,6 +358,21 @@ pub extern "C" fn tui_shutdown() -> i32 {
     })
 }
 
+/// Restrict context access to the thread that initialized it (nonzero, the
+/// default) or allow calls from any host thread (0), e.g. a thread pool.
+/// Calls are serialized through the context lock either way, and each
+/// thread sees only its own `tui_get_last_error()`. Must be called from the
+/// owner thread; `tui_shutdown` restores the default.
+#[no_mangle]
+pub extern "C" fn tui_set_thread_affinity(enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        // Fails from a non-owner thread while affinity is still on.
+        drop(context_read()?);
+        context::set_thread_affinity(enabled != 0);
+        Ok(0)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn tui_get_terminal_size(width: *mut i32, height: *mut i32) -> i32 {
     ffi_wrap(|| {
@@ -265,6 +390,9 @@ pub extern "C" fn tui_get_terminal_size(width: *mut i32, height: *mut i32) -> i3
     })
 }
 
+/// Low 32 capability bits as probed by the active backend (TERM, COLORTERM,
+/// TERM_PROGRAM, multiplexer). Before init this returns the legacy fixed mask;
+/// use `tui_terminal_get_capabilities_checked` to tell the two apart.
 #[no_mangle]
 pub extern "C" fn tui_get_capabilities() -> u32 {
     catch_unwind(AssertUnwindSafe(|| -> u32 {
@@ -287,6 +415,19 @@ pub extern "C" fn tui_get_capabilities() -> u32 {
     .unwrap_or_default()
 }
 
+/// Enable or disable mouse capture at runtime (0 = off, nonzero = on).
+/// While off the terminal's native text selection works, no mouse events
+/// are delivered, and the MOUSE capability bit reads as unset.
+#[no_mangle]
+pub extern "C" fn tui_set_mouse_capture(enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.backend.set_mouse_capture(enabled != 0)?;
+        refresh_terminal_capabilities(&mut ctx);
+        Ok(0)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn tui_terminal_get_capabilities() -> u64 {
     ffi_wrap_u64(|| {
@@ -300,7 +441,9 @@ pub extern "C" fn tui_terminal_get_capabilities() -> u64 {
 pub extern "C" fn tui_terminal_get_capabilities_checked(out_ptr: *mut u64) -> i32 {
     ffi_wrap(|| {
         if out_ptr.is_null() {
-            return Err("terminal capability output pointer is null".to_string());
+            return Err(
+                ErrorCode::InvalidArgument.raise("terminal capability output pointer is null")
+            );
         }
         let mut ctx = context_write()?;
         refresh_terminal_capabilities(&mut ctx);
@@ -317,18 +460,18 @@ pub extern "C" fn tui_terminal_get_capabilities_checked(out_ptr: *mut u64) -> i3
 pub extern "C" fn tui_terminal_get_info(out_ptr: *mut u8, out_len: u32) -> i32 {
     ffi_wrap(|| {
         if out_len > 0 && out_ptr.is_null() {
-            return Err("Null terminal info output pointer".to_string());
+            return Err(ErrorCode::InvalidArgument.raise("Null terminal info output pointer"));
         }
         let mut ctx = context_write()?;
         refresh_terminal_capabilities(&mut ctx);
         let json = ctx.terminal_capabilities.to_json()?;
         let bytes = json.as_bytes();
         if bytes.len() > out_len as usize {
-            return Err(format!(
+            return Err(ErrorCode::InvalidArgument.raise(format!(
                 "terminal info buffer too small: need {}, got {}",
                 bytes.len(),
                 out_len
-            ));
+            )));
         }
         if !bytes.is_empty() {
             unsafe {
@@ -358,8 +501,9 @@ pub extern "C" fn tui_terminal_clipboard_write(target: u8, ptr: *const u8, len:
 #[no_mangle]
 pub extern "C" fn tui_create_node(node_type: u8) -> u32 {
     ffi_wrap_handle(|| {
-        let nt = NodeType::from_u8(node_type)
-            .ok_or_else(|| format!("Invalid node type: {node_type}"))?;
+        let nt = NodeType::from_u8(node_type).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid node type: {node_type}"))
+        })?;
         let mut ctx = context_write()?;
         tree::create_node(&mut ctx, nt)
     })
@@ -386,6 +530,62 @@ pub extern "C" fn tui_destroy_subtree(handle: u32) -> i32 {
     })
 }
 
+/// Attach an opaque host identifier to a node. The core never interprets
+/// it; `tui_next_event_tagged` reports it with each drained event. 0 clears
+/// it.
+#[no_mangle]
+pub extern "C" fn tui_set_node_tag(handle: u32, tag: u64) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        ctx.nodes.get_mut(&handle).unwrap().tag = tag;
+        Ok(0)
+    })
+}
+
+/// Return a node's host tag (0 when untagged or on error).
+#[no_mangle]
+pub extern "C" fn tui_get_node_tag(handle: u32) -> u64 {
+    ffi_wrap_u64(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        Ok(ctx.nodes[&handle].tag)
+    })
+}
+
+/// Return the lowest-numbered node tagged `tag`, or 0 if none. This is a
+/// linear scan over every node; cache the result rather than calling it
+/// per frame.
+#[no_mangle]
+pub extern "C" fn tui_find_by_tag(tag: u64) -> u32 {
+    ffi_wrap_handle(|| {
+        let ctx = context_read()?;
+        Ok(tree::handles_with_tag(&ctx, tag)
+            .first()
+            .copied()
+            .unwrap_or(0))
+    })
+}
+
+/// Write up to `max` handles tagged `tag` to `out`, in ascending order.
+/// Returns the total number of matches, which may exceed `max`. Linear
+/// scan, like `tui_find_by_tag`.
+#[no_mangle]
+pub extern "C" fn tui_find_all_by_tag(tag: u64, out: *mut u32, max: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        let handles = tree::handles_with_tag(&ctx, tag);
+        if !out.is_null() {
+            for (i, &handle) in handles.iter().take(max as usize).enumerate() {
+                unsafe {
+                    *out.add(i) = handle;
+                }
+            }
+        }
+        Ok(handles.len() as i32)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn tui_get_node_type(handle: u32) -> i32 {
     ffi_wrap(|| {
@@ -409,27 +609,8 @@ pub extern "C" fn tui_set_visible(handle: u32, visible: u8) -> i32 {
         if !is_visible {
             crate::tree::clear_focus_if_under(&mut ctx, handle);
         }
-        let (taffy_node, should_display) = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            node.visible = is_visible;
-            node.dirty = true;
-            // For Overlay nodes, display requires BOTH visible AND open.
-            let overlay_open = node.overlay_state.as_ref().is_none_or(|ov| ov.open);
-            (node.taffy_node, is_visible && overlay_open)
-        };
-        let mut style = ctx
-            .tree
-            .style(taffy_node)
-            .map_err(|e| format!("Failed to read style: {e:?}"))?
-            .clone();
-        style.display = if should_display {
-            taffy::Display::Flex
-        } else {
-            taffy::Display::None
-        };
-        ctx.tree
-            .set_style(taffy_node, style)
-            .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        ctx.nodes.get_mut(&handle).unwrap().visible = is_visible;
+        layout::sync_display(&mut ctx, handle)?;
         if !is_visible && focused_under && ctx.focused.is_none() {
             event::refocus_after_loss(&mut ctx, old_focus);
         }
@@ -469,6 +650,18 @@ pub extern "C" fn tui_get_node_count() -> u32 {
     .unwrap_or_default()
 }
 
+/// Cap the number of live nodes. `0` (the default) means unlimited.
+/// Lowering the cap below the current count does not destroy nodes; it only
+/// blocks further creation until the count drops under the limit.
+#[no_mangle]
+pub extern "C" fn tui_set_max_nodes(max_nodes: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.max_nodes = max_nodes;
+        Ok(0)
+    })
+}
+
 // ============================================================================
 // 4.4 Tree Structure
 // ============================================================================
@@ -516,6 +709,18 @@ pub extern "C" fn tui_remove_child(parent: u32, child: u32) -> i32 {
     })
 }
 
+#[no_mangle]
+pub extern "C" fn tui_replace_child(parent: u32, old_child: u32, new_child: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(parent)?;
+        ctx.validate_handle(old_child)?;
+        ctx.validate_handle(new_child)?;
+        tree::replace_child(&mut ctx, parent, old_child, new_child)?;
+        Ok(0)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn tui_get_child_count(handle: u32) -> i32 {
     ffi_wrap(|| {
@@ -532,10 +737,9 @@ pub extern "C" fn tui_get_child_at(handle: u32, index: u32) -> u32 {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        node.children
-            .get(index as usize)
-            .copied()
-            .ok_or_else(|| format!("Index {index} out of bounds"))
+        node.children.get(index as usize).copied().ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Index {index} out of bounds"))
+        })
     })
 }
 
@@ -550,6 +754,74 @@ pub extern "C" fn tui_get_parent(handle: u32) -> u32 {
     .unwrap_or_default()
 }
 
+/// Return the current root handle, or 0 if no root is set.
+#[no_mangle]
+pub extern "C" fn tui_get_root() -> u32 {
+    ffi_wrap_handle(|| {
+        let ctx = context_read()?;
+        Ok(ctx.root.unwrap_or(0))
+    })
+}
+
+/// Write up to `max` live handles to `out`, sorted ascending, and return
+/// the total count (which may exceed `max`). Combine with `tui_get_parent`
+/// and `tui_get_child_at` to rebuild a host-side mirror of the tree.
+#[no_mangle]
+pub extern "C" fn tui_get_all_handles(out: *mut u32, max: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        let mut handles: Vec<u32> = ctx.nodes.keys().copied().collect();
+        handles.sort_unstable();
+        if !out.is_null() {
+            for (i, &handle) in handles.iter().take(max as usize).enumerate() {
+                unsafe {
+                    *out.add(i) = handle;
+                }
+            }
+        }
+        Ok(handles.len() as i32)
+    })
+}
+
+/// Visitor for `tui_walk_subtree`. Return nonzero to stop the walk.
+pub type WalkCallback =
+    extern "C" fn(handle: u32, depth: u32, user_data: *mut std::ffi::c_void) -> i32;
+
+/// Pre-order walk of the subtree rooted at `root`, calling `visit_cb` once
+/// per node with its depth below `root` (0 for `root` itself). No lock is
+/// held while the callback runs, so it may call back into the API and
+/// mutate the tree: each node's child list is snapshotted before its visit,
+/// and nodes destroyed before their turn are skipped. Returns 1 if the
+/// callback stopped the walk, 0 if it ran to completion.
+#[no_mangle]
+pub extern "C" fn tui_walk_subtree(
+    root: u32,
+    visit_cb: Option<WalkCallback>,
+    user_data: *mut std::ffi::c_void,
+) -> i32 {
+    ffi_wrap(|| {
+        let visit =
+            visit_cb.ok_or_else(|| ErrorCode::InvalidArgument.raise("Null visit callback"))?;
+        context_read()?.validate_handle(root)?;
+
+        let mut stack = vec![(root, 0u32)];
+        while let Some((handle, depth)) = stack.pop() {
+            let children = {
+                let ctx = context_read()?;
+                match ctx.nodes.get(&handle) {
+                    Some(node) => node.children.clone(),
+                    None => continue,
+                }
+            };
+            if visit(handle, depth, user_data) != 0 {
+                return Ok(1);
+            }
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        Ok(0)
+    })
+}
+
 // ============================================================================
 // 4.5 Content
 // ============================================================================
@@ -559,19 +831,27 @@ pub extern "C" fn tui_set_content(handle: u32, ptr: *const u8, len: u32) -> i32
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let was_at_bottom = textarea::view_at_bottom(&ctx, handle);
 
-        let text = if ptr.is_null() || len == 0 {
+        let mut text = if ptr.is_null() || len == 0 {
             String::new()
         } else {
             let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
             std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
                 .to_string()
         };
+        if sanitizes_content(&ctx, handle) {
+            let keep_newlines = !ctx.nodes[&handle].node_type.is_line_editor();
+            if let Cow::Owned(clean) = text_utils::strip_control_chars(&text, keep_newlines) {
+                text = clean;
+            }
+        }
 
         let (node_type, text_buffer_handle, edit_buffer_handle, content_clone) = {
+            let text = ctx.node_text(text);
             let node = ctx.nodes.get_mut(&handle).unwrap();
-            node.content = text;
+            node.set_content(text);
             let node_type = node.node_type;
             if node_type == NodeType::TextArea {
                 clamp_textarea_cursor(node);
@@ -580,7 +860,7 @@ pub extern "C" fn tui_set_content(handle: u32, ptr: *const u8, len: u32) -> i32
                     state.undo_stack.clear();
                     state.redo_stack.clear();
                 }
-            } else if node_type == NodeType::Input {
+            } else if node_type.is_line_editor() {
                 let len = grapheme_count(&node.content) as u32;
                 if node.cursor_position > len {
                     node.cursor_position = len;
@@ -599,7 +879,10 @@ pub extern "C" fn tui_set_content(handle: u32, ptr: *const u8, len: u32) -> i32
                 let existing_len = ctx
                     .text_buffers
                     .get(&buffer_handle)
-                    .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
+                    .ok_or_else(|| {
+                        ErrorCode::InvalidHandle
+                            .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+                    })?
                     .byte_len();
                 text_buffer::replace_range(
                     &mut ctx,
@@ -613,3253 +896,6953 @@ pub extern "C" fn tui_set_content(handle: u32, ptr: *const u8, len: u32) -> i32
                 }
             }
         }
+        textarea::enforce_max_lines(&mut ctx, handle, was_at_bottom)?;
         Ok(0)
     })
 }
 
+/// Append UTF-8 text to a node's content without resending what is already
+/// there. Sanitized like `tui_set_content`. Existing highlights and
+/// TextArea cursor/undo state stay valid and are kept.
 #[no_mangle]
-pub extern "C" fn tui_get_content_len(handle: u32) -> i32 {
+pub extern "C" fn tui_content_append(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        let content = if node.node_type == NodeType::TextArea {
-            textarea_content_snapshot(&ctx, node)?
-        } else {
-            node.content.clone()
-        };
-        Ok(content.len() as i32)
+        let was_at_bottom = textarea::view_at_bottom(&ctx, handle);
+        if ptr.is_null() || len == 0 {
+            return Ok(0);
+        }
+        let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+        let mut text = std::str::from_utf8(slice)
+            .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+            .to_string();
+        if sanitizes_content(&ctx, handle) {
+            let keep_newlines = !ctx.nodes[&handle].node_type.is_line_editor();
+            if let Cow::Owned(clean) = text_utils::strip_control_chars(&text, keep_newlines) {
+                text = clean;
+            }
+        }
+
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.content.to_mut().push_str(&text);
+        node.dirty = true;
+        if node.node_type == NodeType::TextArea {
+            if let Some(buffer_handle) = node.text_buffer_handle {
+                text_buffer::append(&mut ctx, buffer_handle, &text)?;
+            }
+        }
+        tree::mark_dirty(&mut ctx, handle);
+        textarea::enforce_max_lines(&mut ctx, handle, was_at_bottom)?;
+        Ok(0)
     })
 }
 
+/// Drop the oldest lines so at most `max_lines` remain. Returns how many
+/// lines were removed. Highlights shift with the text; a TextArea cursor
+/// moves up with its line, and its undo history and selection are cleared.
 #[no_mangle]
-pub extern "C" fn tui_get_content(handle: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
+pub extern "C" fn tui_content_truncate_lines(handle: u32, max_lines: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        let content_string = if node.node_type == NodeType::TextArea {
-            textarea_content_snapshot(&ctx, node)?
-        } else {
-            node.content.clone()
-        };
-        let content = content_string.as_bytes();
-        let copy_len = content.len().min(buffer_len as usize);
+        let follow_tail = textarea::view_at_bottom(&ctx, handle);
+        let dropped = textarea::truncate_content_lines(&mut ctx, handle, max_lines, follow_tail)?;
+        Ok(dropped as i32)
+    })
+}
 
-        if !buffer.is_null() && copy_len > 0 {
-            unsafe {
-                std::ptr::copy_nonoverlapping(content.as_ptr(), buffer, copy_len);
+/// Replace graphemes `start..end` of a node's content with `text`. The range
+/// is clamped to the current content and Input/ComboBox `max_len` limits the
+/// inserted text. Emits Change unless the edit is a no-op.
+fn replace_content_graphemes(
+    ctx: &mut TuiContext,
+    handle: u32,
+    start: u32,
+    end: u32,
+    text: &str,
+) -> Result<(), TuiError> {
+    ctx.validate_handle(handle)?;
+    let was_at_bottom = textarea::view_at_bottom(ctx, handle);
+    let node = ctx.nodes.get(&handle).unwrap();
+    let node_type = node.node_type;
+    let mut text = Cow::Borrowed(text);
+    if sanitizes_content(ctx, handle) {
+        if let Cow::Owned(clean) =
+            text_utils::strip_control_chars(&text, !node_type.is_line_editor())
+        {
+            text = Cow::Owned(clean);
+        }
+    }
+    let content = if node_type == NodeType::TextArea {
+        textarea_content_snapshot(ctx, node)?
+    } else {
+        node.content.to_string()
+    };
+
+    let count = grapheme_count(&content);
+    let start = (start as usize).min(count);
+    let end = (end as usize).clamp(start, count);
+    if node_type.is_line_editor() && node.max_length > 0 {
+        let room = (node.max_length as usize).saturating_sub(count - (end - start));
+        let cut = text_utils::grapheme_to_byte_idx(&text, room);
+        if cut < text.len() {
+            text = Cow::Owned(text[..cut].to_string());
+        }
+    }
+    if start == end && text.is_empty() {
+        return Ok(());
+    }
+    let byte_start = text_utils::grapheme_to_byte_idx(&content, start);
+    let byte_end = text_utils::grapheme_to_byte_idx(&content, end);
+    apply_content_edit(
+        ctx,
+        handle,
+        &content,
+        byte_start..byte_end,
+        &text,
+        was_at_bottom,
+    )?;
+    ctx.event_buffer.push(TuiEvent::change(handle, 0));
+    Ok(())
+}
+
+/// Splice `text` over `range` (grapheme-aligned bytes of `content`, the
+/// node's current content). Cursors and selections before the edit stay
+/// put, ones after it shift with it. TextArea edits go through the edit
+/// buffer so they are undoable.
+fn apply_content_edit(
+    ctx: &mut TuiContext,
+    handle: u32,
+    content: &str,
+    range: std::ops::Range<usize>,
+    text: &str,
+    was_at_bottom: bool,
+) -> Result<(), TuiError> {
+    let node_type = ctx.nodes[&handle].node_type;
+    let (byte_start, byte_end) = (range.start, range.end);
+    let mut new_content = content.to_string();
+    new_content.replace_range(range, text);
+
+    if node_type == NodeType::TextArea {
+        let (edit_handle, cursor_before, selection_before, history_limit) = {
+            let node = ctx.nodes.get(&handle).unwrap();
+            let state = node.textarea_state.as_ref().ok_or("No textarea state")?;
+            (
+                node.edit_buffer_handle,
+                (node.cursor_row, node.cursor_col),
+                (state.selection_anchor, state.selection_focus),
+                state.history_limit,
+            )
+        };
+        let mut cursor =
+            textarea::position_to_byte_offset(content, cursor_before.0, cursor_before.1);
+        let selection_before_edit = match selection_before {
+            (Some(anchor), Some(focus)) => {
+                textarea::position_to_byte_offset(content, anchor.0, anchor.1) <= byte_start
+                    && textarea::position_to_byte_offset(content, focus.0, focus.1) <= byte_start
             }
+            _ => false,
+        };
+        if cursor >= byte_end {
+            cursor = cursor - (byte_end - byte_start) + text.len();
+        } else if cursor > byte_start {
+            cursor = byte_start + text.len();
         }
-        // Null-terminate if space
-        if !buffer.is_null() && (buffer_len as usize) > copy_len {
-            unsafe {
-                *buffer.add(copy_len) = 0;
+        let (cursor_row, cursor_col) = textarea::byte_offset_to_position(&new_content, cursor);
+        if let Some(edit_handle) = edit_handle {
+            edit_buffer::break_coalescing(ctx, edit_handle)?;
+            edit_buffer::apply_replace(ctx, edit_handle, byte_start, byte_end, text)?;
+            let buffer_handle = edit_buffer::buffer_handle(ctx, edit_handle)?;
+            new_content = ctx
+                .text_buffers
+                .get(&buffer_handle)
+                .ok_or_else(|| {
+                    ErrorCode::InvalidHandle
+                        .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+                })?
+                .content()
+                .to_string();
+        }
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.set_content(new_content);
+        node.cursor_row = cursor_row;
+        node.cursor_col = cursor_col;
+        clamp_textarea_cursor(node);
+        if let Some(state) = node.textarea_state.as_mut() {
+            if !selection_before_edit {
+                state.clear_selection();
+            }
+            if edit_handle.is_some() {
+                textarea::record_edit(
+                    state,
+                    types::TextAreaEdit {
+                        cursor_row_before: cursor_before.0,
+                        cursor_col_before: cursor_before.1,
+                        selection_anchor_before: selection_before.0,
+                        selection_focus_before: selection_before.1,
+                        cursor_row_after: node.cursor_row,
+                        cursor_col_after: node.cursor_col,
+                        selection_anchor_after: state.selection_anchor,
+                        selection_focus_after: state.selection_focus,
+                    },
+                    false,
+                );
+            }
+        }
+        node.dirty = true;
+        if let Some(edit_handle) = edit_handle {
+            edit_buffer::trim_history(ctx, edit_handle, history_limit as usize)?;
+        }
+    } else {
+        let start = grapheme_count(&content[..byte_start]);
+        let end = start + grapheme_count(&content[byte_start..byte_end]);
+        let inserted = grapheme_count(text);
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.set_content(new_content);
+        if node_type.is_line_editor() {
+            let cursor = node.cursor_position as usize;
+            if cursor >= end {
+                node.cursor_position = (cursor - (end - start) + inserted) as u32;
+            } else if cursor > start {
+                node.cursor_position = (start + inserted) as u32;
             }
         }
+        if node_type == NodeType::ComboBox {
+            node.selected_index = None;
+            node.marquee = None;
+        }
+        node.dirty = true;
+    }
+    tree::mark_dirty(ctx, handle);
+    textarea::enforce_max_lines(ctx, handle, was_at_bottom)?;
+    Ok(())
+}
 
-        Ok(copy_len as i32)
+/// Insert UTF-8 text before grapheme `index` (clamped to the content end).
+#[no_mangle]
+pub extern "C" fn tui_content_insert_at(handle: u32, index: u32, ptr: *const u8, len: u32) -> i32 {
+    ffi_wrap(|| {
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
+        replace_content_graphemes(&mut ctx, handle, index, index, text)?;
+        Ok(0)
     })
 }
 
+/// Delete graphemes `start..end` (end-exclusive, clamped to the content).
 #[no_mangle]
-pub extern "C" fn tui_set_content_format(handle: u32, format: u8) -> i32 {
+pub extern "C" fn tui_content_delete_range(handle: u32, start: u32, end: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let fmt = types::ContentFormat::from_u8(format)
-            .ok_or_else(|| format!("Invalid content format: {format}"))?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.content_format = fmt;
-        node.dirty = true;
+        replace_content_graphemes(&mut ctx, handle, start, end, "")?;
         Ok(0)
     })
 }
 
+/// Replace graphemes `start..end` (end-exclusive, clamped to the content)
+/// with UTF-8 text.
 #[no_mangle]
-pub extern "C" fn tui_set_code_language(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_content_replace_range(
+    handle: u32,
+    start: u32,
+    end: u32,
+    ptr: *const u8,
+    len: u32,
+) -> i32 {
     ffi_wrap(|| {
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-
-        let lang = if ptr.is_null() || len == 0 {
-            None
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            Some(
-                std::str::from_utf8(slice)
-                    .map_err(|_| "Invalid UTF-8".to_string())?
-                    .to_string(),
-            )
-        };
-
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.code_language = lang;
-        node.dirty = true;
+        replace_content_graphemes(&mut ctx, handle, start, end, text)?;
         Ok(0)
     })
 }
 
+/// Set content like `tui_set_content`, but only splice the part that differs
+/// from the current content. The result is identical; cursors and
+/// selections before the change are preserved, and TextArea undo history is
+/// kept (the splice is recorded as one edit).
 #[no_mangle]
-pub extern "C" fn tui_get_code_language(handle: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
+pub extern "C" fn tui_set_content_diffed(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let was_at_bottom = textarea::view_at_bottom(&ctx, handle);
         let node = ctx.nodes.get(&handle).unwrap();
-
-        match &node.code_language {
-            Some(lang) => {
-                let bytes = lang.as_bytes();
-                let copy_len = bytes.len().min(buffer_len as usize);
-                if !buffer.is_null() && copy_len > 0 {
-                    unsafe {
-                        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-                    }
-                }
-                if !buffer.is_null() && (buffer_len as usize) > copy_len {
-                    unsafe {
-                        *buffer.add(copy_len) = 0;
-                    }
-                }
-                Ok(copy_len as i32)
+        let mut text = Cow::Borrowed(text);
+        if sanitizes_content(&ctx, handle) {
+            let keep_newlines = !node.node_type.is_line_editor();
+            if let Cow::Owned(clean) = text_utils::strip_control_chars(&text, keep_newlines) {
+                text = Cow::Owned(clean);
             }
-            None => Ok(0),
         }
+        let content = if node.node_type == NodeType::TextArea {
+            textarea_content_snapshot(&ctx, node)?
+        } else {
+            node.content.to_string()
+        };
+        let (start, old_end, new_end) = text_utils::grapheme_diff(&content, &text);
+        if start == old_end && start == new_end {
+            return Ok(0);
+        }
+        apply_content_edit(
+            &mut ctx,
+            handle,
+            &content,
+            start..old_end,
+            &text[start..new_end],
+            was_at_bottom,
+        )?;
+        Ok(0)
     })
 }
 
-// ============================================================================
-// 4.6 Widget Properties (Input/Select/TextArea)
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_input_set_cursor(handle: u32, position: u32) -> i32 {
+pub extern "C" fn tui_get_content_len(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Input {
-            return Err(format!("Handle {handle} is not an Input widget"));
-        }
-        node.cursor_position = position.min(grapheme_count(&node.content) as u32);
-        node.dirty = true;
-        Ok(0)
+        let node = ctx.nodes.get(&handle).unwrap();
+        let content = if node.node_type == NodeType::TextArea {
+            textarea_content_snapshot(&ctx, node)?
+        } else {
+            node.content.to_string()
+        };
+        Ok(content.len() as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_input_get_cursor(handle: u32) -> i32 {
+pub extern "C" fn tui_get_content(handle: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Input {
-            return Err(format!("Handle {handle} is not an Input widget"));
+        let content_string = if node.node_type == NodeType::TextArea {
+            textarea_content_snapshot(&ctx, node)?
+        } else {
+            node.content.to_string()
+        };
+        let content = content_string.as_bytes();
+        let copy_len = content.len().min(buffer_len as usize);
+
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(content.as_ptr(), buffer, copy_len);
+            }
         }
-        Ok(node.cursor_position as i32)
+        // Null-terminate if space
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
+            }
+        }
+
+        Ok(copy_len as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_input_set_max_len(handle: u32, max_len: u32) -> i32 {
+pub extern "C" fn tui_set_content_format(handle: u32, format: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let fmt = types::ContentFormat::from_u8(format).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid content format: {format}"))
+        })?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Input {
-            return Err(format!("Handle {handle} is not an Input widget"));
-        }
-        node.max_length = max_len;
+        node.content_format = fmt;
+        node.dirty = true;
         Ok(0)
     })
 }
 
+/// Set the base text direction: 0 = auto (per line, from the first strong
+/// character), 1 = left-to-right, 2 = right-to-left. RTL lines start at the
+/// right edge of the content area. Applies to Input and plain-format Text.
+/// A Text with any RTL line draws all of its lines with the simple
+/// renderer, which keeps search highlights but breaks long lines at any
+/// character rather than between words, LTR lines included.
 #[no_mangle]
-pub extern "C" fn tui_input_set_mask(handle: u32, mask_char: u32) -> i32 {
+pub extern "C" fn tui_set_text_direction(handle: u32, direction: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let direction = types::TextDirection::from_u8(direction).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid text direction: {direction}"))
+        })?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Input {
-            return Err(format!("Handle {handle} is not an Input widget"));
-        }
-        node.mask_char = mask_char;
+        node.text_direction = direction;
         node.dirty = true;
         Ok(0)
     })
 }
 
+/// Draw `extra_rows` blank rows between consecutive rows of a Text or
+/// TextArea (wrapped rows included). 0 (the default) packs rows tightly.
 #[no_mangle]
-pub extern "C" fn tui_input_get_mask(handle: u32) -> i32 {
+pub extern "C" fn tui_set_line_spacing(handle: u32, extra_rows: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Input {
-            return Err(format!("Handle {handle} is not an Input widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if !matches!(node.node_type, NodeType::Text | NodeType::TextArea) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Text or TextArea widget")));
         }
-        Ok(node.mask_char as i32)
+        node.line_spacing = extra_rows;
+        crate::tree::mark_dirty(&mut ctx, handle);
+        Ok(0)
     })
 }
 
+/// Insert `cells` blank columns after each grapheme of a plain Text or an
+/// Input, without changing the content. 0 (the default) packs graphemes.
 #[no_mangle]
-pub extern "C" fn tui_select_add_option(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_set_letter_spacing(handle: u32, cells: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-
-        let text = if ptr.is_null() || len == 0 {
-            String::new()
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
-                .to_string()
-        };
-
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
+        if !matches!(node.node_type, NodeType::Text | NodeType::Input) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Text or Input widget")));
         }
-        node.options.push(text);
-        node.dirty = true;
+        node.letter_spacing = cells;
+        crate::tree::mark_dirty(&mut ctx, handle);
         Ok(0)
     })
 }
 
+/// Draw a plain Text's rows with a DEC line attribute: 0 = normal,
+/// 1 = double width, 2 = double-height top half, 3 = double-height bottom
+/// half. Layout gives each grapheme two columns; terminals without line
+/// attributes show the text at normal size.
 #[no_mangle]
-pub extern "C" fn tui_select_remove_option(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_set_line_scale(handle: u32, scale: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let line_scale = types::LineScale::from_u8(scale).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid line scale: {scale}"))
+        })?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
-        }
-        if (index as usize) >= node.options.len() {
-            return Err(format!("Option index {index} out of bounds"));
+        if node.node_type != NodeType::Text {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Text widget"))
+            );
         }
-        node.options.remove(index as usize);
-        // Adjust selected index
-        if let Some(sel) = node.selected_index {
-            if sel == index {
-                node.selected_index = None;
-            } else if sel > index {
-                node.selected_index = Some(sel - 1);
-            }
+        node.line_scale = line_scale;
+        crate::tree::mark_dirty(&mut ctx, handle);
+        Ok(0)
+    })
+}
+
+/// Monospace grid for a plain Text or Input: every grapheme advances exactly
+/// `cells` columns (`enabled` 1 is the usual one-cell grid; 0 restores
+/// natural widths). Graphemes wider than that are drawn as an ellipsis, so
+/// table columns line up even with CJK or emoji.
+#[no_mangle]
+pub extern "C" fn tui_set_monospace_grid(handle: u32, cells: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if !matches!(node.node_type, NodeType::Text | NodeType::Input) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Text or Input widget")));
         }
-        node.dirty = true;
+        node.fixed_advance = cells;
+        crate::tree::mark_dirty(&mut ctx, handle);
         Ok(0)
     })
 }
 
+/// Case transform applied when drawing a plain Text or Input: 0 = none,
+/// 1 = upper, 2 = lower, 3 = title. Stored content is unchanged.
 #[no_mangle]
-pub extern "C" fn tui_select_clear_options(handle: u32) -> i32 {
+pub extern "C" fn tui_set_text_transform(handle: u32, mode: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let transform = types::TextTransform::from_u8(mode).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid text transform: {mode}"))
+        })?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
+        if !matches!(node.node_type, NodeType::Text | NodeType::Input) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Text or Input widget")));
         }
-        node.options.clear();
-        node.selected_index = None;
-        node.dirty = true;
+        node.text_transform = transform;
+        crate::tree::mark_dirty(&mut ctx, handle);
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_select_get_count(handle: u32) -> i32 {
+pub extern "C" fn tui_get_text_direction(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
-        }
-        Ok(node.options.len() as i32)
+        Ok(ctx.nodes[&handle].text_direction as i32)
     })
 }
 
+/// Find a literal substring in a Text or TextArea node's content.
+/// Writes up to `max` grapheme ranges to `out_matches` and returns the total
+/// number of matches, which may exceed `max`. `case_insensitive`: 0 or 1.
 #[no_mangle]
-pub extern "C" fn tui_select_get_option(
+pub extern "C" fn tui_text_find(
     handle: u32,
-    index: u32,
-    buffer: *mut u8,
-    buffer_len: u32,
+    pattern_ptr: *const u8,
+    pattern_len: u32,
+    case_insensitive: u8,
+    out_matches: *mut types::TuiMatchRange,
+    max: u32,
 ) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
-        }
-        let opt = node
-            .options
-            .get(index as usize)
-            .ok_or_else(|| format!("Option index {index} out of bounds"))?;
-        let bytes = opt.as_bytes();
-        let copy_len = bytes.len().min(buffer_len as usize);
-        if !buffer.is_null() && copy_len > 0 {
-            unsafe {
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+        let content = match node.node_type {
+            NodeType::Text => node.content.to_string(),
+            NodeType::TextArea => textarea_content_snapshot(&ctx, node)?,
+            _ => {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a Text or TextArea widget")))
             }
-        }
-        if !buffer.is_null() && (buffer_len as usize) > copy_len {
-            unsafe {
-                *buffer.add(copy_len) = 0;
+        };
+        let pattern = unsafe { read_utf8_payload(pattern_ptr, pattern_len)? };
+
+        let matches = textarea::find_all(&content, pattern, case_insensitive == 0)?;
+        if !out_matches.is_null() {
+            for (i, &(start, end)) in matches.iter().take(max as usize).enumerate() {
+                unsafe {
+                    *out_matches.add(i) = types::TuiMatchRange {
+                        start: start as u32,
+                        end: end as u32,
+                    };
+                }
             }
         }
-        Ok(copy_len as i32)
+        Ok(matches.len() as i32)
     })
 }
 
+/// Replace the search highlights on a Text or TextArea node. Ranges are
+/// grapheme offsets into the current content; `count == 0` clears them.
+/// Highlights are dropped automatically when the content changes.
 #[no_mangle]
-pub extern "C" fn tui_select_set_selected(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_text_set_highlights(
+    handle: u32,
+    ranges_ptr: *const types::TuiMatchRange,
+    count: u32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let ranges = if ranges_ptr.is_null() || count == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(ranges_ptr, count as usize) }
+        };
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
-        }
-        if (index as usize) >= node.options.len() {
-            return Err(format!("Option index {index} out of bounds"));
-        }
-        node.selected_index = Some(index);
+        if !matches!(node.node_type, NodeType::Text | NodeType::TextArea) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Text or TextArea widget")));
+        }
+        if let Some(bad) = ranges.iter().find(|r| r.start > r.end) {
+            return Err(ErrorCode::InvalidArgument.raise(format!(
+                "Invalid highlight range: start {} > end {}",
+                bad.start, bad.end
+            )));
+        }
+        node.text_highlights = ranges
+            .iter()
+            .map(|r| (r.start as usize, r.end as usize))
+            .collect();
         node.dirty = true;
         Ok(0)
     })
 }
 
+/// Draw host-provided cells into a node, e.g. half-block image art on a
+/// splash screen. Each cell lands at `origin + (x, y)` relative to the
+/// node's top-left, replacing any earlier blit at that position; cells
+/// outside the node's rect are clipped at render time. Surrogates and
+/// control characters are rejected and nothing from the call is drawn.
 #[no_mangle]
-pub extern "C" fn tui_select_get_selected(handle: u32) -> i32 {
+pub extern "C" fn tui_blit_cells(
+    handle: u32,
+    cells_ptr: *const types::TuiCellInit,
+    count: u32,
+    origin_x: u16,
+    origin_y: u16,
+) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Select {
-            return Err(format!("Handle {handle} is not a Select widget"));
+        let cells = if cells_ptr.is_null() || count == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(cells_ptr, count as usize) }
+        };
+        let mut art = Vec::with_capacity(cells.len());
+        for init in cells {
+            // Control characters would reach the terminal as escapes.
+            let ch = char::from_u32(init.codepoint)
+                .filter(|ch| !ch.is_control())
+                .ok_or_else(|| {
+                    ErrorCode::InvalidArgument
+                        .raise(format!("Invalid codepoint: {}", init.codepoint))
+                })?;
+            let pos = (
+                origin_x.saturating_add(init.x),
+                origin_y.saturating_add(init.y),
+            );
+            let cell = types::Cell {
+                ch,
+                fg: init.fg,
+                bg: init.bg,
+                attrs: types::CellAttrs::from_bits_truncate(init.attrs),
+                link: None,
+            };
+            art.push((pos, cell));
         }
-        Ok(node.selected_index.map(|i| i as i32).unwrap_or(-1))
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.cell_art.extend(art);
+        node.dirty = true;
+        Ok(0)
     })
 }
 
-// ============================================================================
-// Table Widget FFI (ADR-T27)
-// ============================================================================
-
+/// Remove every cell drawn into a node by `tui_blit_cells`.
 #[no_mangle]
-pub extern "C" fn tui_table_set_column_count(handle: u32, count: u32) -> i32 {
+pub extern "C" fn tui_clear_cells(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_mut().unwrap();
-        table.columns.resize(
-            count as usize,
-            crate::types::TableColumn {
-                label: String::new(),
-                width_value: 1,
-                width_unit: 2, // flex
-            },
-        );
-        // Truncate row cells if columns shrunk
-        for row in &mut table.rows {
-            row.resize(count as usize, String::new());
-        }
+        node.cell_art.clear();
         node.dirty = true;
         Ok(0)
     })
 }
 
+/// Set the escape bytes (sixel, kitty graphics or iTerm2) an Image node
+/// writes at its rect when the terminal reports INLINE_IMAGES. The bytes
+/// are passed through unchanged, so the host sizes the image to the node.
+/// An empty payload always shows the placeholder with the node's content
+/// as alt text.
 #[no_mangle]
-pub extern "C" fn tui_table_set_column(
-    handle: u32,
-    index: u32,
-    label_ptr: *const u8,
-    label_len: u32,
-    width_value: u16,
-    width_unit: u8,
-) -> i32 {
+pub extern "C" fn tui_image_set_payload(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let label = if label_ptr.is_null() || label_len == 0 {
-            String::new()
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(label_ptr, label_len as usize) };
-            std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
-                .to_string()
-        };
-
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let payload = if ptr.is_null() || len == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(ptr, len as usize) }.to_vec()
+        };
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
+        if node.node_type != NodeType::Image {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Image widget"))
+            );
         }
-        let table = node.table_state.as_mut().unwrap();
-        let col = table
-            .columns
-            .get_mut(index as usize)
-            .ok_or_else(|| format!("Column index {index} out of bounds"))?;
-        col.label = label;
-        col.width_value = width_value;
-        col.width_unit = width_unit;
+        node.image_payload = payload;
         node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_table_insert_row(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_set_code_language(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+
+        let lang = if ptr.is_null() || len == 0 {
+            None
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            Some(
+                std::str::from_utf8(slice)
+                    .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                    .to_string(),
+            )
+        };
+
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_mut().unwrap();
-        let col_count = table.columns.len();
-        let new_row = vec![String::new(); col_count];
-        let idx = (index as usize).min(table.rows.len());
-        table.rows.insert(idx, new_row);
+        node.code_language = lang;
         node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_table_remove_row(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_get_code_language(handle: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_mut().unwrap();
-        if (index as usize) >= table.rows.len() {
-            return Err(format!("Row index {index} out of bounds"));
-        }
-        table.rows.remove(index as usize);
-        // Adjust selected_row
-        if let Some(sel) = table.selected_row {
-            if sel == index {
-                table.selected_row = None;
-            } else if sel > index {
-                table.selected_row = Some(sel - 1);
+        let node = ctx.nodes.get(&handle).unwrap();
+
+        match &node.code_language {
+            Some(lang) => {
+                let bytes = lang.as_bytes();
+                let copy_len = bytes.len().min(buffer_len as usize);
+                if !buffer.is_null() && copy_len > 0 {
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+                    }
+                }
+                if !buffer.is_null() && (buffer_len as usize) > copy_len {
+                    unsafe {
+                        *buffer.add(copy_len) = 0;
+                    }
+                }
+                Ok(copy_len as i32)
             }
+            None => Ok(0),
         }
-        node.dirty = true;
+    })
+}
+
+/// Strip C0/C1 control characters from text passed to `tui_set_content`,
+/// widget labels and items, and transcript blocks (nonzero, the default) so
+/// untrusted content cannot inject terminal escapes. Tabs and, outside Input
+/// and ComboBox widgets and single-line labels, newlines are kept.
+#[no_mangle]
+pub extern "C" fn tui_set_sanitize_content(enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.sanitize_content = enabled != 0;
         Ok(0)
     })
 }
 
+/// Share storage between identical strings passed to `tui_set_content` and
+/// `tui_select_add_option` (nonzero), e.g. the labels repeated across a large
+/// table or Select. Costs a hash lookup per set; off by default. Text stored
+/// while it was on stays shared after it is turned off.
 #[no_mangle]
-pub extern "C" fn tui_table_clear_rows(handle: u32) -> i32 {
+pub extern "C" fn tui_set_string_interning(enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
+        if enabled == 0 {
+            ctx.string_interner = None;
+        } else if ctx.string_interner.is_none() {
+            ctx.string_interner = Some(interner::StringInterner::new());
         }
-        let table = node.table_state.as_mut().unwrap();
-        table.rows.clear();
-        table.selected_row = None;
-        node.dirty = true;
         Ok(0)
     })
 }
 
+/// Measure emoji-presentation sequences (e.g. `❤️`, `#️⃣`) as two cells
+/// (nonzero, the default) or by their base character (0) for terminals that
+/// draw them narrow. Applies process-wide; the whole tree is re-laid out.
 #[no_mangle]
-pub extern "C" fn tui_table_set_cell(
-    handle: u32,
-    row: u32,
-    col: u32,
-    ptr: *const u8,
-    len: u32,
-) -> i32 {
+pub extern "C" fn tui_set_wide_emoji(enabled: u8) -> i32 {
     ffi_wrap(|| {
-        let text = if ptr.is_null() || len == 0 {
-            String::new()
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
-                .to_string()
-        };
+        let mut ctx = context_write()?;
+        let enabled = enabled != 0;
+        if text_utils::wide_emoji_enabled() != enabled {
+            text_utils::set_wide_emoji(enabled);
+            tree::mark_all_dirty(&mut ctx);
+            render::invalidate(&mut ctx);
+        }
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// 4.6 Widget Properties (Input/Select/TextArea)
+// ============================================================================
 
+/// Empty an Input (or ComboBox line) in one call: content, cursor and
+/// selection are reset together. Emits Change if there was content.
+#[no_mangle]
+pub extern "C" fn tui_input_clear(handle: u32) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_mut().unwrap();
-        let row_data = table
-            .rows
-            .get_mut(row as usize)
-            .ok_or_else(|| format!("Row index {row} out of bounds"))?;
-        let cell = row_data
-            .get_mut(col as usize)
-            .ok_or_else(|| format!("Column index {col} out of bounds"))?;
-        *cell = text;
+        if !node.node_type.is_line_editor() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not an Input or ComboBox widget"
+            )));
+        }
+        let changed = !node.content.is_empty();
+        node.set_content(String::new());
+        node.cursor_position = 0;
+        node.input_selection_anchor = None;
         node.dirty = true;
+        if changed {
+            ctx.event_buffer.push(TuiEvent::change(handle, 0));
+        }
         Ok(0)
     })
 }
 
+/// Bind the key that submits an Input: `code` plus an exact `modifiers`
+/// mask (e.g. Enter + CTRL). Defaults to plain Enter; when Enter is not
+/// the binding it is consumed without effect.
 #[no_mangle]
-pub extern "C" fn tui_table_get_cell(
-    handle: u32,
-    row: u32,
-    col: u32,
-    buffer: *mut u8,
-    buffer_len: u32,
-) -> i32 {
+pub extern "C" fn tui_input_set_submit_key(handle: u32, code: u32, modifiers: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_ref().unwrap();
-        let row_data = table
-            .rows
-            .get(row as usize)
-            .ok_or_else(|| format!("Row index {row} out of bounds"))?;
-        let cell = row_data
-            .get(col as usize)
-            .ok_or_else(|| format!("Column index {col} out of bounds"))?;
-        let bytes = cell.as_bytes();
-        let copy_len = bytes.len().min(buffer_len as usize);
-        if !buffer.is_null() && copy_len > 0 {
-            unsafe {
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-            }
-        }
-        if !buffer.is_null() && (buffer_len as usize) > copy_len {
-            unsafe {
-                *buffer.add(copy_len) = 0;
-            }
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        Ok(copy_len as i32)
+        node.submit_key = (code, modifiers);
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_table_set_selected_row(handle: u32, row: i32) -> i32 {
+pub extern "C" fn tui_input_set_cursor(handle: u32, position: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
-        }
-        let table = node.table_state.as_mut().unwrap();
-        if row < 0 {
-            table.selected_row = None;
-        } else {
-            if (row as usize) >= table.rows.len() {
-                return Err(format!("Row index {row} out of bounds"));
-            }
-            table.selected_row = Some(row as u32);
+        if !node.node_type.is_line_editor() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not an Input or ComboBox widget"
+            )));
         }
+        node.cursor_position = position.min(grapheme_count(&node.content) as u32);
+        node.input_selection_anchor = None;
         node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_table_get_selected_row(handle: u32) -> i32 {
+pub extern "C" fn tui_input_get_cursor(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
+        if !node.node_type.is_line_editor() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not an Input or ComboBox widget"
+            )));
         }
-        let table = node.table_state.as_ref().unwrap();
-        Ok(table.selected_row.map(|i| i as i32).unwrap_or(-1))
+        Ok(node.cursor_position as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_table_set_header_visible(handle: u32, visible: u8) -> i32 {
+pub extern "C" fn tui_input_set_max_len(handle: u32, max_len: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Table {
-            return Err(format!("Handle {handle} is not a Table widget"));
+        if !node.node_type.is_line_editor() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not an Input or ComboBox widget"
+            )));
         }
-        let table = node.table_state.as_mut().unwrap();
-        table.header_visible = visible != 0;
-        node.dirty = true;
+        node.max_length = max_len;
         Ok(0)
     })
 }
 
-// ============================================================================
-// List Widget FFI (ADR-T27)
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_list_add_item(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_input_set_mask(handle: u32, mask_char: u32) -> i32 {
     ffi_wrap(|| {
-        let text = if ptr.is_null() || len == 0 {
-            String::new()
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
-                .to_string()
-        };
-
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        let list = node.list_state.as_mut().unwrap();
-        list.items.push(text);
+        node.mask_char = mask_char;
         node.dirty = true;
         Ok(0)
     })
 }
 
+/// Constrain an Input to a simple mask: `#` accepts a digit, `A` a letter,
+/// and any other character is a literal separator inserted automatically
+/// as the user types. Keys that do not fit the next slot are swallowed
+/// without a Change event. An empty pattern removes the constraint.
 #[no_mangle]
-pub extern "C" fn tui_list_remove_item(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_input_set_pattern(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
+        let pattern = unsafe { read_utf8_payload(ptr, len) }?;
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
-        }
-        let list = node.list_state.as_mut().unwrap();
-        if (index as usize) >= list.items.len() {
-            return Err(format!("Item index {index} out of bounds"));
-        }
-        list.items.remove(index as usize);
-        if let Some(sel) = list.selected {
-            if sel == index {
-                list.selected = None;
-            } else if sel > index {
-                list.selected = Some(sel - 1);
-            }
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        node.dirty = true;
+        node.input_pattern = (!pattern.is_empty()).then(|| pattern.to_string());
         Ok(0)
     })
 }
 
+/// 1 if the Input's content fills its pattern completely (or it has no
+/// pattern), 0 otherwise.
 #[no_mangle]
-pub extern "C" fn tui_list_clear_items(handle: u32) -> i32 {
+pub extern "C" fn tui_input_is_valid(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        let list = node.list_state.as_mut().unwrap();
-        list.items.clear();
-        list.selected = None;
-        node.dirty = true;
-        Ok(0)
+        let valid = node
+            .input_pattern
+            .as_deref()
+            .is_none_or(|pattern| text_utils::matches_input_pattern(&node.content, pattern, true));
+        Ok(i32::from(valid))
     })
 }
 
+/// Put an Input in numeric mode: typing is limited to digits, one decimal
+/// point and a leading sign, and Up/Down add or subtract `step`, clamped
+/// to `[min, max]`. Pass `step <= 0` to return to free text.
 #[no_mangle]
-pub extern "C" fn tui_list_get_count(handle: u32) -> i32 {
+pub extern "C" fn tui_input_set_numeric(handle: u32, min: f64, max: f64, step: f64) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        let list = node.list_state.as_ref().unwrap();
-        Ok(list.items.len() as i32)
+        if step <= 0.0 {
+            node.numeric = None;
+            return Ok(0);
+        }
+        if !(min.is_finite() && max.is_finite() && step.is_finite()) || min > max {
+            return Err(ErrorCode::InvalidArgument.raise(format!(
+                "Invalid numeric range: min {min}, max {max}, step {step}"
+            )));
+        }
+        node.numeric = Some(types::NumericInput { min, max, step });
+        Ok(0)
     })
 }
 
+/// Parse an Input's content as a number into `out`. Errors when the
+/// content is not a number (for example empty or a lone sign).
 #[no_mangle]
-pub extern "C" fn tui_list_get_item(
-    handle: u32,
-    index: u32,
-    buffer: *mut u8,
-    buffer_len: u32,
-) -> i32 {
+pub extern "C" fn tui_input_get_numeric(handle: u32, out: *mut f64) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
-        }
-        let list = node.list_state.as_ref().unwrap();
-        let item = list
-            .items
-            .get(index as usize)
-            .ok_or_else(|| format!("Item index {index} out of bounds"))?;
-        let bytes = item.as_bytes();
-        let copy_len = bytes.len().min(buffer_len as usize);
-        if !buffer.is_null() && copy_len > 0 {
-            unsafe {
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-            }
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+        let value = node
+            .content
+            .parse::<f64>()
+            .map_err(|_| format!("Input {handle} content is not a number"))?;
+        if !out.is_null() {
             unsafe {
-                *buffer.add(copy_len) = 0;
+                *out = value;
             }
         }
-        Ok(copy_len as i32)
+        Ok(0)
     })
 }
 
+/// Temporarily show a masked Input's real content (a password "reveal"
+/// toggle) without forgetting its mask character. Emits no Change event.
 #[no_mangle]
-pub extern "C" fn tui_list_set_selected(handle: u32, index: i32) -> i32 {
+pub extern "C" fn tui_input_set_mask_revealed(handle: u32, revealed: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
-        }
-        let list = node.list_state.as_mut().unwrap();
-        if index < 0 {
-            list.selected = None;
-        } else {
-            if (index as usize) >= list.items.len() {
-                return Err(format!("Item index {index} out of bounds"));
-            }
-            list.selected = Some(index as u32);
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        node.dirty = true;
+        node.mask_revealed = revealed != 0;
+        crate::tree::mark_dirty(&mut ctx, handle);
         Ok(0)
     })
 }
 
+/// Show an IME composition (pre-edit) string underlined at the Input
+/// cursor without touching its content. An empty string clears it, as does
+/// any change to the content (the commit). Emits no Change event.
 #[no_mangle]
-pub extern "C" fn tui_list_get_selected(handle: u32) -> i32 {
+pub extern "C" fn tui_input_set_composition(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::List {
-            return Err(format!("Handle {handle} is not a List widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        let list = node.list_state.as_ref().unwrap();
-        Ok(list.selected.map(|i| i as i32).unwrap_or(-1))
+        node.composition = (!text.is_empty()).then(|| text.to_string());
+        crate::tree::mark_dirty(&mut ctx, handle);
+        Ok(0)
     })
 }
 
-// ============================================================================
-// Tabs Widget FFI (ADR-T27)
-// ============================================================================
-
+/// Drop a pending IME composition without committing it.
 #[no_mangle]
-pub extern "C" fn tui_tabs_add_tab(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_input_clear_composition(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let text = if ptr.is_null() || len == 0 {
-            String::new()
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(slice)
-                .map_err(|_| "Invalid UTF-8".to_string())?
-                .to_string()
-        };
-
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        let tabs = node.tabs_state.as_mut().unwrap();
-        tabs.labels.push(text);
-        node.dirty = true;
+        node.composition = None;
+        crate::tree::mark_dirty(&mut ctx, handle);
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_tabs_remove_tab(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_input_get_mask(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
-        }
-        let tabs = node.tabs_state.as_mut().unwrap();
-        if (index as usize) >= tabs.labels.len() {
-            return Err(format!("Tab index {index} out of bounds"));
-        }
-        tabs.labels.remove(index as usize);
-        // Adjust active_index
-        if tabs.labels.is_empty() {
-            tabs.active_index = 0;
-        } else if tabs.active_index >= tabs.labels.len() as u32 {
-            tabs.active_index = tabs.labels.len() as u32 - 1;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Input {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Input widget"))
+            );
         }
-        node.dirty = true;
-        Ok(0)
+        Ok(node.mask_char as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_tabs_clear_tabs(handle: u32) -> i32 {
+pub extern "C" fn tui_select_add_option(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
+
+        let text = if ptr.is_null() || len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
+
+        if !ctx.nodes[&handle].node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let tabs = node.tabs_state.as_mut().unwrap();
-        tabs.labels.clear();
-        tabs.active_index = 0;
+        let text = sanitize_label(&ctx, handle, text);
+        let text = ctx.node_text(text);
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.options.push(text);
         node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_tabs_get_count(handle: u32) -> i32 {
+pub extern "C" fn tui_select_remove_option(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let tabs = node.tabs_state.as_ref().unwrap();
-        Ok(tabs.labels.len() as i32)
+        if (index as usize) >= node.options.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Option index {index} out of bounds"))
+            );
+        }
+        node.options.remove(index as usize);
+        // Adjust selected index
+        if let Some(sel) = node.selected_index {
+            if sel == index {
+                node.selected_index = None;
+            } else if sel > index {
+                node.selected_index = Some(sel - 1);
+            }
+        }
+        node.dirty = true;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_tabs_set_active(handle: u32, index: u32) -> i32 {
+pub extern "C" fn tui_select_clear_options(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
-        }
-        let tabs = node.tabs_state.as_mut().unwrap();
-        if (index as usize) >= tabs.labels.len() {
-            return Err(format!("Tab index {index} out of bounds"));
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        tabs.active_index = index;
+        node.options.clear();
+        node.selected_index = None;
         node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_tabs_get_active(handle: u32) -> i32 {
+pub extern "C" fn tui_select_get_count(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Tabs {
-            return Err(format!("Handle {handle} is not a Tabs widget"));
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let tabs = node.tabs_state.as_ref().unwrap();
-        Ok(tabs.active_index as i32)
+        Ok(node.options.len() as i32)
     })
 }
 
-// ============================================================================
-// Overlay Widget FFI (ADR-T27)
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_overlay_set_open(handle: u32, open: u8) -> i32 {
+pub extern "C" fn tui_select_get_option(
+    handle: u32,
+    index: u32,
+    buffer: *mut u8,
+    buffer_len: u32,
+) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let is_open = open != 0;
-        let previous_focus = ctx.focused;
-        let was_open = {
-            let node = ctx
-                .nodes
-                .get(&handle)
-                .ok_or_else(|| format!("Handle {handle} not found"))?;
-            if node.node_type != NodeType::Overlay {
-                return Err(format!("Handle {handle} is not an Overlay widget"));
-            }
-            node.overlay_state
-                .as_ref()
-                .is_some_and(|overlay| overlay.open)
-        };
-        {
-            let node = ctx
-                .nodes
-                .get_mut(&handle)
-                .ok_or_else(|| format!("Handle {handle} not found"))?;
-            let overlay = node.overlay_state.as_mut().unwrap();
-            if is_open && !was_open {
-                overlay.restore_focus = previous_focus;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
+        }
+        let opt = node.options.get(index as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Option index {index} out of bounds"))
+        })?;
+        let bytes = opt.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
             }
         }
-        let restore_focus = if !is_open {
-            let restore = ctx
-                .nodes
-                .get_mut(&handle)
-                .and_then(|node| node.overlay_state.as_mut())
-                .and_then(|overlay| overlay.restore_focus.take());
-            crate::tree::clear_focus_if_under(&mut ctx, handle);
-            restore
-        } else {
-            None
-        };
-        let (taffy_node, should_display) = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            let overlay = node.overlay_state.as_mut().unwrap();
-            overlay.open = is_open;
-            node.dirty = true;
-            // Display requires BOTH visible AND open.
-            (node.taffy_node, node.visible && is_open)
-        };
-        let mut style = ctx
-            .tree
-            .style(taffy_node)
-            .map_err(|e| format!("Failed to read style: {e:?}"))?
-            .clone();
-        style.display = if should_display {
-            taffy::Display::Flex
-        } else {
-            taffy::Display::None
-        };
-        ctx.tree
-            .set_style(taffy_node, style)
-            .map_err(|e| format!("Failed to set style: {e:?}"))?;
-        if ctx.focused.is_none() {
-            if let Some(restore_handle) = restore_focus {
-                event::restore_focus_handle(&mut ctx, restore_handle);
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
             }
         }
-        Ok(0)
-    })
-}
-
-#[no_mangle]
-pub extern "C" fn tui_overlay_get_open(handle: u32) -> i32 {
-    ffi_wrap(|| {
-        let ctx = context_read()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
-        }
-        let overlay = node.overlay_state.as_ref().unwrap();
-        Ok(if overlay.open { 1 } else { 0 })
+        Ok(copy_len as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_overlay_set_modal(handle: u32, modal: u8) -> i32 {
+pub extern "C" fn tui_select_set_selected(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let overlay = node.overlay_state.as_mut().unwrap();
-        overlay.modal = modal != 0;
+        if (index as usize) >= node.options.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Option index {index} out of bounds"))
+            );
+        }
+        node.selected_index = Some(index);
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_overlay_get_modal(handle: u32) -> i32 {
+pub extern "C" fn tui_select_get_selected(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let overlay = node.overlay_state.as_ref().unwrap();
-        Ok(if overlay.modal { 1 } else { 0 })
+        Ok(node.selected_index.map(|i| i as i32).unwrap_or(-1))
     })
 }
 
+/// How options wider than the Select are shown: 0 = clip, 1 = ellipsis,
+/// 2 = marquee (the selected option scrolls; others clip).
 #[no_mangle]
-pub extern "C" fn tui_overlay_set_clear_under(handle: u32, clear_under: u8) -> i32 {
+pub extern "C" fn tui_select_set_option_overflow(handle: u32, mode: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let overflow = types::OptionOverflow::from_u8(mode).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid option overflow mode: {mode}"))
+        })?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
+        if !node.node_type.has_options() {
+            return Err(ErrorCode::WrongNodeType.raise(format!(
+                "Handle {handle} is not a Select or ComboBox widget"
+            )));
         }
-        let overlay = node.overlay_state.as_mut().unwrap();
-        overlay.clear_under = clear_under != 0;
+        node.option_overflow = overflow;
+        node.marquee = None;
+        node.dirty = true;
         Ok(0)
     })
 }
 
+/// Filter a Select's visible options by a case-insensitive pattern (`*` and
+/// `?` wildcards, otherwise substring). Empty clears the filter. A selection
+/// hidden by the filter moves to the first match; indices stay full-list.
 #[no_mangle]
-pub extern "C" fn tui_overlay_get_clear_under(handle: u32) -> i32 {
+pub extern "C" fn tui_select_set_filter(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
+
+        let filter = if ptr.is_null() || len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
+
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Select {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Select widget"))
+            );
         }
-        let overlay = node.overlay_state.as_ref().unwrap();
-        Ok(if overlay.clear_under { 1 } else { 0 })
+        node.select_filter = filter;
+        let visible = node.visible_option_indices();
+        if let Some(&first) = visible.first() {
+            if node
+                .selected_index
+                .is_none_or(|sel| !visible.contains(&sel))
+            {
+                node.selected_index = Some(first);
+            }
+        }
+        node.dirty = true;
+        Ok(0)
     })
 }
 
+// ============================================================================
+// Popup Menu FFI
+// ============================================================================
+
+/// Create a closed popup menu. Returns its handle (0 on error). The menu is
+/// a detached node: style it like any widget, destroy it with
+/// `tui_destroy_node`.
 #[no_mangle]
-pub extern "C" fn tui_overlay_set_dismiss_on_escape(handle: u32, dismiss: u8) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_menu_create() -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
-        }
-        let overlay = node.overlay_state.as_mut().unwrap();
-        overlay.dismiss_on_escape = dismiss != 0;
-        Ok(0)
+        menu::create(&mut ctx)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_overlay_get_dismiss_on_escape(handle: u32) -> i32 {
+pub extern "C" fn tui_menu_add_item(menu: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::Overlay {
-            return Err(format!("Handle {handle} is not an Overlay widget"));
-        }
-        let overlay = node.overlay_state.as_ref().unwrap();
-        Ok(if overlay.dismiss_on_escape { 1 } else { 0 })
+        let mut ctx = context_write()?;
+        ctx.validate_handle(menu)?;
+        let label = if ptr.is_null() || len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
+        let label = sanitize_label(&ctx, menu, label);
+        menu::add_item(&mut ctx, menu, label)?;
+        Ok(0)
     })
 }
 
+/// Open a menu at screen cell (x, y), nudged to stay on screen. While open
+/// it captures keys and clicks; choosing an item emits `MenuSelect` with the
+/// item index, and Escape or an outside click closes it.
 #[no_mangle]
-pub extern "C" fn tui_textarea_set_cursor(handle: u32, row: u32, col: u32) -> i32 {
+pub extern "C" fn tui_menu_show_at(menu: u32, x: i32, y: i32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let content = {
-            let node = ctx.nodes.get(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            textarea_content_snapshot(&ctx, node)?
-        };
-        let edit_handle = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            node.cursor_row = row;
-            node.cursor_col = col;
-            let lines = split_textarea_lines_owned(&content);
-            clamp_textarea_cursor_lines(&lines, &mut node.cursor_row, &mut node.cursor_col);
-            if node.textarea_view_row > node.cursor_row {
-                node.textarea_view_row = node.cursor_row;
-            }
-            if node.textarea_view_col > node.cursor_col {
-                node.textarea_view_col = node.cursor_col;
-            }
-            // Clear stale selection when cursor is moved programmatically (ADR-T28)
-            if let Some(state) = node.textarea_state.as_mut() {
-                state.clear_selection();
-            }
-            node.dirty = true;
-            node.edit_buffer_handle
-        };
-        if let Some(edit_handle) = edit_handle {
-            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
-        }
+        ctx.validate_handle(menu)?;
+        menu::show_at(&mut ctx, menu, x, y)?;
         Ok(0)
     })
 }
 
+/// Close the open popup menu, if any. No event is emitted.
 #[no_mangle]
-pub extern "C" fn tui_textarea_get_cursor(handle: u32, row: *mut u32, col: *mut u32) -> i32 {
+pub extern "C" fn tui_menu_close() -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
-        }
-
-        unsafe {
-            if !row.is_null() {
-                *row = node.cursor_row;
-            }
-            if !col.is_null() {
-                *col = node.cursor_col;
-            }
-        }
+        let mut ctx = context_write()?;
+        menu::close(&mut ctx);
         Ok(0)
     })
 }
 
+// ============================================================================
+// Table Widget FFI (ADR-T27)
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_textarea_get_line_count(handle: u32) -> i32 {
+pub extern "C" fn tui_table_set_column_count(handle: u32, count: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
-        let content = textarea_content_snapshot(&ctx, node)?;
-        Ok(split_textarea_lines_owned(&content).len() as i32)
+        let table = node.table_state.as_mut().unwrap();
+        table.columns.resize(
+            count as usize,
+            crate::types::TableColumn {
+                label: String::new(),
+                width_value: 1,
+                width_unit: 2, // flex
+            },
+        );
+        // Truncate row cells if columns shrunk
+        for row in &mut table.rows {
+            row.resize(count as usize, String::new());
+        }
+        node.dirty = true;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_set_wrap(handle: u32, wrap_mode: u8) -> i32 {
+pub extern "C" fn tui_table_set_column(
+    handle: u32,
+    index: u32,
+    label_ptr: *const u8,
+    label_len: u32,
+    width_value: u16,
+    width_unit: u8,
+) -> i32 {
     ffi_wrap(|| {
-        if wrap_mode > 1 {
-            return Err(format!("Invalid wrap mode: {wrap_mode}"));
-        }
+        let label = if label_ptr.is_null() || label_len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(label_ptr, label_len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
 
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        let label = sanitize_label(&ctx, handle, label);
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
+        let table = node.table_state.as_mut().unwrap();
+        let col = table.columns.get_mut(index as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Column index {index} out of bounds"))
+        })?;
+        col.label = label;
+        col.width_value = width_value;
+        col.width_unit = width_unit;
+        node.dirty = true;
+        Ok(0)
+    })
+}
 
-        node.wrap_mode = wrap_mode;
-        if wrap_mode != 0 {
-            node.textarea_view_col = 0;
-        }
-        // Clear selection on wrap mode change (ADR-T28)
-        if let Some(state) = node.textarea_state.as_mut() {
-            state.clear_selection();
+#[no_mangle]
+pub extern "C" fn tui_table_insert_row(handle: u32, index: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
+        let table = node.table_state.as_mut().unwrap();
+        let col_count = table.columns.len();
+        let new_row = vec![String::new(); col_count];
+        let idx = (index as usize).min(table.rows.len());
+        table.rows.insert(idx, new_row);
         node.dirty = true;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.6.2 TextArea Editor Extensions (ADR-T28)
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_textarea_set_selection(
-    handle: u32,
-    s_row: u32,
-    s_col: u32,
-    e_row: u32,
-    e_col: u32,
-) -> i32 {
+pub extern "C" fn tui_table_remove_row(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let content = {
-            let node = ctx.nodes.get(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
+        }
+        let table = node.table_state.as_mut().unwrap();
+        if (index as usize) >= table.rows.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Row index {index} out of bounds"))
+            );
+        }
+        table.rows.remove(index as usize);
+        // Adjust selected_row
+        if let Some(sel) = table.selected_row {
+            if sel == index {
+                table.selected_row = None;
+            } else if sel > index {
+                table.selected_row = Some(sel - 1);
             }
-            textarea_content_snapshot(&ctx, node)?
-        };
-        let edit_handle = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            let state = node
-                .textarea_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-
-            // Clamp to content bounds
-            let lines = split_textarea_lines_owned(&content);
-            let mut ar = s_row;
-            let mut ac = s_col;
-            let mut fr = e_row;
-            let mut fc = e_col;
-            clamp_textarea_cursor_lines(&lines, &mut ar, &mut ac);
-            clamp_textarea_cursor_lines(&lines, &mut fr, &mut fc);
-
-            state.selection_anchor = Some((ar, ac));
-            state.selection_focus = Some((fr, fc));
-            node.dirty = true;
-            node.edit_buffer_handle
-        };
-        if let Some(edit_handle) = edit_handle {
-            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
         }
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_clear_selection(handle: u32) -> i32 {
+pub extern "C" fn tui_table_clear_rows(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let edit_handle = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            node.textarea_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no textarea state"))?
-                .clear_selection();
-            node.dirty = true;
-            node.edit_buffer_handle
-        };
-        if let Some(edit_handle) = edit_handle {
-            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
+        let table = node.table_state.as_mut().unwrap();
+        table.rows.clear();
+        table.selected_row = None;
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_get_selected_text_len(handle: u32) -> i32 {
+pub extern "C" fn tui_table_set_cell(
+    handle: u32,
+    row: u32,
+    col: u32,
+    ptr: *const u8,
+    len: u32,
+) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let text = if ptr.is_null() || len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
+
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
-        }
-        let state = node
-            .textarea_state
-            .as_ref()
-            .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-        match (state.selection_anchor, state.selection_focus) {
-            (Some(anchor), Some(focus)) => {
-                let content = textarea_content_snapshot(&ctx, node)?;
-                let text = textarea::get_selected_text(&content, anchor, focus);
-                Ok(text.len() as i32)
-            }
-            _ => Ok(0),
+        let text = sanitize_label(&ctx, handle, text);
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
+        let table = node.table_state.as_mut().unwrap();
+        let row_data = table.rows.get_mut(row as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Row index {row} out of bounds"))
+        })?;
+        let cell = row_data.get_mut(col as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Column index {col} out of bounds"))
+        })?;
+        *cell = text;
+        node.dirty = true;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_get_selected_text(
+pub extern "C" fn tui_table_get_cell(
     handle: u32,
+    row: u32,
+    col: u32,
     buffer: *mut u8,
     buffer_len: u32,
 ) -> i32 {
     ffi_wrap(|| {
-        if buffer.is_null() {
-            return Err("Null buffer pointer".to_string());
-        }
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
-        let state = node
-            .textarea_state
-            .as_ref()
-            .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-        match (state.selection_anchor, state.selection_focus) {
-            (Some(anchor), Some(focus)) => {
-                let content = textarea_content_snapshot(&ctx, node)?;
-                let text = textarea::get_selected_text(&content, anchor, focus);
-                let bytes = text.as_bytes();
-                let copy_len = bytes.len().min(buffer_len as usize);
-                unsafe {
-                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-                }
-                // Null-terminate if space (consistent with tui_get_content et al.)
-                if (buffer_len as usize) > copy_len {
-                    unsafe {
-                        *buffer.add(copy_len) = 0;
-                    }
-                }
-                Ok(copy_len as i32)
+        let table = node.table_state.as_ref().unwrap();
+        let row_data = table.rows.get(row as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Row index {row} out of bounds"))
+        })?;
+        let cell = row_data.get(col as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Column index {col} out of bounds"))
+        })?;
+        let bytes = cell.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+            }
+        }
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
             }
-            _ => Ok(0),
         }
+        Ok(copy_len as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_find_next(
-    handle: u32,
-    ptr: *const u8,
-    len: u32,
-    case_sensitive: u8,
-    regex: u8,
-) -> i32 {
+pub extern "C" fn tui_table_set_selected_row(handle: u32, row: i32) -> i32 {
     ffi_wrap(|| {
-        if ptr.is_null() {
-            return Err("Null pattern pointer".to_string());
-        }
-        let pattern = unsafe {
-            let slice = std::slice::from_raw_parts(ptr, len as usize);
-            std::str::from_utf8(slice)
-                .map_err(|_| "Pattern is not valid UTF-8".to_string())?
-                .to_string()
-        };
-
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let content = {
-            let node = ctx.nodes.get(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            textarea_content_snapshot(&ctx, node)?
-        };
         let node = ctx.nodes.get_mut(&handle).unwrap();
-
-        let result = textarea::find_next(
-            &content,
-            node.cursor_row,
-            node.cursor_col,
-            &pattern,
-            case_sensitive != 0,
-            regex != 0,
-        )?;
-
-        match result {
-            Some((row, col)) => {
-                // Set selection to highlight the match
-                let end = textarea::find_match_end(
-                    &content,
-                    row,
-                    col,
-                    &pattern,
-                    case_sensitive != 0,
-                    regex != 0,
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
+        }
+        let table = node.table_state.as_mut().unwrap();
+        if row < 0 {
+            table.selected_row = None;
+        } else {
+            if (row as usize) >= table.rows.len() {
+                return Err(
+                    ErrorCode::IndexOutOfBounds.raise(format!("Row index {row} out of bounds"))
                 );
-                let state = node
-                    .textarea_state
-                    .as_mut()
-                    .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-                state.selection_anchor = Some((row, col));
-                state.selection_focus = Some(end);
-
-                // Move cursor past match so next find_next advances.
-                // For zero-length matches (e.g. regex `^`), advance one grapheme
-                // to prevent infinite re-matching.
-                if end == (row, col) {
-                    let lines = split_textarea_lines_owned(&content);
-                    let line_len = lines
-                        .get(row as usize)
-                        .map(|l| grapheme_count(l) as u32)
-                        .unwrap_or(0);
-                    if col < line_len {
-                        node.cursor_row = row;
-                        node.cursor_col = col + 1;
-                    } else if (row as usize) + 1 < lines.len() {
-                        node.cursor_row = row + 1;
-                        node.cursor_col = 0;
-                    } else {
-                        // At very end of content — stay put
-                        node.cursor_row = row;
-                        node.cursor_col = col;
-                    }
-                } else {
-                    node.cursor_row = end.0;
-                    node.cursor_col = end.1;
-                }
-
-                let edit_handle = node.edit_buffer_handle;
-                node.dirty = true;
-                if let Some(edit_handle) = edit_handle {
-                    let _ = node;
-                    edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
-                }
-                Ok(1)
             }
-            None => Ok(0),
+            table.selected_row = Some(row as u32);
         }
+        node.dirty = true;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_undo(handle: u32) -> i32 {
+pub extern "C" fn tui_table_get_selected_row(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let (edit_handle, edit) = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            let Some(edit_handle) = node.edit_buffer_handle else {
-                return Ok(0);
-            };
-            let state = node.textarea_state.as_mut().ok_or("No textarea state")?;
-            let Some(edit) = textarea::peek_undo(state) else {
-                return Ok(0);
-            };
-            (edit_handle, edit)
-        };
-        let performed = edit_buffer::undo(&mut ctx, edit_handle)?;
-        if !performed {
-            return Ok(0);
-        }
-        let buffer_handle = edit_buffer::buffer_handle(&ctx, edit_handle)?;
-        let content = ctx
-            .text_buffers
-            .get(&buffer_handle)
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
-            .content()
-            .to_string();
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
-        }
-        node.content = content;
-        node.cursor_row = edit.cursor_row_before;
-        node.cursor_col = edit.cursor_col_before;
-        if let Some(state) = node.textarea_state.as_mut() {
-            // Pop metadata only after the native undo succeeded so a sync
-            // discrepancy cannot silently discard the caller-visible stack.
-            let _ = textarea::take_undo(state);
-            state.selection_anchor = edit.selection_anchor_before;
-            state.selection_focus = edit.selection_focus_before;
-            textarea::push_redo(state, edit);
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
-        clamp_textarea_cursor(node);
-        node.dirty = true;
-        Ok(1)
+        let table = node.table_state.as_ref().unwrap();
+        Ok(table.selected_row.map(|i| i as i32).unwrap_or(-1))
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_textarea_redo(handle: u32) -> i32 {
+pub extern "C" fn tui_table_set_header_visible(handle: u32, visible: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let (edit_handle, edit) = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            let Some(edit_handle) = node.edit_buffer_handle else {
-                return Ok(0);
-            };
-            let state = node.textarea_state.as_mut().ok_or("No textarea state")?;
-            let Some(edit) = textarea::peek_redo(state) else {
-                return Ok(0);
-            };
-            (edit_handle, edit)
-        };
-        let performed = edit_buffer::redo(&mut ctx, edit_handle)?;
-        if !performed {
-            return Ok(0);
-        }
-        let buffer_handle = edit_buffer::buffer_handle(&ctx, edit_handle)?;
-        let content = ctx
-            .text_buffers
-            .get(&buffer_handle)
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
-            .content()
-            .to_string();
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        if node.node_type != NodeType::TextArea {
-            return Err(format!("Handle {handle} is not a TextArea widget"));
-        }
-        node.content = content;
-        node.cursor_row = edit.cursor_row_after;
-        node.cursor_col = edit.cursor_col_after;
-        if let Some(state) = node.textarea_state.as_mut() {
-            // Pop metadata only after the native redo succeeded so a sync
-            // discrepancy cannot silently discard the caller-visible stack.
-            let _ = textarea::take_redo(state);
-            state.selection_anchor = edit.selection_anchor_after;
-            state.selection_focus = edit.selection_focus_after;
-            textarea::push_undo(state, edit);
+        if node.node_type != NodeType::Table {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Table widget"))
+            );
         }
-        clamp_textarea_cursor(node);
+        let table = node.table_state.as_mut().unwrap();
+        table.header_visible = visible != 0;
         node.dirty = true;
-        Ok(1)
-    })
-}
-
-/// Set the maximum number of undo entries. 0 = unlimited (no truncation).
-#[no_mangle]
-pub extern "C" fn tui_textarea_set_history_limit(handle: u32, limit: u32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let edit_handle = {
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            if node.node_type != NodeType::TextArea {
-                return Err(format!("Handle {handle} is not a TextArea widget"));
-            }
-            let state = node
-                .textarea_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-            state.history_limit = limit;
-            if limit > 0 {
-                while state.undo_stack.len() > limit as usize {
-                    state.undo_stack.pop_front();
-                }
-            }
-            node.edit_buffer_handle
-        };
-        if let Some(edit_handle) = edit_handle {
-            let native_history_len = edit_buffer::history_len(&ctx, edit_handle)?;
-            let needs_trim = limit > 0 && native_history_len > limit as usize;
-            if needs_trim && edit_buffer::can_redo(&ctx, edit_handle)? {
-                {
-                    let node = ctx.nodes.get_mut(&handle).unwrap();
-                    let state = node
-                        .textarea_state
-                        .as_mut()
-                        .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-                    // Once the redo branch exists, trimming from the front can
-                    // leave replay steps whose preconditions no longer match the
-                    // current content. Drop redo on both sides before trimming.
-                    state.redo_stack.clear();
-                }
-                edit_buffer::discard_redo(&mut ctx, edit_handle)?;
-            }
-            edit_buffer::trim_history(&mut ctx, edit_handle, limit as usize)?;
-            let native_history_len = edit_buffer::history_len(&ctx, edit_handle)?;
-            let node = ctx.nodes.get_mut(&handle).unwrap();
-            let state = node
-                .textarea_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no textarea state"))?;
-            while state.undo_stack.len() > native_history_len {
-                state.undo_stack.pop_front();
-            }
-        }
         Ok(0)
     })
 }
 
 // ============================================================================
-// 4.7 Layout Properties
+// List Widget FFI (ADR-T27)
 // ============================================================================
 
 #[no_mangle]
-pub extern "C" fn tui_set_layout_dimension(handle: u32, prop: u32, value: f32, unit: u8) -> i32 {
+pub extern "C" fn tui_list_add_item(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
+        let text = if ptr.is_null() || len == 0 {
+            String::new()
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
+        };
+
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        layout::set_dimension(&mut ctx, handle, prop, value, unit)?;
+        let text = sanitize_label(&ctx, handle, text);
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_mut().unwrap();
+        list.items.push(text);
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_layout_flex(handle: u32, prop: u32, value: u32) -> i32 {
+pub extern "C" fn tui_list_remove_item(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        layout::set_flex(&mut ctx, handle, prop, value)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_mut().unwrap();
+        if (index as usize) >= list.items.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Item index {index} out of bounds"))
+            );
+        }
+        list.items.remove(index as usize);
+        if let Some(sel) = list.selected {
+            if sel == index {
+                list.selected = None;
+            } else if sel > index {
+                list.selected = Some(sel - 1);
+            }
+        }
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_layout_flex_factor(handle: u32, prop: u32, value: f32) -> i32 {
+pub extern "C" fn tui_list_clear_items(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        layout::set_flex_factor(&mut ctx, handle, prop, value)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_mut().unwrap();
+        list.items.clear();
+        list.selected = None;
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_layout_edges(
-    handle: u32,
-    prop: u32,
-    top: f32,
-    right: f32,
-    bottom: f32,
-    left: f32,
+pub extern "C" fn tui_list_get_count(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_ref().unwrap();
+        Ok(list.items.len() as i32)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_list_get_item(
+    handle: u32,
+    index: u32,
+    buffer: *mut u8,
+    buffer_len: u32,
 ) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        layout::set_edges(&mut ctx, handle, prop, top, right, bottom, left)?;
-        Ok(0)
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_ref().unwrap();
+        let item = list.items.get(index as usize).ok_or_else(|| {
+            ErrorCode::IndexOutOfBounds.raise(format!("Item index {index} out of bounds"))
+        })?;
+        let bytes = item.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+            }
+        }
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
+            }
+        }
+        Ok(copy_len as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_layout_gap(handle: u32, row_gap: f32, column_gap: f32) -> i32 {
+pub extern "C" fn tui_list_set_selected(handle: u32, index: i32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        layout::set_gap(&mut ctx, handle, row_gap, column_gap)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
+        }
+        let list = node.list_state.as_mut().unwrap();
+        if index < 0 {
+            list.selected = None;
+        } else {
+            if (index as usize) >= list.items.len() {
+                return Err(
+                    ErrorCode::IndexOutOfBounds.raise(format!("Item index {index} out of bounds"))
+                );
+            }
+            list.selected = Some(index as u32);
+        }
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_get_layout(
-    handle: u32,
-    x: *mut i32,
-    y: *mut i32,
-    w: *mut i32,
-    h: *mut i32,
-) -> i32 {
+pub extern "C" fn tui_list_get_selected(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let (lx, ly, lw, lh) = layout::get_layout(&ctx, handle)?;
-        unsafe {
-            if !x.is_null() {
-                *x = lx;
-            }
-            if !y.is_null() {
-                *y = ly;
-            }
-            if !w.is_null() {
-                *w = lw;
-            }
-            if !h.is_null() {
-                *h = lh;
-            }
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::List {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a List widget"))
+            );
         }
-        Ok(0)
+        let list = node.list_state.as_ref().unwrap();
+        Ok(list.selected.map(|i| i as i32).unwrap_or(-1))
     })
 }
 
+// ============================================================================
+// Tabs Widget FFI (ADR-T27)
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_measure_text(ptr: *const u8, len: u32, width: *mut u32) -> i32 {
+pub extern "C" fn tui_tabs_add_tab(handle: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
         let text = if ptr.is_null() || len == 0 {
-            ""
+            String::new()
         } else {
             let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(slice).map_err(|_| "Invalid UTF-8".to_string())?
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                .to_string()
         };
 
-        let measured = text::measure_text(text);
-        if !width.is_null() {
-            unsafe {
-                *width = measured;
-            }
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let text = sanitize_label(&ctx, handle, text);
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
         }
+        let tabs = node.tabs_state.as_mut().unwrap();
+        tabs.labels.push(text);
+        node.dirty = true;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.8 Visual Style Properties
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_set_style_color(handle: u32, prop: u32, color: u32) -> i32 {
+pub extern "C" fn tui_tabs_remove_tab(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        style::set_color(&mut ctx, handle, prop, color)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
+        }
+        let tabs = node.tabs_state.as_mut().unwrap();
+        if (index as usize) >= tabs.labels.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Tab index {index} out of bounds"))
+            );
+        }
+        tabs.labels.remove(index as usize);
+        // Adjust active_index
+        if tabs.labels.is_empty() {
+            tabs.active_index = 0;
+        } else if tabs.active_index >= tabs.labels.len() as u32 {
+            tabs.active_index = tabs.labels.len() as u32 - 1;
+        }
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_style_flag(handle: u32, prop: u32, value: u8) -> i32 {
+pub extern "C" fn tui_tabs_clear_tabs(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        style::set_flag(&mut ctx, handle, prop, value)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
+        }
+        let tabs = node.tabs_state.as_mut().unwrap();
+        tabs.labels.clear();
+        tabs.active_index = 0;
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_style_border(handle: u32, border_style: u8) -> i32 {
+pub extern "C" fn tui_tabs_get_count(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        style::set_border(&mut ctx, handle, border_style)?;
-        Ok(0)
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
+        }
+        let tabs = node.tabs_state.as_ref().unwrap();
+        Ok(tabs.labels.len() as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_style_opacity(handle: u32, opacity: f32) -> i32 {
+pub extern "C" fn tui_tabs_set_active(handle: u32, index: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        style::set_opacity(&mut ctx, handle, opacity)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
+        }
+        let tabs = node.tabs_state.as_mut().unwrap();
+        if (index as usize) >= tabs.labels.len() {
+            return Err(
+                ErrorCode::IndexOutOfBounds.raise(format!("Tab index {index} out of bounds"))
+            );
+        }
+        tabs.active_index = index;
+        node.dirty = true;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.15 Theme Management
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_create_theme() -> u32 {
-    ffi_wrap_handle(|| {
-        let mut ctx = context_write()?;
-        theme::create_theme(&mut ctx)
+pub extern "C" fn tui_tabs_get_active(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Tabs {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Tabs widget"))
+            );
+        }
+        let tabs = node.tabs_state.as_ref().unwrap();
+        Ok(tabs.active_index as i32)
     })
 }
 
+// ============================================================================
+// Overlay Widget FFI (ADR-T27)
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_destroy_theme(theme_handle: u32) -> i32 {
+pub extern "C" fn tui_overlay_set_open(handle: u32, open: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        theme::destroy_theme(&mut ctx, theme_handle)?;
+        ctx.validate_handle(handle)?;
+        let is_open = open != 0;
+        let previous_focus = ctx.focused;
+        let was_open = {
+            let node = ctx.nodes.get(&handle).ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Handle {handle} not found"))
+            })?;
+            if node.node_type != NodeType::Overlay {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not an Overlay widget")));
+            }
+            node.overlay_state
+                .as_ref()
+                .is_some_and(|overlay| overlay.open)
+        };
+        {
+            let node = ctx.nodes.get_mut(&handle).ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Handle {handle} not found"))
+            })?;
+            let overlay = node.overlay_state.as_mut().unwrap();
+            if is_open && !was_open {
+                overlay.restore_focus = previous_focus;
+            }
+        }
+        let restore_focus = if !is_open {
+            let restore = ctx
+                .nodes
+                .get_mut(&handle)
+                .and_then(|node| node.overlay_state.as_mut())
+                .and_then(|overlay| overlay.restore_focus.take());
+            crate::tree::clear_focus_if_under(&mut ctx, handle);
+            restore
+        } else {
+            None
+        };
+        {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            node.overlay_state.as_mut().unwrap().open = is_open;
+        }
+        layout::sync_display(&mut ctx, handle)?;
+        if ctx.focused.is_none() {
+            if let Some(restore_handle) = restore_focus {
+                event::restore_focus_handle(&mut ctx, restore_handle);
+            }
+        }
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_color(theme_handle: u32, prop: u8, color: u32) -> i32 {
+pub extern "C" fn tui_overlay_get_open(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::set_theme_color(&mut ctx, theme_handle, prop, color)?;
-        Ok(0)
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_ref().unwrap();
+        Ok(if overlay.open { 1 } else { 0 })
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_flag(theme_handle: u32, prop: u8, value: u8) -> i32 {
+pub extern "C" fn tui_overlay_set_modal(handle: u32, modal: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        theme::set_theme_flag(&mut ctx, theme_handle, prop, value)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_mut().unwrap();
+        overlay.modal = modal != 0;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_border(theme_handle: u32, border_style: u8) -> i32 {
+pub extern "C" fn tui_overlay_get_modal(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::set_theme_border(&mut ctx, theme_handle, border_style)?;
-        Ok(0)
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_ref().unwrap();
+        Ok(if overlay.modal { 1 } else { 0 })
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_opacity(theme_handle: u32, opacity: f32) -> i32 {
+pub extern "C" fn tui_overlay_set_clear_under(handle: u32, clear_under: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        theme::set_theme_opacity(&mut ctx, theme_handle, opacity)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_mut().unwrap();
+        overlay.clear_under = clear_under != 0;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_type_color(
-    theme_handle: u32,
-    node_type: u8,
-    prop: u8,
-    color: u32,
-) -> i32 {
+pub extern "C" fn tui_overlay_get_clear_under(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::set_theme_type_color(&mut ctx, theme_handle, node_type, prop, color)?;
-        Ok(0)
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_ref().unwrap();
+        Ok(if overlay.clear_under { 1 } else { 0 })
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_type_flag(
-    theme_handle: u32,
-    node_type: u8,
-    prop: u8,
-    value: u8,
-) -> i32 {
+pub extern "C" fn tui_overlay_set_dismiss_on_escape(handle: u32, dismiss: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        theme::set_theme_type_flag(&mut ctx, theme_handle, node_type, prop, value)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_mut().unwrap();
+        overlay.dismiss_on_escape = dismiss != 0;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_theme_type_border(
-    theme_handle: u32,
-    node_type: u8,
-    border_style: u8,
-) -> i32 {
+pub extern "C" fn tui_overlay_get_dismiss_on_escape(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::set_theme_type_border(&mut ctx, theme_handle, node_type, border_style)?;
-        Ok(0)
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::Overlay {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not an Overlay widget"))
+            );
+        }
+        let overlay = node.overlay_state.as_ref().unwrap();
+        Ok(if overlay.dismiss_on_escape { 1 } else { 0 })
     })
 }
 
+/// Current content of TextArea `handle` and the byte range of each logical
+/// line (newlines excluded).
+fn textarea_lines(
+    ctx: &TuiContext,
+    handle: u32,
+) -> Result<(String, Vec<std::ops::Range<usize>>), TuiError> {
+    ctx.validate_handle(handle)?;
+    let node = &ctx.nodes[&handle];
+    if node.node_type != NodeType::TextArea {
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+        );
+    }
+    let content = textarea_content_snapshot(ctx, node)?;
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for line in content.split('\n') {
+        ranges.push(start..start + line.len());
+        start += line.len() + 1;
+    }
+    Ok((content, ranges))
+}
+
+fn line_out_of_range(row: u32, lines: usize) -> TuiError {
+    ErrorCode::IndexOutOfBounds.raise(format!(
+        "Line {row} out of range (TextArea has {lines} lines)"
+    ))
+}
+
+/// Splice `text` over `range` of a TextArea's content as one undoable edit,
+/// shifting the cursor with it, and emit Change.
+fn splice_textarea(
+    ctx: &mut TuiContext,
+    handle: u32,
+    content: &str,
+    range: std::ops::Range<usize>,
+    text: &str,
+) -> Result<(), TuiError> {
+    let was_at_bottom = textarea::view_at_bottom(ctx, handle);
+    let mut text = Cow::Borrowed(text);
+    if ctx.sanitize_content {
+        if let Cow::Owned(clean) = text_utils::strip_control_chars(&text, true) {
+            text = Cow::Owned(clean);
+        }
+    }
+    if content[range.clone()] == *text {
+        return Ok(());
+    }
+    apply_content_edit(ctx, handle, content, range, &text, was_at_bottom)?;
+    ctx.event_buffer.push(TuiEvent::change(handle, 0));
+    Ok(())
+}
+
+/// Byte length of TextArea line `row`, for sizing `tui_textarea_get_line`.
 #[no_mangle]
-pub extern "C" fn tui_set_theme_type_opacity(
-    theme_handle: u32,
-    node_type: u8,
-    opacity: f32,
-) -> i32 {
+pub extern "C" fn tui_textarea_get_line_len(handle: u32, row: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::set_theme_type_opacity(&mut ctx, theme_handle, node_type, opacity)?;
-        Ok(0)
+        let ctx = context_read()?;
+        let (_, lines) = textarea_lines(&ctx, handle)?;
+        let range = lines
+            .get(row as usize)
+            .ok_or_else(|| line_out_of_range(row, lines.len()))?;
+        Ok(range.len() as i32)
     })
 }
 
+/// Copy logical line `row` of a TextArea (without its newline) into
+/// `buffer`. Returns the number of bytes copied, like `tui_get_content`.
 #[no_mangle]
-pub extern "C" fn tui_apply_theme(theme_handle: u32, node_handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_get_line(
+    handle: u32,
+    row: u32,
+    buffer: *mut u8,
+    buffer_len: u32,
+) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        theme::apply_theme(&mut ctx, theme_handle, node_handle)?;
-        Ok(0)
+        let ctx = context_read()?;
+        let (content, lines) = textarea_lines(&ctx, handle)?;
+        let range = lines
+            .get(row as usize)
+            .ok_or_else(|| line_out_of_range(row, lines.len()))?;
+        let line = content[range.clone()].as_bytes();
+        let copy_len = line.len().min(buffer_len as usize);
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(line.as_ptr(), buffer, copy_len);
+            }
+        }
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
+            }
+        }
+        Ok(copy_len as i32)
     })
 }
 
+/// Replace the content of TextArea line `row`. The edit is undoable and
+/// the cursor shifts with it; emits Change when the line differs.
 #[no_mangle]
-pub extern "C" fn tui_clear_theme(node_handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_set_line(handle: u32, row: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
         let mut ctx = context_write()?;
-        theme::clear_theme(&mut ctx, node_handle)?;
+        let (content, lines) = textarea_lines(&ctx, handle)?;
+        let range = lines
+            .get(row as usize)
+            .cloned()
+            .ok_or_else(|| line_out_of_range(row, lines.len()))?;
+        splice_textarea(&mut ctx, handle, &content, range, text)?;
         Ok(0)
     })
 }
 
+/// Insert a new line before line `row`; `row` equal to the line count
+/// appends after the last line.
 #[no_mangle]
-pub extern "C" fn tui_switch_theme(theme_handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_insert_line(handle: u32, row: u32, ptr: *const u8, len: u32) -> i32 {
     ffi_wrap(|| {
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
         let mut ctx = context_write()?;
-        theme::switch_theme(&mut ctx, theme_handle)?;
+        let (content, lines) = textarea_lines(&ctx, handle)?;
+        let row = row as usize;
+        let (at, text) = match lines.get(row) {
+            Some(range) => (range.start, format!("{text}\n")),
+            None if row == lines.len() => (content.len(), format!("\n{text}")),
+            None => return Err(line_out_of_range(row as u32, lines.len())),
+        };
+        splice_textarea(&mut ctx, handle, &content, at..at, &text)?;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.16 Animation (v1)
-// ============================================================================
-
+/// Delete line `row` together with its newline. Deleting the only line
+/// leaves the TextArea empty.
 #[no_mangle]
-pub extern "C" fn tui_animate(
-    handle: u32,
-    property: u8,
-    target_bits: u32,
-    duration_ms: u32,
-    easing: u8,
-) -> u32 {
-    ffi_wrap_handle(|| {
+pub extern "C" fn tui_textarea_delete_line(handle: u32, row: u32) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let prop = types::AnimProp::from_u8(property)
-            .ok_or_else(|| format!("Invalid animation property: {property}"))?;
-        let ease = types::Easing::from_u8(easing)
-            .ok_or_else(|| format!("Invalid easing function: {easing}"))?;
-        animation::start_animation(&mut ctx, handle, prop, target_bits, duration_ms, ease)
+        let (content, lines) = textarea_lines(&ctx, handle)?;
+        let r = row as usize;
+        let range = lines
+            .get(r)
+            .ok_or_else(|| line_out_of_range(row, lines.len()))?;
+        let range = if r + 1 < lines.len() {
+            range.start..lines[r + 1].start
+        } else if r > 0 {
+            lines[r - 1].end..range.end
+        } else {
+            range.clone()
+        };
+        splice_textarea(&mut ctx, handle, &content, range, "")?;
+        Ok(0)
     })
 }
 
+/// Empty a TextArea: content, cursor, viewport, selection and undo history
+/// are reset together. Emits Change if there was content.
 #[no_mangle]
-pub extern "C" fn tui_cancel_animation(anim_handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_clear(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        animation::cancel_animation(&mut ctx, anim_handle)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        let changed = !node.content.is_empty();
+        node.set_content(String::new());
+        node.cursor_row = 0;
+        node.cursor_col = 0;
+        node.textarea_view_row = 0;
+        node.textarea_view_col = 0;
+        if let Some(state) = node.textarea_state.as_mut() {
+            state.clear_selection();
+            state.undo_stack.clear();
+            state.redo_stack.clear();
+        }
+        node.dirty = true;
+        let (text_buffer_handle, edit_buffer_handle) =
+            (node.text_buffer_handle, node.edit_buffer_handle);
+        if let Some(buffer_handle) = text_buffer_handle {
+            let existing_len = ctx
+                .text_buffers
+                .get(&buffer_handle)
+                .ok_or_else(|| {
+                    ErrorCode::InvalidHandle
+                        .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+                })?
+                .byte_len();
+            text_buffer::replace_range(&mut ctx, buffer_handle, 0, existing_len, "")?;
+            if let Some(edit_handle) = edit_buffer_handle {
+                edit_buffer::clear_history(&mut ctx, edit_handle)?;
+            }
+        }
+        if changed {
+            ctx.event_buffer.push(TuiEvent::change(handle, 0));
+        }
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_start_spinner(handle: u32, interval_ms: u32) -> u32 {
-    ffi_wrap_handle(|| {
+pub extern "C" fn tui_textarea_set_cursor(handle: u32, row: u32, col: u32) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        animation::start_spinner(&mut ctx, handle, interval_ms)
+        let content = {
+            let node = ctx.nodes.get(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            textarea_content_snapshot(&ctx, node)?
+        };
+        let edit_handle = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            node.cursor_row = row;
+            node.cursor_col = col;
+            let lines = split_textarea_lines_owned(&content);
+            clamp_textarea_cursor_lines(&lines, &mut node.cursor_row, &mut node.cursor_col);
+            if node.textarea_view_row > node.cursor_row {
+                node.textarea_view_row = node.cursor_row;
+            }
+            if node.textarea_view_col > node.cursor_col {
+                node.textarea_view_col = node.cursor_col;
+            }
+            // Clear stale selection when cursor is moved programmatically (ADR-T28)
+            if let Some(state) = node.textarea_state.as_mut() {
+                state.clear_selection();
+            }
+            node.dirty = true;
+            node.edit_buffer_handle
+        };
+        if let Some(edit_handle) = edit_handle {
+            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
+        }
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_start_progress(handle: u32, duration_ms: u32, easing: u8) -> u32 {
-    ffi_wrap_handle(|| {
-        let mut ctx = context_write()?;
+pub extern "C" fn tui_textarea_get_cursor(handle: u32, row: *mut u32, col: *mut u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let ease = types::Easing::from_u8(easing)
-            .ok_or_else(|| format!("Invalid easing function: {easing}"))?;
-        animation::start_progress(&mut ctx, handle, duration_ms, ease)
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+
+        unsafe {
+            if !row.is_null() {
+                *row = node.cursor_row;
+            }
+            if !col.is_null() {
+                *col = node.cursor_col;
+            }
+        }
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_start_pulse(handle: u32, duration_ms: u32, easing: u8) -> u32 {
-    ffi_wrap_handle(|| {
-        let mut ctx = context_write()?;
+pub extern "C" fn tui_textarea_get_line_count(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let ease = types::Easing::from_u8(easing)
-            .ok_or_else(|| format!("Invalid easing function: {easing}"))?;
-        animation::start_pulse(&mut ctx, handle, duration_ms, ease)
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        let content = textarea_content_snapshot(&ctx, node)?;
+        Ok(split_textarea_lines_owned(&content).len() as i32)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_animation_looping(anim_id: u32) -> i32 {
+pub extern "C" fn tui_textarea_set_wrap(handle: u32, wrap_mode: u8) -> i32 {
     ffi_wrap(|| {
+        if wrap_mode > 1 {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid wrap mode: {wrap_mode}")));
+        }
+
         let mut ctx = context_write()?;
-        animation::set_animation_looping(&mut ctx, anim_id)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+
+        node.wrap_mode = wrap_mode;
+        if wrap_mode != 0 {
+            node.textarea_view_col = 0;
+        }
+        // Clear selection on wrap mode change (ADR-T28)
+        if let Some(state) = node.textarea_state.as_mut() {
+            state.clear_selection();
+        }
+        node.dirty = true;
         Ok(0)
     })
 }
 
+// ============================================================================
+// 4.6.2 TextArea Editor Extensions (ADR-T28)
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_chain_animation(after_anim: u32, next_anim: u32) -> i32 {
+pub extern "C" fn tui_textarea_set_selection(
+    handle: u32,
+    s_row: u32,
+    s_col: u32,
+    e_row: u32,
+    e_col: u32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        animation::chain_animation(&mut ctx, after_anim, next_anim)?;
-        Ok(0)
-    })
-}
-
-#[no_mangle]
-pub extern "C" fn tui_create_choreo_group() -> u32 {
-    ffi_wrap_handle(|| {
-        let mut ctx = context_write()?;
-        animation::create_choreography_group(&mut ctx)
-    })
-}
-
-#[no_mangle]
-pub extern "C" fn tui_choreo_add(group_id: u32, anim_id: u32, start_at_ms: u32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        animation::choreography_add(&mut ctx, group_id, anim_id, start_at_ms)?;
-        Ok(0)
-    })
-}
-
-#[no_mangle]
-pub extern "C" fn tui_choreo_start(group_id: u32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        animation::choreography_start(&mut ctx, group_id)?;
-        Ok(0)
-    })
-}
+        ctx.validate_handle(handle)?;
+        let content = {
+            let node = ctx.nodes.get(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            textarea_content_snapshot(&ctx, node)?
+        };
+        let edit_handle = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            let state = node.textarea_state.as_mut().ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+            })?;
 
-#[no_mangle]
-pub extern "C" fn tui_choreo_cancel(group_id: u32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        animation::choreography_cancel(&mut ctx, group_id)?;
-        Ok(0)
-    })
-}
+            // Clamp to content bounds
+            let lines = split_textarea_lines_owned(&content);
+            let mut ar = s_row;
+            let mut ac = s_col;
+            let mut fr = e_row;
+            let mut fc = e_col;
+            clamp_textarea_cursor_lines(&lines, &mut ar, &mut ac);
+            clamp_textarea_cursor_lines(&lines, &mut fr, &mut fc);
 
-#[no_mangle]
-pub extern "C" fn tui_destroy_choreo_group(group_id: u32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        animation::destroy_choreography_group(&mut ctx, group_id)?;
+            state.selection_anchor = Some((ar, ac));
+            state.selection_focus = Some((fr, fc));
+            node.dirty = true;
+            node.edit_buffer_handle
+        };
+        if let Some(edit_handle) = edit_handle {
+            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
+        }
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.9 Focus Management
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_set_focusable(handle: u32, focusable: u8) -> i32 {
+pub extern "C" fn tui_textarea_clear_selection(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.focusable = focusable != 0;
+        let edit_handle = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            node.textarea_state
+                .as_mut()
+                .ok_or_else(|| {
+                    ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+                })?
+                .clear_selection();
+            node.dirty = true;
+            node.edit_buffer_handle
+        };
+        if let Some(edit_handle) = edit_handle {
+            edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
+        }
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_is_focusable(handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_get_selected_text_len(handle: u32) -> i32 {
     ffi_wrap(|| {
         let ctx = context_read()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get(&handle).unwrap();
-        Ok(if node.focusable { 1 } else { 0 })
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        let state = node.textarea_state.as_ref().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+        })?;
+        match (state.selection_anchor, state.selection_focus) {
+            (Some(anchor), Some(focus)) => {
+                let content = textarea_content_snapshot(&ctx, node)?;
+                let text = textarea::get_selected_text(&content, anchor, focus);
+                Ok(text.len() as i32)
+            }
+            _ => Ok(0),
+        }
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_focus(handle: u32) -> i32 {
+pub extern "C" fn tui_textarea_get_selected_text(
+    handle: u32,
+    buffer: *mut u8,
+    buffer_len: u32,
+) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
+        if buffer.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null buffer pointer"));
+        }
+        let ctx = context_read()?;
         ctx.validate_handle(handle)?;
-        let old = ctx.focused.unwrap_or(0);
-        ctx.focused = Some(handle);
-        if old != handle {
-            ctx.event_buffer.push(TuiEvent::focus_change(old, handle));
-            event::maybe_emit_accessibility_event(&mut ctx, handle);
+        let node = ctx.nodes.get(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        let state = node.textarea_state.as_ref().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+        })?;
+        match (state.selection_anchor, state.selection_focus) {
+            (Some(anchor), Some(focus)) => {
+                let content = textarea_content_snapshot(&ctx, node)?;
+                let text = textarea::get_selected_text(&content, anchor, focus);
+                let bytes = text.as_bytes();
+                let copy_len = bytes.len().min(buffer_len as usize);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+                }
+                // Null-terminate if space (consistent with tui_get_content et al.)
+                if (buffer_len as usize) > copy_len {
+                    unsafe {
+                        *buffer.add(copy_len) = 0;
+                    }
+                }
+                Ok(copy_len as i32)
+            }
+            _ => Ok(0),
         }
-        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_get_focused() -> u32 {
-    catch_unwind(AssertUnwindSafe(|| -> u32 {
-        context_read().ok().and_then(|ctx| ctx.focused).unwrap_or(0)
-    }))
-    .unwrap_or_default()
-}
-
-#[no_mangle]
-pub extern "C" fn tui_focus_next() -> i32 {
+pub extern "C" fn tui_textarea_find_next(
+    handle: u32,
+    ptr: *const u8,
+    len: u32,
+    case_sensitive: u8,
+    regex: u8,
+) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        event::focus_next(&mut ctx);
-        Ok(0)
-    })
-}
+        if ptr.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null pattern pointer"));
+        }
+        let pattern = unsafe {
+            let slice = std::slice::from_raw_parts(ptr, len as usize);
+            std::str::from_utf8(slice)
+                .map_err(|_| "Pattern is not valid UTF-8".to_string())?
+                .to_string()
+        };
 
-#[no_mangle]
-pub extern "C" fn tui_focus_prev() -> i32 {
-    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        event::focus_prev(&mut ctx);
-        Ok(0)
-    })
-}
+        ctx.validate_handle(handle)?;
+        let content = {
+            let node = ctx.nodes.get(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            textarea_content_snapshot(&ctx, node)?
+        };
+        let node = ctx.nodes.get_mut(&handle).unwrap();
 
-// ============================================================================
-// 4.10 Scroll
-// ============================================================================
+        let result = textarea::find_next(
+            &content,
+            node.cursor_row,
+            node.cursor_col,
+            &pattern,
+            case_sensitive != 0,
+            regex != 0,
+        )?;
 
-#[no_mangle]
-pub extern "C" fn tui_set_scroll(handle: u32, x: i32, y: i32) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        scroll::set_scroll(&mut ctx, handle, x, y)?;
-        Ok(0)
+        match result {
+            Some((row, col)) => {
+                // Set selection to highlight the match
+                let end = textarea::find_match_end(
+                    &content,
+                    row,
+                    col,
+                    &pattern,
+                    case_sensitive != 0,
+                    regex != 0,
+                );
+                let state = node.textarea_state.as_mut().ok_or_else(|| {
+                    ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+                })?;
+                state.selection_anchor = Some((row, col));
+                state.selection_focus = Some(end);
+
+                // Move cursor past match so next find_next advances.
+                // For zero-length matches (e.g. regex `^`), advance one grapheme
+                // to prevent infinite re-matching.
+                if end == (row, col) {
+                    let lines = split_textarea_lines_owned(&content);
+                    let line_len = lines
+                        .get(row as usize)
+                        .map(|l| grapheme_count(l) as u32)
+                        .unwrap_or(0);
+                    if col < line_len {
+                        node.cursor_row = row;
+                        node.cursor_col = col + 1;
+                    } else if (row as usize) + 1 < lines.len() {
+                        node.cursor_row = row + 1;
+                        node.cursor_col = 0;
+                    } else {
+                        // At very end of content — stay put
+                        node.cursor_row = row;
+                        node.cursor_col = col;
+                    }
+                } else {
+                    node.cursor_row = end.0;
+                    node.cursor_col = end.1;
+                }
+
+                let edit_handle = node.edit_buffer_handle;
+                node.dirty = true;
+                if let Some(edit_handle) = edit_handle {
+                    let _ = node;
+                    edit_buffer::break_coalescing(&mut ctx, edit_handle)?;
+                }
+                Ok(1)
+            }
+            None => Ok(0),
+        }
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_get_scroll(handle: u32, x: *mut i32, y: *mut i32) -> i32 {
+pub extern "C" fn tui_textarea_undo(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let (sx, sy) = scroll::get_scroll(&ctx, handle)?;
-        unsafe {
-            if !x.is_null() {
-                *x = sx;
-            }
-            if !y.is_null() {
-                *y = sy;
+        let was_at_bottom = textarea::view_at_bottom(&ctx, handle);
+        let (edit_handle, edit) = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
             }
+            let Some(edit_handle) = node.edit_buffer_handle else {
+                return Ok(0);
+            };
+            let state = node.textarea_state.as_mut().ok_or("No textarea state")?;
+            let Some(edit) = textarea::peek_undo(state) else {
+                return Ok(0);
+            };
+            (edit_handle, edit)
+        };
+        let performed = edit_buffer::undo(&mut ctx, edit_handle)?;
+        if !performed {
+            return Ok(0);
         }
-        Ok(0)
+        let buffer_handle = edit_buffer::buffer_handle(&ctx, edit_handle)?;
+        let content = ctx
+            .text_buffers
+            .get(&buffer_handle)
+            .ok_or_else(|| {
+                ErrorCode::InvalidHandle
+                    .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+            })?
+            .content()
+            .to_string();
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        node.set_content(content);
+        node.cursor_row = edit.cursor_row_before;
+        node.cursor_col = edit.cursor_col_before;
+        if let Some(state) = node.textarea_state.as_mut() {
+            // Pop metadata only after the native undo succeeded so a sync
+            // discrepancy cannot silently discard the caller-visible stack.
+            let _ = textarea::take_undo(state);
+            state.selection_anchor = edit.selection_anchor_before;
+            state.selection_focus = edit.selection_focus_before;
+            textarea::push_redo(state, edit);
+        }
+        clamp_textarea_cursor(node);
+        node.dirty = true;
+        textarea::enforce_max_lines(&mut ctx, handle, was_at_bottom)?;
+        Ok(1)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_scroll_by(handle: u32, dx: i32, dy: i32) -> i32 {
+pub extern "C" fn tui_textarea_redo(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        scroll::scroll_by(&mut ctx, handle, dx, dy);
-        Ok(0)
+        let was_at_bottom = textarea::view_at_bottom(&ctx, handle);
+        let (edit_handle, edit) = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            let Some(edit_handle) = node.edit_buffer_handle else {
+                return Ok(0);
+            };
+            let state = node.textarea_state.as_mut().ok_or("No textarea state")?;
+            let Some(edit) = textarea::peek_redo(state) else {
+                return Ok(0);
+            };
+            (edit_handle, edit)
+        };
+        let performed = edit_buffer::redo(&mut ctx, edit_handle)?;
+        if !performed {
+            return Ok(0);
+        }
+        let buffer_handle = edit_buffer::buffer_handle(&ctx, edit_handle)?;
+        let content = ctx
+            .text_buffers
+            .get(&buffer_handle)
+            .ok_or_else(|| {
+                ErrorCode::InvalidHandle
+                    .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+            })?
+            .content()
+            .to_string();
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        node.set_content(content);
+        node.cursor_row = edit.cursor_row_after;
+        node.cursor_col = edit.cursor_col_after;
+        if let Some(state) = node.textarea_state.as_mut() {
+            // Pop metadata only after the native redo succeeded so a sync
+            // discrepancy cannot silently discard the caller-visible stack.
+            let _ = textarea::take_redo(state);
+            state.selection_anchor = edit.selection_anchor_after;
+            state.selection_focus = edit.selection_focus_after;
+            textarea::push_undo(state, edit);
+        }
+        clamp_textarea_cursor(node);
+        node.dirty = true;
+        textarea::enforce_max_lines(&mut ctx, handle, was_at_bottom)?;
+        Ok(1)
     })
 }
 
+/// Cap a TextArea at `max_lines` lines, dropping the oldest ones after every
+/// content change (set, append, typing, undo/redo). 0 = unlimited.
 #[no_mangle]
-pub extern "C" fn tui_scroll_set_show_scrollbar(handle: u32, enabled: u8) -> i32 {
+pub extern "C" fn tui_textarea_set_max_lines(handle: u32, max_lines: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
         let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.show_scrollbar = enabled != 0;
-        node.dirty = true;
+        if node.node_type != NodeType::TextArea {
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a TextArea widget"))
+            );
+        }
+        node.textarea_max_lines = max_lines;
+        let at_bottom = textarea::view_at_bottom(&ctx, handle);
+        textarea::enforce_max_lines(&mut ctx, handle, at_bottom)?;
         Ok(0)
     })
 }
 
+/// Set the maximum number of undo entries. 0 = unlimited (no truncation).
 #[no_mangle]
-pub extern "C" fn tui_scroll_set_scrollbar_side(handle: u32, side: u8) -> i32 {
+pub extern "C" fn tui_textarea_set_history_limit(handle: u32, limit: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        if side > 1 {
-            return Err(format!(
-                "Invalid scrollbar side {side}: must be 0 (right) or 1 (left)"
-            ));
+        let edit_handle = {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            if node.node_type != NodeType::TextArea {
+                return Err(ErrorCode::WrongNodeType
+                    .raise(format!("Handle {handle} is not a TextArea widget")));
+            }
+            let state = node.textarea_state.as_mut().ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+            })?;
+            state.history_limit = limit;
+            if limit > 0 {
+                while state.undo_stack.len() > limit as usize {
+                    state.undo_stack.pop_front();
+                }
+            }
+            node.edit_buffer_handle
+        };
+        if let Some(edit_handle) = edit_handle {
+            let native_history_len = edit_buffer::history_len(&ctx, edit_handle)?;
+            let needs_trim = limit > 0 && native_history_len > limit as usize;
+            if needs_trim && edit_buffer::can_redo(&ctx, edit_handle)? {
+                {
+                    let node = ctx.nodes.get_mut(&handle).unwrap();
+                    let state = node.textarea_state.as_mut().ok_or_else(|| {
+                        ErrorCode::WrongNodeType
+                            .raise(format!("Handle {handle} has no textarea state"))
+                    })?;
+                    // Once the redo branch exists, trimming from the front can
+                    // leave replay steps whose preconditions no longer match the
+                    // current content. Drop redo on both sides before trimming.
+                    state.redo_stack.clear();
+                }
+                edit_buffer::discard_redo(&mut ctx, edit_handle)?;
+            }
+            edit_buffer::trim_history(&mut ctx, edit_handle, limit as usize)?;
+            let native_history_len = edit_buffer::history_len(&ctx, edit_handle)?;
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            let state = node.textarea_state.as_mut().ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no textarea state"))
+            })?;
+            while state.undo_stack.len() > native_history_len {
+                state.undo_stack.pop_front();
+            }
         }
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.scrollbar_side = side;
-        node.dirty = true;
         Ok(0)
     })
 }
 
+// ============================================================================
+// 4.7 Layout Properties
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_scroll_set_scrollbar_width(handle: u32, width: u8) -> i32 {
+pub extern "C" fn tui_set_layout_dimension(handle: u32, prop: u32, value: f32, unit: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        if !(1..=3).contains(&width) {
-            return Err(format!("Invalid scrollbar width {width}: must be 1..=3"));
-        }
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.scrollbar_width = width;
-        node.dirty = true;
+        layout::set_dimension(&mut ctx, handle, prop, value, unit)?;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.11 Input & Rendering
-// ============================================================================
-
 #[no_mangle]
-pub extern "C" fn tui_read_input(timeout_ms: u32) -> i32 {
+pub extern "C" fn tui_set_layout_flex(handle: u32, prop: u32, value: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        let count = event::read_input(&mut ctx, timeout_ms)?;
-        Ok(count as i32)
+        ctx.validate_handle(handle)?;
+        layout::set_flex(&mut ctx, handle, prop, value)?;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_next_event(out: *mut TuiEvent) -> i32 {
+pub extern "C" fn tui_set_layout_flex_factor(handle: u32, prop: u32, value: f32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        match event::next_event(&mut ctx) {
-            Some(evt) => {
-                if !out.is_null() {
-                    unsafe {
-                        *out = evt;
-                    }
-                }
-                Ok(1)
-            }
-            None => Ok(0),
-        }
+        ctx.validate_handle(handle)?;
+        layout::set_flex_factor(&mut ctx, handle, prop, value)?;
+        Ok(0)
     })
 }
 
+/// Set how a node lays out its children: 0 = flex (default), 1 = grid,
+/// 2 = none. A `none` node and its subtree take no layout space, unlike
+/// `tui_set_visible(0)` which only stops painting.
 #[no_mangle]
-pub extern "C" fn tui_render() -> i32 {
+pub extern "C" fn tui_set_display(handle: u32, display: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        render::render(&mut ctx)?;
+        ctx.validate_handle(handle)?;
+        layout::set_display(&mut ctx, handle, display)?;
+        if display == 2 {
+            event::release_focus_under(&mut ctx, handle);
+        }
         Ok(0)
     })
 }
 
+/// Remove a node and its subtree from layout flow (`enabled != 0`) while
+/// keeping them alive; siblings reflow into the freed space. Focus
+/// traversal skips the subtree. Independent of the flex/grid mode.
 #[no_mangle]
-pub extern "C" fn tui_mark_dirty(handle: u32) -> i32 {
+pub extern "C" fn tui_set_display_none(handle: u32, enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        tree::mark_dirty(&mut ctx, handle);
+        layout::set_display_none(&mut ctx, handle, enabled != 0)?;
+        if enabled != 0 {
+            event::release_focus_under(&mut ctx, handle);
+        }
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.18 Accessibility (ADR-T23)
-// ============================================================================
-
+/// Set a grid container's track list: axis 0 = columns, 1 = rows.
+/// `count == 0` clears it (tracks are then implicit).
 #[no_mangle]
-pub extern "C" fn tui_set_node_role(handle: u32, role: u32) -> i32 {
+pub extern "C" fn tui_set_grid_template(
+    handle: u32,
+    axis: u8,
+    tracks_ptr: *const types::TuiGridTrack,
+    count: u32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let accessibility_role = types::AccessibilityRole::from_u32(role)
-            .ok_or_else(|| format!("Invalid accessibility role: {role}"))?;
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.role = Some(accessibility_role);
+        let tracks = if count == 0 {
+            &[][..]
+        } else {
+            if tracks_ptr.is_null() {
+                return Err(ErrorCode::InvalidArgument.raise("Null grid track pointer"));
+            }
+            unsafe { std::slice::from_raw_parts(tracks_ptr, count as usize) }
+        };
+        layout::set_grid_template(&mut ctx, handle, axis, tracks)?;
         Ok(0)
     })
 }
 
+/// Place a grid item between 1-based grid lines; negative lines count from
+/// the end. 0 leaves that edge to auto-placement.
 #[no_mangle]
-pub extern "C" fn tui_set_node_label(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_set_grid_placement(
+    handle: u32,
+    col_start: i32,
+    col_end: i32,
+    row_start: i32,
+    row_end: i32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-
-        let text = if ptr.is_null() || len == 0 {
-            None
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            Some(
-                std::str::from_utf8(slice)
-                    .map_err(|_| "Invalid UTF-8".to_string())?
-                    .to_string(),
-            )
+        let to_line = |v: i32| {
+            i16::try_from(v).map_err(|_| {
+                ErrorCode::IndexOutOfBounds.raise(format!("Grid line out of range: {v}"))
+            })
         };
-
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.label = text;
+        layout::set_grid_placement(
+            &mut ctx,
+            handle,
+            to_line(col_start)?,
+            to_line(col_end)?,
+            to_line(row_start)?,
+            to_line(row_end)?,
+        )?;
         Ok(0)
     })
 }
 
+/// Keep the node's width / height at `ratio` when only one axis is sized.
+/// 0 clears it.
 #[no_mangle]
-pub extern "C" fn tui_set_node_description(handle: u32, ptr: *const u8, len: u32) -> i32 {
+pub extern "C" fn tui_set_layout_aspect_ratio(handle: u32, ratio: f32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
+        layout::set_aspect_ratio(&mut ctx, handle, ratio)?;
+        Ok(0)
+    })
+}
 
-        let text = if ptr.is_null() || len == 0 {
-            None
-        } else {
-            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            Some(
-                std::str::from_utf8(slice)
-                    .map_err(|_| "Invalid UTF-8".to_string())?
-                    .to_string(),
-            )
-        };
+/// Set padding (prop 0), margin (1) or inset (2) on all four sides, in
+/// cells. Inset positions a `position: absolute` node within its parent;
+/// NaN leaves a side `auto`.
+#[no_mangle]
+pub extern "C" fn tui_set_layout_edges(
+    handle: u32,
+    prop: u32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+) -> i32 {
+    tui_set_layout_edges_unit(handle, prop, top, right, bottom, left, 1)
+}
 
-        let node = ctx.nodes.get_mut(&handle).unwrap();
-        node.description = text;
+/// `tui_set_layout_edges` with a `unit` that follows
+/// `tui_set_layout_dimension` (0 = auto for margin/inset, 1 = cells, 2 =
+/// percent of the containing block).
+#[no_mangle]
+pub extern "C" fn tui_set_layout_edges_unit(
+    handle: u32,
+    prop: u32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+    unit: u8,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        layout::set_edges(&mut ctx, handle, prop, top, right, bottom, left, unit)?;
         Ok(0)
     })
 }
 
-// ============================================================================
-// 4.12 Diagnostics
-// ============================================================================
+/// Set row and column gap in cells.
+#[no_mangle]
+pub extern "C" fn tui_set_layout_gap(handle: u32, row_gap: f32, column_gap: f32) -> i32 {
+    tui_set_layout_gap_unit(handle, row_gap, column_gap, 1)
+}
 
+/// `tui_set_layout_gap` with a `unit`: 1 = cells, 2 = percent of the
+/// container.
 #[no_mangle]
-pub extern "C" fn tui_get_last_error() -> *const std::os::raw::c_char {
-    match catch_unwind(AssertUnwindSafe(|| -> *const std::os::raw::c_char {
-        let Some(snapshot) = get_last_error_snapshot() else {
-            return std::ptr::null();
-        };
-        let Ok(cstring) = CString::new(snapshot) else {
-            return std::ptr::null();
-        };
-
-        LAST_ERROR_SNAPSHOT.with(|slot| {
-            let mut slot = slot.borrow_mut();
-            *slot = Some(cstring);
-            slot.as_ref().map_or(std::ptr::null(), |s| {
-                s.as_ptr() as *const std::os::raw::c_char
-            })
-        })
-    })) {
-        Ok(ptr) => ptr,
-        Err(_) => {
-            LAST_ERROR_SNAPSHOT.with(|slot| {
-                *slot.borrow_mut() = None;
-            });
-            std::ptr::null()
-        }
-    }
-}
-
-#[no_mangle]
-pub extern "C" fn tui_clear_error() {
-    let _ = catch_unwind(AssertUnwindSafe(|| {
-        clear_last_error();
-        LAST_ERROR_SNAPSHOT.with(|slot| {
-            *slot.borrow_mut() = None;
-        });
-    }));
+pub extern "C" fn tui_set_layout_gap_unit(
+    handle: u32,
+    row_gap: f32,
+    column_gap: f32,
+    unit: u8,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        layout::set_gap(&mut ctx, handle, row_gap, column_gap, unit)?;
+        Ok(0)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_set_debug(enabled: u8) -> i32 {
+pub extern "C" fn tui_get_layout(
+    handle: u32,
+    x: *mut i32,
+    y: *mut i32,
+    w: *mut i32,
+    h: *mut i32,
+) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        ctx.debug_mode = enabled != 0;
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let (lx, ly, lw, lh) = layout::get_layout(&ctx, handle)?;
+        unsafe {
+            if !x.is_null() {
+                *x = lx;
+            }
+            if !y.is_null() {
+                *y = ly;
+            }
+            if !w.is_null() {
+                *w = lw;
+            }
+            if !h.is_null() {
+                *h = lh;
+            }
+        }
         Ok(0)
     })
 }
 
+/// Fill `out` with the absolute screen rect of every node, taken from one
+/// consistent snapshot. Positions match what `render` draws (parent
+/// offsets, render offsets, ScrollBox scroll). Returns the total number of
+/// nodes, which may exceed `max`; only the first `max` are written.
 #[no_mangle]
-pub extern "C" fn tui_get_perf_counter(counter_id: u32) -> u64 {
-    catch_unwind(AssertUnwindSafe(|| -> u64 {
-        let ctx = match context_read() {
-            Ok(c) => c,
-            Err(_) => return 0,
-        };
-        match counter_id {
-            0 => ctx.perf_layout_us,
-            1 => ctx.perf_render_us,
-            2 => ctx.perf_diff_cells as u64,
-            3 => ctx.event_buffer.len() as u64,
-            4 => ctx.nodes.len() as u64,
-            5 => ctx.nodes.values().filter(|n| n.dirty).count() as u64,
-            6 => ctx.animations.len() as u64,
-            7 => ctx.perf_write_bytes_estimate,
-            8 => ctx.perf_write_runs as u64,
-            9 => ctx.perf_style_deltas as u64,
-            10 => ctx.perf_text_parse_us,
-            11 => ctx.perf_text_wrap_us,
-            12 => ctx.perf_text_cache_hits as u64,
-            13 => ctx.perf_text_cache_misses as u64,
-            // v4 additions (TechSpec §4.5)
-            14 => ctx
-                .nodes
-                .values()
-                .filter_map(|n| n.transcript_state.as_ref())
-                .map(|t| t.blocks.len() as u64)
-                .sum(),
-            15 => ctx
-                .nodes
-                .values()
-                .filter_map(|n| n.transcript_state.as_ref())
-                .map(|t| t.viewport_rows as u64)
-                .sum(),
-            16 => ctx
-                .nodes
-                .values()
-                .filter_map(|n| n.transcript_state.as_ref())
-                .map(|t| t.unread_count as u64)
-                .sum(),
-            17 => ctx.debug_traces.iter().map(|d| d.len() as u64).sum(),
-            18 => ctx
-                .nodes
-                .values()
-                .filter_map(|n| n.transcript_state.as_ref())
-                .filter(|t| t.tail_attached)
-                .count() as u64,
-            _ => 0,
+pub extern "C" fn tui_get_computed_layout_all(out: *mut types::TuiNodeLayout, max: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        let layouts = layout::screen_layouts(&ctx);
+        if !out.is_null() {
+            for (i, rect) in layouts.iter().take(max as usize).enumerate() {
+                unsafe {
+                    *out.add(i) = *rect;
+                }
+            }
         }
-    }))
-    .unwrap_or_default()
+        Ok(layouts.len() as i32)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_free_string(_ptr: *const u8) {
-    // In the current implementation, strings are either context-owned
-    // (get_last_error) or caller-provides-buffer (get_content).
-    // This function is reserved for future use when the native core
-    // allocates strings that the host must free.
+pub extern "C" fn tui_measure_text(ptr: *const u8, len: u32, width: *mut u32) -> i32 {
+    ffi_wrap(|| {
+        let text = if ptr.is_null() || len == 0 {
+            ""
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(slice)
+                .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+        };
+
+        let measured = text::measure_text(text);
+        if !width.is_null() {
+            unsafe {
+                *width = measured;
+            }
+        }
+        Ok(0)
+    })
 }
 
 // ============================================================================
-// 4.13 Debug and Devtools (ADR-T34, TechSpec §4.3.3)
+// 4.8 Visual Style Properties
 // ============================================================================
 
+/// Set a color property (0 = fg, 1 = bg, 2 = border). `0x00000000` is the
+/// terminal-default sentinel (SGR 39/49), distinct from RGB black `0x01000000`.
 #[no_mangle]
-pub extern "C" fn tui_debug_set_overlay(flags: u32) -> i32 {
+pub extern "C" fn tui_set_style_color(handle: u32, prop: u32, color: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.debug_overlay_flags = flags;
+        ctx.validate_handle(handle)?;
+        style::set_color(&mut ctx, handle, prop, color)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_debug_set_trace_flags(flags: u32) -> i32 {
+pub extern "C" fn tui_set_style_flag(handle: u32, prop: u32, value: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.debug_trace_flags = flags;
+        ctx.validate_handle(handle)?;
+        style::set_flag(&mut ctx, handle, prop, value)?;
         Ok(0)
     })
 }
 
-/// Return the byte length of the current debug snapshot JSON.
-/// Returns -1 on error.
 #[no_mangle]
-pub extern "C" fn tui_debug_get_snapshot_len() -> i32 {
+pub extern "C" fn tui_set_style_border(handle: u32, border_style: u8) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        let json = devtools::build_snapshot_json(&ctx)?;
-        Ok(json.len() as i32)
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        style::set_border(&mut ctx, handle, border_style)?;
+        Ok(0)
     })
 }
 
-/// Copy the current debug snapshot JSON into caller-provided buffer.
-/// Returns the number of bytes written, or -1 on error.
 #[no_mangle]
-pub extern "C" fn tui_debug_get_snapshot(buffer: *mut u8, buffer_len: u32) -> i32 {
+pub extern "C" fn tui_set_style_opacity(handle: u32, opacity: f32) -> i32 {
     ffi_wrap(|| {
-        if buffer.is_null() {
-            return Err("Null buffer pointer".to_string());
-        }
-        let ctx = context_read()?;
-        let json = devtools::build_snapshot_json(&ctx)?;
-        let bytes = json.as_bytes();
-        let copy_len = bytes.len().min(buffer_len as usize);
-        unsafe {
-            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-        }
-        Ok(copy_len as i32)
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        style::set_opacity(&mut ctx, handle, opacity)?;
+        Ok(0)
     })
 }
 
-/// Return the byte length of a trace stream JSON for the given kind.
-/// Returns -1 on error.
+/// Set child overflow: 0 = visible (children may paint past the node's
+/// bounds), 1 = hidden (children are clipped to its content area, like a
+/// ScrollBox without scrolling).
 #[no_mangle]
-pub extern "C" fn tui_debug_get_trace_len(kind: u8) -> i32 {
+pub extern "C" fn tui_set_style_overflow(handle: u32, mode: u8) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        let json = devtools::build_trace_json(&ctx, kind)?;
-        Ok(json.len() as i32)
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let overflow = types::Overflow::from_u8(mode).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid overflow mode: {mode}"))
+        })?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.overflow = overflow;
+        node.dirty = true;
+        Ok(0)
     })
 }
 
-/// Copy a trace stream JSON for the given kind into caller-provided buffer.
-/// Returns the number of bytes written, or -1 on error.
+/// Make a node's opacity multiply down to all its descendants, so fading
+/// the node fades its whole subtree.
 #[no_mangle]
-pub extern "C" fn tui_debug_get_trace(kind: u8, buffer: *mut u8, buffer_len: u32) -> i32 {
+pub extern "C" fn tui_set_style_opacity_cascade(handle: u32, enabled: u8) -> i32 {
     ffi_wrap(|| {
-        if buffer.is_null() {
-            return Err("Null buffer pointer".to_string());
-        }
-        let ctx = context_read()?;
-        let json = devtools::build_trace_json(&ctx, kind)?;
-        let bytes = json.as_bytes();
-        let copy_len = bytes.len().min(buffer_len as usize);
-        unsafe {
-            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
-        }
-        Ok(copy_len as i32)
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        style::set_opacity_cascade(&mut ctx, handle, enabled != 0)?;
+        Ok(0)
     })
 }
 
+/// Choose how a node below full opacity is faded: 0 = blend RGB colors
+/// toward the background (default), 1 = set the DIM attribute instead,
+/// 2 = blend and fall back to DIM when the foreground isn't RGB.
 #[no_mangle]
-pub extern "C" fn tui_debug_clear_traces() -> i32 {
+pub extern "C" fn tui_set_opacity_mode(handle: u32, mode: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        devtools::clear_traces(&mut ctx);
+        ctx.validate_handle(handle)?;
+        let opacity_mode = types::OpacityMode::from_u8(mode).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid opacity mode: {mode}"))
+        })?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.opacity_mode = opacity_mode;
+        node.dirty = true;
         Ok(0)
     })
 }
 
 // ============================================================================
-// 4.19 Threaded Render Experiment (ADR-T31, TASK-H1)
+// 4.8.1 Color Helpers
 // ============================================================================
 
-#[cfg(feature = "threaded-render")]
+/// Parse `#rgb`, `#rrggbb`, `#rrggbbaa`, `default`, or `idx:N` into the
+/// packed color encoding. Returns 0 and sets last-error on invalid input.
 #[no_mangle]
-pub extern "C" fn tui_threaded_render_start() -> i32 {
-    ffi_wrap(|| {
-        // This is an experimental entry point. In a full implementation,
-        // it would spawn the background render thread and switch `tui_render()`
-        // to snapshot-dispatch mode. For the prototype, we validate that the
-        // threaded_render module compiles and the snapshot protocol works.
-        let mut ctx = context_write()?;
-        let snapshot = threaded_render::create_snapshot(&mut ctx)?;
-        ctx.debug_log(&format!(
-            "threaded_render: snapshot created with {} nodes, root={:?}",
-            snapshot.nodes.len(),
-            snapshot.root,
-        ));
-        Ok(0)
+pub extern "C" fn tui_color_from_hex(ptr: *const u8, len: u32) -> u32 {
+    ffi_wrap_u32(|| {
+        let text = unsafe { read_utf8_payload(ptr, len) }?;
+        color::parse_color(text)
     })
 }
 
-#[cfg(feature = "threaded-render")]
+/// Format a packed color as `#rrggbb`, `default`, or `idx:N` into a
+/// caller-provided buffer. Returns the number of bytes written.
 #[no_mangle]
-pub extern "C" fn tui_threaded_render_stop() -> i32 {
+pub extern "C" fn tui_color_to_hex(color: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
     ffi_wrap(|| {
-        // In the prototype, this is a no-op that confirms the FFI symbol exists
-        // and the rollback path is wired. A full implementation would join the
-        // render thread and switch back to synchronous mode.
-        let ctx = context_read()?;
-        ctx.debug_log("threaded_render: stop (synchronous mode restored)");
-        Ok(0)
+        let text = color::format_color(color)?;
+        let bytes = text.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        if !buffer.is_null() && copy_len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+            }
+        }
+        if !buffer.is_null() && (buffer_len as usize) > copy_len {
+            unsafe {
+                *buffer.add(copy_len) = 0;
+            }
+        }
+        Ok(copy_len as i32)
     })
 }
 
-// ============================================================================
-// Transcript Widget FFI (ADR-T32)
-// ============================================================================
+/// Build an RGB color from HSL. Hue is in degrees; saturation and lightness
+/// are in 0..=1.
+#[no_mangle]
+pub extern "C" fn tui_color_from_hsl(h: f32, s: f32, l: f32) -> u32 {
+    ffi_wrap_u32(|| color::from_hsl(h, s, l))
+}
 
+/// Build an RGB color from HSV. Hue is in degrees; saturation and value are
+/// in 0..=1.
 #[no_mangle]
-pub extern "C" fn tui_transcript_append_block(
-    handle: u32,
-    block_id: u64,
-    kind: u8,
-    role: u8,
-    ptr: *const u8,
-    len: u32,
-) -> i32 {
-    ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let k = types::TranscriptBlockKind::from_u8(kind)
-            .ok_or_else(|| format!("Invalid block kind: {kind}"))?;
-        let content = if ptr.is_null() {
-            ""
-        } else {
-            let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {e}"))?
-        };
-        transcript::append_block(&mut ctx, handle, block_id, k, role, content)?;
-        Ok(0)
-    })
+pub extern "C" fn tui_color_from_hsv(h: f32, s: f32, v: f32) -> u32 {
+    ffi_wrap_u32(|| color::from_hsv(h, s, v))
 }
 
+/// Raise HSL lightness by `amount` (0..=1). Non-RGB colors pass through.
 #[no_mangle]
-pub extern "C" fn tui_transcript_patch_block(
-    handle: u32,
-    block_id: u64,
-    patch_mode: u8,
-    ptr: *const u8,
-    len: u32,
-) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_color_lighten(color: u32, amount: f32) -> u32 {
+    ffi_wrap_u32(|| color::adjust_lightness(color, amount))
+}
+
+/// Lower HSL lightness by `amount` (0..=1). Non-RGB colors pass through.
+#[no_mangle]
+pub extern "C" fn tui_color_darken(color: u32, amount: f32) -> u32 {
+    ffi_wrap_u32(|| color::adjust_lightness(color, -amount))
+}
+
+/// Return packed white or black, whichever reads better on `bg` (WCAG
+/// contrast). Indexed backgrounds resolve through the xterm 256-color table;
+/// a default background returns the default foreground (0).
+#[no_mangle]
+pub extern "C" fn tui_contrast_color(bg: u32) -> u32 {
+    ffi_wrap_u32(|| color::contrast_color(bg))
+}
+
+// ============================================================================
+// 4.15 Theme Management
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_create_theme() -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let content = if ptr.is_null() {
-            ""
-        } else {
-            let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
-            std::str::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {e}"))?
-        };
-        transcript::patch_block(&mut ctx, handle, block_id, patch_mode, content)?;
-        Ok(0)
+        theme::create_theme(&mut ctx)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_finish_block(handle: u32, block_id: u64) -> i32 {
+pub extern "C" fn tui_destroy_theme(theme_handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::finish_block(&mut ctx, handle, block_id)?;
+        theme::destroy_theme(&mut ctx, theme_handle)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_set_parent(handle: u32, block_id: u64, parent_id: u64) -> i32 {
+pub extern "C" fn tui_set_theme_color(theme_handle: u32, prop: u8, color: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::set_parent(&mut ctx, handle, block_id, parent_id)?;
+        theme::set_theme_color(&mut ctx, theme_handle, prop, color)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_set_collapsed(handle: u32, block_id: u64, collapsed: u8) -> i32 {
+pub extern "C" fn tui_set_theme_flag(theme_handle: u32, prop: u8, value: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::set_collapsed(&mut ctx, handle, block_id, collapsed != 0)?;
+        theme::set_theme_flag(&mut ctx, theme_handle, prop, value)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_set_hidden(handle: u32, block_id: u64, hidden: u8) -> i32 {
+pub extern "C" fn tui_set_theme_border(theme_handle: u32, border_style: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::set_hidden(&mut ctx, handle, block_id, hidden != 0)?;
+        theme::set_theme_border(&mut ctx, theme_handle, border_style)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_jump_to_block(handle: u32, block_id: u64, align: u8) -> i32 {
+pub extern "C" fn tui_set_theme_opacity(theme_handle: u32, opacity: f32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::jump_to_block(&mut ctx, handle, block_id, align)?;
+        theme::set_theme_opacity(&mut ctx, theme_handle, opacity)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_jump_to_unread(handle: u32) -> i32 {
+pub extern "C" fn tui_set_theme_type_color(
+    theme_handle: u32,
+    node_type: u8,
+    prop: u8,
+    color: u32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::jump_to_unread(&mut ctx, handle)?;
+        theme::set_theme_type_color(&mut ctx, theme_handle, node_type, prop, color)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_set_follow_mode(handle: u32, mode: u8) -> i32 {
+pub extern "C" fn tui_set_theme_type_flag(
+    theme_handle: u32,
+    node_type: u8,
+    prop: u8,
+    value: u8,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let m = types::FollowMode::from_u8(mode)
-            .ok_or_else(|| format!("Invalid follow mode: {mode}"))?;
-        transcript::set_follow_mode(&mut ctx, handle, m)?;
+        theme::set_theme_type_flag(&mut ctx, theme_handle, node_type, prop, value)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_get_follow_mode(handle: u32) -> i32 {
+pub extern "C" fn tui_set_theme_type_border(
+    theme_handle: u32,
+    node_type: u8,
+    border_style: u8,
+) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        ctx.validate_handle(handle)?;
-        let mode = transcript::get_follow_mode(&ctx, handle)?;
-        Ok(mode as i32)
+        let mut ctx = context_write()?;
+        theme::set_theme_type_border(&mut ctx, theme_handle, node_type, border_style)?;
+        Ok(0)
     })
 }
 
-/// Set the foreground color for a specific transcript role.
-/// role: 0=system, 1=user, 2=assistant, 3=tool, 4=reasoning.
-/// color: 0 = inherit node default, or 0x01RRGGBB for RGB color.
 #[no_mangle]
-pub extern "C" fn tui_transcript_set_role_color(handle: u32, role: u8, color: u32) -> i32 {
+pub extern "C" fn tui_set_theme_type_opacity(
+    theme_handle: u32,
+    node_type: u8,
+    opacity: f32,
+) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        let node = ctx
-            .nodes
-            .get_mut(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-        if node.node_type != types::NodeType::Transcript {
-            return Err(format!("Handle {handle} is not a Transcript widget"));
-        }
-        let state = node
-            .transcript_state
-            .as_mut()
-            .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
-        if (role as usize) >= state.role_colors.len() {
-            return Err(format!("Invalid role index: {role}"));
-        }
-        state.role_colors[role as usize] = color;
-        node.dirty = true;
+        theme::set_theme_type_opacity(&mut ctx, theme_handle, node_type, opacity)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-/// Clear all blocks from a Transcript widget.
-pub extern "C" fn tui_transcript_clear(handle: u32) -> i32 {
+pub extern "C" fn tui_apply_theme(theme_handle: u32, node_handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::clear_blocks(&mut ctx, handle)?;
+        theme::apply_theme(&mut ctx, theme_handle, node_handle)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_mark_read(handle: u32) -> i32 {
+pub extern "C" fn tui_clear_theme(node_handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        transcript::mark_read(&mut ctx, handle)?;
+        theme::clear_theme(&mut ctx, node_handle)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_transcript_get_unread_count(handle: u32) -> i32 {
+pub extern "C" fn tui_switch_theme(theme_handle: u32) -> i32 {
     ffi_wrap(|| {
-        let ctx = context_read()?;
-        ctx.validate_handle(handle)?;
-        let count = transcript::get_unread_count(&ctx, handle)?;
-        Ok(count as i32)
+        let mut ctx = context_write()?;
+        theme::switch_theme(&mut ctx, theme_handle)?;
+        Ok(0)
     })
 }
 
 // ============================================================================
-// SplitPane FFI (ADR-T35)
+// 4.16 Animation (v1)
 // ============================================================================
 
 #[no_mangle]
-pub extern "C" fn tui_splitpane_set_axis(handle: u32, axis: u8) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_animate(
+    handle: u32,
+    property: u8,
+    target_bits: u32,
+    duration_ms: u32,
+    easing: u8,
+) -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        splitpane::set_axis(&mut ctx, handle, axis)?;
-        Ok(0)
+        let prop = types::AnimProp::from_u8(property).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid animation property: {property}"))
+        })?;
+        let ease = types::Easing::from_u8(easing).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid easing function: {easing}"))
+        })?;
+        animation::start_animation(&mut ctx, handle, prop, target_bits, duration_ms, ease)
     })
 }
 
+/// Start a multi-stop animation from `count` `KeyframeStop`s. Returns the
+/// animation handle, or 0 on error.
 #[no_mangle]
-pub extern "C" fn tui_splitpane_set_ratio(handle: u32, ratio: u16) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_animate_keyframes(
+    handle: u32,
+    property: u8,
+    stops: *const types::KeyframeStop,
+    count: u32,
+    total_duration_ms: u32,
+) -> u32 {
+    ffi_wrap_handle(|| {
+        if stops.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null keyframe stops pointer"));
+        }
+        let stops = unsafe { std::slice::from_raw_parts(stops, count as usize) };
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        splitpane::set_ratio(&mut ctx, handle, ratio)?;
-        Ok(0)
+        let prop = types::AnimProp::from_u8(property).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid animation property: {property}"))
+        })?;
+        animation::start_keyframe_animation(&mut ctx, handle, prop, stops, total_duration_ms)
     })
 }
 
+/// Start a damped-spring animation on an f32 property (opacity, position).
+/// Returns the animation handle, or 0 on error (including color properties).
 #[no_mangle]
-pub extern "C" fn tui_splitpane_get_ratio(handle: u32) -> i32 {
-    ffi_wrap(|| {
-        let ctx = context_read()?;
+pub extern "C" fn tui_animate_spring(
+    handle: u32,
+    property: u8,
+    target_bits: u32,
+    stiffness: f32,
+    damping: f32,
+) -> u32 {
+    ffi_wrap_handle(|| {
+        let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        let ratio = splitpane::get_ratio(&ctx, handle)?;
-        Ok(ratio as i32)
+        let prop = types::AnimProp::from_u8(property).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid animation property: {property}"))
+        })?;
+        animation::start_spring(&mut ctx, handle, prop, target_bits, stiffness, damping)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_splitpane_set_min_sizes(
-    handle: u32,
-    min_primary: u16,
-    min_secondary: u16,
-) -> i32 {
+pub extern "C" fn tui_cancel_animation(anim_handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        splitpane::set_min_sizes(&mut ctx, handle, min_primary, min_secondary)?;
+        animation::cancel_animation(&mut ctx, anim_handle)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_splitpane_set_resize_step(handle: u32, step: u16) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_start_spinner(handle: u32, interval_ms: u32) -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
         ctx.validate_handle(handle)?;
-        splitpane::set_resize_step(&mut ctx, handle, step)?;
-        Ok(0)
+        animation::start_spinner(&mut ctx, handle, interval_ms)
     })
 }
 
+/// Start a spinner with custom frames: `frames` is a newline-separated list
+/// of frame strings (empty lines are ignored). At least one frame is
+/// required. Returns the animation handle, or 0 on error.
 #[no_mangle]
-pub extern "C" fn tui_splitpane_set_resizable(handle: u32, enabled: u8) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_start_spinner_frames(
+    handle: u32,
+    frames_ptr: *const u8,
+    frames_len: u32,
+    interval_ms: u32,
+) -> u32 {
+    ffi_wrap_handle(|| {
+        let frames = unsafe { read_utf8_payload(frames_ptr, frames_len) }?;
+        let frames = frames
+            .split('\n')
+            .filter(|frame| !frame.is_empty())
+            .map(str::to_string)
+            .collect();
         let mut ctx = context_write()?;
-        ctx.validate_handle(handle)?;
-        splitpane::set_resizable(&mut ctx, handle, enabled != 0)?;
-        Ok(0)
+        animation::start_spinner_frames(&mut ctx, handle, frames, interval_ms)
     })
 }
 
-// ============================================================================
-// Native Text Substrate FFI (ADR-T37, TechSpec §4.4 `text_buffer`, `text_view`)
-// ============================================================================
+/// Start a color spinner: the node's fg cycles through `count` colors read
+/// from `colors_ptr` every `interval_ms`, leaving its content unchanged.
+/// Returns the animation handle, or 0 on error.
+#[no_mangle]
+pub extern "C" fn tui_start_color_spinner(
+    handle: u32,
+    colors_ptr: *const u32,
+    count: u32,
+    interval_ms: u32,
+) -> u32 {
+    ffi_wrap_handle(|| {
+        if colors_ptr.is_null() || count == 0 {
+            return Err(ErrorCode::InvalidArgument.raise("Color spinner needs at least one color"));
+        }
+        let colors = unsafe { std::slice::from_raw_parts(colors_ptr, count as usize) }.to_vec();
+        let mut ctx = context_write()?;
+        animation::start_color_spinner(&mut ctx, handle, colors, interval_ms)
+    })
+}
 
+/// Reveal a Text node's content `chars_per_sec` graphemes per second. The
+/// stored content is the full text throughout; only rendering is clipped.
+/// Emits AnimationEnd when fully shown. Returns the animation handle, or 0
+/// on error.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_create() -> u32 {
+pub extern "C" fn tui_animate_typewriter(handle: u32, chars_per_sec: f32) -> u32 {
     ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        text_buffer::create(&mut ctx)
+        animation::start_typewriter(&mut ctx, handle, chars_per_sec)
     })
 }
 
+/// Scroll a Text node's content `speed` cells per second when it is wider
+/// than the node, wrapping with `gap` blank cells between repetitions.
+/// Loops until cancelled. Returns the animation handle, or 0 on error.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_destroy(handle: u32) -> i32 {
-    ffi_wrap(|| {
+pub extern "C" fn tui_animate_marquee(handle: u32, speed: f32, gap: u32) -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        text_buffer::destroy(&mut ctx, handle)?;
-        Ok(0)
+        animation::start_marquee(&mut ctx, handle, speed, gap)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_replace_range(
-    handle: u32,
-    start_byte: u32,
-    end_byte: u32,
-    ptr: *const u8,
-    len: u32,
-) -> i32 {
-    ffi_wrap(|| {
-        let payload = unsafe { read_utf8_payload(ptr, len) }?;
+pub extern "C" fn tui_start_progress(handle: u32, duration_ms: u32, easing: u8) -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        text_buffer::replace_range(
-            &mut ctx,
-            handle,
-            start_byte as usize,
-            end_byte as usize,
-            payload,
-        )?;
-        Ok(0)
+        ctx.validate_handle(handle)?;
+        let ease = types::Easing::from_u8(easing).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid easing function: {easing}"))
+        })?;
+        animation::start_progress(&mut ctx, handle, duration_ms, ease)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_append(handle: u32, ptr: *const u8, len: u32) -> i32 {
-    ffi_wrap(|| {
-        let payload = unsafe { read_utf8_payload(ptr, len) }?;
+pub extern "C" fn tui_start_pulse(handle: u32, duration_ms: u32, easing: u8) -> u32 {
+    ffi_wrap_handle(|| {
         let mut ctx = context_write()?;
-        text_buffer::append(&mut ctx, handle, payload)?;
-        Ok(0)
+        ctx.validate_handle(handle)?;
+        let ease = types::Easing::from_u8(easing).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid easing function: {easing}"))
+        })?;
+        animation::start_pulse(&mut ctx, handle, duration_ms, ease)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_get_epoch(handle: u32) -> u64 {
-    ffi_wrap_u64(|| {
-        let ctx = context_read()?;
-        let buf = ctx
-            .text_buffers
-            .get(&handle)
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
-        Ok(buf.epoch())
+pub extern "C" fn tui_set_animation_looping(anim_id: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        animation::set_animation_looping(&mut ctx, anim_id)?;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_get_byte_len(handle: u32) -> u32 {
-    ffi_wrap_handle(|| {
-        let ctx = context_read()?;
-        let buf = ctx
-            .text_buffers
-            .get(&handle)
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
-        usize_to_u32_or_err(buf.byte_len(), "byte_len")
+pub extern "C" fn tui_chain_animation(after_anim: u32, next_anim: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        animation::chain_animation(&mut ctx, after_anim, next_anim)?;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_get_line_count(handle: u32) -> u32 {
+pub extern "C" fn tui_create_choreo_group() -> u32 {
     ffi_wrap_handle(|| {
-        let ctx = context_read()?;
-        let buf = ctx
-            .text_buffers
-            .get(&handle)
-            .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
-        usize_to_u32_or_err(buf.line_count(), "line_count")
+        let mut ctx = context_write()?;
+        animation::create_choreography_group(&mut ctx)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_set_style_span(
-    handle: u32,
-    start_byte: u32,
-    end_byte: u32,
-    fg: u32,
-    bg: u32,
-    attrs: u8,
-) -> i32 {
+pub extern "C" fn tui_choreo_add(group_id: u32, anim_id: u32, start_at_ms: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::set_style_span(
-            &mut ctx,
-            handle,
-            start_byte as usize,
-            end_byte as usize,
-            fg,
-            bg,
-            attrs,
-        )?;
+        animation::choreography_add(&mut ctx, group_id, anim_id, start_at_ms)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_clear_style_spans(handle: u32) -> i32 {
+pub extern "C" fn tui_choreo_start(group_id: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::clear_style_spans(&mut ctx, handle)?;
+        animation::choreography_start(&mut ctx, group_id)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_set_link(
-    handle: u32,
-    start_byte: u32,
-    end_byte: u32,
-    uri_ptr: *const u8,
-    uri_len: u32,
-    id_ptr: *const u8,
-    id_len: u32,
-) -> i32 {
+pub extern "C" fn tui_choreo_cancel(group_id: u32) -> i32 {
     ffi_wrap(|| {
-        let uri = unsafe { read_utf8_payload(uri_ptr, uri_len) }?;
-        let id = unsafe { read_utf8_payload(id_ptr, id_len) }?;
-        let id = if id_len == 0 { None } else { Some(id) };
         let mut ctx = context_write()?;
-        text_buffer::set_link(
-            &mut ctx,
-            handle,
-            start_byte as usize,
-            end_byte as usize,
-            uri,
-            id,
-        )?;
+        animation::choreography_cancel(&mut ctx, group_id)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_clear_links(handle: u32) -> i32 {
+pub extern "C" fn tui_destroy_choreo_group(group_id: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::clear_links(&mut ctx, handle)?;
+        animation::destroy_choreography_group(&mut ctx, group_id)?;
         Ok(0)
     })
 }
 
+/// Advance all animations by a host-supplied delta, independent of `tui_render`.
+/// Pair with `tui_set_animation_auto_advance(0)` for a fixed-timestep clock.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_set_selection(
-    handle: u32,
-    start_byte: u32,
-    end_byte: u32,
-) -> i32 {
+pub extern "C" fn tui_advance_animations(elapsed_ms: f32) -> i32 {
     ffi_wrap(|| {
+        if !elapsed_ms.is_finite() || elapsed_ms < 0.0 {
+            return Err(
+                ErrorCode::InvalidArgument.raise(format!("Invalid elapsed time: {elapsed_ms}"))
+            );
+        }
         let mut ctx = context_write()?;
-        text_buffer::set_selection(&mut ctx, handle, start_byte as usize, end_byte as usize)?;
+        animation::advance_animations(&mut ctx, elapsed_ms);
         Ok(0)
     })
 }
 
+/// Enable (1, default) or disable (0) the wall-clock animation advance that
+/// runs at the start of every `tui_render`.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_clear_selection(handle: u32) -> i32 {
+pub extern "C" fn tui_set_animation_auto_advance(enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::clear_selection(&mut ctx, handle)?;
+        ctx.auto_advance_animations = enabled != 0;
         Ok(0)
     })
 }
 
+/// FLIP step 1: record the on-screen position of every descendant of `root`.
+/// Returns the number of nodes captured.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_set_highlight(
-    handle: u32,
-    start_byte: u32,
-    end_byte: u32,
-    kind: u8,
-) -> i32 {
+pub extern "C" fn tui_capture_positions(root: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::set_highlight(
-            &mut ctx,
-            handle,
-            start_byte as usize,
-            end_byte as usize,
-            kind,
-        )?;
-        Ok(0)
+        let count = animation::capture_positions(&mut ctx, root)?;
+        Ok(count as i32)
     })
 }
 
+/// FLIP step 2: after mutating the tree, animate every captured descendant
+/// that moved from its old position back to its new layout position.
+/// Returns the number of nodes transitioned.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_clear_highlights(handle: u32) -> i32 {
+pub extern "C" fn tui_play_position_transitions(root: u32, duration_ms: u32, easing: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::clear_highlights(&mut ctx, handle)?;
-        Ok(0)
+        let ease = types::Easing::from_u8(easing).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid easing function: {easing}"))
+        })?;
+        let count = animation::play_position_transitions(&mut ctx, root, duration_ms, ease)?;
+        Ok(count as i32)
     })
 }
 
-/// Drain the buffer's `dirty_ranges` list after a consumer has processed
-/// them. Without this drain call, `dirty_ranges` grows unbounded across
-/// the session lifetime as `replace_range` / `append` keeps appending.
-/// Returns 0 on success; negative codes per the standard error model.
+/// Opt in (1) or out (0, default) of AnimationStart events for chained and
+/// choreographed animations. AnimationEnd events are always emitted.
 #[no_mangle]
-pub extern "C" fn tui_text_buffer_clear_dirty_ranges(handle: u32) -> i32 {
+pub extern "C" fn tui_set_animation_events(enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_buffer::clear_dirty_ranges(&mut ctx, handle)?;
+        ctx.animation_start_events = enabled != 0;
         Ok(0)
     })
 }
 
+/// Scale all animation time (0 freezes, 1 is real time, 2 doubles speed).
+/// Negative values clamp to 0; NaN is rejected.
 #[no_mangle]
-pub extern "C" fn tui_text_view_create(buffer_handle: u32) -> u32 {
-    ffi_wrap_handle(|| {
+pub extern "C" fn tui_set_animation_time_scale(scale: f32) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::create(&mut ctx, buffer_handle)
+        animation::set_time_scale(&mut ctx, scale)?;
+        Ok(0)
     })
 }
 
+/// Replace the real clock with a virtual one reading `ms` milliseconds, for
+/// reproducible tests of animations, ticks, render throttling and typeahead.
+/// Only differences between values matter: the first call reads the real
+/// current time, and the clock then moves only by what later values add.
+/// Later calls may not go backwards. It stays in effect until
+/// `tui_shutdown`. Debug builds only.
+#[cfg(any(test, debug_assertions))]
 #[no_mangle]
-pub extern "C" fn tui_text_view_destroy(handle: u32) -> i32 {
+pub extern "C" fn tui_test_set_clock(ms: u64) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::destroy(&mut ctx, handle)?;
+        let clock = match ctx.test_clock {
+            Some(clock) if ms < clock.ms => {
+                return Err(ErrorCode::InvalidArgument.raise(format!(
+                    "Invalid clock: {ms}ms is before the current {}ms",
+                    clock.ms
+                )));
+            }
+            Some(clock) => context::TestClock { ms, ..clock },
+            None => context::TestClock {
+                anchor: std::time::Instant::now(),
+                anchor_ms: ms,
+                ms,
+            },
+        };
+        ctx.test_clock = Some(clock);
         Ok(0)
     })
 }
 
+// ============================================================================
+// 4.9 Focus Management
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_text_view_set_wrap(handle: u32, width: u32, mode: u8, tab_width: u8) -> i32 {
+pub extern "C" fn tui_set_focusable(handle: u32, focusable: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::set_wrap(&mut ctx, handle, width, mode, tab_width)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.focusable = focusable != 0;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_set_viewport(
-    handle: u32,
-    rows: u32,
-    scroll_row: u32,
-    scroll_col: u32,
-) -> i32 {
+pub extern "C" fn tui_is_focusable(handle: u32) -> i32 {
     ffi_wrap(|| {
-        let mut ctx = context_write()?;
-        text_view::set_viewport(&mut ctx, handle, rows, scroll_row, scroll_col)?;
-        Ok(0)
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get(&handle).unwrap();
+        Ok(if node.focusable { 1 } else { 0 })
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_set_cursor(handle: u32, byte_offset: u32) -> i32 {
+pub extern "C" fn tui_focus(handle: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::set_cursor(&mut ctx, handle, byte_offset as usize)?;
+        ctx.validate_handle(handle)?;
+        let old = ctx.focused.unwrap_or(0);
+        ctx.focused = Some(handle);
+        if old != handle {
+            ctx.event_buffer.push(TuiEvent::focus_change(old, handle));
+            event::maybe_emit_accessibility_event(&mut ctx, handle);
+        }
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_clear_cursor(handle: u32) -> i32 {
+pub extern "C" fn tui_get_focused() -> u32 {
+    catch_unwind(AssertUnwindSafe(|| -> u32 {
+        context_read().ok().and_then(|ctx| ctx.focused).unwrap_or(0)
+    }))
+    .unwrap_or_default()
+}
+
+#[no_mangle]
+pub extern "C" fn tui_focus_next() -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::clear_cursor(&mut ctx, handle)?;
+        event::focus_next(&mut ctx);
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_get_visual_line_count(handle: u32) -> u32 {
-    ffi_wrap_handle(|| {
+pub extern "C" fn tui_focus_prev() -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::get_visual_line_count(&mut ctx, handle)
+        event::focus_prev(&mut ctx);
+        Ok(0)
     })
 }
 
+// ============================================================================
+// 4.10 Scroll
+// ============================================================================
+
 #[no_mangle]
-pub extern "C" fn tui_text_view_byte_to_visual(
-    handle: u32,
-    byte_offset: u32,
-    out_row: *mut u32,
-    out_col: *mut u32,
-) -> i32 {
+pub extern "C" fn tui_set_scroll(handle: u32, x: i32, y: i32) -> i32 {
     ffi_wrap(|| {
-        if out_row.is_null() || out_col.is_null() {
-            return Err("Null out-pointer".to_string());
-        }
         let mut ctx = context_write()?;
-        let (row, col) = text_view::byte_to_visual(&mut ctx, handle, byte_offset as usize)?;
-        unsafe {
-            *out_row = row;
-            *out_col = col;
-        }
+        ctx.validate_handle(handle)?;
+        scroll::set_scroll(&mut ctx, handle, x, y)?;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_visual_to_byte(
-    handle: u32,
-    row: u32,
-    col: u32,
-    out_byte: *mut u32,
-) -> i32 {
+pub extern "C" fn tui_get_scroll(handle: u32, x: *mut i32, y: *mut i32) -> i32 {
     ffi_wrap(|| {
-        if out_byte.is_null() {
-            return Err("Null out-pointer".to_string());
-        }
-        let mut ctx = context_write()?;
-        let byte = text_view::visual_to_byte(&mut ctx, handle, row, col)?;
-        let byte_u32 = usize_to_u32_or_err(byte, "byte_offset")?;
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let (sx, sy) = scroll::get_scroll(&ctx, handle)?;
         unsafe {
-            *out_byte = byte_u32;
+            if !x.is_null() {
+                *x = sx;
+            }
+            if !y.is_null() {
+                *y = sy;
+            }
         }
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_text_view_get_cache_epoch(handle: u32) -> u64 {
-    ffi_wrap_u64(|| {
+pub extern "C" fn tui_scroll_by(handle: u32, dx: i32, dy: i32) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        text_view::get_cache_epoch(&mut ctx, handle)
+        ctx.validate_handle(handle)?;
+        scroll::scroll_by(&mut ctx, handle, dx, dy);
+        Ok(0)
     })
 }
 
+/// Keep a ScrollBox or TextArea scrolled to the bottom as its content
+/// grows. Following pauses while the user scrolls up and resumes once they
+/// return to the bottom. Enabling pins the view to the bottom.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_create(buffer_handle: u32) -> u32 {
-    ffi_wrap_handle(|| {
+pub extern "C" fn tui_set_autoscroll(handle: u32, enabled: u8) -> i32 {
+    ffi_wrap(|| {
         let mut ctx = context_write()?;
-        edit_buffer::create(&mut ctx, buffer_handle)
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        if !matches!(node.node_type, NodeType::ScrollBox | NodeType::TextArea) {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a ScrollBox or TextArea")));
+        }
+        node.autoscroll = enabled != 0;
+        node.autoscroll_pinned = true;
+        node.dirty = true;
+        Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_destroy(handle: u32) -> i32 {
+pub extern "C" fn tui_scroll_set_show_scrollbar(handle: u32, enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        edit_buffer::destroy(&mut ctx, handle)?;
+        ctx.validate_handle(handle)?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.show_scrollbar = enabled != 0;
+        node.dirty = true;
         Ok(0)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_apply_op(
-    handle: u32,
-    op_kind: u8,
-    ptr: *const u8,
-    len: u32,
-    start_byte: u32,
-    end_byte: u32,
-) -> i32 {
+pub extern "C" fn tui_scroll_set_scrollbar_side(handle: u32, side: u8) -> i32 {
     ffi_wrap(|| {
-        let payload = unsafe { read_utf8_payload(ptr, len) }?;
         let mut ctx = context_write()?;
-        match op_kind {
-            0 => {
-                let _ = edit_buffer::apply_insert(&mut ctx, handle, start_byte as usize, payload)?;
-            }
-            1 => {
-                let _ = edit_buffer::apply_delete(
-                    &mut ctx,
-                    handle,
-                    start_byte as usize,
-                    end_byte as usize,
-                )?;
+        ctx.validate_handle(handle)?;
+        if side > 1 {
+            return Err(ErrorCode::InvalidArgument.raise(format!(
+                "Invalid scrollbar side {side}: must be 0 (right) or 1 (left)"
+            )));
+        }
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.scrollbar_side = side;
+        node.dirty = true;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_scroll_set_scrollbar_width(handle: u32, width: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        if !(1..=3).contains(&width) {
+            return Err(ErrorCode::InvalidArgument
+                .raise(format!("Invalid scrollbar width {width}: must be 1..=3")));
+        }
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.scrollbar_width = width;
+        node.dirty = true;
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// 4.11 Input & Rendering
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_read_input(timeout_ms: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        let count = event::read_input(&mut ctx, timeout_ms)?;
+        Ok(count as i32)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_next_event(out: *mut TuiEvent) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        match event::next_event(&mut ctx) {
+            Some(evt) => {
+                if !out.is_null() {
+                    unsafe {
+                        *out = evt;
+                    }
+                }
+                Ok(1)
             }
-            2 => {
-                let _ = edit_buffer::apply_replace(
-                    &mut ctx,
-                    handle,
-                    start_byte as usize,
-                    end_byte as usize,
-                    payload,
-                )?;
+            None => Ok(0),
+        }
+    })
+}
+
+/// `tui_next_event` that also writes the host tag of the event's node (see
+/// `tui_set_node_tag`), read before a later call can destroy the node.
+#[no_mangle]
+pub extern "C" fn tui_next_event_tagged(out: *mut TuiTaggedEvent) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        match event::next_event(&mut ctx) {
+            Some(evt) => {
+                if !out.is_null() {
+                    let tag = event::event_tag(&ctx, &evt);
+                    unsafe {
+                        *out = TuiTaggedEvent { event: evt, tag };
+                    }
+                }
+                Ok(1)
             }
-            _ => return Err(format!("Invalid edit op kind: {op_kind}")),
+            None => Ok(0),
         }
-        Ok(0)
     })
 }
 
+/// Emit a Tick event from `tui_read_input` when no input arrived for
+/// `interval_ms`. `data[0]` carries the elapsed milliseconds. 0 disables.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_break_coalescing(handle: u32) -> i32 {
+pub extern "C" fn tui_set_tick_interval(interval_ms: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        edit_buffer::break_coalescing(&mut ctx, handle)?;
+        event::set_tick_interval(&mut ctx, interval_ms);
         Ok(0)
     })
 }
 
+/// Idle window in milliseconds after which Select typeahead starts a new
+/// prefix. Default 1000.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_undo(handle: u32) -> i32 {
+pub extern "C" fn tui_set_typeahead_ms(ms: u32) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        let performed = edit_buffer::undo(&mut ctx, handle)?;
-        Ok(if performed { 1 } else { 0 })
+        ctx.typeahead_ms = ms;
+        Ok(0)
     })
 }
 
+/// When enabled, Escape on a focused widget (not dismissing an overlay)
+/// moves focus to its nearest focusable ancestor, or clears it, and emits
+/// FocusChange instead of a Key event. Default off.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_redo(handle: u32) -> i32 {
+pub extern "C" fn tui_set_escape_blurs(enabled: u8) -> i32 {
     ffi_wrap(|| {
         let mut ctx = context_write()?;
-        let performed = edit_buffer::redo(&mut ctx, handle)?;
-        Ok(if performed { 1 } else { 0 })
+        ctx.escape_blurs = enabled != 0;
+        Ok(0)
     })
 }
 
+/// Show or hide the focused widget's caret (Input, TextArea, ComboBox)
+/// so the host can blink it on a timer. The hidden state is tied to the
+/// current focus: any focus change brings the caret back.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_can_undo(handle: u32) -> u8 {
-    ffi_wrap_u8(|| {
-        let ctx = context_read()?;
-        Ok(if edit_buffer::can_undo(&ctx, handle)? {
-            1
+pub extern "C" fn tui_set_caret_visible(visible: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.caret_hidden_for = if visible != 0 {
+            None
         } else {
-            0
-        })
+            Some(ctx.focused.unwrap_or(0))
+        };
+        Ok(0)
     })
 }
 
+/// Set how the focused widget's caret is drawn: 0 = block (default),
+/// 1 = bar, 2 = underline. The terminal's own cursor stays hidden, so no
+/// DECSCUSR sequence is emitted; the caret is drawn into the cell buffer.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_can_redo(handle: u32) -> u8 {
-    ffi_wrap_u8(|| {
-        let ctx = context_read()?;
-        Ok(if edit_buffer::can_redo(&ctx, handle)? {
-            1
-        } else {
-            0
-        })
+pub extern "C" fn tui_set_caret_style(style: u8) -> i32 {
+    ffi_wrap(|| {
+        let style = types::CaretStyle::from_u8(style).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid caret style: {style}"))
+        })?;
+        let mut ctx = context_write()?;
+        ctx.caret_style = style;
+        Ok(0)
     })
 }
 
+/// Characters that end a word for Ctrl+Left/Right and Ctrl+Backspace in
+/// Input and TextArea, in addition to whitespace (e.g. "._-()"). Each run of
+/// them is a token of its own. An empty set restores whitespace-only words.
 #[no_mangle]
-pub extern "C" fn tui_edit_buffer_history_len(handle: u32) -> u32 {
-    ffi_wrap_handle(|| {
-        let ctx = context_read()?;
-        let len = edit_buffer::history_len(&ctx, handle)?;
-        usize_to_u32_or_err(len, "history_len")
+pub extern "C" fn tui_set_word_break_chars(ptr: *const u8, len: u32) -> i32 {
+    ffi_wrap(|| {
+        let chars = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
+        ctx.word_break_chars = chars.to_string();
+        Ok(0)
     })
 }
 
-/// Read a UTF-8 payload from a (ptr, len) pair, or accept a null pointer
-/// only when `len == 0`. Used by substrate FFI mutation entry points.
-///
-/// Returns a borrowed `&str` instead of an owned `String` so substrate
-/// callees can write the bytes directly into buffer storage with one
-/// boundary copy. Previously this function allocated an owned `String`
-/// that `text_buffer::replace_range` / `append` then copied into
-/// `buf.content`, doubling the work on the hot streaming-append path.
-///
-/// # Safety
-/// Caller must ensure `ptr` is non-null when `len > 0` and remains
-/// valid for at least `len` bytes for the duration of the returned
-/// slice's use. FFI entry points satisfy this because the caller
-/// (Bun/Node FFI) keeps the buffer alive for the call.
-unsafe fn read_utf8_payload<'a>(ptr: *const u8, len: u32) -> Result<&'a str, String> {
-    if len == 0 {
-        return Ok("");
-    }
-    if ptr.is_null() {
-        return Err("Null payload pointer".to_string());
-    }
-    let bytes = std::slice::from_raw_parts(ptr, len as usize);
-    std::str::from_utf8(bytes).map_err(|e| format!("Payload is not valid UTF-8: {e}"))
+/// Run the render pipeline. Returns 0, or 1 when the frame was skipped
+/// because nothing changed since the last one or it came too soon (see
+/// `tui_set_min_render_interval`).
+#[no_mangle]
+pub extern "C" fn tui_render() -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        if render::throttled(&ctx) {
+            return Ok(1);
+        }
+        Ok(if render::render(&mut ctx)? { 0 } else { 1 })
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::context::ffi_test_guard;
+/// Make `tui_render` a no-op returning 1 when called within `ms` of the
+/// previous frame while nothing is dirty, capping the frame rate of a host
+/// that renders in a tight loop. Dirty nodes, an invalidated screen or a
+/// resize always render. 0 (the default) never skips.
+#[no_mangle]
+pub extern "C" fn tui_set_min_render_interval(ms: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.min_render_interval_ms = ms;
+        Ok(0)
+    })
+}
 
-    fn content_from_handle(handle: u32) -> String {
-        let len = tui_get_content_len(handle);
-        assert!(len >= 0);
-        let mut buf = vec![0u8; len as usize + 1];
-        let written = tui_get_content(handle, buf.as_mut_ptr(), buf.len() as u32);
-        assert_eq!(written, len);
-        String::from_utf8(buf[..len as usize].to_vec()).unwrap()
-    }
+/// Render only the screen rect (x, y, w, h), e.g. when the TUI is embedded
+/// in a larger host surface. Layout and animations run as for `tui_render`,
+/// but cells outside the rect are neither painted nor emitted: the host
+/// manages that part of the surface, and the next full `tui_render`
+/// repaints whatever changed there.
+#[no_mangle]
+pub extern "C" fn tui_render_region(x: u16, y: u16, w: u16, h: u16) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::render_region(&mut ctx, x, y, w, h)?;
+        Ok(0)
+    })
+}
 
-    #[test]
-    fn test_get_last_error_snapshot_round_trip() {
-        let _guard = ffi_test_guard();
+#[no_mangle]
+pub extern "C" fn tui_mark_dirty(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        tree::mark_dirty(&mut ctx, handle);
+        Ok(0)
+    })
+}
 
-        // Initialize a headless context for testing
-        tui_init_headless(80, 24);
+/// Invalidate the screen buffer so the next `tui_render` repaints every cell.
+/// Use after anything else has drawn to the terminal (e.g. a shell-out).
+#[no_mangle]
+pub extern "C" fn tui_invalidate() -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::invalidate(&mut ctx);
+        Ok(0)
+    })
+}
 
-        // Set an error via an operation that will fail (invalid handle)
-        let result = tui_destroy_node(999);
-        assert_eq!(result, -1);
+/// Set the output color depth: 0 = truecolor, 1 = 256-color, 2 = 16-color.
+/// Defaults to the deepest mode the detected capabilities allow. RGB colors
+/// are quantized to the nearest xterm palette entry on output only; the
+/// stored colors keep full fidelity.
+#[no_mangle]
+pub extern "C" fn tui_set_color_mode(mode: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::set_color_mode(&mut ctx, mode)?;
+        Ok(0)
+    })
+}
 
-        // Get the error pointer
-        let ptr = tui_get_last_error();
-        assert!(!ptr.is_null(), "Error pointer should not be null");
+/// Current output color depth (see `tui_set_color_mode`).
+#[no_mangle]
+pub extern "C" fn tui_get_color_mode() -> u8 {
+    ffi_wrap_u8(|| {
+        let ctx = context_read()?;
+        Ok(ctx.writer_state.color_mode as u8)
+    })
+}
 
-        // Read it as a C string snapshot.
-        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr) };
-        let error_msg = c_str.to_str().expect("Error should be valid UTF-8");
-        assert!(
-            error_msg.contains("Invalid handle"),
-            "Expected error about invalid handle, got: {error_msg}"
-        );
+/// Emit ASCII stand-ins ('+', '-', '|', '#', '*') for box-drawing, block
+/// and braille glyphs, for terminals that can't show them (e.g. a VT100 or
+/// a non-UTF-8 locale over SSH). The buffers keep the Unicode glyphs; only
+/// the output is substituted. Defaults to on exactly when the terminal
+/// lacks the UTF8 capability.
+#[no_mangle]
+pub extern "C" fn tui_set_ascii_fallback(enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::set_ascii_fallback(&mut ctx, enabled != 0);
+        Ok(0)
+    })
+}
 
-        // Clear the error
-        tui_clear_error();
-        let ptr_after_clear = tui_get_last_error();
-        assert!(
-            ptr_after_clear.is_null(),
-            "Error pointer should be null after clear"
-        );
+/// Temporarily hand the terminal back (leave the alternate screen, disable
+/// raw mode), e.g. to run an external editor. The tree and all state are
+/// kept; `tui_render` does nothing until `tui_resume`.
+#[no_mangle]
+pub extern "C" fn tui_suspend() -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::suspend(&mut ctx)?;
+        Ok(0)
+    })
+}
+
+/// Re-enter the alternate screen and raw mode after `tui_suspend`, then
+/// repaint the full screen.
+#[no_mangle]
+pub extern "C" fn tui_resume() -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        render::resume(&mut ctx)?;
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// 4.18 Accessibility (ADR-T23)
+// ============================================================================
 
+#[no_mangle]
+pub extern "C" fn tui_set_node_role(handle: u32, role: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let accessibility_role = types::AccessibilityRole::from_u32(role).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid accessibility role: {role}"))
+        })?;
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.role = Some(accessibility_role);
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_set_node_label(handle: u32, ptr: *const u8, len: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+
+        let text = if ptr.is_null() || len == 0 {
+            None
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            Some(
+                std::str::from_utf8(slice)
+                    .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                    .to_string(),
+            )
+        };
+
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.label = text;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_set_node_description(handle: u32, ptr: *const u8, len: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+
+        let text = if ptr.is_null() || len == 0 {
+            None
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            Some(
+                std::str::from_utf8(slice)
+                    .map_err(|_| ErrorCode::InvalidArgument.raise("Invalid UTF-8"))?
+                    .to_string(),
+            )
+        };
+
+        let node = ctx.nodes.get_mut(&handle).unwrap();
+        node.description = text;
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// 4.12 Diagnostics
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_get_last_error() -> *const std::os::raw::c_char {
+    match catch_unwind(AssertUnwindSafe(|| -> *const std::os::raw::c_char {
+        let Some(snapshot) = get_last_error_snapshot() else {
+            return std::ptr::null();
+        };
+        let Ok(cstring) = CString::new(snapshot) else {
+            return std::ptr::null();
+        };
+
+        LAST_ERROR_SNAPSHOT.with(|slot| {
+            let mut slot = slot.borrow_mut();
+            *slot = Some(cstring);
+            slot.as_ref().map_or(std::ptr::null(), |s| {
+                s.as_ptr() as *const std::os::raw::c_char
+            })
+        })
+    })) {
+        Ok(ptr) => ptr,
+        Err(_) => {
+            LAST_ERROR_SNAPSHOT.with(|slot| {
+                *slot.borrow_mut() = None;
+            });
+            std::ptr::null()
+        }
+    }
+}
+
+/// Machine-readable kind of the calling thread's last error (see
+/// `types::ErrorCode`): 0 after a successful call, otherwise a stable code
+/// to branch on instead of parsing `tui_get_last_error()`.
+#[no_mangle]
+pub extern "C" fn tui_get_last_error_code() -> i32 {
+    catch_unwind(|| get_last_error_code() as i32).unwrap_or(types::ErrorCode::Panic as i32)
+}
+
+#[no_mangle]
+pub extern "C" fn tui_clear_error() {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        clear_last_error();
+        LAST_ERROR_SNAPSHOT.with(|slot| {
+            *slot.borrow_mut() = None;
+        });
+    }));
+}
+
+#[no_mangle]
+pub extern "C" fn tui_set_debug(enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.debug_mode = enabled != 0;
+        Ok(0)
+    })
+}
+
+/// Install a host logger, or remove it with a null `cb`. It receives every
+/// failed call's error (`log_level::ERROR`) and internal warnings, plus
+/// debug messages while `tui_set_debug` is on, which then no longer go to
+/// stderr. Process-wide: it may be set before `tui_init` and outlives
+/// `tui_shutdown`.
+#[no_mangle]
+pub extern "C" fn tui_set_log_callback(
+    cb: Option<context::LogCallback>,
+    user_data: *mut std::ffi::c_void,
+) -> i32 {
+    ffi_wrap(|| {
+        context::set_log_callback(cb, user_data);
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_get_perf_counter(counter_id: u32) -> u64 {
+    catch_unwind(AssertUnwindSafe(|| -> u64 {
+        let ctx = match context_read() {
+            Ok(c) => c,
+            Err(_) => return 0,
+        };
+        match counter_id {
+            0 => ctx.perf_layout_us,
+            1 => ctx.perf_render_us,
+            2 => ctx.perf_diff_cells as u64,
+            3 => ctx.event_buffer.len() as u64,
+            4 => ctx.nodes.len() as u64,
+            5 => ctx.nodes.values().filter(|n| n.dirty).count() as u64,
+            6 => ctx.animations.len() as u64,
+            7 => ctx.perf_write_bytes_estimate,
+            8 => ctx.perf_write_runs as u64,
+            9 => ctx.perf_style_deltas as u64,
+            10 => ctx.perf_text_parse_us,
+            11 => ctx.perf_text_wrap_us,
+            12 => ctx.perf_text_cache_hits as u64,
+            13 => ctx.perf_text_cache_misses as u64,
+            // v4 additions (TechSpec §4.5)
+            14 => ctx
+                .nodes
+                .values()
+                .filter_map(|n| n.transcript_state.as_ref())
+                .map(|t| t.blocks.len() as u64)
+                .sum(),
+            15 => ctx
+                .nodes
+                .values()
+                .filter_map(|n| n.transcript_state.as_ref())
+                .map(|t| t.viewport_rows as u64)
+                .sum(),
+            16 => ctx
+                .nodes
+                .values()
+                .filter_map(|n| n.transcript_state.as_ref())
+                .map(|t| t.unread_count as u64)
+                .sum(),
+            17 => ctx.debug_traces.iter().map(|d| d.len() as u64).sum(),
+            18 => ctx
+                .nodes
+                .values()
+                .filter_map(|n| n.transcript_state.as_ref())
+                .filter(|t| t.tail_attached)
+                .count() as u64,
+            19 => ctx.perf_anim_advance_us,
+            20 => ctx.perf_anim_advanced as u64,
+            21 => ctx.perf_frame_interval_us,
+            _ => 0,
+        }
+    }))
+    .unwrap_or_default()
+}
+
+/// Fill `out` with layout/render timings and tree/animation/event counts in
+/// one crossing. Equivalent to perf counters 0-6.
+#[no_mangle]
+pub extern "C" fn tui_get_stats(out: *mut types::TuiStats) -> i32 {
+    ffi_wrap(|| {
+        if out.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("stats output pointer is null"));
+        }
+        let ctx = context_read()?;
+        unsafe {
+            *out = ctx.stats();
+        }
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_free_string(_ptr: *const u8) {
+    // In the current implementation, strings are either context-owned
+    // (get_last_error) or caller-provides-buffer (get_content).
+    // This function is reserved for future use when the native core
+    // allocates strings that the host must free.
+}
+
+// ============================================================================
+// 4.13 Debug and Devtools (ADR-T34, TechSpec §4.3.3)
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_debug_set_overlay(flags: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.debug_overlay_flags = flags;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_debug_set_trace_flags(flags: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.debug_trace_flags = flags;
+        Ok(0)
+    })
+}
+
+/// Return the byte length of the current debug snapshot JSON.
+/// Returns -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_debug_get_snapshot_len() -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        let json = devtools::build_snapshot_json(&ctx)?;
+        Ok(json.len() as i32)
+    })
+}
+
+/// Copy the current debug snapshot JSON into caller-provided buffer.
+/// Returns the number of bytes written, or -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_debug_get_snapshot(buffer: *mut u8, buffer_len: u32) -> i32 {
+    ffi_wrap(|| {
+        if buffer.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null buffer pointer"));
+        }
+        let ctx = context_read()?;
+        let json = devtools::build_snapshot_json(&ctx)?;
+        let bytes = json.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+        }
+        Ok(copy_len as i32)
+    })
+}
+
+/// Return the byte length of a trace stream JSON for the given kind.
+/// Returns -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_debug_get_trace_len(kind: u8) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        let json = devtools::build_trace_json(&ctx, kind)?;
+        Ok(json.len() as i32)
+    })
+}
+
+/// Copy a trace stream JSON for the given kind into caller-provided buffer.
+/// Returns the number of bytes written, or -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_debug_get_trace(kind: u8, buffer: *mut u8, buffer_len: u32) -> i32 {
+    ffi_wrap(|| {
+        if buffer.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null buffer pointer"));
+        }
+        let ctx = context_read()?;
+        let json = devtools::build_trace_json(&ctx, kind)?;
+        let bytes = json.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+        }
+        Ok(copy_len as i32)
+    })
+}
+
+/// Return the byte length of the layout explanation JSON for `handle`.
+/// Returns -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_explain_layout_len(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let json = devtools::build_layout_explain_json(&ctx, handle)?;
+        Ok(json.len() as i32)
+    })
+}
+
+/// Copy a JSON explanation of `handle`'s last computed size into
+/// caller-provided buffer: requested/min/max/available/resolved per axis,
+/// flex factors, and the constraints that decided the result (`active`).
+/// Returns the number of bytes written, or -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_explain_layout(handle: u32, buffer: *mut u8, buffer_len: u32) -> i32 {
+    ffi_wrap(|| {
+        if buffer.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null buffer pointer"));
+        }
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let json = devtools::build_layout_explain_json(&ctx, handle)?;
+        let bytes = json.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+        }
+        Ok(copy_len as i32)
+    })
+}
+
+/// Enable the post-mortem frame log with room for `capacity` renders
+/// (max 10000). 0 (the default) disables it and drops recorded frames.
+#[no_mangle]
+pub extern "C" fn tui_set_frame_log(capacity: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        devtools::set_frame_log_capacity(&mut ctx, capacity);
+        Ok(0)
+    })
+}
+
+/// Return the byte length of the frame log text.
+/// Returns -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_get_frame_log_len() -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        Ok(devtools::build_frame_log_text(&ctx).len() as i32)
+    })
+}
+
+/// Copy the frame log text (one line per render, oldest first) into
+/// caller-provided buffer. Returns the number of bytes written, or -1 on error.
+#[no_mangle]
+pub extern "C" fn tui_dump_frame_log(buffer: *mut u8, buffer_len: u32) -> i32 {
+    ffi_wrap(|| {
+        if buffer.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null buffer pointer"));
+        }
+        let ctx = context_read()?;
+        let text = devtools::build_frame_log_text(&ctx);
+        let bytes = text.as_bytes();
+        let copy_len = bytes.len().min(buffer_len as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, copy_len);
+        }
+        Ok(copy_len as i32)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_debug_clear_traces() -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        devtools::clear_traces(&mut ctx);
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// 4.19 Threaded Render Experiment (ADR-T31, TASK-H1)
+// ============================================================================
+
+#[cfg(feature = "threaded-render")]
+#[no_mangle]
+pub extern "C" fn tui_threaded_render_start() -> i32 {
+    ffi_wrap(|| {
+        // This is an experimental entry point. In a full implementation,
+        // it would spawn the background render thread and switch `tui_render()`
+        // to snapshot-dispatch mode. For the prototype, we validate that the
+        // threaded_render module compiles and the snapshot protocol works.
+        let mut ctx = context_write()?;
+        let snapshot = threaded_render::create_snapshot(&mut ctx)?;
+        ctx.debug_log(&format!(
+            "threaded_render: snapshot created with {} nodes, root={:?}",
+            snapshot.nodes.len(),
+            snapshot.root,
+        ));
+        Ok(0)
+    })
+}
+
+#[cfg(feature = "threaded-render")]
+#[no_mangle]
+pub extern "C" fn tui_threaded_render_stop() -> i32 {
+    ffi_wrap(|| {
+        // In the prototype, this is a no-op that confirms the FFI symbol exists
+        // and the rollback path is wired. A full implementation would join the
+        // render thread and switch back to synchronous mode.
+        let ctx = context_read()?;
+        ctx.debug_log("threaded_render: stop (synchronous mode restored)");
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// Transcript Widget FFI (ADR-T32)
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_append_block(
+    handle: u32,
+    block_id: u64,
+    kind: u8,
+    role: u8,
+    ptr: *const u8,
+    len: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let k = types::TranscriptBlockKind::from_u8(kind).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid block kind: {kind}"))
+        })?;
+        let content = if ptr.is_null() {
+            ""
+        } else {
+            let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(bytes)
+                .map_err(|e| ErrorCode::InvalidArgument.raise(format!("Invalid UTF-8: {e}")))?
+        };
+        let mut content = Cow::Borrowed(content);
+        if sanitizes_content(&ctx, handle) {
+            if let Cow::Owned(clean) = text_utils::strip_control_chars(&content, true) {
+                content = Cow::Owned(clean);
+            }
+        }
+        transcript::append_block(&mut ctx, handle, block_id, k, role, &content)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_patch_block(
+    handle: u32,
+    block_id: u64,
+    patch_mode: u8,
+    ptr: *const u8,
+    len: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let content = if ptr.is_null() {
+            ""
+        } else {
+            let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+            std::str::from_utf8(bytes)
+                .map_err(|e| ErrorCode::InvalidArgument.raise(format!("Invalid UTF-8: {e}")))?
+        };
+        let mut content = Cow::Borrowed(content);
+        if sanitizes_content(&ctx, handle) {
+            if let Cow::Owned(clean) = text_utils::strip_control_chars(&content, true) {
+                content = Cow::Owned(clean);
+            }
+        }
+        transcript::patch_block(&mut ctx, handle, block_id, patch_mode, &content)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_finish_block(handle: u32, block_id: u64) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::finish_block(&mut ctx, handle, block_id)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_set_parent(handle: u32, block_id: u64, parent_id: u64) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::set_parent(&mut ctx, handle, block_id, parent_id)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_set_collapsed(handle: u32, block_id: u64, collapsed: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::set_collapsed(&mut ctx, handle, block_id, collapsed != 0)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_set_hidden(handle: u32, block_id: u64, hidden: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::set_hidden(&mut ctx, handle, block_id, hidden != 0)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_jump_to_block(handle: u32, block_id: u64, align: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::jump_to_block(&mut ctx, handle, block_id, align)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_jump_to_unread(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::jump_to_unread(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_set_follow_mode(handle: u32, mode: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let m = types::FollowMode::from_u8(mode).ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!("Invalid follow mode: {mode}"))
+        })?;
+        transcript::set_follow_mode(&mut ctx, handle, m)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_get_follow_mode(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let mode = transcript::get_follow_mode(&ctx, handle)?;
+        Ok(mode as i32)
+    })
+}
+
+/// Set the foreground color for a specific transcript role.
+/// role: 0=system, 1=user, 2=assistant, 3=tool, 4=reasoning.
+/// color: 0 = inherit node default, or 0x01RRGGBB for RGB color.
+#[no_mangle]
+pub extern "C" fn tui_transcript_set_role_color(handle: u32, role: u8, color: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        let node = ctx
+            .nodes
+            .get_mut(&handle)
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+        if node.node_type != types::NodeType::Transcript {
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Transcript widget")));
+        }
+        let state = node.transcript_state.as_mut().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+        })?;
+        if (role as usize) >= state.role_colors.len() {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid role index: {role}")));
+        }
+        state.role_colors[role as usize] = color;
+        node.dirty = true;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+/// Clear all blocks from a Transcript widget.
+pub extern "C" fn tui_transcript_clear(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::clear_blocks(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_mark_read(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        transcript::mark_read(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_transcript_get_unread_count(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let count = transcript::get_unread_count(&ctx, handle)?;
+        Ok(count as i32)
+    })
+}
+
+// ============================================================================
+// SplitPane FFI (ADR-T35)
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_set_axis(handle: u32, axis: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        splitpane::set_axis(&mut ctx, handle, axis)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_set_ratio(handle: u32, ratio: u16) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        splitpane::set_ratio(&mut ctx, handle, ratio)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_get_ratio(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let ctx = context_read()?;
+        ctx.validate_handle(handle)?;
+        let ratio = splitpane::get_ratio(&ctx, handle)?;
+        Ok(ratio as i32)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_set_min_sizes(
+    handle: u32,
+    min_primary: u16,
+    min_secondary: u16,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        splitpane::set_min_sizes(&mut ctx, handle, min_primary, min_secondary)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_set_resize_step(handle: u32, step: u16) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        splitpane::set_resize_step(&mut ctx, handle, step)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_splitpane_set_resizable(handle: u32, enabled: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        ctx.validate_handle(handle)?;
+        splitpane::set_resizable(&mut ctx, handle, enabled != 0)?;
+        Ok(0)
+    })
+}
+
+// ============================================================================
+// Native Text Substrate FFI (ADR-T37, TechSpec §4.4 `text_buffer`, `text_view`)
+// ============================================================================
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_create() -> u32 {
+    ffi_wrap_handle(|| {
+        let mut ctx = context_write()?;
+        text_buffer::create(&mut ctx)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_destroy(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::destroy(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_replace_range(
+    handle: u32,
+    start_byte: u32,
+    end_byte: u32,
+    ptr: *const u8,
+    len: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let payload = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
+        text_buffer::replace_range(
+            &mut ctx,
+            handle,
+            start_byte as usize,
+            end_byte as usize,
+            payload,
+        )?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_append(handle: u32, ptr: *const u8, len: u32) -> i32 {
+    ffi_wrap(|| {
+        let payload = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
+        text_buffer::append(&mut ctx, handle, payload)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_get_epoch(handle: u32) -> u64 {
+    ffi_wrap_u64(|| {
+        let ctx = context_read()?;
+        let buf = ctx.text_buffers.get(&handle).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+        })?;
+        Ok(buf.epoch())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_get_byte_len(handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let ctx = context_read()?;
+        let buf = ctx.text_buffers.get(&handle).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+        })?;
+        usize_to_u32_or_err(buf.byte_len(), "byte_len")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_get_line_count(handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let ctx = context_read()?;
+        let buf = ctx.text_buffers.get(&handle).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+        })?;
+        usize_to_u32_or_err(buf.line_count(), "line_count")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_set_style_span(
+    handle: u32,
+    start_byte: u32,
+    end_byte: u32,
+    fg: u32,
+    bg: u32,
+    attrs: u8,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::set_style_span(
+            &mut ctx,
+            handle,
+            start_byte as usize,
+            end_byte as usize,
+            fg,
+            bg,
+            attrs,
+        )?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_clear_style_spans(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::clear_style_spans(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_set_link(
+    handle: u32,
+    start_byte: u32,
+    end_byte: u32,
+    uri_ptr: *const u8,
+    uri_len: u32,
+    id_ptr: *const u8,
+    id_len: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let uri = unsafe { read_utf8_payload(uri_ptr, uri_len) }?;
+        let id = unsafe { read_utf8_payload(id_ptr, id_len) }?;
+        let id = if id_len == 0 { None } else { Some(id) };
+        let mut ctx = context_write()?;
+        text_buffer::set_link(
+            &mut ctx,
+            handle,
+            start_byte as usize,
+            end_byte as usize,
+            uri,
+            id,
+        )?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_clear_links(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::clear_links(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_set_selection(
+    handle: u32,
+    start_byte: u32,
+    end_byte: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::set_selection(&mut ctx, handle, start_byte as usize, end_byte as usize)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_clear_selection(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::clear_selection(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_set_highlight(
+    handle: u32,
+    start_byte: u32,
+    end_byte: u32,
+    kind: u8,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::set_highlight(
+            &mut ctx,
+            handle,
+            start_byte as usize,
+            end_byte as usize,
+            kind,
+        )?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_clear_highlights(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::clear_highlights(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+/// Drain the buffer's `dirty_ranges` list after a consumer has processed
+/// them. Without this drain call, `dirty_ranges` grows unbounded across
+/// the session lifetime as `replace_range` / `append` keeps appending.
+/// Returns 0 on success; negative codes per the standard error model.
+#[no_mangle]
+pub extern "C" fn tui_text_buffer_clear_dirty_ranges(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_buffer::clear_dirty_ranges(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_create(buffer_handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let mut ctx = context_write()?;
+        text_view::create(&mut ctx, buffer_handle)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_destroy(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_view::destroy(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_set_wrap(handle: u32, width: u32, mode: u8, tab_width: u8) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_view::set_wrap(&mut ctx, handle, width, mode, tab_width)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_set_viewport(
+    handle: u32,
+    rows: u32,
+    scroll_row: u32,
+    scroll_col: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_view::set_viewport(&mut ctx, handle, rows, scroll_row, scroll_col)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_set_cursor(handle: u32, byte_offset: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_view::set_cursor(&mut ctx, handle, byte_offset as usize)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_clear_cursor(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        text_view::clear_cursor(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_get_visual_line_count(handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let mut ctx = context_write()?;
+        text_view::get_visual_line_count(&mut ctx, handle)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_byte_to_visual(
+    handle: u32,
+    byte_offset: u32,
+    out_row: *mut u32,
+    out_col: *mut u32,
+) -> i32 {
+    ffi_wrap(|| {
+        if out_row.is_null() || out_col.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null out-pointer"));
+        }
+        let mut ctx = context_write()?;
+        let (row, col) = text_view::byte_to_visual(&mut ctx, handle, byte_offset as usize)?;
+        unsafe {
+            *out_row = row;
+            *out_col = col;
+        }
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_visual_to_byte(
+    handle: u32,
+    row: u32,
+    col: u32,
+    out_byte: *mut u32,
+) -> i32 {
+    ffi_wrap(|| {
+        if out_byte.is_null() {
+            return Err(ErrorCode::InvalidArgument.raise("Null out-pointer"));
+        }
+        let mut ctx = context_write()?;
+        let byte = text_view::visual_to_byte(&mut ctx, handle, row, col)?;
+        let byte_u32 = usize_to_u32_or_err(byte, "byte_offset")?;
+        unsafe {
+            *out_byte = byte_u32;
+        }
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_text_view_get_cache_epoch(handle: u32) -> u64 {
+    ffi_wrap_u64(|| {
+        let mut ctx = context_write()?;
+        text_view::get_cache_epoch(&mut ctx, handle)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_create(buffer_handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let mut ctx = context_write()?;
+        edit_buffer::create(&mut ctx, buffer_handle)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_destroy(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        edit_buffer::destroy(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_apply_op(
+    handle: u32,
+    op_kind: u8,
+    ptr: *const u8,
+    len: u32,
+    start_byte: u32,
+    end_byte: u32,
+) -> i32 {
+    ffi_wrap(|| {
+        let payload = unsafe { read_utf8_payload(ptr, len) }?;
+        let mut ctx = context_write()?;
+        match op_kind {
+            0 => {
+                let _ = edit_buffer::apply_insert(&mut ctx, handle, start_byte as usize, payload)?;
+            }
+            1 => {
+                let _ = edit_buffer::apply_delete(
+                    &mut ctx,
+                    handle,
+                    start_byte as usize,
+                    end_byte as usize,
+                )?;
+            }
+            2 => {
+                let _ = edit_buffer::apply_replace(
+                    &mut ctx,
+                    handle,
+                    start_byte as usize,
+                    end_byte as usize,
+                    payload,
+                )?;
+            }
+            _ => {
+                return Err(
+                    ErrorCode::InvalidArgument.raise(format!("Invalid edit op kind: {op_kind}"))
+                )
+            }
+        }
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_break_coalescing(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        edit_buffer::break_coalescing(&mut ctx, handle)?;
+        Ok(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_undo(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        let performed = edit_buffer::undo(&mut ctx, handle)?;
+        Ok(if performed { 1 } else { 0 })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_redo(handle: u32) -> i32 {
+    ffi_wrap(|| {
+        let mut ctx = context_write()?;
+        let performed = edit_buffer::redo(&mut ctx, handle)?;
+        Ok(if performed { 1 } else { 0 })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_can_undo(handle: u32) -> u8 {
+    ffi_wrap_u8(|| {
+        let ctx = context_read()?;
+        Ok(if edit_buffer::can_undo(&ctx, handle)? {
+            1
+        } else {
+            0
+        })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_can_redo(handle: u32) -> u8 {
+    ffi_wrap_u8(|| {
+        let ctx = context_read()?;
+        Ok(if edit_buffer::can_redo(&ctx, handle)? {
+            1
+        } else {
+            0
+        })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn tui_edit_buffer_history_len(handle: u32) -> u32 {
+    ffi_wrap_handle(|| {
+        let ctx = context_read()?;
+        let len = edit_buffer::history_len(&ctx, handle)?;
+        usize_to_u32_or_err(len, "history_len")
+    })
+}
+
+/// Whether content written to `handle` has control characters stripped.
+/// ANSI-format Text keeps its escapes; its parser drops the rest.
+fn sanitizes_content(ctx: &TuiContext, handle: u32) -> bool {
+    ctx.sanitize_content
+        && ctx
+            .nodes
+            .get(&handle)
+            .is_none_or(|n| n.content_format != types::ContentFormat::Ansi)
+}
+
+/// Strip control characters from a single-line host label (Select option,
+/// menu item, List item, tab, Table header or cell) when content sanitizing
+/// is on.
+fn sanitize_label(ctx: &TuiContext, handle: u32, mut label: String) -> String {
+    if sanitizes_content(ctx, handle) {
+        if let Cow::Owned(clean) = text_utils::strip_control_chars(&label, false) {
+            label = clean;
+        }
+    }
+    label
+}
+
+/// Read a UTF-8 payload from a (ptr, len) pair, or accept a null pointer
+/// only when `len == 0`. Used by substrate FFI mutation entry points.
+///
+/// Returns a borrowed `&str` instead of an owned `String` so substrate
+/// callees can write the bytes directly into buffer storage with one
+/// boundary copy. Previously this function allocated an owned `String`
+/// that `text_buffer::replace_range` / `append` then copied into
+/// `buf.content`, doubling the work on the hot streaming-append path.
+///
+/// # Safety
+/// Caller must ensure `ptr` is non-null when `len > 0` and remains
+/// valid for at least `len` bytes for the duration of the returned
+/// slice's use. FFI entry points satisfy this because the caller
+/// (Bun/Node FFI) keeps the buffer alive for the call.
+unsafe fn read_utf8_payload<'a>(ptr: *const u8, len: u32) -> Result<&'a str, TuiError> {
+    if len == 0 {
+        return Ok("");
+    }
+    if ptr.is_null() {
+        return Err(ErrorCode::InvalidArgument.raise("Null payload pointer"));
+    }
+    let bytes = std::slice::from_raw_parts(ptr, len as usize);
+    std::str::from_utf8(bytes)
+        .map_err(|e| ErrorCode::InvalidArgument.raise(format!("Payload is not valid UTF-8: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::ffi_test_guard;
+
+    fn content_from_handle(handle: u32) -> String {
+        let len = tui_get_content_len(handle);
+        assert!(len >= 0);
+        let mut buf = vec![0u8; len as usize + 1];
+        let written = tui_get_content(handle, buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(written, len);
+        String::from_utf8(buf[..len as usize].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_get_last_error_snapshot_round_trip() {
+        let _guard = ffi_test_guard();
+
+        // Initialize a headless context for testing
+        tui_init_headless(80, 24);
+
+        // Set an error via an operation that will fail (invalid handle)
+        let result = tui_destroy_node(999);
+        assert_eq!(result, -1);
+
+        // Get the error pointer
+        let ptr = tui_get_last_error();
+        assert!(!ptr.is_null(), "Error pointer should not be null");
+
+        // Read it as a C string snapshot.
+        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr) };
+        let error_msg = c_str.to_str().expect("Error should be valid UTF-8");
+        assert!(
+            error_msg.contains("Invalid handle"),
+            "Expected error about invalid handle, got: {error_msg}"
+        );
+
+        // Clear the error
+        tui_clear_error();
+        let ptr_after_clear = tui_get_last_error();
+        assert!(
+            ptr_after_clear.is_null(),
+            "Error pointer should be null after clear"
+        );
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_get_stats_matches_perf_counters() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+
+        let root = tui_create_node(0);
+        tui_set_root(root);
+        tui_create_node(1);
+        tui_render();
+
+        let mut stats = types::TuiStats::default();
+        assert_eq!(tui_get_stats(&mut stats), 0);
+        assert_eq!(stats.layout_us, tui_get_perf_counter(0));
+        assert_eq!(stats.render_us, tui_get_perf_counter(1));
+        assert_eq!(stats.diff_cells as u64, tui_get_perf_counter(2));
+        assert_eq!(stats.event_buffer_len as u64, tui_get_perf_counter(3));
+        assert_eq!(stats.node_count, 2);
+        assert_eq!(stats.dirty_count as u64, tui_get_perf_counter(5));
+        assert_eq!(stats.animation_count, 0);
+
+        assert_eq!(tui_get_stats(std::ptr::null_mut()), -1);
+        assert_eq!(std::mem::size_of::<types::TuiStats>(), 40);
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_headless_resize_reflows_through_event_pipeline() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+
+        let root = tui_create_node(0);
+        tui_set_root(root);
+        tui_set_layout_dimension(root, 0, 100.0, 2); // width 100%
+        tui_render();
+
+        assert_eq!(tui_headless_resize(100, 30), 0);
+        assert_eq!(tui_read_input(0), 1);
+        let mut event = TuiEvent::none();
+        assert_eq!(tui_next_event(&mut event), 1);
+        assert_eq!(event.event_type, types::TuiEventType::Resize as u32);
+        assert_eq!(event.data[..2], [100, 30]);
+
+        tui_render();
+        let mut w = 0;
+        tui_get_layout(
+            root,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut w,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(w, 100);
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_headless_inject_drives_focus_and_key_events() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+
+        let root = tui_create_node(0);
+        tui_set_root(root);
+        tui_set_layout_dimension(root, 0, 100.0, 2);
+        tui_set_layout_dimension(root, 1, 100.0, 2);
+        let input = tui_create_node(2); // Input
+        tui_append_child(root, input);
+        tui_set_layout_dimension(input, 0, 10.0, 1);
+        tui_set_layout_dimension(input, 1, 1.0, 1);
+        tui_render();
+
+        // Click focuses the input, then a typed character edits it.
+        assert_eq!(tui_headless_inject_mouse(0, 0, 0, 0), 0);
+        assert_eq!(tui_headless_inject_key('a' as u32, 0, 'a' as u32), 0);
+        assert!(tui_read_input(0) > 0);
+        assert_eq!(tui_get_focused(), input);
+
+        let mut buf = [0u8; 8];
+        let len = tui_get_content(input, buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(&buf[..len as usize], b"a");
+
+        assert_eq!(tui_headless_inject_key(0, 0, 0xD800), -1);
+
+        tui_shutdown();
+    }
+
+    #[derive(Default)]
+    struct CallbackCapture {
+        cells: Vec<types::TuiCellUpdate>,
+        flushes: u32,
+    }
+
+    extern "C" fn capture_diff(
+        updates: *const types::TuiCellUpdate,
+        len: u32,
+        user_data: *mut std::ffi::c_void,
+    ) {
+        let capture = unsafe { &mut *(user_data as *mut CallbackCapture) };
+        let updates = unsafe { std::slice::from_raw_parts(updates, len as usize) };
+        capture.cells.extend_from_slice(updates);
+    }
+
+    extern "C" fn capture_flush(user_data: *mut std::ffi::c_void) {
+        let capture = unsafe { &mut *(user_data as *mut CallbackCapture) };
+        capture.flushes += 1;
+    }
+
+    #[test]
+    fn test_init_with_callback_forwards_cell_diff() {
+        let _guard = ffi_test_guard();
+        let mut capture = CallbackCapture::default();
+        let user_data = &mut capture as *mut CallbackCapture as *mut std::ffi::c_void;
+        assert_eq!(tui_init_with_callback(10, 2, None, None, user_data), -1);
+        assert_eq!(
+            tui_init_with_callback(10, 2, Some(capture_diff), Some(capture_flush), user_data),
+            0
+        );
+
+        let root = tui_create_node(1); // Text
+        tui_set_root(root);
+        tui_set_layout_dimension(root, 0, 10.0, 1);
+        tui_set_layout_dimension(root, 1, 1.0, 1);
+        tui_set_content(root, b"hi".as_ptr(), 2);
+        tui_render();
+
+        assert_eq!(capture.flushes, 1);
+        let at = |x: u16| capture.cells.iter().find(|c| c.x == x && c.y == 0);
+        assert_eq!(at(0).map(|c| c.codepoint), Some('h' as u32));
+        assert_eq!(at(1).map(|c| c.codepoint), Some('i' as u32));
+
+        // The callback backend accepts host-driven resizes.
+        assert_eq!(tui_headless_resize(20, 4), 0);
+        let (mut w, mut h) = (0, 0);
+        tui_get_terminal_size(&mut w, &mut h);
+        assert_eq!((w, h), (20, 4));
+
+        tui_shutdown();
+    }
+
+    /// Calls back into the FFI from inside a frame, as a misbehaving host
+    /// callback would, and records what it saw.
+    extern "C" fn reentrant_diff(
+        _updates: *const types::TuiCellUpdate,
+        _len: u32,
+        user_data: *mut std::ffi::c_void,
+    ) {
+        let seen = unsafe { &mut *(user_data as *mut Option<(i32, String)>) };
+        let code = tui_mark_dirty(1);
+        let error = unsafe { std::ffi::CStr::from_ptr(tui_get_last_error()) };
+        *seen = Some((code, error.to_string_lossy().into_owned()));
+    }
+
+    #[test]
+    fn test_reentrant_ffi_call_fails_instead_of_deadlocking() {
+        let _guard = ffi_test_guard();
+        let mut seen: Option<(i32, String)> = None;
+        let user_data = &mut seen as *mut Option<(i32, String)> as *mut std::ffi::c_void;
+        assert_eq!(
+            tui_init_with_callback(4, 1, Some(reentrant_diff), None, user_data),
+            0
+        );
+        let root = tui_create_node(NodeType::Box as u8);
+        tui_set_root(root);
+        tui_set_layout_dimension(root, 0, 4.0, 1);
+        tui_set_layout_dimension(root, 1, 1.0, 1);
+        tui_set_style_color(root, 1, 0x01FF0000);
+
+        assert_eq!(tui_render(), 0);
+        let (code, error) = seen.take().expect("callback ran");
+        assert_eq!(code, -1);
+        assert!(error.contains("Reentrant context access"), "{error}");
+
+        // The outer call released the context normally.
+        assert_eq!(tui_get_node_count(), 1);
+        assert_eq!(tui_mark_dirty(root), 0);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_set_mouse_capture_toggles_events_and_capability() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+        let mouse = terminal_capabilities::terminal_capability::MOUSE as u32;
+        assert_ne!(tui_get_capabilities() & mouse, 0);
+
+        assert_eq!(tui_set_mouse_capture(0), 0);
+        assert_eq!(tui_get_capabilities() & mouse, 0);
+        tui_headless_inject_mouse(1, 1, 0, 0);
+        assert_eq!(tui_read_input(0), 0);
+
+        assert_eq!(tui_set_mouse_capture(1), 0);
+        assert_ne!(tui_get_capabilities() & mouse, 0);
+        tui_headless_inject_mouse(1, 1, 0, 0);
+        assert_eq!(tui_read_input(0), 1);
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_color_mode_defaults_to_capabilities_and_validates() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+        // Headless reports truecolor.
+        assert_eq!(tui_get_color_mode(), 0);
+        assert_eq!(tui_set_color_mode(2), 0);
+        assert_eq!(tui_get_color_mode(), 2);
+        assert_eq!(tui_set_color_mode(3), -1);
+        assert_eq!(tui_get_color_mode(), 2);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_set_content_strips_control_characters() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+        let text = tui_create_node(1); // Text
+        let input = tui_create_node(2); // Input
+
+        let read = |handle: u32| {
+            let mut buf = [0u8; 64];
+            let len = tui_get_content(handle, buf.as_mut_ptr(), buf.len() as u32);
+            String::from_utf8(buf[..len as usize].to_vec()).unwrap()
+        };
+
+        let raw = b"red\x1b[31m\x07\r\nline";
+        tui_set_content(text, raw.as_ptr(), raw.len() as u32);
+        assert_eq!(read(text), "red[31m\nline");
+        tui_set_content(input, raw.as_ptr(), raw.len() as u32);
+        assert_eq!(read(input), "red[31mline");
+
+        assert_eq!(tui_set_sanitize_content(0), 0);
+        tui_set_content(text, raw.as_ptr(), raw.len() as u32);
+        assert_eq!(read(text).as_bytes(), raw);
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_widget_labels_strip_control_characters() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(40, 12), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        assert_eq!(tui_set_root(root), 0);
+        tui_set_layout_dimension(root, 0, 40.0, 1);
+        tui_set_layout_dimension(root, 1, 12.0, 1);
+        tui_set_layout_flex(root, 0, 1); // column
+        let mut widgets = Vec::new();
+        for node_type in [
+            NodeType::Select,
+            NodeType::List,
+            NodeType::Tabs,
+            NodeType::Table,
+            NodeType::Transcript,
+        ] {
+            let handle = tui_create_node(node_type as u8);
+            assert_eq!(tui_append_child(root, handle), 0);
+            tui_set_layout_dimension(handle, 1, 2.0, 1);
+            widgets.push(handle);
+        }
+        let [select, list, tabs, table, transcript] = widgets[..] else {
+            unreachable!()
+        };
+
+        let raw = b"a\x1b[2Jb\x07";
+        let len = raw.len() as u32;
+        assert_eq!(tui_select_add_option(select, raw.as_ptr(), len), 0);
+        assert_eq!(tui_select_set_selected(select, 0), 0);
+        assert_eq!(tui_list_add_item(list, raw.as_ptr(), len), 0);
+        assert_eq!(tui_tabs_add_tab(tabs, raw.as_ptr(), len), 0);
+        assert_eq!(tui_table_set_column_count(table, 1), 0);
+        assert_eq!(tui_table_set_column(table, 0, raw.as_ptr(), len, 10, 0), 0);
+        assert_eq!(tui_table_insert_row(table, 0), 0);
+        assert_eq!(tui_table_set_cell(table, 0, 0, raw.as_ptr(), len), 0);
+        assert_eq!(
+            tui_transcript_append_block(transcript, 1, 0, 0, raw.as_ptr(), len),
+            0
+        );
+        assert_eq!(tui_render(), 0);
+
+        let ctx = context_read().unwrap();
+        assert_eq!(ctx.nodes[&select].options[0].as_str(), "a[2Jb");
+        assert!(
+            ctx.back_buffer
+                .cells
+                .iter()
+                .all(|cell| !cell.ch.is_control()),
+            "a control character reached the screen"
+        );
+        drop(ctx);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_menu_item_labels_strip_control_characters() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(30, 8), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        assert_eq!(tui_set_root(root), 0);
+        let menu = tui_menu_create();
+        let raw = b"Copy\x1b]0;pwned\x07";
+        assert_eq!(tui_menu_add_item(menu, raw.as_ptr(), raw.len() as u32), 0);
+        assert_eq!(tui_menu_show_at(menu, 1, 1), 0);
+        assert_eq!(tui_render(), 0);
+
+        let ctx = context_read().unwrap();
+        assert_eq!(ctx.nodes[&menu].options[0].as_str(), "Copy]0;pwned");
+        assert!(
+            ctx.back_buffer
+                .cells
+                .iter()
+                .all(|cell| !cell.ch.is_control()),
+            "a control character reached the screen"
+        );
+        drop(ctx);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_text_find_and_highlights_clear_on_content_change() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let text = tui_create_node(NodeType::Text as u8);
+        let content = "warn: disk; WARN: cpu";
+        assert_eq!(
+            tui_set_content(text, content.as_ptr(), content.len() as u32),
+            0
+        );
+
+        let pattern = "warn";
+        let mut out = [types::TuiMatchRange { start: 0, end: 0 }; 4];
+        let found = tui_text_find(
+            text,
+            pattern.as_ptr(),
+            pattern.len() as u32,
+            0,
+            out.as_mut_ptr(),
+            out.len() as u32,
+        );
+        assert_eq!(found, 1);
+        assert_eq!(out[0], types::TuiMatchRange { start: 0, end: 4 });
+        let found = tui_text_find(
+            text,
+            pattern.as_ptr(),
+            pattern.len() as u32,
+            1,
+            out.as_mut_ptr(),
+            1,
+        );
+        assert_eq!(found, 2, "total count is reported even past `max`");
+
+        let ranges = [
+            types::TuiMatchRange { start: 0, end: 4 },
+            types::TuiMatchRange { start: 12, end: 16 },
+        ];
+        assert_eq!(tui_text_set_highlights(text, ranges.as_ptr(), 2), 0);
+        assert_eq!(
+            tui_set_content(text, content.as_ptr(), content.len() as u32),
+            0
+        );
+        {
+            let ctx = context_read().unwrap();
+            assert_eq!(ctx.nodes[&text].text_highlights, vec![(0, 4), (12, 16)]);
+        }
+        let other = "fresh";
+        assert_eq!(tui_set_content(text, other.as_ptr(), other.len() as u32), 0);
+        {
+            let ctx = context_read().unwrap();
+            assert!(ctx.nodes[&text].text_highlights.is_empty());
+        }
+
+        let boxed = tui_create_node(NodeType::Box as u8);
+        assert_eq!(tui_text_set_highlights(boxed, ranges.as_ptr(), 2), -1);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_select_filter_keeps_full_list_selection() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let select = tui_create_node(NodeType::Select as u8);
+        for label in ["Red", "Green", "Blue", "Greenish"] {
+            assert_eq!(
+                tui_select_add_option(select, label.as_ptr(), label.len() as u32),
+                0
+            );
+        }
+        assert_eq!(tui_select_set_selected(select, 0), 0);
+
+        let filter = "GREEN";
+        assert_eq!(
+            tui_select_set_filter(select, filter.as_ptr(), filter.len() as u32),
+            0
+        );
+        // "Red" is hidden, so the selection snaps to the first match.
+        assert_eq!(tui_select_get_selected(select), 1);
+        assert_eq!(tui_select_set_selected(select, 3), 0);
+
+        assert_eq!(tui_select_set_filter(select, std::ptr::null(), 0), 0);
+        assert_eq!(tui_select_get_selected(select), 3);
+        assert_eq!(tui_select_get_count(select), 4);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_content_append_and_truncate_lines() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let text = tui_create_node(NodeType::Text as u8);
+        for line in ["one\n", "two\n", "three\n", "four\n"] {
+            assert_eq!(
+                tui_content_append(text, line.as_ptr(), line.len() as u32),
+                0
+            );
+        }
+        {
+            let mut ctx = context_write().unwrap();
+            assert_eq!(ctx.nodes[&text].content, "one\ntwo\nthree\nfour\n");
+            // "three" in graphemes: 8..13.
+            ctx.nodes.get_mut(&text).unwrap().text_highlights = vec![(0, 3), (8, 13)];
+        }
+        assert_eq!(tui_content_truncate_lines(text, 2), 2);
+        assert_eq!(tui_content_truncate_lines(text, 2), 0);
+        {
+            let ctx = context_read().unwrap();
+            assert_eq!(ctx.nodes[&text].content, "three\nfour\n");
+            assert_eq!(ctx.nodes[&text].text_highlights, vec![(0, 5)]);
+        }
+
+        let area = tui_create_node(NodeType::TextArea as u8);
+        let initial = "a\nb\nc";
+        assert_eq!(
+            tui_set_content(area, initial.as_ptr(), initial.len() as u32),
+            0
+        );
+        let more = "\nd";
+        assert_eq!(
+            tui_content_append(area, more.as_ptr(), more.len() as u32),
+            0
+        );
+        assert_eq!(tui_textarea_set_cursor(area, 3, 1), 0);
+        assert_eq!(tui_content_truncate_lines(area, 2), 2);
+        let mut buf = [0u8; 16];
+        let n = tui_get_content(area, buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(&buf[..n as usize], b"c\nd");
+        let (mut row, mut col) = (0u32, 0u32);
+        assert_eq!(tui_textarea_get_cursor(area, &mut row, &mut col), 0);
+        assert_eq!((row, col), (1, 1));
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_textarea_max_lines_follows_tail_only_at_bottom() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let area = tui_create_node(NodeType::TextArea as u8);
+        assert_eq!(tui_set_root(area), 0);
+        assert_eq!(tui_set_layout_dimension(area, 0, 20.0, 1), 0);
+        assert_eq!(tui_set_layout_dimension(area, 1, 3.0, 1), 0);
+        let initial = "l0\nl1\nl2\nl3\nl4";
+        assert_eq!(
+            tui_set_content(area, initial.as_ptr(), initial.len() as u32),
+            0
+        );
+        assert_eq!(tui_textarea_set_max_lines(area, 5), 0);
+        assert_eq!(tui_render(), 0);
+
+        // Viewport showing the last line follows the tail.
+        context_write()
+            .unwrap()
+            .nodes
+            .get_mut(&area)
+            .unwrap()
+            .textarea_view_row = 2;
+        let more = "\nl5\nl6";
+        assert_eq!(
+            tui_content_append(area, more.as_ptr(), more.len() as u32),
+            0
+        );
+        {
+            let ctx = context_read().unwrap();
+            let node = &ctx.nodes[&area];
+            assert_eq!(node.content, "l2\nl3\nl4\nl5\nl6");
+            assert_eq!(node.textarea_view_row, 2);
+        }
+
+        // Scrolled up into history: the same line stays in view.
+        context_write()
+            .unwrap()
+            .nodes
+            .get_mut(&area)
+            .unwrap()
+            .textarea_view_row = 1;
+        let more = "\nl7";
+        assert_eq!(
+            tui_content_append(area, more.as_ptr(), more.len() as u32),
+            0
+        );
+        {
+            let ctx = context_read().unwrap();
+            let node = &ctx.nodes[&area];
+            assert_eq!(node.content, "l3\nl4\nl5\nl6\nl7");
+            assert_eq!(node.textarea_view_row, 0);
+        }
+
+        let text = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_textarea_set_max_lines(text, 5), -1);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_content_grapheme_edits_clamp_and_shift_cursor() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        let initial = "héllo 👍🏽!";
+        assert_eq!(
+            tui_set_content(input, initial.as_ptr(), initial.len() as u32),
+            0
+        );
+        assert_eq!(tui_input_set_cursor(input, 6), 0);
+
+        let word = "big ";
+        assert_eq!(
+            tui_content_insert_at(input, 0, word.as_ptr(), word.len() as u32),
+            0
+        );
+        assert_eq!(content_from_handle(input), "big héllo 👍🏽!");
+        assert_eq!(tui_input_get_cursor(input), 10);
+
+        // The emoji with its skin-tone modifier is one grapheme.
+        let thumbs = "👎";
+        assert_eq!(
+            tui_content_replace_range(input, 10, 11, thumbs.as_ptr(), thumbs.len() as u32),
+            0
+        );
+        assert_eq!(content_from_handle(input), "big héllo 👎!");
+        assert_eq!(tui_input_get_cursor(input), 10);
+
+        // Out-of-range bounds clamp instead of failing.
+        assert_eq!(tui_content_delete_range(input, 9, 999), 0);
+        assert_eq!(content_from_handle(input), "big héllo");
+        assert_eq!(tui_input_get_cursor(input), 9);
+        assert_eq!(tui_content_delete_range(input, 50, 60), 0);
+
+        let mut changes = 0;
+        let mut event = TuiEvent::none();
+        while tui_next_event(&mut event) == 1 {
+            if event.event_type == types::TuiEventType::Change as u32 {
+                changes += 1;
+            }
+        }
+        assert_eq!(changes, 3);
+
+        let area = tui_create_node(NodeType::TextArea as u8);
+        let text = "ab\ncd";
+        assert_eq!(tui_set_content(area, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_textarea_set_cursor(area, 1, 1), 0);
+        let line = "xy\n";
+        assert_eq!(
+            tui_content_insert_at(area, 0, line.as_ptr(), line.len() as u32),
+            0
+        );
+        assert_eq!(content_from_handle(area), "xy\nab\ncd");
+        let (mut row, mut col) = (0u32, 0u32);
+        assert_eq!(tui_textarea_get_cursor(area, &mut row, &mut col), 0);
+        assert_eq!((row, col), (2, 1));
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_set_content_diffed_preserves_cursor_before_change() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        let initial = "hello world";
+        assert_eq!(
+            tui_set_content(input, initial.as_ptr(), initial.len() as u32),
+            0
+        );
+        assert_eq!(tui_input_set_cursor(input, 2), 0);
+        let next = "hello there world";
+        assert_eq!(
+            tui_set_content_diffed(input, next.as_ptr(), next.len() as u32),
+            0
+        );
+        assert_eq!(content_from_handle(input), next);
+        assert_eq!(tui_input_get_cursor(input), 2);
+
+        let area = tui_create_node(NodeType::TextArea as u8);
+        let text = "one\ntwo\nthree";
+        assert_eq!(tui_set_content(area, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_textarea_set_selection(area, 0, 0, 0, 3), 0);
+        let next = "one\ntwo\nthree\nfour";
+        assert_eq!(
+            tui_set_content_diffed(area, next.as_ptr(), next.len() as u32),
+            0
+        );
+        assert_eq!(content_from_handle(area), next);
+        let mut buf = [0u8; 8];
+        assert_eq!(tui_textarea_get_selected_text(area, buf.as_mut_ptr(), 8), 3);
+        assert_eq!(&buf[..3], b"one");
+
+        // Identical content is a no-op.
+        context_write().unwrap().nodes.get_mut(&area).unwrap().dirty = false;
+        assert_eq!(
+            tui_set_content_diffed(area, next.as_ptr(), next.len() as u32),
+            0
+        );
+        assert!(!context_read().unwrap().nodes[&area].dirty);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_get_computed_layout_all_reports_total_count() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let child = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_append_child(root, child), 0);
+        assert_eq!(tui_set_root(root), 0);
+        assert_eq!(tui_set_layout_dimension(root, 0, 30.0, 1), 0);
+        assert_eq!(tui_set_layout_dimension(root, 1, 10.0, 1), 0);
+        assert_eq!(tui_render(), 0);
+
+        assert_eq!(tui_get_computed_layout_all(std::ptr::null_mut(), 0), 2);
+        let mut out = [types::TuiNodeLayout {
+            handle: 0,
+            x: 0,
+            y: 0,
+            w: 0,
+            h: 0,
+        }; 1];
+        assert_eq!(tui_get_computed_layout_all(out.as_mut_ptr(), 1), 2);
+        assert_eq!(out[0].handle, root);
+        assert_eq!((out[0].w, out[0].h), (30, 10));
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_grid_layout_ffi() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let grid = tui_create_node(NodeType::Box as u8);
+        let a = tui_create_node(NodeType::Box as u8);
+        let b = tui_create_node(NodeType::Box as u8);
+        assert_eq!(tui_append_child(grid, a), 0);
+        assert_eq!(tui_append_child(grid, b), 0);
+        assert_eq!(tui_set_root(grid), 0);
+        assert_eq!(tui_set_layout_dimension(grid, 0, 30.0, 1), 0);
+        assert_eq!(tui_set_display(grid, 1), 0);
+        let tracks = [
+            types::TuiGridTrack {
+                kind: 2,
+                value: 1.0,
+            },
+            types::TuiGridTrack {
+                kind: 2,
+                value: 2.0,
+            },
+        ];
+        assert_eq!(tui_set_grid_template(grid, 0, tracks.as_ptr(), 2), 0);
+        assert_eq!(tui_set_grid_placement(b, 1, -1, 2, 0), 0);
+        assert_eq!(tui_set_grid_placement(b, 1, 40_000, 0, 0), -1);
+        assert_eq!(tui_render(), 0);
+
+        let (mut x, mut y, mut w, mut h) = (0, 0, 0, 0);
+        assert_eq!(tui_get_layout(a, &mut x, &mut y, &mut w, &mut h), 0);
+        assert_eq!((x, w), (0, 10));
+        assert_eq!(tui_get_layout(b, &mut x, &mut y, &mut w, &mut h), 0);
+        assert_eq!((x, w), (0, 30));
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_display_none_skips_focus_and_survives_set_visible() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(80, 24), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let panel = tui_create_node(NodeType::Box as u8);
+        let hidden_input = tui_create_node(NodeType::Input as u8);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(root), 0);
+        assert_eq!(tui_append_child(root, panel), 0);
+        assert_eq!(tui_append_child(panel, hidden_input), 0);
+        assert_eq!(tui_append_child(root, input), 0);
+        assert_eq!(tui_focus(hidden_input), 0);
+
+        assert_eq!(tui_set_display_none(panel, 1), 0);
+        assert_eq!(tui_get_focused(), input);
+        assert_eq!(tui_focus_next(), 0);
+        assert_eq!(tui_get_focused(), input);
+
+        // Toggling paint visibility must not bring the panel back into layout.
+        assert_eq!(tui_set_visible(panel, 0), 0);
+        assert_eq!(tui_set_visible(panel, 1), 0);
+        let taffy_node = context_read().unwrap().nodes[&panel].taffy_node;
+        let display = context_read()
+            .unwrap()
+            .tree
+            .style(taffy_node)
+            .unwrap()
+            .display;
+        assert_eq!(display, taffy::Display::None);
+
+        assert_eq!(tui_set_display_none(panel, 0), 0);
+        assert_eq!(tui_focus(hidden_input), 0);
+        assert_eq!(tui_get_focused(), hidden_input);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_text_transform_keeps_stored_content() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(input), 0);
+        let text = "straße";
+        assert_eq!(tui_set_content(input, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_set_text_transform(input, 1), 0);
+        assert_eq!(tui_set_text_transform(input, 9), -1);
+        assert_eq!(tui_focus(input), 0);
+        context_write()
+            .unwrap()
+            .nodes
+            .get_mut(&input)
+            .unwrap()
+            .cursor_position = 5;
+        assert_eq!(tui_render(), 0);
+
+        assert_eq!(content_from_handle(input), "straße");
+        let ctx = context_read().unwrap();
+        let row: String = (0..7)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "STRASSE");
+        // The cursor before "e" sits after both "S"s of the expanded "ß".
+        assert_ne!(
+            ctx.back_buffer.get(6, 0).unwrap().bg,
+            ctx.back_buffer.get(5, 0).unwrap().bg
+        );
+        drop(ctx);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_mask_reveal_shows_content_and_keeps_mask() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(10, 1), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(input), 0);
+        let text = "pw1";
+        assert_eq!(tui_set_content(input, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_input_set_mask(input, '*' as u32), 0);
+        let row = || -> String {
+            let ctx = context_read().unwrap();
+            (0..3)
+                .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+                .collect()
+        };
+        assert_eq!(tui_render(), 0);
+        assert_eq!(row(), "***");
+
+        let mut event = TuiEvent::none();
+        while tui_next_event(&mut event) == 1 {}
+        assert_eq!(tui_input_set_mask_revealed(input, 1), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(row(), "pw1");
+        assert_eq!(tui_input_get_mask(input), '*' as i32);
+        assert_eq!(tui_next_event(&mut event), 0, "revealing emits no Change");
+
+        assert_eq!(tui_input_set_mask_revealed(input, 0), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(row(), "***");
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_node_tag_round_trips_and_rides_on_events() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(input), 0);
+        assert_eq!(tui_get_node_tag(input), 0);
+        let tag = 0xDEAD_BEEF_0000_0042;
+        assert_eq!(tui_set_node_tag(input, tag), 0);
+        assert_eq!(tui_get_node_tag(input), tag);
+        assert_eq!(tui_set_node_tag(9999, 1), -1);
+
+        assert_eq!(tui_focus(input), 0);
+        let mut tagged = TuiTaggedEvent {
+            event: TuiEvent::none(),
+            tag: 0,
+        };
+        let mut focus_tag = None;
+        while tui_next_event_tagged(&mut tagged) == 1 {
+            if tagged.event.event_type == types::TuiEventType::FocusChange as u32 {
+                focus_tag = Some(tagged.tag);
+            }
+        }
+        assert_eq!(focus_tag, Some(tag));
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_find_by_tag_returns_matches_in_handle_order() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let a = tui_create_node(NodeType::Text as u8);
+        let b = tui_create_node(NodeType::Text as u8);
+        let c = tui_create_node(NodeType::Text as u8);
+        for child in [a, b, c] {
+            assert_eq!(tui_append_child(root, child), 0);
+        }
+        assert_eq!(tui_set_node_tag(c, 7), 0);
+        assert_eq!(tui_set_node_tag(a, 7), 0);
+        assert_eq!(tui_set_node_tag(b, 8), 0);
+
+        assert_eq!(tui_find_by_tag(7), a);
+        assert_eq!(tui_find_by_tag(8), b);
+        assert_eq!(tui_find_by_tag(9), 0);
+        assert_eq!(tui_find_by_tag(0), 0, "untagged nodes never match");
+
+        let mut out = [0u32; 1];
+        assert_eq!(tui_find_all_by_tag(7, out.as_mut_ptr(), 1), 2);
+        assert_eq!(out, [a]);
+        let mut out = [0u32; 4];
+        assert_eq!(tui_find_all_by_tag(7, out.as_mut_ptr(), 4), 2);
+        assert_eq!(&out[..2], &[a, c]);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_get_all_handles_and_root() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        assert_eq!(tui_get_root(), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let a = tui_create_node(NodeType::Text as u8);
+        let b = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_append_child(root, a), 0);
+        assert_eq!(tui_append_child(root, b), 0);
+        assert_eq!(tui_set_root(root), 0);
+        assert_eq!(tui_get_root(), root);
+        assert_eq!(tui_destroy_node(a), 0);
+
+        assert_eq!(tui_get_all_handles(std::ptr::null_mut(), 0), 2);
+        let mut out = [0u32; 1];
+        assert_eq!(tui_get_all_handles(out.as_mut_ptr(), 1), 2);
+        assert_eq!(out, [root]);
+        let mut out = [0u32; 4];
+        assert_eq!(tui_get_all_handles(out.as_mut_ptr(), 4), 2);
+        assert_eq!(&out[..2], &[root, b]);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    extern "C" fn record_walk(handle: u32, depth: u32, user_data: *mut std::ffi::c_void) -> i32 {
+        let seen = unsafe { &mut *(user_data as *mut Vec<(u32, u32)>) };
+        seen.push((handle, depth));
+        // Tag 1 stops the walk; tag 2 destroys the next-created node, which
+        // must neither deadlock nor be visited afterwards.
+        if tui_get_node_tag(handle) == 2 {
+            tui_destroy_subtree(handle + 1);
+        }
+        i32::from(tui_get_node_tag(handle) == 1)
+    }
+
+    #[test]
+    fn test_walk_subtree_visits_pre_order_and_tolerates_mutation() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let a = tui_create_node(NodeType::Box as u8);
+        let a1 = tui_create_node(NodeType::Text as u8);
+        let b = tui_create_node(NodeType::Box as u8);
+        let b1 = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_append_child(root, a), 0);
+        assert_eq!(tui_append_child(a, a1), 0);
+        assert_eq!(tui_append_child(root, b), 0);
+        assert_eq!(tui_append_child(b, b1), 0);
+
+        let mut seen: Vec<(u32, u32)> = Vec::new();
+        let user_data = &mut seen as *mut Vec<(u32, u32)> as *mut std::ffi::c_void;
+        assert_eq!(tui_walk_subtree(root, Some(record_walk), user_data), 0);
+        assert_eq!(seen, vec![(root, 0), (a, 1), (a1, 2), (b, 1), (b1, 2)]);
+
+        seen.clear();
+        assert_eq!(tui_set_node_tag(a1, 1), 0);
+        assert_eq!(tui_walk_subtree(root, Some(record_walk), user_data), 1);
+        assert_eq!(seen, vec![(root, 0), (a, 1), (a1, 2)]);
+
+        // a1 (tag 2) destroys handle a1 + 1 == b, which is still queued.
+        seen.clear();
+        assert_eq!(b, a1 + 1);
+        assert_eq!(tui_set_node_tag(a1, 2), 0);
+        assert_eq!(tui_walk_subtree(root, Some(record_walk), user_data), 0);
+        assert_eq!(seen, vec![(root, 0), (a, 1), (a1, 2)]);
+        assert_eq!(tui_get_node_type(b), -1);
+
+        assert_eq!(tui_walk_subtree(root, None, user_data), -1);
+        assert_eq!(tui_walk_subtree(9999, Some(record_walk), user_data), -1);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_numeric_input_filters_keys_and_steps_within_range() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 2), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(input), 0);
+        assert_eq!(tui_focus(input), 0);
+        assert_eq!(tui_input_set_numeric(input, 0.0, 1.0, 0.25), 0);
+        assert_eq!(tui_input_set_numeric(input, 2.0, 1.0, 1.0), -1);
+
+        let mut event = TuiEvent::none();
+        while tui_next_event(&mut event) == 1 {}
+        for ch in ['x', '0', '.', '5', '.', '-'] {
+            assert_eq!(tui_headless_inject_key(ch as u32, 0, ch as u32), 0);
+        }
+        assert_eq!(tui_headless_inject_key(types::key::UP, 0, 0), 0);
+        assert_eq!(tui_headless_inject_key(types::key::UP, 0, 0), 0);
+        assert_eq!(tui_headless_inject_key(types::key::UP, 0, 0), 0);
+        assert!(tui_read_input(0) > 0);
+        assert_eq!(content_from_handle(input), "1.00");
+
+        let mut changes = 0;
+        while tui_next_event(&mut event) == 1 {
+            if event.event_type == types::TuiEventType::Change as u32 {
+                changes += 1;
+            }
+        }
+        // "0", ".", "5", then two steps; the third step is clamped at max.
+        assert_eq!(changes, 5);
+
+        let mut value = 0.0;
+        assert_eq!(tui_input_get_numeric(input, &mut value), 0);
+        assert_eq!(value, 1.0);
+        assert_eq!(tui_headless_inject_key(types::key::DOWN, 0, 0), 0);
+        assert!(tui_read_input(0) > 0);
+        assert_eq!(content_from_handle(input), "0.75");
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_input_and_textarea_clear_reset_state() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 4), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let input = tui_create_node(NodeType::Input as u8);
+        let area = tui_create_node(NodeType::TextArea as u8);
+        assert_eq!(tui_append_child(root, input), 0);
+        assert_eq!(tui_append_child(root, area), 0);
+        assert_eq!(tui_set_root(root), 0);
+
+        let text = "hello";
+        assert_eq!(tui_set_content(input, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_input_set_cursor(input, 3), 0);
+        let text = "one\ntwo\nthree";
+        assert_eq!(tui_set_content(area, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_textarea_set_cursor(area, 2, 4), 0);
+        assert_eq!(tui_render(), 0);
+        let mut event = TuiEvent::none();
+        while tui_next_event(&mut event) == 1 {}
+
+        assert_eq!(tui_input_clear(input), 0);
+        assert_eq!(tui_textarea_clear(area), 0);
+        assert_eq!(content_from_handle(input), "");
+        assert_eq!(content_from_handle(area), "");
+        {
+            let ctx = context_read().unwrap();
+            assert_eq!(ctx.nodes[&input].cursor_position, 0);
+            let node = &ctx.nodes[&area];
+            assert_eq!((node.cursor_row, node.cursor_col), (0, 0));
+            assert_eq!((node.textarea_view_row, node.textarea_view_col), (0, 0));
+        }
+        let mut changed = Vec::new();
+        while tui_next_event(&mut event) == 1 {
+            assert_eq!(event.event_type, types::TuiEventType::Change as u32);
+            changed.push(event.target);
+        }
+        assert_eq!(changed, vec![input, area]);
+
+        // Clearing what is already empty is silent; wrong types are rejected.
+        assert_eq!(tui_input_clear(input), 0);
+        assert_eq!(tui_next_event(&mut event), 0);
+        assert_eq!(tui_input_clear(area), -1);
+        assert_eq!(tui_textarea_clear(input), -1);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_textarea_line_addressed_editing() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 4), 0);
+        let area = tui_create_node(NodeType::TextArea as u8);
+        assert_eq!(tui_set_root(area), 0);
+        let text = "alpha\nbeta\ngamma";
+        assert_eq!(tui_set_content(area, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_textarea_set_cursor(area, 2, 3), 0);
+
+        let mut buf = [0u8; 16];
+        assert_eq!(tui_textarea_get_line_len(area, 1), 4);
+        assert_eq!(tui_textarea_get_line(area, 1, buf.as_mut_ptr(), 16), 4);
+        assert_eq!(&buf[..4], b"beta");
+        assert_eq!(tui_textarea_get_line(area, 3, buf.as_mut_ptr(), 16), -1);
+
+        let line = "BETA!";
+        assert_eq!(tui_textarea_set_line(area, 1, line.as_ptr(), 5), 0);
+        assert_eq!(content_from_handle(area), "alpha\nBETA!\ngamma");
+        let line = "zero";
+        assert_eq!(tui_textarea_insert_line(area, 0, line.as_ptr(), 4), 0);
+        let line = "end";
+        assert_eq!(tui_textarea_insert_line(area, 4, line.as_ptr(), 3), 0);
+        assert_eq!(tui_textarea_insert_line(area, 9, line.as_ptr(), 3), -1);
+        assert_eq!(content_from_handle(area), "zero\nalpha\nBETA!\ngamma\nend");
+
+        assert_eq!(tui_textarea_delete_line(area, 4), 0);
+        assert_eq!(tui_textarea_delete_line(area, 0), 0);
+        assert_eq!(content_from_handle(area), "alpha\nBETA!\ngamma");
+        // The cursor stayed on "gamma" through the edits around it.
+        let (mut row, mut col) = (0, 0);
+        assert_eq!(tui_textarea_get_cursor(area, &mut row, &mut col), 0);
+        assert_eq!((row, col), (2, 3));
+
+        let text = "solo";
+        assert_eq!(tui_set_content(area, text.as_ptr(), text.len() as u32), 0);
+        assert_eq!(tui_textarea_delete_line(area, 0), 0);
+        assert_eq!(content_from_handle(area), "");
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_input_composition_previews_without_editing_content() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(10, 1), 0);
+        let input = tui_create_node(NodeType::Input as u8);
+        assert_eq!(tui_set_root(input), 0);
+        assert_eq!(tui_set_layout_dimension(input, 0, 10.0, 1), 0);
+        let text = "ab";
+        assert_eq!(tui_set_content(input, text.as_ptr(), 2), 0);
+        assert_eq!(tui_input_set_cursor(input, 1), 0);
+        assert_eq!(tui_focus(input), 0);
+        let mut event = TuiEvent::none();
+        while tui_next_event(&mut event) == 1 {}
+
+        let pending = "日本";
+        assert_eq!(
+            tui_input_set_composition(input, pending.as_ptr(), pending.len() as u32),
+            0
+        );
+        assert_eq!(tui_render(), 0);
+        {
+            let ctx = context_read().unwrap();
+            let cell = |x| ctx.back_buffer.get(x, 0).unwrap().clone();
+            assert_eq!(cell(0).ch, 'a');
+            assert!(!cell(0).attrs.contains(types::CellAttrs::UNDERLINE));
+            for (x, ch) in [(1, '日'), (3, '本')] {
+                assert_eq!(cell(x).ch, ch);
+                assert!(cell(x).attrs.contains(types::CellAttrs::UNDERLINE));
+            }
+            // The caret follows the composition.
+            assert_eq!(cell(5).ch, 'b');
+            assert_ne!(cell(5).bg, cell(0).bg);
+        }
+        assert_eq!(content_from_handle(input), "ab");
+        assert_eq!(tui_next_event(&mut event), 0);
+
+        // Committing the text edits the content and drops the preview.
+        assert_eq!(tui_headless_inject_key('x' as u32, 0, 'x' as u32), 0);
+        assert_eq!(tui_read_input(0), 1);
+        assert_eq!(content_from_handle(input), "axb");
+        assert!(context_read().unwrap().nodes[&input].composition.is_none());
+
+        assert_eq!(tui_input_set_composition(input, pending.as_ptr(), 3), 0);
+        assert_eq!(tui_input_clear_composition(input), 0);
+        assert!(context_read().unwrap().nodes[&input].composition.is_none());
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_ansi_content_keeps_escapes_and_renders_styles() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(10, 1), 0);
+        let text = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_set_root(text), 0);
+        assert_eq!(tui_set_layout_dimension(text, 0, 10.0, 1), 0);
+        assert_eq!(tui_set_layout_dimension(text, 1, 1.0, 1), 0);
+        assert_eq!(tui_set_content_format(text, 3), 0);
+        let raw = "\x1b[32mok\x1b[0m!\x07";
+        assert_eq!(tui_set_content(text, raw.as_ptr(), raw.len() as u32), 0);
+        assert_eq!(content_from_handle(text), raw);
+
+        assert_eq!(tui_render(), 0);
+        let ctx = context_read().unwrap();
+        let cell = |x| ctx.back_buffer.get(x, 0).unwrap().clone();
+        assert_eq!((cell(0).ch, cell(0).fg), ('o', 0x0200_0002));
+        assert_eq!(cell(2).ch, '!');
+        assert_ne!(cell(2).fg, 0x0200_0002);
+        assert_eq!(cell(3).ch, ' ');
+        drop(ctx);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_blit_cells_draws_art_clipped_to_node() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(6, 2), 0);
+        let root = tui_create_node(NodeType::Box as u8);
+        let art = tui_create_node(NodeType::Box as u8);
+        assert_eq!(tui_set_root(root), 0);
+        assert_eq!(tui_append_child(root, art), 0);
+        tui_set_layout_dimension(root, 0, 6.0, 1);
+        tui_set_layout_dimension(root, 1, 2.0, 1);
+        tui_set_layout_dimension(art, 0, 3.0, 1);
+        tui_set_layout_dimension(art, 1, 2.0, 1);
+
+        let cell = |x, y, ch: char| types::TuiCellInit {
+            x,
+            y,
+            codepoint: ch as u32,
+            fg: 0x01FF0000,
+            bg: 0x010000FF,
+            attrs: types::CellAttrs::BOLD.bits(),
+        };
+        let cells = [cell(0, 0, '▀'), cell(2, 1, '█')];
+        assert_eq!(tui_blit_cells(art, cells.as_ptr(), 2, 0, 0), 0);
+        // Lands at x = 3, just past the node's right edge.
+        let outside = [cell(1, 0, '▄')];
+        assert_eq!(tui_blit_cells(art, outside.as_ptr(), 1, 2, 0), 0);
+        let bad = [types::TuiCellInit {
+            codepoint: 0xD800,
+            ..cell(0, 0, ' ')
+        }];
+        assert_eq!(tui_blit_cells(art, bad.as_ptr(), 1, 0, 0), -1);
+        let escape = [cell(1, 1, 'x'), cell(0, 1, '\x1b'), cell(1, 0, '\x07')];
+        assert_eq!(tui_blit_cells(art, escape.as_ptr(), 3, 0, 0), -1);
+        assert_eq!(
+            tui_get_last_error_code(),
+            types::ErrorCode::InvalidArgument as i32
+        );
+        assert_eq!(tui_render(), 0);
+        {
+            let ctx = context_read().unwrap();
+            let top = ctx.back_buffer.get(0, 0).unwrap();
+            assert_eq!(top.ch, '▀');
+            assert_eq!((top.fg, top.bg), (0x01FF0000, 0x010000FF));
+            assert!(top.attrs.contains(types::CellAttrs::BOLD));
+            assert_eq!(ctx.back_buffer.get(2, 1).unwrap().ch, '█');
+            assert_eq!(ctx.back_buffer.get(3, 0).unwrap().ch, ' ');
+            // The rejected call drew none of its cells.
+            assert_eq!(ctx.back_buffer.get(1, 1).unwrap().ch, ' ');
+            assert!(ctx.back_buffer.cells.iter().all(|c| !c.ch.is_control()));
+        }
+
+        assert_eq!(tui_clear_cells(art), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(
+            context_read().unwrap().back_buffer.get(0, 0).unwrap().ch,
+            ' '
+        );
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_min_render_interval_skips_only_clean_frames() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(10, 1), 0);
+        let text = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_set_root(text), 0);
+        assert_eq!(tui_set_min_render_interval(60_000), 0);
+        assert_eq!(tui_render(), 0);
+
+        let last = context_read().unwrap().last_render_time;
+        assert_eq!(tui_render(), 1);
+        assert_eq!(context_read().unwrap().last_render_time, last);
+
+        let content = "hi";
+        assert_eq!(tui_set_content(text, content.as_ptr(), 2), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(tui_invalidate(), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(tui_render(), 1);
+
+        assert_eq!(tui_set_min_render_interval(0), 0);
+        assert_eq!(tui_set_content(text, "ho".as_ptr(), 2), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_string_interning_shares_text_and_round_trips() {
+        let _guard = ffi_test_guard();
+        assert_eq!(tui_init_headless(20, 4), 0);
+        assert_eq!(tui_set_string_interning(1), 0);
+        let label = "caf\u{e9} \u{1F600}";
+        let a = tui_create_node(NodeType::Text as u8);
+        let b = tui_create_node(NodeType::Text as u8);
+        let select = tui_create_node(NodeType::Select as u8);
+        for handle in [a, b] {
+            assert_eq!(
+                tui_set_content(handle, label.as_ptr(), label.len() as u32),
+                0
+            );
+        }
+        for _ in 0..2 {
+            assert_eq!(
+                tui_select_add_option(select, label.as_ptr(), label.len() as u32),
+                0
+            );
+        }
+
+        let mut buf = [0u8; 32];
+        for handle in [a, b] {
+            let n = tui_get_content(handle, buf.as_mut_ptr(), buf.len() as u32);
+            assert_eq!(&buf[..n as usize], label.as_bytes());
+        }
+        let n = tui_select_get_option(select, 1, buf.as_mut_ptr(), buf.len() as u32);
+        assert_eq!(&buf[..n as usize], label.as_bytes());
+        {
+            let ctx = context_read().unwrap();
+            let shared = |text: &types::NodeText| match text {
+                types::NodeText::Shared(s) => s.clone(),
+                types::NodeText::Owned(_) => panic!("text should be interned"),
+            };
+            let first = shared(&ctx.nodes[&a].content);
+            assert!(std::sync::Arc::ptr_eq(
+                &first,
+                &shared(&ctx.nodes[&b].content)
+            ));
+            assert!(std::sync::Arc::ptr_eq(
+                &first,
+                &shared(&ctx.nodes[&select].options[1])
+            ));
+        }
+
+        assert_eq!(tui_set_string_interning(0), 0);
+        assert_eq!(tui_set_content(a, label.as_ptr(), label.len() as u32), 0);
+        let ctx = context_read().unwrap();
+        assert!(matches!(ctx.nodes[&a].content, types::NodeText::Owned(_)));
+        drop(ctx);
+        assert_eq!(tui_shutdown(), 0);
+    }
+
+    #[test]
+    fn test_deep_tree_lays_out_and_renders() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(80, 24);
+
+        // Far deeper than the old 512-level limit, and than Taffy's recursion
+        // fits in a default thread stack. Sized boxes keep the flex pass
+        // cheap.
+        let root = tui_create_node(NodeType::Box as u8);
+        let mut parent = root;
+        for _ in 0..2000 {
+            let child = tui_create_node(NodeType::Box as u8);
+            tui_set_layout_dimension(child, 0, 80.0, 1);
+            tui_set_layout_dimension(child, 1, 24.0, 1);
+            assert_eq!(tui_append_child(parent, child), 0);
+            parent = child;
+        }
+        let leaf = tui_create_node(NodeType::Text as u8);
+        assert_eq!(tui_set_content(leaf, b"deep".as_ptr(), 4), 0);
+        assert_eq!(tui_set_focusable(leaf, 1), 0);
+        assert_eq!(tui_append_child(parent, leaf), 0);
+        assert_eq!(tui_set_root(root), 0);
+
+        assert_eq!(tui_render(), 0);
+        let (mut x, mut y, mut w, mut h) = (-1, -1, 0, 0);
+        assert_eq!(tui_get_layout(leaf, &mut x, &mut y, &mut w, &mut h), 0);
+        assert_eq!((x, y, w, h), (0, 0, 4, 24));
+        {
+            let ctx = context_read().unwrap();
+            let row: String = (0..4)
+                .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+                .collect();
+            assert_eq!(row, "deep");
+            assert_eq!(layout::hit_test(&ctx, 1, 0), Some(leaf));
+        }
+        assert_eq!(tui_focus_next(), 0);
+        assert_eq!(tui_get_focused(), leaf);
+
+        assert_eq!(tui_destroy_subtree(root), 0);
+        assert_eq!(tui_get_node_count(), 0);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_concurrent_calls_are_serialized_with_per_thread_errors() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(40, 10);
+        let root = tui_create_node(NodeType::Box as u8);
+        tui_set_root(root);
+        assert_eq!(tui_set_thread_affinity(0), 0);
+
+        let renderer = std::thread::spawn(move || {
+            for _ in 0..200 {
+                assert!(tui_render() >= 0);
+                assert_eq!(tui_mark_dirty(root), 0);
+            }
+        });
+        let counter = std::thread::spawn(|| {
+            assert_eq!(tui_destroy_node(99_999), -1);
+            for _ in 0..200 {
+                assert_eq!(tui_get_node_count(), 1);
+            }
+            // Successful calls on the other thread left this one's error.
+            assert!(!tui_get_last_error().is_null());
+        });
+        renderer.join().unwrap();
+        counter.join().unwrap();
+        assert!(
+            tui_get_last_error().is_null(),
+            "errors stay on their thread"
+        );
+
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_last_error_code_tracks_error_kind() {
+        use types::ErrorCode;
+        let _guard = ffi_test_guard();
+        tui_shutdown();
+        assert_eq!(tui_get_node_count(), 0);
+        assert_eq!(tui_create_node(NodeType::Box as u8), 0);
+        assert_eq!(tui_get_last_error_code(), ErrorCode::NotInitialized as i32);
+
+        tui_init_headless(80, 24);
+        assert_eq!(tui_init_headless(80, 24), -1);
+        assert_eq!(
+            tui_get_last_error_code(),
+            ErrorCode::AlreadyInitialized as i32
+        );
+        let text = tui_create_node(NodeType::Text as u8);
+        let table = tui_create_node(NodeType::Table as u8);
+        let failed_with = |result: i32, expected: ErrorCode| {
+            assert_eq!(result, -1);
+            assert_eq!(tui_get_last_error_code(), expected as i32);
+        };
+        failed_with(tui_destroy_node(99_999), ErrorCode::InvalidHandle);
+        failed_with(tui_create_node(200) as i32 - 1, ErrorCode::InvalidArgument);
+        failed_with(
+            tui_table_set_column_count(text, 2),
+            ErrorCode::WrongNodeType,
+        );
+        failed_with(
+            tui_table_set_cell(table, 5, 5, b"x".as_ptr(), 1),
+            ErrorCode::IndexOutOfBounds,
+        );
+        failed_with(tui_set_text_direction(text, 9), ErrorCode::InvalidArgument);
+
+        failed_with(
+            tui_content_insert_at(text, 0, [0xff].as_ptr(), 1),
+            ErrorCode::InvalidArgument,
+        );
+
+        // The kind travels with the error, whatever its wording.
+        set_last_error(ErrorCode::Layout.raise("Invalid handle: 7"));
+        assert_eq!(tui_get_last_error_code(), ErrorCode::Layout as i32);
+        // A bare message, e.g. from the host's terminal callbacks, reports
+        // Other.
+        set_last_error("write: broken pipe".into());
+        assert_eq!(tui_get_last_error_code(), ErrorCode::Other as i32);
+
+        assert_eq!(tui_set_root(text), 0);
+        assert_eq!(tui_get_last_error_code(), ErrorCode::None as i32);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_layout_edges_and_gap_keep_cell_signatures() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(20, 10);
+        let root = tui_create_node(NodeType::Box as u8);
+        let a = tui_create_node(NodeType::Box as u8);
+        let b = tui_create_node(NodeType::Box as u8);
+        tui_set_layout_dimension(root, 0, 20.0, 1);
+        tui_set_layout_dimension(root, 1, 10.0, 1);
+        for child in [a, b] {
+            tui_set_layout_dimension(child, 0, 2.0, 1);
+            tui_set_layout_dimension(child, 1, 1.0, 1);
+            tui_append_child(root, child);
+        }
+        tui_set_root(root);
+        let x_of = |handle: u32| {
+            let (mut x, mut y, mut w, mut h) = (0, 0, 0, 0);
+            assert_eq!(tui_get_layout(handle, &mut x, &mut y, &mut w, &mut h), 0);
+            x
+        };
+
+        // The original signatures take cells.
+        assert_eq!(tui_set_layout_edges(root, 0, 0.0, 0.0, 0.0, 1.0), 0);
+        assert_eq!(tui_set_layout_gap(root, 0.0, 3.0), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!((x_of(a), x_of(b)), (1, 6));
+
+        assert_eq!(
+            tui_set_layout_edges_unit(root, 0, 0.0, 0.0, 0.0, 10.0, 2),
+            0
+        );
+        assert_eq!(tui_set_layout_gap_unit(root, 0.0, 25.0, 2), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!((x_of(a), x_of(b)), (2, 9));
+        assert_eq!(tui_set_layout_gap_unit(root, 0.0, 1.0, 9), -1);
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_virtual_clock_drives_animations_deterministically() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(10, 2);
+        let root = tui_create_node(NodeType::Box as u8);
+        tui_set_root(root);
+        let opacity = || context_read().unwrap().nodes[&root].visual_style.opacity;
+
+        assert_eq!(tui_test_set_clock(1_000), 0);
+        assert_eq!(tui_render(), 0);
+        let anim = tui_animate(
+            root,
+            types::AnimProp::Opacity as u8,
+            0.0f32.to_bits(),
+            100,
+            types::Easing::Linear as u8,
+        );
+        assert_ne!(anim, 0);
+
+        assert_eq!(tui_test_set_clock(1_025), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(opacity(), 0.75);
+        // No time passes between renders while the clock stands still.
+        assert_eq!(tui_render(), 0);
+        assert_eq!(opacity(), 0.75);
+
+        assert_eq!(tui_test_set_clock(1_100), 0);
+        assert_eq!(tui_render(), 0);
+        assert_eq!(opacity(), 0.0);
+        assert!(context_read().unwrap().animations.is_empty());
+
+        assert_eq!(tui_test_set_clock(1_099), -1);
+        assert_eq!(
+            tui_get_last_error_code(),
+            types::ErrorCode::InvalidArgument as i32
+        );
+        tui_shutdown();
+    }
+
+    #[test]
+    fn test_virtual_clock_starts_at_real_time() {
+        let _guard = ffi_test_guard();
+        tui_init_headless(10, 2);
+        let root = tui_create_node(NodeType::Box as u8);
+        tui_set_root(root);
+        assert_eq!(tui_render(), 0);
+        // Started on the real clock, before any virtual time exists.
+        let anim = tui_animate(
+            root,
+            types::AnimProp::Opacity as u8,
+            0.0f32.to_bits(),
+            100,
+            types::Easing::Linear as u8,
+        );
+        assert_ne!(anim, 0);
+
+        // A large first value is not a jump: the animation has barely begun.
+        assert_eq!(tui_test_set_clock(5_000_000), 0);
+        assert_eq!(tui_render(), 0);
+        {
+            let ctx = context_read().unwrap();
+            assert_eq!(ctx.animations.len(), 1);
+            assert!(ctx.nodes[&root].visual_style.opacity > 0.5);
+        }
+
+        assert_eq!(tui_test_set_clock(5_000_100), 0);
+        assert_eq!(tui_render(), 0);
+        assert!(context_read().unwrap().animations.is_empty());
+        tui_shutdown();
+    }
+
+    extern "C" fn capture_log(
+        level: u8,
+        msg: *const u8,
+        len: u32,
+        user_data: *mut std::ffi::c_void,
+    ) {
+        let log = unsafe { &*(user_data as *const std::sync::Mutex<Vec<(u8, String)>>) };
+        let bytes = unsafe { std::slice::from_raw_parts(msg, len as usize) };
+        let msg = String::from_utf8(bytes.to_vec()).expect("log messages are UTF-8");
+        log.lock().unwrap().push((level, msg));
+    }
+
+    #[test]
+    fn test_log_callback_receives_errors_and_debug_messages() {
+        use types::log_level;
+        let _guard = ffi_test_guard();
+        let log = std::sync::Mutex::new(Vec::<(u8, String)>::new());
+        let user_data = &log as *const _ as *mut std::ffi::c_void;
+        let take = || std::mem::take(&mut *log.lock().unwrap());
+        tui_shutdown();
+        assert_eq!(tui_set_log_callback(Some(capture_log), user_data), 0);
+
+        // Errors are logged even before init.
+        assert_eq!(tui_create_node(NodeType::Box as u8), 0);
+        let logged = take();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].0, log_level::ERROR);
+        assert!(logged[0].1.contains("not initialized"), "{logged:?}");
+
+        tui_init_headless(20, 5);
+        let root = tui_create_node(NodeType::Box as u8);
+        tui_set_root(root);
+        assert_eq!(tui_render(), 0);
+        assert!(take().is_empty(), "debug messages need debug mode");
+
+        tui_set_debug(1);
+        tui_set_layout_dimension(root, 0, 10.0, 1);
+        assert_eq!(tui_render(), 0);
+        let logged = take();
+        assert!(
+            logged
+                .iter()
+                .any(|(level, msg)| *level == log_level::DEBUG && msg.starts_with("compute_layout")),
+            "{logged:?}"
+        );
+
+        assert_eq!(tui_set_log_callback(None, std::ptr::null_mut()), 0);
+        tui_set_debug(0);
+        assert_eq!(tui_destroy_node(99_999), -1);
+        assert!(take().is_empty(), "removed logger must not be called");
         tui_shutdown();
     }
 
@@ -3910,7 +7893,7 @@ mod tests {
         tui_init_headless(80, 24);
 
         // Trigger a known error message
-        set_last_error("test error".to_string());
+        set_last_error("test error".into());
         let ptr = tui_get_last_error();
         assert!(!ptr.is_null());
 
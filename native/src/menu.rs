@@ -0,0 +1,197 @@
+//! Menu Module — Popup context menus on the overlay layer.
+//!
+//! Responsibilities:
+//! - Menu creation and items (a menu is a detached Select node: items are
+//!   its options, the highlight is its `selected_index`)
+//! - Opening at a screen position and placement within the terminal
+//! - Key/mouse capture while open and `MenuSelect` emission
+//!
+//! The open menu is drawn by the render pass after the node tree, so it
+//! sits above everything without being part of the layout.
+
+use crate::context::TuiContext;
+use crate::tree;
+use crate::types::{key, ErrorCode, NodeType, TuiError, TuiEvent};
+
+/// The currently open popup menu and its requested anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenMenu {
+    pub handle: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Create an empty, closed menu.
+pub(crate) fn create(ctx: &mut TuiContext) -> Result<u32, TuiError> {
+    tree::create_node(ctx, NodeType::Select)
+}
+
+/// Append an item label to a menu.
+pub(crate) fn add_item(ctx: &mut TuiContext, handle: u32, label: String) -> Result<(), TuiError> {
+    let node = menu_node_mut(ctx, handle)?;
+    node.options.push(label.into());
+    node.dirty = true;
+    Ok(())
+}
+
+/// Open `handle` at screen cell (x, y), closing any other open menu. The
+/// first item starts highlighted.
+pub(crate) fn show_at(ctx: &mut TuiContext, handle: u32, x: i32, y: i32) -> Result<(), TuiError> {
+    let node = menu_node_mut(ctx, handle)?;
+    if node.options.is_empty() {
+        return Err(format!("Menu {handle} has no items").into());
+    }
+    move_highlight(node, 0);
+    ctx.open_menu = Some(OpenMenu { handle, x, y });
+    Ok(())
+}
+
+/// Close the open menu, if any, without emitting an event.
+pub(crate) fn close(ctx: &mut TuiContext) {
+    ctx.open_menu = None;
+}
+
+/// Screen rectangle (x, y, w, h) of the open menu including its border,
+/// shifted left/up as needed to stay on screen.
+pub(crate) fn open_rect(ctx: &TuiContext) -> Option<(i32, i32, i32, i32)> {
+    let open = ctx.open_menu?;
+    let node = ctx.nodes.get(&open.handle)?;
+    let label_w = node
+        .options
+        .iter()
+        .map(|label| crate::text_utils::str_display_width(label) as i32)
+        .max()
+        .unwrap_or(0);
+    let screen_w = ctx.front_buffer.width as i32;
+    let screen_h = ctx.front_buffer.height as i32;
+    let w = (label_w + 2).min(screen_w);
+    let h = (node.options.len() as i32 + 2).min(screen_h);
+    let x = open.x.min(screen_w - w).max(0);
+    let y = open.y.min(screen_h - h).max(0);
+    Some((x, y, w, h))
+}
+
+/// Handle a key while a menu is open. Every key is captured: Up/Down move
+/// the highlight, Enter emits `MenuSelect` and closes, Escape closes.
+pub(crate) fn handle_key(ctx: &mut TuiContext, code: u32) -> bool {
+    let Some(open) = ctx.open_menu else {
+        return false;
+    };
+    let Some(node) = ctx.nodes.get_mut(&open.handle) else {
+        close(ctx);
+        return false;
+    };
+    let current = node.selected_index.unwrap_or(0);
+    match code {
+        key::UP if current > 0 => move_highlight(node, current - 1),
+        key::DOWN if current + 1 < node.options.len() as u32 => move_highlight(node, current + 1),
+        key::ENTER => select(ctx, open.handle, current),
+        key::ESCAPE => close(ctx),
+        _ => {}
+    }
+    true
+}
+
+/// Handle a mouse press while a menu is open. A click on an item selects
+/// it; a click anywhere outside the menu dismisses it. Both are consumed.
+pub(crate) fn handle_mouse(ctx: &mut TuiContext, x: u16, y: u16, button: u8) -> bool {
+    if button > 2 {
+        return false;
+    }
+    let Some((mx, my, mw, mh)) = open_rect(ctx) else {
+        return false;
+    };
+    let (x, y) = (x as i32, y as i32);
+    let inside = x >= mx && x < mx + mw && y >= my && y < my + mh;
+    if !inside {
+        close(ctx);
+        return true;
+    }
+    let row = y - my - 1;
+    if let Some(open) = ctx.open_menu {
+        let count = ctx.nodes.get(&open.handle).map_or(0, |n| n.options.len()) as i32;
+        if row >= 0 && row < count && x > mx && x < mx + mw - 1 {
+            select(ctx, open.handle, row as u32);
+        }
+    }
+    true
+}
+
+/// The highlight is painted from the node, so moving it marks the node
+/// dirty for the next frame.
+fn move_highlight(node: &mut crate::types::TuiNode, index: u32) {
+    node.selected_index = Some(index);
+    node.dirty = true;
+}
+
+fn select(ctx: &mut TuiContext, handle: u32, index: u32) {
+    ctx.event_buffer.push(TuiEvent::menu_select(handle, index));
+    close(ctx);
+}
+
+fn menu_node_mut(
+    ctx: &mut TuiContext,
+    handle: u32,
+) -> Result<&mut crate::types::TuiNode, TuiError> {
+    let node = ctx
+        .nodes
+        .get_mut(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    if node.node_type != NodeType::Select {
+        return Err(ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a menu")));
+    }
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::MockBackend;
+    use crate::types::TuiEventType;
+
+    fn menu_ctx() -> (TuiContext, u32) {
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(20, 6)));
+        let menu = create(&mut ctx).unwrap();
+        for label in ["Copy", "Paste", "Delete"] {
+            add_item(&mut ctx, menu, label.to_string()).unwrap();
+        }
+        (ctx, menu)
+    }
+
+    #[test]
+    fn test_keys_move_and_enter_selects() {
+        let (mut ctx, menu) = menu_ctx();
+        show_at(&mut ctx, menu, 2, 1).unwrap();
+        ctx.nodes.get_mut(&menu).unwrap().dirty = false;
+        assert!(handle_key(&mut ctx, key::DOWN));
+        assert!(ctx.nodes[&menu].dirty, "highlight moves repaint the menu");
+        assert!(handle_key(&mut ctx, key::DOWN));
+        assert!(handle_key(&mut ctx, key::DOWN));
+        assert!(handle_key(&mut ctx, 'x' as u32));
+        assert!(handle_key(&mut ctx, key::ENTER));
+
+        assert!(ctx.open_menu.is_none());
+        let event = ctx.event_buffer.remove(0);
+        assert_eq!(event.event_type, TuiEventType::MenuSelect as u32);
+        assert_eq!(event.target, menu);
+        assert_eq!(event.data[0], 2);
+        assert!(!handle_key(&mut ctx, key::ENTER));
+    }
+
+    #[test]
+    fn test_rect_stays_on_screen_and_clicks_select_or_dismiss() {
+        let (mut ctx, menu) = menu_ctx();
+        show_at(&mut ctx, menu, 18, 5).unwrap();
+        // 6 label columns + border, 3 items + border, pushed up and left.
+        assert_eq!(open_rect(&ctx), Some((12, 1, 8, 5)));
+
+        assert!(handle_mouse(&mut ctx, 13, 3, 0));
+        assert_eq!(ctx.event_buffer.remove(0).data[0], 1);
+        assert!(ctx.open_menu.is_none());
+
+        show_at(&mut ctx, menu, 0, 0).unwrap();
+        assert!(handle_mouse(&mut ctx, 15, 5, 0));
+        assert!(ctx.open_menu.is_none());
+        assert!(ctx.event_buffer.is_empty());
+    }
+}
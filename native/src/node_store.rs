@@ -0,0 +1,179 @@
+//! Node storage — a slab of `TuiNode`s addressed by handle.
+//!
+//! Handles are sequential and never reused (ADR-003), so a handle maps to
+//! its slot through a plain `Vec` instead of a hash: lookups are two index
+//! operations and live nodes sit contiguously for traversal. Slots freed by
+//! destroyed nodes are recycled for new ones; only the 4-byte handle→slot
+//! entry of a destroyed handle is kept.
+
+use std::ops::Index;
+
+use crate::types::TuiNode;
+
+/// Marks a handle whose node was destroyed, or that was never allocated.
+const VACANT: u32 = u32::MAX;
+
+#[derive(Default)]
+pub struct NodeStore {
+    /// Slot index per handle, `VACANT` when the handle has no live node.
+    slot_of: Vec<u32>,
+    /// Live nodes with their handles; `None` slots are on `free`.
+    slots: Vec<Option<(u32, TuiNode)>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl NodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of live nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn slot(&self, handle: u32) -> Option<usize> {
+        match self.slot_of.get(handle as usize) {
+            Some(&slot) if slot != VACANT => Some(slot as usize),
+            _ => None,
+        }
+    }
+
+    pub fn contains_key(&self, handle: &u32) -> bool {
+        self.slot(*handle).is_some()
+    }
+
+    pub fn get(&self, handle: &u32) -> Option<&TuiNode> {
+        let slot = self.slot(*handle)?;
+        self.slots[slot].as_ref().map(|(_, node)| node)
+    }
+
+    pub fn get_mut(&mut self, handle: &u32) -> Option<&mut TuiNode> {
+        let slot = self.slot(*handle)?;
+        self.slots[slot].as_mut().map(|(_, node)| node)
+    }
+
+    /// Store `node` under `handle`, returning the node it replaces.
+    pub fn insert(&mut self, handle: u32, node: TuiNode) -> Option<TuiNode> {
+        if let Some(slot) = self.slot(handle) {
+            let (_, old) = self.slots[slot].replace((handle, node))?;
+            return Some(old);
+        }
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot as usize] = Some((handle, node));
+                slot
+            }
+            None => {
+                self.slots.push(Some((handle, node)));
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let index = handle as usize;
+        if self.slot_of.len() <= index {
+            self.slot_of.resize(index + 1, VACANT);
+        }
+        self.slot_of[index] = slot;
+        self.len += 1;
+        None
+    }
+
+    pub fn remove(&mut self, handle: &u32) -> Option<TuiNode> {
+        let slot = self.slot(*handle)?;
+        let (_, node) = self.slots[slot].take()?;
+        self.slot_of[*handle as usize] = VACANT;
+        self.free.push(slot as u32);
+        self.len -= 1;
+        Some(node)
+    }
+
+    /// Live nodes with their handles, in slot order (not handle order).
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            slots: self.slots.iter(),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &u32> {
+        self.iter().map(|(handle, _)| handle)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &TuiNode> {
+        self.iter().map(|(_, node)| node)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut TuiNode> {
+        self.slots
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(_, node)| node))
+    }
+}
+
+pub struct Iter<'a> {
+    slots: std::slice::Iter<'a, Option<(u32, TuiNode)>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a u32, &'a TuiNode);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots
+            .by_ref()
+            .find_map(|slot| slot.as_ref().map(|(handle, node)| (handle, node)))
+    }
+}
+
+impl<'a> IntoIterator for &'a NodeStore {
+    type Item = (&'a u32, &'a TuiNode);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Index<&u32> for NodeStore {
+    type Output = TuiNode;
+
+    fn index(&self, handle: &u32) -> &TuiNode {
+        self.get(handle)
+            .unwrap_or_else(|| panic!("Invalid handle: {handle}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::NodeType;
+
+    fn node() -> TuiNode {
+        let mut tree: taffy::TaffyTree<u32> = taffy::TaffyTree::new();
+        TuiNode::new(NodeType::Box, tree.new_leaf(taffy::Style::DEFAULT).unwrap())
+    }
+
+    #[test]
+    fn freed_slots_are_reused_but_handles_stay_dead() {
+        let mut store = NodeStore::new();
+        for handle in 1..=3 {
+            assert!(store.insert(handle, node()).is_none());
+        }
+        assert!(store.remove(&2).is_some());
+        assert!(store.remove(&2).is_none());
+        assert!(!store.contains_key(&2));
+        assert_eq!(store.len(), 2);
+
+        store.insert(4, node());
+        assert_eq!(store.slots.len(), 3, "slot of handle 2 is recycled");
+        assert!(store.get(&2).is_none());
+        let mut handles: Vec<u32> = store.keys().copied().collect();
+        handles.sort_unstable();
+        assert_eq!(handles, vec![1, 3, 4]);
+        assert!(store.get(&0).is_none());
+        assert!(store.get(&99).is_none());
+    }
+}
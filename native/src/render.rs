@@ -9,14 +9,14 @@
 use crate::context::TuiContext;
 use crate::text_buffer;
 use crate::text_renderer::{self, BaseStyle, Rect};
-use crate::text_utils::grapheme_count;
+use crate::text_utils::{grapheme_count, grapheme_to_byte_idx};
 use crate::text_view;
-use crate::types::{BorderStyle, Buffer, Cell, CellAttrs, CellUpdate, ContentFormat, NodeType};
+use crate::types::{
+    is_default_color, BorderStyle, Buffer, CaretStyle, Cell, CellAttrs, CellUpdate, ContentFormat,
+    ErrorCode, LineScale, NodeType, OpacityMode, OptionMarquee, OptionOverflow, Overflow,
+    TextDirection, TuiError, OPTION_MARQUEE_STEP_MS,
+};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
-
-#[cfg(test)]
-use crate::text_utils::grapheme_to_byte_idx;
 
 // ============================================================================
 // Clip Rectangle
@@ -72,12 +72,12 @@ fn clip_set(buffer: &mut Buffer, sx: i32, sy: i32, cell: Cell, clip: ClipRect) {
     }
 }
 
-fn ensure_node_text_handles(ctx: &mut TuiContext, handle: u32) -> Result<(u32, u32), String> {
+fn ensure_node_text_handles(ctx: &mut TuiContext, handle: u32) -> Result<(u32, u32), TuiError> {
     let (buffer_handle, view_handle, content) = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         (
             node.text_buffer_handle,
             node.text_view_handle,
@@ -103,7 +103,7 @@ fn ensure_node_text_handles(ctx: &mut TuiContext, handle: u32) -> Result<(u32, u
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     node.text_buffer_handle = Some(buffer_handle);
     node.text_view_handle = Some(view_handle);
     Ok((buffer_handle, view_handle))
@@ -115,7 +115,7 @@ fn apply_styled_text_to_buffer(
     spans: &[crate::types::StyledSpan],
     default_bg: u32,
     opacity: f32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let mut rendered = String::new();
     for span in spans {
         rendered.push_str(&span.text);
@@ -124,11 +124,22 @@ fn apply_styled_text_to_buffer(
     let existing = ctx
         .text_buffers
         .get(&buffer_handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+        })?
         .content()
         .to_string();
     if existing != rendered {
-        text_buffer::replace_range(ctx, buffer_handle, 0, existing.len(), &rendered)?;
+        // Splice only the changed middle so dirty ranges and line bookkeeping
+        // stay proportional to the edit rather than the whole text.
+        let (start, old_end, new_end) = crate::text_utils::grapheme_diff(&existing, &rendered);
+        text_buffer::replace_range(
+            ctx,
+            buffer_handle,
+            start,
+            old_end,
+            &rendered[start..new_end],
+        )?;
     }
 
     let mut style_spans = Vec::new();
@@ -187,6 +198,34 @@ fn apply_styled_text_to_buffer(
     Ok(())
 }
 
+/// Convert a node's grapheme-indexed search highlights into byte ranges over
+/// `content`. Ranges that no longer fit the content are dropped.
+fn node_highlight_ranges(
+    ctx: &TuiContext,
+    handle: u32,
+    content: &str,
+) -> Vec<crate::types::HighlightRange> {
+    let Some(node) = ctx.nodes.get(&handle) else {
+        return Vec::new();
+    };
+    if node.text_highlights.is_empty() {
+        return Vec::new();
+    }
+    let bounds: Vec<usize> = UnicodeSegmentation::grapheme_indices(content, true)
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(content.len()))
+        .collect();
+    node.text_highlights
+        .iter()
+        .filter(|&&(start, end)| start < end && end < bounds.len())
+        .map(|&(start, end)| crate::types::HighlightRange {
+            start: bounds[start],
+            end: bounds[end],
+            kind: 0,
+        })
+        .collect()
+}
+
 fn first_buffer_line(ctx: &TuiContext, buffer_handle: u32) -> Option<String> {
     ctx.text_buffers
         .get(&buffer_handle)
@@ -212,14 +251,40 @@ fn render_substrate_view(
     content_h: i32,
     base_scroll_row: u32,
     base_scroll_col: u32,
+    line_spacing: u32,
     fg: u32,
     bg: u32,
     attrs: CellAttrs,
     clip: ClipRect,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     if content_w <= 0 || content_h <= 0 {
         return Ok(());
     }
+    if line_spacing > 0 {
+        // Draw one visual row at a time, leaving the spacing rows untouched.
+        let pitch = 1 + line_spacing as i32;
+        for (row, y) in (content_y..content_y + content_h)
+            .step_by(pitch as usize)
+            .enumerate()
+        {
+            render_substrate_view(
+                ctx,
+                view_handle,
+                content_x,
+                y,
+                content_w,
+                1,
+                base_scroll_row + row as u32,
+                base_scroll_col,
+                0,
+                fg,
+                bg,
+                attrs,
+                clip,
+            )?;
+        }
+        return Ok(());
+    }
 
     let rect = clip.intersect(ClipRect {
         x: content_x,
@@ -275,9 +340,11 @@ fn render_substrate_view(
 /// Apply opacity blending to a foreground color.
 /// Linearly interpolates fg toward bg per RGB channel.
 /// Only applies to RGB-encoded colors (tag 0x01). Default and indexed colors
-/// are returned unchanged since their RGB values are unknown.
+/// are returned unchanged since their RGB values are unknown. A default bg
+/// means "whatever the terminal shows", so fg is left alone rather than
+/// being dimmed toward an assumed black.
 fn blend_opacity(fg: u32, bg: u32, opacity: f32) -> u32 {
-    use crate::types::color_tag;
+    use crate::types::{color_tag, is_default_color};
 
     if opacity >= 1.0 {
         return fg;
@@ -286,7 +353,7 @@ fn blend_opacity(fg: u32, bg: u32, opacity: f32) -> u32 {
     if opacity <= 0.0 {
         return bg;
     }
-    if color_tag(fg) != 0x01 {
+    if color_tag(fg) != 0x01 || is_default_color(bg) {
         return fg;
     }
 
@@ -325,7 +392,132 @@ fn blend_opacity(fg: u32, bg: u32, opacity: f32) -> u32 {
 /// 5. Send diff to backend
 /// 6. Swap buffers
 /// 7. Clear dirty flags
-pub(crate) fn render(ctx: &mut TuiContext) -> Result<(), String> {
+///
+/// Returns false when nothing changed since the previous frame and the
+/// whole pipeline was skipped.
+pub(crate) fn render(ctx: &mut TuiContext) -> Result<bool, TuiError> {
+    if !ctx.suspended
+        && unchanged_since_last_frame(ctx)
+        && ctx.last_frame_key.as_ref() == Some(&frame_key(ctx))
+    {
+        // Keep the animation clock current for the next animation started.
+        ctx.last_render_time = Some(ctx.now());
+        ctx.perf_diff_cells = 0;
+        return Ok(false);
+    }
+    render_clipped(ctx, None)?;
+    // Taken after the frame, which may itself scroll views or move carets.
+    // A frame the debug overlay drew is never reused: its stats change.
+    let key = frame_key(ctx);
+    ctx.last_frame_key = (!ctx.suspended && !key.overlay).then_some(key);
+    Ok(true)
+}
+
+/// Global state that changes what a frame paints without marking any node
+/// dirty. Equal keys and a clean tree mean the same frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FrameKey {
+    size: (u16, u16),
+    root: Option<u32>,
+    focused: Option<u32>,
+    caret_hidden_for: Option<u32>,
+    caret_style: crate::types::CaretStyle,
+    open_menu: Option<crate::menu::OpenMenu>,
+    capabilities: u64,
+    sanitize_content: bool,
+    word_break_chars: String,
+    overlay: bool,
+    substrates: (usize, u64),
+}
+
+fn frame_key(ctx: &TuiContext) -> FrameKey {
+    FrameKey {
+        size: ctx.backend.size(),
+        root: ctx.root,
+        focused: ctx.focused,
+        caret_hidden_for: ctx.caret_hidden_for,
+        caret_style: ctx.caret_style,
+        open_menu: ctx.open_menu,
+        capabilities: ctx.terminal_capabilities.flags,
+        sanitize_content: ctx.sanitize_content,
+        word_break_chars: ctx.word_break_chars.clone(),
+        overlay: ctx.debug_mode && ctx.debug_overlay_flags != 0,
+        substrates: substrate_fingerprint(ctx),
+    }
+}
+
+/// Text buffers and views are edited through their own handles without
+/// touching the nodes that show them. Entries are hashed independently and
+/// summed so the result does not depend on map iteration order.
+fn substrate_fingerprint(ctx: &TuiContext) -> (usize, u64) {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    fn entry(state: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        hasher.finish()
+    }
+    let buffers = ctx
+        .text_buffers
+        .iter()
+        .map(|(h, b)| entry((h, b.epoch(), b.style_fingerprint())));
+    let views = ctx.text_views.iter().map(|(h, v)| {
+        entry((
+            h,
+            v.buffer(),
+            v.wrap_width(),
+            v.wrap_mode() as u8,
+            v.tab_width(),
+            v.viewport_rows(),
+            v.scroll_row(),
+            v.scroll_col(),
+            v.cursor().map(|c| c.byte_offset),
+        ))
+    });
+    (
+        ctx.text_buffers.len() + ctx.text_views.len(),
+        buffers.chain(views).fold(0, u64::wrapping_add),
+    )
+}
+
+/// No node is dirty, nothing moves with the clock (animations, a scrolling
+/// Select option) and the screen was not invalidated, so re-rendering would
+/// produce an empty diff. Node state that changes what is painted must mark
+/// the node dirty, or the change is not shown until something else is.
+fn unchanged_since_last_frame(ctx: &TuiContext) -> bool {
+    !ctx.screen_invalidated
+        && ctx.animations.is_empty()
+        && !ctx.nodes.values().any(|n| n.dirty || n.marquee.is_some())
+}
+
+/// Run the full pipeline but paint, diff and emit only the cells inside the
+/// given screen rect. Cells outside it keep their previous back-buffer
+/// contents and are never written, so the host owns the rest of the
+/// surface; the next full `render` reconciles them.
+pub(crate) fn render_region(
+    ctx: &mut TuiContext,
+    x: u16,
+    y: u16,
+    w: u16,
+    h: u16,
+) -> Result<(), TuiError> {
+    let region = ClipRect {
+        x: x as i32,
+        y: y as i32,
+        w: w as i32,
+        h: h as i32,
+    };
+    // Cells outside the region may now be stale, so the next full render
+    // must not be skipped.
+    ctx.last_frame_key = None;
+    render_clipped(ctx, Some(region))
+}
+
+fn render_clipped(ctx: &mut TuiContext, region: Option<ClipRect>) -> Result<(), TuiError> {
+    // The host owns the terminal until tui_resume.
+    if ctx.suspended {
+        return Ok(());
+    }
+
     let start = std::time::Instant::now();
 
     // Reset per-frame text cache counters
@@ -334,44 +526,81 @@ pub(crate) fn render(ctx: &mut TuiContext) -> Result<(), String> {
     ctx.perf_text_cache_hits = 0;
     ctx.perf_text_cache_misses = 0;
 
-    // 0. Advance animations (ADR-T13: before layout resolution)
-    let elapsed_ms = match ctx.last_render_time {
-        Some(last) => (start.duration_since(last).as_secs_f64() * 1000.0) as f32,
-        None => 0.0,
-    };
-    crate::animation::advance_animations(ctx, elapsed_ms);
-    ctx.last_render_time = Some(start);
-
-    // 1. Compute layout
-    crate::layout::compute_layout(ctx)?;
+    // 0. Advance animations (ADR-T13: before layout resolution). Skipped when
+    // the host drives the animation clock itself; the timestamp still moves so
+    // re-enabling does not replay the whole host-driven span.
+    let now = ctx.now();
+    let frame_interval = ctx.last_render_time.map(|last| now.duration_since(last));
+    ctx.perf_frame_interval_us = frame_interval.map_or(0, |d| d.as_micros() as u64);
+    if ctx.auto_advance_animations {
+        let elapsed_ms = frame_interval.map_or(0.0, |d| (d.as_secs_f64() * 1000.0) as f32);
+        crate::animation::advance_animations(ctx, elapsed_ms);
+    }
+    ctx.last_render_time = Some(now);
 
-    // Resize buffers if terminal size changed
+    // Terminal size changed: resize buffers, reflow the whole tree against the
+    // new available space, and repaint every cell (the terminal's own
+    // reflow leaves the screen contents unknown).
     let (w, h) = ctx.backend.size();
     if ctx.front_buffer.width != w || ctx.front_buffer.height != h {
         ctx.front_buffer.resize(w, h);
         ctx.back_buffer.resize(w, h);
+        invalidate(ctx);
+        crate::tree::mark_all_paint_dirty(ctx);
     }
 
+    // 1. Compute layout
+    crate::layout::compute_layout(ctx)?;
+
     // 2. Clear front buffer
     ctx.front_buffer.clear();
+    ctx.image_placements.clear();
+    ctx.line_scale_rows.clear();
 
     // 3. Traverse and render
     if let Some(root) = ctx.root {
-        let clip = ClipRect::full(ctx.front_buffer.width, ctx.front_buffer.height);
-        render_node(ctx, root, 0, 0, clip)?;
+        let full = ClipRect::full(ctx.front_buffer.width, ctx.front_buffer.height);
+        let clip = region.map_or(full, |r| full.intersect(r));
+        render_tree(ctx, root, clip)?;
     }
 
+    // 3b. Popup menu above the tree
+    render_open_menu(ctx);
+
     // 4. Overlay rendering (ADR-T34): draw markers into front_buffer before diff
     if ctx.debug_mode && ctx.debug_overlay_flags != 0 {
         crate::devtools::render_overlay(ctx);
     }
 
-    // 5. Diff
-    let diff = diff_buffers(ctx);
+    // 4b. Outside an explicit region the screen is left as it was: carry the
+    // back buffer over so those cells neither diff nor change on swap.
+    if let Some(region) = region {
+        keep_outside_region(ctx, region);
+    }
+
+    // 4c. Rows whose DEC line attribute changes get the new attribute and
+    // a full repaint, since the terminal redraws them at the new size.
+    let line_scales = sync_line_scales(ctx)?;
+
+    // 5. Diff into the context's reused update vector
+    let mut diff = std::mem::take(&mut ctx.diff_updates);
+    diff_buffers(ctx, &mut diff);
     ctx.perf_diff_cells = diff.len() as u32;
+    fold_scaled_rows(&mut diff, &line_scales);
 
     // 6. Compact runs and emit via writer through backend (ADR-T24)
-    let runs = crate::writer::compact_runs(&diff);
+    ctx.backend.write_diff(&diff)?;
+    let mut runs = crate::writer::compact_runs(&diff);
+    // The buffers keep the Unicode glyphs; only the emitted text changes.
+    if ascii_fallback_enabled(ctx) {
+        for run in &mut runs {
+            run.chars = run
+                .chars
+                .chars()
+                .map(crate::writer::ascii_fallback)
+                .collect();
+        }
+    }
     let root_bg = match ctx.root {
         Some(h) if ctx.nodes.contains_key(&h) => crate::style::resolve_style(h, ctx).bg_color,
         _ => 0,
@@ -394,8 +623,30 @@ pub(crate) fn render(ctx: &mut TuiContext) -> Result<(), String> {
     ctx.perf_write_runs = metrics.run_count;
     ctx.perf_style_deltas = metrics.style_delta_count;
 
+    // 6b. Inline images go over their reserved blanks. Unchanged images
+    // stay on screen, so a payload is rewritten only when its node is
+    // dirty or this frame repainted cells under it.
+    for (handle, x, y, w, h) in std::mem::take(&mut ctx.image_placements) {
+        let Some(node) = ctx.nodes.get(&handle) else {
+            continue;
+        };
+        let (x1, y1) = (x as i32 + w as i32 - 1, y as i32 + h as i32 - 1);
+        if region.is_some_and(|r| !r.contains(x as i32, y as i32) || !r.contains(x1, y1)) {
+            continue;
+        }
+        let repainted = diff
+            .iter()
+            .any(|u| u.x >= x && u.x < x + w && u.y >= y && u.y < y + h);
+        if node.dirty || repainted {
+            let payload = node.image_payload.clone();
+            ctx.backend.write_image(x, y, &payload)?;
+        }
+    }
+    ctx.diff_updates = diff;
+
     // 7. Swap buffers
     std::mem::swap(&mut ctx.front_buffer, &mut ctx.back_buffer);
+    ctx.screen_invalidated = false;
 
     ctx.perf_render_us = start.elapsed().as_micros() as u64;
     ctx.debug_log(&format!(
@@ -409,27 +660,154 @@ pub(crate) fn render(ctx: &mut TuiContext) -> Result<(), String> {
         ctx.frame_seq += 1;
     }
 
+    crate::devtools::record_frame(ctx);
+
     // 9. Clear dirty flags
     crate::tree::clear_dirty_flags(ctx);
 
     Ok(())
 }
 
-/// Render a single node into the front buffer at the given parent offset,
-/// clipped to the given clip rectangle.
-fn render_node(
-    ctx: &mut TuiContext,
+/// Force the next render to repaint every cell, e.g. after the host wrote to
+/// the terminal directly. Unlike `mark_dirty`, this targets the screen
+/// buffer, not the tree.
+pub(crate) fn invalidate(ctx: &mut TuiContext) {
+    ctx.back_buffer.invalidate();
+    // Row line attributes are unknown too.
+    ctx.emitted_line_scales.fill(None);
+    ctx.screen_invalidated = true;
+}
+
+/// Whether a `tui_render` call can be skipped: a minimum interval is set,
+/// the last frame is more recent than it, and nothing has changed since.
+pub(crate) fn throttled(ctx: &TuiContext) -> bool {
+    if ctx.min_render_interval_ms == 0 || ctx.screen_invalidated {
+        return false;
+    }
+    let Some(last) = ctx.last_render_time else {
+        return false;
+    };
+    let interval = std::time::Duration::from_millis(ctx.min_render_interval_ms as u64);
+    ctx.now().duration_since(last) < interval
+        && ctx.backend.size() == (ctx.front_buffer.width, ctx.front_buffer.height)
+        && !ctx.nodes.values().any(|n| n.dirty)
+}
+
+/// Change the output color depth. The screen is repainted because every
+/// emitted color may change even though the buffers do not.
+pub(crate) fn set_color_mode(ctx: &mut TuiContext, mode: u8) -> Result<(), TuiError> {
+    let mode = crate::types::ColorMode::from_u8(mode)
+        .ok_or_else(|| ErrorCode::InvalidArgument.raise(format!("Invalid color mode: {mode}")))?;
+    if ctx.writer_state.color_mode != mode {
+        ctx.writer_state.color_mode = mode;
+        invalidate(ctx);
+    }
+    Ok(())
+}
+
+/// Turn the ASCII glyph fallback on or off. Like a color mode change, the
+/// screen is repainted because emitted glyphs change while the buffers do
+/// not.
+pub(crate) fn set_ascii_fallback(ctx: &mut TuiContext, enabled: bool) {
+    let was = ascii_fallback_enabled(ctx);
+    ctx.ascii_fallback = Some(enabled);
+    if was != enabled {
+        invalidate(ctx);
+    }
+}
+
+fn ascii_fallback_enabled(ctx: &TuiContext) -> bool {
+    ctx.ascii_fallback.unwrap_or_else(|| {
+        !ctx.terminal_capabilities
+            .supports(crate::terminal_capabilities::terminal_capability::UTF8)
+    })
+}
+
+/// Release the terminal to the host, keeping the context and tree intact.
+pub(crate) fn suspend(ctx: &mut TuiContext) -> Result<(), TuiError> {
+    if ctx.suspended {
+        return Ok(());
+    }
+    ctx.backend.suspend()?;
+    ctx.suspended = true;
+    Ok(())
+}
+
+/// Re-acquire the terminal and repaint everything: whatever the host drew
+/// while suspended is now on screen. Animations resume from where they
+/// paused rather than jumping over the suspended span.
+pub(crate) fn resume(ctx: &mut TuiContext) -> Result<(), TuiError> {
+    if !ctx.suspended {
+        return Ok(());
+    }
+    ctx.backend.resume()?;
+    ctx.suspended = false;
+    ctx.last_render_time = None;
+    ctx.terminal_capabilities = ctx.backend.capabilities();
+    invalidate(ctx);
+    render(ctx)?;
+    Ok(())
+}
+
+/// A node waiting on the render work stack, with the absolute offset of its
+/// parent and the clip rect inherited from its ancestors.
+struct PendingNode {
     handle: u32,
     parent_x: i32,
     parent_y: i32,
     clip: ClipRect,
-) -> Result<(), String> {
+}
+
+/// Render the subtree under `root` in depth-first pre-order, so parents paint
+/// before their children and later siblings over earlier ones. An explicit
+/// work stack replaces recursion: deep trees cannot overflow the call stack.
+fn render_tree(ctx: &mut TuiContext, root: u32, clip: ClipRect) -> Result<(), TuiError> {
+    let mut pending = vec![PendingNode {
+        handle: root,
+        parent_x: 0,
+        parent_y: 0,
+        clip,
+    }];
+    while let Some(next) = pending.pop() {
+        render_node(ctx, next, &mut pending)?;
+    }
+    Ok(())
+}
+
+/// Queue `children` so they pop, and therefore paint, in document order.
+fn push_children(
+    pending: &mut Vec<PendingNode>,
+    children: &[u32],
+    parent_x: i32,
+    parent_y: i32,
+    clip: ClipRect,
+) {
+    pending.extend(children.iter().rev().map(|&handle| PendingNode {
+        handle,
+        parent_x,
+        parent_y,
+        clip,
+    }));
+}
+
+/// Render a single node into the front buffer at its parent's offset,
+/// clipped to the given clip rectangle, and queue its children on `pending`.
+fn render_node(
+    ctx: &mut TuiContext,
+    PendingNode {
+        handle,
+        parent_x,
+        parent_y,
+        clip,
+    }: PendingNode,
+    pending: &mut Vec<PendingNode>,
+) -> Result<(), TuiError> {
     let node = match ctx.nodes.get(&handle) {
         Some(n) => n,
         None => return Ok(()),
     };
 
-    if !node.visible {
+    if !node.visible || node.display_none {
         return Ok(());
     }
 
@@ -443,10 +821,9 @@ fn render_node(
     }
 
     let taffy_node = node.taffy_node;
-    let layout = ctx
-        .tree
-        .layout(taffy_node)
-        .map_err(|e| format!("Layout not computed for handle {handle}: {e:?}"))?;
+    let layout = ctx.tree.layout(taffy_node).map_err(|e| {
+        ErrorCode::Layout.raise(format!("Layout not computed for handle {handle}: {e:?}"))
+    })?;
 
     let abs_x = parent_x + layout.location.x as i32 + node.render_offset.0.round() as i32;
     let abs_y = parent_y + layout.location.y as i32 + node.render_offset.1.round() as i32;
@@ -457,32 +834,70 @@ fn render_node(
     let resolved = crate::style::resolve_style(handle, ctx);
     let raw_fg = resolved.fg_color;
     let bg = resolved.bg_color;
-    let opacity = resolved.opacity;
+    // Dim mode fades with the DIM attribute instead of touching colors;
+    // Both blends and falls back to DIM when blending can't fade the fg.
+    let opacity = match node.opacity_mode {
+        OpacityMode::Dim => 1.0,
+        OpacityMode::Blend | OpacityMode::Both => resolved.opacity,
+    };
     let fg = blend_opacity(raw_fg, bg, opacity);
+    let dim = resolved.opacity < 1.0
+        && match node.opacity_mode {
+            OpacityMode::Blend => false,
+            OpacityMode::Dim => true,
+            OpacityMode::Both => fg == raw_fg,
+        };
     let raw_border = resolved.border_color;
-    // Fall back to fg_color when border_color is unset (0 = default)
-    let border_fg = if raw_border != 0 {
+    // Fall back to fg_color when border_color is unset (default tag)
+    let border_fg = if !is_default_color(raw_border) {
         blend_opacity(raw_border, bg, opacity)
     } else {
         fg
     };
-    let attrs = resolved.attrs;
+    let attrs = if dim {
+        resolved.attrs | CellAttrs::DIM
+    } else {
+        resolved.attrs
+    };
     let border_style = resolved.border_style;
-    let content = node.content.clone();
+    // A running typewriter animation draws only the revealed prefix.
+    let content = match node.typewriter_reveal {
+        Some(shown) => {
+            node.content[..grapheme_to_byte_idx(&node.content, shown as usize)].to_string()
+        }
+        None => node.content.to_string(),
+    };
     let content_format = node.content_format;
+    let text_marquee = node.text_marquee;
     let code_language = node.code_language.clone();
     let scroll_x = node.scroll_x;
     let scroll_y = node.scroll_y;
+    let overflow = node.overflow;
     let wrap_mode = node.wrap_mode;
-    let mask_char = node.mask_char;
+    let mask_char = node.shown_mask();
+    let text_direction = node.text_direction;
+    let line_spacing = node.line_spacing;
+    let letter_spacing = node.letter_spacing;
+    let fixed_advance = node.fixed_advance as u32;
+    let line_scale = node.line_scale;
+    let scaled_spacing = node.laid_out_spacing();
+    let text_transform = node.text_transform;
+    let composition = node.shown_composition();
+    let caret_offset = node.display_offset(node.cursor_position as usize);
+    let cell_art: Vec<((u16, u16), Cell)> = node
+        .cell_art
+        .iter()
+        .map(|(&pos, cell)| (pos, cell.clone()))
+        .collect();
     let children: Vec<u32> = node.children.clone();
 
-    // Render background fill
-    if bg != 0 {
+    // Render background fill. A default bg leaves the terminal's own
+    // background showing through.
+    if !is_default_color(bg) {
         // Use the resolved fg color (not 0/default) so the writer never emits
         // SetForegroundColor(Reset) for background-only cells — some terminals
         // render thin lines when reset escapes interleave with RGB bg fills.
-        let fill_fg = if fg != 0 { fg } else { bg };
+        let fill_fg = if !is_default_color(fg) { fg } else { bg };
         for row in 0..h {
             for col in 0..w {
                 clip_set(
@@ -507,6 +922,25 @@ fn render_node(
         render_border(ctx, abs_x, abs_y, w, h, border_style, border_fg, bg, clip);
     }
 
+    // Blitted cell art sits over the fill and border, clipped to the node.
+    if !cell_art.is_empty() {
+        let art_clip = clip.intersect(ClipRect {
+            x: abs_x,
+            y: abs_y,
+            w,
+            h,
+        });
+        for ((x, y), cell) in cell_art {
+            clip_set(
+                &mut ctx.front_buffer,
+                abs_x + x as i32,
+                abs_y + y as i32,
+                cell,
+                art_clip,
+            );
+        }
+    }
+
     // Render content area (inside border if present)
     let (content_x, content_y, content_w, content_h) = if border_style != BorderStyle::None {
         let ch = (h - 2).max(0);
@@ -529,10 +963,80 @@ fn render_node(
                 let mask = char::from_u32(mask_char).unwrap_or('*');
                 mask.to_string().repeat(grapheme_count(&content))
             } else {
-                content.clone()
+                crate::text_utils::transform_case(&content, text_transform).into_owned()
             };
+            // An IME composition is drawn inserted at the Input cursor and
+            // underlined; the content itself is untouched.
+            let mut composing = None;
+            let display_content = match composition {
+                Some(text) if node_type == NodeType::Input => {
+                    let at = caret_offset.min(grapheme_count(&display_content));
+                    composing = Some((at, at + grapheme_count(&text)));
+                    let mut shown = display_content;
+                    shown.insert_str(grapheme_to_byte_idx(&shown, at), &text);
+                    shown
+                }
+                _ => display_content,
+            };
+
+            // A scaled line keeps its glyphs on even columns so the writer can
+            // fold the row into the half-width grid the terminal draws. Without
+            // terminal support it is drawn packed, at normal size.
+            let scaled = node_type == NodeType::Text
+                && content_format == ContentFormat::Plain
+                && line_scale != LineScale::Normal
+                && ctx.terminal_capabilities.supports(
+                    crate::terminal_capabilities::terminal_capability::DEC_LINE_ATTRIBUTES,
+                );
+            let (letter_spacing, fixed_advance) = if scaled {
+                scaled_spacing
+            } else {
+                (letter_spacing, fixed_advance)
+            };
+            let (content_x, content_w) = if scaled {
+                let pad = content_x.rem_euclid(2);
+                for row in content_y..content_y + content_h {
+                    if clip.contains(clip.x, row) && row >= 0 {
+                        ctx.line_scale_rows.push((row as u16, line_scale));
+                    }
+                }
+                (content_x + pad, content_w - pad)
+            } else {
+                (content_x, content_w)
+            };
+
+            // Plain Text with right-to-left lines, letter spacing or a
+            // monospace grid uses the direct renderer; the substrate view
+            // only lays out packed left-to-right text.
+            let direct_plain = node_type == NodeType::Text
+                && content_format == ContentFormat::Plain
+                && (letter_spacing > 0
+                    || fixed_advance > 0
+                    || display_content
+                        .split('\n')
+                        .any(|line| crate::text_utils::is_rtl_line(line, text_direction)));
+
+            // A marquee only scrolls text that does not fit on one row.
+            let marquee = text_marquee.filter(|_| {
+                node_type == NodeType::Text
+                    && display_width_of_text_graphemes(&display_content) > content_w
+            });
 
-            if node_type == NodeType::Text {
+            if let Some((offset, gap)) = marquee {
+                render_marquee_text(
+                    ctx,
+                    &display_content.replace('\n', " "),
+                    content_x,
+                    content_y,
+                    content_w,
+                    offset as i32,
+                    gap as i32,
+                    fg,
+                    bg,
+                    attrs,
+                    clip,
+                );
+            } else if node_type == NodeType::Text && !direct_plain {
                 let (_buffer_handle, view_handle) = ensure_node_text_handles(ctx, handle)?;
                 let spans = if content_format == ContentFormat::Plain {
                     vec![crate::types::StyledSpan {
@@ -553,6 +1057,14 @@ fn render_node(
                 };
                 let wrap_start = std::time::Instant::now();
                 apply_styled_text_to_buffer(ctx, _buffer_handle, &spans, bg, opacity)?;
+                // Highlight offsets index the source text, which only lines
+                // up with the rendered buffer for plain content.
+                let highlights = if content_format == ContentFormat::Plain {
+                    node_highlight_ranges(ctx, handle, &display_content)
+                } else {
+                    Vec::new()
+                };
+                text_buffer::replace_highlights(ctx, _buffer_handle, &highlights)?;
                 text_view::clear_cursor(ctx, view_handle)?;
                 text_view::set_wrap(ctx, view_handle, content_w.max(1) as u32, 1, 4)?;
                 render_substrate_view(
@@ -564,6 +1076,7 @@ fn render_node(
                     content_h,
                     0,
                     0,
+                    line_spacing,
                     fg,
                     bg,
                     attrs,
@@ -572,6 +1085,31 @@ fn render_node(
                 drain_buffer_dirty_ranges(ctx, _buffer_handle);
                 ctx.perf_text_wrap_us += wrap_start.elapsed().as_micros() as u64;
             } else {
+                let highlights = if node_type == NodeType::Text {
+                    ctx.nodes
+                        .get(&handle)
+                        .map(|n| n.text_highlights.clone())
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+                // A focused Input shows its selection inverted, mapped onto
+                // the transformed text the same way the cursor is. It is
+                // hidden while a composition is pending.
+                let selection = if node_type == NodeType::Input
+                    && ctx.focused == Some(handle)
+                    && composing.is_none()
+                {
+                    ctx.nodes.get(&handle).and_then(|n| {
+                        let (start, end) = n.input_selection()?;
+                        Some((
+                            n.display_offset(start as usize),
+                            n.display_offset(end as usize),
+                        ))
+                    })
+                } else {
+                    None
+                };
                 render_plain_text(
                     ctx,
                     &display_content,
@@ -582,6 +1120,13 @@ fn render_node(
                     fg,
                     bg,
                     attrs,
+                    text_direction,
+                    &highlights,
+                    selection,
+                    composing,
+                    line_spacing as i32,
+                    letter_spacing as i32,
+                    fixed_advance as i32,
                     clip,
                 );
             }
@@ -597,6 +1142,7 @@ fn render_node(
                     content_w,
                     fg,
                     bg,
+                    text_direction,
                     clip,
                 );
             }
@@ -606,7 +1152,10 @@ fn render_node(
             let content = ctx
                 .text_buffers
                 .get(&buffer_handle)
-                .ok_or_else(|| format!("Invalid TextBuffer handle: {buffer_handle}"))?
+                .ok_or_else(|| {
+                    ErrorCode::InvalidHandle
+                        .raise(format!("Invalid TextBuffer handle: {buffer_handle}"))
+                })?
                 .content()
                 .to_string();
             let focused = ctx.focused == Some(handle);
@@ -617,6 +1166,7 @@ fn render_node(
                 mut ts_view_col,
                 ts_sel_anchor,
                 ts_sel_focus,
+                ts_follow_tail,
             ) = ctx
                 .nodes
                 .get(&handle)
@@ -636,9 +1186,10 @@ fn render_node(
                         n.textarea_view_col,
                         sel_a,
                         sel_f,
+                        n.autoscroll && n.autoscroll_pinned,
                     )
                 })
-                .unwrap_or((0, 0, 0, 0, None, None));
+                .unwrap_or((0, 0, 0, 0, None, None, false));
             let mut cursor_visual = None;
             text_buffer::clear_style_spans(ctx, buffer_handle)?;
             if focused {
@@ -653,7 +1204,8 @@ fn render_node(
             } else {
                 text_buffer::clear_selection(ctx, buffer_handle)?;
             }
-            text_buffer::clear_highlights(ctx, buffer_handle)?;
+            let highlights = node_highlight_ranges(ctx, handle, &content);
+            text_buffer::replace_highlights(ctx, buffer_handle, &highlights)?;
             text_view::set_wrap(
                 ctx,
                 view_handle,
@@ -661,17 +1213,32 @@ fn render_node(
                 if wrap_mode == 0 { 0 } else { 1 },
                 4,
             )?;
+            let visual_rows = text_view::get_visual_line_count(ctx, view_handle)?;
+            let view_rows =
+                crate::text::rows_in_height(content_h.max(1) as u32, line_spacing).max(1);
+            let max_view_row = visual_rows.saturating_sub(view_rows);
+            if ts_follow_tail {
+                ts_view_row = max_view_row;
+            }
             let cursor_byte =
                 crate::textarea::position_to_byte_offset(&content, ts_cursor_row, ts_cursor_col);
             if focused {
-                text_view::set_cursor(ctx, view_handle, cursor_byte)?;
+                // A hidden caret (host-driven blink) still steers the viewport.
+                let caret_shown = ctx.caret_visible();
+                if caret_shown {
+                    text_view::set_cursor(ctx, view_handle, cursor_byte)?;
+                } else {
+                    text_view::clear_cursor(ctx, view_handle)?;
+                }
                 let (cursor_visual_row, cursor_visual_col) =
                     text_view::byte_to_visual(ctx, view_handle, cursor_byte)?;
-                cursor_visual = Some((cursor_visual_row, cursor_visual_col));
+                if caret_shown {
+                    cursor_visual = Some((cursor_visual_row, cursor_visual_col));
+                }
                 if cursor_visual_row < ts_view_row {
                     ts_view_row = cursor_visual_row;
-                } else if cursor_visual_row >= ts_view_row + content_h.max(1) as u32 {
-                    ts_view_row = cursor_visual_row - content_h.max(1) as u32 + 1;
+                } else if cursor_visual_row >= ts_view_row + view_rows {
+                    ts_view_row = cursor_visual_row - view_rows + 1;
                 }
                 if wrap_mode != 0 {
                     ts_view_col = 0;
@@ -685,6 +1252,7 @@ fn render_node(
             }
             if let Some(node) = ctx.nodes.get_mut(&handle) {
                 node.textarea_view_row = ts_view_row;
+                node.autoscroll_pinned = ts_view_row >= max_view_row;
                 node.textarea_view_col = if wrap_mode != 0 { 0 } else { ts_view_col };
             }
             render_substrate_view(
@@ -696,13 +1264,15 @@ fn render_node(
                 content_h,
                 ts_view_row,
                 if wrap_mode != 0 { 0 } else { ts_view_col },
+                line_spacing,
                 fg,
                 bg,
                 attrs,
                 clip,
             )?;
             if let Some((cursor_visual_row, cursor_visual_col)) = cursor_visual {
-                let screen_y = content_y + (cursor_visual_row as i32 - ts_view_row as i32);
+                let screen_y = content_y
+                    + (cursor_visual_row as i32 - ts_view_row as i32) * (1 + line_spacing as i32);
                 let screen_x = content_x
                     + (cursor_visual_col as i32
                         - if wrap_mode != 0 {
@@ -721,21 +1291,7 @@ fn render_node(
                         .and_then(|tail| UnicodeSegmentation::graphemes(tail, true).next())
                         .and_then(|g| g.chars().next())
                         .unwrap_or(' ');
-                    let inv_fg = if bg != 0 { bg } else { 0x00000000 };
-                    let inv_bg = if fg != 0 { fg } else { 0x01FFFFFF };
-                    clip_set(
-                        &mut ctx.front_buffer,
-                        screen_x,
-                        screen_y,
-                        Cell {
-                            ch: cursor_char,
-                            fg: inv_fg,
-                            bg: inv_bg,
-                            attrs: CellAttrs::empty(),
-                            link: None,
-                        },
-                        clip,
-                    );
+                    draw_caret(ctx, screen_x, screen_y, cursor_char, fg, bg, clip);
                 }
             }
             text_buffer::clear_dirty_ranges(ctx, buffer_handle)?;
@@ -745,15 +1301,74 @@ fn render_node(
                 ctx, handle, content_x, content_y, content_w, content_h, fg, bg, attrs, clip,
             );
         }
+        NodeType::ComboBox => {
+            // Editable line on the first row, matching options below it.
+            render_plain_text(
+                ctx,
+                &content,
+                content_x,
+                content_y,
+                content_w,
+                content_h.min(1),
+                fg,
+                bg,
+                attrs,
+                text_direction,
+                &[],
+                None,
+                None,
+                0,
+                0,
+                0,
+                clip,
+            );
+            if ctx.focused == Some(handle) {
+                render_input_cursor(
+                    ctx,
+                    handle,
+                    &content,
+                    content_x,
+                    content_y,
+                    content_w,
+                    fg,
+                    bg,
+                    text_direction,
+                    clip,
+                );
+            }
+            if content_h > 1 {
+                render_select_options(
+                    ctx,
+                    handle,
+                    content_x,
+                    content_y + 1,
+                    content_w,
+                    content_h - 1,
+                    fg,
+                    bg,
+                    attrs,
+                    clip,
+                );
+            }
+        }
         NodeType::ScrollBox => {
             // Re-clamp scroll positions to current layout bounds (safety net
             // for cases where layout changed since set_scroll was called)
             let (max_sx, max_sy) = crate::scroll::compute_max_scroll(ctx, handle);
+            let follow_tail = ctx
+                .nodes
+                .get(&handle)
+                .is_some_and(|n| n.autoscroll && n.autoscroll_pinned);
             let clamped_sx = scroll_x.clamp(0, max_sx);
-            let clamped_sy = scroll_y.clamp(0, max_sy);
+            let clamped_sy = if follow_tail {
+                max_sy
+            } else {
+                scroll_y.clamp(0, max_sy)
+            };
             if let Some(node) = ctx.nodes.get_mut(&handle) {
                 node.scroll_x = clamped_sx;
                 node.scroll_y = clamped_sy;
+                node.autoscroll_pinned = clamped_sy >= max_sy;
             }
 
             // Compute clip rect for ScrollBox children: intersection of parent clip
@@ -766,15 +1381,16 @@ fn render_node(
             };
             let child_clip = clip.intersect(scrollbox_clip);
 
-            for &child_handle in &children {
-                render_node(
-                    ctx,
-                    child_handle,
-                    content_x - clamped_sx,
-                    content_y - clamped_sy,
-                    child_clip,
-                )?;
+            if dim {
+                dim_cells(ctx, abs_x, abs_y, w, h, clip);
             }
+            push_children(
+                pending,
+                &children,
+                content_x - clamped_sx,
+                content_y - clamped_sy,
+                child_clip,
+            );
             return Ok(());
         }
         NodeType::Overlay => {
@@ -835,18 +1451,112 @@ fn render_node(
                 ctx, handle, content_x, content_y, content_w, content_h, fg, bg, clip,
             );
         }
+        NodeType::Image => {
+            // The payload is only emitted when the terminal can show it and
+            // the whole rect is on screen: an image escape can't be clipped.
+            let supported = ctx
+                .terminal_capabilities
+                .supports(crate::terminal_capabilities::terminal_capability::INLINE_IMAGES);
+            let has_payload = ctx
+                .nodes
+                .get(&handle)
+                .is_some_and(|n| !n.image_payload.is_empty());
+            let visible = w > 0
+                && h > 0
+                && abs_x >= 0
+                && abs_y >= 0
+                && clip.contains(abs_x, abs_y)
+                && clip.contains(abs_x + w - 1, abs_y + h - 1);
+            if supported && has_payload && visible {
+                // Reserve the rect with blanks; the image is drawn over them.
+                for row in 0..h {
+                    for col in 0..w {
+                        clip_set(
+                            &mut ctx.front_buffer,
+                            abs_x + col,
+                            abs_y + row,
+                            Cell {
+                                ch: ' ',
+                                fg,
+                                bg,
+                                attrs: CellAttrs::empty(),
+                                link: None,
+                            },
+                            clip,
+                        );
+                    }
+                }
+                ctx.image_placements
+                    .push((handle, abs_x as u16, abs_y as u16, w as u16, h as u16));
+            } else {
+                // Placeholder: a border (unless the node has its own) around
+                // the alt text.
+                let (x, y, alt_w, alt_h) = if border_style == BorderStyle::None {
+                    render_border(ctx, abs_x, abs_y, w, h, BorderStyle::Single, fg, bg, clip);
+                    (abs_x + 1, abs_y + 1, (w - 2).max(0), (h - 2).max(0))
+                } else {
+                    (content_x, content_y, content_w, content_h)
+                };
+                render_plain_text(
+                    ctx,
+                    &content,
+                    x,
+                    y,
+                    alt_w,
+                    alt_h,
+                    fg,
+                    bg,
+                    attrs,
+                    text_direction,
+                    &[],
+                    None,
+                    None,
+                    0,
+                    0,
+                    0,
+                    clip,
+                );
+            }
+        }
+    }
+
+    if dim {
+        dim_cells(ctx, abs_x, abs_y, w, h, clip);
     }
 
     // Render children (except ScrollBox which handled above; leaf types have no children)
     if !node_type.is_leaf() && node_type != NodeType::ScrollBox {
-        for &child_handle in &children {
-            render_node(ctx, child_handle, abs_x, abs_y, clip)?;
-        }
+        let child_clip = if overflow == Overflow::Hidden {
+            clip.intersect(ClipRect {
+                x: content_x,
+                y: content_y,
+                w: content_w,
+                h: content_h,
+            })
+        } else {
+            clip
+        };
+        push_children(pending, &children, abs_x, abs_y, child_clip);
     }
 
     Ok(())
 }
 
+/// Set DIM on every cell of a node's rect. Runs after the node's own fill,
+/// border and content and before its children, so children fade only
+/// through their own opacity.
+fn dim_cells(ctx: &mut TuiContext, x: i32, y: i32, w: i32, h: i32, clip: ClipRect) {
+    for row in y.max(0)..y + h {
+        for col in x.max(0)..x + w {
+            if let Some(cell) = ctx.front_buffer.get(col as u16, row as u16) {
+                let mut cell = cell.clone();
+                cell.attrs |= CellAttrs::DIM;
+                clip_set(&mut ctx.front_buffer, col, row, cell, clip);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Border Rendering
 // ============================================================================
@@ -1010,44 +1720,95 @@ fn render_plain_text(
     fg: u32,
     bg: u32,
     attrs: CellAttrs,
+    direction: TextDirection,
+    highlights: &[(usize, usize)],
+    selection: Option<(usize, usize)>,
+    underline: Option<(usize, usize)>,
+    line_spacing: i32,
+    letter_spacing: i32,
+    fixed_advance: i32,
     clip: ClipRect,
 ) {
-    let mut col = 0i32;
+    let pitch = 1 + line_spacing;
+    let highlight_bg = text_renderer::HighlightPalette::theme_tinted(bg).search_bg;
     let mut row = 0i32;
+    // Grapheme index into `text`, matched against `highlights`.
+    let mut g_idx = 0usize;
 
-    for ch in text.chars() {
+    for line in text.split('\n') {
         if row >= max_h {
             break;
         }
-        if ch == '\n' {
-            row += 1;
-            col = 0;
-            continue;
-        }
-        let char_width = UnicodeWidthChar::width(ch).unwrap_or(0) as i32;
-        if col + char_width > max_w {
-            row += 1;
-            col = 0;
-            if row >= max_h {
-                break;
+        // Right-to-left lines mirror the column: they start at the right
+        // edge and wrap onto the next row the same way.
+        let rtl = crate::text_utils::is_rtl_line(line, direction);
+        let mut col = 0i32;
+
+        // Walk grapheme clusters so the advance matches the cursor math in
+        // `render_input_cursor`; the cell shows the cluster's base scalar.
+        for grapheme in UnicodeSegmentation::graphemes(line, true) {
+            let highlighted = highlights
+                .iter()
+                .any(|&(start, end)| g_idx >= start && g_idx < end);
+            let selected = selection.is_some_and(|(start, end)| g_idx >= start && g_idx < end);
+            let underlined = underline.is_some_and(|(start, end)| g_idx >= start && g_idx < end);
+            g_idx += 1;
+            let natural_width = crate::text_utils::display_width(grapheme) as i32;
+            if natural_width == 0 {
+                continue;
             }
+            let mut ch = grapheme.chars().next().unwrap_or(' ');
+            // A monospace grid gives every grapheme the same advance; ones
+            // too wide for it are truncated to an ellipsis.
+            let char_width = if fixed_advance > 0 {
+                if natural_width > fixed_advance {
+                    ch = '\u{2026}';
+                }
+                fixed_advance
+            } else {
+                natural_width
+            };
+            if col + char_width > max_w {
+                row += pitch;
+                col = 0;
+                if row >= max_h {
+                    return;
+                }
+            }
+            if col < max_w {
+                // Selection inverts fg/bg, like the TextArea substrate.
+                let cell_bg = if highlighted { highlight_bg } else { bg };
+                let cell_x = if rtl {
+                    (max_w - col - char_width).max(0)
+                } else {
+                    col
+                };
+                clip_set(
+                    &mut ctx.front_buffer,
+                    x + cell_x,
+                    y + row,
+                    Cell {
+                        ch,
+                        fg: if selected { cell_bg } else { fg },
+                        bg: if selected { fg } else { cell_bg },
+                        attrs: if underlined {
+                            attrs | CellAttrs::UNDERLINE
+                        } else {
+                            attrs
+                        },
+                        link: None,
+                    },
+                    clip,
+                );
+            }
+            col += char_width + letter_spacing;
         }
-        if col < max_w {
-            clip_set(
-                &mut ctx.front_buffer,
-                x + col,
-                y + row,
-                Cell {
-                    ch,
-                    fg,
-                    bg,
-                    attrs,
-                    link: None,
-                },
-                clip,
-            );
+        // The '\n' separator is a grapheme of its own unless it joined a
+        // preceding '\r', which the line walk already counted.
+        if !line.ends_with('\r') {
+            g_idx += 1;
         }
-        col += char_width;
+        row += pitch;
     }
 }
 
@@ -1087,7 +1848,7 @@ fn render_styled_spans(
                 col = 0;
                 continue;
             }
-            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0) as i32;
+            let char_width = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0) as i32;
             if col + char_width > max_w {
                 row += 1;
                 col = 0;
@@ -1131,8 +1892,12 @@ fn render_input_cursor(
     content_w: i32,
     fg: u32,
     bg: u32,
+    direction: TextDirection,
     clip: ClipRect,
 ) {
+    if !ctx.caret_visible() {
+        return;
+    }
     let node = match ctx.nodes.get(&handle) {
         Some(n) => n,
         None => return,
@@ -1140,42 +1905,93 @@ fn render_input_cursor(
 
     // Clamp cursor_pos to display content length to handle edge cases
     // where cursor_position exceeds content (e.g., content truncated externally)
+    // The caret sits after a pending composition.
     let grapheme_len = grapheme_count(display_content);
-    let cursor_pos = (node.cursor_position as usize).min(grapheme_len);
-
-    // Calculate cursor x-offset by measuring width of graphemes up to cursor_pos.
-    let cursor_x_offset = display_width_of_prefix_graphemes(display_content, cursor_pos);
+    let composed = node.shown_composition().map_or(0, |c| grapheme_count(&c));
+    let cursor_pos =
+        (node.display_offset(node.cursor_position as usize) + composed).min(grapheme_len);
+
+    // Calculate cursor x-offset by measuring width of graphemes up to cursor_pos,
+    // plus the letter spacing that follows each of them.
+    let prefix_width = if node.fixed_advance > 0 {
+        cursor_pos as i32 * node.fixed_advance as i32
+    } else {
+        display_width_of_prefix_graphemes(display_content, cursor_pos)
+    };
+    let cursor_x_offset = prefix_width + cursor_pos as i32 * node.letter_spacing as i32;
 
     if cursor_x_offset >= content_w {
         return; // Cursor is beyond visible area
     }
 
-    let sx = content_x + cursor_x_offset;
+    // RTL mirrors the offset: the cursor cell is the rightmost cell of the
+    // grapheme at the cursor (or the empty cell left of the text at the end).
+    let sx = if crate::text_utils::is_rtl_line(display_content, direction) {
+        content_x + content_w - cursor_x_offset - 1
+    } else {
+        content_x + cursor_x_offset
+    };
     let sy = content_y; // Single-line input, cursor always on row 0
 
-    // Character under the cursor (or space if at end of content)
+    // Character under the cursor (or space if at end of content), shown the
+    // way `render_plain_text` draws it.
+    let fixed_advance = node.fixed_advance as usize;
     let cursor_char = UnicodeSegmentation::graphemes(display_content, true)
         .nth(cursor_pos)
-        .and_then(|g| g.chars().next())
+        .and_then(|g| {
+            if fixed_advance > 0 && crate::text_utils::display_width(g) > fixed_advance {
+                Some('\u{2026}')
+            } else {
+                g.chars().next()
+            }
+        })
         .unwrap_or(' ');
 
-    // Inverted colors: swap fg and bg
-    let inv_fg = if bg != 0 { bg } else { 0x00000000 };
-    let inv_bg = if fg != 0 { fg } else { 0x01FFFFFF };
+    draw_caret(ctx, sx, sy, cursor_char, fg, bg, clip);
+}
 
-    clip_set(
-        &mut ctx.front_buffer,
-        sx,
-        sy,
-        Cell {
-            ch: cursor_char,
-            fg: inv_fg,
-            bg: inv_bg,
+/// Paint the caret at (sx, sy) in the context's caret style. `ch` is the
+/// character under the caret; bar and underline keep whatever was already
+/// drawn in the cell (selection, highlights) underneath.
+fn draw_caret(ctx: &mut TuiContext, sx: i32, sy: i32, ch: char, fg: u32, bg: u32, clip: ClipRect) {
+    let under = if sx >= 0 && sy >= 0 {
+        ctx.front_buffer.get(sx as u16, sy as u16).cloned()
+    } else {
+        None
+    };
+    let cell = match ctx.caret_style {
+        CaretStyle::Block => {
+            // Inverted colors: swap fg and bg
+            let inv_fg = if bg != 0 { bg } else { 0x00000000 };
+            let inv_bg = if fg != 0 { fg } else { 0x01FFFFFF };
+            Cell {
+                ch,
+                fg: inv_fg,
+                bg: inv_bg,
+                attrs: CellAttrs::empty(),
+                link: None,
+            }
+        }
+        CaretStyle::Bar => Cell {
+            ch: '\u{258F}',
+            fg,
+            bg: under.map_or(bg, |c| c.bg),
             attrs: CellAttrs::empty(),
             link: None,
         },
-        clip,
-    );
+        CaretStyle::Underline => {
+            let mut cell = under.unwrap_or(Cell {
+                ch,
+                fg,
+                bg,
+                attrs: CellAttrs::empty(),
+                link: None,
+            });
+            cell.attrs |= CellAttrs::UNDERLINE;
+            cell
+        }
+    };
+    clip_set(&mut ctx.front_buffer, sx, sy, cell, clip);
 }
 
 #[cfg(test)]
@@ -1195,7 +2011,7 @@ fn slice_graphemes(s: &str, start: usize, end: usize) -> String {
 }
 
 fn display_width_of_grapheme(grapheme: &str) -> i32 {
-    (UnicodeWidthStr::width(grapheme) as i32).max(1)
+    (crate::text_utils::display_width(grapheme) as i32).max(1)
 }
 
 fn display_width_of_prefix_graphemes(s: &str, graphemes: usize) -> i32 {
@@ -1205,7 +2021,6 @@ fn display_width_of_prefix_graphemes(s: &str, graphemes: usize) -> i32 {
         .sum()
 }
 
-#[cfg(test)]
 fn display_width_of_text_graphemes(s: &str) -> i32 {
     UnicodeSegmentation::graphemes(s, true)
         .map(display_width_of_grapheme)
@@ -1353,7 +2168,10 @@ fn render_select_options(
 
     let options = node.options.clone();
     let selected_index = node.selected_index;
-    let option_count = options.len() as i32;
+    let overflow = node.option_overflow;
+    // Rows map onto the filtered view; each entry is a full-list index.
+    let visible = node.visible_option_indices();
+    let option_count = visible.len() as i32;
 
     if option_count == 0 {
         return;
@@ -1361,7 +2179,9 @@ fn render_select_options(
 
     // Compute viewport offset when options exceed visible height
     let viewport_offset = if option_count > content_h {
-        let selected = selected_index.unwrap_or(0) as i32;
+        let selected = selected_index
+            .and_then(|sel| visible.iter().position(|&i| i == sel))
+            .unwrap_or(0) as i32;
         let ideal_offset = selected - content_h / 2;
         ideal_offset.max(0).min(option_count - content_h)
     } else {
@@ -1370,10 +2190,10 @@ fn render_select_options(
 
     // Render visible options
     for row in 0..content_h {
-        let option_idx = (viewport_offset + row) as usize;
-        if option_idx >= options.len() {
+        let Some(&option_idx) = visible.get((viewport_offset + row) as usize) else {
             break;
-        }
+        };
+        let option_idx = option_idx as usize;
 
         let is_selected = selected_index == Some(option_idx as u32);
         let (row_fg, row_bg) = if is_selected {
@@ -1403,12 +2223,28 @@ fn render_select_options(
             }
         }
 
-        // Render option text (truncated to content_w)
+        // Render option text, fitted to content_w per the overflow mode
         let opt = &options[option_idx];
+        let overflows = display_width_of_text_graphemes(opt) > content_w;
+        let skip = if overflow == OptionOverflow::Marquee && is_selected && overflows {
+            advance_option_marquee(ctx, handle, option_idx as u32, opt, content_w)
+        } else {
+            if is_selected {
+                if let Some(node) = ctx.nodes.get_mut(&handle) {
+                    node.marquee = None;
+                }
+            }
+            0
+        };
+        let ellipsis = overflow == OptionOverflow::Ellipsis && overflows;
+        let text_w = if ellipsis { content_w - 1 } else { content_w };
         let mut col = 0i32;
-        for ch in opt.chars() {
-            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0) as i32;
-            if col + char_width > content_w {
+        for grapheme in UnicodeSegmentation::graphemes(opt.as_str(), true).skip(skip) {
+            let char_width = display_width_of_grapheme(grapheme);
+            if char_width == 0 {
+                continue;
+            }
+            if col + char_width > text_w {
                 break;
             }
             clip_set(
@@ -1416,7 +2252,7 @@ fn render_select_options(
                 content_x + col,
                 content_y + row,
                 Cell {
-                    ch,
+                    ch: grapheme.chars().next().unwrap_or(' '),
                     fg: row_fg,
                     bg: row_bg,
                     attrs,
@@ -1426,9 +2262,160 @@ fn render_select_options(
             );
             col += char_width;
         }
+        if ellipsis && content_w > 0 {
+            clip_set(
+                &mut ctx.front_buffer,
+                content_x + col,
+                content_y + row,
+                Cell {
+                    ch: '\u{2026}',
+                    fg: row_fg,
+                    bg: row_bg,
+                    attrs,
+                    link: None,
+                },
+                clip,
+            );
+        }
     }
 }
 
+/// Draw one row of `text` scrolled left by `offset` cells, repeating it
+/// with `gap` blank cells in between so the row is always full.
+#[allow(clippy::too_many_arguments)]
+fn render_marquee_text(
+    ctx: &mut TuiContext,
+    text: &str,
+    x: i32,
+    y: i32,
+    max_w: i32,
+    offset: i32,
+    gap: i32,
+    fg: u32,
+    bg: u32,
+    attrs: CellAttrs,
+    clip: ClipRect,
+) {
+    let period = display_width_of_text_graphemes(text) + gap;
+    if period <= 0 {
+        return;
+    }
+    let mut col = -(offset % period);
+    while col < max_w {
+        for grapheme in UnicodeSegmentation::graphemes(text, true) {
+            let char_width = display_width_of_grapheme(grapheme);
+            if col >= 0 && col + char_width <= max_w {
+                clip_set(
+                    &mut ctx.front_buffer,
+                    x + col,
+                    y,
+                    Cell {
+                        ch: grapheme.chars().next().unwrap_or(' '),
+                        fg,
+                        bg,
+                        attrs,
+                        link: None,
+                    },
+                    clip,
+                );
+            }
+            col += char_width;
+        }
+        col += gap;
+    }
+}
+
+/// Step the Select marquee for the selected option and return how many
+/// graphemes to skip this frame. The offset grows by one every
+/// `OPTION_MARQUEE_STEP_MS` of `ctx.now()` until the tail is in view, then
+/// wraps to the start, so the speed does not depend on the frame rate.
+fn advance_option_marquee(
+    ctx: &mut TuiContext,
+    handle: u32,
+    option_idx: u32,
+    label: &str,
+    content_w: i32,
+) -> usize {
+    // Largest skip that still leaves the label's tail filling the row.
+    let widths: Vec<i32> = UnicodeSegmentation::graphemes(label, true)
+        .map(display_width_of_grapheme)
+        .collect();
+    let mut remaining: i32 = widths.iter().sum();
+    let mut max_skip = 0usize;
+    for w in &widths {
+        if remaining <= content_w {
+            break;
+        }
+        remaining -= w;
+        max_skip += 1;
+    }
+
+    let now = ctx.now();
+    let Some(node) = ctx.nodes.get_mut(&handle) else {
+        return 0;
+    };
+    let marquee = match node.marquee {
+        Some(m) if m.option == option_idx => {
+            let steps =
+                now.duration_since(m.stepped_at).as_millis() as u64 / OPTION_MARQUEE_STEP_MS;
+            OptionMarquee {
+                option: option_idx,
+                skip: ((m.skip as u64 + steps) % (max_skip as u64 + 1)) as usize,
+                stepped_at: m.stepped_at
+                    + std::time::Duration::from_millis(steps * OPTION_MARQUEE_STEP_MS),
+            }
+        }
+        _ => OptionMarquee {
+            option: option_idx,
+            skip: 0,
+            stepped_at: now,
+        },
+    };
+    node.marquee = Some(marquee);
+    marquee.skip
+}
+
+/// Draw the open popup menu: a cleared, bordered box with the items laid
+/// out like Select options.
+fn render_open_menu(ctx: &mut TuiContext) {
+    let (Some(open), Some((x, y, w, h))) = (ctx.open_menu, crate::menu::open_rect(ctx)) else {
+        return;
+    };
+    let resolved = crate::style::resolve_style(open.handle, ctx);
+    let (fg, bg) = (resolved.fg_color, resolved.bg_color);
+    let clip = ClipRect::full(ctx.front_buffer.width, ctx.front_buffer.height);
+    for row in 0..h {
+        for col in 0..w {
+            clip_set(
+                &mut ctx.front_buffer,
+                x + col,
+                y + row,
+                Cell {
+                    ch: ' ',
+                    fg,
+                    bg,
+                    attrs: CellAttrs::empty(),
+                    link: None,
+                },
+                clip,
+            );
+        }
+    }
+    render_border(ctx, x, y, w, h, BorderStyle::Single, fg, bg, clip);
+    render_select_options(
+        ctx,
+        open.handle,
+        x + 1,
+        y + 1,
+        w - 2,
+        h - 2,
+        fg,
+        bg,
+        resolved.attrs,
+        clip,
+    );
+}
+
 // ============================================================================
 // Table Rendering (ADR-T27)
 // ============================================================================
@@ -1827,32 +2814,98 @@ fn render_tabs(
 // ============================================================================
 
 /// Diff front buffer vs back buffer. Returns updates for changed cells.
-fn diff_buffers(ctx: &TuiContext) -> Vec<CellUpdate> {
-    let mut updates = Vec::new();
-    let w = ctx.front_buffer.width;
-    let h = ctx.front_buffer.height;
-
-    for y in 0..h {
-        for x in 0..w {
-            let front = ctx.front_buffer.get(x, y).unwrap();
-            let back = ctx.back_buffer.get(x, y);
-
-            let changed = match back {
-                Some(b) => front != b,
-                None => true,
-            };
+/// Per-row line scale for this frame. Changed rows are written to the
+/// backend and invalidated in the back buffer. Without terminal support
+/// every row stays `Normal`.
+fn sync_line_scales(ctx: &mut TuiContext) -> Result<Vec<LineScale>, TuiError> {
+    let height = ctx.front_buffer.height as usize;
+    let mut scales = vec![LineScale::Normal; height];
+    if !ctx
+        .terminal_capabilities
+        .supports(crate::terminal_capabilities::terminal_capability::DEC_LINE_ATTRIBUTES)
+    {
+        return Ok(scales);
+    }
+    for &(row, scale) in &ctx.line_scale_rows {
+        if let Some(slot) = scales.get_mut(row as usize) {
+            *slot = scale;
+        }
+    }
+    ctx.emitted_line_scales.resize(height, None);
+    for (y, &scale) in scales.iter().enumerate() {
+        if ctx.emitted_line_scales[y] == Some(scale) {
+            continue;
+        }
+        ctx.backend.write_line_scale(y as u16, scale)?;
+        ctx.emitted_line_scales[y] = Some(scale);
+        ctx.back_buffer.invalidate_row(y as u16);
+    }
+    Ok(scales)
+}
 
-            if changed {
-                updates.push(CellUpdate {
-                    x,
-                    y,
-                    cell: front.clone(),
-                });
+/// On scaled rows the terminal draws buffer column `2t` at its column `t`;
+/// the odd spacer columns are never written.
+fn fold_scaled_rows(diff: &mut Vec<CellUpdate>, scales: &[LineScale]) {
+    if scales.iter().all(|&s| s == LineScale::Normal) {
+        return;
+    }
+    diff.retain_mut(|update| {
+        if scales.get(update.y as usize).copied().unwrap_or_default() == LineScale::Normal {
+            return true;
+        }
+        if update.x % 2 != 0 {
+            return false;
+        }
+        update.x /= 2;
+        true
+    });
+}
+
+fn keep_outside_region(ctx: &mut TuiContext, region: ClipRect) {
+    for y in 0..ctx.front_buffer.height {
+        for x in 0..ctx.front_buffer.width {
+            if region.contains(x as i32, y as i32) {
+                continue;
+            }
+            if let Some(cell) = ctx.back_buffer.get(x, y) {
+                let cell = cell.clone();
+                ctx.front_buffer.set(x, y, cell);
             }
         }
     }
+}
 
-    updates
+/// Refill `updates` with the cells that differ between the front and back
+/// buffers, in row-major order. The vector is cleared first.
+///
+/// Rows are compared as whole slices first: most rows of a typical frame are
+/// unchanged and are skipped without per-cell work. `Cell` declares its
+/// scalar fields before `link`, so the derived comparison rejects a changed
+/// cell before touching the link.
+fn diff_buffers(ctx: &TuiContext, updates: &mut Vec<CellUpdate>) {
+    updates.clear();
+    let front = &ctx.front_buffer;
+    let back = &ctx.back_buffer;
+
+    for y in 0..front.height {
+        let Some(front_row) = front.row(y) else {
+            continue;
+        };
+        let back_row = back.row(y).filter(|row| row.len() == front_row.len());
+        if back_row == Some(front_row) {
+            continue;
+        }
+        for (x, cell) in front_row.iter().enumerate() {
+            if back_row.is_some_and(|row| row[x] == *cell) {
+                continue;
+            }
+            updates.push(CellUpdate {
+                x: x as u16,
+                y,
+                cell: cell.clone(),
+            });
+        }
+    }
 }
 
 // ============================================================================
@@ -2160,6 +3213,7 @@ fn render_transcript(
                     (max_y - y).max(0),
                     rows_to_skip,
                     0,
+                    0,
                     block_fg,
                     bg,
                     attrs,
@@ -2200,6 +3254,7 @@ fn render_transcript(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::TestClock;
     use crate::types::Buffer;
 
     // --- ClipRect tests (B1) ---
@@ -2309,6 +3364,86 @@ mod tests {
 
     // --- Opacity blending tests (B4) ---
 
+    #[test]
+    fn test_resize_reflows_percentage_layout() {
+        use crate::terminal::HeadlessBackend;
+
+        let mut ctx = TuiContext::new(Box::new(HeadlessBackend::new(80, 24)));
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let child = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        crate::tree::append_child(&mut ctx, root, child).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_dimension(&mut ctx, root, 0, 100.0, 2).unwrap(); // width 100%
+        crate::layout::set_dimension(&mut ctx, root, 1, 100.0, 2).unwrap(); // height 100%
+        crate::layout::set_dimension(&mut ctx, child, 0, 50.0, 2).unwrap(); // width 50%
+        render(&mut ctx).unwrap();
+        assert_eq!(crate::layout::get_layout(&ctx, root).unwrap().2, 80);
+
+        {
+            let backend = ctx
+                .backend
+                .as_any_mut()
+                .downcast_mut::<HeadlessBackend>()
+                .unwrap();
+            backend.width = 120;
+            backend.height = 40;
+        }
+        render(&mut ctx).unwrap();
+
+        let (_, _, rw, rh) = crate::layout::get_layout(&ctx, root).unwrap();
+        assert_eq!((rw, rh), (120, 40));
+        assert_eq!(crate::layout::get_layout(&ctx, child).unwrap().2, 60);
+        assert_eq!(ctx.front_buffer.width, 120);
+        // The whole resized grid is repainted.
+        assert_eq!(ctx.perf_diff_cells, 120 * 40);
+    }
+
+    #[test]
+    fn test_invalidate_repaints_full_grid() {
+        use crate::terminal::MockBackend;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(20, 5)));
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+
+        render(&mut ctx).unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 0);
+
+        invalidate(&mut ctx);
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 20 * 5);
+
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 0);
+    }
+
+    #[test]
+    fn test_suspend_skips_render_and_resume_repaints() {
+        use crate::terminal::MockBackend;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(20, 5)));
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        render(&mut ctx).unwrap();
+
+        suspend(&mut ctx).unwrap();
+        let frames_before = ctx.frame_seq;
+        ctx.perf_diff_cells = u32::MAX;
+        render(&mut ctx).unwrap();
+        assert_eq!(
+            ctx.perf_diff_cells,
+            u32::MAX,
+            "render must not run while suspended"
+        );
+        assert_eq!(ctx.frame_seq, frames_before);
+
+        resume(&mut ctx).unwrap();
+        assert!(!ctx.suspended);
+        assert_eq!(ctx.root, Some(root));
+        assert_eq!(ctx.perf_diff_cells, 20 * 5);
+    }
+
     #[test]
     fn test_blend_opacity_full() {
         let fg = 0x01FF0000; // red RGB
@@ -2341,94 +3476,758 @@ mod tests {
     }
 
     #[test]
-    fn test_blend_opacity_non_rgb_passthrough() {
-        // Default color — not blended
-        let default = 0x00000000;
-        assert_eq!(blend_opacity(default, 0x01000000, 0.5), default);
+    fn test_blend_opacity_non_rgb_passthrough() {
+        // Default color — not blended
+        let default = 0x00000000;
+        assert_eq!(blend_opacity(default, 0x01000000, 0.5), default);
+
+        // Indexed color — not blended
+        let indexed = 0x02000001;
+        assert_eq!(blend_opacity(indexed, 0x01000000, 0.5), indexed);
+    }
+
+    #[test]
+    fn test_blend_opacity_with_non_rgb_bg() {
+        let fg = 0x01FF0000; // red RGB
+                             // Indexed bg has no known RGB value: blends toward black (0,0,0)
+        let result = blend_opacity(fg, 0x02000004, 0.5);
+        let r = (result >> 16) & 0xFF;
+        assert!(r == 127 || r == 128);
+    }
+
+    #[test]
+    fn test_blend_opacity_leaves_fg_alone_on_default_bg() {
+        let fg = 0x01FF0000; // red RGB
+                             // Terminal-default bg is not black: fg is not dimmed toward it
+        assert_eq!(blend_opacity(fg, crate::types::COLOR_DEFAULT, 0.5), fg);
+        // True RGB black still blends
+        assert_ne!(blend_opacity(fg, 0x01000000, 0.5), fg);
+    }
+
+    #[test]
+    fn test_styled_spans_default_fg_opacity() {
+        use crate::terminal::MockBackend;
+        use crate::types::StyledSpan;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
+        let default_fg = 0x01FF0000; // red RGB
+        let default_bg = 0x01000000; // black RGB
+        let opacity = 0.5;
+        let blended_fg = blend_opacity(default_fg, default_bg, opacity);
+
+        // Span with no explicit fg (fg=0) should inherit the node's blended fg
+        let spans = vec![StyledSpan {
+            text: "A".to_string(),
+            attrs: CellAttrs::empty(),
+            fg: 0, // default — should inherit node's blended fg
+            bg: 0,
+            link: None,
+        }];
+
+        let clip = ClipRect::full(80, 24);
+        render_styled_spans(
+            &mut ctx, &spans, 0, 0, 80, 24, blended_fg, default_bg, clip, opacity,
+        );
+
+        let cell = ctx.front_buffer.get(0, 0).unwrap();
+        assert_eq!(cell.ch, 'A');
+        // The default-colored span should use the blended fg, not 0 (default)
+        assert_eq!(cell.fg, blended_fg);
+    }
+
+    #[test]
+    fn test_blend_opacity_zero_non_rgb() {
+        let bg = 0x01000000; // black RGB
+
+        // Default fg at zero opacity should return bg
+        let default_fg = 0x00000000;
+        assert_eq!(blend_opacity(default_fg, bg, 0.0), bg);
+
+        // Indexed fg at zero opacity should return bg
+        let indexed_fg = 0x02000001;
+        assert_eq!(blend_opacity(indexed_fg, bg, 0.0), bg);
+    }
+
+    // --- Input cursor tests (B2) ---
+
+    #[test]
+    fn test_input_cursor_focused() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
+        let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        ctx.nodes.get_mut(&h).unwrap().content = "hello".into();
+        ctx.nodes.get_mut(&h).unwrap().cursor_position = 2;
+        ctx.nodes.get_mut(&h).unwrap().visual_style.fg_color = 0x01FFFFFF; // white
+        ctx.nodes.get_mut(&h).unwrap().visual_style.bg_color = 0x01000000; // black
+        ctx.focused = Some(h);
+
+        let clip = ClipRect::full(80, 24);
+        render_input_cursor(
+            &mut ctx,
+            h,
+            "hello",
+            0,
+            0,
+            80,
+            0x01FFFFFF,
+            0x01000000,
+            TextDirection::Auto,
+            clip,
+        );
+
+        // Cursor at position 2 means column 2 (ASCII chars are 1-wide)
+        let cell = ctx.front_buffer.get(2, 0).unwrap();
+        assert_eq!(cell.ch, 'l'); // 3rd char of "hello"
+                                  // Colors should be inverted
+        assert_eq!(cell.fg, 0x01000000); // was bg -> now fg
+        assert_eq!(cell.bg, 0x01FFFFFF); // was fg -> now bg
+    }
+
+    #[test]
+    fn test_rtl_input_renders_from_right_edge_with_mirrored_cursor() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(10, 1)));
+        let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        ctx.root = Some(h);
+        // "שלום" — four strong RTL letters, auto-detected.
+        ctx.nodes.get_mut(&h).unwrap().content = "\u{05E9}\u{05DC}\u{05D5}\u{05DD}".into();
+        ctx.nodes.get_mut(&h).unwrap().cursor_position = 1;
+        ctx.focused = Some(h);
+        crate::layout::set_dimension(&mut ctx, h, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, h, 1, 1.0, 1).unwrap();
+        render(&mut ctx).unwrap();
+
+        // First logical letter sits at the right edge, the last one leftmost.
+        assert_eq!(ctx.back_buffer.get(9, 0).unwrap().ch, '\u{05E9}');
+        assert_eq!(ctx.back_buffer.get(6, 0).unwrap().ch, '\u{05DD}');
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, ' ');
+
+        // Cursor before the second letter highlights that letter (column 8).
+        let cursor = ctx.back_buffer.get(8, 0).unwrap();
+        assert_eq!(cursor.ch, '\u{05DC}');
+        assert_ne!(cursor.bg, ctx.back_buffer.get(9, 0).unwrap().bg);
+
+        // An explicit LTR override restores left-aligned placement.
+        ctx.nodes.get_mut(&h).unwrap().text_direction = TextDirection::Ltr;
+        ctx.nodes.get_mut(&h).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, '\u{05E9}');
+    }
+
+    #[test]
+    fn test_mixed_direction_text_wraps_by_character_and_keeps_highlights() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(8, 3)));
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(h);
+        crate::layout::set_dimension(&mut ctx, h, 0, 8.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, h, 1, 3.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&h).unwrap();
+            node.set_content("hello world\n\u{05E9}\u{05DC}\u{05D5}\u{05DD}".to_string());
+            node.text_highlights = vec![(6, 11)]; // "world"
+        }
+        render(&mut ctx).unwrap();
+        let row = |ctx: &TuiContext, y| -> String {
+            (0..8)
+                .map(|x| ctx.back_buffer.get(x, y).unwrap().ch)
+                .collect()
+        };
+
+        // The RTL line sends the whole widget to the simple renderer: the
+        // LTR line breaks mid-word instead of before "world".
+        assert_eq!(row(&ctx, 0), "hello wo");
+        assert_eq!(row(&ctx, 1), "rld     ");
+        assert_eq!(row(&ctx, 2), "    \u{05DD}\u{05D5}\u{05DC}\u{05E9}");
+        let plain_bg = ctx.back_buffer.get(0, 0).unwrap().bg;
+        assert_ne!(ctx.back_buffer.get(6, 0).unwrap().bg, plain_bg);
+        assert_ne!(ctx.back_buffer.get(0, 1).unwrap().bg, plain_bg);
+        assert_eq!(ctx.back_buffer.get(3, 1).unwrap().bg, plain_bg);
+    }
+
+    #[test]
+    fn test_focused_input_selection_renders_inverted() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(10, 1)));
+        let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        ctx.root = Some(h);
+        crate::layout::set_dimension(&mut ctx, h, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, h, 1, 1.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&h).unwrap();
+            node.content = "abcdef".into();
+            node.visual_style.fg_color = 0x01_FF_FF_FF;
+            node.visual_style.bg_color = 0x01_00_00_00;
+            node.cursor_position = 4;
+            node.input_selection_anchor = Some(1);
+        }
+        ctx.focused = Some(h);
+        render(&mut ctx).unwrap();
+
+        let a = ctx.back_buffer.get(0, 0).unwrap();
+        assert_eq!((a.fg, a.bg), (0x01_FF_FF_FF, 0x01_00_00_00));
+        for x in 1..4 {
+            let cell = ctx.back_buffer.get(x, 0).unwrap();
+            assert_eq!(
+                (cell.fg, cell.bg),
+                (0x01_00_00_00, 0x01_FF_FF_FF),
+                "cell {x}"
+            );
+        }
+
+        // Without focus the selection is not drawn.
+        ctx.focused = None;
+        ctx.nodes.get_mut(&h).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(2, 0).unwrap().bg, 0x01_00_00_00);
+    }
+
+    #[test]
+    fn test_hidden_caret_leaves_cell_unstyled_until_focus_moves() {
+        let mut ctx = integration_ctx(10, 4);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let input = crate::tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        let area = crate::tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        crate::tree::append_child(&mut ctx, root, input).unwrap();
+        crate::tree::append_child(&mut ctx, root, area).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_flex(&mut ctx, root, 0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, input, 1, 1.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, area, 1, 2.0, 1).unwrap();
+        for (h, text) in [(input, "ab"), (area, "cd")] {
+            let node = ctx.nodes.get_mut(&h).unwrap();
+            node.content = text.into();
+            node.visual_style.fg_color = 0x01_FF_FF_FF;
+            node.visual_style.bg_color = 0x01_00_00_00;
+        }
+        let caret_bg = |ctx: &TuiContext, y| ctx.back_buffer.get(0, y).unwrap().bg;
+
+        ctx.focused = Some(input);
+        render(&mut ctx).unwrap();
+        assert_eq!(caret_bg(&ctx, 0), 0x01_FF_FF_FF);
+        ctx.caret_hidden_for = Some(input);
+        ctx.nodes.get_mut(&input).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        let cell = ctx.back_buffer.get(0, 0).unwrap();
+        assert_eq!((cell.ch, cell.bg), ('a', 0x01_00_00_00));
+
+        // Moving focus shows the caret again on the new target.
+        ctx.focused = Some(area);
+        assert!(ctx.caret_visible());
+        ctx.nodes.get_mut(&area).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(caret_bg(&ctx, 1), 0x01_FF_FF_FF);
+        ctx.caret_hidden_for = Some(area);
+        ctx.nodes.get_mut(&area).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        let cell = ctx.back_buffer.get(0, 1).unwrap();
+        assert_eq!((cell.ch, cell.bg), ('c', 0x01_00_00_00));
+        assert!(!cell.attrs.contains(CellAttrs::UNDERLINE));
+    }
+
+    #[test]
+    fn test_caret_styles_bar_and_underline() {
+        let mut ctx = integration_ctx(6, 1);
+        let input = crate::tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        ctx.root = Some(input);
+        crate::layout::set_dimension(&mut ctx, input, 0, 6.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, input, 1, 1.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&input).unwrap();
+            node.content = "ab".into();
+            node.visual_style.fg_color = 0x01_FF_FF_FF;
+            node.visual_style.bg_color = 0x01_00_00_00;
+        }
+        ctx.focused = Some(input);
+
+        ctx.caret_style = CaretStyle::Bar;
+        render(&mut ctx).unwrap();
+        let cell = ctx.back_buffer.get(0, 0).unwrap();
+        assert_eq!(
+            (cell.ch, cell.fg, cell.bg),
+            ('\u{258F}', 0x01_FF_FF_FF, 0x01_00_00_00)
+        );
+
+        ctx.caret_style = CaretStyle::Underline;
+        ctx.nodes.get_mut(&input).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        let cell = ctx.back_buffer.get(0, 0).unwrap();
+        assert_eq!(
+            (cell.ch, cell.fg, cell.bg),
+            ('a', 0x01_FF_FF_FF, 0x01_00_00_00)
+        );
+        assert!(cell.attrs.contains(CellAttrs::UNDERLINE));
+        assert!(!ctx
+            .back_buffer
+            .get(1, 0)
+            .unwrap()
+            .attrs
+            .contains(CellAttrs::UNDERLINE));
+    }
+
+    #[test]
+    fn test_typewriter_reveal_draws_only_prefix() {
+        let mut ctx = integration_ctx(6, 1);
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(text);
+        crate::layout::set_dimension(&mut ctx, text, 0, 6.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&text).unwrap();
+            node.content = "hello".into();
+            node.typewriter_reveal = Some(2);
+        }
+        render(&mut ctx).unwrap();
+        let row: String = (0..5)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "he   ");
+    }
+
+    #[test]
+    fn test_text_marquee_scrolls_only_when_overflowing() {
+        let mut ctx = integration_ctx(5, 1);
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(text);
+        crate::layout::set_dimension(&mut ctx, text, 0, 5.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&text).unwrap();
+            node.content = "abcdef".into();
+            node.text_marquee = Some((4, 2));
+        }
+        let row = |ctx: &TuiContext| -> String {
+            (0..5)
+                .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+                .collect()
+        };
+        render(&mut ctx).unwrap();
+        assert_eq!(row(&ctx), "ef  a");
+
+        // Text that fits stays put.
+        ctx.nodes.get_mut(&text).unwrap().content = "abc".into();
+        ctx.nodes.get_mut(&text).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(row(&ctx), "abc  ");
+    }
+
+    #[test]
+    fn test_opacity_mode_dims_cells_that_cannot_blend() {
+        let mut ctx = integration_ctx(4, 1);
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(text);
+        crate::layout::set_dimension(&mut ctx, text, 0, 4.0, 1).unwrap();
+        ctx.nodes.get_mut(&text).unwrap().content = "ab".into();
+        crate::style::set_color(&mut ctx, text, 0, 0x02000003).unwrap();
+        crate::style::set_opacity(&mut ctx, text, 0.5).unwrap();
+        let dimmed = |ctx: &TuiContext| {
+            (0..4).all(|x| {
+                ctx.back_buffer
+                    .get(x, 0)
+                    .unwrap()
+                    .attrs
+                    .contains(CellAttrs::DIM)
+            })
+        };
+
+        // Blend leaves palette colors untouched and unfaded.
+        render(&mut ctx).unwrap();
+        assert!(!dimmed(&ctx));
+
+        // Both falls back to DIM for a palette fg.
+        ctx.nodes.get_mut(&text).unwrap().opacity_mode = OpacityMode::Both;
+        ctx.nodes.get_mut(&text).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert!(dimmed(&ctx));
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().fg, 0x02000003);
+
+        // An RGB fg over an RGB bg blends instead.
+        crate::style::set_color(&mut ctx, text, 0, 0x01FF0000).unwrap();
+        crate::style::set_color(&mut ctx, text, 1, 0x01000000).unwrap();
+        render(&mut ctx).unwrap();
+        assert!(!dimmed(&ctx));
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().fg, 0x01800000);
+
+        // Dim keeps the RGB fg and sets DIM.
+        ctx.nodes.get_mut(&text).unwrap().opacity_mode = OpacityMode::Dim;
+        ctx.nodes.get_mut(&text).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert!(dimmed(&ctx));
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().fg, 0x01FF0000);
+    }
+
+    #[test]
+    fn test_image_emits_payload_or_falls_back_to_placeholder() {
+        use crate::terminal_capabilities::terminal_capability;
+
+        let mut ctx = integration_ctx(8, 3);
+        let image = crate::tree::create_node(&mut ctx, NodeType::Image).unwrap();
+        ctx.root = Some(image);
+        crate::layout::set_dimension(&mut ctx, image, 0, 5.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, image, 1, 3.0, 1).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&image).unwrap();
+            node.content = "cat".into();
+            node.image_payload = b"\x1bPq#0~-\x1b\\".to_vec();
+        }
+        let take_output = |ctx: &mut TuiContext| {
+            let mock = ctx
+                .backend
+                .as_any_mut()
+                .downcast_mut::<crate::terminal::MockBackend>()
+                .unwrap();
+            String::from_utf8_lossy(&std::mem::take(&mut mock.output)).into_owned()
+        };
+
+        // No image support: bordered alt text, no payload.
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, '┌');
+        assert_eq!(ctx.back_buffer.get(1, 1).unwrap().ch, 'c');
+        assert!(!take_output(&mut ctx).contains("\x1bPq"));
+
+        // Supported: the rect is blanked and the payload lands at its origin.
+        ctx.terminal_capabilities.flags |= terminal_capability::INLINE_IMAGES;
+        ctx.nodes.get_mut(&image).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, ' ');
+        assert_eq!(ctx.back_buffer.get(1, 1).unwrap().ch, ' ');
+        assert!(take_output(&mut ctx).ends_with("\x1b[1;1H\x1bPq#0~-\x1b\\"));
+
+        // Nothing changed under it: the image is left on screen as is.
+        render(&mut ctx).unwrap();
+        assert!(!take_output(&mut ctx).contains("\x1bPq"));
+    }
+
+    #[test]
+    fn test_render_region_leaves_cells_outside_untouched() {
+        let mut ctx = integration_ctx(6, 2);
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(text);
+        crate::layout::set_dimension(&mut ctx, text, 0, 6.0, 1).unwrap();
+        ctx.nodes.get_mut(&text).unwrap().content = "abcdef".into();
+        render(&mut ctx).unwrap();
+
+        ctx.nodes.get_mut(&text).unwrap().content = "uvwxyz".into();
+        ctx.nodes.get_mut(&text).unwrap().dirty = true;
+        render_region(&mut ctx, 2, 0, 2, 1).unwrap();
+        let row: String = (0..6)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "abwxef");
+        assert_eq!(ctx.perf_diff_cells, 2);
+
+        // A full render catches up on the cells the region skipped.
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 4);
+    }
+
+    #[test]
+    fn test_double_width_line_folds_row_for_the_terminal() {
+        use crate::terminal_capabilities::terminal_capability;
+
+        let setup = |supported: bool| {
+            let mut ctx = integration_ctx(10, 2);
+            if supported {
+                ctx.terminal_capabilities.flags |= terminal_capability::DEC_LINE_ATTRIBUTES;
+            }
+            let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+            ctx.root = Some(text);
+            let node = ctx.nodes.get_mut(&text).unwrap();
+            node.content = "AB".into();
+            node.line_scale = LineScale::DoubleWidth;
+            render(&mut ctx).unwrap();
+            ctx
+        };
+
+        // Supported: glyphs on even columns, the row folded to half width.
+        let mut ctx = setup(true);
+        let row: String = (0..4)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "A B ");
+        let mock = ctx
+            .backend
+            .as_any_mut()
+            .downcast_mut::<crate::terminal::MockBackend>()
+            .unwrap();
+        let output = String::from_utf8_lossy(&mock.output);
+        assert!(output.contains("\x1b[1;1H\x1b#6"));
+        assert!(output.contains("\x1b[2;1H\x1b#5"));
+        assert!(output.contains("AB"));
+
+        // Unsupported: normal packed text.
+        let ctx = setup(false);
+        let row: String = (0..4)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "AB  ");
+    }
+
+    #[test]
+    fn test_ascii_fallback_substitutes_output_only() {
+        use crate::terminal_capabilities::terminal_capability;
+
+        let mut ctx = integration_ctx(4, 3);
+        ctx.terminal_capabilities.flags &= !terminal_capability::UTF8;
+        let boxed = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(boxed);
+        crate::layout::set_dimension(&mut ctx, boxed, 0, 4.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, boxed, 1, 3.0, 1).unwrap();
+        crate::style::set_border(&mut ctx, boxed, BorderStyle::Rounded as u8).unwrap();
+        let take_output = |ctx: &mut TuiContext| {
+            let mock = ctx
+                .backend
+                .as_any_mut()
+                .downcast_mut::<crate::terminal::MockBackend>()
+                .unwrap();
+            String::from_utf8_lossy(&std::mem::take(&mut mock.output)).into_owned()
+        };
 
-        // Indexed color — not blended
-        let indexed = 0x02000001;
-        assert_eq!(blend_opacity(indexed, 0x01000000, 0.5), indexed);
+        // Defaults on without UTF-8; the buffer keeps the real glyph.
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, '╭');
+        let output = take_output(&mut ctx);
+        assert!(output.contains("+--+"), "{output:?}");
+        assert!(!output.contains('╭'));
+
+        // Switching it off repaints with the Unicode glyphs.
+        set_ascii_fallback(&mut ctx, false);
+        render(&mut ctx).unwrap();
+        assert!(take_output(&mut ctx).contains("╭──╮"));
     }
 
     #[test]
-    fn test_blend_opacity_with_non_rgb_bg() {
-        let fg = 0x01FF0000; // red RGB
-        let bg = 0x00000000; // default (non-RGB)
-        let result = blend_opacity(fg, bg, 0.5);
-        // Blends toward black (0,0,0) when bg is not RGB
-        let r = (result >> 16) & 0xFF;
-        assert!(r == 127 || r == 128);
+    fn test_render_skips_frame_when_nothing_changed() {
+        let mut ctx = integration_ctx(10, 2);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        crate::layout::set_dimension(&mut ctx, root, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, root, 1, 2.0, 1).unwrap();
+        ctx.nodes
+            .get_mut(&root)
+            .unwrap()
+            .set_content("hi".to_string());
+        ctx.root = Some(root);
+        assert!(render(&mut ctx).unwrap());
+
+        let mock = ctx
+            .backend
+            .as_any_mut()
+            .downcast_mut::<crate::terminal::MockBackend>()
+            .unwrap();
+        let written = mock.output.len();
+        assert!(!render(&mut ctx).unwrap());
+        assert_eq!(ctx.perf_diff_cells, 0);
+        let mock = ctx
+            .backend
+            .as_any_mut()
+            .downcast_mut::<crate::terminal::MockBackend>()
+            .unwrap();
+        assert_eq!(mock.output.len(), written);
+
+        // Global paint state without a dirty node still renders.
+        ctx.focused = Some(root);
+        assert!(render(&mut ctx).unwrap());
+        assert!(!render(&mut ctx).unwrap());
+        ctx.nodes.get_mut(&root).unwrap().dirty = true;
+        assert!(render(&mut ctx).unwrap());
+        invalidate(&mut ctx);
+        assert!(render(&mut ctx).unwrap());
+        assert!(ctx.perf_diff_cells > 0);
     }
 
     #[test]
-    fn test_styled_spans_default_fg_opacity() {
-        use crate::terminal::MockBackend;
-        use crate::types::StyledSpan;
+    fn test_render_repaints_menu_highlight_moves() {
+        let mut ctx = integration_ctx(10, 5);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        let menu = crate::menu::create(&mut ctx).unwrap();
+        for label in ["Copy", "Paste"] {
+            crate::menu::add_item(&mut ctx, menu, label.to_string()).unwrap();
+        }
+        crate::menu::show_at(&mut ctx, menu, 0, 0).unwrap();
+        assert!(render(&mut ctx).unwrap());
+        let highlight = ctx.back_buffer.get(1, 1).unwrap().bg;
+        assert_ne!(ctx.back_buffer.get(1, 2).unwrap().bg, highlight);
+
+        assert!(crate::menu::handle_key(&mut ctx, crate::types::key::DOWN));
+        assert!(render(&mut ctx).unwrap());
+        assert_eq!(ctx.back_buffer.get(1, 2).unwrap().bg, highlight);
+        assert_ne!(ctx.back_buffer.get(1, 1).unwrap().bg, highlight);
+    }
 
-        let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
-        let default_fg = 0x01FF0000; // red RGB
-        let default_bg = 0x01000000; // black RGB
-        let opacity = 0.5;
-        let blended_fg = blend_opacity(default_fg, default_bg, opacity);
+    #[test]
+    fn test_render_keeps_scrolling_select_marquee() {
+        let mut ctx = integration_ctx(5, 1);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Select).unwrap();
+        crate::layout::set_dimension(&mut ctx, root, 0, 5.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, root, 1, 1.0, 1).unwrap();
+        let node = ctx.nodes.get_mut(&root).unwrap();
+        node.options = vec!["abcdefgh".into()];
+        node.selected_index = Some(0);
+        node.option_overflow = OptionOverflow::Marquee;
+        ctx.root = Some(root);
+        let row = |ctx: &TuiContext| -> String {
+            (0..5)
+                .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+                .collect()
+        };
 
-        // Span with no explicit fg (fg=0) should inherit the node's blended fg
-        let spans = vec![StyledSpan {
-            text: "A".to_string(),
-            attrs: CellAttrs::empty(),
-            fg: 0, // default — should inherit node's blended fg
-            bg: 0,
-            link: None,
-        }];
+        let epoch = std::time::Instant::now();
+        let clock = |ms| TestClock {
+            anchor: epoch,
+            anchor_ms: 0,
+            ms,
+        };
+        ctx.test_clock = Some(clock(0));
+        assert!(render(&mut ctx).unwrap());
+        assert_eq!(row(&ctx), "abcde");
+        // Nothing is dirty, but the scrolling option still needs frames.
+        ctx.test_clock = Some(clock(OPTION_MARQUEE_STEP_MS));
+        assert!(render(&mut ctx).unwrap());
+        assert_eq!(row(&ctx), "bcdef");
+        ctx.test_clock = Some(clock(2 * OPTION_MARQUEE_STEP_MS));
+        assert!(render(&mut ctx).unwrap());
+        assert_eq!(row(&ctx), "cdefg");
+
+        // Once the option fits, frames are skipped again.
+        ctx.nodes.get_mut(&root).unwrap().options = vec!["abc".into()];
+        ctx.nodes.get_mut(&root).unwrap().dirty = true;
+        assert!(render(&mut ctx).unwrap());
+        assert!(!render(&mut ctx).unwrap());
+    }
 
-        let clip = ClipRect::full(80, 24);
-        render_styled_spans(
-            &mut ctx, &spans, 0, 0, 80, 24, blended_fg, default_bg, clip, opacity,
-        );
+    #[test]
+    fn test_diff_vector_is_reused_across_frames() {
+        let mut ctx = integration_ctx(20, 5);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+        invalidate(&mut ctx);
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 20 * 5);
+        let ptr = ctx.diff_updates.as_ptr();
 
-        let cell = ctx.front_buffer.get(0, 0).unwrap();
-        assert_eq!(cell.ch, 'A');
-        // The default-colored span should use the blended fg, not 0 (default)
-        assert_eq!(cell.fg, blended_fg);
+        invalidate(&mut ctx);
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.perf_diff_cells, 20 * 5);
+        assert_eq!(ctx.diff_updates.as_ptr(), ptr, "no reallocation");
     }
 
     #[test]
-    fn test_blend_opacity_zero_non_rgb() {
-        let bg = 0x01000000; // black RGB
+    fn test_row_skipping_diff_matches_per_cell_diff() {
+        let mut ctx = integration_ctx(16, 6);
+        ctx.back_buffer = Buffer::new(16, 6);
+        ctx.front_buffer = Buffer::new(16, 6);
+        let mark = |ch| Cell {
+            ch,
+            ..Cell::default()
+        };
+        // Row 0 unchanged; row 2 has scattered changes; row 4 is all new;
+        // row 5 only differs in a link.
+        ctx.front_buffer.set(3, 2, mark('a'));
+        ctx.front_buffer.set(15, 2, mark('b'));
+        ctx.back_buffer.set(7, 2, mark('c'));
+        for x in 0..16 {
+            ctx.front_buffer.set(x, 4, mark('d'));
+        }
+        ctx.front_buffer.set(
+            0,
+            5,
+            Cell {
+                link: Some(crate::types::TerminalLink {
+                    uri: "https://example.com".into(),
+                    id: None,
+                }),
+                ..Cell::default()
+            },
+        );
 
-        // Default fg at zero opacity should return bg
-        let default_fg = 0x00000000;
-        assert_eq!(blend_opacity(default_fg, bg, 0.0), bg);
+        let mut diff = Vec::new();
+        diff_buffers(&ctx, &mut diff);
 
-        // Indexed fg at zero opacity should return bg
-        let indexed_fg = 0x02000001;
-        assert_eq!(blend_opacity(indexed_fg, bg, 0.0), bg);
+        let mut expected = Vec::new();
+        for y in 0..6 {
+            for x in 0..16 {
+                let cell = ctx.front_buffer.get(x, y).unwrap();
+                if ctx.back_buffer.get(x, y) != Some(cell) {
+                    expected.push((x, y, cell.clone()));
+                }
+            }
+        }
+        let actual: Vec<_> = diff.into_iter().map(|u| (u.x, u.y, u.cell)).collect();
+        assert_eq!(actual, expected);
+        assert_eq!(actual.len(), 3 + 16 + 1);
     }
 
-    // --- Input cursor tests (B2) ---
+    #[test]
+    fn test_children_paint_in_pre_order() {
+        use crate::{layout, tree};
+
+        let mut ctx = integration_ctx(10, 1);
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let first = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let nested = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let second = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        for handle in [first, nested, second] {
+            layout::set_dimension(&mut ctx, handle, 0, 2.0, 1).unwrap();
+            layout::set_dimension(&mut ctx, handle, 1, 1.0, 1).unwrap();
+        }
+        tree::append_child(&mut ctx, root, first).unwrap();
+        tree::append_child(&mut ctx, first, nested).unwrap();
+        tree::append_child(&mut ctx, root, second).unwrap();
+        ctx.nodes.get_mut(&nested).unwrap().set_content("aa");
+        ctx.nodes.get_mut(&second).unwrap().set_content("bb");
+        // Shift the nested child under `second`: the whole subtree of the
+        // earlier sibling paints first, so `second` ends up on top.
+        ctx.nodes.get_mut(&nested).unwrap().render_offset = (2.0, 0.0);
+        ctx.root = Some(root);
+
+        render(&mut ctx).unwrap();
+
+        let row: String = (0..4)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "  bb");
+    }
 
     #[test]
-    fn test_input_cursor_focused() {
+    fn test_text_highlights_paint_search_background() {
         use crate::terminal::MockBackend;
         use crate::tree;
 
-        let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
-        let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
-        ctx.nodes.get_mut(&h).unwrap().content = "hello".to_string();
-        ctx.nodes.get_mut(&h).unwrap().cursor_position = 2;
-        ctx.nodes.get_mut(&h).unwrap().visual_style.fg_color = 0x01FFFFFF; // white
-        ctx.nodes.get_mut(&h).unwrap().visual_style.bg_color = 0x01000000; // black
-        ctx.focused = Some(h);
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(12, 2)));
+        let h = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(h);
+        crate::layout::set_dimension(&mut ctx, h, 0, 12.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, h, 1, 2.0, 1).unwrap();
+        let search_bg = text_renderer::HighlightPalette::theme_tinted(0).search_bg;
 
-        let clip = ClipRect::full(80, 24);
-        render_input_cursor(&mut ctx, h, "hello", 0, 0, 80, 0x01FFFFFF, 0x01000000, clip);
+        ctx.nodes
+            .get_mut(&h)
+            .unwrap()
+            .set_content("ab\ncd ab".to_string());
+        ctx.nodes.get_mut(&h).unwrap().text_highlights = vec![(6, 8)];
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(3, 1).unwrap().bg, search_bg);
+        assert_eq!(ctx.back_buffer.get(4, 1).unwrap().bg, search_bg);
+        assert_ne!(ctx.back_buffer.get(0, 0).unwrap().bg, search_bg);
 
-        // Cursor at position 2 means column 2 (ASCII chars are 1-wide)
-        let cell = ctx.front_buffer.get(2, 0).unwrap();
-        assert_eq!(cell.ch, 'l'); // 3rd char of "hello"
-                                  // Colors should be inverted
-        assert_eq!(cell.fg, 0x01000000); // was bg -> now fg
-        assert_eq!(cell.bg, 0x01FFFFFF); // was fg -> now bg
+        // The direct right-to-left path honors the same ranges.
+        ctx.nodes
+            .get_mut(&h)
+            .unwrap()
+            .set_content("\u{05D0}\u{05D1}\u{05D2}".to_string());
+        ctx.nodes.get_mut(&h).unwrap().text_highlights = vec![(1, 2)];
+        ctx.nodes.get_mut(&h).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(10, 0).unwrap().ch, '\u{05D1}');
+        assert_eq!(ctx.back_buffer.get(10, 0).unwrap().bg, search_bg);
+        assert_ne!(ctx.back_buffer.get(11, 0).unwrap().bg, search_bg);
     }
 
     #[test]
@@ -2438,14 +4237,25 @@ mod tests {
 
         let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
         let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
-        ctx.nodes.get_mut(&h).unwrap().content = "hi".to_string();
+        ctx.nodes.get_mut(&h).unwrap().content = "hi".into();
         ctx.nodes.get_mut(&h).unwrap().cursor_position = 2; // at end
         ctx.nodes.get_mut(&h).unwrap().visual_style.fg_color = 0x01FFFFFF;
         ctx.nodes.get_mut(&h).unwrap().visual_style.bg_color = 0x01000000;
         ctx.focused = Some(h);
 
         let clip = ClipRect::full(80, 24);
-        render_input_cursor(&mut ctx, h, "hi", 0, 0, 80, 0x01FFFFFF, 0x01000000, clip);
+        render_input_cursor(
+            &mut ctx,
+            h,
+            "hi",
+            0,
+            0,
+            80,
+            0x01FFFFFF,
+            0x01000000,
+            TextDirection::Auto,
+            clip,
+        );
 
         // Cursor at end renders a space
         let cell = ctx.front_buffer.get(2, 0).unwrap();
@@ -2460,12 +4270,23 @@ mod tests {
 
         let mut ctx = TuiContext::new(Box::new(MockBackend::new(10, 1)));
         let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
-        ctx.nodes.get_mut(&h).unwrap().content = "hi".to_string();
+        ctx.nodes.get_mut(&h).unwrap().content = "hi".into();
         ctx.nodes.get_mut(&h).unwrap().cursor_position = 2; // at end
         ctx.focused = Some(h);
 
         let clip = ClipRect::full(10, 1);
-        render_input_cursor(&mut ctx, h, "hi", 0, 0, 2, 0x01FFFFFF, 0x01000000, clip);
+        render_input_cursor(
+            &mut ctx,
+            h,
+            "hi",
+            0,
+            0,
+            2,
+            0x01FFFFFF,
+            0x01000000,
+            TextDirection::Auto,
+            clip,
+        );
 
         // Cursor at x=2 is outside the 2-cell content area [0,1].
         assert_eq!(ctx.front_buffer.get(2, 0).unwrap().ch, ' ');
@@ -2478,7 +4299,7 @@ mod tests {
 
         let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
         let h = tree::create_node(&mut ctx, NodeType::Input).unwrap();
-        ctx.nodes.get_mut(&h).unwrap().content = "hello".to_string();
+        ctx.nodes.get_mut(&h).unwrap().content = "hello".into();
         ctx.nodes.get_mut(&h).unwrap().cursor_position = 0;
         // Not focused — cursor should not be rendered by render_node
 
@@ -2497,7 +4318,7 @@ mod tests {
         let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
         let h = tree::create_node(&mut ctx, NodeType::Select).unwrap();
         let node = ctx.nodes.get_mut(&h).unwrap();
-        node.options = vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()];
+        node.options = vec!["Red".into(), "Green".into(), "Blue".into()];
         node.selected_index = None;
 
         let clip = ClipRect::full(80, 24);
@@ -2534,7 +4355,7 @@ mod tests {
         let fg = 0x01FFFFFF; // white
         let bg = 0x01000000; // black
         let node = ctx.nodes.get_mut(&h).unwrap();
-        node.options = vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()];
+        node.options = vec!["Red".into(), "Green".into(), "Blue".into()];
         node.selected_index = Some(1); // Green
 
         let clip = ClipRect::full(80, 24);
@@ -2551,6 +4372,337 @@ mod tests {
         assert_eq!(green_cell.bg, fg); // inverted: fg -> bg
     }
 
+    #[test]
+    fn test_select_filter_renders_matching_options_only() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(20, 5)));
+        let h = tree::create_node(&mut ctx, NodeType::Select).unwrap();
+        let node = ctx.nodes.get_mut(&h).unwrap();
+        node.options = ["Apple", "Banana", "Pineapple"]
+            .iter()
+            .map(|&s| s.into())
+            .collect();
+        node.selected_index = Some(2);
+        node.select_filter = "apple".to_string();
+
+        render_select_options(
+            &mut ctx,
+            h,
+            0,
+            0,
+            20,
+            5,
+            0x01FFFFFF,
+            0,
+            CellAttrs::empty(),
+            ClipRect::full(20, 5),
+        );
+
+        assert_eq!(ctx.front_buffer.get(0, 0).unwrap().ch, 'A');
+        assert_eq!(ctx.front_buffer.get(0, 1).unwrap().ch, 'P');
+        assert_eq!(ctx.front_buffer.get(0, 1).unwrap().bg, 0x01FFFFFF); // selected
+        assert_eq!(ctx.front_buffer.get(0, 2).unwrap().ch, ' ');
+    }
+
+    #[test]
+    fn test_overflow_hidden_box_truncates_children_at_its_edge() {
+        let mut ctx = integration_ctx(20, 5);
+        let card = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        crate::tree::append_child(&mut ctx, card, text).unwrap();
+        ctx.root = Some(card);
+        crate::layout::set_dimension(&mut ctx, card, 0, 5.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, card, 1, 2.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, text, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, text, 1, 1.0, 1).unwrap();
+        crate::layout::set_flex_factor(&mut ctx, text, 1, 0.0).unwrap();
+        ctx.nodes.get_mut(&text).unwrap().content = "ABCDEFGHIJ".into();
+
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(7, 0).unwrap().ch, 'H');
+
+        ctx.nodes.get_mut(&card).unwrap().overflow = Overflow::Hidden;
+        ctx.nodes.get_mut(&card).unwrap().dirty = true;
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.back_buffer.get(4, 0).unwrap().ch, 'E');
+        assert_eq!(ctx.back_buffer.get(5, 0).unwrap().ch, ' ');
+        assert_eq!(ctx.back_buffer.get(7, 0).unwrap().ch, ' ');
+    }
+
+    #[test]
+    fn test_absolute_badge_pins_to_parent_top_right() {
+        use crate::layout::{set_dimension, set_edges, set_flex};
+        let mut ctx = integration_ctx(30, 8);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let card = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let filler = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let badge = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        crate::tree::append_child(&mut ctx, root, card).unwrap();
+        crate::tree::append_child(&mut ctx, card, filler).unwrap();
+        crate::tree::append_child(&mut ctx, card, badge).unwrap();
+        ctx.root = Some(root);
+        set_edges(&mut ctx, card, 1, 2.0, 0.0, 0.0, 5.0, 1).unwrap();
+        set_dimension(&mut ctx, card, 0, 20.0, 1).unwrap();
+        set_dimension(&mut ctx, card, 1, 4.0, 1).unwrap();
+        set_dimension(&mut ctx, filler, 0, 20.0, 1).unwrap();
+        set_dimension(&mut ctx, filler, 1, 1.0, 1).unwrap();
+        ctx.nodes.get_mut(&filler).unwrap().content = "x".repeat(20).into();
+        set_dimension(&mut ctx, badge, 0, 2.0, 1).unwrap();
+        set_dimension(&mut ctx, badge, 1, 1.0, 1).unwrap();
+        set_flex(&mut ctx, badge, 6, 1).unwrap();
+        set_edges(&mut ctx, badge, 2, 0.0, 0.0, f32::NAN, f32::NAN, 1).unwrap();
+        ctx.nodes.get_mut(&badge).unwrap().content = "99".into();
+
+        render(&mut ctx).unwrap();
+        // Out of flow: the filler keeps the full row and the badge overlays it.
+        assert_eq!(ctx.back_buffer.get(5, 2).unwrap().ch, 'x');
+        assert_eq!(ctx.back_buffer.get(22, 2).unwrap().ch, 'x');
+        assert_eq!(ctx.back_buffer.get(23, 2).unwrap().ch, '9');
+        assert_eq!(ctx.back_buffer.get(24, 2).unwrap().ch, '9');
+        assert_eq!(ctx.back_buffer.get(25, 2).unwrap().ch, ' ');
+    }
+
+    #[test]
+    fn test_line_spacing_text_and_textarea_cursor() {
+        let mut ctx = integration_ctx(10, 8);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let area = crate::tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        crate::tree::append_child(&mut ctx, root, text).unwrap();
+        crate::tree::append_child(&mut ctx, root, area).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_flex(&mut ctx, root, 0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, area, 1, 3.0, 1).unwrap();
+        crate::layout::set_flex_factor(&mut ctx, area, 1, 0.0).unwrap();
+        {
+            let node = ctx.nodes.get_mut(&text).unwrap();
+            node.content = "ab\ncd".into();
+            node.line_spacing = 1;
+        }
+        {
+            let node = ctx.nodes.get_mut(&area).unwrap();
+            node.content = "one\ntwo\nthree".into();
+            node.line_spacing = 1;
+            node.cursor_row = 2;
+            node.cursor_col = 0;
+        }
+        ctx.focused = Some(area);
+
+        render(&mut ctx).unwrap();
+        // Text measures 3 rows: "ab", a blank spacer, "cd".
+        assert_eq!(ctx.back_buffer.get(0, 0).unwrap().ch, 'a');
+        assert_eq!(ctx.back_buffer.get(0, 1).unwrap().ch, ' ');
+        assert_eq!(ctx.back_buffer.get(0, 2).unwrap().ch, 'c');
+        // Three rows fit two spaced lines; following the cursor on the last
+        // line scrolls so "two" is on top and "three" two rows below.
+        assert_eq!(ctx.nodes[&area].textarea_view_row, 1);
+        assert_eq!(ctx.back_buffer.get(0, 3).unwrap().ch, 't');
+        assert_eq!(ctx.back_buffer.get(1, 3).unwrap().ch, 'w');
+        assert_eq!(ctx.back_buffer.get(1, 4).unwrap().ch, ' ');
+        let cursor = ctx.back_buffer.get(0, 5).unwrap();
+        assert_eq!(cursor.ch, 't');
+        assert_eq!(ctx.back_buffer.get(1, 5).unwrap().ch, 'h');
+        assert_ne!(cursor.bg, ctx.back_buffer.get(1, 5).unwrap().bg);
+    }
+
+    #[test]
+    fn test_letter_spacing_text_and_input_cursor() {
+        let mut ctx = integration_ctx(12, 3);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let text = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let input = crate::tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        crate::tree::append_child(&mut ctx, root, text).unwrap();
+        crate::tree::append_child(&mut ctx, root, input).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_flex(&mut ctx, root, 0, 1).unwrap();
+        crate::layout::set_flex(&mut ctx, root, 3, 1).unwrap(); // align_items: start
+        for (handle, content) in [(text, "HEY"), (input, "ab")] {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            node.content = content.into();
+            node.letter_spacing = 1;
+        }
+        ctx.nodes.get_mut(&input).unwrap().cursor_position = 1;
+        ctx.focused = Some(input);
+
+        render(&mut ctx).unwrap();
+        assert_eq!(crate::layout::get_layout(&ctx, text).unwrap().2, 5);
+        let row: String = (0..5)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "H E Y");
+        // Cursor sits on "b", two columns in.
+        assert_eq!(ctx.back_buffer.get(0, 1).unwrap().ch, 'a');
+        let cursor = ctx.back_buffer.get(2, 1).unwrap();
+        assert_eq!(cursor.ch, 'b');
+        assert_ne!(cursor.bg, ctx.back_buffer.get(0, 1).unwrap().bg);
+    }
+
+    #[test]
+    fn test_monospace_grid_fixes_grapheme_advance() {
+        let mut ctx = integration_ctx(12, 2);
+        let root = crate::tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let cell = crate::tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        let input = crate::tree::create_node(&mut ctx, NodeType::Input).unwrap();
+        crate::tree::append_child(&mut ctx, root, cell).unwrap();
+        crate::tree::append_child(&mut ctx, root, input).unwrap();
+        ctx.root = Some(root);
+        crate::layout::set_flex(&mut ctx, root, 0, 1).unwrap();
+        crate::layout::set_flex(&mut ctx, root, 3, 1).unwrap(); // align_items: start
+        for handle in [cell, input] {
+            let node = ctx.nodes.get_mut(&handle).unwrap();
+            node.content = "a\u{4E2D}b".into();
+            node.fixed_advance = 1;
+        }
+        ctx.nodes.get_mut(&input).unwrap().cursor_position = 2;
+        ctx.focused = Some(input);
+
+        render(&mut ctx).unwrap();
+        assert_eq!(crate::layout::get_layout(&ctx, cell).unwrap().2, 3);
+        let row: String = (0..3)
+            .map(|x| ctx.back_buffer.get(x, 0).unwrap().ch)
+            .collect();
+        assert_eq!(row, "a\u{2026}b");
+        // The cursor on "b" lands in the third column, not the fourth.
+        let cursor = ctx.back_buffer.get(2, 1).unwrap();
+        assert_eq!(cursor.ch, 'b');
+        assert_ne!(cursor.bg, ctx.back_buffer.get(0, 1).unwrap().bg);
+    }
+
+    #[test]
+    fn test_select_option_overflow_ellipsis_and_marquee() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(10, 2)));
+        let h = tree::create_node(&mut ctx, NodeType::Select).unwrap();
+        let node = ctx.nodes.get_mut(&h).unwrap();
+        // "日本" is two wide graphemes; the label is 9 columns.
+        node.options = vec!["Long one".into(), "ab\u{65E5}\u{672C}xyz".into()];
+        node.selected_index = Some(1);
+        node.option_overflow = OptionOverflow::Ellipsis;
+
+        let draw = |ctx: &mut TuiContext| {
+            ctx.front_buffer.clear();
+            render_select_options(
+                ctx,
+                h,
+                0,
+                0,
+                5,
+                2,
+                0x01FFFFFF,
+                0,
+                CellAttrs::empty(),
+                ClipRect::full(10, 2),
+            );
+        };
+        let row = |ctx: &TuiContext, y: u16| -> String {
+            (0..5)
+                .map(|x| ctx.front_buffer.get(x, y).unwrap().ch)
+                .collect()
+        };
+
+        draw(&mut ctx);
+        assert_eq!(row(&ctx, 0), "Long\u{2026}");
+        // Wide glyphs count two columns toward the fit.
+        assert_eq!(row(&ctx, 1), "ab\u{65E5} \u{2026}");
+
+        ctx.nodes.get_mut(&h).unwrap().option_overflow = OptionOverflow::Marquee;
+        let epoch = std::time::Instant::now();
+        let at = |ctx: &mut TuiContext, ms: u64| {
+            ctx.test_clock = Some(TestClock {
+                anchor: epoch,
+                anchor_ms: 0,
+                ms,
+            });
+            draw(ctx);
+        };
+        at(&mut ctx, 0);
+        assert_eq!(row(&ctx, 0), "Long "); // unselected: clipped
+        assert_eq!(row(&ctx, 1), "ab\u{65E5}  ");
+        // Frames inside one step leave the offset alone.
+        at(&mut ctx, OPTION_MARQUEE_STEP_MS - 1);
+        assert_eq!(row(&ctx, 1), "ab\u{65E5}  ");
+        at(&mut ctx, OPTION_MARQUEE_STEP_MS);
+        assert_eq!(row(&ctx, 1), "b\u{65E5} \u{672C} ");
+        // A late frame catches up on every step it missed.
+        at(&mut ctx, 3 * OPTION_MARQUEE_STEP_MS);
+        assert_eq!(row(&ctx, 1), "\u{672C} xyz");
+        // Tail reached: the next step wraps back to the start.
+        at(&mut ctx, 4 * OPTION_MARQUEE_STEP_MS);
+        assert_eq!(row(&ctx, 1), "ab\u{65E5}  ");
+    }
+
+    #[test]
+    fn test_combobox_renders_text_line_over_filtered_options() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(10, 4)));
+        let h = tree::create_node(&mut ctx, NodeType::ComboBox).unwrap();
+        ctx.root = Some(h);
+        crate::layout::set_dimension(&mut ctx, h, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, h, 1, 4.0, 1).unwrap();
+        let node = ctx.nodes.get_mut(&h).unwrap();
+        node.options = ["Apple", "Banana", "Grape"]
+            .iter()
+            .map(|&s| s.into())
+            .collect();
+        node.set_content("ap".to_string());
+        render(&mut ctx).unwrap();
+
+        let row = |ctx: &TuiContext, y: u16| -> String {
+            (0..5)
+                .map(|x| ctx.back_buffer.get(x, y).unwrap().ch)
+                .collect()
+        };
+        assert_eq!(row(&ctx, 0), "ap   ");
+        assert_eq!(row(&ctx, 1), "Apple");
+        assert_eq!(row(&ctx, 2), "Grape");
+        assert_eq!(row(&ctx, 3), "     ");
+    }
+
+    #[test]
+    fn test_open_menu_draws_above_tree() {
+        use crate::terminal::MockBackend;
+        use crate::tree;
+
+        let mut ctx = TuiContext::new(Box::new(MockBackend::new(12, 5)));
+        let text = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        ctx.root = Some(text);
+        crate::layout::set_dimension(&mut ctx, text, 0, 12.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, text, 1, 5.0, 1).unwrap();
+        ctx.nodes
+            .get_mut(&text)
+            .unwrap()
+            .set_content(vec!["x".repeat(12); 5].join("\n"));
+        let menu = crate::menu::create(&mut ctx).unwrap();
+        crate::menu::add_item(&mut ctx, menu, "Open".to_string()).unwrap();
+        crate::menu::add_item(&mut ctx, menu, "Quit".to_string()).unwrap();
+        crate::menu::show_at(&mut ctx, menu, 1, 0).unwrap();
+        render(&mut ctx).unwrap();
+
+        let row = |ctx: &TuiContext, y: u16| -> String {
+            (0..8)
+                .map(|x| ctx.back_buffer.get(x, y).unwrap().ch)
+                .collect()
+        };
+        assert_eq!(
+            row(&ctx, 0),
+            "x\u{250C}\u{2500}\u{2500}\u{2500}\u{2500}\u{2510}x"
+        );
+        assert_eq!(row(&ctx, 1), "x\u{2502}Open\u{2502}x");
+        assert_eq!(row(&ctx, 2), "x\u{2502}Quit\u{2502}x");
+        // The highlighted first item is drawn inverted.
+        assert_eq!(ctx.back_buffer.get(2, 1).unwrap().bg, 0x01FFFFFF);
+
+        crate::menu::close(&mut ctx);
+        render(&mut ctx).unwrap();
+        assert_eq!(row(&ctx, 1), "xxxxxxxx");
+    }
+
     #[test]
     fn test_select_viewport_scrolls() {
         use crate::terminal::MockBackend;
@@ -2559,7 +4711,7 @@ mod tests {
         let mut ctx = TuiContext::new(Box::new(MockBackend::new(80, 24)));
         let h = tree::create_node(&mut ctx, NodeType::Select).unwrap();
         let node = ctx.nodes.get_mut(&h).unwrap();
-        node.options = (0..20).map(|i| format!("Option {i}")).collect();
+        node.options = (0..20).map(|i| format!("Option {i}").into()).collect();
         node.selected_index = Some(10);
 
         let clip = ClipRect::full(80, 24);
@@ -2621,7 +4773,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 20.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 1.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "Hello".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "Hello".into();
 
         render(&mut ctx).unwrap();
 
@@ -2667,8 +4819,8 @@ mod tests {
         layout::set_dimension(&mut ctx, text2, 0, 10.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text2, 1, 1.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text1).unwrap().content = "TOP".to_string();
-        ctx.nodes.get_mut(&text2).unwrap().content = "BOT".to_string();
+        ctx.nodes.get_mut(&text1).unwrap().content = "TOP".into();
+        ctx.nodes.get_mut(&text2).unwrap().content = "BOT".into();
 
         render(&mut ctx).unwrap();
 
@@ -2728,7 +4880,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 40.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 3.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "**bold** plain".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "**bold** plain".into();
         ctx.nodes.get_mut(&text).unwrap().content_format = ContentFormat::Markdown;
 
         render(&mut ctx).unwrap();
@@ -2777,7 +4929,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 40.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 3.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "[guide](./guide.md)".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "[guide](./guide.md)".into();
         ctx.nodes.get_mut(&text).unwrap().content_format = ContentFormat::Markdown;
 
         render(&mut ctx).unwrap();
@@ -2810,7 +4962,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 40.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 3.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "[Open](https://example.com)".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "[Open](https://example.com)".into();
         ctx.nodes.get_mut(&text).unwrap().content_format = ContentFormat::Markdown;
 
         render(&mut ctx).unwrap();
@@ -2850,7 +5002,7 @@ mod tests {
         style::set_opacity(&mut ctx, text, 0.5).unwrap();
 
         let node = ctx.nodes.get_mut(&text).unwrap();
-        node.content = "`A`".to_string();
+        node.content = "`A`".into();
         node.content_format = ContentFormat::Markdown;
 
         render(&mut ctx).unwrap();
@@ -2877,7 +5029,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 1, 2.0, 1).unwrap();
 
         let node = ctx.nodes.get_mut(&text).unwrap();
-        node.content = "**bold** `code`".to_string();
+        node.content = "**bold** `code`".into();
         node.content_format = ContentFormat::Markdown;
 
         render(&mut ctx).unwrap();
@@ -2905,13 +5057,13 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 20.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 1.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "hello".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "hello".into();
         render(&mut ctx).unwrap();
 
         let buffer_handle = ctx.nodes[&text].text_buffer_handle.unwrap();
         assert!(ctx.text_buffers[&buffer_handle].dirty_ranges().is_empty());
 
-        ctx.nodes.get_mut(&text).unwrap().content = "hello world".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "hello world".into();
         render(&mut ctx).unwrap();
         assert!(ctx.text_buffers[&buffer_handle].dirty_ranges().is_empty());
     }
@@ -2957,7 +5109,7 @@ mod tests {
         layout::set_dimension(&mut ctx, text, 0, 20.0, 1).unwrap();
         layout::set_dimension(&mut ctx, text, 1, 1.0, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "Hidden".to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = "Hidden".into();
         ctx.nodes.get_mut(&text).unwrap().visible = false;
 
         render(&mut ctx).unwrap();
@@ -3021,7 +5173,7 @@ mod tests {
 
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abcdefghij".to_string();
+            node.content = "abcdefghij".into();
             node.cursor_row = 0;
             node.cursor_col = 8;
             node.wrap_mode = 1;
@@ -3084,7 +5236,7 @@ mod tests {
 
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abcdefghij".to_string();
+            node.content = "abcdefghij".into();
             node.cursor_row = 0;
             node.cursor_col = 8;
             node.wrap_mode = 0;
@@ -3112,7 +5264,7 @@ mod tests {
 
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "abcdef".to_string();
+            node.content = "abcdef".into();
             node.wrap_mode = 1;
             node.textarea_state.as_mut().unwrap().selection_anchor = Some((0, 1));
             node.textarea_state.as_mut().unwrap().selection_focus = Some((0, 4));
@@ -3185,7 +5337,7 @@ mod tests {
 
     #[test]
     fn test_render_text_highlight_uses_theme_tinted_palette() {
-        use crate::{layout, text_buffer, theme, tree};
+        use crate::{layout, theme, tree};
 
         let mut ctx = integration_ctx(20, 3);
         let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
@@ -3205,9 +5357,8 @@ mod tests {
         theme::set_theme_color(&mut ctx, theme_handle, 0, 0x01_FF_FF_FF).unwrap();
         theme::apply_theme(&mut ctx, theme_handle, root).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = "A".to_string();
-        let (buffer_handle, _) = ensure_node_text_handles(&mut ctx, text).unwrap();
-        text_buffer::set_highlight(&mut ctx, buffer_handle, 0, 1, 0).unwrap();
+        ctx.nodes.get_mut(&text).unwrap().content = "A".into();
+        ctx.nodes.get_mut(&text).unwrap().text_highlights = vec![(0, 1)];
 
         render(&mut ctx).unwrap();
 
@@ -3285,7 +5436,7 @@ mod tests {
 
         {
             let node = ctx.nodes.get_mut(&textarea).unwrap();
-            node.content = "中".to_string();
+            node.content = "中".into();
             node.cursor_row = 0;
             node.cursor_col = 1;
             node.wrap_mode = 1;
@@ -3333,7 +5484,7 @@ mod tests {
         layout::set_dimension(ctx, text, 0, child_w, 1).unwrap();
         layout::set_dimension(ctx, text, 1, child_h, 1).unwrap();
 
-        ctx.nodes.get_mut(&text).unwrap().content = content.to_string();
+        ctx.nodes.get_mut(&text).unwrap().content = content.into();
 
         (sb, child, text)
     }
@@ -3432,4 +5583,62 @@ mod tests {
         // After swap, the node's scroll_y should be clamped to max (2)
         assert_eq!(ctx.nodes[&sb].scroll_y, 2);
     }
+
+    #[test]
+    fn test_render_scrollbox_autoscroll_follows_until_user_scrolls_up() {
+        use crate::{layout, scroll};
+
+        let mut ctx = integration_ctx(80, 24);
+        let (sb, child, _) =
+            setup_scrollbox_render(&mut ctx, 10.0, 5.0, 10.0, 7.0, "AAA\nBBB\nCCC");
+        ctx.nodes.get_mut(&sb).unwrap().autoscroll = true;
+
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&sb].scroll_y, 2);
+
+        layout::set_dimension(&mut ctx, child, 1, 9.0, 1).unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&sb].scroll_y, 4);
+
+        // Scrolling up suspends following...
+        scroll::scroll_by(&mut ctx, sb, 0, -1);
+        layout::set_dimension(&mut ctx, child, 1, 11.0, 1).unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&sb].scroll_y, 3);
+
+        // ...until the view is back at the bottom.
+        scroll::scroll_by(&mut ctx, sb, 0, 100);
+        layout::set_dimension(&mut ctx, child, 1, 12.0, 1).unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&sb].scroll_y, 7);
+    }
+
+    #[test]
+    fn test_render_textarea_autoscroll_pins_view_to_last_line() {
+        let mut ctx = integration_ctx(20, 10);
+        let ta = crate::tree::create_node(&mut ctx, NodeType::TextArea).unwrap();
+        ctx.root = Some(ta);
+        crate::layout::set_dimension(&mut ctx, ta, 0, 10.0, 1).unwrap();
+        crate::layout::set_dimension(&mut ctx, ta, 1, 3.0, 1).unwrap();
+        ctx.nodes.get_mut(&ta).unwrap().autoscroll = true;
+        ctx.nodes.get_mut(&ta).unwrap().content = "a\nb\nc\nd\ne".into();
+
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&ta].textarea_view_row, 2);
+        assert_eq!(ctx.back_buffer.get(0, 2).unwrap().ch, 'e');
+
+        let buffer = ctx.nodes[&ta].text_buffer_handle.unwrap();
+        text_buffer::append(&mut ctx, buffer, "\nf\ng").unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&ta].textarea_view_row, 4);
+        assert_eq!(ctx.back_buffer.get(0, 2).unwrap().ch, 'g');
+
+        let node = ctx.nodes.get_mut(&ta).unwrap();
+        node.textarea_view_row = 1;
+        node.autoscroll_pinned = false;
+        text_buffer::append(&mut ctx, buffer, "\nh").unwrap();
+        render(&mut ctx).unwrap();
+        assert_eq!(ctx.nodes[&ta].textarea_view_row, 1);
+        assert!(!ctx.nodes[&ta].autoscroll_pinned);
+    }
 }
@@ -6,7 +6,7 @@
 //! - Persist scroll position across Render Passes
 
 use crate::context::TuiContext;
-use crate::types::{BorderStyle, NodeType};
+use crate::types::{BorderStyle, ErrorCode, NodeType, TuiError};
 
 /// Compute the maximum scroll position for a ScrollBox based on Taffy layout.
 ///
@@ -57,15 +57,22 @@ pub(crate) fn compute_max_scroll(ctx: &TuiContext, handle: u32) -> (i32, i32) {
 
 /// Set absolute scroll position for a ScrollBox node.
 /// Clamped to `[0, max_scroll]` based on content bounds.
-pub(crate) fn set_scroll(ctx: &mut TuiContext, handle: u32, x: i32, y: i32) -> Result<(), String> {
+pub(crate) fn set_scroll(
+    ctx: &mut TuiContext,
+    handle: u32,
+    x: i32,
+    y: i32,
+) -> Result<(), TuiError> {
     // Validate handle and type first
     {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         if node.node_type != NodeType::ScrollBox {
-            return Err(format!("Handle {handle} is not a ScrollBox"));
+            return Err(
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a ScrollBox"))
+            );
         }
     }
 
@@ -77,19 +84,20 @@ pub(crate) fn set_scroll(ctx: &mut TuiContext, handle: u32, x: i32, y: i32) -> R
         .expect("handle was validated above");
     node.scroll_x = x.clamp(0, max_x);
     node.scroll_y = y.clamp(0, max_y);
+    node.autoscroll_pinned = node.scroll_y >= max_y;
     node.dirty = true;
     Ok(())
 }
 
 /// Get current scroll position.
-pub(crate) fn get_scroll(ctx: &TuiContext, handle: u32) -> Result<(i32, i32), String> {
+pub(crate) fn get_scroll(ctx: &TuiContext, handle: u32) -> Result<(i32, i32), TuiError> {
     let node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
 
     if node.node_type != NodeType::ScrollBox {
-        return Err(format!("Handle {handle} is not a ScrollBox"));
+        return Err(ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a ScrollBox")));
     }
 
     Ok((node.scroll_x, node.scroll_y))
@@ -103,6 +111,7 @@ pub(crate) fn scroll_by(ctx: &mut TuiContext, handle: u32, dx: i32, dy: i32) {
         if node.node_type == NodeType::ScrollBox {
             node.scroll_x = (node.scroll_x + dx).clamp(0, max_x);
             node.scroll_y = (node.scroll_y + dy).clamp(0, max_y);
+            node.autoscroll_pinned = node.scroll_y >= max_y;
             node.dirty = true;
         }
     }
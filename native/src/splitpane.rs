@@ -8,21 +8,21 @@
 
 use crate::context::TuiContext;
 use crate::tree;
-use crate::types::{key, SplitAxis, TuiEvent};
+use crate::types::{key, ErrorCode, SplitAxis, TuiError, TuiEvent};
 use taffy::prelude::*;
 
 /// Set the split axis (0=Horizontal, 1=Vertical).
-pub(crate) fn set_axis(ctx: &mut TuiContext, handle: u32, axis: u8) -> Result<(), String> {
-    let axis = SplitAxis::from_u8(axis).ok_or_else(|| format!("Invalid split axis: {axis}"))?;
+pub(crate) fn set_axis(ctx: &mut TuiContext, handle: u32, axis: u8) -> Result<(), TuiError> {
+    let axis = SplitAxis::from_u8(axis)
+        .ok_or_else(|| ErrorCode::InvalidArgument.raise(format!("Invalid split axis: {axis}")))?;
     {
         let node = ctx
             .nodes
             .get_mut(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-        let state = node
-            .split_pane_state
-            .as_mut()
-            .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+        let state = node.split_pane_state.as_mut().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+        })?;
         state.axis = axis;
         node.dirty = true;
     }
@@ -32,16 +32,15 @@ pub(crate) fn set_axis(ctx: &mut TuiContext, handle: u32, axis: u8) -> Result<()
 }
 
 /// Set the split ratio in permille (0..=1000).
-pub(crate) fn set_ratio(ctx: &mut TuiContext, handle: u32, ratio: u16) -> Result<(), String> {
+pub(crate) fn set_ratio(ctx: &mut TuiContext, handle: u32, ratio: u16) -> Result<(), TuiError> {
     {
         let node = ctx
             .nodes
             .get_mut(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-        let state = node
-            .split_pane_state
-            .as_mut()
-            .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+        let state = node.split_pane_state.as_mut().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+        })?;
         state.primary_ratio_permille = ratio.min(1000);
         node.dirty = true;
     }
@@ -51,15 +50,14 @@ pub(crate) fn set_ratio(ctx: &mut TuiContext, handle: u32, ratio: u16) -> Result
 }
 
 /// Get the current split ratio in permille.
-pub(crate) fn get_ratio(ctx: &TuiContext, handle: u32) -> Result<u16, String> {
+pub(crate) fn get_ratio(ctx: &TuiContext, handle: u32) -> Result<u16, TuiError> {
     let node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .split_pane_state
-        .as_ref()
-        .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.split_pane_state.as_ref().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+    })?;
     Ok(state.primary_ratio_permille)
 }
 
@@ -69,16 +67,15 @@ pub(crate) fn set_min_sizes(
     handle: u32,
     min_primary: u16,
     min_secondary: u16,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     {
         let node = ctx
             .nodes
             .get_mut(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-        let state = node
-            .split_pane_state
-            .as_mut()
-            .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+        let state = node.split_pane_state.as_mut().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+        })?;
         state.min_primary = min_primary;
         state.min_secondary = min_secondary;
         node.dirty = true;
@@ -89,15 +86,18 @@ pub(crate) fn set_min_sizes(
 }
 
 /// Set the keyboard resize step (in cells).
-pub(crate) fn set_resize_step(ctx: &mut TuiContext, handle: u32, step: u16) -> Result<(), String> {
+pub(crate) fn set_resize_step(
+    ctx: &mut TuiContext,
+    handle: u32,
+    step: u16,
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .split_pane_state
-        .as_mut()
-        .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.split_pane_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+    })?;
     state.resize_step = step;
     node.dirty = true;
     tree::mark_dirty(ctx, handle);
@@ -109,15 +109,14 @@ pub(crate) fn set_resizable(
     ctx: &mut TuiContext,
     handle: u32,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .split_pane_state
-        .as_mut()
-        .ok_or_else(|| format!("Node {handle} is not a SplitPane"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.split_pane_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Node {handle} is not a SplitPane"))
+    })?;
     state.resizable = enabled;
     node.dirty = true;
     tree::mark_dirty(ctx, handle);
@@ -242,32 +241,29 @@ pub(crate) fn handle_mouse(ctx: &mut TuiContext, handle: u32, click_pos: u16, si
 /// Synchronize Taffy layout properties for SplitPane children.
 ///
 /// Sets the SplitPane's flex_direction based on axis, then configures each
-/// child's flex_basis to match the requested ratio.
-pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+/// child's flex_basis to match the requested ratio. Returns whether any
+/// style changed.
+pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<bool, TuiError> {
     let node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     let state = match node.split_pane_state.as_ref() {
         Some(s) => s.clone(),
-        None => return Ok(()), // Not a SplitPane, nothing to do
+        None => return Ok(false), // Not a SplitPane, nothing to do
     };
     let children = node.children.clone();
     let taffy_node = node.taffy_node;
-    let visible = node.visible;
+    let display = crate::layout::node_display(node);
 
     // Set flex direction on the SplitPane node itself
     let mut pane_style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Taffy style read failed: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Taffy style read failed: {e:?}")))?
         .clone();
 
-    pane_style.display = if visible {
-        Display::Flex
-    } else {
-        Display::None
-    };
+    pane_style.display = display;
     pane_style.flex_direction = match state.axis {
         SplitAxis::Horizontal => FlexDirection::Row,
         SplitAxis::Vertical => FlexDirection::Column,
@@ -283,9 +279,7 @@ pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<
         }
     }
 
-    ctx.tree
-        .set_style(taffy_node, pane_style)
-        .map_err(|e| format!("Taffy set_style failed: {e:?}"))?;
+    let mut changed = store_style(ctx, taffy_node, pane_style)?;
 
     // Configure children's flex properties
     if children.len() >= 2 {
@@ -330,7 +324,7 @@ pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<
             let mut style = ctx
                 .tree
                 .style(primary_taffy)
-                .map_err(|e| format!("Taffy style read failed: {e:?}"))?
+                .map_err(|e| ErrorCode::Layout.raise(format!("Taffy style read failed: {e:?}")))?
                 .clone();
             style.flex_basis = if available > 0.0 {
                 length(exact_primary)
@@ -358,9 +352,7 @@ pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<
                 }
             }
 
-            ctx.tree
-                .set_style(primary_taffy, style)
-                .map_err(|e| format!("Taffy set_style failed: {e:?}"))?;
+            changed |= store_style(ctx, primary_taffy, style)?;
         }
 
         // Secondary child: explicitly reserve the remaining cells once the
@@ -371,7 +363,7 @@ pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<
             let mut style = ctx
                 .tree
                 .style(secondary_taffy)
-                .map_err(|e| format!("Taffy style read failed: {e:?}"))?
+                .map_err(|e| ErrorCode::Layout.raise(format!("Taffy style read failed: {e:?}")))?
                 .clone();
             style.flex_basis = if available > 0.0 {
                 length(exact_secondary)
@@ -399,13 +391,24 @@ pub(crate) fn sync_children_layout(ctx: &mut TuiContext, handle: u32) -> Result<
                 }
             }
 
-            ctx.tree
-                .set_style(secondary_taffy, style)
-                .map_err(|e| format!("Taffy set_style failed: {e:?}"))?;
+            changed |= store_style(ctx, secondary_taffy, style)?;
         }
     }
 
-    Ok(())
+    Ok(changed)
+}
+
+/// Write a Taffy style only when it differs: `set_style` always dirties the
+/// node, and the pane is re-synced on every layout pass.
+fn store_style(ctx: &mut TuiContext, node: NodeId, style: Style) -> Result<bool, TuiError> {
+    if ctx.tree.style(node).is_ok_and(|current| *current == style) {
+        return Ok(false);
+    }
+    ctx.tree
+        .set_style(node, style)
+        .map_err(|e| ErrorCode::Layout.raise(format!("Taffy set_style failed: {e:?}")))?;
+    ctx.layout_dirty = true;
+    Ok(true)
 }
 
 #[cfg(test)]
@@ -1333,12 +1336,7 @@ mod tests {
         );
 
         // Shrink to 80: min_primary(60) + min_secondary(30) + gap(1) = 91 > 80
-        {
-            let tn = ctx.nodes[&sp].taffy_node;
-            let mut s = ctx.tree.style(tn).unwrap().clone();
-            s.size.width = length(80.0);
-            ctx.tree.set_style(tn, s).unwrap();
-        }
+        crate::layout::set_dimension(&mut ctx, sp, 0, 80.0, 1).unwrap();
         crate::layout::compute_layout(&mut ctx).unwrap();
 
         let c1_layout = ctx.tree.layout(ctx.nodes[&c1].taffy_node).unwrap();
@@ -1418,12 +1416,7 @@ mod tests {
         crate::layout::compute_layout(&mut ctx).unwrap();
 
         // Shrink height to 30: 25+25+1 = 51 > 30
-        {
-            let tn = ctx.nodes[&sp].taffy_node;
-            let mut s = ctx.tree.style(tn).unwrap().clone();
-            s.size.height = length(30.0);
-            ctx.tree.set_style(tn, s).unwrap();
-        }
+        crate::layout::set_dimension(&mut ctx, sp, 1, 30.0, 1).unwrap();
         crate::layout::compute_layout(&mut ctx).unwrap();
 
         let c1_layout = ctx.tree.layout(ctx.nodes[&c1].taffy_node).unwrap();
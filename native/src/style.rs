@@ -7,7 +7,9 @@
 
 use crate::context::TuiContext;
 use crate::theme::Theme;
-use crate::types::{BorderStyle, CellAttrs, VisualStyle};
+use crate::types::{
+    is_default_color, BorderStyle, CellAttrs, ErrorCode, TuiError, VisualStyle, COLOR_DEFAULT,
+};
 
 /// Set a color property (foreground, background, border_color).
 pub(crate) fn set_color(
@@ -15,11 +17,19 @@ pub(crate) fn set_color(
     handle: u32,
     prop: u32,
     color: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+
+    // Any default-tagged value collapses to the canonical sentinel so stray
+    // low bits never make it look like a distinct color downstream.
+    let color = if is_default_color(color) {
+        COLOR_DEFAULT
+    } else {
+        color
+    };
 
     let mask_bit = match prop {
         0 => {
@@ -34,7 +44,9 @@ pub(crate) fn set_color(
             node.visual_style.border_color = color;
             VisualStyle::MASK_BORDER_COLOR
         }
-        _ => return Err(format!("Invalid color property: {prop}")),
+        _ => {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid color property: {prop}")))
+        }
     };
     node.visual_style.style_mask |= mask_bit;
 
@@ -48,17 +60,17 @@ pub(crate) fn set_flag(
     handle: u32,
     prop: u32,
     value: u8,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
 
     let flag = match prop {
         0 => CellAttrs::BOLD,
         1 => CellAttrs::ITALIC,
         2 => CellAttrs::UNDERLINE,
-        _ => return Err(format!("Invalid flag property: {prop}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid flag property: {prop}"))),
     };
 
     if value != 0 {
@@ -81,15 +93,16 @@ pub(crate) fn set_border(
     ctx: &mut TuiContext,
     handle: u32,
     border_style: u8,
-) -> Result<(), String> {
-    let bs = BorderStyle::from_u8(border_style)
-        .ok_or_else(|| format!("Invalid border style: {border_style}"))?;
+) -> Result<(), TuiError> {
+    let bs = BorderStyle::from_u8(border_style).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid border style: {border_style}"))
+    })?;
 
     let taffy_node = {
         let node = ctx
             .nodes
             .get_mut(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         node.visual_style.border_style = bs;
         node.visual_style.style_mask |= VisualStyle::MASK_BORDER_STYLE;
         node.dirty = true;
@@ -99,7 +112,7 @@ pub(crate) fn set_border(
     let mut style = ctx
         .tree
         .style(taffy_node)
-        .map_err(|e| format!("Failed to read style: {e:?}"))?
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to read style: {e:?}")))?
         .clone();
     let thickness = if bs == BorderStyle::None { 0.0 } else { 1.0 };
     style.border = taffy::Rect {
@@ -110,17 +123,18 @@ pub(crate) fn set_border(
     };
     ctx.tree
         .set_style(taffy_node, style)
-        .map_err(|e| format!("Failed to set style: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Failed to set style: {e:?}")))?;
+    ctx.layout_dirty = true;
 
     Ok(())
 }
 
 /// Set opacity (0.0–1.0).
-pub(crate) fn set_opacity(ctx: &mut TuiContext, handle: u32, opacity: f32) -> Result<(), String> {
+pub(crate) fn set_opacity(ctx: &mut TuiContext, handle: u32, opacity: f32) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
 
     node.visual_style.opacity = opacity.clamp(0.0, 1.0);
     node.visual_style.style_mask |= VisualStyle::MASK_OPACITY;
@@ -128,7 +142,47 @@ pub(crate) fn set_opacity(ctx: &mut TuiContext, handle: u32, opacity: f32) -> Re
     Ok(())
 }
 
-/// Resolve the effective visual style for a node, merging explicit node styles
+/// Enable or disable opacity cascade: the node's opacity multiplies into
+/// the effective opacity of all its descendants.
+pub(crate) fn set_opacity_cascade(
+    ctx: &mut TuiContext,
+    handle: u32,
+    enabled: bool,
+) -> Result<(), TuiError> {
+    let node = ctx
+        .nodes
+        .get_mut(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+
+    node.opacity_cascade = enabled;
+    node.dirty = true;
+    if enabled {
+        ctx.opacity_cascade_used = true;
+    }
+    Ok(())
+}
+
+/// Resolve the effective visual style for a node: its own style (see
+/// `resolve_own_style`) with opacity multiplied by every cascading
+/// ancestor's.
+pub(crate) fn resolve_style(handle: u32, ctx: &TuiContext) -> VisualStyle {
+    let mut resolved = resolve_own_style(handle, ctx);
+    if ctx.opacity_cascade_used {
+        let mut current = ctx.nodes.get(&handle).and_then(|n| n.parent);
+        while let Some(ancestor) = current {
+            let Some(node) = ctx.nodes.get(&ancestor) else {
+                break;
+            };
+            if node.opacity_cascade {
+                resolved.opacity *= resolve_own_style(ancestor, ctx).opacity;
+            }
+            current = node.parent;
+        }
+    }
+    resolved
+}
+
+/// Resolve a node's own visual style, merging explicit node styles
 /// with nearest-ancestor theme defaults.
 ///
 /// Precedence per property (ADR-T21):
@@ -136,7 +190,7 @@ pub(crate) fn set_opacity(ctx: &mut TuiContext, handle: u32, opacity: f32) -> Re
 /// 2. Theme NodeType-specific default (theme.type_defaults[node_type] + mask bit)
 /// 3. Theme global default (theme.mask bit set)
 /// 4. Node stored value
-pub(crate) fn resolve_style(handle: u32, ctx: &TuiContext) -> VisualStyle {
+fn resolve_own_style(handle: u32, ctx: &TuiContext) -> VisualStyle {
     let node = match ctx.nodes.get(&handle) {
         Some(n) => n,
         None => return VisualStyle::default(),
@@ -424,6 +478,48 @@ mod tests {
         assert_eq!(resolved.fg_color, 0x01E0E0E0); // from dark theme (not explicit)
     }
 
+    #[test]
+    fn test_resolve_style_explicit_default_overrides_theme() {
+        use crate::theme;
+
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        ctx.root = Some(root);
+
+        theme::apply_theme(&mut ctx, 1, root).unwrap();
+        // Stray low bits under the default tag collapse to the sentinel
+        set_color(&mut ctx, root, 1, 0x00123456).unwrap();
+        assert_eq!(ctx.nodes[&root].visual_style.bg_color, COLOR_DEFAULT);
+
+        // An explicit terminal default is not replaced by the theme's bg
+        let resolved = resolve_style(root, &ctx);
+        assert_eq!(resolved.bg_color, COLOR_DEFAULT);
+    }
+
+    #[test]
+    fn test_resolve_style_multiplies_cascading_ancestor_opacity() {
+        let mut ctx = test_ctx();
+        let root = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let panel = tree::create_node(&mut ctx, NodeType::Box).unwrap();
+        let label = tree::create_node(&mut ctx, NodeType::Text).unwrap();
+        tree::append_child(&mut ctx, root, panel).unwrap();
+        tree::append_child(&mut ctx, panel, label).unwrap();
+        set_opacity(&mut ctx, root, 0.5).unwrap();
+        set_opacity(&mut ctx, panel, 0.5).unwrap();
+        set_opacity(&mut ctx, label, 0.8).unwrap();
+
+        // Without cascade each node keeps its own opacity.
+        assert_eq!(resolve_style(label, &ctx).opacity, 0.8);
+
+        set_opacity_cascade(&mut ctx, panel, true).unwrap();
+        assert_eq!(resolve_style(label, &ctx).opacity, 0.4);
+        assert_eq!(resolve_style(panel, &ctx).opacity, 0.5);
+
+        set_opacity_cascade(&mut ctx, root, true).unwrap();
+        assert_eq!(resolve_style(label, &ctx).opacity, 0.2);
+        assert_eq!(resolve_style(panel, &ctx).opacity, 0.25);
+    }
+
     #[test]
     fn test_resolve_style_all_explicit_skips_theme() {
         use crate::theme;
@@ -4,7 +4,7 @@
 //! not on crossterm directly. This enables mock backends for testing
 //! and future backend substitution.
 
-use crate::types::TerminalInputEvent;
+use crate::types::{CellUpdate, LineScale, TerminalInputEvent, TuiCellUpdate, TuiError};
 use crate::writer::{WriteRun, WriterMetrics, WriterState};
 use crate::{terminal_capabilities, terminal_capabilities::TerminalCapabilityState};
 
@@ -12,9 +12,11 @@ use crate::{terminal_capabilities, terminal_capabilities::TerminalCapabilityStat
 // TerminalBackend Trait
 // ============================================================================
 
-pub trait TerminalBackend {
-    fn init(&mut self) -> Result<(), String>;
-    fn shutdown(&mut self) -> Result<(), String>;
+/// Backends live in the global context, which any host thread may lock when
+/// thread affinity is off (`tui_set_thread_affinity`), hence `Send + Sync`.
+pub trait TerminalBackend: Send + Sync {
+    fn init(&mut self) -> Result<(), TuiError>;
+    fn shutdown(&mut self) -> Result<(), TuiError>;
     fn size(&self) -> (u16, u16);
     fn capabilities(&mut self) -> TerminalCapabilityState;
     fn write_clipboard(
@@ -22,7 +24,7 @@ pub trait TerminalBackend {
         state: &TerminalCapabilityState,
         target: u8,
         text: &str,
-    ) -> Result<bool, String>;
+    ) -> Result<bool, TuiError>;
     fn read_events(&mut self, timeout_ms: u32) -> Vec<TerminalInputEvent>;
 
     /// Emit compacted writer runs through this backend's output channel.
@@ -35,7 +37,62 @@ pub trait TerminalBackend {
         root_bg: u32,
         osc8_enabled: bool,
         synchronized_output_enabled: bool,
-    ) -> Result<WriterMetrics, String>;
+    ) -> Result<WriterMetrics, TuiError>;
+
+    /// Hand the terminal back to the host (leave the alternate screen, drop
+    /// raw mode) without tearing down the context. No-op for backends that
+    /// do not own a terminal.
+    fn suspend(&mut self) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Re-acquire the terminal after `suspend`.
+    fn resume(&mut self) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Turn terminal mouse reporting on or off. While off, no `Mouse` events
+    /// are reported and the MOUSE capability bit is cleared, leaving the
+    /// terminal's native selection to the user.
+    fn set_mouse_capture(&mut self, _enabled: bool) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Write a host-provided inline image escape with its top-left at cell
+    /// (x, y). Called after the frame's runs only when the terminal reports
+    /// INLINE_IMAGES; backends without a terminal ignore it.
+    fn write_image(&mut self, _x: u16, _y: u16, _payload: &[u8]) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Set the DEC line attribute (DECSWL/DECDWL/DECDHL) of screen row `y`.
+    /// Called before the frame's runs only when the terminal reports
+    /// DEC_LINE_ATTRIBUTES.
+    fn write_line_scale(&mut self, _y: u16, _scale: LineScale) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Receive the raw cell diff for the frame before it is compacted into
+    /// runs. Terminal backends ignore it; embedding backends forward it.
+    /// Terminal output does not need per-cell coalescing here: the diff is
+    /// merged into same-style row runs by `writer::compact_runs` before
+    /// `emit_runs`, so a changed row costs one cursor move per style change.
+    fn write_diff(&mut self, _updates: &[CellUpdate]) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    /// Change the reported size and queue a matching `Resize` input event.
+    /// Only test/headless backends support this; real terminals resize
+    /// themselves.
+    fn simulate_resize(&mut self, _width: u16, _height: u16) -> Result<(), TuiError> {
+        Err("Active backend is not headless; cannot simulate a resize".into())
+    }
+
+    /// Queue a synthetic input event for the next `read_events` call.
+    /// Only the headless backend supports injection.
+    fn inject_event(&mut self, _event: TerminalInputEvent) -> Result<(), TuiError> {
+        Err("Active backend is not headless; cannot inject input".into())
+    }
 
     /// Downcast support for test code. Returns self as Any for type-safe downcasting.
     #[cfg(test)]
@@ -56,6 +113,7 @@ pub struct CrosstermBackend {
     /// application's root bg, any gaps become invisible.
     osc11_bg: u32,
     kitty_keyboard_enabled: bool,
+    mouse_capture: bool,
 }
 
 impl CrosstermBackend {
@@ -66,6 +124,7 @@ impl CrosstermBackend {
             height: h,
             osc11_bg: 0,
             kitty_keyboard_enabled: false,
+            mouse_capture: true,
         }
     }
 
@@ -86,7 +145,7 @@ impl CrosstermBackend {
 }
 
 impl TerminalBackend for CrosstermBackend {
-    fn init(&mut self) -> Result<(), String> {
+    fn init(&mut self) -> Result<(), TuiError> {
         use crossterm::{
             cursor,
             event::{EnableMouseCapture, KeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
@@ -99,9 +158,11 @@ impl TerminalBackend for CrosstermBackend {
         stdout
             .execute(EnterAlternateScreen)
             .map_err(|e| format!("alternate screen: {e}"))?;
-        stdout
-            .execute(EnableMouseCapture)
-            .map_err(|e| format!("mouse capture: {e}"))?;
+        if self.mouse_capture {
+            stdout
+                .execute(EnableMouseCapture)
+                .map_err(|e| format!("mouse capture: {e}"))?;
+        }
         // Hide the terminal cursor for the entire TUI session.
         // Input widget cursors are rendered as inverted cells in the buffer
         // (render.rs render_input_cursor), so the OS cursor is not needed and
@@ -136,7 +197,7 @@ impl TerminalBackend for CrosstermBackend {
         Ok(())
     }
 
-    fn shutdown(&mut self) -> Result<(), String> {
+    fn shutdown(&mut self) -> Result<(), TuiError> {
         use crossterm::{
             cursor,
             event::{DisableMouseCapture, PopKeyboardEnhancementFlags},
@@ -207,7 +268,42 @@ impl TerminalBackend for CrosstermBackend {
             disable_raw_mode().map_err(|e| format!("disable raw mode: {e}")),
         );
 
-        first_error.map_or(Ok(()), Err)
+        first_error.map_or(Ok(()), |error| Err(error.into()))
+    }
+
+    fn suspend(&mut self) -> Result<(), TuiError> {
+        let result = self.shutdown();
+        // shutdown restored the terminal's own default background; re-sync
+        // OSC 11 on the first frame after resume.
+        self.osc11_bg = 0;
+        result
+    }
+
+    fn resume(&mut self) -> Result<(), TuiError> {
+        self.init()
+    }
+
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), TuiError> {
+        use crossterm::{
+            event::{DisableMouseCapture, EnableMouseCapture},
+            ExecutableCommand,
+        };
+
+        if enabled == self.mouse_capture {
+            return Ok(());
+        }
+        let mut stdout = std::io::stdout();
+        if enabled {
+            stdout
+                .execute(EnableMouseCapture)
+                .map_err(|e| format!("mouse capture: {e}"))?;
+        } else {
+            stdout
+                .execute(DisableMouseCapture)
+                .map_err(|e| format!("disable mouse: {e}"))?;
+        }
+        self.mouse_capture = enabled;
+        Ok(())
     }
 
     fn size(&self) -> (u16, u16) {
@@ -216,13 +312,17 @@ impl TerminalBackend for CrosstermBackend {
 
     fn capabilities(&mut self) -> TerminalCapabilityState {
         let (pixel_width, pixel_height, columns, rows) = self.window_pixels();
-        TerminalCapabilityState::from_current_env(
+        let mut state = TerminalCapabilityState::from_current_env(
             columns,
             rows,
             pixel_width,
             pixel_height,
             self.kitty_keyboard_enabled,
-        )
+        );
+        if !self.mouse_capture {
+            state.flags &= !terminal_capabilities::terminal_capability::MOUSE;
+        }
+        state
     }
 
     fn write_clipboard(
@@ -230,7 +330,7 @@ impl TerminalBackend for CrosstermBackend {
         state: &TerminalCapabilityState,
         target: u8,
         text: &str,
-    ) -> Result<bool, String> {
+    ) -> Result<bool, TuiError> {
         // Malformed host input is always an error, even when the current
         // backend would otherwise no-op because OSC52 is unsupported.
         terminal_capabilities::clipboard_target_code(target)?;
@@ -312,6 +412,7 @@ impl TerminalBackend for CrosstermBackend {
                             character: ch,
                         });
                     }
+                    Ok(Event::Mouse(_)) if !self.mouse_capture => {}
                     Ok(Event::Mouse(mouse_event)) => {
                         let button = match mouse_event.kind {
                             MouseEventKind::Down(MouseButton::Left) => 0u8,
@@ -378,7 +479,7 @@ impl TerminalBackend for CrosstermBackend {
         root_bg: u32,
         osc8_enabled: bool,
         synchronized_output_enabled: bool,
-    ) -> Result<WriterMetrics, String> {
+    ) -> Result<WriterMetrics, TuiError> {
         use std::io::Write;
         let mut buf: Vec<u8> = Vec::with_capacity(32 * 1024);
 
@@ -393,6 +494,11 @@ impl TerminalBackend for CrosstermBackend {
             let b = root_bg & 0xFF;
             buf.extend_from_slice(format!("\x1b]11;rgb:{r:02x}/{g:02x}/{b:02x}\x1b\\").as_bytes());
             self.osc11_bg = root_bg;
+        } else if crate::types::is_default_color(root_bg) && self.osc11_bg != 0 {
+            // Root went back to the terminal default: restore the user's own
+            // background (OSC 111) instead of leaving our last RGB in place.
+            buf.extend_from_slice(b"\x1b]111\x1b\\");
+            self.osc11_bg = 0;
         }
 
         let metrics = emit_writer_frame(
@@ -409,6 +515,30 @@ impl TerminalBackend for CrosstermBackend {
         Ok(metrics)
     }
 
+    fn write_line_scale(&mut self, y: u16, scale: LineScale) -> Result<(), TuiError> {
+        use std::io::Write;
+        let mut stdout = std::io::stdout();
+        stdout
+            .write_all(&line_scale_sequence(y, scale))
+            .map_err(|e| format!("line scale write: {e}"))?;
+        stdout
+            .flush()
+            .map_err(|e| format!("line scale flush: {e}"))?;
+        Ok(())
+    }
+
+    fn write_image(&mut self, x: u16, y: u16, payload: &[u8]) -> Result<(), TuiError> {
+        use std::io::Write;
+        let mut stdout = std::io::stdout();
+        write!(stdout, "\x1b[{};{}H", y as u32 + 1, x as u32 + 1)
+            .map_err(|e| format!("image move: {e}"))?;
+        stdout
+            .write_all(payload)
+            .map_err(|e| format!("image write: {e}"))?;
+        stdout.flush().map_err(|e| format!("image flush: {e}"))?;
+        Ok(())
+    }
+
     #[cfg(test)]
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
@@ -422,20 +552,28 @@ impl TerminalBackend for CrosstermBackend {
 pub struct HeadlessBackend {
     pub width: u16,
     pub height: u16,
+    /// Synthetic input (e.g. simulated resizes) delivered on the next read.
+    pub pending_events: Vec<TerminalInputEvent>,
+    pub mouse_capture: bool,
 }
 
 impl HeadlessBackend {
     pub fn new(width: u16, height: u16) -> Self {
-        Self { width, height }
+        Self {
+            width,
+            height,
+            pending_events: Vec::new(),
+            mouse_capture: true,
+        }
     }
 }
 
 impl TerminalBackend for HeadlessBackend {
-    fn init(&mut self) -> Result<(), String> {
+    fn init(&mut self) -> Result<(), TuiError> {
         Ok(())
     }
 
-    fn shutdown(&mut self) -> Result<(), String> {
+    fn shutdown(&mut self) -> Result<(), TuiError> {
         Ok(())
     }
 
@@ -444,7 +582,11 @@ impl TerminalBackend for HeadlessBackend {
     }
 
     fn capabilities(&mut self) -> TerminalCapabilityState {
-        TerminalCapabilityState::headless(self.width, self.height)
+        let mut state = TerminalCapabilityState::headless(self.width, self.height);
+        if !self.mouse_capture {
+            state.flags &= !terminal_capabilities::terminal_capability::MOUSE;
+        }
+        state
     }
 
     fn write_clipboard(
@@ -452,7 +594,7 @@ impl TerminalBackend for HeadlessBackend {
         state: &TerminalCapabilityState,
         target: u8,
         text: &str,
-    ) -> Result<bool, String> {
+    ) -> Result<bool, TuiError> {
         // Validate even on unsupported backends so malformed host requests
         // fail consistently instead of being masked by headless no-op behavior.
         terminal_capabilities::clipboard_target_code(target)?;
@@ -464,7 +606,30 @@ impl TerminalBackend for HeadlessBackend {
     }
 
     fn read_events(&mut self, _timeout_ms: u32) -> Vec<TerminalInputEvent> {
-        Vec::new() // No terminal input
+        // No terminal input; only synthetic events queued by the host
+        let mut events = std::mem::take(&mut self.pending_events);
+        if !self.mouse_capture {
+            events.retain(|e| !matches!(e, TerminalInputEvent::Mouse { .. }));
+        }
+        events
+    }
+
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), TuiError> {
+        self.mouse_capture = enabled;
+        Ok(())
+    }
+
+    fn simulate_resize(&mut self, width: u16, height: u16) -> Result<(), TuiError> {
+        self.width = width;
+        self.height = height;
+        self.pending_events
+            .push(TerminalInputEvent::Resize { width, height });
+        Ok(())
+    }
+
+    fn inject_event(&mut self, event: TerminalInputEvent) -> Result<(), TuiError> {
+        self.pending_events.push(event);
+        Ok(())
     }
 
     fn emit_runs(
@@ -474,10 +639,138 @@ impl TerminalBackend for HeadlessBackend {
         _root_bg: u32,
         osc8_enabled: bool,
         _synchronized_output_enabled: bool,
-    ) -> Result<WriterMetrics, String> {
+    ) -> Result<WriterMetrics, TuiError> {
         let mut sink = std::io::sink();
         crate::writer::emit_frame(state, runs, &mut sink, osc8_enabled)
-            .map_err(|e| format!("writer: {e}"))
+            .map_err(|e| format!("writer: {e}").into())
+    }
+
+    #[cfg(test)]
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+// ============================================================================
+// CallbackBackend (for embedding in a host-owned surface)
+// ============================================================================
+
+/// Receives each frame's changed cells. `updates` is valid only for the
+/// duration of the call.
+pub type WriteDiffCallback =
+    extern "C" fn(updates: *const TuiCellUpdate, len: u32, user_data: *mut std::ffi::c_void);
+
+/// Called once per frame after the diff has been delivered.
+pub type FlushCallback = extern "C" fn(user_data: *mut std::ffi::c_void);
+
+/// Forwards frame diffs to host function pointers instead of writing escape
+/// codes, so the host can draw cells on its own surface. Size and input are
+/// host-driven, as with the headless backend.
+pub struct CallbackBackend {
+    width: u16,
+    height: u16,
+    pending_events: Vec<TerminalInputEvent>,
+    write_diff_cb: WriteDiffCallback,
+    flush_cb: Option<FlushCallback>,
+    user_data: *mut std::ffi::c_void,
+}
+
+impl CallbackBackend {
+    pub fn new(
+        width: u16,
+        height: u16,
+        write_diff_cb: WriteDiffCallback,
+        flush_cb: Option<FlushCallback>,
+        user_data: *mut std::ffi::c_void,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            pending_events: Vec::new(),
+            write_diff_cb,
+            flush_cb,
+            user_data,
+        }
+    }
+}
+
+// SAFETY: `user_data` is never dereferenced here, only handed back to the
+// host's callbacks, and those run under the context write lock, so at most
+// one thread calls them at a time. Callbacks that are only valid on one thread
+// require the host to keep thread affinity on (the default).
+unsafe impl Send for CallbackBackend {}
+unsafe impl Sync for CallbackBackend {}
+
+impl TerminalBackend for CallbackBackend {
+    fn init(&mut self) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<(), TuiError> {
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn capabilities(&mut self) -> TerminalCapabilityState {
+        TerminalCapabilityState::headless(self.width, self.height)
+    }
+
+    fn write_clipboard(
+        &mut self,
+        _state: &TerminalCapabilityState,
+        target: u8,
+        text: &str,
+    ) -> Result<bool, TuiError> {
+        terminal_capabilities::clipboard_target_code(target)?;
+        terminal_capabilities::validate_clipboard_text(text)?;
+        Ok(false)
+    }
+
+    fn read_events(&mut self, _timeout_ms: u32) -> Vec<TerminalInputEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    fn write_diff(&mut self, updates: &[CellUpdate]) -> Result<(), TuiError> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        let cells: Vec<TuiCellUpdate> = updates.iter().map(TuiCellUpdate::from).collect();
+        (self.write_diff_cb)(cells.as_ptr(), cells.len() as u32, self.user_data);
+        Ok(())
+    }
+
+    fn simulate_resize(&mut self, width: u16, height: u16) -> Result<(), TuiError> {
+        self.width = width;
+        self.height = height;
+        self.pending_events
+            .push(TerminalInputEvent::Resize { width, height });
+        Ok(())
+    }
+
+    fn inject_event(&mut self, event: TerminalInputEvent) -> Result<(), TuiError> {
+        self.pending_events.push(event);
+        Ok(())
+    }
+
+    fn emit_runs(
+        &mut self,
+        state: &mut WriterState,
+        runs: &[WriteRun],
+        _root_bg: u32,
+        osc8_enabled: bool,
+        _synchronized_output_enabled: bool,
+    ) -> Result<WriterMetrics, TuiError> {
+        // The host already received the cells; keep writer metrics populated.
+        let mut sink = std::io::sink();
+        let metrics = crate::writer::emit_frame(state, runs, &mut sink, osc8_enabled)
+            .map_err(|e| format!("writer: {e}"))?;
+        if let Some(flush) = self.flush_cb {
+            flush(self.user_data);
+        }
+        Ok(metrics)
     }
 
     #[cfg(test)]
@@ -514,11 +807,11 @@ impl MockBackend {
 
 #[cfg(test)]
 impl TerminalBackend for MockBackend {
-    fn init(&mut self) -> Result<(), String> {
+    fn init(&mut self) -> Result<(), TuiError> {
         Ok(())
     }
 
-    fn shutdown(&mut self) -> Result<(), String> {
+    fn shutdown(&mut self) -> Result<(), TuiError> {
         Ok(())
     }
 
@@ -535,7 +828,7 @@ impl TerminalBackend for MockBackend {
         state: &TerminalCapabilityState,
         target: u8,
         text: &str,
-    ) -> Result<bool, String> {
+    ) -> Result<bool, TuiError> {
         if !state.supports(terminal_capabilities::terminal_capability::OSC52_CLIPBOARD_WRITE) {
             terminal_capabilities::clipboard_target_code(target)?;
             terminal_capabilities::validate_clipboard_text(text)?;
@@ -557,9 +850,22 @@ impl TerminalBackend for MockBackend {
         _root_bg: u32,
         osc8_enabled: bool,
         _synchronized_output_enabled: bool,
-    ) -> Result<WriterMetrics, String> {
+    ) -> Result<WriterMetrics, TuiError> {
         crate::writer::emit_frame(state, runs, &mut self.output, osc8_enabled)
-            .map_err(|e| format!("writer: {e}"))
+            .map_err(|e| format!("writer: {e}").into())
+    }
+
+    fn write_line_scale(&mut self, y: u16, scale: LineScale) -> Result<(), TuiError> {
+        self.output
+            .extend_from_slice(&line_scale_sequence(y, scale));
+        Ok(())
+    }
+
+    fn write_image(&mut self, x: u16, y: u16, payload: &[u8]) -> Result<(), TuiError> {
+        self.output
+            .extend_from_slice(format!("\x1b[{};{}H", y as u32 + 1, x as u32 + 1).as_bytes());
+        self.output.extend_from_slice(payload);
+        Ok(())
     }
 
     #[cfg(test)]
@@ -568,13 +874,24 @@ impl TerminalBackend for MockBackend {
     }
 }
 
+/// Move to the start of row `y` and set its DEC line attribute.
+fn line_scale_sequence(y: u16, scale: LineScale) -> Vec<u8> {
+    let code = match scale {
+        LineScale::Normal => '5',
+        LineScale::DoubleWidth => '6',
+        LineScale::DoubleHeightTop => '3',
+        LineScale::DoubleHeightBottom => '4',
+    };
+    format!("\x1b[{};1H\x1b#{code}", y as u32 + 1).into_bytes()
+}
+
 fn emit_writer_frame(
     state: &mut WriterState,
     runs: &[WriteRun],
     buf: &mut Vec<u8>,
     osc8_enabled: bool,
     synchronized_output_enabled: bool,
-) -> Result<WriterMetrics, String> {
+) -> Result<WriterMetrics, TuiError> {
     // Mode 2026 is a terminal protocol, not a generic buffering primitive.
     // Emit it only when capability detection has positively allowed it.
     if synchronized_output_enabled {
@@ -592,6 +909,46 @@ fn emit_writer_frame(
 mod tests {
     use super::*;
 
+    #[test]
+    fn simulate_resize_only_supported_on_headless() {
+        let mut headless = HeadlessBackend::new(80, 24);
+        headless.simulate_resize(100, 30).unwrap();
+        assert_eq!(headless.size(), (100, 30));
+        assert!(matches!(
+            headless.read_events(0).as_slice(),
+            [TerminalInputEvent::Resize {
+                width: 100,
+                height: 30
+            }]
+        ));
+        assert!(headless.read_events(0).is_empty());
+
+        let mut mock = MockBackend::new(80, 24);
+        assert!(mock.simulate_resize(100, 30).is_err());
+        assert_eq!(mock.size(), (80, 24));
+    }
+
+    #[test]
+    fn inject_event_only_supported_on_headless() {
+        let mut headless = HeadlessBackend::new(80, 24);
+        headless
+            .inject_event(TerminalInputEvent::FocusGained)
+            .unwrap();
+        headless
+            .inject_event(TerminalInputEvent::FocusLost)
+            .unwrap();
+        assert!(matches!(
+            headless.read_events(0).as_slice(),
+            [
+                TerminalInputEvent::FocusGained,
+                TerminalInputEvent::FocusLost
+            ]
+        ));
+
+        let mut mock = MockBackend::new(80, 24);
+        assert!(mock.inject_event(TerminalInputEvent::FocusGained).is_err());
+    }
+
     #[test]
     fn emit_writer_frame_omits_sync_mode_when_disabled() {
         let mut state = WriterState::new();
@@ -4,6 +4,8 @@ use base64::Engine;
 use serde::{Serialize, Serializer};
 use std::collections::HashMap;
 
+use crate::types::{ErrorCode, TuiError};
+
 pub const OSC52_MAX_BYTES: usize = 100 * 1024;
 pub const OSC8_MAX_URI_BYTES: usize = 4096;
 pub const OSC8_MAX_ID_BYTES: usize = 128;
@@ -22,6 +24,8 @@ pub mod terminal_capability {
     pub const COLOR_DEPTH_QUERY: u64 = 1 << 10;
     pub const MULTIPLEXER_PRESENT: u64 = 1 << 11;
     pub const SYNCHRONIZED_OUTPUT: u64 = 1 << 12;
+    pub const INLINE_IMAGES: u64 = 1 << 13;
+    pub const DEC_LINE_ATTRIBUTES: u64 = 1 << 14;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -115,14 +119,27 @@ impl TerminalCapabilityState {
         let terminal_program = get_env(env, "TERM_PROGRAM");
         let multiplexer =
             detect_multiplexer(env, terminal_name.as_deref(), terminal_program.as_deref());
-        let color_depth_bits = detect_color_depth(env, terminal_name.as_deref());
+        let color_depth_bits =
+            detect_color_depth(env, terminal_name.as_deref(), terminal_program.as_deref());
 
-        let mut flags = terminal_capability::UTF8
-            | terminal_capability::COLOR_16
-            | terminal_capability::MOUSE
-            | terminal_capability::ALTERNATE_SCREEN
-            | terminal_capability::COLOR_DEPTH_QUERY;
+        let mut flags = terminal_capability::COLOR_DEPTH_QUERY;
 
+        // `dumb` has no cursor addressing at all, and the Linux VT console
+        // ignores xterm mouse reporting.
+        let term_lower = terminal_name.as_deref().map(str::to_ascii_lowercase);
+        let is_dumb = term_lower.as_deref() == Some("dumb");
+        if detect_utf8(env, term_lower.as_deref()) {
+            flags |= terminal_capability::UTF8;
+        }
+        if !is_dumb {
+            flags |= terminal_capability::ALTERNATE_SCREEN;
+            if term_lower.as_deref() != Some("linux") {
+                flags |= terminal_capability::MOUSE;
+            }
+        }
+        if color_depth_bits >= 4 {
+            flags |= terminal_capability::COLOR_16;
+        }
         if color_depth_bits >= 8 {
             flags |= terminal_capability::COLOR_256;
         }
@@ -142,6 +159,20 @@ impl TerminalCapabilityState {
             flags |=
                 terminal_capability::OSC52_CLIPBOARD_WRITE | terminal_capability::OSC8_HYPERLINKS;
             flags |= terminal_capability::SYNCHRONIZED_OUTPUT;
+            if allows_inline_images(terminal_name.as_deref(), terminal_program.as_deref()) {
+                flags |= terminal_capability::INLINE_IMAGES;
+            }
+            // Most emulators claim TERM=xterm without double-size lines;
+            // only real xterm (which exports XTERM_VERSION) and a few
+            // named emulators draw them.
+            let program = terminal_program.as_deref().unwrap_or_default();
+            if env.contains_key("XTERM_VERSION")
+                || ["wezterm", "mlterm", "konsole"]
+                    .iter()
+                    .any(|needle| program.to_ascii_lowercase().contains(needle))
+            {
+                flags |= terminal_capability::DEC_LINE_ATTRIBUTES;
+            }
         } else if multiplexer == TerminalMultiplexer::Tmux
             && allows_direct_risky_protocols(None, terminal_program.as_deref())
         {
@@ -183,8 +214,8 @@ impl TerminalCapabilityState {
         self.flags & flag != 0
     }
 
-    pub fn to_json(&self) -> Result<String, String> {
-        serde_json::to_string(self).map_err(|e| format!("terminal info json: {e}"))
+    pub fn to_json(&self) -> Result<String, TuiError> {
+        serde_json::to_string(self).map_err(|e| format!("terminal info json: {e}").into())
     }
 }
 
@@ -252,6 +283,42 @@ fn allows_direct_risky_protocols(term: Option<&str>, term_program: Option<&str>)
     known_term || known_program
 }
 
+/// Emulators known to display at least one inline image protocol (kitty
+/// graphics, iTerm2 images or sixel). The host picks the encoding; native
+/// only decides whether to emit its payload at all.
+fn allows_inline_images(term: Option<&str>, term_program: Option<&str>) -> bool {
+    let term = term.map(str::to_ascii_lowercase).unwrap_or_default();
+    let program = term_program
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    ["kitty", "wezterm", "ghostty", "iterm", "foot", "mlterm"]
+        .iter()
+        .any(|needle| term.contains(needle) || program.contains(needle))
+}
+
+/// UTF-8 output unless the effective locale (LC_ALL, then LC_CTYPE, then
+/// LANG) names another charset or the terminal is a pre-Unicode type. An
+/// unset locale keeps UTF-8 so ordinary sessions are never downgraded.
+fn detect_utf8(env: &HashMap<String, String>, term_lower: Option<&str>) -> bool {
+    if matches!(
+        term_lower,
+        Some("dumb" | "vt52" | "vt100" | "vt102" | "vt220")
+    ) {
+        return false;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|key| get_env(env, key));
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
 fn get_env(env: &HashMap<String, String>, key: &str) -> Option<String> {
     env.get(key)
         .map(|value| value.trim().to_string())
@@ -284,7 +351,16 @@ fn detect_multiplexer(
     TerminalMultiplexer::None
 }
 
-fn detect_color_depth(env: &HashMap<String, String>, term: Option<&str>) -> u8 {
+fn detect_color_depth(
+    env: &HashMap<String, String>,
+    term: Option<&str>,
+    term_program: Option<&str>,
+) -> u8 {
+    let term = term.map(str::to_ascii_lowercase).unwrap_or_default();
+    if term == "dumb" {
+        return 0;
+    }
+
     let colorterm = get_env(env, "COLORTERM")
         .map(|v| v.to_ascii_lowercase())
         .unwrap_or_default();
@@ -292,10 +368,19 @@ fn detect_color_depth(env: &HashMap<String, String>, term: Option<&str>) -> u8 {
         return 24;
     }
 
-    let term = term.map(str::to_ascii_lowercase).unwrap_or_default();
     if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
         return 24;
     }
+    // Emulators known to render 24-bit color even when COLORTERM is not
+    // forwarded (e.g. over ssh, or from a GUI launcher).
+    if get_env(env, "WT_SESSION").is_some()
+        || matches!(
+            term_program,
+            Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+        )
+    {
+        return 24;
+    }
     if term.contains("256color") || term.contains("256") {
         return 8;
     }
@@ -312,20 +397,23 @@ fn derive_cell_pixels(columns: u16, rows: u16, pixel_width: u32, pixel_height: u
     )
 }
 
-pub fn clipboard_target_code(target: u8) -> Result<&'static str, String> {
+pub fn clipboard_target_code(target: u8) -> Result<&'static str, TuiError> {
     match target {
         0 => Ok("c"),
         1 => Ok("p"),
-        _ => Err(format!("Invalid OSC52 clipboard target: {target}")),
+        _ => {
+            Err(ErrorCode::InvalidArgument
+                .raise(format!("Invalid OSC52 clipboard target: {target}")))
+        }
     }
 }
 
-pub fn validate_clipboard_text(text: &str) -> Result<(), String> {
+pub fn validate_clipboard_text(text: &str) -> Result<(), TuiError> {
     if text.len() > OSC52_MAX_BYTES {
-        return Err(format!(
+        return Err(ErrorCode::LimitExceeded.raise(format!(
             "OSC52 payload is {} bytes; limit is {OSC52_MAX_BYTES}",
             text.len()
-        ));
+        )));
     }
     // OSC52 carries the clipboard payload as base64, so raw text controls such
     // as newlines, tabs, and pasted stack traces cannot escape into the stream.
@@ -333,33 +421,33 @@ pub fn validate_clipboard_text(text: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub fn build_osc52_sequence(target: u8, text: &str) -> Result<Vec<u8>, String> {
+pub fn build_osc52_sequence(target: u8, text: &str) -> Result<Vec<u8>, TuiError> {
     validate_clipboard_text(text)?;
     let target = clipboard_target_code(target)?;
     let encoded = base64::engine::general_purpose::STANDARD.encode(text.as_bytes());
     Ok(format!("\x1b]52;{target};{encoded}\x1b\\").into_bytes())
 }
 
-pub fn validate_osc8_uri(uri: &str) -> Result<(), String> {
+pub fn validate_osc8_uri(uri: &str) -> Result<(), TuiError> {
     if uri.is_empty() {
-        return Err("OSC8 URI must not be empty".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("OSC8 URI must not be empty"));
     }
     // Link metadata is cloned into visible cells during render; keep payloads
     // bounded before storage so a valid-but-huge URI cannot amplify per cell.
     if uri.len() > OSC8_MAX_URI_BYTES {
-        return Err(format!(
+        return Err(ErrorCode::LimitExceeded.raise(format!(
             "OSC8 URI is {} bytes; limit is {OSC8_MAX_URI_BYTES}",
             uri.len()
-        ));
+        )));
     }
     if !uri.is_ascii() {
-        return Err("OSC8 URI must be ASCII or percent-encoded".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("OSC8 URI must be ASCII or percent-encoded"));
     }
     if uri
         .bytes()
         .any(|b| b < 0x20 || b == 0x7f || b == 0x1b || b == b'\\')
     {
-        return Err("OSC8 URI contains a disallowed control byte".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("OSC8 URI contains a disallowed control byte"));
     }
     let lower = uri.to_ascii_lowercase();
     let allowed = [
@@ -371,31 +459,31 @@ pub fn validate_osc8_uri(uri: &str) -> Result<(), String> {
         "kraken://",
     ];
     if !allowed.iter().any(|prefix| lower.starts_with(prefix)) {
-        return Err(format!("Unsupported OSC8 URI scheme: {uri}"));
+        return Err(ErrorCode::InvalidArgument.raise(format!("Unsupported OSC8 URI scheme: {uri}")));
     }
     Ok(())
 }
 
-pub fn validate_osc8_id(id: &str) -> Result<(), String> {
+pub fn validate_osc8_id(id: &str) -> Result<(), TuiError> {
     if id.len() > OSC8_MAX_ID_BYTES {
-        return Err(format!(
+        return Err(ErrorCode::LimitExceeded.raise(format!(
             "OSC8 id is {} bytes; limit is {OSC8_MAX_ID_BYTES}",
             id.len()
-        ));
+        )));
     }
     if !id.is_ascii() {
-        return Err("OSC8 id must be ASCII".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("OSC8 id must be ASCII"));
     }
     if id
         .bytes()
         .any(|b| !(0x21..=0x7e).contains(&b) || matches!(b, b';' | b':' | b'=' | b'\\'))
     {
-        return Err("OSC8 id contains a disallowed byte".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("OSC8 id contains a disallowed byte"));
     }
     Ok(())
 }
 
-pub fn build_osc8_open(uri: &str, id: Option<&str>) -> Result<Vec<u8>, String> {
+pub fn build_osc8_open(uri: &str, id: Option<&str>) -> Result<Vec<u8>, TuiError> {
     validate_osc8_uri(uri)?;
     if let Some(id) = id {
         validate_osc8_id(id)?;
@@ -438,6 +526,48 @@ mod tests {
         assert_eq!(caps.cell_height_px, 30);
     }
 
+    #[test]
+    fn basic_terminals_report_limited_capabilities() {
+        let dumb =
+            TerminalCapabilityState::from_env_map(&env(&[("TERM", "dumb")]), 80, 24, 0, 0, false);
+        assert_eq!(dumb.color_depth_bits, 0);
+        assert!(!dumb.supports(terminal_capability::COLOR_16));
+        assert!(!dumb.supports(terminal_capability::MOUSE));
+        assert!(!dumb.supports(terminal_capability::ALTERNATE_SCREEN));
+
+        let console =
+            TerminalCapabilityState::from_env_map(&env(&[("TERM", "linux")]), 80, 24, 0, 0, false);
+        assert!(console.supports(terminal_capability::COLOR_16));
+        assert!(!console.supports(terminal_capability::COLOR_256));
+        assert!(!console.supports(terminal_capability::TRUECOLOR));
+        assert!(!console.supports(terminal_capability::MOUSE));
+
+        let xterm = TerminalCapabilityState::from_env_map(
+            &env(&[("TERM", "xterm-256color")]),
+            80,
+            24,
+            0,
+            0,
+            false,
+        );
+        assert!(xterm.supports(terminal_capability::COLOR_256));
+        assert!(!xterm.supports(terminal_capability::TRUECOLOR));
+        assert!(xterm.supports(terminal_capability::MOUSE));
+    }
+
+    #[test]
+    fn known_truecolor_programs_do_not_need_colorterm() {
+        let caps = TerminalCapabilityState::from_env_map(
+            &env(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]),
+            80,
+            24,
+            0,
+            0,
+            false,
+        );
+        assert!(caps.supports(terminal_capability::TRUECOLOR));
+    }
+
     #[test]
     fn headless_preserves_legacy_low_bits_without_risky_protocols() {
         let caps = TerminalCapabilityState::headless(80, 24);
@@ -471,6 +601,61 @@ mod tests {
         assert!(!caps.supports(terminal_capability::SYNCHRONIZED_OUTPUT));
     }
 
+    #[test]
+    fn inline_images_are_direct_known_emulators_only() {
+        let detect = |vars: &[(&str, &str)]| {
+            TerminalCapabilityState::from_env_map(&env(vars), 80, 24, 0, 0, false)
+                .supports(terminal_capability::INLINE_IMAGES)
+        };
+        assert!(detect(&[("TERM", "xterm-kitty")]));
+        assert!(detect(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "iTerm.app")
+        ]));
+        assert!(!detect(&[("TERM", "xterm-256color")]));
+        assert!(!detect(&[
+            ("TERM", "tmux-256color"),
+            ("TMUX", "/tmp/tmux"),
+            ("TERM_PROGRAM", "WezTerm"),
+        ]));
+    }
+
+    #[test]
+    fn dec_line_attributes_need_real_xterm_or_known_program() {
+        let detect = |vars: &[(&str, &str)]| {
+            TerminalCapabilityState::from_env_map(&env(vars), 80, 24, 0, 0, false)
+                .supports(terminal_capability::DEC_LINE_ATTRIBUTES)
+        };
+        assert!(detect(&[
+            ("TERM", "xterm-256color"),
+            ("XTERM_VERSION", "XTerm(390)")
+        ]));
+        assert!(detect(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "WezTerm")
+        ]));
+        assert!(!detect(&[("TERM", "xterm-256color")]));
+        assert!(!detect(&[("TERM", "alacritty")]));
+    }
+
+    #[test]
+    fn utf8_follows_locale_and_terminal_type() {
+        let detect = |vars: &[(&str, &str)]| {
+            TerminalCapabilityState::from_env_map(&env(vars), 80, 24, 0, 0, false)
+                .supports(terminal_capability::UTF8)
+        };
+        assert!(detect(&[("TERM", "xterm-256color")]));
+        assert!(detect(&[("TERM", "xterm"), ("LANG", "en_US.UTF-8")]));
+        assert!(!detect(&[("TERM", "xterm"), ("LANG", "C")]));
+        // LC_ALL overrides LANG.
+        assert!(!detect(&[
+            ("TERM", "xterm"),
+            ("LANG", "en_US.UTF-8"),
+            ("LC_ALL", "POSIX")
+        ]));
+        assert!(!detect(&[("TERM", "vt100"), ("LANG", "en_US.UTF-8")]));
+    }
+
     #[test]
     fn tmux_requires_known_host_terminal_for_osc8() {
         let unknown = TerminalCapabilityState::from_env_map(
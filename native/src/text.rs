@@ -93,6 +93,7 @@ pub(crate) fn parse_content(
         }],
         ContentFormat::Markdown => parse_markdown(content),
         ContentFormat::Code => parse_code(ctx, content, language),
+        ContentFormat::Ansi => parse_ansi(content),
     }
 }
 
@@ -152,8 +153,8 @@ fn hash_language(language: Option<&str>) -> u64 {
 
 fn style_fingerprint(format: ContentFormat) -> u64 {
     match format {
-        // Plain and Markdown don't depend on external style configuration
-        ContentFormat::Plain | ContentFormat::Markdown => 0,
+        // Plain, Markdown and ANSI don't depend on external style configuration
+        ContentFormat::Plain | ContentFormat::Markdown | ContentFormat::Ansi => 0,
         // Code uses the syntect theme — hash the theme name as fingerprint
         ContentFormat::Code => {
             let mut hasher = std::collections::hash_map::DefaultHasher::new();
@@ -163,6 +164,145 @@ fn style_fingerprint(format: ContentFormat) -> u64 {
     }
 }
 
+/// Parse text carrying ANSI escape sequences into styled spans.
+///
+/// SGR (`ESC [ ... m`) sets bold, italic, underline, strikethrough and the
+/// 16-color, 256-color and truecolor fg/bg, and OSC 8 hyperlinks become
+/// span links. Every other CSI, OSC or two-byte escape, and any other
+/// control character but newline and tab, is dropped without output.
+fn parse_ansi(content: &str) -> Vec<StyledSpan> {
+    let mut spans: Vec<StyledSpan> = Vec::new();
+    let mut text = String::new();
+    let (mut attrs, mut fg, mut bg) = (CellAttrs::empty(), 0u32, 0u32);
+    let mut link: Option<StyledLink> = None;
+
+    // Close the running span before the style or link changes.
+    let flush =
+        |spans: &mut Vec<StyledSpan>, text: &mut String, attrs, fg, bg, link: &Option<_>| {
+            if !text.is_empty() {
+                spans.push(StyledSpan {
+                    text: std::mem::take(text),
+                    attrs,
+                    fg,
+                    bg,
+                    link: Option::clone(link),
+                });
+            }
+        };
+
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            // Content in this format skips sanitizing, so stray controls
+            // are dropped here instead.
+            if !c.is_control() || c == '\n' || c == '\t' {
+                text.push(c);
+            }
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // CSI: parameter/intermediate bytes up to a final byte.
+                let mut params = String::new();
+                let mut final_byte = None;
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        final_byte = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if final_byte == Some('m') {
+                    flush(&mut spans, &mut text, attrs, fg, bg, &link);
+                    apply_sgr(&params, &mut attrs, &mut fg, &mut bg);
+                }
+            }
+            Some(']') => {
+                // OSC: runs to BEL or ST (ESC \).
+                let mut body = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                    body.push(c);
+                }
+                // OSC 8 ; params ; uri — an empty uri closes the link.
+                if let Some(rest) = body.strip_prefix("8;") {
+                    if let Some((params, uri)) = rest.split_once(';') {
+                        flush(&mut spans, &mut text, attrs, fg, bg, &link);
+                        link = (!uri.is_empty()).then(|| StyledLink {
+                            uri: uri.to_string(),
+                            id: params
+                                .split(':')
+                                .find_map(|p| p.strip_prefix("id="))
+                                .map(str::to_string),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut spans, &mut text, attrs, fg, bg, &link);
+    spans
+}
+
+/// Apply one SGR parameter list (`"1;38;5;208"`) to the running style.
+/// Unknown codes are ignored.
+fn apply_sgr(params: &str, attrs: &mut CellAttrs, fg: &mut u32, bg: &mut u32) {
+    let indexed = |n: u32| ((crate::color::TAG_INDEXED as u32) << 24) | (n & 0xFF);
+    let codes: Vec<u32> = params
+        .split([';', ':'])
+        .map(|p| p.parse().unwrap_or(0))
+        .collect();
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => (*attrs, *fg, *bg) = (CellAttrs::empty(), 0, 0),
+            1 => attrs.insert(CellAttrs::BOLD),
+            2 => attrs.insert(CellAttrs::DIM),
+            3 => attrs.insert(CellAttrs::ITALIC),
+            4 => attrs.insert(CellAttrs::UNDERLINE),
+            9 => attrs.insert(CellAttrs::STRIKETHROUGH),
+            22 => attrs.remove(CellAttrs::BOLD | CellAttrs::DIM),
+            23 => attrs.remove(CellAttrs::ITALIC),
+            24 => attrs.remove(CellAttrs::UNDERLINE),
+            29 => attrs.remove(CellAttrs::STRIKETHROUGH),
+            n @ 30..=37 => *fg = indexed(n - 30),
+            n @ 90..=97 => *fg = indexed(n - 90 + 8),
+            n @ 40..=47 => *bg = indexed(n - 40),
+            n @ 100..=107 => *bg = indexed(n - 100 + 8),
+            39 => *fg = 0,
+            49 => *bg = 0,
+            code @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        let color = codes.get(i + 2).map(|&n| indexed(n));
+                        i += 2;
+                        color
+                    }
+                    Some(2) => {
+                        let channel = |k: usize| codes.get(i + k).copied().unwrap_or(0) as u8;
+                        let color = crate::color::rgb(channel(2), channel(3), channel(4));
+                        i += 4;
+                        Some(color)
+                    }
+                    _ => None,
+                };
+                if let Some(color) = color {
+                    if code == 38 {
+                        *fg = color;
+                    } else {
+                        *bg = color;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
 /// Parse Markdown into styled spans.
 ///
 /// Supported structures: headings H1-H4 (coloured), bold, italic, strikethrough,
@@ -445,8 +585,53 @@ fn parse_code(ctx: &TuiContext, content: &str, language: Option<&str>) -> Vec<St
 /// Measure the display cell width of a UTF-8 string.
 /// Accounts for CJK (2 cells), emoji (2 cells), combining chars (0 cells).
 pub(crate) fn measure_text(text: &str) -> u32 {
-    use unicode_width::UnicodeWidthStr;
-    UnicodeWidthStr::width(text) as u32
+    crate::text_utils::str_display_width(text) as u32
+}
+
+/// Measure `text` as plain Text renders it: rows break on '\n' and wrap by
+/// grapheme once a row would exceed `max_w` cells (never, when `None`), with
+/// `letter_spacing` blank columns after each grapheme (not counted after the
+/// last one on a row). A non-zero `fixed_advance` replaces every visible
+/// grapheme's width. Returns `(widest row, row count)`.
+pub(crate) fn measure_wrapped(
+    text: &str,
+    max_w: Option<u32>,
+    letter_spacing: u32,
+    fixed_advance: u32,
+) -> (u32, u32) {
+    use unicode_segmentation::UnicodeSegmentation;
+    let mut widest = 0u32;
+    let mut rows = 0u32;
+    for line in text.split('\n') {
+        rows += 1;
+        let mut col = 0u32;
+        for grapheme in line.graphemes(true) {
+            let w = match crate::text_utils::display_width(grapheme) as u32 {
+                0 => continue,
+                w if fixed_advance == 0 => w,
+                _ => fixed_advance,
+            };
+            if col > 0 && max_w.is_some_and(|max_w| col + w > max_w) {
+                rows += 1;
+                col = 0;
+            }
+            col += w;
+            widest = widest.max(col);
+            col += letter_spacing;
+        }
+    }
+    (widest, rows)
+}
+
+/// Height in cells of `rows` text rows with `spacing` blank rows between them.
+pub(crate) fn spaced_height(rows: u32, spacing: u32) -> u32 {
+    rows + rows.saturating_sub(1) * spacing
+}
+
+/// How many text rows fit in `height` cells when each is followed by
+/// `spacing` blank rows (the last one may be cut off).
+pub(crate) fn rows_in_height(height: u32, spacing: u32) -> u32 {
+    (height + spacing) / (1 + spacing)
 }
 
 #[cfg(test)]
@@ -466,6 +651,32 @@ mod tests {
         assert_eq!(measure_text("你好"), 4);
     }
 
+    #[test]
+    fn test_measure_wrapped() {
+        assert_eq!(measure_wrapped("hello\nhi", None, 0, 0), (5, 2));
+        assert_eq!(measure_wrapped("abcdefg", Some(3), 0, 0), (3, 3));
+        assert_eq!(measure_wrapped("你好", Some(3), 0, 0), (2, 2));
+        // Min-content: every grapheme on its own row.
+        assert_eq!(measure_wrapped("ab", Some(0), 0, 0), (1, 2));
+        assert_eq!(measure_wrapped("", None, 0, 0), (0, 1));
+        // "a b c" fits 5 columns; a 4-column row wraps after "a b".
+        assert_eq!(measure_wrapped("abc", None, 1, 0), (5, 1));
+        assert_eq!(measure_wrapped("abc", Some(4), 1, 0), (3, 2));
+        // Monospace grid: wide and narrow graphemes alike take one cell.
+        assert_eq!(measure_wrapped("你a好", None, 0, 1), (3, 1));
+        assert_eq!(measure_wrapped("ab", None, 0, 2), (4, 1));
+    }
+
+    #[test]
+    fn test_line_spacing_rows() {
+        assert_eq!(spaced_height(3, 0), 3);
+        assert_eq!(spaced_height(3, 1), 5);
+        assert_eq!(spaced_height(0, 2), 0);
+        assert_eq!(rows_in_height(5, 1), 3);
+        assert_eq!(rows_in_height(4, 1), 2);
+        assert_eq!(rows_in_height(7, 0), 7);
+    }
+
     #[test]
     fn test_parse_markdown_bold() {
         let spans = parse_markdown("**bold** text");
@@ -477,6 +688,41 @@ mod tests {
             .any(|s| s.text == " text" && !s.attrs.contains(CellAttrs::BOLD)));
     }
 
+    #[test]
+    fn test_parse_ansi_sgr_styles_and_skips_other_escapes() {
+        let ctx = TuiContext::new_for_test();
+        let content =
+            "\x1b[1;31mERR\x1b[0m ok \x1b[2K\x1b]0;title\x07\x1b[38;5;208;48;2;1;2;3mx\x1b[39;4my";
+        let spans = parse_content(&ctx, content, ContentFormat::Ansi, None);
+        let text: String = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(text, "ERR ok xy");
+
+        assert_eq!(spans[0].text, "ERR");
+        assert!(spans[0].attrs.contains(CellAttrs::BOLD));
+        assert_eq!(spans[0].fg, 0x0200_0001);
+        assert_eq!((spans[1].text.as_str(), spans[1].fg), (" ok ", 0));
+        assert!(spans[1].attrs.is_empty());
+        assert_eq!((spans[2].fg, spans[2].bg), (0x0200_00D0, 0x0101_0203));
+        assert_eq!((spans[3].fg, spans[3].bg), (0, 0x0101_0203));
+        assert!(spans[3].attrs.contains(CellAttrs::UNDERLINE));
+    }
+
+    #[test]
+    fn test_parse_ansi_osc8_hyperlinks_become_span_links() {
+        let content = "\x1b]8;id=a;https://example.com\x1b\\go\x1b]8;;\x1b\\ on";
+        let spans = parse_ansi(content);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "go");
+        assert_eq!(
+            spans[0].link,
+            Some(StyledLink {
+                uri: "https://example.com".to_string(),
+                id: Some("a".to_string()),
+            })
+        );
+        assert_eq!((spans[1].text.as_str(), &spans[1].link), (" on", &None));
+    }
+
     #[test]
     fn test_parse_plain() {
         let ctx = TuiContext::new_for_test();
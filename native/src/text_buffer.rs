@@ -7,11 +7,11 @@
 //! Contract is locked in `docs/spikes/CORE-M0-substrate-contract.md`.
 
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 use crate::context::TuiContext;
 use crate::types::{
-    CellAttrs, DirtyRange, HighlightRange, SelectionRange, StyleSpan, TerminalLinkSpan,
+    CellAttrs, DirtyRange, ErrorCode, HighlightRange, SelectionRange, StyleSpan, TerminalLinkSpan,
+    TuiError,
 };
 
 const DEFAULT_TAB_WIDTH: u8 = 4;
@@ -121,28 +121,28 @@ impl Default for TextBuffer {
 // ============================================================================
 
 /// Allocate a new buffer and return its handle.
-pub(crate) fn create(ctx: &mut TuiContext) -> Result<u32, String> {
+pub(crate) fn create(ctx: &mut TuiContext) -> Result<u32, TuiError> {
     let handle = ctx.alloc_substrate_handle()?;
     ctx.text_buffers.insert(handle, TextBuffer::new());
     Ok(handle)
 }
 
 /// Drop a buffer. Errors if any TextView/EditBuffer still references it.
-pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     if !ctx.text_buffers.contains_key(&handle) {
-        return Err(format!("Invalid TextBuffer handle: {handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}")));
     }
     let referenced_by_view = ctx.text_views.values().any(|v| v.buffer() == handle);
     if referenced_by_view {
-        return Err(format!(
-            "Cannot destroy TextBuffer {handle} while TextViews reference it"
-        ));
+        return Err(
+            format!("Cannot destroy TextBuffer {handle} while TextViews reference it").into(),
+        );
     }
     let referenced_by_edit_buffer = ctx.edit_buffers.values().any(|e| e.buffer() == handle);
     if referenced_by_edit_buffer {
-        return Err(format!(
-            "Cannot destroy TextBuffer {handle} while EditBuffers reference it"
-        ));
+        return Err(
+            format!("Cannot destroy TextBuffer {handle} while EditBuffers reference it").into(),
+        );
     }
     ctx.text_buffers.remove(&handle);
     Ok(())
@@ -160,25 +160,28 @@ pub(crate) fn replace_range(
     start: usize,
     end: usize,
     payload: &str,
-) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     if start > end {
-        return Err(format!("Invalid byte range: start={start} > end={end}"));
+        return Err(ErrorCode::InvalidArgument
+            .raise(format!("Invalid byte range: start={start} > end={end}")));
     }
     if end > buf.content.len() {
-        return Err(format!(
+        return Err(ErrorCode::IndexOutOfBounds.raise(format!(
             "Byte range end={end} out of bounds (byte_len={})",
             buf.content.len()
-        ));
+        )));
     }
     if !buf.content.is_char_boundary(start) {
-        return Err(format!("Byte offset {start} is not a UTF-8 boundary"));
+        return Err(ErrorCode::InvalidArgument
+            .raise(format!("Byte offset {start} is not a UTF-8 boundary")));
     }
     if !buf.content.is_char_boundary(end) {
-        return Err(format!("Byte offset {end} is not a UTF-8 boundary"));
+        return Err(
+            ErrorCode::InvalidArgument.raise(format!("Byte offset {end} is not a UTF-8 boundary"))
+        );
     }
     if start == end && payload.is_empty() {
         return Ok(());
@@ -188,7 +191,7 @@ pub(crate) fn replace_range(
     buf.epoch = buf
         .epoch
         .checked_add(1)
-        .ok_or_else(|| "TextBuffer epoch overflow".to_string())?;
+        .ok_or_else(|| ErrorCode::LimitExceeded.raise("TextBuffer epoch overflow"))?;
 
     let removed = end - start;
     let inserted = payload.len();
@@ -200,12 +203,14 @@ pub(crate) fn replace_range(
 }
 
 /// Convenience for streaming appends. Equivalent to `replace_range(byte_len, byte_len, payload)`.
-pub(crate) fn append(ctx: &mut TuiContext, handle: u32, payload: &str) -> Result<(), String> {
+pub(crate) fn append(ctx: &mut TuiContext, handle: u32, payload: &str) -> Result<(), TuiError> {
     let len = ctx
         .text_buffers
         .get(&handle)
         .map(|b| b.content.len())
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+        })?;
     replace_range(ctx, handle, len, len, payload)
 }
 
@@ -217,11 +222,10 @@ pub(crate) fn set_style_span(
     fg: u32,
     bg: u32,
     attrs: u8,
-) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     validate_byte_range(buf, start, end)?;
     let attrs = CellAttrs::from_bits_truncate(attrs);
     buf.style_spans.push(StyleSpan {
@@ -239,11 +243,10 @@ pub(crate) fn replace_style_spans(
     ctx: &mut TuiContext,
     handle: u32,
     spans: &[StyleSpan],
-) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     for span in spans {
         validate_byte_range(buf, span.start, span.end)?;
     }
@@ -256,11 +259,10 @@ pub(crate) fn replace_style_spans(
     Ok(())
 }
 
-pub(crate) fn clear_style_spans(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+pub(crate) fn clear_style_spans(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     if !buf.style_spans.is_empty() {
         buf.style_spans.clear();
         bump_style_fingerprint(buf);
@@ -275,15 +277,14 @@ pub(crate) fn set_link(
     end: usize,
     uri: &str,
     id: Option<&str>,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     crate::terminal_capabilities::validate_osc8_uri(uri)?;
     if let Some(id) = id {
         crate::terminal_capabilities::validate_osc8_id(id)?;
     }
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     validate_byte_range(buf, start, end)?;
     validate_link_range_non_empty(start, end)?;
     buf.terminal_link_spans.push(TerminalLinkSpan {
@@ -300,17 +301,16 @@ pub(crate) fn replace_link_spans(
     ctx: &mut TuiContext,
     handle: u32,
     spans: &[TerminalLinkSpan],
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     for span in spans {
         crate::terminal_capabilities::validate_osc8_uri(&span.uri)?;
         if let Some(id) = span.id.as_deref() {
             crate::terminal_capabilities::validate_osc8_id(id)?;
         }
     }
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     for span in spans {
         validate_byte_range(buf, span.start, span.end)?;
         validate_link_range_non_empty(span.start, span.end)?;
@@ -324,11 +324,10 @@ pub(crate) fn replace_link_spans(
     Ok(())
 }
 
-pub(crate) fn clear_links(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+pub(crate) fn clear_links(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     if !buf.terminal_link_spans.is_empty() {
         buf.terminal_link_spans.clear();
         bump_style_fingerprint(buf);
@@ -341,11 +340,10 @@ pub(crate) fn set_selection(
     handle: u32,
     start: usize,
     end: usize,
-) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     validate_byte_range(buf, start, end)?;
     let next = Some(SelectionRange { start, end });
     if buf.selection == next {
@@ -356,11 +354,10 @@ pub(crate) fn set_selection(
     Ok(())
 }
 
-pub(crate) fn clear_selection(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+pub(crate) fn clear_selection(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     if buf.selection.is_some() {
         buf.selection = None;
         bump_style_fingerprint(buf);
@@ -374,22 +371,40 @@ pub(crate) fn set_highlight(
     start: usize,
     end: usize,
     kind: u8,
-) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     validate_byte_range(buf, start, end)?;
     buf.highlights.push(HighlightRange { start, end, kind });
     bump_style_fingerprint(buf);
     Ok(())
 }
 
-pub(crate) fn clear_highlights(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+pub(crate) fn replace_highlights(
+    ctx: &mut TuiContext,
+    handle: u32,
+    highlights: &[HighlightRange],
+) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
+    for h in highlights {
+        validate_byte_range(buf, h.start, h.end)?;
+    }
+    if buf.highlights.as_slice() == highlights {
+        return Ok(());
+    }
+    buf.highlights.clear();
+    buf.highlights.extend_from_slice(highlights);
+    bump_style_fingerprint(buf);
+    Ok(())
+}
+
+pub(crate) fn clear_highlights(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     if !buf.highlights.is_empty() {
         buf.highlights.clear();
         bump_style_fingerprint(buf);
@@ -405,11 +420,10 @@ pub(crate) fn clear_highlights(ctx: &mut TuiContext, handle: u32) -> Result<(),
 /// after each pass that uses the ranges. Does NOT bump the content epoch
 /// or style fingerprint — clearing the dirty list is purely a consumer
 /// signal, not a buffer mutation.
-pub(crate) fn clear_dirty_ranges(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
-    let buf = ctx
-        .text_buffers
-        .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextBuffer handle: {handle}"))?;
+pub(crate) fn clear_dirty_ranges(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
+    let buf = ctx.text_buffers.get_mut(&handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {handle}"))
+    })?;
     buf.dirty_ranges.clear();
     Ok(())
 }
@@ -418,31 +432,35 @@ pub(crate) fn clear_dirty_ranges(ctx: &mut TuiContext, handle: u32) -> Result<()
 // Internal helpers
 // ============================================================================
 
-fn validate_byte_range(buf: &TextBuffer, start: usize, end: usize) -> Result<(), String> {
+fn validate_byte_range(buf: &TextBuffer, start: usize, end: usize) -> Result<(), TuiError> {
     if start > end {
-        return Err(format!("Invalid byte range: start={start} > end={end}"));
+        return Err(ErrorCode::InvalidArgument
+            .raise(format!("Invalid byte range: start={start} > end={end}")));
     }
     if end > buf.content.len() {
-        return Err(format!(
+        return Err(ErrorCode::IndexOutOfBounds.raise(format!(
             "Byte range end={end} out of bounds (byte_len={})",
             buf.content.len()
-        ));
+        )));
     }
     if !buf.content.is_char_boundary(start) {
-        return Err(format!("Byte offset {start} is not a UTF-8 boundary"));
+        return Err(ErrorCode::InvalidArgument
+            .raise(format!("Byte offset {start} is not a UTF-8 boundary")));
     }
     if !buf.content.is_char_boundary(end) {
-        return Err(format!("Byte offset {end} is not a UTF-8 boundary"));
+        return Err(
+            ErrorCode::InvalidArgument.raise(format!("Byte offset {end} is not a UTF-8 boundary"))
+        );
     }
     Ok(())
 }
 
-fn validate_link_range_non_empty(start: usize, end: usize) -> Result<(), String> {
+fn validate_link_range_non_empty(start: usize, end: usize) -> Result<(), TuiError> {
     // OSC8 spans are projected by overlap with rendered graphemes; a zero-width
     // range can never produce output, so accepting it would falsely signal that
     // link metadata was attached.
     if start == end {
-        return Err("Terminal link range must not be empty".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("Terminal link range must not be empty"));
     }
     Ok(())
 }
@@ -493,7 +511,7 @@ fn recompute_line_metadata(buf: &mut TextBuffer) {
 ///
 /// Tabs expand to the next `tab_width` boundary starting from column `0` of
 /// the segment. Combining marks have width `0`. Wide glyphs use `wcwidth`
-/// width through `unicode_width::UnicodeWidthStr`.
+/// width through `text_utils::display_width`.
 pub(crate) fn line_cell_width(segment: &str, tab_width: u8) -> u32 {
     let tw = tab_width.max(1) as u32;
     let mut col: u32 = 0;
@@ -505,7 +523,7 @@ pub(crate) fn line_cell_width(segment: &str, tab_width: u8) -> u32 {
             // Defensive: line segments should not contain a newline, but if
             // they ever do, treat it as zero advance.
         } else {
-            let w = UnicodeWidthStr::width(g) as u32;
+            let w = crate::text_utils::display_width(g) as u32;
             col = col.saturating_add(w);
         }
     }
@@ -772,7 +790,7 @@ mod tests {
             // "é" is two bytes in UTF-8 (0xC3 0xA9).
             append(ctx, h, "é").unwrap();
             let err = replace_range(ctx, h, 1, 1, "x").unwrap_err();
-            assert!(err.contains("UTF-8 boundary"), "{err}");
+            assert!(err.message.contains("UTF-8 boundary"), "{err}");
         });
     }
 
@@ -12,13 +12,12 @@
 #![allow(dead_code)]
 
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 use crate::context::TuiContext;
 use crate::text_view;
 use crate::types::{
-    color_tag, Buffer, Cell, CellAttrs, HighlightRange, SelectionRange, StyleSpan, TerminalLink,
-    TerminalLinkSpan,
+    color_tag, Buffer, Cell, CellAttrs, ErrorCode, HighlightRange, SelectionRange, StyleSpan,
+    TerminalLink, TerminalLinkSpan, TuiError,
 };
 
 /// Axis-aligned target rectangle in absolute screen coordinates.
@@ -107,7 +106,7 @@ pub(crate) fn render_text_view(
     target: &mut Buffer,
     rect: Rect,
     base: BaseStyle,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     text_view::ensure_projection(ctx, view_handle)?;
 
     // Borrow view + buffer immutably together. `ctx.text_views` and
@@ -118,10 +117,9 @@ pub(crate) fn render_text_view(
     // `Vec`s — that allocation paid an O(visual_lines + style_spans +
     // highlights) cost per render call without any borrow-checker
     // benefit.
-    let view = ctx
-        .text_views
-        .get(&view_handle)
-        .ok_or_else(|| format!("Invalid TextView handle: {view_handle}"))?;
+    let view = ctx.text_views.get(&view_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid TextView handle: {view_handle}"))
+    })?;
     let buffer_handle = view.buffer();
     let scroll_row = view.scroll_row();
     let scroll_col = view.scroll_col();
@@ -129,10 +127,11 @@ pub(crate) fn render_text_view(
     let cursor_byte = view.cursor().map(|c| c.byte_offset);
     let visual_lines: &[crate::text_view::VisualLine] = view.visual_lines();
 
-    let buf = ctx
-        .text_buffers
-        .get(&buffer_handle)
-        .ok_or_else(|| format!("Buffer {buffer_handle} missing for view {view_handle}"))?;
+    let buf = ctx.text_buffers.get(&buffer_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!(
+            "Buffer {buffer_handle} missing for view {view_handle}"
+        ))
+    })?;
     let content = buf.content();
     let style_spans: &[StyleSpan] = buf.style_spans();
     let link_spans: &[TerminalLinkSpan] = buf.terminal_link_spans();
@@ -172,7 +171,7 @@ pub(crate) fn render_text_view(
             let advance: u32 = if g == "\t" {
                 tab_width - (col % tab_width)
             } else {
-                UnicodeWidthStr::width(g) as u32
+                crate::text_utils::display_width(g) as u32
             };
 
             // Skip graphemes that are entirely scrolled off the left
@@ -332,6 +331,36 @@ pub(crate) fn render_text_view(
             }
         }
 
+        // A row broken at a soft hyphen shows the hyphen in its last cell.
+        if line.soft_hyphen {
+            let hyphen_col = line.cell_width.saturating_sub(1);
+            if hyphen_col >= scroll_col {
+                let screen_col = rect.x + (hyphen_col as i32 - scroll_col as i32);
+                if screen_col >= rect.x
+                    && screen_col < rect.x + rect.w
+                    && screen_col >= 0
+                    && screen_col < target.width as i32
+                {
+                    // The soft hyphen itself is zero-width, so a cursor
+                    // resting on it is drawn on the materialized '-'.
+                    let shy_start = line.byte_end - '\u{00AD}'.len_utf8();
+                    let attrs = if cursor_byte == Some(shy_start) {
+                        base.attrs | CellAttrs::UNDERLINE
+                    } else {
+                        base.attrs
+                    };
+                    let hyphen = Cell {
+                        ch: '-',
+                        fg: base.fg,
+                        bg: base.bg,
+                        attrs,
+                        link: None,
+                    };
+                    target.set(screen_col as u16, screen_y as u16, hyphen);
+                }
+            }
+        }
+
         // Cursor at end-of-line: place a marker at the trailing column if
         // it falls inside the rect. Skip when the next visual line starts
         // at the same byte offset AND that next row is within the rendered
@@ -619,6 +648,34 @@ mod tests {
         assert!(buf_row_to_string(&target, 2, 4).starts_with("ij"));
     }
 
+    #[test]
+    fn soft_hyphen_renders_dash_only_when_break_is_used() {
+        let _g = fresh_ctx();
+        let mut target = Buffer::new(10, 3);
+        with_ctx(|ctx| {
+            let buf = text_buffer::create(ctx).unwrap();
+            text_buffer::append(ctx, buf, "extra\u{AD}ordinary").unwrap();
+            let view = text_view::create(ctx, buf).unwrap();
+            text_view::set_wrap(ctx, view, 8, WrapMode::Word as u8, 4).unwrap();
+            text_view::set_viewport(ctx, view, 3, 0, 0).unwrap();
+            render_text_view(
+                ctx,
+                view,
+                &mut target,
+                Rect {
+                    x: 0,
+                    y: 0,
+                    w: 8,
+                    h: 3,
+                },
+                BaseStyle::default(),
+            )
+            .unwrap();
+        });
+        assert_eq!(buf_row_to_string(&target, 0, 6), "extra-");
+        assert_eq!(buf_row_to_string(&target, 1, 8), "ordinary");
+    }
+
     #[test]
     fn highlight_overrides_background() {
         let _g = fresh_ctx();
@@ -1,4 +1,92 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::types::{TextDirection, TextTransform};
+
+/// Whether emoji-presentation sequences (base + U+FE0F, keycaps) measure as
+/// two cells. Process-wide because width math runs in helpers that have no
+/// context access; toggled through `tui_set_wide_emoji`.
+static WIDE_EMOJI: AtomicBool = AtomicBool::new(true);
+
+pub(crate) fn set_wide_emoji(enabled: bool) {
+    WIDE_EMOJI.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn wide_emoji_enabled() -> bool {
+    WIDE_EMOJI.load(Ordering::Relaxed)
+}
+
+/// Display width in cells of one grapheme cluster. Every width calculation
+/// (layout measurement, wrap, rendering, cursor placement) goes through here
+/// so they agree on emoji sequences.
+pub(crate) fn display_width(grapheme: &str) -> usize {
+    grapheme_width(grapheme, wide_emoji_enabled())
+}
+
+/// Display width in cells of a string, summed per grapheme cluster.
+pub(crate) fn str_display_width(text: &str) -> usize {
+    let wide_emoji = wide_emoji_enabled();
+    UnicodeSegmentation::graphemes(text, true)
+        .map(|g| grapheme_width(g, wide_emoji))
+        .sum()
+}
+
+fn grapheme_width(grapheme: &str, wide_emoji: bool) -> usize {
+    // Control characters occupy no cell (unicode-width counts them as 1 in
+    // string context). Callers that expand tabs handle them before this.
+    if grapheme.starts_with(char::is_control) {
+        return 0;
+    }
+    if wide_emoji {
+        // unicode-width treats emoji-presentation sequences as wide.
+        UnicodeWidthStr::width(grapheme)
+    } else {
+        // Terminals that ignore VS16 draw the base scalar's own width.
+        grapheme
+            .chars()
+            .next()
+            .and_then(UnicodeWidthChar::width)
+            .unwrap_or(0)
+    }
+}
+
+/// Strip C0/C1 control characters (and DEL) that could act as terminal
+/// escapes when rendered. Tabs are kept, and so are line feeds unless
+/// `keep_newlines` is false (single-line widgets). Format characters such as
+/// ZWJ are not controls and survive, so emoji clusters stay intact.
+pub(crate) fn strip_control_chars(text: &str, keep_newlines: bool) -> Cow<'_, str> {
+    let is_stripped = |c: char| c.is_control() && c != '\t' && !(keep_newlines && c == '\n');
+    if !text.chars().any(is_stripped) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().filter(|&c| !is_stripped(c)).collect())
+}
+
+/// True for strong right-to-left scalars (Hebrew, Arabic, Syriac, Thaana,
+/// N'Ko and related blocks, plus their presentation forms).
+fn is_strong_rtl(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+/// Resolve whether a line lays out right-to-left. `Auto` follows the first
+/// strong character (letters; digits and punctuation are neutral) and
+/// defaults to LTR when there is none.
+pub(crate) fn is_rtl_line(line: &str, direction: TextDirection) -> bool {
+    match direction {
+        TextDirection::Ltr => false,
+        TextDirection::Rtl => true,
+        TextDirection::Auto => line
+            .chars()
+            .find(|&c| is_strong_rtl(c) || c.is_alphabetic())
+            .is_some_and(is_strong_rtl),
+    }
+}
 
 /// Split textarea content into owned logical lines.
 pub(crate) fn split_textarea_lines_owned(content: &str) -> Vec<String> {
@@ -25,6 +113,228 @@ pub(crate) fn grapheme_to_byte_idx(content: &str, grapheme_idx: usize) -> usize
     }
 }
 
+/// Smallest single splice turning `old` into `new`: keeps the common
+/// grapheme prefix and suffix and returns `(start, old_end, new_end)` so that
+/// `old[start..old_end]` is replaced by `new[start..new_end]`. Offsets are
+/// bytes on grapheme boundaries.
+pub(crate) fn grapheme_diff(old: &str, new: &str) -> (usize, usize, usize) {
+    let mut start = 0;
+    for (a, b) in old.graphemes(true).zip(new.graphemes(true)) {
+        if a != b {
+            break;
+        }
+        start += a.len();
+    }
+    let mut suffix = 0;
+    for (a, b) in old[start..]
+        .graphemes(true)
+        .rev()
+        .zip(new[start..].graphemes(true).rev())
+    {
+        if a != b {
+            break;
+        }
+        suffix += a.len();
+    }
+    (start, old.len() - suffix, new.len() - suffix)
+}
+
+/// Byte offset at which the last `max_lines` lines of `content` begin. A
+/// trailing newline terminates the final line rather than starting a new
+/// one.
+pub(crate) fn tail_lines_start(content: &str, max_lines: usize) -> usize {
+    if max_lines == 0 {
+        return content.len();
+    }
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    body.rmatch_indices('\n')
+        .nth(max_lines - 1)
+        .map_or(0, |(idx, _)| idx + 1)
+}
+
+/// Apply a render-time case transform using Unicode case mappings. Title
+/// case upper-cases the first letter of each whitespace-separated word and
+/// lower-cases the rest.
+pub(crate) fn transform_case(text: &str, transform: TextTransform) -> Cow<'_, str> {
+    match transform {
+        TextTransform::None => Cow::Borrowed(text),
+        TextTransform::Upper => Cow::Owned(text.to_uppercase()),
+        TextTransform::Lower => Cow::Owned(text.to_lowercase()),
+        TextTransform::Title => {
+            let mut out = String::with_capacity(text.len());
+            let mut word_start = true;
+            for ch in text.chars() {
+                if word_start {
+                    out.extend(ch.to_uppercase());
+                } else {
+                    out.extend(ch.to_lowercase());
+                }
+                word_start = ch.is_whitespace();
+            }
+            Cow::Owned(out)
+        }
+    }
+}
+
+/// Map a grapheme offset in `text` to the matching offset in its transformed
+/// form (case mappings such as "ß" → "SS" can change the grapheme count).
+pub(crate) fn transformed_grapheme_offset(
+    text: &str,
+    transform: TextTransform,
+    grapheme_idx: usize,
+) -> usize {
+    if transform == TextTransform::None {
+        return grapheme_idx;
+    }
+    let prefix = &text[..grapheme_to_byte_idx(text, grapheme_idx)];
+    grapheme_count(&transform_case(prefix, transform))
+}
+
+/// Case-insensitive, unanchored wildcard match: `*` matches any run of
+/// characters and `?` any single character; everything else is literal.
+/// An empty pattern matches everything.
+pub(crate) fn glob_contains(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut pat: Vec<char> = vec!['*'];
+    pat.extend(pattern.to_lowercase().chars());
+    pat.push('*');
+
+    // Iterative wildcard matching with single-star backtracking.
+    let (mut t, mut p) = (0usize, 0usize);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pat.len() && (pat[p] == '?' || pat[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pat.len() && pat[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pat[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether `c` fits one slot of an Input pattern: `#` takes a digit, `A` a
+/// letter, and any other pattern character is a literal matching itself.
+fn pattern_slot_accepts(slot: char, c: char) -> bool {
+    match slot {
+        '#' => c.is_ascii_digit(),
+        'A' => c.is_alphabetic(),
+        literal => literal == c,
+    }
+}
+
+/// Whether `text` follows `pattern` slot by slot, one grapheme per slot.
+/// With `complete`, every slot must also be filled.
+pub(crate) fn matches_input_pattern(text: &str, pattern: &str, complete: bool) -> bool {
+    let slots: Vec<char> = pattern.chars().collect();
+    let mut filled = 0;
+    for (i, grapheme) in text.graphemes(true).enumerate() {
+        let mut chars = grapheme.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return false;
+        };
+        if !slots
+            .get(i)
+            .is_some_and(|&slot| pattern_slot_accepts(slot, c))
+        {
+            return false;
+        }
+        filled = i + 1;
+    }
+    !complete || filled == slots.len()
+}
+
+/// Text to insert at grapheme `at` when `c` is typed into a patterned
+/// Input: the literal separators that come before the next slot, then `c`.
+pub(crate) fn pattern_insertion(pattern: &str, at: usize, c: char) -> String {
+    let mut out = String::new();
+    for slot in pattern.chars().skip(at) {
+        if matches!(slot, '#' | 'A') || slot == c {
+            break;
+        }
+        out.push(slot);
+    }
+    out.push(c);
+    out
+}
+
+/// Whether `text` could still become a number while being typed: an
+/// optional leading sign, digits, and at most one decimal point.
+pub(crate) fn is_partial_number(text: &str) -> bool {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let mut seen_point = false;
+    digits.chars().all(|c| match c {
+        '0'..='9' => true,
+        '.' if !seen_point => {
+            seen_point = true;
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Word-movement class of a grapheme: whitespace separates words, and each
+/// run of `breaks` characters is a token of its own.
+fn word_class(grapheme: &str, breaks: &str) -> u8 {
+    let c = grapheme.chars().next().unwrap_or(' ');
+    if c.is_whitespace() {
+        0
+    } else if breaks.contains(c) {
+        1
+    } else {
+        2
+    }
+}
+
+/// Grapheme index of the start of the word before `at` (Ctrl+Left,
+/// Ctrl+Backspace): skips whitespace, then one run of word or break
+/// characters.
+pub(crate) fn prev_word_start(text: &str, at: usize, breaks: &str) -> usize {
+    let classes: Vec<u8> = text
+        .graphemes(true)
+        .take(at)
+        .map(|g| word_class(g, breaks))
+        .collect();
+    let mut i = classes.len();
+    while i > 0 && classes[i - 1] == 0 {
+        i -= 1;
+    }
+    if i > 0 {
+        let class = classes[i - 1];
+        while i > 0 && classes[i - 1] == class {
+            i -= 1;
+        }
+    }
+    i
+}
+
+/// Grapheme index of the end of the word after `at` (Ctrl+Right).
+pub(crate) fn next_word_end(text: &str, at: usize, breaks: &str) -> usize {
+    let mut classes = text
+        .graphemes(true)
+        .skip(at)
+        .map(|g| word_class(g, breaks))
+        .peekable();
+    let mut i = at;
+    while classes.next_if_eq(&0).is_some() {
+        i += 1;
+    }
+    if let Some(class) = classes.next() {
+        i += 1;
+        while classes.next_if_eq(&class).is_some() {
+            i += 1;
+        }
+    }
+    i
+}
+
 /// Clamp a textarea cursor row/col pair against a set of logical lines.
 pub(crate) fn clamp_textarea_cursor_lines<T: AsRef<str>>(
     lines: &[T],
@@ -41,3 +351,148 @@ pub(crate) fn clamp_textarea_cursor_lines<T: AsRef<str>>(
         *col = line_len;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grapheme_diff() {
+        assert_eq!(grapheme_diff("hello", "hello"), (5, 5, 5));
+        assert_eq!(grapheme_diff("hello", "help"), (3, 5, 4));
+        assert_eq!(grapheme_diff("abc", "aXbc"), (1, 1, 2));
+        assert_eq!(grapheme_diff("aaa", "aa"), (2, 3, 2));
+        // A combining mark changes the whole grapheme, not just its tail.
+        assert_eq!(grapheme_diff("cafe", "cafe\u{301}"), (3, 4, 6));
+    }
+
+    #[test]
+    fn test_input_pattern_matching_and_insertion() {
+        let phone = "###-###-####";
+        assert!(matches_input_pattern("555-12", phone, false));
+        assert!(!matches_input_pattern("555-12", phone, true));
+        assert!(matches_input_pattern("555-123-4567", phone, true));
+        assert!(!matches_input_pattern("55a", phone, false));
+        assert!(!matches_input_pattern("555-123-45678", phone, false));
+        assert!(matches_input_pattern("AB1", "AA#", true));
+
+        assert_eq!(pattern_insertion(phone, 2, '5'), "5");
+        assert_eq!(pattern_insertion(phone, 3, '1'), "-1");
+        assert_eq!(pattern_insertion(phone, 3, '-'), "-");
+        assert_eq!(pattern_insertion("(###)", 0, '4'), "(4");
+    }
+
+    #[test]
+    fn test_is_partial_number() {
+        for ok in ["", "-", "+4", "12", "1.", "-0.25", ".5"] {
+            assert!(is_partial_number(ok), "{ok:?}");
+        }
+        for bad in ["1-", "1.2.3", "--1", "1e3", "a"] {
+            assert!(!is_partial_number(bad), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_tail_lines_start() {
+        assert_eq!(tail_lines_start("a\nb\nc", 2), 2);
+        assert_eq!(tail_lines_start("a\nb\nc\n", 2), 2);
+        assert_eq!(tail_lines_start("a\nb\nc", 5), 0);
+        assert_eq!(tail_lines_start("a\nb\nc", 0), 5);
+        assert_eq!(tail_lines_start("", 1), 0);
+    }
+
+    #[test]
+    fn test_transform_case() {
+        assert_eq!(transform_case("Save", TextTransform::None), "Save");
+        assert_eq!(transform_case("straße", TextTransform::Upper), "STRASSE");
+        assert_eq!(transform_case("ÉCOLE", TextTransform::Lower), "école");
+        assert_eq!(
+            transform_case("hello wORLD\nnew line", TextTransform::Title),
+            "Hello World\nNew Line"
+        );
+        // The cursor after "ß" moves past both "S"s of its upper-case form.
+        assert_eq!(
+            transformed_grapheme_offset("aßb", TextTransform::Upper, 2),
+            3
+        );
+        assert_eq!(
+            transformed_grapheme_offset("aßb", TextTransform::None, 2),
+            2
+        );
+    }
+
+    #[test]
+    fn test_glob_contains() {
+        assert!(glob_contains("Green Apple", "apple"));
+        assert!(glob_contains("Green Apple", ""));
+        assert!(glob_contains("Green Apple", "gr*ple"));
+        assert!(glob_contains("Green Apple", "a?ple"));
+        assert!(!glob_contains("Green Apple", "pear"));
+        assert!(!glob_contains("Green Apple", "apple?x"));
+    }
+
+    #[test]
+    fn test_emoji_presentation_sequences_are_wide() {
+        assert_eq!(grapheme_width("\u{1F44D}", true), 2); // 👍
+        assert_eq!(grapheme_width("#\u{FE0F}\u{20E3}", true), 2); // #️⃣
+        assert_eq!(grapheme_width("\u{2764}\u{FE0F}", true), 2); // ❤️
+        assert_eq!(grapheme_width("#", true), 1);
+    }
+
+    #[test]
+    fn test_wide_emoji_off_uses_base_scalar_width() {
+        // 👍 is wide on its own; keycaps and VS16 hearts fall back to narrow.
+        assert_eq!(grapheme_width("\u{1F44D}", false), 2);
+        assert_eq!(grapheme_width("#\u{FE0F}\u{20E3}", false), 1);
+        assert_eq!(grapheme_width("\u{2764}\u{FE0F}", false), 1);
+        assert_eq!(grapheme_width("e\u{0301}", false), 1);
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        assert_eq!(strip_control_chars("a\x1b[2Jb\x07", true), "a[2Jb");
+        assert_eq!(strip_control_chars("one\r\ntwo\tx", true), "one\ntwo\tx");
+        assert_eq!(strip_control_chars("one\ntwo", false), "onetwo");
+        assert_eq!(strip_control_chars("c1\u{9b}31m", true), "c131m");
+        // ZWJ family stays a single cluster.
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(strip_control_chars(family, true), family);
+        assert!(matches!(
+            strip_control_chars("plain", true),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_word_boundaries_with_break_chars() {
+        // Whitespace only: "foo.bar" is one word.
+        assert_eq!(next_word_end("foo.bar baz", 0, ""), 7);
+        assert_eq!(prev_word_start("foo.bar baz", 11, ""), 8);
+        assert_eq!(prev_word_start("foo.bar baz", 8, ""), 0);
+        // With '.' as a break character it is three tokens.
+        assert_eq!(next_word_end("foo.bar", 0, "."), 3);
+        assert_eq!(next_word_end("foo.bar", 3, "."), 4);
+        assert_eq!(next_word_end("foo.bar", 4, "."), 7);
+        assert_eq!(prev_word_start("foo.bar", 7, "."), 4);
+        assert_eq!(prev_word_start("foo.bar", 4, "."), 3);
+        assert_eq!(prev_word_start("foo.bar", 3, "."), 0);
+        assert_eq!(next_word_end("a  ", 1, "."), 3);
+        assert_eq!(prev_word_start("  ", 2, "."), 0);
+    }
+
+    #[test]
+    fn test_rtl_line_detection() {
+        assert!(is_rtl_line(
+            "\u{05E9}\u{05DC}\u{05D5}\u{05DD}",
+            TextDirection::Auto
+        )); // שלום
+        assert!(is_rtl_line(
+            "42 \u{0645}\u{0631}\u{062D}\u{0628}\u{0627} ok",
+            TextDirection::Auto
+        ));
+        assert!(!is_rtl_line("ok \u{05E9}", TextDirection::Auto));
+        assert!(!is_rtl_line("123 !", TextDirection::Auto));
+        assert!(is_rtl_line("abc", TextDirection::Rtl));
+        assert!(!is_rtl_line("\u{05E9}", TextDirection::Ltr));
+    }
+}
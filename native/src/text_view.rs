@@ -7,11 +7,10 @@
 //! invalidates projection only when the wrap width changes.
 
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 use crate::context::TuiContext;
 use crate::text_buffer::{line_cell_width, TextBuffer};
-use crate::types::WrapMode;
+use crate::types::{ErrorCode, TuiError, WrapMode};
 
 const DEFAULT_TAB_WIDTH: u8 = 4;
 
@@ -27,6 +26,9 @@ pub struct VisualLine {
     pub cell_width: u32,
     /// Logical line index (`buffer.line_starts` index) this visual row belongs to.
     pub logical_line: u32,
+    /// The row breaks at a soft hyphen (U+00AD), which renders as a visible
+    /// '-' in the last cell. `cell_width` includes that cell.
+    pub soft_hyphen: bool,
 }
 
 /// Composite invalidation key for the wrap cache.
@@ -37,6 +39,7 @@ struct CacheKey {
     wrap_mode: u8,
     tab_width: u8,
     style_fingerprint: u64,
+    wide_emoji: bool,
 }
 
 impl CacheKey {
@@ -47,6 +50,7 @@ impl CacheKey {
             wrap_mode: u8::MAX,
             tab_width: u8::MAX,
             style_fingerprint: u64::MAX,
+            wide_emoji: false,
         }
     }
 }
@@ -133,18 +137,18 @@ impl TextView {
 // Module-level mutation API (called from FFI wrappers)
 // ============================================================================
 
-pub(crate) fn create(ctx: &mut TuiContext, buffer: u32) -> Result<u32, String> {
+pub(crate) fn create(ctx: &mut TuiContext, buffer: u32) -> Result<u32, TuiError> {
     if buffer == 0 || !ctx.text_buffers.contains_key(&buffer) {
-        return Err(format!("Invalid TextBuffer handle: {buffer}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid TextBuffer handle: {buffer}")));
     }
     let handle = ctx.alloc_substrate_handle()?;
     ctx.text_views.insert(handle, TextView::new(buffer));
     Ok(handle)
 }
 
-pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn destroy(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     if ctx.text_views.remove(&handle).is_none() {
-        return Err(format!("Invalid TextView handle: {handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid TextView handle: {handle}")));
     }
     Ok(())
 }
@@ -155,8 +159,9 @@ pub(crate) fn set_wrap(
     width: u32,
     mode: u8,
     tab_width: u8,
-) -> Result<(), String> {
-    let mode = WrapMode::from_u8(mode).ok_or_else(|| format!("Invalid wrap mode: {mode}"))?;
+) -> Result<(), TuiError> {
+    let mode = WrapMode::from_u8(mode)
+        .ok_or_else(|| ErrorCode::InvalidArgument.raise(format!("Invalid wrap mode: {mode}")))?;
     let view = view_mut(ctx, handle)?;
     view.wrap_width = width;
     view.wrap_mode = mode;
@@ -170,7 +175,7 @@ pub(crate) fn set_viewport(
     rows: u32,
     scroll_row: u32,
     scroll_col: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let view = view_mut(ctx, handle)?;
     view.viewport_rows = rows;
     view.scroll_row = scroll_row;
@@ -182,32 +187,33 @@ pub(crate) fn set_cursor(
     ctx: &mut TuiContext,
     handle: u32,
     byte_offset: usize,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let buffer_handle = view(ctx, handle)?.buffer;
-    let buf = ctx
-        .text_buffers
-        .get(&buffer_handle)
-        .ok_or_else(|| format!("TextView {handle} references missing buffer {buffer_handle}"))?;
+    let buf = ctx.text_buffers.get(&buffer_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!(
+            "TextView {handle} references missing buffer {buffer_handle}"
+        ))
+    })?;
     if byte_offset > buf.byte_len() {
-        return Err(format!(
+        return Err(ErrorCode::IndexOutOfBounds.raise(format!(
             "Cursor byte offset {byte_offset} out of range (byte_len={})",
             buf.byte_len()
-        ));
+        )));
     }
     let content = buf.content();
     if !content.is_char_boundary(byte_offset) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Cursor byte offset {byte_offset} is not a UTF-8 boundary"
-        ));
+        )));
     }
     // Reject offsets that fall inside a grapheme cluster. The renderer only
     // draws the cursor at grapheme boundaries (matching by `byte_start` or at
     // end-of-line), so accepting an interior offset would silently hide the
     // cursor. Callers must align to a boundary before calling.
     if !is_grapheme_boundary(content, byte_offset) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Cursor byte offset {byte_offset} is not a grapheme boundary"
-        ));
+        )));
     }
     // Reject offsets that fall in a consumed-whitespace gap between visual
     // lines (word-wrap mode skips inter-word whitespace at row breaks, so
@@ -223,9 +229,9 @@ pub(crate) fn set_cursor(
         .map(|b| b.byte_len())
         .unwrap_or(0);
     if !is_visible_offset(&view.visual_lines, byte_offset, buf_byte_len) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Cursor byte offset {byte_offset} is not addressable (lies in a consumed-whitespace gap between visual rows)"
-        ));
+        )));
     }
     let view = view_mut(ctx, handle)?;
     view.cursor = Some(CursorPos { byte_offset });
@@ -260,21 +266,22 @@ fn is_grapheme_boundary(content: &str, byte_offset: usize) -> bool {
         .any(|(start, _)| start == byte_offset)
 }
 
-pub(crate) fn clear_cursor(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn clear_cursor(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let view = view_mut(ctx, handle)?;
     view.cursor = None;
     Ok(())
 }
 
-pub(crate) fn get_visual_line_count(ctx: &mut TuiContext, handle: u32) -> Result<u32, String> {
+pub(crate) fn get_visual_line_count(ctx: &mut TuiContext, handle: u32) -> Result<u32, TuiError> {
     ensure_projection(ctx, handle)?;
     let count = ctx.text_views.get(&handle).unwrap().visual_lines.len();
     u32::try_from(count).map_err(|_| {
         format!("visual_line_count ({count}) exceeds u32::MAX; substrate ABI cannot represent it")
+            .into()
     })
 }
 
-pub(crate) fn get_cache_epoch(ctx: &mut TuiContext, handle: u32) -> Result<u64, String> {
+pub(crate) fn get_cache_epoch(ctx: &mut TuiContext, handle: u32) -> Result<u64, TuiError> {
     // Refresh the projection first so callers polling this for cache
     // invalidation observe a fresh epoch after `set_wrap` / `set_viewport`
     // / buffer mutation, even if no other read has run yet. Without this,
@@ -292,36 +299,41 @@ pub(crate) fn byte_to_visual(
     ctx: &mut TuiContext,
     handle: u32,
     byte_offset: usize,
-) -> Result<(u32, u32), String> {
+) -> Result<(u32, u32), TuiError> {
     ensure_projection(ctx, handle)?;
     let view = ctx.text_views.get(&handle).unwrap();
     let buf = ctx
         .text_buffers
         .get(&view.buffer)
-        .ok_or_else(|| "TextView buffer missing".to_string())?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise("TextView buffer missing"))?;
     if byte_offset > buf.byte_len() {
-        return Err(format!(
+        return Err(ErrorCode::IndexOutOfBounds.raise(format!(
             "Byte offset {byte_offset} out of range (byte_len={})",
             buf.byte_len()
-        ));
+        )));
     }
     let content = buf.content();
     if !content.is_char_boundary(byte_offset) {
-        return Err(format!("Byte offset {byte_offset} is not a UTF-8 boundary"));
+        return Err(ErrorCode::InvalidArgument
+            .raise(format!("Byte offset {byte_offset} is not a UTF-8 boundary")));
     }
     // The substrate treats grapheme boundaries as the canonical addressable
     // positions. `visual_to_byte` snaps to grapheme starts, so accepting
     // interior offsets here would break the byte<->visual round-trip.
     // `set_cursor` enforces the same rule; reject here for consistency.
     if !is_grapheme_boundary(content, byte_offset) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Byte offset {byte_offset} is not a grapheme boundary"
-        ));
+        )));
     }
 
     for (row, line) in view.visual_lines.iter().enumerate() {
         let in_range = if line.byte_start == line.byte_end {
             byte_offset == line.byte_start
+        } else if line.soft_hyphen {
+            // The materialized '-' takes the end slot, so the boundary after
+            // the hyphen belongs to the next row's start.
+            byte_offset >= line.byte_start && byte_offset < line.byte_end
         } else {
             byte_offset >= line.byte_start && byte_offset <= line.byte_end
         };
@@ -357,9 +369,9 @@ pub(crate) fn byte_to_visual(
     // it — it lies in a consumed-whitespace gap (word-wrap mode). Reject
     // for consistency with set_cursor, which would also reject this
     // offset, instead of misrouting to the end-of-last-row position.
-    Err(format!(
+    Err(ErrorCode::InvalidArgument.raise(format!(
         "Byte offset {byte_offset} is not addressable (lies in a consumed-whitespace gap between visual rows)"
-    ))
+    )))
 }
 
 /// Convert (visual_row, visual_col) to a buffer byte offset, clamped to the
@@ -369,13 +381,13 @@ pub(crate) fn visual_to_byte(
     handle: u32,
     row: u32,
     col: u32,
-) -> Result<usize, String> {
+) -> Result<usize, TuiError> {
     ensure_projection(ctx, handle)?;
     let view = ctx.text_views.get(&handle).unwrap();
     let buf = ctx
         .text_buffers
         .get(&view.buffer)
-        .ok_or_else(|| "TextView buffer missing".to_string())?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise("TextView buffer missing"))?;
 
     if view.visual_lines.is_empty() {
         return Ok(0);
@@ -390,9 +402,11 @@ pub(crate) fn visual_to_byte(
         let advance = if g == "\t" {
             tw - (walked % tw)
         } else {
-            UnicodeWidthStr::width(g) as u32
+            crate::text_utils::display_width(g) as u32
         };
-        if walked + advance > col {
+        // Zero-width graphemes (U+200B, U+00AD) share a column with what
+        // follows; resolve to the first boundary at that column.
+        if walked + advance > col || (advance == 0 && walked == col) {
             return Ok(line.byte_start + g_off);
         }
         walked = walked.saturating_add(advance);
@@ -404,16 +418,16 @@ pub(crate) fn visual_to_byte(
 // Internal helpers
 // ============================================================================
 
-fn view(ctx: &TuiContext, handle: u32) -> Result<&TextView, String> {
+fn view(ctx: &TuiContext, handle: u32) -> Result<&TextView, TuiError> {
     ctx.text_views
         .get(&handle)
-        .ok_or_else(|| format!("Invalid TextView handle: {handle}"))
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid TextView handle: {handle}")))
 }
 
-fn view_mut(ctx: &mut TuiContext, handle: u32) -> Result<&mut TextView, String> {
+fn view_mut(ctx: &mut TuiContext, handle: u32) -> Result<&mut TextView, TuiError> {
     ctx.text_views
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid TextView handle: {handle}"))
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid TextView handle: {handle}")))
 }
 
 /// Ensure the cached projection matches the current buffer + view parameters.
@@ -423,7 +437,7 @@ fn view_mut(ctx: &mut TuiContext, handle: u32) -> Result<&mut TextView, String>
 /// buffer in place. This keeps repeated `byte_to_visual` / `visual_to_byte`
 /// / `render_text_view` calls O(1) on stable buffers, which is the common
 /// transcript-streaming workload.
-pub(crate) fn ensure_projection(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn ensure_projection(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let buffer_handle = view(ctx, handle)?.buffer;
     let (epoch, fingerprint) = {
         let buf = ctx.text_buffers.get(&buffer_handle).ok_or_else(|| {
@@ -439,6 +453,7 @@ pub(crate) fn ensure_projection(ctx: &mut TuiContext, handle: u32) -> Result<(),
         wrap_mode: v.wrap_mode as u8,
         tab_width: v.tab_width,
         style_fingerprint: fingerprint,
+        wide_emoji: crate::text_utils::wide_emoji_enabled(),
     };
 
     if v.cached_key == key {
@@ -534,6 +549,7 @@ fn compute_visual_lines(
                 byte_end: logical_end,
                 cell_width,
                 logical_line: idx as u32,
+                soft_hyphen: false,
             });
             continue;
         }
@@ -546,15 +562,17 @@ fn compute_visual_lines(
                 byte_end: logical_end,
                 cell_width: 0,
                 logical_line: idx as u32,
+                soft_hyphen: false,
             });
             continue;
         }
-        for (a, b, w) in breaks {
+        for run in breaks {
             out.push(VisualLine {
-                byte_start: line_start + a,
-                byte_end: line_start + b,
-                cell_width: w,
+                byte_start: line_start + run.start,
+                byte_end: line_start + run.end,
+                cell_width: run.cell_width,
                 logical_line: idx as u32,
+                soft_hyphen: run.soft_hyphen,
             });
         }
     }
@@ -562,53 +580,65 @@ fn compute_visual_lines(
     out
 }
 
-/// Wrap a single logical-line segment into `(rel_start, rel_end, cell_width)`
-/// runs at the given wrap width. Handles tabs, Unicode width, ZWJ, CJK.
+/// One wrapped row of a logical-line segment, in segment-relative bytes.
+struct WrapRun {
+    start: usize,
+    end: usize,
+    cell_width: u32,
+    soft_hyphen: bool,
+}
+
+const SOFT_HYPHEN: &str = "\u{00AD}";
+const ZERO_WIDTH_SPACE: &str = "\u{200B}";
+
+/// Wrap a single logical-line segment into runs at the given wrap width.
+/// Handles tabs, Unicode width, ZWJ, CJK.
 ///
 /// Algorithm:
 /// 1. Walk graphemes in order, tracking byte offset and cell column.
 /// 2. When the next grapheme would push us past `wrap_width`, break.
 ///    - In `Char` mode: break exactly at the current grapheme.
-///    - In `Word` mode: search backward for the latest whitespace boundary
-///      in the current run and break there; fall back to char break if none.
-fn wrap_segment(
-    segment: &str,
-    wrap_width: u32,
-    mode: WrapMode,
-    tab_width: u8,
-) -> Vec<(usize, usize, u32)> {
+///    - In `Word` mode: break at the latest opportunity in the current run
+///      (whitespace, U+200B, or a U+00AD whose '-' still fits); fall back to
+///      a char break if none.
+fn wrap_segment(segment: &str, wrap_width: u32, mode: WrapMode, tab_width: u8) -> Vec<WrapRun> {
     if segment.is_empty() {
         return Vec::new();
     }
     let tw = tab_width.max(1) as u32;
-    let mut runs: Vec<(usize, usize, u32)> = Vec::new();
+    let mut runs: Vec<WrapRun> = Vec::new();
     let mut run_start: usize = 0;
     let mut run_col: u32 = 0;
-    // Track positions of the last whitespace boundary inside the current run
-    // for word-wrap fallback. Stored as (byte_offset, col_at_boundary).
-    let mut last_ws: Option<(usize, u32)> = None;
+    // Latest break opportunity inside the current run for word wrap:
+    // (byte offset the next run starts at, break materializes a hyphen).
+    let mut last_break: Option<(usize, bool)> = None;
 
     for (g_off, g) in segment.grapheme_indices(true) {
         let mut advance = if g == "\t" {
             tw - (run_col % tw)
         } else {
-            UnicodeWidthStr::width(g) as u32
+            crate::text_utils::display_width(g) as u32
         };
 
+        // Invisible break opportunities. A soft hyphen only qualifies while
+        // the '-' it turns into still fits on the row.
+        if matches!(mode, WrapMode::Word) && g_off >= run_start {
+            if g == ZERO_WIDTH_SPACE {
+                last_break = Some((g_off + g.len(), false));
+            } else if g == SOFT_HYPHEN && run_col < wrap_width {
+                last_break = Some((g_off + g.len(), true));
+            }
+        }
+
         if advance == 0 {
             // Combining mark / zero-width — never causes a wrap by itself.
             continue;
         }
 
         if run_col + advance > wrap_width && run_col > 0 {
-            let break_at: usize = if matches!(mode, WrapMode::Word) {
-                if let Some((ws_off, _)) = last_ws {
-                    ws_off + ws_grapheme_len(segment, ws_off)
-                } else {
-                    g_off
-                }
-            } else {
-                g_off
+            let (break_at, soft_hyphen) = match (mode, last_break) {
+                (WrapMode::Word, Some(opportunity)) => opportunity,
+                _ => (g_off, false),
             };
 
             // Refuse to emit a zero-length run. This can otherwise happen
@@ -618,8 +648,13 @@ fn wrap_segment(
             // get_visual_line_count and misroute byte_to_visual mappings.
             if break_at > run_start {
                 let segment_run = &segment[run_start..break_at];
-                let cell_width = line_cell_width(segment_run, tab_width);
-                runs.push((run_start, break_at, cell_width));
+                let cell_width = line_cell_width(segment_run, tab_width) + u32::from(soft_hyphen);
+                runs.push(WrapRun {
+                    start: run_start,
+                    end: break_at,
+                    cell_width,
+                    soft_hyphen,
+                });
             }
             run_start = break_at;
             // Skip leading whitespace at the start of the new run for word mode
@@ -649,7 +684,7 @@ fn wrap_segment(
             } else {
                 0
             };
-            last_ws = None;
+            last_break = None;
             // Tab advance is column-dependent (tab_width - run_col % tab_width).
             // The cached `advance` was computed against the OLD run_col before
             // the wrap; after the reset, the current grapheme's tab width must
@@ -663,10 +698,10 @@ fn wrap_segment(
 
         // Only track whitespace boundaries inside the active run. Without
         // this guard, whitespace already consumed by the leading-skip above
-        // would still update `last_ws`, and the next wrap break-point could
+        // would still update `last_break`, and the next wrap break-point could
         // land at or before `run_start`, emitting a zero-length phantom row.
         if is_ws_grapheme(g) && g_off >= run_start {
-            last_ws = Some((g_off, run_col));
+            last_break = Some((g_off + g.len(), false));
         }
 
         // Advance only if grapheme is at/after run_start (in word mode the
@@ -679,11 +714,21 @@ fn wrap_segment(
     if run_start < segment.len() {
         let segment_run = &segment[run_start..];
         let cell_width = line_cell_width(segment_run, tab_width);
-        runs.push((run_start, segment.len(), cell_width));
+        runs.push(WrapRun {
+            start: run_start,
+            end: segment.len(),
+            cell_width,
+            soft_hyphen: false,
+        });
     } else if runs.is_empty() {
         // Whole segment fit but algorithm didn't push a run.
         let cell_width = line_cell_width(segment, tab_width);
-        runs.push((0, segment.len(), cell_width));
+        runs.push(WrapRun {
+            start: 0,
+            end: segment.len(),
+            cell_width,
+            soft_hyphen: false,
+        });
     }
 
     runs
@@ -700,14 +745,6 @@ fn is_ws_grapheme(g: &str) -> bool {
     g == " " || g == "\t"
 }
 
-fn ws_grapheme_len(segment: &str, offset: usize) -> usize {
-    segment[offset..]
-        .grapheme_indices(true)
-        .next()
-        .map(|(_, g)| g.len())
-        .unwrap_or(0)
-}
-
 // ============================================================================
 // Tests
 // ============================================================================
@@ -797,6 +834,71 @@ mod tests {
         });
     }
 
+    #[test]
+    fn word_wrap_breaks_at_soft_hyphen_and_zero_width_space() {
+        let rows = |text: &str, width: u32| -> Vec<(String, u32, bool)> {
+            wrap_segment(text, width, WrapMode::Word, 4)
+                .into_iter()
+                .map(|r| {
+                    (
+                        text[r.start..r.end].to_string(),
+                        r.cell_width,
+                        r.soft_hyphen,
+                    )
+                })
+                .collect()
+        };
+
+        // The hyphen is materialized only when the break is taken.
+        assert_eq!(
+            rows("extra\u{AD}ordinary", 8),
+            vec![
+                ("extra\u{AD}".to_string(), 6, true),
+                ("ordinary".to_string(), 8, false),
+            ]
+        );
+        assert_eq!(
+            rows("extra\u{AD}ordinary", 20),
+            vec![("extra\u{AD}ordinary".to_string(), 13, false)]
+        );
+        // A soft hyphen whose '-' would not fit is not a break opportunity.
+        assert_eq!(
+            rows("abcd\u{AD}ef", 4)[0],
+            ("abcd\u{AD}".to_string(), 4, false)
+        );
+
+        // Zero-width space breaks without rendering anything.
+        assert_eq!(
+            rows("foo\u{200B}barbaz", 6),
+            vec![
+                ("foo\u{200B}".to_string(), 3, false),
+                ("barbaz".to_string(), 6, false),
+            ]
+        );
+
+        // No opportunity: hard break fallback.
+        assert_eq!(rows("abcdefgh", 4)[0], ("abcd".to_string(), 4, false));
+    }
+
+    #[test]
+    fn soft_hyphen_break_keeps_cursor_mapping_round_trip() {
+        let _g = fresh_ctx();
+        let view = with_ctx(|ctx| {
+            let buf = text_buffer::create(ctx).unwrap();
+            text_buffer::append(ctx, buf, "extra\u{AD}ordinary").unwrap();
+            create(ctx, buf).unwrap()
+        });
+        with_ctx(|ctx| {
+            set_wrap(ctx, view, 8, WrapMode::Word as u8, 4).unwrap();
+            // Before the soft hyphen: on the hyphen cell of row 0.
+            assert_eq!(byte_to_visual(ctx, view, 5).unwrap(), (0, 5));
+            assert_eq!(visual_to_byte(ctx, view, 0, 5).unwrap(), 5);
+            // After it: start of the next row.
+            assert_eq!(byte_to_visual(ctx, view, 7).unwrap(), (1, 0));
+            assert_eq!(visual_to_byte(ctx, view, 1, 0).unwrap(), 7);
+        });
+    }
+
     #[test]
     fn word_wrap_does_not_emit_phantom_zero_length_row() {
         // Regression for wave-4 P1: with consumed inter-word whitespace
@@ -1090,7 +1192,7 @@ mod tests {
             // Interior offset 2 must be rejected.
             let err = byte_to_visual(ctx, view, 2).unwrap_err();
             assert!(
-                err.contains("grapheme boundary"),
+                err.message.contains("grapheme boundary"),
                 "expected grapheme-boundary error, got: {err}"
             );
         });
@@ -1123,14 +1225,14 @@ mod tests {
             // them to the end of the last row.
             let err = byte_to_visual(ctx, view, 5).unwrap_err();
             assert!(
-                err.contains("not addressable"),
+                err.message.contains("not addressable"),
                 "byte_to_visual must reject gap offsets, got: {err}"
             );
 
             // set_cursor rejects gap offsets symmetrically.
             let err = set_cursor(ctx, view, 5).unwrap_err();
             assert!(
-                err.contains("not addressable"),
+                err.message.contains("not addressable"),
                 "set_cursor must reject gap offsets, got: {err}"
             );
 
@@ -1226,7 +1328,7 @@ mod tests {
             // Byte 2 sits inside the e+combining grapheme cluster.
             let err = set_cursor(ctx, view, 2).unwrap_err();
             assert!(
-                err.contains("grapheme boundary"),
+                err.message.contains("grapheme boundary"),
                 "expected grapheme-boundary error, got: {err}"
             );
         });
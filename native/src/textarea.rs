@@ -1,9 +1,16 @@
 //! TextArea editor extensions (ADR-T28): selection, undo/redo, find-next.
 
+use std::borrow::Cow;
+
 use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::text_utils::{grapheme_count, grapheme_to_byte_idx, split_textarea_lines_owned};
-use crate::types::{TextAreaEdit, TextAreaState};
+use crate::context::TuiContext;
+use crate::text_utils::{
+    clamp_textarea_cursor_lines, grapheme_count, grapheme_to_byte_idx, split_textarea_lines_owned,
+};
+use crate::types::{BorderStyle, ErrorCode, NodeType, TextAreaEdit, TextAreaState, TuiError};
+use crate::{edit_buffer, text_buffer, tree};
 
 /// Normalize a selection so that start <= end (row-major order).
 pub(crate) fn normalize_selection(a: (u32, u32), b: (u32, u32)) -> ((u32, u32), (u32, u32)) {
@@ -211,6 +218,47 @@ pub(crate) fn byte_offset_to_position(content: &str, byte_offset: usize) -> (u32
     (last_row as u32, last_col as u32)
 }
 
+/// Compile a search `pattern`, a regex or (without `is_regex`) a literal.
+/// Matching literals through the regex engine too keeps case-insensitive
+/// match offsets on the original string rather than a lowercased copy.
+fn search_regex(pattern: &str, case_sensitive: bool, is_regex: bool) -> Result<Regex, TuiError> {
+    let pattern = if is_regex {
+        Cow::Borrowed(pattern)
+    } else {
+        Cow::Owned(regex::escape(pattern))
+    };
+    let pattern = if case_sensitive {
+        pattern
+    } else {
+        Cow::Owned(format!("(?i){pattern}"))
+    };
+    Regex::new(&pattern).map_err(|e| {
+        let what = if is_regex { "regex" } else { "pattern" };
+        ErrorCode::InvalidArgument.raise(format!("Invalid {what}: {e}"))
+    })
+}
+
+/// Every non-overlapping literal match of `pattern` in `content`, as
+/// end-exclusive grapheme ranges. Matches that would split a grapheme
+/// cluster are skipped; an empty pattern matches nothing.
+pub(crate) fn find_all(
+    content: &str,
+    pattern: &str,
+    case_sensitive: bool,
+) -> Result<Vec<(usize, usize)>, TuiError> {
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+    let re = search_regex(pattern, case_sensitive, false)?;
+    let mut boundaries: Vec<usize> = content.grapheme_indices(true).map(|(i, _)| i).collect();
+    boundaries.push(content.len());
+    let grapheme_at = |byte: usize| boundaries.binary_search(&byte).ok();
+    Ok(re
+        .find_iter(content)
+        .filter_map(|m| Some((grapheme_at(m.start())?, grapheme_at(m.end())?)))
+        .collect())
+}
+
 /// Find the next match of `pattern` after the current cursor position.
 /// Returns `Some((row, col))` of match start if found, `None` otherwise.
 pub(crate) fn find_next(
@@ -220,9 +268,9 @@ pub(crate) fn find_next(
     pattern: &str,
     case_sensitive: bool,
     is_regex: bool,
-) -> Result<Option<(u32, u32)>, String> {
+) -> Result<Option<(u32, u32)>, TuiError> {
     if pattern.is_empty() {
-        return Err("Search pattern is empty".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("Search pattern is empty"));
     }
 
     // Search from cursor position (inclusive per TechSpec §4.3.2 / TASK-E3)
@@ -232,25 +280,9 @@ pub(crate) fn find_next(
     }
 
     let haystack = &content[search_offset..];
-
-    let match_offset = if is_regex {
-        let effective_pattern = if case_sensitive {
-            pattern.to_string()
-        } else {
-            format!("(?i){pattern}")
-        };
-        let re = Regex::new(&effective_pattern).map_err(|e| format!("Invalid regex: {e}"))?;
-        re.find(haystack).map(|m| m.start())
-    } else if case_sensitive {
-        haystack.find(pattern)
-    } else {
-        // Use regex with escaped literal + (?i) to get correct byte offsets
-        // on the original string (avoids lowercasing byte offset mismatch).
-        let escaped = regex::escape(pattern);
-        let re =
-            Regex::new(&format!("(?i){escaped}")).map_err(|e| format!("Invalid pattern: {e}"))?;
-        re.find(haystack).map(|m| m.start())
-    };
+    let match_offset = search_regex(pattern, case_sensitive, is_regex)?
+        .find(haystack)
+        .map(|m| m.start());
 
     match match_offset {
         Some(rel_offset) => {
@@ -303,6 +335,120 @@ pub(crate) fn find_match_end(
     byte_offset_to_position(content, end_offset)
 }
 
+/// Drop the oldest lines of a node's content so at most `max_lines` remain,
+/// returning how many were removed. Highlights shift with the text. For a
+/// TextArea the cursor moves up with its line and selection and undo
+/// history are cleared. With `follow_tail` the viewport is pinned to the
+/// last line; otherwise it keeps showing the same text.
+pub(crate) fn truncate_content_lines(
+    ctx: &mut TuiContext,
+    handle: u32,
+    max_lines: u32,
+    follow_tail: bool,
+) -> Result<u32, TuiError> {
+    let viewport_rows = viewport_rows(ctx, handle);
+    let node = ctx
+        .nodes
+        .get_mut(&handle)
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let cut = crate::text_utils::tail_lines_start(&node.content, max_lines as usize);
+    if cut == 0 {
+        return Ok(0);
+    }
+    let removed = &node.content[..cut];
+    let dropped_lines = removed.matches('\n').count() as u32;
+    let dropped_graphemes = grapheme_count(removed);
+    node.content.to_mut().replace_range(..cut, "");
+    node.text_highlights = node
+        .text_highlights
+        .iter()
+        .filter(|&&(_, end)| end > dropped_graphemes)
+        .map(|&(start, end)| {
+            (
+                start.saturating_sub(dropped_graphemes),
+                end - dropped_graphemes,
+            )
+        })
+        .collect();
+    node.dirty = true;
+
+    let (text_buffer_handle, edit_buffer_handle) = if node.node_type == NodeType::TextArea {
+        if node.cursor_row < dropped_lines {
+            node.cursor_row = 0;
+            node.cursor_col = 0;
+        } else {
+            node.cursor_row -= dropped_lines;
+        }
+        let lines = split_textarea_lines_owned(&node.content);
+        clamp_textarea_cursor_lines(&lines, &mut node.cursor_row, &mut node.cursor_col);
+        node.textarea_view_row = if follow_tail {
+            (lines.len() as u32).saturating_sub(viewport_rows)
+        } else {
+            node.textarea_view_row.saturating_sub(dropped_lines)
+        };
+        if let Some(state) = node.textarea_state.as_mut() {
+            state.clear_selection();
+            state.undo_stack.clear();
+            state.redo_stack.clear();
+        }
+        (node.text_buffer_handle, node.edit_buffer_handle)
+    } else {
+        (None, None)
+    };
+    if let Some(buffer_handle) = text_buffer_handle {
+        text_buffer::replace_range(ctx, buffer_handle, 0, cut, "")?;
+        if let Some(edit_handle) = edit_buffer_handle {
+            edit_buffer::clear_history(ctx, edit_handle)?;
+        }
+    }
+    tree::mark_dirty(ctx, handle);
+    Ok(dropped_lines)
+}
+
+/// Apply a TextArea's `max_lines` cap after a content mutation.
+/// `was_at_bottom` is `view_at_bottom` sampled before the mutation, so a
+/// tail-following view stays on the newest line. No-op when the cap is 0
+/// (unlimited) or the node is not a TextArea.
+pub(crate) fn enforce_max_lines(
+    ctx: &mut TuiContext,
+    handle: u32,
+    was_at_bottom: bool,
+) -> Result<u32, TuiError> {
+    let max_lines = match ctx.nodes.get(&handle) {
+        Some(node) if node.node_type == NodeType::TextArea => node.textarea_max_lines,
+        _ => return Ok(0),
+    };
+    if max_lines == 0 {
+        return Ok(0);
+    }
+    truncate_content_lines(ctx, handle, max_lines, was_at_bottom)
+}
+
+/// Whether a TextArea's viewport currently shows its last line. False
+/// before the first layout pass.
+pub(crate) fn view_at_bottom(ctx: &TuiContext, handle: u32) -> bool {
+    let rows = viewport_rows(ctx, handle);
+    match ctx.nodes.get(&handle) {
+        Some(node) if node.node_type == NodeType::TextArea && rows > 0 => {
+            let lines = split_textarea_lines_owned(&node.content).len() as u32;
+            node.textarea_view_row + rows >= lines
+        }
+        _ => false,
+    }
+}
+
+/// Text rows visible inside a node's last computed layout, or 0 before the
+/// first layout pass.
+fn viewport_rows(ctx: &TuiContext, handle: u32) -> u32 {
+    let Ok((_, _, _, h)) = crate::layout::get_layout(ctx, handle) else {
+        return 0;
+    };
+    let bordered = crate::style::resolve_style(handle, ctx).border_style != BorderStyle::None;
+    let height = (if bordered { h - 2 } else { h }).max(0) as u32;
+    let spacing = ctx.nodes.get(&handle).map_or(0, |n| n.line_spacing);
+    crate::text::rows_in_height(height, spacing)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,6 +605,26 @@ mod tests {
         assert_eq!(result3, Some((1, 2)));
     }
 
+    #[test]
+    fn test_find_all_returns_grapheme_ranges() {
+        assert_eq!(
+            find_all("error: ERROR error", "error", true).unwrap(),
+            vec![(0, 5), (13, 18)]
+        );
+        assert_eq!(
+            find_all("error: ERROR error", "Error", false).unwrap(),
+            vec![(0, 5), (7, 12), (13, 18)]
+        );
+        // Non-overlapping, offsets in graphemes rather than bytes.
+        assert_eq!(find_all("aaaa", "aa", true).unwrap(), vec![(0, 2), (2, 4)]);
+        assert_eq!(find_all("caf\u{e9} x", "x", true).unwrap(), vec![(5, 6)]);
+        // A match may not end inside a cluster.
+        assert!(find_all("e\u{301}", "e", true).unwrap().is_empty());
+        assert!(find_all("abc", "", true).unwrap().is_empty());
+        // The pattern is literal.
+        assert_eq!(find_all("a.b axb", "a.b", true).unwrap(), vec![(0, 3)]);
+    }
+
     #[test]
     fn test_find_next_invalid_regex() {
         let content = "test";
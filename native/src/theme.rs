@@ -9,7 +9,7 @@
 use std::collections::HashMap;
 
 use crate::context::TuiContext;
-use crate::types::{BorderStyle, CellAttrs, NodeType, VisualStyle};
+use crate::types::{BorderStyle, CellAttrs, ErrorCode, NodeType, TuiError, VisualStyle};
 
 /// A theme provides visual style defaults for a subtree.
 /// Properties are only applied as defaults if the corresponding mask bit is set.
@@ -80,7 +80,7 @@ pub(crate) fn create_builtin_themes(themes: &mut HashMap<u32, Theme>) {
 }
 
 /// Create a new empty theme. Returns theme handle >= 3.
-pub(crate) fn create_theme(ctx: &mut TuiContext) -> Result<u32, String> {
+pub(crate) fn create_theme(ctx: &mut TuiContext) -> Result<u32, TuiError> {
     let handle = ctx.next_theme_handle;
     ctx.next_theme_handle += 1;
     ctx.themes.insert(handle, Theme::default());
@@ -90,14 +90,14 @@ pub(crate) fn create_theme(ctx: &mut TuiContext) -> Result<u32, String> {
 
 /// Destroy a theme. Built-in themes (1, 2) cannot be destroyed.
 /// Removes all bindings referencing this theme and marks affected subtrees dirty.
-pub(crate) fn destroy_theme(ctx: &mut TuiContext, theme_handle: u32) -> Result<(), String> {
+pub(crate) fn destroy_theme(ctx: &mut TuiContext, theme_handle: u32) -> Result<(), TuiError> {
     if theme_handle == DARK_THEME_HANDLE || theme_handle == LIGHT_THEME_HANDLE {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Cannot destroy built-in theme (handle {theme_handle})"
-        ));
+        )));
     }
     if !ctx.themes.contains_key(&theme_handle) {
-        return Err(format!("Invalid theme handle: {theme_handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}")));
     }
 
     ctx.themes.remove(&theme_handle);
@@ -125,11 +125,10 @@ pub(crate) fn set_theme_color(
     theme_handle: u32,
     prop: u8,
     color: u32,
-) -> Result<(), String> {
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
 
     let mask_bit = match prop {
         0 => {
@@ -144,7 +143,9 @@ pub(crate) fn set_theme_color(
             theme.border_color = color;
             VisualStyle::MASK_BORDER_COLOR
         }
-        _ => return Err(format!("Invalid color property: {prop}")),
+        _ => {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid color property: {prop}")))
+        }
     };
     theme.mask |= mask_bit;
 
@@ -159,17 +160,16 @@ pub(crate) fn set_theme_flag(
     theme_handle: u32,
     prop: u8,
     value: u8,
-) -> Result<(), String> {
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
 
     let flag = match prop {
         0 => CellAttrs::BOLD,
         1 => CellAttrs::ITALIC,
         2 => CellAttrs::UNDERLINE,
-        _ => return Err(format!("Invalid flag property: {prop}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid flag property: {prop}"))),
     };
 
     if value != 0 {
@@ -188,14 +188,14 @@ pub(crate) fn set_theme_border(
     ctx: &mut TuiContext,
     theme_handle: u32,
     border_style: u8,
-) -> Result<(), String> {
-    let bs = BorderStyle::from_u8(border_style)
-        .ok_or_else(|| format!("Invalid border style: {border_style}"))?;
+) -> Result<(), TuiError> {
+    let bs = BorderStyle::from_u8(border_style).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid border style: {border_style}"))
+    })?;
 
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
 
     theme.border_style = bs;
     theme.mask |= VisualStyle::MASK_BORDER_STYLE;
@@ -209,11 +209,10 @@ pub(crate) fn set_theme_opacity(
     ctx: &mut TuiContext,
     theme_handle: u32,
     opacity: f32,
-) -> Result<(), String> {
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
 
     theme.opacity = opacity.clamp(0.0, 1.0);
     theme.mask |= VisualStyle::MASK_OPACITY;
@@ -229,14 +228,14 @@ pub(crate) fn set_theme_type_color(
     node_type: u8,
     prop: u8,
     color: u32,
-) -> Result<(), String> {
-    let node_type =
-        NodeType::from_u8(node_type).ok_or_else(|| format!("Invalid node type: {node_type}"))?;
-
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let node_type = NodeType::from_u8(node_type).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid node type: {node_type}"))
+    })?;
+
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
     let style = theme.type_defaults.entry(node_type).or_default();
 
     let mask_bit = match prop {
@@ -252,7 +251,9 @@ pub(crate) fn set_theme_type_color(
             style.border_color = color;
             VisualStyle::MASK_BORDER_COLOR
         }
-        _ => return Err(format!("Invalid color property: {prop}")),
+        _ => {
+            return Err(ErrorCode::InvalidArgument.raise(format!("Invalid color property: {prop}")))
+        }
     };
     style.style_mask |= mask_bit;
 
@@ -267,21 +268,21 @@ pub(crate) fn set_theme_type_flag(
     node_type: u8,
     prop: u8,
     value: u8,
-) -> Result<(), String> {
-    let node_type =
-        NodeType::from_u8(node_type).ok_or_else(|| format!("Invalid node type: {node_type}"))?;
-
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let node_type = NodeType::from_u8(node_type).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid node type: {node_type}"))
+    })?;
+
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
     let style = theme.type_defaults.entry(node_type).or_default();
 
     let flag = match prop {
         0 => CellAttrs::BOLD,
         1 => CellAttrs::ITALIC,
         2 => CellAttrs::UNDERLINE,
-        _ => return Err(format!("Invalid flag property: {prop}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid flag property: {prop}"))),
     };
 
     if value != 0 {
@@ -301,16 +302,17 @@ pub(crate) fn set_theme_type_border(
     theme_handle: u32,
     node_type: u8,
     border_style: u8,
-) -> Result<(), String> {
-    let node_type =
-        NodeType::from_u8(node_type).ok_or_else(|| format!("Invalid node type: {node_type}"))?;
-    let bs = BorderStyle::from_u8(border_style)
-        .ok_or_else(|| format!("Invalid border style: {border_style}"))?;
-
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let node_type = NodeType::from_u8(node_type).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid node type: {node_type}"))
+    })?;
+    let bs = BorderStyle::from_u8(border_style).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid border style: {border_style}"))
+    })?;
+
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
     let style = theme.type_defaults.entry(node_type).or_default();
     style.border_style = bs;
     style.style_mask |= VisualStyle::MASK_BORDER_STYLE;
@@ -325,14 +327,14 @@ pub(crate) fn set_theme_type_opacity(
     theme_handle: u32,
     node_type: u8,
     opacity: f32,
-) -> Result<(), String> {
-    let node_type =
-        NodeType::from_u8(node_type).ok_or_else(|| format!("Invalid node type: {node_type}"))?;
-
-    let theme = ctx
-        .themes
-        .get_mut(&theme_handle)
-        .ok_or_else(|| format!("Invalid theme handle: {theme_handle}"))?;
+) -> Result<(), TuiError> {
+    let node_type = NodeType::from_u8(node_type).ok_or_else(|| {
+        ErrorCode::InvalidArgument.raise(format!("Invalid node type: {node_type}"))
+    })?;
+
+    let theme = ctx.themes.get_mut(&theme_handle).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}"))
+    })?;
     let style = theme.type_defaults.entry(node_type).or_default();
     style.opacity = opacity.clamp(0.0, 1.0);
     style.style_mask |= VisualStyle::MASK_OPACITY;
@@ -346,9 +348,9 @@ pub(crate) fn apply_theme(
     ctx: &mut TuiContext,
     theme_handle: u32,
     node_handle: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     if !ctx.themes.contains_key(&theme_handle) {
-        return Err(format!("Invalid theme handle: {theme_handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid theme handle: {theme_handle}")));
     }
     ctx.validate_handle(node_handle)?;
 
@@ -361,7 +363,7 @@ pub(crate) fn apply_theme(
 }
 
 /// Remove theme binding from a node. Marks the subtree dirty.
-pub(crate) fn clear_theme(ctx: &mut TuiContext, node_handle: u32) -> Result<(), String> {
+pub(crate) fn clear_theme(ctx: &mut TuiContext, node_handle: u32) -> Result<(), TuiError> {
     ctx.validate_handle(node_handle)?;
 
     if ctx.theme_bindings.remove(&node_handle).is_some() {
@@ -372,8 +374,10 @@ pub(crate) fn clear_theme(ctx: &mut TuiContext, node_handle: u32) -> Result<(),
 }
 
 /// Apply theme to the current root. Convenience for apply_theme(theme, root).
-pub(crate) fn switch_theme(ctx: &mut TuiContext, theme_handle: u32) -> Result<(), String> {
-    let root = ctx.root.ok_or_else(|| "No root set".to_string())?;
+pub(crate) fn switch_theme(ctx: &mut TuiContext, theme_handle: u32) -> Result<(), TuiError> {
+    let root = ctx
+        .root
+        .ok_or_else(|| ErrorCode::NotInitialized.raise("No root set"))?;
     apply_theme(ctx, theme_handle, root)
 }
 
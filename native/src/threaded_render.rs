@@ -35,7 +35,9 @@ use std::thread::{self, JoinHandle};
 use std::time::Instant;
 
 use crate::context::TuiContext;
-use crate::types::{Buffer, Cell, CellAttrs, CellUpdate, ContentFormat, NodeType};
+use crate::types::{
+    Buffer, Cell, CellAttrs, CellUpdate, ContentFormat, NodeType, Overflow, TuiError,
+};
 use crate::writer::{WriterMetrics, WriterState};
 
 // ============================================================================
@@ -65,6 +67,7 @@ pub(crate) struct NodeSnapshot {
 
     pub scroll_x: i32,
     pub scroll_y: i32,
+    pub overflow: Overflow,
     pub render_offset: (f32, f32),
 
     pub cursor_row: u32,
@@ -169,7 +172,7 @@ impl ThreadedRenderer {
     }
 
     /// Send a render snapshot to the background thread.
-    pub fn dispatch(&mut self, snapshot: RenderSnapshot) -> Result<(), String> {
+    pub fn dispatch(&mut self, snapshot: RenderSnapshot) -> Result<(), TuiError> {
         self.command_tx
             .send(RenderCommand::Frame(snapshot))
             .map_err(|_| "Render thread channel closed".to_string())?;
@@ -183,7 +186,7 @@ impl ThreadedRenderer {
     }
 
     /// Stop the render thread and join it within a bounded timeout.
-    pub fn stop(&mut self) -> Result<(), String> {
+    pub fn stop(&mut self) -> Result<(), TuiError> {
         // Send shutdown command (ignore error if channel already closed)
         let _ = self.command_tx.send(RenderCommand::Shutdown);
 
@@ -221,7 +224,7 @@ impl ThreadedRenderer {
 /// This runs on the main thread and captures a consistent view of the
 /// render-relevant state. Layout must be computed before snapshotting
 /// so the render thread has pre-resolved positions.
-pub(crate) fn create_snapshot(ctx: &mut TuiContext) -> Result<RenderSnapshot, String> {
+pub(crate) fn create_snapshot(ctx: &mut TuiContext) -> Result<RenderSnapshot, TuiError> {
     // Compute layout on the main thread so snapshot has resolved positions
     crate::layout::compute_layout(ctx)?;
 
@@ -246,8 +249,8 @@ pub(crate) fn create_snapshot(ctx: &mut TuiContext) -> Result<RenderSnapshot, St
         let snap = NodeSnapshot {
             handle,
             node_type: node.node_type,
-            visible: node.visible,
-            content: node.content.clone(),
+            visible: node.visible && !node.display_none,
+            content: node.content.to_string(),
             content_format: node.content_format,
             code_language: node.code_language.clone(),
             children: node.children.clone(),
@@ -260,6 +263,7 @@ pub(crate) fn create_snapshot(ctx: &mut TuiContext) -> Result<RenderSnapshot, St
             opacity: resolved.opacity,
             scroll_x: node.scroll_x,
             scroll_y: node.scroll_y,
+            overflow: node.overflow,
             render_offset: node.render_offset,
             cursor_row: node.cursor_row,
             cursor_col: node.cursor_col,
@@ -267,7 +271,7 @@ pub(crate) fn create_snapshot(ctx: &mut TuiContext) -> Result<RenderSnapshot, St
             wrap_mode: node.wrap_mode,
             textarea_view_row: node.textarea_view_row,
             textarea_view_col: node.textarea_view_col,
-            mask_char: node.mask_char,
+            mask_char: node.shown_mask(),
             focusable: node.focusable,
             layout_x: lx,
             layout_y: ly,
@@ -694,7 +698,7 @@ fn render_snapshot_node(
     }
 
     // Determine clip for children
-    let child_clip = if node.node_type == NodeType::ScrollBox {
+    let child_clip = if node.node_type == NodeType::ScrollBox || node.overflow == Overflow::Hidden {
         let inner_x = abs_x + if has_border { 1 } else { 0 };
         let inner_y = abs_y + if has_border { 1 } else { 0 };
         let inner_w = w - if has_border { 2 } else { 0 };
@@ -15,8 +15,8 @@ use crate::context::TuiContext;
 use crate::text_buffer;
 use crate::text_view;
 use crate::types::{
-    ContentFormat, FollowMode, NodeType, TranscriptBlock, TranscriptBlockKind, TranscriptState,
-    ViewportAnchorKind, WrapMode,
+    ContentFormat, ErrorCode, FollowMode, NodeType, TranscriptBlock, TranscriptBlockKind,
+    TranscriptState, TuiError, ViewportAnchorKind, WrapMode,
 };
 
 // ============================================================================
@@ -28,35 +28,42 @@ use crate::types::{
 fn validate_transcript_mut(
     ctx: &mut TuiContext,
     handle: u32,
-) -> Result<&mut TranscriptState, String> {
+) -> Result<&mut TranscriptState, TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    node.transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))
+    node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })
 }
 
 /// Validate that the handle refers to a Transcript node and return a shared
 /// reference to its TranscriptState.
-fn validate_transcript(ctx: &TuiContext, handle: u32) -> Result<&TranscriptState, String> {
+fn validate_transcript(ctx: &TuiContext, handle: u32) -> Result<&TranscriptState, TuiError> {
     let node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    node.transcript_state
-        .as_ref()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))
+    node.transcript_state.as_ref().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })
 }
 
-fn ensure_block_substrate(ctx: &mut TuiContext, block: &mut TranscriptBlock) -> Result<(), String> {
+fn ensure_block_substrate(
+    ctx: &mut TuiContext,
+    block: &mut TranscriptBlock,
+) -> Result<(), TuiError> {
     if block.buffer_handle != 0 && block.view_handle != 0 {
         return Ok(());
     }
@@ -79,7 +86,7 @@ fn sync_block_projection(
     ctx: &mut TuiContext,
     block: &mut TranscriptBlock,
     viewport_width: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     ensure_block_substrate(ctx, block)?;
     text_view::set_wrap(
         ctx,
@@ -344,18 +351,21 @@ fn first_visible_unread_block_id(state: &TranscriptState) -> Option<u64> {
 // ============================================================================
 
 /// Clear all blocks from a Transcript widget, resetting it to empty state.
-pub(crate) fn clear_blocks(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn clear_blocks(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let blocks = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         if node.node_type != NodeType::Transcript {
-            return Err(format!("Handle {handle} is not a Transcript widget"));
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Transcript widget")));
         }
         node.transcript_state
             .as_ref()
-            .ok_or_else(|| format!("Handle {handle} has no transcript state"))?
+            .ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+            })?
             .blocks
             .clone()
     };
@@ -367,11 +377,10 @@ pub(crate) fn clear_blocks(ctx: &mut TuiContext, handle: u32) -> Result<(), Stri
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
     state.blocks.clear();
     state.block_index.clear();
     state.anchor_kind = ViewportAnchorKind::Tail;
@@ -389,21 +398,21 @@ pub(crate) fn append_block(
     kind: TranscriptBlockKind,
     role: u8,
     content: &str,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let (viewport_width, unread) = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         if node.node_type != NodeType::Transcript {
-            return Err(format!("Handle {handle} is not a Transcript widget"));
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Transcript widget")));
         }
-        let state = node
-            .transcript_state
-            .as_ref()
-            .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+        let state = node.transcript_state.as_ref().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+        })?;
         if state.block_index.contains_key(&block_id) {
-            return Err(format!("Duplicate block_id: {block_id}"));
+            return Err(ErrorCode::InvalidArgument.raise(format!("Duplicate block_id: {block_id}")));
         }
         (state.viewport_width, !state.tail_attached)
     };
@@ -433,11 +442,10 @@ pub(crate) fn append_block(
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
     let idx = state.blocks.len();
     state.blocks.push(block);
     state.block_index.insert(block_id, idx);
@@ -458,23 +466,22 @@ pub(crate) fn patch_block(
     block_id: u64,
     patch_mode: u8,
     content: &str,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let (idx, viewport_width, mut block) = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         if node.node_type != NodeType::Transcript {
-            return Err(format!("Handle {handle} is not a Transcript widget"));
-        }
-        let state = node
-            .transcript_state
-            .as_ref()
-            .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
-        let &idx = state
-            .block_index
-            .get(&block_id)
-            .ok_or_else(|| format!("Unknown block_id: {block_id}"))?;
+            return Err(ErrorCode::WrongNodeType
+                .raise(format!("Handle {handle} is not a Transcript widget")));
+        }
+        let state = node.transcript_state.as_ref().ok_or_else(|| {
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+        })?;
+        let &idx = state.block_index.get(&block_id).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}"))
+        })?;
         (idx, state.viewport_width, state.blocks[idx].clone())
     };
 
@@ -487,11 +494,20 @@ pub(crate) fn patch_block(
             let existing_len = ctx
                 .text_buffers
                 .get(&block.buffer_handle)
-                .ok_or_else(|| format!("Invalid TextBuffer handle: {}", block.buffer_handle))?
+                .ok_or_else(|| {
+                    ErrorCode::InvalidHandle.raise(format!(
+                        "Invalid TextBuffer handle: {}",
+                        block.buffer_handle
+                    ))
+                })?
                 .byte_len();
             text_buffer::replace_range(ctx, block.buffer_handle, 0, existing_len, content)?;
         }
-        _ => return Err(format!("Invalid patch_mode: {patch_mode}")),
+        _ => {
+            return Err(
+                ErrorCode::InvalidArgument.raise(format!("Invalid patch_mode: {patch_mode}"))
+            )
+        }
     }
 
     block.version += 1;
@@ -500,27 +516,30 @@ pub(crate) fn patch_block(
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
     state.blocks[idx] = block;
     recompute_anchor_after_insert(state);
     node.dirty = true;
     Ok(())
 }
 
-pub(crate) fn finish_block(ctx: &mut TuiContext, handle: u32, block_id: u64) -> Result<(), String> {
+pub(crate) fn finish_block(
+    ctx: &mut TuiContext,
+    handle: u32,
+    block_id: u64,
+) -> Result<(), TuiError> {
     let state = validate_transcript_mut(ctx, handle)?;
     let &idx = state
         .block_index
         .get(&block_id)
-        .ok_or_else(|| format!("Unknown block_id: {block_id}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}")))?;
     state.blocks[idx].streaming = false;
     ctx.nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?
         .dirty = true;
     Ok(())
 }
@@ -530,28 +549,30 @@ pub(crate) fn set_parent(
     handle: u32,
     block_id: u64,
     parent_id: u64,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     // Validate and mutate transcript state in a scoped borrow, then mark dirty.
     {
         let state = validate_transcript_mut(ctx, handle)?;
         if !state.block_index.contains_key(&parent_id) {
-            return Err(format!("Unknown parent block_id: {parent_id}"));
+            return Err(
+                ErrorCode::InvalidHandle.raise(format!("Unknown parent block_id: {parent_id}"))
+            );
         }
         if block_id == parent_id {
-            return Err(format!("Block {block_id} cannot be its own parent"));
+            return Err(ErrorCode::InvalidArgument
+                .raise(format!("Block {block_id} cannot be its own parent")));
         }
-        let &idx = state
-            .block_index
-            .get(&block_id)
-            .ok_or_else(|| format!("Unknown block_id: {block_id}"))?;
+        let &idx = state.block_index.get(&block_id).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}"))
+        })?;
 
         // Detect cycles: walk from parent_id up the chain; if we reach block_id, it's circular
         let mut current = Some(parent_id);
         while let Some(pid) = current {
             if pid == block_id {
-                return Err(format!(
+                return Err(ErrorCode::InvalidArgument.raise(format!(
                     "Circular parent reference: {block_id} -> {parent_id} creates a cycle"
-                ));
+                )));
             }
             if let Some(&pidx) = state.block_index.get(&pid) {
                 current = state.blocks[pidx].parent_id;
@@ -576,23 +597,24 @@ pub(crate) fn set_collapsed(
     handle: u32,
     block_id: u64,
     collapsed: bool,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     let &idx = state
         .block_index
         .get(&block_id)
-        .ok_or_else(|| format!("Unknown block_id: {block_id}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}")))?;
     state.blocks[idx].collapsed = collapsed;
 
     recompute_anchor_after_collapse(state, block_id);
@@ -606,23 +628,24 @@ pub(crate) fn set_hidden(
     handle: u32,
     block_id: u64,
     hidden: bool,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     let &idx = state
         .block_index
         .get(&block_id)
-        .ok_or_else(|| format!("Unknown block_id: {block_id}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}")))?;
     let target_row = anchor_to_row(state);
     let was_tail_attached = state.tail_attached;
     state.blocks[idx].hidden = hidden;
@@ -638,29 +661,30 @@ pub(crate) fn jump_to_block(
     handle: u32,
     block_id: u64,
     align: u8,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     let Some(&idx) = state.block_index.get(&block_id) else {
-        return Err(format!("Unknown block_id: {block_id}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Unknown block_id: {block_id}")));
     };
     if is_block_hidden(state, &state.blocks[idx]) {
-        return Err(format!("Block {block_id} is hidden"));
+        return Err(format!("Block {block_id} is hidden").into());
     }
 
     match align {
         0..=2 => {}
-        _ => return Err(format!("Invalid align: {align}")),
+        _ => return Err(ErrorCode::InvalidArgument.raise(format!("Invalid align: {align}"))),
     };
 
     let block_row = block_start_row(state, block_id).unwrap_or(0);
@@ -697,18 +721,19 @@ pub(crate) fn jump_to_block(
     Ok(())
 }
 
-pub(crate) fn jump_to_unread(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn jump_to_unread(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     if let Some(unread_id) = first_visible_unread_block_id(state) {
         state.anchor_kind = ViewportAnchorKind::BlockStart {
@@ -745,18 +770,19 @@ pub(crate) fn set_follow_mode(
     ctx: &mut TuiContext,
     handle: u32,
     mode: FollowMode,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     state.follow_mode = mode;
 
@@ -768,12 +794,12 @@ pub(crate) fn set_follow_mode(
     Ok(())
 }
 
-pub(crate) fn get_follow_mode(ctx: &TuiContext, handle: u32) -> Result<u8, String> {
+pub(crate) fn get_follow_mode(ctx: &TuiContext, handle: u32) -> Result<u8, TuiError> {
     let state = validate_transcript(ctx, handle)?;
     Ok(state.follow_mode as u8)
 }
 
-pub(crate) fn mark_read(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn mark_read(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let state = validate_transcript_mut(ctx, handle)?;
 
     // Mark all unread blocks as read. This matches the flagship example
@@ -787,7 +813,7 @@ pub(crate) fn mark_read(ctx: &mut TuiContext, handle: u32) -> Result<(), String>
     Ok(())
 }
 
-pub(crate) fn get_unread_count(ctx: &TuiContext, handle: u32) -> Result<u32, String> {
+pub(crate) fn get_unread_count(ctx: &TuiContext, handle: u32) -> Result<u32, TuiError> {
     let state = validate_transcript(ctx, handle)?;
     Ok(state.unread_count)
 }
@@ -812,18 +838,19 @@ fn recompute_unread_state(state: &mut TranscriptState) {
 
 /// Handle a scroll event on a transcript. Returns true if the scroll was
 /// consumed (viewport moved), false if at boundary (allowing parent to scroll).
-pub(crate) fn handle_scroll(ctx: &mut TuiContext, handle: u32, dy: i32) -> Result<bool, String> {
+pub(crate) fn handle_scroll(ctx: &mut TuiContext, handle: u32, dy: i32) -> Result<bool, TuiError> {
     let node = ctx
         .nodes
         .get_mut(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
-    let state = node
-        .transcript_state
-        .as_mut()
-        .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+    let state = node.transcript_state.as_mut().ok_or_else(|| {
+        ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+    })?;
 
     let total = compute_total_visible_rows(state);
     let current_row = anchor_to_row(state);
@@ -892,15 +919,17 @@ fn set_anchor_to_row(state: &mut TranscriptState, target_row: u32) {
 }
 
 /// Handle keyboard navigation on transcript.
-pub(crate) fn handle_key(ctx: &mut TuiContext, handle: u32, code: u32) -> Result<bool, String> {
+pub(crate) fn handle_key(ctx: &mut TuiContext, handle: u32, code: u32) -> Result<bool, TuiError> {
     use crate::types::key;
 
     let node = ctx
         .nodes
         .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     if node.node_type != NodeType::Transcript {
-        return Err(format!("Handle {handle} is not a Transcript widget"));
+        return Err(
+            ErrorCode::WrongNodeType.raise(format!("Handle {handle} is not a Transcript widget"))
+        );
     }
     let viewport_rows = node
         .transcript_state
@@ -927,14 +956,12 @@ pub(crate) fn handle_key(ctx: &mut TuiContext, handle: u32, code: u32) -> Result
         }
         key::HOME => {
             // Jump to first block
-            let node = ctx
-                .nodes
-                .get_mut(&handle)
-                .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-            let state = node
-                .transcript_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+            let node = ctx.nodes.get_mut(&handle).ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}"))
+            })?;
+            let state = node.transcript_state.as_mut().ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+            })?;
             if let Some(first) = state.blocks.first() {
                 let first_id = first.id;
                 state.anchor_kind = ViewportAnchorKind::BlockStart {
@@ -948,14 +975,12 @@ pub(crate) fn handle_key(ctx: &mut TuiContext, handle: u32, code: u32) -> Result
         }
         key::END => {
             // Jump to tail (reattach)
-            let node = ctx
-                .nodes
-                .get_mut(&handle)
-                .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-            let state = node
-                .transcript_state
-                .as_mut()
-                .ok_or_else(|| format!("Handle {handle} has no transcript state"))?;
+            let node = ctx.nodes.get_mut(&handle).ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}"))
+            })?;
+            let state = node.transcript_state.as_mut().ok_or_else(|| {
+                ErrorCode::WrongNodeType.raise(format!("Handle {handle} has no transcript state"))
+            })?;
             state.anchor_kind = ViewportAnchorKind::Tail;
             state.tail_attached = true;
             node.dirty = true;
@@ -1383,7 +1408,7 @@ mod tests {
         append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "A").unwrap();
         let result = append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "B");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Duplicate"));
+        assert!(result.unwrap_err().message.contains("Duplicate"));
     }
 
     #[test]
@@ -1393,7 +1418,7 @@ mod tests {
 
         let result = append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "A");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not a Transcript"));
+        assert!(result.unwrap_err().message.contains("not a Transcript"));
     }
 
     #[test]
@@ -1445,7 +1470,7 @@ mod tests {
 
         let result = patch_block(&mut ctx, handle, 999, 0, "content");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Unknown block_id"));
+        assert!(result.unwrap_err().message.contains("Unknown block_id"));
     }
 
     #[test]
@@ -1912,7 +1937,10 @@ mod tests {
         append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "A").unwrap();
         let result = set_parent(&mut ctx, handle, 1, 1);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("cannot be its own parent"));
+        assert!(result
+            .unwrap_err()
+            .message
+            .contains("cannot be its own parent"));
     }
 
     #[test]
@@ -1926,7 +1954,7 @@ mod tests {
         set_parent(&mut ctx, handle, 2, 1).unwrap(); // 2 -> 1 OK
         let result = set_parent(&mut ctx, handle, 1, 2); // 1 -> 2 would create cycle
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Circular"));
+        assert!(result.unwrap_err().message.contains("Circular"));
     }
 
     #[test]
@@ -1942,7 +1970,7 @@ mod tests {
         set_parent(&mut ctx, handle, 3, 2).unwrap(); // 3 -> 2 -> 1
         let result = set_parent(&mut ctx, handle, 1, 3); // 1 -> 3 -> 2 -> 1 cycle!
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Circular"));
+        assert!(result.unwrap_err().message.contains("Circular"));
     }
 
     #[test]
@@ -2370,7 +2398,7 @@ mod tests {
 
         let result = jump_to_block(&mut ctx, handle, 2, 0);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("hidden"));
+        assert!(result.unwrap_err().message.contains("hidden"));
     }
 
     #[test]
@@ -2429,7 +2457,7 @@ mod tests {
         append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "A").unwrap();
         let result = jump_to_block(&mut ctx, handle, 1, 5);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid align"));
+        assert!(result.unwrap_err().message.contains("Invalid align"));
     }
 
     #[test]
@@ -2440,7 +2468,7 @@ mod tests {
         append_block(&mut ctx, handle, 1, TranscriptBlockKind::Message, 2, "A").unwrap();
         let result = patch_block(&mut ctx, handle, 1, 99, "content");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid patch_mode"));
+        assert!(result.unwrap_err().message.contains("Invalid patch_mode"));
     }
 
     #[test]
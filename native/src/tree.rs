@@ -12,16 +12,16 @@ use crate::edit_buffer;
 use crate::text_buffer;
 use crate::text_view;
 use crate::transcript;
-use crate::types::{NodeType, TuiNode};
+use crate::types::{ErrorCode, NodeType, TuiError, TuiNode};
 use std::collections::HashSet;
 use taffy::prelude::*;
 
-fn cleanup_node_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+fn cleanup_node_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     let (node_type, text_buffer_handle, text_view_handle, edit_buffer_handle) = {
         let node = ctx
             .nodes
             .get(&handle)
-            .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
         (
             node.node_type,
             node.text_buffer_handle,
@@ -54,7 +54,15 @@ fn cleanup_node_substrate(ctx: &mut TuiContext, handle: u32) -> Result<(), Strin
 }
 
 /// Allocate a new handle and create a node in the tree.
-pub(crate) fn create_node(ctx: &mut TuiContext, node_type: NodeType) -> Result<u32, String> {
+pub(crate) fn create_node(ctx: &mut TuiContext, node_type: NodeType) -> Result<u32, TuiError> {
+    if ctx.max_nodes > 0 && ctx.nodes.len() >= ctx.max_nodes as usize {
+        return Err(ErrorCode::LimitExceeded.raise(format!(
+            "Node limit exceeded: {} live nodes (max_nodes = {})",
+            ctx.nodes.len(),
+            ctx.max_nodes
+        )));
+    }
+
     let handle = ctx.next_handle;
     ctx.next_handle += 1;
 
@@ -85,13 +93,17 @@ pub(crate) fn create_node(ctx: &mut TuiContext, node_type: NodeType) -> Result<u
         style.display = taffy::Display::None;
     }
 
-    let taffy_result = if node_type.is_leaf() {
+    // Text and Input size to their content via `layout::measure_leaf`.
+    let taffy_result = if matches!(node_type, NodeType::Text | NodeType::Input) {
+        ctx.tree.new_leaf_with_context(style, handle)
+    } else if node_type.is_leaf() {
         ctx.tree.new_leaf(style)
     } else {
         ctx.tree.new_with_children(style, &[])
     };
 
-    let taffy_node = taffy_result.map_err(|e| format!("Taffy node creation failed: {e:?}"))?;
+    let taffy_node = taffy_result
+        .map_err(|e| ErrorCode::Layout.raise(format!("Taffy node creation failed: {e:?}")))?;
 
     let node = TuiNode::new(node_type, taffy_node);
     ctx.nodes.insert(handle, node);
@@ -101,14 +113,17 @@ pub(crate) fn create_node(ctx: &mut TuiContext, node_type: NodeType) -> Result<u
 }
 
 /// Destroy a node. Detaches from parent. Orphans children (does not cascade).
-pub(crate) fn destroy_node(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn destroy_node(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     cleanup_node_substrate(ctx, handle)?;
 
     let node = ctx
         .nodes
         .remove(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")))?;
     let detached_parent = node.parent;
+    if ctx.open_menu.is_some_and(|open| open.handle == handle) {
+        ctx.open_menu = None;
+    }
 
     // Detach from parent
     if let Some(parent_handle) = detached_parent {
@@ -152,9 +167,9 @@ pub(crate) fn destroy_node(ctx: &mut TuiContext, handle: u32) -> Result<(), Stri
 /// - clear theme binding
 /// - remove from Taffy
 /// - detach parent-child bookkeeping
-pub(crate) fn destroy_subtree(ctx: &mut TuiContext, handle: u32) -> Result<(), String> {
+pub(crate) fn destroy_subtree(ctx: &mut TuiContext, handle: u32) -> Result<(), TuiError> {
     if !ctx.nodes.contains_key(&handle) {
-        return Err(format!("Invalid handle: {handle}"));
+        return Err(ErrorCode::InvalidHandle.raise(format!("Invalid handle: {handle}")));
     }
 
     let mut post_order = Vec::new();
@@ -167,10 +182,9 @@ pub(crate) fn destroy_subtree(ctx: &mut TuiContext, handle: u32) -> Result<(), S
         ctx.theme_bindings.remove(&node_handle);
         cleanup_node_substrate(ctx, node_handle)?;
 
-        let node = ctx
-            .nodes
-            .remove(&node_handle)
-            .ok_or_else(|| format!("Invalid handle: {node_handle}"))?;
+        let node = ctx.nodes.remove(&node_handle).ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid handle: {node_handle}"))
+        })?;
 
         if let Some(parent_handle) = node.parent {
             if let Some(parent) = ctx.nodes.get_mut(&parent_handle) {
@@ -209,15 +223,14 @@ pub(crate) fn destroy_subtree(ctx: &mut TuiContext, handle: u32) -> Result<(), S
 }
 
 /// Append a child to a parent node.
-pub(crate) fn append_child(ctx: &mut TuiContext, parent: u32, child: u32) -> Result<(), String> {
-    let parent_node = ctx
-        .nodes
-        .get(&parent)
-        .ok_or_else(|| format!("Invalid parent handle: {parent}"))?;
+pub(crate) fn append_child(ctx: &mut TuiContext, parent: u32, child: u32) -> Result<(), TuiError> {
+    let parent_node = ctx.nodes.get(&parent).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid parent handle: {parent}"))
+    })?;
     let child_parent = ctx
         .nodes
         .get(&child)
-        .ok_or_else(|| format!("Invalid child handle: {child}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {child}")))?
         .parent;
 
     // Preserve existing append idempotency for already-attached children.
@@ -242,27 +255,27 @@ pub(crate) fn insert_child(
     parent: u32,
     child: u32,
     index: u32,
-) -> Result<(), String> {
+) -> Result<(), TuiError> {
     if parent == child {
-        return Err("Tree invariant violation: node cannot be parent of itself".to_string());
+        return Err(ErrorCode::InvalidArgument
+            .raise("Tree invariant violation: node cannot be parent of itself"));
     }
 
     if would_create_cycle(ctx, parent, child) {
-        return Err(format!(
+        return Err(ErrorCode::InvalidArgument.raise(format!(
             "Tree invariant violation: inserting child {child} under parent {parent} would create a cycle"
-        ));
+        )));
     }
 
     // Validate both handles exist
-    let parent_node = ctx
-        .nodes
-        .get(&parent)
-        .ok_or_else(|| format!("Invalid parent handle: {parent}"))?;
+    let parent_node = ctx.nodes.get(&parent).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid parent handle: {parent}"))
+    })?;
 
     let child_parent = ctx
         .nodes
         .get(&child)
-        .ok_or_else(|| format!("Invalid child handle: {child}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {child}")))?
         .parent;
 
     let mut parent_children = parent_node.children.clone();
@@ -275,23 +288,22 @@ pub(crate) fn insert_child(
 
     // Leaf node types cannot have children.
     if parent_node.node_type.is_leaf() {
-        return Err(format!(
+        return Err(ErrorCode::WrongNodeType.raise(format!(
             "{:?} nodes are leaf nodes and cannot have children",
             parent_node.node_type
-        ));
+        )));
     }
 
     // Enforce ScrollBox single-child constraint (TechSpec 4.10).
     if parent_node.node_type == NodeType::ScrollBox && parent_children.len() > 1 {
-        return Err(
-            "ScrollBox accepts exactly one child. Wrap multiple widgets in a Box container."
-                .to_string(),
-        );
+        return Err(ErrorCode::InvalidArgument.raise(
+            "ScrollBox accepts exactly one child. Wrap multiple widgets in a Box container.",
+        ));
     }
 
     // Enforce SplitPane two-child constraint (ADR-T35).
     if parent_node.node_type == NodeType::SplitPane && parent_children.len() > 2 {
-        return Err("SplitPane accepts exactly two children".to_string());
+        return Err(ErrorCode::InvalidArgument.raise("SplitPane accepts exactly two children"));
     }
 
     // Detach from previous parent if any.
@@ -315,7 +327,111 @@ pub(crate) fn insert_child(
 
     // Update Taffy to exactly match logical order.
     sync_taffy_children(ctx, parent)?;
+    apply_attach_constraints(ctx, parent, child)?;
+
+    mark_dirty_ancestors(ctx, parent);
+    ctx.debug_log(&format!(
+        "insert_child: parent={parent}, child={child}, index={insert_index}"
+    ));
+    Ok(())
+}
+
+/// Replace `old_child` with `new_child` in the same slot under `parent`.
+///
+/// `new_child` is detached from any previous parent first. If it was already
+/// a sibling, it moves into `old_child`'s slot rather than keeping its own.
+/// `old_child` is left detached, not destroyed. Taffy children are synced
+/// once for `parent`, so the slot never transiently disappears.
+pub(crate) fn replace_child(
+    ctx: &mut TuiContext,
+    parent: u32,
+    old_child: u32,
+    new_child: u32,
+) -> Result<(), TuiError> {
+    let parent_node = ctx.nodes.get(&parent).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid parent handle: {parent}"))
+    })?;
+    if !parent_node.children.contains(&old_child) {
+        return Err(ErrorCode::InvalidArgument.raise(format!(
+            "Node {old_child} is not a child of parent {parent}"
+        )));
+    }
+    let new_child_parent = ctx
+        .nodes
+        .get(&new_child)
+        .ok_or_else(|| {
+            ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {new_child}"))
+        })?
+        .parent;
+
+    if old_child == new_child {
+        mark_dirty_ancestors(ctx, parent);
+        return Ok(());
+    }
+
+    if parent == new_child {
+        return Err(ErrorCode::InvalidArgument
+            .raise("Tree invariant violation: node cannot be parent of itself"));
+    }
+    if would_create_cycle(ctx, parent, new_child) {
+        return Err(ErrorCode::InvalidArgument.raise(format!(
+            "Tree invariant violation: replacing with child {new_child} under parent {parent} would create a cycle"
+        )));
+    }
+
+    let mut parent_children = parent_node.children.clone();
+    let slot = parent_children
+        .iter()
+        .position(|&h| h == old_child)
+        .ok_or_else(|| {
+            ErrorCode::InvalidArgument.raise(format!(
+                "Node {old_child} is not a child of parent {parent}"
+            ))
+        })?;
+    parent_children[slot] = new_child;
+    // If `new_child` was already a sibling, drop its previous slot.
+    if let Some(previous_slot) = parent_children
+        .iter()
+        .enumerate()
+        .position(|(i, &h)| h == new_child && i != slot)
+    {
+        parent_children.remove(previous_slot);
+    }
+
+    // Detach from previous parent if any.
+    if let Some(prev_parent) = new_child_parent {
+        if prev_parent != parent {
+            if let Some(prev) = ctx.nodes.get_mut(&prev_parent) {
+                prev.children.retain(|&h| h != new_child);
+            }
+            sync_taffy_children(ctx, prev_parent)?;
+            mark_dirty_ancestors(ctx, prev_parent);
+        }
+    }
+
+    if let Some(p) = ctx.nodes.get_mut(&parent) {
+        p.children = parent_children;
+    }
+    if let Some(c) = ctx.nodes.get_mut(&old_child) {
+        c.parent = None;
+    }
+    if let Some(c) = ctx.nodes.get_mut(&new_child) {
+        c.parent = Some(parent);
+    }
+
+    sync_taffy_children(ctx, parent)?;
+    apply_attach_constraints(ctx, parent, new_child)?;
+
+    mark_dirty_ancestors(ctx, parent);
+    ctx.debug_log(&format!(
+        "replace_child: parent={parent}, old={old_child}, new={new_child}"
+    ));
+    Ok(())
+}
 
+/// Re-apply container-specific layout constraints after `child` is attached
+/// to `parent`.
+fn apply_attach_constraints(ctx: &mut TuiContext, parent: u32, child: u32) -> Result<(), TuiError> {
     // ScrollBox children must not shrink so they can overflow the viewport.
     // Without this, Taffy's default flex_shrink:1 constrains the child to
     // the ScrollBox's size, making scrolling impossible.
@@ -327,11 +443,14 @@ pub(crate) fn insert_child(
         let child_taffy = ctx
             .nodes
             .get(&child)
-            .ok_or_else(|| format!("Invalid child handle: {child}"))?
+            .ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {child}"))
+            })?
             .taffy_node;
         if let Ok(mut child_style) = ctx.tree.style(child_taffy).cloned() {
             child_style.flex_shrink = 0.0;
             let _ = ctx.tree.set_style(child_taffy, child_style);
+            ctx.layout_dirty = true;
         }
     }
 
@@ -346,30 +465,27 @@ pub(crate) fn insert_child(
         crate::splitpane::sync_children_layout(ctx, parent)?;
     }
 
-    mark_dirty_ancestors(ctx, parent);
-    ctx.debug_log(&format!(
-        "insert_child: parent={parent}, child={child}, index={insert_index}"
-    ));
     Ok(())
 }
 
 /// Remove a child from a parent node.
-pub(crate) fn remove_child(ctx: &mut TuiContext, parent: u32, child: u32) -> Result<(), String> {
+pub(crate) fn remove_child(ctx: &mut TuiContext, parent: u32, child: u32) -> Result<(), TuiError> {
     let parent_taffy = ctx
         .nodes
         .get(&parent)
-        .ok_or_else(|| format!("Invalid parent handle: {parent}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid parent handle: {parent}")))?
         .taffy_node;
 
     let child_taffy = ctx
         .nodes
         .get(&child)
-        .ok_or_else(|| format!("Invalid child handle: {child}"))?
+        .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {child}")))?
         .taffy_node;
 
     ctx.tree
         .remove_child(parent_taffy, child_taffy)
-        .map_err(|e| format!("Taffy remove_child failed: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Taffy remove_child failed: {e:?}")))?;
+    ctx.layout_dirty = true;
 
     if let Some(p) = ctx.nodes.get_mut(&parent) {
         p.children.retain(|&h| h != child);
@@ -383,28 +499,34 @@ pub(crate) fn remove_child(ctx: &mut TuiContext, parent: u32, child: u32) -> Res
     Ok(())
 }
 
+/// Iterative so that a subtree too deep to render can still be destroyed.
 fn collect_subtree_post_order(
     ctx: &TuiContext,
     handle: u32,
     out: &mut Vec<u32>,
-) -> Result<(), String> {
-    let node = ctx
-        .nodes
-        .get(&handle)
-        .ok_or_else(|| format!("Invalid handle: {handle}"))?;
-
-    for &child in &node.children {
-        collect_subtree_post_order(ctx, child, out)?;
+) -> Result<(), TuiError> {
+    // (node, index of its next child to visit)
+    let mut stack = vec![(handle, 0usize)];
+    while let Some((current, next_child)) = stack.pop() {
+        let node = ctx
+            .nodes
+            .get(&current)
+            .ok_or_else(|| ErrorCode::InvalidHandle.raise(format!("Invalid handle: {current}")))?;
+        match node.children.get(next_child) {
+            Some(&child) => {
+                stack.push((current, next_child + 1));
+                stack.push((child, 0));
+            }
+            None => out.push(current),
+        }
     }
-    out.push(handle);
     Ok(())
 }
 
-fn sync_taffy_children(ctx: &mut TuiContext, parent: u32) -> Result<(), String> {
-    let parent_node = ctx
-        .nodes
-        .get(&parent)
-        .ok_or_else(|| format!("Invalid parent handle: {parent}"))?;
+fn sync_taffy_children(ctx: &mut TuiContext, parent: u32) -> Result<(), TuiError> {
+    let parent_node = ctx.nodes.get(&parent).ok_or_else(|| {
+        ErrorCode::InvalidHandle.raise(format!("Invalid parent handle: {parent}"))
+    })?;
     let parent_taffy = parent_node.taffy_node;
     let child_handles = parent_node.children.clone();
 
@@ -413,14 +535,17 @@ fn sync_taffy_children(ctx: &mut TuiContext, parent: u32) -> Result<(), String>
         let child_taffy = ctx
             .nodes
             .get(&child_handle)
-            .ok_or_else(|| format!("Invalid child handle: {child_handle}"))?
+            .ok_or_else(|| {
+                ErrorCode::InvalidHandle.raise(format!("Invalid child handle: {child_handle}"))
+            })?
             .taffy_node;
         taffy_children.push(child_taffy);
     }
 
     ctx.tree
         .set_children(parent_taffy, &taffy_children)
-        .map_err(|e| format!("Taffy set_children failed: {e:?}"))?;
+        .map_err(|e| ErrorCode::Layout.raise(format!("Taffy set_children failed: {e:?}")))?;
+    ctx.layout_dirty = true;
     Ok(())
 }
 
@@ -454,6 +579,22 @@ pub(crate) fn is_self_or_descendant(ctx: &TuiContext, child: u32, ancestor: u32)
     false
 }
 
+/// Every live node carrying host tag `tag`, in ascending handle order.
+/// Linear in the number of nodes. Tag 0 means "untagged" and never matches.
+pub(crate) fn handles_with_tag(ctx: &TuiContext, tag: u64) -> Vec<u32> {
+    if tag == 0 {
+        return Vec::new();
+    }
+    let mut handles: Vec<u32> = ctx
+        .nodes
+        .iter()
+        .filter(|(_, node)| node.tag == tag)
+        .map(|(&handle, _)| handle)
+        .collect();
+    handles.sort_unstable();
+    handles
+}
+
 /// If `ctx.focused` is `handle` or a descendant of `handle`, clear it.
 pub(crate) fn clear_focus_if_under(ctx: &mut TuiContext, handle: u32) {
     if let Some(focused) = ctx.focused {
@@ -472,7 +613,7 @@ pub(crate) fn is_effectively_visible(ctx: &TuiContext, handle: u32) -> bool {
         let Some(node) = ctx.nodes.get(&node_handle) else {
             return false;
         };
-        if !node.visible {
+        if !node.visible || node.display_none {
             return false;
         }
         if node.node_type == NodeType::Overlay
@@ -496,6 +637,18 @@ pub(crate) fn mark_dirty(ctx: &mut TuiContext, handle: u32) {
     mark_dirty_ancestors(ctx, handle);
 }
 
+/// Mark a node dirty after a change that moves or resizes it, so the next
+/// render recomputes layout as well as repainting. Taffy re-solves only
+/// this node and its ancestors; untouched sibling subtrees keep their
+/// cached layout.
+pub(crate) fn mark_layout_dirty(ctx: &mut TuiContext, handle: u32) {
+    ctx.layout_dirty = true;
+    if let Some(node) = ctx.nodes.get(&handle) {
+        let _ = ctx.tree.mark_dirty(node.taffy_node);
+    }
+    mark_dirty(ctx, handle);
+}
+
 /// Propagate dirty flag up to ancestors.
 fn mark_dirty_ancestors(ctx: &mut TuiContext, handle: u32) {
     let mut current = handle;
@@ -509,6 +662,26 @@ fn mark_dirty_ancestors(ctx: &mut TuiContext, handle: u32) {
     }
 }
 
+/// Mark every node dirty and invalidate every cached Taffy layout, so the
+/// next layout pass recomputes the whole tree (e.g. after a change to how
+/// text is measured).
+pub(crate) fn mark_all_dirty(ctx: &mut TuiContext) {
+    ctx.layout_dirty = true;
+    for node in ctx.nodes.values_mut() {
+        node.dirty = true;
+        let _ = ctx.tree.mark_dirty(node.taffy_node);
+    }
+}
+
+/// Mark every node dirty for repaint but keep Taffy's caches: they are keyed
+/// by the space each node is offered, so after a terminal resize only nodes
+/// whose constraints actually changed are re-solved.
+pub(crate) fn mark_all_paint_dirty(ctx: &mut TuiContext) {
+    for node in ctx.nodes.values_mut() {
+        node.dirty = true;
+    }
+}
+
 /// Clear dirty flags on all nodes.
 pub(crate) fn clear_dirty_flags(ctx: &mut TuiContext) {
     for node in ctx.nodes.values_mut() {
@@ -583,6 +756,39 @@ mod tests {
         assert!(ctx.validate_handle(0).is_err());
     }
 
+    #[test]
+    fn test_validate_handle_distinguishes_destroyed_from_unallocated() {
+        let mut ctx = test_ctx();
+        let h = create_node(&mut ctx, NodeType::Box).unwrap();
+        destroy_node(&mut ctx, h).unwrap();
+
+        let destroyed = ctx.validate_handle(h).unwrap_err();
+        assert!(destroyed.message.starts_with("Invalid handle"));
+        assert!(destroyed.message.contains("destroyed"));
+
+        let unallocated = ctx.validate_handle(h + 100).unwrap_err();
+        assert!(unallocated.message.starts_with("Invalid handle"));
+        assert!(unallocated.message.contains("never allocated"));
+    }
+
+    #[test]
+    fn test_create_node_respects_max_nodes() {
+        let mut ctx = test_ctx();
+        ctx.max_nodes = 2;
+        let a = create_node(&mut ctx, NodeType::Box).unwrap();
+        create_node(&mut ctx, NodeType::Box).unwrap();
+
+        let err = create_node(&mut ctx, NodeType::Box).unwrap_err();
+        assert!(err.message.contains("Node limit exceeded"));
+        assert_eq!(ctx.nodes.len(), 2);
+
+        destroy_node(&mut ctx, a).unwrap();
+        assert!(create_node(&mut ctx, NodeType::Box).is_ok());
+
+        ctx.max_nodes = 0;
+        assert!(create_node(&mut ctx, NodeType::Box).is_ok());
+    }
+
     #[test]
     fn test_sequential_handles() {
         let mut ctx = test_ctx();
@@ -615,6 +821,7 @@ mod tests {
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
+            .message
             .contains("ScrollBox accepts exactly one child"));
         // ScrollBox still has only the first child
         assert_eq!(ctx.nodes[&sb].children, vec![child1]);
@@ -712,7 +919,7 @@ mod tests {
         ctx.root = Some(textarea);
         layout::set_dimension(&mut ctx, textarea, 0, 20.0, 1).unwrap();
         layout::set_dimension(&mut ctx, textarea, 1, 3.0, 1).unwrap();
-        ctx.nodes.get_mut(&textarea).unwrap().content = "hello".to_string();
+        ctx.nodes.get_mut(&textarea).unwrap().content = "hello".into();
 
         render::render(&mut ctx).unwrap();
 
@@ -831,6 +1038,7 @@ mod tests {
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
+            .message
             .contains("ScrollBox accepts exactly one child"));
         assert_eq!(ctx.nodes[&sb].children, vec![child1]);
     }
@@ -860,6 +1068,7 @@ mod tests {
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
+            .message
             .contains("SplitPane accepts exactly two children"));
         assert_eq!(ctx.nodes[&sp].children, vec![c1, c2]);
     }
@@ -877,6 +1086,77 @@ mod tests {
         assert!(append_child(&mut ctx, sp, c1).is_ok());
     }
 
+    #[test]
+    fn test_replace_child_preserves_slot_and_detaches_old() {
+        let mut ctx = test_ctx();
+        let parent = create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = create_node(&mut ctx, NodeType::Text).unwrap();
+        let b = create_node(&mut ctx, NodeType::Text).unwrap();
+        let c = create_node(&mut ctx, NodeType::Text).unwrap();
+        let x = create_node(&mut ctx, NodeType::Text).unwrap();
+
+        append_child(&mut ctx, parent, a).unwrap();
+        append_child(&mut ctx, parent, b).unwrap();
+        append_child(&mut ctx, parent, c).unwrap();
+        replace_child(&mut ctx, parent, b, x).unwrap();
+
+        assert_eq!(ctx.nodes[&parent].children, vec![a, x, c]);
+        assert_eq!(taffy_children_handles(&ctx, parent), vec![a, x, c]);
+        assert_eq!(ctx.nodes[&x].parent, Some(parent));
+        assert!(ctx.nodes.contains_key(&b));
+        assert_eq!(ctx.nodes[&b].parent, None);
+    }
+
+    #[test]
+    fn test_replace_child_with_sibling_takes_old_slot() {
+        let mut ctx = test_ctx();
+        let parent = create_node(&mut ctx, NodeType::Box).unwrap();
+        let a = create_node(&mut ctx, NodeType::Text).unwrap();
+        let b = create_node(&mut ctx, NodeType::Text).unwrap();
+        let c = create_node(&mut ctx, NodeType::Text).unwrap();
+        let d = create_node(&mut ctx, NodeType::Text).unwrap();
+
+        for child in [a, b, c, d] {
+            append_child(&mut ctx, parent, child).unwrap();
+        }
+        replace_child(&mut ctx, parent, c, a).unwrap();
+
+        assert_eq!(ctx.nodes[&parent].children, vec![b, a, d]);
+        assert_eq!(taffy_children_handles(&ctx, parent), vec![b, a, d]);
+        assert_eq!(ctx.nodes[&c].parent, None);
+    }
+
+    #[test]
+    fn test_replace_child_reparents_from_other_parent() {
+        let mut ctx = test_ctx();
+        let old_parent = create_node(&mut ctx, NodeType::Box).unwrap();
+        let parent = create_node(&mut ctx, NodeType::ScrollBox).unwrap();
+        let old = create_node(&mut ctx, NodeType::Box).unwrap();
+        let moved = create_node(&mut ctx, NodeType::Box).unwrap();
+
+        append_child(&mut ctx, parent, old).unwrap();
+        append_child(&mut ctx, old_parent, moved).unwrap();
+        replace_child(&mut ctx, parent, old, moved).unwrap();
+
+        assert!(ctx.nodes[&old_parent].children.is_empty());
+        assert_eq!(taffy_children_handles(&ctx, old_parent), Vec::<u32>::new());
+        assert_eq!(ctx.nodes[&parent].children, vec![moved]);
+        let moved_taffy = ctx.nodes[&moved].taffy_node;
+        assert_eq!(ctx.tree.style(moved_taffy).unwrap().flex_shrink, 0.0);
+    }
+
+    #[test]
+    fn test_replace_child_rejects_non_child() {
+        let mut ctx = test_ctx();
+        let parent = create_node(&mut ctx, NodeType::Box).unwrap();
+        let stranger = create_node(&mut ctx, NodeType::Text).unwrap();
+        let x = create_node(&mut ctx, NodeType::Text).unwrap();
+
+        let err = replace_child(&mut ctx, parent, stranger, x).unwrap_err();
+        assert!(err.message.contains("is not a child of parent"));
+        assert_eq!(ctx.nodes[&x].parent, None);
+    }
+
     #[test]
     fn test_insert_child_rejects_cycles() {
         let mut ctx = test_ctx();
@@ -4,6 +4,7 @@
 
 use serde::Serialize;
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 #[allow(unused)]
 use bitflags::bitflags;
@@ -27,6 +28,9 @@ pub enum NodeType {
     Overlay = 9,
     Transcript = 10,
     SplitPane = 11,
+    ComboBox = 12,
+    /// Reserves a box for a host-provided inline image (sixel/kitty/iTerm).
+    Image = 13,
 }
 
 impl NodeType {
@@ -44,6 +48,8 @@ impl NodeType {
             9 => Some(Self::Overlay),
             10 => Some(Self::Transcript),
             11 => Some(Self::SplitPane),
+            12 => Some(Self::ComboBox),
+            13 => Some(Self::Image),
             _ => None,
         }
     }
@@ -60,8 +66,20 @@ impl NodeType {
                 | Self::List
                 | Self::Tabs
                 | Self::Transcript
+                | Self::ComboBox
+                | Self::Image
         )
     }
+
+    /// Whether this node type carries a selectable option list.
+    pub fn has_options(self) -> bool {
+        matches!(self, Self::Select | Self::ComboBox)
+    }
+
+    /// Whether this node type edits a single line of text with a cursor.
+    pub fn is_line_editor(self) -> bool {
+        matches!(self, Self::Input | Self::ComboBox)
+    }
 }
 
 // ============================================================================
@@ -73,13 +91,56 @@ impl NodeType {
 //   0x01 = RGB truecolor (bits 23-0 = 0xRRGGBB)
 //   0x02 = Indexed (bits 7-0 = palette index 0-255)
 
-#[allow(dead_code)]
+/// Terminal-default sentinel. Maps to SGR 39 (fg) / 49 (bg) in the writer
+/// and is never treated as black: a themed terminal keeps its own colors.
+/// Use `0x01000000` for true RGB black.
 pub const COLOR_DEFAULT: u32 = 0x00000000;
 
 pub fn color_tag(color: u32) -> u8 {
     ((color >> 24) & 0xFF) as u8
 }
 
+/// True when the color carries the default tag (inherit the terminal's color).
+pub fn is_default_color(color: u32) -> bool {
+    color_tag(color) == 0x00
+}
+
+/// Output color depth. The buffers always hold the full-fidelity color; the
+/// writer quantizes RGB (and, in 16-color mode, high indexed) colors on emit.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    #[default]
+    TrueColor = 0,
+    Color256 = 1,
+    Color16 = 2,
+}
+
+impl ColorMode {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::TrueColor),
+            1 => Some(Self::Color256),
+            2 => Some(Self::Color16),
+            _ => None,
+        }
+    }
+
+    /// Deepest mode the detected capabilities allow.
+    pub fn from_capabilities(
+        state: &crate::terminal_capabilities::TerminalCapabilityState,
+    ) -> Self {
+        use crate::terminal_capabilities::terminal_capability;
+        if state.supports(terminal_capability::TRUECOLOR) {
+            Self::TrueColor
+        } else if state.supports(terminal_capability::COLOR_256) {
+            Self::Color256
+        } else {
+            Self::Color16
+        }
+    }
+}
+
 pub fn color_to_crossterm(color: u32) -> Option<crossterm::style::Color> {
     match color_tag(color) {
         0x00 => None, // Default — no override
@@ -147,6 +208,7 @@ bitflags! {
         const ITALIC        = 0b0000_0010;
         const UNDERLINE     = 0b0000_0100;
         const STRIKETHROUGH = 0b0000_1000;
+        const DIM           = 0b0001_0000;
     }
 }
 
@@ -160,6 +222,8 @@ pub enum ContentFormat {
     Plain = 0,
     Markdown = 1,
     Code = 2,
+    /// Text with embedded ANSI SGR escapes (e.g. piped program output).
+    Ansi = 3,
 }
 
 impl ContentFormat {
@@ -168,6 +232,190 @@ impl ContentFormat {
             0 => Some(Self::Plain),
             1 => Some(Self::Markdown),
             2 => Some(Self::Code),
+            3 => Some(Self::Ansi),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Text Direction
+// ============================================================================
+
+/// Base direction for plain text lines. `Auto` picks per line from the first
+/// strong character. Only base-direction reversal is done, not full BiDi
+/// reordering.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    Auto = 0,
+    Ltr = 1,
+    Rtl = 2,
+}
+
+impl TextDirection {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Auto),
+            1 => Some(Self::Ltr),
+            2 => Some(Self::Rtl),
+            _ => None,
+        }
+    }
+}
+
+/// Render-time case transform for plain Text and Input. The stored content
+/// is never changed.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextTransform {
+    #[default]
+    None = 0,
+    Upper = 1,
+    Lower = 2,
+    Title = 3,
+}
+
+impl TextTransform {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::None),
+            1 => Some(Self::Upper),
+            2 => Some(Self::Lower),
+            3 => Some(Self::Title),
+            _ => None,
+        }
+    }
+}
+
+/// DEC line attribute for the rows a plain Text occupies (DECDWL/DECDHL).
+/// Scaled text is laid out two columns per grapheme; terminals without
+/// line attributes show it at normal size.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineScale {
+    #[default]
+    Normal = 0,
+    DoubleWidth = 1,
+    /// Upper half of double-height text; pair with a `DoubleHeightBottom`
+    /// row showing the same text.
+    DoubleHeightTop = 2,
+    DoubleHeightBottom = 3,
+}
+
+impl LineScale {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Normal),
+            1 => Some(Self::DoubleWidth),
+            2 => Some(Self::DoubleHeightTop),
+            3 => Some(Self::DoubleHeightBottom),
+            _ => None,
+        }
+    }
+}
+
+/// How a Select option wider than the widget is shown. `Marquee` scrolls
+/// the selected option one grapheme per `OPTION_MARQUEE_STEP_MS`; other rows
+/// are clipped.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptionOverflow {
+    #[default]
+    Clip = 0,
+    Ellipsis = 1,
+    Marquee = 2,
+}
+
+impl OptionOverflow {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Clip),
+            1 => Some(Self::Ellipsis),
+            2 => Some(Self::Marquee),
+            _ => None,
+        }
+    }
+}
+
+/// Time the Select marquee spends on each grapheme.
+pub const OPTION_MARQUEE_STEP_MS: u64 = 150;
+
+/// Scroll position of a Select's selected option under `OptionOverflow::Marquee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionMarquee {
+    pub option: u32,
+    /// Graphemes scrolled off the left edge.
+    pub skip: usize,
+    /// When `skip` last advanced.
+    pub stepped_at: std::time::Instant,
+}
+
+/// Whether children may paint outside a node's content area. ScrollBox
+/// always clips; `Hidden` gives any other container the same clipping
+/// without scrolling.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Visible = 0,
+    Hidden = 1,
+}
+
+impl Overflow {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Visible),
+            1 => Some(Self::Hidden),
+            _ => None,
+        }
+    }
+}
+
+/// How the focused Input/TextArea/ComboBox caret is drawn.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaretStyle {
+    /// Inverts the cell under the caret.
+    #[default]
+    Block = 0,
+    /// A thin '▏' at the cell's left edge in the foreground color.
+    Bar = 1,
+    /// Underlines the cell under the caret, keeping its colors.
+    Underline = 2,
+}
+
+impl CaretStyle {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Block),
+            1 => Some(Self::Bar),
+            2 => Some(Self::Underline),
+            _ => None,
+        }
+    }
+}
+
+/// How a node with opacity below 1.0 is faded.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpacityMode {
+    /// Blend RGB colors toward the background. Palette colors are unchanged.
+    #[default]
+    Blend = 0,
+    /// Leave colors alone and set the DIM attribute on the node's cells.
+    Dim = 1,
+    /// Blend RGB colors; fall back to DIM when the foreground isn't RGB.
+    Both = 2,
+}
+
+impl OpacityMode {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Blend),
+            1 => Some(Self::Dim),
+            2 => Some(Self::Both),
             _ => None,
         }
     }
@@ -188,6 +436,10 @@ pub enum TuiEventType {
     Change = 5,
     Submit = 6,
     Accessibility = 7,
+    Tick = 8,
+    AnimationEnd = 9,
+    AnimationStart = 10,
+    MenuSelect = 11,
 }
 
 // ============================================================================
@@ -234,6 +486,18 @@ pub struct TuiEvent {
     pub data: [u32; 4],
 }
 
+/// A `TuiEvent` with the host tag of its node, written by
+/// `tui_next_event_tagged`. Fixed layout, 32 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TuiTaggedEvent {
+    pub event: TuiEvent,
+    /// Host tag of `event.target` (of the newly focused node for
+    /// FocusChange; see `tui_set_node_tag`), read when the event is drained.
+    /// 0 when untagged or the node is gone.
+    pub tag: u64,
+}
+
 impl TuiEvent {
     pub fn none() -> Self {
         Self {
@@ -298,6 +562,38 @@ impl TuiEvent {
             data: [role_code, 0, 0, 0],
         }
     }
+
+    pub fn animation_end(target: u32, anim_id: u32) -> Self {
+        Self {
+            event_type: TuiEventType::AnimationEnd as u32,
+            target,
+            data: [anim_id, 0, 0, 0],
+        }
+    }
+
+    pub fn animation_start(target: u32, anim_id: u32) -> Self {
+        Self {
+            event_type: TuiEventType::AnimationStart as u32,
+            target,
+            data: [anim_id, 0, 0, 0],
+        }
+    }
+
+    pub fn menu_select(menu: u32, index: u32) -> Self {
+        Self {
+            event_type: TuiEventType::MenuSelect as u32,
+            target: menu,
+            data: [index, 0, 0, 0],
+        }
+    }
+
+    pub fn tick(elapsed_ms: u32) -> Self {
+        Self {
+            event_type: TuiEventType::Tick as u32,
+            target: 0,
+            data: [elapsed_ms, 0, 0, 0],
+        }
+    }
 }
 
 // ============================================================================
@@ -446,6 +742,34 @@ impl Buffer {
         }
     }
 
+    /// Fill every cell with a sentinel no rendered cell can equal (its color
+    /// tags are invalid), so the next diff against this buffer repaints all.
+    pub fn invalidate(&mut self) {
+        for cell in &mut self.cells {
+            *cell = Self::sentinel();
+        }
+    }
+
+    /// `invalidate` for a single row.
+    pub fn invalidate_row(&mut self, y: u16) {
+        if y < self.height {
+            let start = y as usize * self.width as usize;
+            for cell in &mut self.cells[start..start + self.width as usize] {
+                *cell = Self::sentinel();
+            }
+        }
+    }
+
+    fn sentinel() -> Cell {
+        Cell {
+            ch: '\u{FFFF}',
+            fg: u32::MAX,
+            bg: u32::MAX,
+            attrs: CellAttrs::empty(),
+            link: None,
+        }
+    }
+
     pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
         if x < self.width && y < self.height {
             Some(&self.cells[(y as usize) * (self.width as usize) + (x as usize)])
@@ -459,6 +783,16 @@ impl Buffer {
             self.cells[(y as usize) * (self.width as usize) + (x as usize)] = cell;
         }
     }
+
+    /// The cells of row `y`, left to right.
+    pub fn row(&self, y: u16) -> Option<&[Cell]> {
+        if y < self.height {
+            let start = y as usize * self.width as usize;
+            Some(&self.cells[start..start + self.width as usize])
+        } else {
+            None
+        }
+    }
 }
 
 // ============================================================================
@@ -472,6 +806,77 @@ pub struct CellUpdate {
     pub cell: Cell,
 }
 
+/// FFI-safe cell update passed to `tui_init_with_callback`'s diff callback.
+/// Fixed layout, 20 bytes (3 trailing padding bytes after `attrs`).
+/// Hyperlinks are not forwarded.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuiCellUpdate {
+    pub x: u16,
+    pub y: u16,
+    pub codepoint: u32,
+    pub fg: u32,
+    pub bg: u32,
+    pub attrs: u8,
+}
+
+impl From<&CellUpdate> for TuiCellUpdate {
+    fn from(update: &CellUpdate) -> Self {
+        Self {
+            x: update.x,
+            y: update.y,
+            codepoint: update.cell.ch as u32,
+            fg: update.cell.fg,
+            bg: update.cell.bg,
+            attrs: update.cell.attrs.bits(),
+        }
+    }
+}
+
+/// FFI-safe cell read by `tui_blit_cells`. Same 20-byte layout as
+/// `TuiCellUpdate`, with `x`/`y` relative to the blit origin.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuiCellInit {
+    pub x: u16,
+    pub y: u16,
+    pub codepoint: u32,
+    pub fg: u32,
+    pub bg: u32,
+    pub attrs: u8,
+}
+
+/// FFI-safe match range written by `tui_text_find` and read by
+/// `tui_text_set_highlights`. Grapheme offsets, end-exclusive. 8 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuiMatchRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Absolute screen rect of one node, filled by `tui_get_computed_layout_all`.
+/// 20 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuiNodeLayout {
+    pub handle: u32,
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+/// One grid track size for `tui_set_grid_template`. `kind`: 0 = auto,
+/// 1 = fixed cells, 2 = fr (flexible fraction), 3 = percent. `value` is
+/// ignored for auto. 8 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuiGridTrack {
+    pub kind: u32,
+    pub value: f32,
+}
+
 // ============================================================================
 // Terminal Input Event (internal, not FFI)
 // ============================================================================
@@ -555,6 +960,34 @@ impl Easing {
     }
 }
 
+/// FFI-safe render statistics snapshot for `tui_get_stats`. Fixed layout,
+/// 40 bytes (4 trailing padding bytes). Mirrors perf counters 0-6.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TuiStats {
+    pub layout_us: u64,
+    pub render_us: u64,
+    pub diff_cells: u32,
+    pub node_count: u32,
+    pub dirty_count: u32,
+    pub animation_count: u32,
+    pub event_buffer_len: u32,
+}
+
+/// FFI-safe keyframe stop for `tui_animate_keyframes`. Fixed layout, 12 bytes
+/// (3 trailing padding bytes after `easing`).
+///
+/// `time` is normalized to [0, 1] over the animation duration; `value` uses
+/// the same bit encoding as `tui_animate`'s target; `easing` shapes the
+/// segment that ends at this stop.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct KeyframeStop {
+    pub time: f32,
+    pub value: u32,
+    pub easing: u8,
+}
+
 // ============================================================================
 // Styled Span (for Text Module output)
 // ============================================================================
@@ -670,6 +1103,24 @@ pub struct TabsState {
     pub active_index: u32,
 }
 
+/// Numeric mode for an Input (see `tui_input_set_numeric`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericInput {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl NumericInput {
+    /// Format `value` with as many decimals as `step` carries, so stepping
+    /// by 0.25 shows "1.25" while stepping by 1 shows "3".
+    pub fn format(&self, value: f64) -> String {
+        let step = self.step.to_string();
+        let decimals = step.split_once('.').map_or(0, |(_, frac)| frac.len());
+        format!("{value:.decimals$}")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OverlayState {
     pub open: bool,
@@ -957,6 +1408,9 @@ pub struct DirtyRange {
 /// Maximum number of trace entries retained per trace kind.
 pub const DEBUG_TRACE_MAX: usize = 256;
 
+/// Upper bound for the frame log ring capacity.
+pub const FRAME_LOG_MAX: usize = 10_000;
+
 /// Overlay rendering flag bits.
 pub mod overlay_flags {
     pub const BOUNDS: u32 = 0x01;
@@ -975,6 +1429,14 @@ pub mod trace_kind {
     pub const COUNT: usize = 4;
 }
 
+/// Log levels passed to the host's `tui_set_log_callback` logger.
+pub mod log_level {
+    pub const DEBUG: u8 = 0;
+    pub const INFO: u8 = 1;
+    pub const WARN: u8 = 2;
+    pub const ERROR: u8 = 3;
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DebugTraceEntry {
     pub seq: u64,
@@ -983,6 +1445,16 @@ pub struct DebugTraceEntry {
     pub detail: String,
 }
 
+/// One render recorded by the opt-in frame log.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameLogEntry {
+    /// Wall-clock time at the end of the render, ms since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub dirty_nodes: u32,
+    pub diff_cells: u32,
+    pub render_us: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DebugFrameSnapshot {
     pub frame_id: u64,
@@ -995,6 +1467,186 @@ pub struct DebugFrameSnapshot {
     pub tail_attached: bool,
 }
 
+// ============================================================================
+// Error Codes
+// ============================================================================
+
+/// Machine-readable kind of the last error, returned by
+/// `tui_get_last_error_code`. Values are stable ABI; new kinds are appended.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCode {
+    /// The calling thread's last FFI call succeeded.
+    #[default]
+    None = 0,
+    /// A failure not covered by a more specific kind.
+    Other = 1,
+    NotInitialized = 2,
+    AlreadyInitialized = 3,
+    /// A node, buffer, view, theme, animation or block id that does not exist.
+    InvalidHandle = 4,
+    /// The handle exists but is the wrong widget type for the call.
+    WrongNodeType = 5,
+    IndexOutOfBounds = 6,
+    /// An enum value, pointer or other argument the call does not accept.
+    InvalidArgument = 7,
+    /// Taffy rejected a style or failed to compute the layout.
+    Layout = 8,
+    /// A configured or protocol limit, e.g. the node limit.
+    LimitExceeded = 9,
+    /// A call from a non-owner thread or from inside another call.
+    ThreadAccess = 10,
+    /// A panic was caught at the FFI boundary.
+    Panic = 11,
+}
+
+impl ErrorCode {
+    /// `message` as an error of this kind.
+    pub fn raise(self, message: impl Into<String>) -> TuiError {
+        TuiError {
+            code: self,
+            message: message.into(),
+        }
+    }
+}
+
+/// An error and its kind. Module functions return it, and the FFI wrappers
+/// report both halves through `tui_get_last_error` and
+/// `tui_get_last_error_code`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// A bare message, e.g. from the host's terminal callbacks, is `Other`.
+impl From<String> for TuiError {
+    fn from(message: String) -> Self {
+        ErrorCode::Other.raise(message)
+    }
+}
+
+impl From<&str> for TuiError {
+    fn from(message: &str) -> Self {
+        ErrorCode::Other.raise(message)
+    }
+}
+
+impl std::fmt::Display for TuiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TuiError {}
+
+// ============================================================================
+// NodeText
+// ============================================================================
+
+/// Node content or an option label. `Shared` text comes from the context's
+/// string interner (`tui_set_string_interning`) and shares its storage with
+/// every node holding the same string; editing it first takes an owned copy.
+#[derive(Debug, Clone)]
+pub enum NodeText {
+    Owned(String),
+    Shared(Arc<str>),
+}
+
+impl NodeText {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NodeText::Owned(s) => s,
+            NodeText::Shared(s) => s,
+        }
+    }
+
+    /// The text as an editable `String`, unsharing it if needed.
+    pub fn to_mut(&mut self) -> &mut String {
+        if let NodeText::Shared(s) = self {
+            *self = NodeText::Owned(s.to_string());
+        }
+        match self {
+            NodeText::Owned(s) => s,
+            NodeText::Shared(_) => unreachable!(),
+        }
+    }
+}
+
+impl Default for NodeText {
+    fn default() -> Self {
+        NodeText::Owned(String::new())
+    }
+}
+
+impl std::ops::Deref for NodeText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for NodeText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for NodeText {
+    fn from(s: String) -> Self {
+        NodeText::Owned(s)
+    }
+}
+
+impl From<&str> for NodeText {
+    fn from(s: &str) -> Self {
+        NodeText::Owned(s.to_string())
+    }
+}
+
+impl From<Arc<str>> for NodeText {
+    fn from(s: Arc<str>) -> Self {
+        NodeText::Shared(s)
+    }
+}
+
+impl From<NodeText> for String {
+    fn from(text: NodeText) -> Self {
+        match text {
+            NodeText::Owned(s) => s,
+            NodeText::Shared(s) => s.to_string(),
+        }
+    }
+}
+
+// Shared and owned copies of the same text are equal.
+impl PartialEq for NodeText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for NodeText {}
+
+impl PartialEq<str> for NodeText {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for NodeText {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for NodeText {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
 // ============================================================================
 // TuiNode
 // ============================================================================
@@ -1003,8 +1655,33 @@ pub struct DebugFrameSnapshot {
 pub struct TuiNode {
     pub node_type: NodeType,
     pub taffy_node: taffy::NodeId,
-    pub content: String,
+    pub content: NodeText,
     pub content_format: ContentFormat,
+    pub text_direction: TextDirection,
+    /// Blank rows drawn between consecutive text rows (Text / TextArea).
+    pub line_spacing: u32,
+    /// Extra columns after each grapheme (plain Text / Input).
+    pub letter_spacing: u32,
+    /// Monospace grid: when non-zero every grapheme advances exactly this many
+    /// cells, overriding its display width (plain Text / Input).
+    pub fixed_advance: u8,
+    pub text_transform: TextTransform,
+    pub line_scale: LineScale,
+    /// Fingerprint of this node's `measure_leaf` inputs at the last layout.
+    /// A dirty Text/Input is only re-measured when it changes.
+    pub measure_key: Option<u64>,
+    /// Opaque host identifier (see `tui_set_node_tag`). 0 = untagged.
+    pub tag: u64,
+    /// Search highlights as grapheme ranges into `content` (Text/TextArea).
+    /// Cleared by `set_content` whenever the content changes.
+    pub text_highlights: Vec<(usize, usize)>,
+    /// Host-drawn cells keyed by position relative to the node's top-left,
+    /// painted over its background and under its content (see
+    /// `tui_blit_cells`).
+    pub cell_art: HashMap<(u16, u16), Cell>,
+    /// Image escape bytes (sixel/kitty/iTerm) written at the node's rect
+    /// when the terminal supports inline images. `content` is the alt text.
+    pub image_payload: Vec<u8>,
     pub code_language: Option<String>,
     pub text_buffer_handle: Option<u32>,
     pub text_view_handle: Option<u32>,
@@ -1012,11 +1689,24 @@ pub struct TuiNode {
     pub children: Vec<u32>,
     pub parent: Option<u32>,
     pub visual_style: VisualStyle,
+    pub overflow: Overflow,
+    /// Multiply this node's opacity into every descendant's.
+    pub opacity_cascade: bool,
+    pub opacity_mode: OpacityMode,
     pub dirty: bool,
     pub focusable: bool,
     pub visible: bool,
+    /// Removed from layout flow (Taffy `display: none`), independent of `visible`.
+    pub display_none: bool,
+    /// Lay out children as a CSS grid instead of flexbox when displayed.
+    pub display_grid: bool,
     pub scroll_x: i32,
     pub scroll_y: i32,
+    /// Keep ScrollBox/TextArea content pinned to the bottom as it grows.
+    pub autoscroll: bool,
+    /// Whether the viewport is currently at the bottom; autoscroll only
+    /// follows new content while this holds.
+    pub autoscroll_pinned: bool,
     pub show_scrollbar: bool,
     pub scrollbar_side: u8,  // 0=right, 1=left
     pub scrollbar_width: u8, // valid 1..=3
@@ -1024,17 +1714,51 @@ pub struct TuiNode {
     pub z_index: i32,
     // Input widget state
     pub cursor_position: u32,
+    /// Grapheme offset where a Shift-extended selection started; the
+    /// selection runs to `cursor_position`. None = no selection.
+    pub input_selection_anchor: Option<u32>,
+    /// Key code and exact modifier mask that submit an Input. Default is
+    /// plain Enter; an unbound Enter is swallowed.
+    pub submit_key: (u32, u32),
+    /// Input mask: `#` = digit, `A` = letter, anything else a literal
+    /// separator that is inserted automatically. None = unconstrained.
+    pub input_pattern: Option<String>,
+    /// Numeric mode: digits, one '.', and a leading sign only; Up/Down step
+    /// the value within range. None = free text.
+    pub numeric: Option<NumericInput>,
     pub max_length: u32,
     pub mask_char: u32,
+    /// Show an Input's real content while keeping `mask_char` configured.
+    pub mask_revealed: bool,
+    /// IME composition shown underlined at the Input cursor until it is
+    /// committed or cleared. Never part of `content`.
+    pub composition: Option<String>,
+    /// Graphemes of a Text's content drawn while a typewriter animation
+    /// runs. None = all of it.
+    pub typewriter_reveal: Option<u32>,
+    /// Text marquee scroll: (offset in cells, gap between repetitions).
+    /// Only applied while the text is wider than the node.
+    pub text_marquee: Option<(u32, u32)>,
     // TextArea widget state
     pub cursor_row: u32,
     pub cursor_col: u32,
     pub wrap_mode: u8,
     pub textarea_view_row: u32,
     pub textarea_view_col: u32,
+    /// Ring-buffer cap on TextArea lines; 0 = unlimited.
+    pub textarea_max_lines: u32,
     // Select widget state
-    pub options: Vec<String>,
+    pub options: Vec<NodeText>,
     pub selected_index: Option<u32>,
+    /// Select type-to-filter pattern; empty shows every option.
+    pub select_filter: String,
+    /// Select typeahead prefix and the time of its last keystroke.
+    pub typeahead: String,
+    pub typeahead_at: Option<std::time::Instant>,
+    pub option_overflow: OptionOverflow,
+    /// Marquee position for the selected option while it scrolls. Restarts
+    /// when the selection moves.
+    pub marquee: Option<OptionMarquee>,
     // Accessibility fields (ADR-T23)
     pub role: Option<AccessibilityRole>,
     pub label: Option<String>,
@@ -1064,12 +1788,23 @@ impl TuiNode {
                 | NodeType::Tabs
                 | NodeType::Transcript
                 | NodeType::SplitPane
+                | NodeType::ComboBox
         );
         Self {
             node_type,
             taffy_node,
-            content: String::new(),
+            content: NodeText::default(),
             content_format: ContentFormat::Plain,
+            text_direction: TextDirection::Auto,
+            line_spacing: 0,
+            letter_spacing: 0,
+            fixed_advance: 0,
+            text_transform: TextTransform::None,
+            line_scale: LineScale::Normal,
+            tag: 0,
+            text_highlights: Vec::new(),
+            cell_art: HashMap::new(),
+            image_payload: Vec::new(),
             code_language: None,
             text_buffer_handle: None,
             text_view_handle: None,
@@ -1077,26 +1812,48 @@ impl TuiNode {
             children: Vec::new(),
             parent: None,
             visual_style: VisualStyle::default(),
+            overflow: Overflow::Visible,
+            opacity_cascade: false,
+            opacity_mode: OpacityMode::Blend,
             dirty: true,
             focusable,
             visible: true,
+            display_none: false,
+            display_grid: false,
             scroll_x: 0,
             scroll_y: 0,
+            autoscroll: false,
+            autoscroll_pinned: true,
             show_scrollbar: false,
             scrollbar_side: 0,
             scrollbar_width: 1,
             render_offset: (0.0, 0.0),
+            measure_key: None,
             z_index: 0,
             cursor_position: 0,
+            input_selection_anchor: None,
+            submit_key: (key::ENTER, 0),
+            input_pattern: None,
+            numeric: None,
             max_length: 0,
             mask_char: 0,
+            mask_revealed: false,
+            composition: None,
+            typewriter_reveal: None,
+            text_marquee: None,
             cursor_row: 0,
             cursor_col: 0,
             wrap_mode: 0,
             textarea_view_row: 0,
             textarea_view_col: 0,
+            textarea_max_lines: 0,
             options: Vec::new(),
             selected_index: None,
+            select_filter: String::new(),
+            typeahead: String::new(),
+            typeahead_at: None,
+            option_overflow: OptionOverflow::Clip,
+            marquee: None,
             role: None,
             label: None,
             description: None,
@@ -1137,6 +1894,83 @@ impl TuiNode {
             },
         }
     }
+
+    /// Indices into `options` that pass the filter, in list order. A
+    /// ComboBox filters by its typed text, a Select by `select_filter`.
+    pub fn visible_option_indices(&self) -> Vec<u32> {
+        let filter = if self.node_type == NodeType::ComboBox {
+            self.content.as_str()
+        } else {
+            &self.select_filter
+        };
+        self.options
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| crate::text_utils::glob_contains(label, filter))
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+
+    /// Replace the node's content. Highlight ranges and the Input selection
+    /// index into the old text, so they are dropped when the content
+    /// actually changes; so is a pending composition, which a commit
+    /// replaces.
+    pub fn set_content(&mut self, content: impl Into<NodeText>) {
+        let content = content.into();
+        if self.content != content {
+            self.text_highlights.clear();
+            self.input_selection_anchor = None;
+            self.composition = None;
+        }
+        self.content = content;
+    }
+
+    /// The mask character to draw with: 0 while the mask is revealed.
+    /// Letter spacing and fixed advance as laid out. A scaled line gives
+    /// every grapheme and gap two columns, the first holding the glyph.
+    pub fn laid_out_spacing(&self) -> (u32, u32) {
+        if self.line_scale == LineScale::Normal {
+            (self.letter_spacing, self.fixed_advance as u32)
+        } else {
+            (self.letter_spacing * 2 + 1, self.fixed_advance as u32 * 2)
+        }
+    }
+
+    pub fn shown_mask(&self) -> u32 {
+        if self.mask_revealed {
+            0
+        } else {
+            self.mask_char
+        }
+    }
+
+    /// Grapheme offset in the drawn Input text for content offset `g`: case
+    /// transforms can change grapheme counts, a mask cannot.
+    pub fn display_offset(&self, g: usize) -> usize {
+        if self.shown_mask() == 0 {
+            crate::text_utils::transformed_grapheme_offset(&self.content, self.text_transform, g)
+        } else {
+            g
+        }
+    }
+
+    /// The composition as drawn: masked or case-transformed like the content.
+    pub fn shown_composition(&self) -> Option<String> {
+        let text = self.composition.as_deref()?;
+        Some(match char::from_u32(self.shown_mask()) {
+            Some(mask) if mask != '\0' => mask
+                .to_string()
+                .repeat(crate::text_utils::grapheme_count(text)),
+            _ => crate::text_utils::transform_case(text, self.text_transform).into_owned(),
+        })
+    }
+
+    /// The Input selection as an ordered, non-empty grapheme range.
+    pub fn input_selection(&self) -> Option<(u32, u32)> {
+        let anchor = self.input_selection_anchor?;
+        let cursor = self.cursor_position;
+        (anchor != cursor).then(|| (anchor.min(cursor), anchor.max(cursor)))
+    }
 }
 
 #[cfg(test)]
@@ -1155,7 +1989,9 @@ mod tests {
         assert_eq!(NodeType::from_u8(9), Some(NodeType::Overlay));
         assert_eq!(NodeType::from_u8(10), Some(NodeType::Transcript));
         assert_eq!(NodeType::from_u8(11), Some(NodeType::SplitPane));
-        assert_eq!(NodeType::from_u8(12), None);
+        assert_eq!(NodeType::from_u8(12), Some(NodeType::ComboBox));
+        assert_eq!(NodeType::from_u8(13), Some(NodeType::Image));
+        assert_eq!(NodeType::from_u8(14), None);
     }
 
     #[test]
@@ -1195,6 +2031,11 @@ mod tests {
         assert_eq!(std::mem::size_of::<TuiEvent>(), 24);
     }
 
+    #[test]
+    fn test_tui_tagged_event_size() {
+        assert_eq!(std::mem::size_of::<TuiTaggedEvent>(), 32);
+    }
+
     #[test]
     fn test_buffer_operations() {
         let mut buf = Buffer::new(10, 5);
@@ -1276,7 +2117,7 @@ mod tests {
 
     #[test]
     fn test_tui_node_accessibility_defaults() {
-        let mut taffy_tree: taffy::TaffyTree<()> = taffy::TaffyTree::new();
+        let mut taffy_tree: taffy::TaffyTree<u32> = taffy::TaffyTree::new();
         let taffy_node = taffy_tree.new_leaf(taffy::Style::DEFAULT).unwrap();
         let node = TuiNode::new(NodeType::Box, taffy_node);
         assert_eq!(node.role, None);
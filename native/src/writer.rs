@@ -16,7 +16,7 @@
 //! 5. **Run coalescing** — consecutive cells with identical style on the same
 //!    row are merged into a single `Print(string)` payload.
 
-use crate::types::{CellAttrs, CellUpdate, TerminalLink};
+use crate::types::{CellAttrs, CellUpdate, ColorMode, TerminalLink, TuiError};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 // ============================================================================
@@ -35,6 +35,9 @@ pub struct WriterState {
     /// When true, the first run of the frame unconditionally emits MoveTo
     /// because the terminal cursor position is unknown after reset.
     pub force_move: bool,
+    /// Output color depth. Persists across frames; colors are quantized to
+    /// this mode at emit time only.
+    pub color_mode: ColorMode,
 }
 
 impl Default for WriterState {
@@ -53,6 +56,7 @@ impl WriterState {
             attrs: CellAttrs::empty(),
             has_cursor: false,
             force_move: true,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -139,6 +143,9 @@ pub fn baseline_metrics(diff: &[CellUpdate]) -> WriterMetrics {
         if update.cell.attrs.contains(CellAttrs::STRIKETHROUGH) {
             metrics.style_delta_count += 1;
         }
+        if update.cell.attrs.contains(CellAttrs::DIM) {
+            metrics.style_delta_count += 1;
+        }
 
         // Reset after each cell
         metrics.style_delta_count += 1;
@@ -176,6 +183,9 @@ fn estimate_per_cell_bytes(update: &CellUpdate) -> u64 {
     if update.cell.attrs.contains(CellAttrs::STRIKETHROUGH) {
         bytes += 4;
     }
+    if update.cell.attrs.contains(CellAttrs::DIM) {
+        bytes += 4;
+    }
     // Print(char): 1-4 bytes
     bytes += update.cell.ch.len_utf8() as u64;
     // Reset: \x1b[0m = 4 bytes
@@ -202,6 +212,34 @@ fn color_esc_bytes(color: u32) -> u64 {
     }
 }
 
+// ============================================================================
+// ASCII fallback — legible borders on terminals without Unicode glyphs
+// ============================================================================
+
+/// ASCII stand-in for box-drawing, block and braille glyphs; any other
+/// character is returned unchanged.
+pub fn ascii_fallback(ch: char) -> char {
+    match ch {
+        // Horizontal lines, including dashed and half-length variants.
+        '─' | '━' | '═' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' | '╴' | '╶' | '╸' | '╺' | '╼' | '╾' => {
+            '-'
+        }
+        // Vertical lines, plus the bar caret.
+        '│' | '┃' | '║' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' | '╵' | '╷' | '╹' | '╻' | '╽' | '╿'
+        | '▏' | '▕' => '|',
+        '╱' => '/',
+        '╲' => '\\',
+        '╳' => 'X',
+        // Corners, tees and crosses.
+        '\u{2500}'..='\u{257F}' => '+',
+        // Half blocks, shades and full blocks.
+        '\u{2580}'..='\u{259F}' => '#',
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28FF}' => '*',
+        _ => ch,
+    }
+}
+
 // ============================================================================
 // Run compaction — merge adjacent same-style cells into WriteRuns
 // ============================================================================
@@ -273,7 +311,7 @@ pub fn emit_frame<W: std::io::Write>(
     runs: &[WriteRun],
     out: &mut W,
     osc8_enabled: bool,
-) -> Result<WriterMetrics, String> {
+) -> Result<WriterMetrics, TuiError> {
     use crossterm::{
         cursor::MoveTo,
         style::{Attribute, Print, SetAttribute},
@@ -297,25 +335,33 @@ pub fn emit_frame<W: std::io::Write>(
             metrics.bytes_estimated += 4 + digit_count(run.x) + digit_count(run.y);
         }
 
-        // 2. Foreground delta
-        if run.fg != state.fg {
-            let bytes = emit_fg(out, run.fg)?;
-            state.fg = run.fg;
+        // 2. Foreground delta (compared after quantization so RGB colors that
+        //    share a palette entry do not re-emit)
+        let fg = crate::color::quantize(run.fg, state.color_mode);
+        if fg != state.fg {
+            let bytes = emit_fg(out, fg, state.color_mode)?;
+            state.fg = fg;
             metrics.style_delta_count += 1;
             metrics.bytes_estimated += bytes;
         }
 
         // 3. Background delta
-        if run.bg != state.bg {
-            let bytes = emit_bg(out, run.bg)?;
-            state.bg = run.bg;
+        let bg = crate::color::quantize(run.bg, state.color_mode);
+        if bg != state.bg {
+            let bytes = emit_bg(out, bg, state.color_mode)?;
+            state.bg = bg;
             metrics.style_delta_count += 1;
             metrics.bytes_estimated += bytes;
         }
 
         // 4. Attribute delta: handle both adding and removing attrs
-        let added = run.attrs & !state.attrs;
+        let mut added = run.attrs & !state.attrs;
         let removed = state.attrs & !run.attrs;
+        // SGR 22 clears both bold and dim, so dropping either one has to
+        // re-set whichever of the two this run keeps.
+        if removed.intersects(INTENSITY) {
+            added |= run.attrs & INTENSITY;
+        }
         if !added.is_empty() || !removed.is_empty() {
             let bytes = emit_attr_delta(out, added, removed)?;
             state.attrs = run.attrs;
@@ -366,11 +412,38 @@ pub fn emit_frame<W: std::io::Write>(
     Ok(metrics)
 }
 
-fn emit_fg<W: std::io::Write>(out: &mut W, fg: u32) -> Result<u64, String> {
+/// SGR parameter for one of the 16 base colors (30-37/90-97 fg, 40-47/100-107
+/// bg). crossterm always uses the `38;5;N` form, which 16-color terminals
+/// do not understand.
+fn ansi16_sgr(index: u8, background: bool) -> u8 {
+    let base = match (index < 8, background) {
+        (true, false) => 30,
+        (false, false) => 90 - 8,
+        (true, true) => 40,
+        (false, true) => 100 - 8,
+    };
+    base + index
+}
+
+fn emit_ansi16<W: std::io::Write>(
+    out: &mut W,
+    color: u32,
+    background: bool,
+) -> Result<u64, TuiError> {
+    let seq = format!("\x1b[{}m", ansi16_sgr((color & 0x0F) as u8, background));
+    out.write_all(seq.as_bytes())
+        .map_err(|e| format!("ansi16: {e}"))?;
+    Ok(seq.len() as u64)
+}
+
+fn emit_fg<W: std::io::Write>(out: &mut W, fg: u32, mode: ColorMode) -> Result<u64, TuiError> {
     use crossterm::{
         style::{Color, SetForegroundColor},
         QueueableCommand,
     };
+    if mode == ColorMode::Color16 && crate::types::color_tag(fg) == crate::color::TAG_INDEXED {
+        return emit_ansi16(out, fg, false);
+    }
     match crate::types::color_to_crossterm(fg) {
         Some(c) => {
             out.queue(SetForegroundColor(c))
@@ -385,11 +458,14 @@ fn emit_fg<W: std::io::Write>(out: &mut W, fg: u32) -> Result<u64, String> {
     }
 }
 
-fn emit_bg<W: std::io::Write>(out: &mut W, bg: u32) -> Result<u64, String> {
+fn emit_bg<W: std::io::Write>(out: &mut W, bg: u32, mode: ColorMode) -> Result<u64, TuiError> {
     use crossterm::{
         style::{Color, SetBackgroundColor},
         QueueableCommand,
     };
+    if mode == ColorMode::Color16 && crate::types::color_tag(bg) == crate::color::TAG_INDEXED {
+        return emit_ansi16(out, bg, true);
+    }
     match crate::types::color_to_crossterm(bg) {
         Some(c) => {
             out.queue(SetBackgroundColor(c))
@@ -404,17 +480,33 @@ fn emit_bg<W: std::io::Write>(out: &mut W, bg: u32) -> Result<u64, String> {
     }
 }
 
+/// Attributes cleared together by SGR 22 ("normal intensity").
+const INTENSITY: CellAttrs = CellAttrs::BOLD.union(CellAttrs::DIM);
+
 fn emit_attr_delta<W: std::io::Write>(
     out: &mut W,
     added: CellAttrs,
     removed: CellAttrs,
-) -> Result<u64, String> {
+) -> Result<u64, TuiError> {
     use crossterm::{
         style::{Attribute, SetAttribute},
         QueueableCommand,
     };
     let mut bytes: u64 = 0;
 
+    // Intensity reset goes first so it cannot cancel a bold or dim set
+    // in the same delta.
+    if removed.intersects(INTENSITY) {
+        // Crossterm's Attribute::NoBold emits SGR 21, which per ECMA-48 means
+        // "doubly underlined" — NOT bold-off.  Kitty (and other standards-
+        // compliant terminals) interpret SGR 21 literally, activating double
+        // underline instead of deactivating bold.  SGR 22 ("normal intensity")
+        // is the correct code for bold-off and dim-off.  Emit it directly.
+        out.write_all(b"\x1b[22m")
+            .map_err(|e| format!("normal intensity: {e}"))?;
+        bytes += 5;
+    }
+
     // Set newly added attributes
     if added.contains(CellAttrs::BOLD) {
         out.queue(SetAttribute(Attribute::Bold))
@@ -436,18 +528,13 @@ fn emit_attr_delta<W: std::io::Write>(
             .map_err(|e| format!("strikethrough: {e}"))?;
         bytes += 4;
     }
+    if added.contains(CellAttrs::DIM) {
+        out.queue(SetAttribute(Attribute::Dim))
+            .map_err(|e| format!("dim: {e}"))?;
+        bytes += 4;
+    }
 
     // Unset removed attributes
-    if removed.contains(CellAttrs::BOLD) {
-        // Crossterm's Attribute::NoBold emits SGR 21, which per ECMA-48 means
-        // "doubly underlined" — NOT bold-off.  Kitty (and other standards-
-        // compliant terminals) interpret SGR 21 literally, activating double
-        // underline instead of deactivating bold.  SGR 22 ("normal intensity")
-        // is the correct code for bold-off.  Emit it directly.
-        out.write_all(b"\x1b[22m")
-            .map_err(|e| format!("no bold: {e}"))?;
-        bytes += 5;
-    }
     if removed.contains(CellAttrs::ITALIC) {
         out.queue(SetAttribute(Attribute::NoItalic))
             .map_err(|e| format!("no italic: {e}"))?;
@@ -481,7 +568,11 @@ fn count_attr_changes(added: CellAttrs, removed: CellAttrs) -> u32 {
     if added.contains(CellAttrs::STRIKETHROUGH) {
         count += 1;
     }
-    if removed.contains(CellAttrs::BOLD) {
+    if added.contains(CellAttrs::DIM) {
+        count += 1;
+    }
+    // Bold-off and dim-off share a single SGR 22.
+    if removed.intersects(INTENSITY) {
         count += 1;
     }
     if removed.contains(CellAttrs::ITALIC) {
@@ -994,6 +1085,44 @@ mod tests {
         assert_eq!(metrics.style_delta_count, 7);
     }
 
+    #[test]
+    fn ascii_fallback_maps_borders_blocks_and_braille() {
+        let map = |s: &str| s.chars().map(ascii_fallback).collect::<String>();
+        assert_eq!(map("┌──┐"), "+--+");
+        assert_eq!(map("│ok║"), "|ok|");
+        assert_eq!(map("╰━╯"), "+-+");
+        assert_eq!(map("█▀░"), "###");
+        assert_eq!(map("⠋\u{2800}"), "* ");
+        assert_eq!(map("héllo→"), "héllo→");
+    }
+
+    #[test]
+    fn emit_intensity_reset_precedes_dim_set() {
+        let run = |x, attrs| WriteRun {
+            x,
+            y: 0,
+            fg: 0x01FF0000,
+            bg: 0,
+            attrs,
+            link: None,
+            chars: "A".to_string(),
+        };
+        let runs = vec![
+            run(0, CellAttrs::BOLD),
+            run(1, CellAttrs::DIM),
+            run(2, CellAttrs::BOLD | CellAttrs::DIM),
+            run(3, CellAttrs::BOLD),
+        ];
+        let mut state = WriterState::new();
+        let mut buf = Vec::new();
+        emit_frame(&mut state, &runs, &mut buf, false).unwrap();
+        let out = String::from_utf8(buf).unwrap();
+        // Bold→dim resets intensity before setting dim; dropping dim while
+        // keeping bold re-sets bold after the shared SGR 22.
+        assert!(out.contains("\x1b[22m\x1b[2mA"), "{out:?}");
+        assert!(out.contains("\x1b[22m\x1b[1mA"), "{out:?}");
+    }
+
     // --- Bug fix regression tests ---
 
     /// PR #21 review: After reset(), force_move ensures the first run of every
@@ -1210,6 +1339,48 @@ mod tests {
 
     // --- Regression benchmark: writer vs baseline ---
 
+    #[test]
+    fn coalesced_status_line_emits_fewer_bytes() {
+        // A typical incremental frame: an 80-column status line rewritten
+        // in one style, plus a spinner glyph elsewhere.
+        let cell = |x: u16, y: u16, ch: char, fg: u32| CellUpdate {
+            x,
+            y,
+            cell: Cell {
+                ch,
+                fg,
+                bg: 0x01202020,
+                attrs: CellAttrs::empty(),
+                link: None,
+            },
+        };
+        let status = format!("{:<80}", " Ready - 3 files changed, 2 warnings");
+        let mut diff: Vec<CellUpdate> = status
+            .chars()
+            .enumerate()
+            .map(|(x, ch)| cell(x as u16, 23, ch, 0x01FFFFFF))
+            .collect();
+        diff.insert(0, cell(2, 1, '\u{280B}', 0x0100FF00));
+
+        let emit = |runs: &[WriteRun]| {
+            let mut buf = Vec::new();
+            emit_frame(&mut WriterState::new(), runs, &mut buf, false).unwrap();
+            buf.len()
+        };
+        // Before: every cell positioned and styled on its own.
+        let naive: usize = diff
+            .iter()
+            .map(|u| emit(&compact_runs(std::slice::from_ref(u))))
+            .sum();
+        let runs = compact_runs(&diff);
+        assert_eq!(runs.len(), 2);
+        let coalesced = emit(&runs);
+        // Two moves, fg+bg once, fg again for the status line, 83 glyph
+        // bytes and the closing reset.
+        assert_eq!(coalesced, 150);
+        assert_eq!(naive, 3875);
+    }
+
     #[test]
     fn writer_reduces_ops_full_diff() {
         let diff = full_diff();
@@ -1402,4 +1573,64 @@ mod tests {
         eprintln!("╚══════════════════════════════════════════════════════════════════════════╝");
         eprintln!();
     }
+
+    #[test]
+    fn emit_frame_quantizes_rgb_for_limited_color_modes() {
+        let runs = vec![WriteRun {
+            x: 0,
+            y: 0,
+            fg: 0x01F01010,
+            bg: 0x01101010,
+            attrs: CellAttrs::empty(),
+            link: None,
+            chars: "x".to_string(),
+        }];
+        let emit = |mode: ColorMode| {
+            let mut state = WriterState::new();
+            state.color_mode = mode;
+            let mut buf = Vec::new();
+            emit_frame(&mut state, &runs, &mut buf, false).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        let truecolor = emit(ColorMode::TrueColor);
+        assert!(truecolor.contains("\x1b[38;2;240;16;16m"));
+
+        let palette = emit(ColorMode::Color256);
+        assert!(palette.contains("\x1b[38;5;9m"));
+        assert!(palette.contains("\x1b[48;5;233m"));
+        assert!(!palette.contains("38;2;"));
+
+        // 16-color mode uses the classic SGR codes, not the 38;5 form.
+        let basic = emit(ColorMode::Color16);
+        assert!(basic.contains("\x1b[91m"));
+        assert!(basic.contains("\x1b[40m"));
+        assert!(!basic.contains("38;5;"));
+    }
+
+    #[test]
+    fn emit_frame_distinguishes_default_from_black() {
+        let run = |x: u16, color: u32| WriteRun {
+            x,
+            y: 0,
+            fg: color,
+            bg: color,
+            attrs: CellAttrs::empty(),
+            link: None,
+            chars: "x".to_string(),
+        };
+        // Start from a non-default state so the default run must emit a delta.
+        let runs = vec![run(0, 0x01000000), run(1, crate::types::COLOR_DEFAULT)];
+        let mut state = WriterState::new();
+        let mut buf = Vec::new();
+        emit_frame(&mut state, &runs, &mut buf, false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        // True black is an explicit RGB sequence...
+        assert!(output.contains("\x1b[38;2;0;0;0m"));
+        assert!(output.contains("\x1b[48;2;0;0;0m"));
+        // ...while the default sentinel resets to the terminal's own colors.
+        assert!(output.contains("\x1b[39m"));
+        assert!(output.contains("\x1b[49m"));
+    }
 }
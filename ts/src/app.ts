@@ -6,10 +6,21 @@
  */
 
 import { ffi } from "./ffi";
-import { CString, ptr } from "bun:ffi";
+import { CString, JSCallback, ptr, toArrayBuffer, type Pointer } from "bun:ffi";
 import { checkResult, KrakenError } from "./errors";
 import { readInput, drainEvents, type KrakenEvent } from "./events";
 import { dispatchToJsxHandlers, PERF_ACTIVE_ANIMATIONS } from "./loop";
+import {
+	readCellUpdates,
+	readNodeLayouts,
+	readStats,
+	TUI_CELL_UPDATE_SIZE,
+	TUI_NODE_LAYOUT_SIZE,
+	TUI_STATS_SIZE,
+	type CellUpdate,
+	type NodeLayout,
+	type TuiStats,
+} from "./ffi/structs";
 import { Widget } from "./widget";
 import type { Theme } from "./theme";
 
@@ -45,6 +56,8 @@ const TERMINAL_CAPABILITY_FLAGS = {
 	colorDepthQuery: 1n << 10n,
 	multiplexerPresent: 1n << 11n,
 	synchronizedOutput: 1n << 12n,
+	inlineImages: 1n << 13n,
+	decLineAttributes: 1n << 14n,
 } as const;
 
 export interface TerminalCapabilities {
@@ -63,6 +76,8 @@ export interface TerminalCapabilities {
 	colorDepthQuery: boolean;
 	multiplexerPresent: boolean;
 	synchronizedOutput: boolean;
+	inlineImages: boolean;
+	decLineAttributes: boolean;
 }
 
 export interface TerminalInfo {
@@ -173,9 +188,24 @@ function terminalInfoRetrySize(message: string, currentSize: number): number | u
 	return nextSize;
 }
 
+export type ColorMode = "truecolor" | "256" | "16";
+
+const COLOR_MODES: readonly ColorMode[] = ["truecolor", "256", "16"];
+
+/** Severity passed to a `setLogger` callback (mirrors `log_level`). */
+export const LogLevel = {
+	Debug: 0,
+	Info: 1,
+	Warn: 2,
+	Error: 3,
+} as const;
+export type LogLevel = (typeof LogLevel)[keyof typeof LogLevel];
+
 export class Kraken {
 	private idMap: Map<string, number> = new Map();
 	private _running = false;
+	private callbacks: JSCallback[] = [];
+	private logCallback: JSCallback | null = null;
 
 	private constructor() {}
 
@@ -198,12 +228,54 @@ export class Kraken {
 		return new Kraken();
 	}
 
+	/**
+	 * Initialize the TUI system as a pure layout/diff engine. Each render()
+	 * hands the changed cells to `onDiff` (then calls `onFlush`) instead of
+	 * writing to a terminal. Drive size and input with headlessResize() and
+	 * the headlessInject* methods.
+	 */
+	static initWithCallback(
+		width: number,
+		height: number,
+		onDiff: (cells: CellUpdate[]) => void,
+		onFlush?: () => void,
+	): Kraken {
+		const diffCb = new JSCallback(
+			(updates: Pointer, len: number) => {
+				const bytes = toArrayBuffer(updates, 0, len * TUI_CELL_UPDATE_SIZE);
+				onDiff(readCellUpdates(bytes, len));
+			},
+			{ args: ["ptr", "u32", "ptr"], returns: "void" },
+		);
+		const flushCb = onFlush
+			? new JSCallback(() => onFlush(), { args: ["ptr"], returns: "void" })
+			: null;
+		const result = ffi.tui_init_with_callback(
+			width,
+			height,
+			diffCb.ptr,
+			flushCb?.ptr ?? null,
+			null,
+		);
+		if (result !== 0) {
+			diffCb.close();
+			flushCb?.close();
+		}
+		checkResult(result, "Kraken.initWithCallback");
+		const app = new Kraken();
+		app.callbacks = flushCb ? [diffCb, flushCb] : [diffCb];
+		return app;
+	}
+
 	/**
 	 * Shut down the TUI system. Restores terminal state.
 	 */
 	shutdown(): void {
 		checkResult(ffi.tui_shutdown(), "shutdown");
 		this.idMap.clear();
+		for (const cb of this.callbacks) cb.close();
+		this.callbacks = [];
+		this.setLogger(null);
 	}
 
 	/**
@@ -213,6 +285,28 @@ export class Kraken {
 		checkResult(ffi.tui_set_root(widget.handle), "setRoot");
 	}
 
+	/** Handle of the current root, or 0 if none is set. */
+	getRootHandle(): number {
+		return ffi.tui_get_root();
+	}
+
+	/**
+	 * Every live node handle, ascending. Use with `tui_get_parent` /
+	 * `tui_get_child_at` to rebuild a host-side mirror after a reload.
+	 */
+	getAllHandles(): number[] {
+		let capacity = 256;
+		for (;;) {
+			const out = new Uint32Array(capacity);
+			const total = ffi.tui_get_all_handles(out, capacity);
+			checkResult(total, "getAllHandles");
+			if (total <= capacity) {
+				return Array.from(out.subarray(0, total));
+			}
+			capacity = total;
+		}
+	}
+
 	/**
 	 * Read terminal input and buffer events.
 	 * @param timeoutMs 0 = non-blocking, >0 = wait up to N ms for first input
@@ -228,11 +322,229 @@ export class Kraken {
 		return drainEvents();
 	}
 
+	/**
+	 * Emit a "tick" event from readInput() whenever no input has arrived for
+	 * `intervalMs`. The event's `elapsedMs` carries the idle time. 0 disables.
+	 */
+	setTickInterval(intervalMs: number): void {
+		checkResult(ffi.tui_set_tick_interval(intervalMs), "setTickInterval");
+	}
+
+	/**
+	 * Idle window after which Select typeahead starts a fresh prefix.
+	 * Defaults to 1000 ms.
+	 */
+	setTypeaheadTimeout(ms: number): void {
+		checkResult(ffi.tui_set_typeahead_ms(ms), "setTypeaheadTimeout");
+	}
+
+	/**
+	 * When enabled, Escape on a focused widget moves focus to its nearest
+	 * focusable ancestor (or clears it) instead of arriving as a key event.
+	 */
+	setEscapeBlurs(enabled: boolean): void {
+		checkResult(ffi.tui_set_escape_blurs(enabled ? 1 : 0), "setEscapeBlurs");
+	}
+
+	/**
+	 * Show or hide the focused widget's caret, e.g. from a blink timer.
+	 * Any focus change makes the caret visible again.
+	 */
+	setCaretVisible(visible: boolean): void {
+		checkResult(ffi.tui_set_caret_visible(visible ? 1 : 0), "setCaretVisible");
+	}
+
+	/** How the focused widget's caret is drawn. Default "block". */
+	setCaretStyle(style: "block" | "bar" | "underline"): void {
+		const map: Record<string, number> = { block: 0, bar: 1, underline: 2 };
+		checkResult(ffi.tui_set_caret_style(map[style] ?? 0), "setCaretStyle");
+	}
+
+	/**
+	 * Characters that break words for Ctrl+Arrow and Ctrl+Backspace in
+	 * addition to whitespace, e.g. "._-" so "foo.bar" is three tokens.
+	 */
+	setWordBreakChars(chars: string): void {
+		const encoded = Buffer.from(chars, "utf-8");
+		checkResult(
+			ffi.tui_set_word_break_chars(encoded, encoded.length),
+			"setWordBreakChars",
+		);
+	}
+
+	/**
+	 * Absolute screen rects of every node from the last layout pass, taken
+	 * in one snapshot. Intended for layout inspectors and debug overlays.
+	 */
+	getAllLayouts(): NodeLayout[] {
+		let capacity = 256;
+		for (;;) {
+			const out = new Uint8Array(capacity * TUI_NODE_LAYOUT_SIZE);
+			const total = ffi.tui_get_computed_layout_all(out, capacity);
+			checkResult(total, "getAllLayouts");
+			if (total <= capacity) {
+				return readNodeLayouts(out, total);
+			}
+			capacity = total;
+		}
+	}
+
+	/**
+	 * Handle of the lowest-numbered node tagged `tag` (see `Widget.setTag`),
+	 * or 0 if none. Linear scan over all nodes; cache the result.
+	 */
+	findByTag(tag: bigint | number): number {
+		return ffi.tui_find_by_tag(BigInt(tag));
+	}
+
+	/** Handles of every node tagged `tag`, ascending. Linear scan. */
+	findAllByTag(tag: bigint | number): number[] {
+		let capacity = 16;
+		for (;;) {
+			const out = new Uint32Array(capacity);
+			const total = ffi.tui_find_all_by_tag(BigInt(tag), out, capacity);
+			checkResult(total, "findAllByTag");
+			if (total <= capacity) {
+				return Array.from(out.subarray(0, total));
+			}
+			capacity = total;
+		}
+	}
+
 	/**
 	 * Execute the full render pipeline: layout → diff → terminal I/O.
+	 * Returns false when the frame was skipped because nothing changed since
+	 * the last one, or by setMinRenderInterval().
+	 */
+	render(): boolean {
+		const code = ffi.tui_render();
+		checkResult(code, "render");
+		return code === 0;
+	}
+
+	/**
+	 * Skip render() calls made within `ms` of the previous frame while
+	 * nothing has changed, capping the frame rate of a tight render loop.
+	 * 0 disables the cap.
+	 */
+	setMinRenderInterval(ms: number): void {
+		checkResult(ffi.tui_set_min_render_interval(ms), "setMinRenderInterval");
+	}
+
+	/**
+	 * Render only the given screen rect. Cells outside it are not updated;
+	 * the host manages the rest of the surface until the next render().
+	 */
+	renderRegion(x: number, y: number, width: number, height: number): void {
+		checkResult(ffi.tui_render_region(x, y, width, height), "renderRegion");
+	}
+
+	/**
+	 * Force the next render() to repaint the whole screen. Call after
+	 * anything outside the TUI has written to the terminal (e.g. a shell-out).
+	 */
+	invalidate(): void {
+		checkResult(ffi.tui_invalidate(), "invalidate");
+	}
+
+	/**
+	 * Strip control characters (ESC, BEL, CR, ...) from widget content on
+	 * set (default on) so untrusted text cannot inject terminal escapes.
+	 */
+	setSanitizeContent(enabled: boolean): void {
+		checkResult(ffi.tui_set_sanitize_content(enabled ? 1 : 0), "setSanitizeContent");
+	}
+
+	/**
+	 * Share storage between identical content and option strings, e.g. the
+	 * labels repeated across a large table or Select (default off). Trades a
+	 * lookup per set for the dedup.
 	 */
-	render(): void {
-		checkResult(ffi.tui_render(), "render");
+	setStringInterning(enabled: boolean): void {
+		checkResult(ffi.tui_set_string_interning(enabled ? 1 : 0), "setStringInterning");
+	}
+
+	/**
+	 * Measure emoji-presentation sequences (e.g. ❤️, #️⃣) as two cells
+	 * (default) or as their narrow base character. Turn off for terminals
+	 * that draw them one cell wide, to avoid cursor drift.
+	 */
+	setWideEmoji(enabled: boolean): void {
+		checkResult(ffi.tui_set_wide_emoji(enabled ? 1 : 0), "setWideEmoji");
+	}
+
+	/**
+	 * Set the output color depth. Defaults to what the terminal reports.
+	 * RGB colors are quantized to the nearest xterm palette entry on output
+	 * only, so switching back to truecolor restores full fidelity.
+	 */
+	setColorMode(mode: ColorMode): void {
+		const code = COLOR_MODES.indexOf(mode);
+		if (code < 0) {
+			throw new KrakenError(`Invalid color mode: ${mode}`, -1);
+		}
+		checkResult(ffi.tui_set_color_mode(code), "setColorMode");
+	}
+
+	getColorMode(): ColorMode {
+		return COLOR_MODES[ffi.tui_get_color_mode()] ?? "truecolor";
+	}
+
+	/**
+	 * Emit ASCII stand-ins for box-drawing, block and braille glyphs so
+	 * borders stay legible on terminals without them. Defaults to on when
+	 * the terminal does not report UTF-8.
+	 */
+	setAsciiFallback(enabled: boolean): void {
+		checkResult(ffi.tui_set_ascii_fallback(enabled ? 1 : 0), "setAsciiFallback");
+	}
+
+	/**
+	 * Hand the terminal back (normal screen, cooked mode) without tearing
+	 * down the UI, e.g. to run an external editor. render() is a no-op until
+	 * resume().
+	 */
+	suspend(): void {
+		checkResult(ffi.tui_suspend(), "suspend");
+	}
+
+	/**
+	 * Re-enter the alternate screen and raw mode after suspend() and repaint
+	 * the full screen.
+	 */
+	resume(): void {
+		checkResult(ffi.tui_resume(), "resume");
+	}
+
+	/**
+	 * Simulate a terminal resize (headless mode only). The next readInput()
+	 * delivers a resize event and the next render() reflows the tree.
+	 */
+	headlessResize(width: number, height: number): void {
+		checkResult(ffi.tui_headless_resize(width, height), "headlessResize");
+	}
+
+	/**
+	 * Queue a synthetic key event (headless mode only), delivered by the
+	 * next readInput(). For character keys pass the codepoint as both
+	 * `code` and `codepoint`.
+	 */
+	headlessInjectKey(code: number, modifiers = 0, codepoint = 0): void {
+		checkResult(
+			ffi.tui_headless_inject_key(code, modifiers, codepoint),
+			"headlessInjectKey",
+		);
+	}
+
+	/**
+	 * Queue a synthetic mouse event (headless mode only), delivered by the
+	 * next readInput().
+	 */
+	headlessInjectMouse(x: number, y: number, button: number, modifiers = 0): void {
+		checkResult(
+			ffi.tui_headless_inject_mouse(x, y, button, modifiers),
+			"headlessInjectMouse",
+		);
 	}
 
 	/**
@@ -245,6 +557,14 @@ export class Kraken {
 		return { width: wBuf[0]!, height: hBuf[0]! };
 	}
 
+	/**
+	 * Toggle mouse capture. Disable it to let the terminal's native text
+	 * selection work; no mouse events are delivered while it is off.
+	 */
+	setMouseCapture(enabled: boolean): void {
+		checkResult(ffi.tui_set_mouse_capture(enabled ? 1 : 0), "setMouseCapture");
+	}
+
 	getCapabilities(): TerminalCapabilities {
 		const out = new BigUint64Array(1);
 		// Use the status-returning ABI so a destroyed or uninitialized native
@@ -270,6 +590,8 @@ export class Kraken {
 			colorDepthQuery: has(TERMINAL_CAPABILITY_FLAGS.colorDepthQuery),
 			multiplexerPresent: has(TERMINAL_CAPABILITY_FLAGS.multiplexerPresent),
 			synchronizedOutput: has(TERMINAL_CAPABILITY_FLAGS.synchronizedOutput),
+			inlineImages: has(TERMINAL_CAPABILITY_FLAGS.inlineImages),
+			decLineAttributes: has(TERMINAL_CAPABILITY_FLAGS.decLineAttributes),
 		};
 	}
 
@@ -362,6 +684,30 @@ export class Kraken {
 		checkResult(ffi.tui_set_debug(enabled ? 1 : 0), "setDebug");
 	}
 
+	/**
+	 * Forward native diagnostics to `onLog`: every failed call's error,
+	 * internal warnings, and debug messages while setDebug is on (which then
+	 * no longer go to stderr). Pass null to remove it; shutdown() does too.
+	 */
+	setLogger(onLog: ((level: LogLevel, message: string) => void) | null): void {
+		const cb = onLog
+			? new JSCallback(
+					(level: number, msg: Pointer, len: number) => {
+						const bytes = new Uint8Array(toArrayBuffer(msg, 0, len));
+						onLog(level as LogLevel, new TextDecoder().decode(bytes));
+					},
+					{ args: ["u8", "ptr", "u32", "ptr"], returns: "void" },
+				)
+			: null;
+		const result = ffi.tui_set_log_callback(cb?.ptr ?? null, null);
+		if (result !== 0) {
+			cb?.close();
+		}
+		checkResult(result, "setLogger");
+		this.logCallback?.close();
+		this.logCallback = cb;
+	}
+
 	/**
 	 * Query a performance counter.
 	 */
@@ -369,6 +715,16 @@ export class Kraken {
 		return ffi.tui_get_perf_counter(id);
 	}
 
+	/**
+	 * Read the core per-frame statistics in a single FFI call
+	 * (same values as perf counters 0-6).
+	 */
+	getStats(): TuiStats {
+		const buffer = new ArrayBuffer(TUI_STATS_SIZE);
+		checkResult(ffi.tui_get_stats(ptr(buffer)), "getStats");
+		return readStats(buffer);
+	}
+
 	// =========================================================================
 	// Debug / Devtools (ADR-T34, TechSpec §4.3.3)
 	// =========================================================================
@@ -424,6 +780,28 @@ export class Kraken {
 		checkResult(ffi.tui_debug_clear_traces(), "debugClearTraces");
 	}
 
+	/**
+	 * Keep a ring of the last `capacity` renders (timestamp, dirty nodes,
+	 * diff cells, render µs) for post-mortem debugging. 0 disables it.
+	 * Works without debug mode.
+	 */
+	setFrameLog(capacity: number): void {
+		checkResult(ffi.tui_set_frame_log(capacity), "setFrameLog");
+	}
+
+	/**
+	 * Dump the frame log as text, one line per render, oldest first.
+	 */
+	dumpFrameLog(): string {
+		const len = ffi.tui_get_frame_log_len();
+		checkResult(len, "dumpFrameLog:len");
+		if (len <= 0) return "";
+		const buf = Buffer.alloc(len);
+		const written = ffi.tui_dump_frame_log(ptr(buf), len);
+		checkResult(written, "dumpFrameLog");
+		return buf.toString("utf-8", 0, written);
+	}
+
 	/**
 	 * Get total node count.
 	 */
@@ -431,6 +809,14 @@ export class Kraken {
 		return ffi.tui_get_node_count();
 	}
 
+	/**
+	 * Cap the number of live nodes. Node creation fails once the cap is
+	 * reached. 0 (the default) means unlimited.
+	 */
+	setMaxNodes(maxNodes: number): void {
+		checkResult(ffi.tui_set_max_nodes(maxNodes), "setMaxNodes");
+	}
+
 	/**
 	 * Chain animation B to start when animation A completes.
 	 * Cancelling A prevents B from auto-starting.
@@ -479,6 +865,40 @@ export class Kraken {
 		checkResult(ffi.tui_destroy_choreo_group(group), "destroyChoreoGroup");
 	}
 
+	/**
+	 * Advance all animations by `elapsedMs` without rendering. Combine with
+	 * setAnimationAutoAdvance(false) to run a fixed-timestep animation clock.
+	 */
+	advanceAnimations(elapsedMs: number): void {
+		checkResult(ffi.tui_advance_animations(elapsedMs), "advanceAnimations");
+	}
+
+	/**
+	 * Whether render() advances animations by wall-clock time (default true).
+	 */
+	setAnimationAutoAdvance(enabled: boolean): void {
+		checkResult(
+			ffi.tui_set_animation_auto_advance(enabled ? 1 : 0),
+			"setAnimationAutoAdvance",
+		);
+	}
+
+	/**
+	 * Scale all animation time for slow-motion debugging. 0 freezes
+	 * animations in place, 1 is real time, 2 doubles speed.
+	 */
+	setAnimationTimeScale(scale: number): void {
+		checkResult(ffi.tui_set_animation_time_scale(scale), "setAnimationTimeScale");
+	}
+
+	/**
+	 * Opt in to "animationStart" events when chained or choreographed
+	 * animations activate. "animationEnd" events are always delivered.
+	 */
+	setAnimationEvents(enabled: boolean): void {
+		checkResult(ffi.tui_set_animation_events(enabled ? 1 : 0), "setAnimationEvents");
+	}
+
 	/**
 	 * Run the application event loop (ADR-T26, TechSpec §4.7).
 	 *
@@ -6,7 +6,7 @@
 
 import type { Kraken } from "../app";
 
-/** Names for all perf counters 0-18 (TechSpec §4.5). Indexed by counter ID. */
+/** Names for all perf counters 0-21 (TechSpec §4.5). Indexed by counter ID. */
 export const PERF_COUNTER_NAMES: string[] = [
 	"layout_us",
 	"render_us",
@@ -27,10 +27,13 @@ export const PERF_COUNTER_NAMES: string[] = [
 	"transcript_unread",
 	"debug_trace_depth",
 	"transcript_tail_attached",
+	"anim_advance_us",
+	"anims_advanced",
+	"frame_interval_us",
 ];
 
 /** Total number of perf counters. */
-export const PERF_COUNTER_COUNT = 19;
+export const PERF_COUNTER_COUNT = 22;
 
 export class PerfHud {
 	constructor(private app: Kraken) {}
@@ -5,13 +5,37 @@
 import { ffi } from "./ffi";
 import { CString } from "bun:ffi";
 
+/**
+ * Machine-readable error kinds. These match the Rust ErrorCode enum
+ * (`tui_get_last_error_code`); values are stable.
+ */
+export const ErrorKind = {
+	None: 0,
+	Other: 1,
+	NotInitialized: 2,
+	AlreadyInitialized: 3,
+	InvalidHandle: 4,
+	WrongNodeType: 5,
+	IndexOutOfBounds: 6,
+	InvalidArgument: 7,
+	Layout: 8,
+	LimitExceeded: 9,
+	ThreadAccess: 10,
+	Panic: 11,
+} as const;
+
+export type ErrorKind = (typeof ErrorKind)[keyof typeof ErrorKind];
+
 export class KrakenError extends Error {
 	public readonly code: number;
+	/** What went wrong, for branching without parsing `message`. */
+	public readonly kind: ErrorKind;
 
-	constructor(message: string, code: number) {
+	constructor(message: string, code: number, kind: ErrorKind = ErrorKind.Other) {
 		super(message);
 		this.name = "KrakenError";
 		this.code = code;
+		this.kind = kind;
 	}
 }
 
@@ -23,6 +47,7 @@ export function checkResult(code: number, context?: string): void {
 	if (code >= 0) return;
 
 	let message: string;
+	const kind = ffi.tui_get_last_error_code() as ErrorKind;
 	if (code === -2) {
 		message = "Internal panic in native core";
 	} else {
@@ -39,5 +64,5 @@ export function checkResult(code: number, context?: string): void {
 		message = `${context}: ${message}`;
 	}
 
-	throw new KrakenError(message, code);
+	throw new KrakenError(message, code, kind);
 }
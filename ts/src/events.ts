@@ -7,11 +7,11 @@
 import { ptr } from "bun:ffi";
 import { ffi } from "./ffi";
 import {
-	allocEventBuffer,
-	readEvent,
+	allocTaggedEventBuffer,
+	readTaggedEvent,
 	EventType,
 	KeyCode,
-	type TuiEvent,
+	type TuiTaggedEvent,
 } from "./ffi/structs";
 import { checkResult } from "./errors";
 
@@ -22,11 +22,17 @@ export type KrakenEventType =
 	| "focus"
 	| "change"
 	| "submit"
-	| "accessibility";
+	| "accessibility"
+	| "tick"
+	| "animationEnd"
+	| "animationStart"
+	| "menuSelect";
 
 export interface KrakenEvent {
 	type: KrakenEventType;
 	target: number;
+	/** Host tag of the target (or newly focused node); 0n when untagged. */
+	targetTag?: bigint;
 	keyCode?: number;
 	modifiers?: number;
 	codepoint?: number;
@@ -39,6 +45,8 @@ export interface KrakenEvent {
 	toHandle?: number;
 	selectedIndex?: number;
 	roleCode?: number;
+	elapsedMs?: number;
+	animationId?: number;
 }
 
 function mapEventType(raw: number): KrakenEventType | null {
@@ -57,16 +65,28 @@ function mapEventType(raw: number): KrakenEventType | null {
 			return "submit";
 		case EventType.Accessibility:
 			return "accessibility";
+		case EventType.Tick:
+			return "tick";
+		case EventType.AnimationEnd:
+			return "animationEnd";
+		case EventType.AnimationStart:
+			return "animationStart";
+		case EventType.MenuSelect:
+			return "menuSelect";
 		default:
 			return null;
 	}
 }
 
-function mapRawEvent(raw: TuiEvent): KrakenEvent | null {
+function mapRawEvent(raw: TuiTaggedEvent): KrakenEvent | null {
 	const type = mapEventType(raw.eventType);
 	if (!type) return null;
 
-	const base: KrakenEvent = { type, target: raw.target };
+	const base: KrakenEvent = {
+		type,
+		target: raw.target,
+		targetTag: raw.tag,
+	};
 
 	switch (type) {
 		case "key":
@@ -89,6 +109,7 @@ function mapRawEvent(raw: TuiEvent): KrakenEvent | null {
 			base.toHandle = raw.data[1];
 			break;
 		case "change":
+		case "menuSelect":
 			base.selectedIndex = raw.data[0];
 			break;
 		case "submit":
@@ -96,6 +117,13 @@ function mapRawEvent(raw: TuiEvent): KrakenEvent | null {
 		case "accessibility":
 			base.roleCode = raw.data[0];
 			break;
+		case "tick":
+			base.elapsedMs = raw.data[0];
+			break;
+		case "animationEnd":
+		case "animationStart":
+			base.animationId = raw.data[0];
+			break;
 	}
 
 	return base;
@@ -115,14 +143,14 @@ export function readInput(timeoutMs: number = 0): number {
  */
 export function drainEvents(): KrakenEvent[] {
 	const events: KrakenEvent[] = [];
-	const buffer = allocEventBuffer();
+	const buffer = allocTaggedEventBuffer();
 	const bufPtr = ptr(buffer);
 
 	while (true) {
-		const result = ffi.tui_next_event(bufPtr);
+		const result = ffi.tui_next_event_tagged(bufPtr);
 		if (result <= 0) break;
 
-		const raw = readEvent(buffer);
+		const raw = readTaggedEvent(buffer);
 		const mapped = mapRawEvent(raw);
 		if (mapped) events.push(mapped);
 	}
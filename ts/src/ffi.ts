@@ -19,12 +19,29 @@ const symbols = {
 		args: ["u16", "u16"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_init_with_callback: {
+		args: ["u16", "u16", "function", "function", "ptr"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_headless_resize: {
+		args: ["u16", "u16"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_headless_inject_key: {
+		args: ["u32", "u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_headless_inject_mouse: {
+		args: ["u16", "u16", "u8", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_shutdown: { args: [] as FFIType[], returns: "i32" as const },
 	tui_get_terminal_size: {
 		args: ["ptr", "ptr"] as FFIType[],
 		returns: "i32" as const,
 	},
 	tui_get_capabilities: { args: [] as FFIType[], returns: "u32" as const },
+	tui_set_mouse_capture: { args: ["u8"] as FFIType[], returns: "i32" as const },
 	tui_terminal_get_capabilities: { args: [] as FFIType[], returns: "u64" as const },
 	tui_terminal_get_capabilities_checked: {
 		args: ["ptr"] as FFIType[],
@@ -43,6 +60,16 @@ const symbols = {
 	tui_create_node: { args: ["u8"] as FFIType[], returns: "u32" as const },
 	tui_destroy_node: { args: ["u32"] as FFIType[], returns: "i32" as const },
 	tui_destroy_subtree: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_set_node_tag: {
+		args: ["u32", "u64"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_get_node_tag: { args: ["u32"] as FFIType[], returns: "u64" as const },
+	tui_find_by_tag: { args: ["u64"] as FFIType[], returns: "u32" as const },
+	tui_find_all_by_tag: {
+		args: ["u64", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_get_node_type: { args: ["u32"] as FFIType[], returns: "i32" as const },
 	tui_set_visible: {
 		args: ["u32", "u8"] as FFIType[],
@@ -50,6 +77,7 @@ const symbols = {
 	},
 	tui_get_visible: { args: ["u32"] as FFIType[], returns: "i32" as const },
 	tui_get_node_count: { args: [] as FFIType[], returns: "u32" as const },
+	tui_set_max_nodes: { args: ["u32"] as FFIType[], returns: "i32" as const },
 
 	// Tree Structure
 	tui_set_root: { args: ["u32"] as FFIType[], returns: "i32" as const },
@@ -65,6 +93,10 @@ const symbols = {
 		args: ["u32", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_replace_child: {
+		args: ["u32", "u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_get_child_count: {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
@@ -74,12 +106,45 @@ const symbols = {
 		returns: "u32" as const,
 	},
 	tui_get_parent: { args: ["u32"] as FFIType[], returns: "u32" as const },
+	tui_get_root: { args: [] as FFIType[], returns: "u32" as const },
+	tui_walk_subtree: {
+		args: ["u32", "function", "ptr"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_get_all_handles: {
+		args: ["ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 
 	// Content
 	tui_set_content: {
 		args: ["u32", "ptr", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_content_diffed: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_content_append: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_content_truncate_lines: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_content_insert_at: {
+		args: ["u32", "u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_content_delete_range: {
+		args: ["u32", "u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_content_replace_range: {
+		args: ["u32", "u32", "u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_get_content_len: {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
@@ -88,10 +153,55 @@ const symbols = {
 		args: ["u32", "ptr", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_text_direction: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_line_spacing: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_letter_spacing: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_line_scale: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_monospace_grid: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_text_transform: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_get_text_direction: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_text_find: {
+		args: ["u32", "ptr", "u32", "u8", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_text_set_highlights: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_blit_cells: {
+		args: ["u32", "ptr", "u32", "u16", "u16"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_clear_cells: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_set_content_format: {
 		args: ["u32", "u8"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_image_set_payload: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_set_code_language: {
 		args: ["u32", "ptr", "u32"] as FFIType[],
 		returns: "i32" as const,
@@ -106,6 +216,14 @@ const symbols = {
 		args: ["u32", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_input_clear: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_set_submit_key: {
+		args: ["u32", "u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_input_get_cursor: {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
@@ -122,6 +240,58 @@ const symbols = {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_input_set_mask_revealed: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_set_composition: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_clear_composition: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_set_pattern: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_is_valid: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_set_numeric: {
+		args: ["u32", "f64", "f64", "f64"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_input_get_numeric: {
+		args: ["u32", "ptr"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_get_line_len: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_get_line: {
+		args: ["u32", "u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_set_line: {
+		args: ["u32", "u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_insert_line: {
+		args: ["u32", "u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_delete_line: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_textarea_clear: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_textarea_set_cursor: {
 		args: ["u32", "u32", "u32"] as FFIType[],
 		returns: "i32" as const,
@@ -172,6 +342,10 @@ const symbols = {
 		args: ["u32", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_textarea_set_max_lines: {
+		args: ["u32", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 
 	// Widget Properties (Select)
 	tui_select_add_option: {
@@ -202,6 +376,24 @@ const symbols = {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_menu_create: { args: [] as FFIType[], returns: "u32" as const },
+	tui_menu_add_item: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_menu_show_at: {
+		args: ["u32", "i32", "i32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_menu_close: { args: [] as FFIType[], returns: "i32" as const },
+	tui_select_set_option_overflow: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_select_set_filter: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 
 	// Layout
 	tui_set_layout_dimension: {
@@ -216,18 +408,50 @@ const symbols = {
 		args: ["u32", "u32", "f32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_layout_aspect_ratio: {
+		args: ["u32", "f32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_display: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_display_none: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_grid_template: {
+		args: ["u32", "u8", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_grid_placement: {
+		args: ["u32", "i32", "i32", "i32", "i32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_set_layout_edges: {
 		args: ["u32", "u32", "f32", "f32", "f32", "f32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_layout_edges_unit: {
+		args: ["u32", "u32", "f32", "f32", "f32", "f32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_set_layout_gap: {
 		args: ["u32", "f32", "f32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_layout_gap_unit: {
+		args: ["u32", "f32", "f32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_get_layout: {
 		args: ["u32", "ptr", "ptr", "ptr", "ptr"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_get_computed_layout_all: {
+		args: ["ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_measure_text: {
 		args: ["ptr", "u32", "ptr"] as FFIType[],
 		returns: "i32" as const,
@@ -250,6 +474,45 @@ const symbols = {
 		args: ["u32", "f32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_style_overflow: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_style_opacity_cascade: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_opacity_mode: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+
+	// Color Helpers
+	tui_color_from_hex: {
+		args: ["ptr", "u32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_color_to_hex: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_color_from_hsl: {
+		args: ["f32", "f32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_color_from_hsv: {
+		args: ["f32", "f32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_color_lighten: {
+		args: ["u32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_color_darken: {
+		args: ["u32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_contrast_color: { args: ["u32"] as FFIType[], returns: "u32" as const },
 
 	// Theme Management
 	tui_create_theme: { args: [] as FFIType[], returns: "u32" as const },
@@ -304,6 +567,14 @@ const symbols = {
 		args: ["u32", "u8", "u32", "u32", "u8"] as FFIType[],
 		returns: "u32" as const,
 	},
+	tui_animate_keyframes: {
+		args: ["u32", "u8", "ptr", "u32", "u32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_animate_spring: {
+		args: ["u32", "u8", "u32", "f32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
 	tui_cancel_animation: {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
@@ -312,6 +583,22 @@ const symbols = {
 		args: ["u32", "u32"] as FFIType[],
 		returns: "u32" as const,
 	},
+	tui_start_spinner_frames: {
+		args: ["u32", "ptr", "u32", "u32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_start_color_spinner: {
+		args: ["u32", "ptr", "u32", "u32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_animate_typewriter: {
+		args: ["u32", "f32"] as FFIType[],
+		returns: "u32" as const,
+	},
+	tui_animate_marquee: {
+		args: ["u32", "f32", "u32"] as FFIType[],
+		returns: "u32" as const,
+	},
 	tui_start_progress: {
 		args: ["u32", "u32", "u8"] as FFIType[],
 		returns: "u32" as const,
@@ -348,6 +635,21 @@ const symbols = {
 		args: ["u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_advance_animations: { args: ["f32"] as FFIType[], returns: "i32" as const },
+	tui_set_animation_auto_advance: {
+		args: ["u8"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_animation_time_scale: {
+		args: ["f32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_animation_events: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_capture_positions: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_play_position_transitions: {
+		args: ["u32", "u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
 
 	// Focus
 	tui_set_focusable: {
@@ -373,12 +675,44 @@ const symbols = {
 		args: ["u32", "i32", "i32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_autoscroll: {
+		args: ["u32", "u8"] as FFIType[],
+		returns: "i32" as const,
+	},
 
 	// Input & Rendering
 	tui_read_input: { args: ["u32"] as FFIType[], returns: "i32" as const },
 	tui_next_event: { args: ["ptr"] as FFIType[], returns: "i32" as const },
+	tui_next_event_tagged: {
+		args: ["ptr"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_set_tick_interval: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_set_typeahead_ms: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_set_escape_blurs: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_caret_visible: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_caret_style: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_word_break_chars: {
+		args: ["ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_render: { args: [] as FFIType[], returns: "i32" as const },
+	tui_set_min_render_interval: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_render_region: {
+		args: ["u16", "u16", "u16", "u16"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_mark_dirty: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_set_sanitize_content: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_string_interning: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_thread_affinity: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_wide_emoji: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_color_mode: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_ascii_fallback: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_get_color_mode: { args: [] as FFIType[], returns: "u8" as const },
+	tui_suspend: { args: [] as FFIType[], returns: "i32" as const },
+	tui_resume: { args: [] as FFIType[], returns: "i32" as const },
+	tui_invalidate: { args: [] as FFIType[], returns: "i32" as const },
 
 	// Accessibility (ADR-T23)
 	tui_set_node_role: {
@@ -396,12 +730,18 @@ const symbols = {
 
 	// Diagnostics
 	tui_get_last_error: { args: [] as FFIType[], returns: "ptr" as const },
+	tui_get_last_error_code: { args: [] as FFIType[], returns: "i32" as const },
 	tui_clear_error: { args: [] as FFIType[], returns: "void" as const },
 	tui_set_debug: { args: ["u8"] as FFIType[], returns: "i32" as const },
+	tui_set_log_callback: {
+		args: ["function", "ptr"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_get_perf_counter: {
 		args: ["u32"] as FFIType[],
 		returns: "u64" as const,
 	},
+	tui_get_stats: { args: ["ptr"] as FFIType[], returns: "i32" as const },
 	tui_free_string: { args: ["ptr"] as FFIType[], returns: "void" as const },
 
 	// Table Widget (ADR-T27)
@@ -637,6 +977,14 @@ const symbols = {
 		args: ["ptr", "u32"] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_explain_layout_len: {
+		args: ["u32"] as FFIType[],
+		returns: "i32" as const,
+	},
+	tui_explain_layout: {
+		args: ["u32", "ptr", "u32"] as FFIType[],
+		returns: "i32" as const,
+	},
 	tui_debug_get_trace_len: {
 		args: ["u8"] as FFIType[],
 		returns: "i32" as const,
@@ -649,6 +997,9 @@ const symbols = {
 		args: [] as FFIType[],
 		returns: "i32" as const,
 	},
+	tui_set_frame_log: { args: ["u32"] as FFIType[], returns: "i32" as const },
+	tui_get_frame_log_len: { args: [] as FFIType[], returns: "i32" as const },
+	tui_dump_frame_log: { args: ["ptr", "u32"] as FFIType[], returns: "i32" as const },
 
 	// Native Text Substrate — TextBuffer (ADR-T37, TechSpec §4.4)
 	// Handle constructor: 0 = invalid handle / error (consult tui_get_last_error).
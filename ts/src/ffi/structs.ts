@@ -42,6 +42,195 @@ export function readEvent(buffer: ArrayBuffer): TuiEvent {
 	};
 }
 
+/**
+ * TuiTaggedEvent: 32 bytes, #[repr(C)], written by tui_next_event_tagged
+ *
+ * Layout:
+ *   offset 0:  TuiEvent event
+ *   offset 24: u64 tag
+ */
+export const TUI_TAGGED_EVENT_SIZE = 32;
+
+export interface TuiTaggedEvent extends TuiEvent {
+	tag: bigint;
+}
+
+export function allocTaggedEventBuffer(): ArrayBuffer {
+	return new ArrayBuffer(TUI_TAGGED_EVENT_SIZE);
+}
+
+export function readTaggedEvent(buffer: ArrayBuffer): TuiTaggedEvent {
+	const view = new DataView(buffer);
+	return {
+		...readEvent(buffer),
+		tag: view.getBigUint64(TUI_EVENT_SIZE, true),
+	};
+}
+
+/**
+ * TuiStats: 40 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset 0:  u64 layout_us
+ *   offset 8:  u64 render_us
+ *   offset 16: u32 diff_cells
+ *   offset 20: u32 node_count
+ *   offset 24: u32 dirty_count
+ *   offset 28: u32 animation_count
+ *   offset 32: u32 event_buffer_len
+ *   offset 36: 4 bytes padding
+ */
+export const TUI_STATS_SIZE = 40;
+
+export interface TuiStats {
+	layoutUs: bigint;
+	renderUs: bigint;
+	diffCells: number;
+	nodeCount: number;
+	dirtyCount: number;
+	animationCount: number;
+	eventBufferLen: number;
+}
+
+export function readStats(buffer: ArrayBuffer): TuiStats {
+	const view = new DataView(buffer);
+	return {
+		layoutUs: view.getBigUint64(0, true),
+		renderUs: view.getBigUint64(8, true),
+		diffCells: view.getUint32(16, true),
+		nodeCount: view.getUint32(20, true),
+		dirtyCount: view.getUint32(24, true),
+		animationCount: view.getUint32(28, true),
+		eventBufferLen: view.getUint32(32, true),
+	};
+}
+
+/**
+ * TuiCellUpdate: 20 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset 0:  u16 x
+ *   offset 2:  u16 y
+ *   offset 4:  u32 codepoint
+ *   offset 8:  u32 fg
+ *   offset 12: u32 bg
+ *   offset 16: u8  attrs
+ *   offset 17: 3 bytes padding
+ */
+export const TUI_CELL_UPDATE_SIZE = 20;
+
+export interface CellUpdate {
+	x: number;
+	y: number;
+	codepoint: number;
+	fg: number;
+	bg: number;
+	attrs: number;
+}
+
+export function readCellUpdates(buffer: ArrayBuffer, count: number): CellUpdate[] {
+	const view = new DataView(buffer);
+	const updates: CellUpdate[] = [];
+	for (let i = 0; i < count; i++) {
+		const base = i * TUI_CELL_UPDATE_SIZE;
+		updates.push({
+			x: view.getUint16(base, true),
+			y: view.getUint16(base + 2, true),
+			codepoint: view.getUint32(base + 4, true),
+			fg: view.getUint32(base + 8, true),
+			bg: view.getUint32(base + 12, true),
+			attrs: view.getUint8(base + 16),
+		});
+	}
+	return updates;
+}
+
+/**
+ * TuiCellInit: same 20-byte layout as TuiCellUpdate, read by
+ * tui_blit_cells with x/y relative to the blit origin.
+ */
+export function packCellInits(cells: CellUpdate[]): Uint8Array {
+	const bytes = new Uint8Array(Math.max(cells.length, 1) * TUI_CELL_UPDATE_SIZE);
+	const view = new DataView(bytes.buffer);
+	cells.forEach((cell, i) => {
+		const base = i * TUI_CELL_UPDATE_SIZE;
+		view.setUint16(base, cell.x, true);
+		view.setUint16(base + 2, cell.y, true);
+		view.setUint32(base + 4, cell.codepoint >>> 0, true);
+		view.setUint32(base + 8, cell.fg >>> 0, true);
+		view.setUint32(base + 12, cell.bg >>> 0, true);
+		view.setUint8(base + 16, cell.attrs);
+	});
+	return bytes;
+}
+
+/**
+ * TuiMatchRange: 8 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset 0: u32 start (grapheme offset)
+ *   offset 4: u32 end   (grapheme offset, exclusive)
+ */
+export const TUI_MATCH_RANGE_SIZE = 8;
+
+export interface MatchRange {
+	start: number;
+	end: number;
+}
+
+export function packMatchRanges(ranges: MatchRange[]): Uint8Array {
+	const bytes = new Uint8Array(Math.max(ranges.length, 1) * TUI_MATCH_RANGE_SIZE);
+	const view = new DataView(bytes.buffer);
+	ranges.forEach((range, i) => {
+		const offset = i * TUI_MATCH_RANGE_SIZE;
+		view.setUint32(offset, range.start >>> 0, true);
+		view.setUint32(offset + 4, range.end >>> 0, true);
+	});
+	return bytes;
+}
+
+export function readMatchRanges(bytes: Uint8Array, count: number): MatchRange[] {
+	const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+	const ranges: MatchRange[] = [];
+	for (let i = 0; i < count; i++) {
+		const offset = i * TUI_MATCH_RANGE_SIZE;
+		ranges.push({
+			start: view.getUint32(offset, true),
+			end: view.getUint32(offset + 4, true),
+		});
+	}
+	return ranges;
+}
+
+/**
+ * KeyframeStop: 12 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset 0: f32 time (normalized 0..1)
+ *   offset 4: u32 value (same encoding as tui_animate's target)
+ *   offset 8: u8  easing
+ *   offset 9: 3 bytes padding
+ */
+export const KEYFRAME_STOP_SIZE = 12;
+
+export interface KeyframeStop {
+	time: number;
+	value: number;
+	easing: number;
+}
+
+export function packKeyframeStops(stops: KeyframeStop[]): Uint8Array {
+	const bytes = new Uint8Array(Math.max(stops.length, 1) * KEYFRAME_STOP_SIZE);
+	const view = new DataView(bytes.buffer);
+	stops.forEach((stop, i) => {
+		const offset = i * KEYFRAME_STOP_SIZE;
+		view.setFloat32(offset, stop.time, true);
+		view.setUint32(offset + 4, stop.value >>> 0, true);
+		view.setUint8(offset + 8, stop.easing);
+	});
+	return bytes;
+}
+
 /**
  * Event type constants (matches TuiEventType enum)
  */
@@ -54,6 +243,10 @@ export const EventType = {
 	Change: 5,
 	Submit: 6,
 	Accessibility: 7,
+	Tick: 8,
+	AnimationEnd: 9,
+	AnimationStart: 10,
+	MenuSelect: 11,
 } as const;
 
 /**
@@ -104,6 +297,8 @@ export const NodeType = {
 	Overlay: 9,
 	Transcript: 10,
 	SplitPane: 11,
+	ComboBox: 12,
+	Image: 13,
 } as const;
 
 /**
@@ -120,3 +315,59 @@ export const AccessibilityRole = {
 	Region: 7,
 	Status: 8,
 } as const;
+
+/**
+ * TuiNodeLayout: 20 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset  0: u32 handle
+ *   offset  4: i32 x (absolute screen column)
+ *   offset  8: i32 y (absolute screen row)
+ *   offset 12: i32 w
+ *   offset 16: i32 h
+ */
+export const TUI_NODE_LAYOUT_SIZE = 20;
+
+export interface NodeLayout {
+	handle: number;
+	x: number;
+	y: number;
+	w: number;
+	h: number;
+}
+
+export function readNodeLayouts(bytes: Uint8Array, count: number): NodeLayout[] {
+	const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+	const layouts: NodeLayout[] = [];
+	for (let i = 0; i < count; i++) {
+		const offset = i * TUI_NODE_LAYOUT_SIZE;
+		layouts.push({
+			handle: view.getUint32(offset, true),
+			x: view.getInt32(offset + 4, true),
+			y: view.getInt32(offset + 8, true),
+			w: view.getInt32(offset + 12, true),
+			h: view.getInt32(offset + 16, true),
+		});
+	}
+	return layouts;
+}
+
+/**
+ * TuiGridTrack: 8 bytes, #[repr(C)]
+ *
+ * Layout:
+ *   offset 0: u32 kind (0 = auto, 1 = cells, 2 = fr, 3 = percent)
+ *   offset 4: f32 value
+ */
+export const TUI_GRID_TRACK_SIZE = 8;
+
+export function packGridTracks(tracks: Array<[number, number]>): Uint8Array {
+	const bytes = new Uint8Array(Math.max(tracks.length, 1) * TUI_GRID_TRACK_SIZE);
+	const view = new DataView(bytes.buffer);
+	tracks.forEach(([kind, value], i) => {
+		const offset = i * TUI_GRID_TRACK_SIZE;
+		view.setUint32(offset, kind, true);
+		view.setFloat32(offset + 4, value, true);
+	});
+	return bytes;
+}
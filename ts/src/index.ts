@@ -10,14 +10,17 @@
  */
 
 // Imperative API
-export { Kraken } from "./app";
-export type { RunOptions, TerminalCapabilities, TerminalInfo } from "./app";
+export { Kraken, LogLevel } from "./app";
+export type { ColorMode, RunOptions, TerminalCapabilities, TerminalInfo } from "./app";
 export { Widget } from "./widget";
 export { Box } from "./widgets/box";
 export { Text } from "./widgets/text";
 export { Input } from "./widgets/input";
 export { TextArea } from "./widgets/textarea";
 export { Select } from "./widgets/select";
+export { ComboBox } from "./widgets/combobox";
+export { Menu } from "./widgets/menu";
+export type { ComboBoxOptions } from "./widgets/combobox";
 export { ScrollBox } from "./widgets/scrollbox";
 export { Table } from "./widgets/table";
 export { List } from "./widgets/list";
@@ -25,15 +28,18 @@ export { Tabs } from "./widgets/tabs";
 export { Overlay } from "./widgets/overlay";
 export { TranscriptView } from "./widgets/transcript";
 export { SplitPane } from "./widgets/splitpane";
+export { Image } from "./widgets/image";
+export type { ImageOptions } from "./widgets/image";
 export type { SplitPaneOptions, SplitAxis } from "./widgets/splitpane";
 export type { TranscriptOptions, BlockKind, FollowModeStr } from "./widgets/transcript";
 export { applyReplayEvent } from "./widgets/transcript-adapters";
 export type { TranscriptReplayEvent } from "./widgets/transcript-adapters";
 export { Theme, DARK_THEME, LIGHT_THEME } from "./theme";
-export { KrakenError, checkResult } from "./errors";
-export { parseColor, parseDimension } from "./style";
+export { ErrorKind, KrakenError, checkResult } from "./errors";
+export { COLOR_DEFAULT, parseColor, parseDimension } from "./style";
 export { AnimProp, Easing } from "./animation-constants";
 export { EventType, KeyCode, Modifier, NodeType, AccessibilityRole } from "./ffi/structs";
+export type { CellUpdate, MatchRange, NodeLayout, TuiStats } from "./ffi/structs";
 export type { KrakenEvent, KrakenEventType } from "./events";
 
 // Dev Mode and Devtools (ADR-T34)
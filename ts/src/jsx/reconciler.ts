@@ -39,6 +39,7 @@ const WIDGET_MAP: Record<string, number> = {
 	Overlay: NodeType.Overlay,
 	Transcript: NodeType.Transcript,
 	SplitPane: NodeType.SplitPane,
+	ComboBox: NodeType.ComboBox,
 };
 
 // ---------------------------------------------------------------------------
@@ -291,9 +292,11 @@ function applyStaticProp(handle: number, type: string, prop: string, value: unkn
 			}
 			break;
 		}
-			case "gap":
-				checkResult(ffi.tui_set_layout_gap(handle, value as number, value as number));
+			case "gap": {
+				const [v, u] = parseDimension(value as string | number);
+				checkResult(ffi.tui_set_layout_gap_unit(handle, v, v, u));
 				break;
+			}
 			case "flexGrow":
 				checkResult(ffi.tui_set_layout_flex_factor(handle, 0, value as number));
 				break;
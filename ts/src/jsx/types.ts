@@ -60,7 +60,7 @@ export interface CommonProps extends EventHandlerProps {
 	height?: MaybeSignal<string | number>;
 	padding?: MaybeSignal<number | [number, number, number, number]>;
 	margin?: MaybeSignal<number | [number, number, number, number]>;
-	gap?: MaybeSignal<number>;
+	gap?: MaybeSignal<number | string>;
 	flexGrow?: MaybeSignal<number>;
 	flexShrink?: MaybeSignal<number>;
 	flexBasis?: MaybeSignal<number>;
@@ -106,6 +106,11 @@ export interface SelectProps extends CommonProps {
 	selected?: MaybeSignal<number>;
 }
 
+export interface ComboBoxProps extends CommonProps {
+	options?: MaybeSignal<string[]>;
+	maxLength?: number;
+}
+
 export interface ScrollBoxProps extends CommonProps {
 	scrollX?: MaybeSignal<number>;
 	scrollY?: MaybeSignal<number>;
@@ -206,6 +211,7 @@ export declare namespace JSX {
 		Overlay: OverlayProps;
 		Transcript: TranscriptProps;
 		SplitPane: SplitPaneProps;
+		ComboBox: ComboBoxProps;
 	}
 
 	interface ElementChildrenAttribute {
@@ -25,6 +25,13 @@ const NAMED_COLORS: Record<string, number> = {
 	"bright-white": 15,
 };
 
+/**
+ * Terminal-default color sentinel. Renders as SGR 39/49 so the terminal's
+ * own (possibly themed) fg/bg shows through. Distinct from RGB black
+ * (`0x01000000`, or `"#000000"`).
+ */
+export const COLOR_DEFAULT = 0x00000000;
+
 /**
  * Parse a color value into the u32 encoding.
  *
@@ -36,14 +43,14 @@ const NAMED_COLORS: Record<string, number> = {
  */
 export function parseColor(value: string | number): number {
 	if (typeof value === "number") {
-		if (value === 0) return 0; // Default
+		if (value === 0) return COLOR_DEFAULT;
 		if (value >= 0 && value <= 255) return 0x02000000 | value; // ANSI index
 		return value; // Already encoded
 	}
 
 	const lower = value.toLowerCase().trim();
 
-	if (lower === "default" || lower === "") return 0;
+	if (lower === "default" || lower === "") return COLOR_DEFAULT;
 
 	// Hex color
 	if (lower.startsWith("#")) {
@@ -148,6 +155,23 @@ export function parseAlignItems(val: string): number {
  * - `"50"` or `50` → (50, 1)
  * - `"auto"` → (0, 0)
  */
+/**
+ * Parse a grid track size to [kind, value].
+ * Numbers are fixed cells (kind 1); strings accept "auto" (0), "Nfr" (2)
+ * and "N%" (3).
+ */
+export function parseGridTrack(val: string | number): [number, number] {
+	if (typeof val === "number") return [1, val];
+
+	const s = val.trim().toLowerCase();
+	if (s === "auto") return [0, 0];
+	const n = parseFloat(s);
+	if (isNaN(n)) throw new Error(`Invalid grid track: ${val}`);
+	if (s.endsWith("fr")) return [2, n];
+	if (s.endsWith("%")) return [3, n];
+	return [1, n];
+}
+
 export function parseDimension(val: string | number): [number, number] {
 	if (typeof val === "number") return [val, 1]; // length in cells
 
@@ -160,3 +184,10 @@ export function parseDimension(val: string | number): [number, number] {
 	const n = parseFloat(s);
 	return [isNaN(n) ? 0 : n, 1];
 }
+
+/** Unit for padding, margin, inset and gap values. */
+export type LengthUnit = "cells" | "percent";
+
+export function lengthUnitCode(unit: LengthUnit): number {
+	return unit === "percent" ? 2 : 1;
+}
@@ -23,6 +23,7 @@ type NodeTypeInput =
 	| "select"
 	| "scrollBox"
 	| "textarea"
+	| "combobox"
 	| number;
 
 export class Theme {
@@ -230,6 +231,8 @@ function normalizeNodeType(nodeType: NodeTypeInput): number {
 			return NodeType.ScrollBox;
 		case "textarea":
 			return NodeType.TextArea;
+		case "combobox":
+			return NodeType.ComboBox;
 		default:
 			throw new TypeError(`Invalid node type: ${String(nodeType)}`);
 	}
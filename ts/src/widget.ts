@@ -5,11 +5,117 @@
  * Contains zero business logic per Architecture invariant.
  */
 
+import { JSCallback } from "bun:ffi";
 import { ffi } from "./ffi";
 import { checkResult } from "./errors";
-import { parseColor, parseDimension, parseFlexDirection } from "./style";
+import {
+	lengthUnitCode,
+	parseColor,
+	parseDimension,
+	parseFlexDirection,
+	parseGridTrack,
+	type LengthUnit,
+} from "./style";
+import {
+	packCellInits,
+	packGridTracks,
+	packKeyframeStops,
+	packMatchRanges,
+	readMatchRanges,
+	TUI_MATCH_RANGE_SIZE,
+	type CellUpdate,
+	type MatchRange,
+} from "./ffi/structs";
 import { Buffer } from "buffer";
 
+export type AnimProperty =
+	| "opacity"
+	| "fgColor"
+	| "bgColor"
+	| "borderColor"
+	| "positionX"
+	| "positionY";
+
+export type AnimEasing =
+	| "linear"
+	| "easeIn"
+	| "easeOut"
+	| "easeInOut"
+	| "cubicIn"
+	| "cubicOut"
+	| "elastic"
+	| "bounce";
+
+const ANIM_PROPERTIES: Record<string, number> = {
+	opacity: 0,
+	fgColor: 1,
+	bgColor: 2,
+	borderColor: 3,
+	positionX: 4,
+	positionY: 5,
+};
+
+const ANIM_EASINGS: Record<string, number> = {
+	linear: 0,
+	easeIn: 1,
+	easeOut: 2,
+	easeInOut: 3,
+	cubicIn: 4,
+	cubicOut: 5,
+	elastic: 6,
+	bounce: 7,
+};
+
+function animPropertyCode(property: string, fn: string): number {
+	const prop = ANIM_PROPERTIES[property];
+	if (prop === undefined) {
+		throw new TypeError(
+			`${fn}: invalid property "${property}". ` +
+				`Expected one of: opacity, fgColor, bgColor, borderColor, positionX, positionY`,
+		);
+	}
+	return prop;
+}
+
+function animEasingCode(easing: string, fn: string): number {
+	const code = ANIM_EASINGS[easing];
+	if (code === undefined) {
+		throw new TypeError(
+			`${fn}: invalid easing "${easing}". ` +
+				`Expected one of: linear, easeIn, easeOut, easeInOut, cubicIn, cubicOut, elastic, bounce`,
+		);
+	}
+	return code;
+}
+
+function checkAnimDuration(duration: number, fn: string): void {
+	if (!Number.isFinite(duration) || duration < 0) {
+		throw new TypeError(
+			`${fn}: duration must be a non-negative finite number, got ${duration}`,
+		);
+	}
+}
+
+/** Encode an animation value: f32 bits for numeric props, packed color otherwise. */
+function encodeAnimValue(property: string, value: number | string, fn: string): number {
+	if (property === "opacity" || property === "positionX" || property === "positionY") {
+		let numericValue: number;
+		if (typeof value === "number") {
+			numericValue = value;
+		} else {
+			numericValue = parseFloat(value);
+			if (isNaN(numericValue)) {
+				throw new TypeError(
+					`${fn}: ${property} target must be a number or numeric string, got "${value}"`,
+				);
+			}
+		}
+		const f32 = new Float32Array([numericValue]);
+		return new Uint32Array(f32.buffer)[0]!;
+	}
+	return parseColor(value);
+}
+
 export abstract class Widget {
 	public readonly handle: number;
 
@@ -29,6 +135,36 @@ export abstract class Widget {
 		return result === 1;
 	}
 
+	/** Attach an opaque host identifier; it rides on events targeting this node. */
+	setTag(tag: bigint | number): void {
+		checkResult(ffi.tui_set_node_tag(this.handle, BigInt(tag)), "setTag");
+	}
+
+	/** The host tag set by `setTag`, or 0n when untagged. */
+	getTag(): bigint {
+		return BigInt(ffi.tui_get_node_tag(this.handle));
+	}
+
+	/**
+	 * Pre-order walk of this subtree in a single native call. `visit` gets
+	 * each handle and its depth below this node; return `true` to stop.
+	 * The tree may be mutated from inside `visit`. Returns whether the walk
+	 * was stopped early.
+	 */
+	walkSubtree(visit: (handle: number, depth: number) => boolean | void): boolean {
+		const cb = new JSCallback(
+			(handle: number, depth: number) => (visit(handle, depth) ? 1 : 0),
+			{ args: ["u32", "u32", "ptr"], returns: "i32" },
+		);
+		try {
+			const result = ffi.tui_walk_subtree(this.handle, cb.ptr, null);
+			checkResult(result, "walkSubtree");
+			return result === 1;
+		} finally {
+			cb.close();
+		}
+	}
+
 	/** Append a child widget */
 	append(child: Widget): void {
 		checkResult(ffi.tui_append_child(this.handle, child.handle));
@@ -44,6 +180,14 @@ export abstract class Widget {
 		checkResult(ffi.tui_remove_child(this.handle, child.handle));
 	}
 
+	/** Replace an existing child with another widget, keeping its slot. */
+	replaceChild(oldChild: Widget, newChild: Widget): void {
+		checkResult(
+			ffi.tui_replace_child(this.handle, oldChild.handle, newChild.handle),
+			"replaceChild",
+		);
+	}
+
 	/** Get number of children */
 	childCount(): number {
 		const result = ffi.tui_get_child_count(this.handle);
@@ -84,15 +228,99 @@ export abstract class Widget {
 		checkResult(ffi.tui_set_layout_dimension(this.handle, 1, v, u));
 	}
 
-	setPadding(top: number, right: number, bottom: number, left: number): void {
+	/**
+	 * How this widget lays out its children. "none" removes it and its
+	 * subtree from layout entirely, unlike `setVisible(false)`.
+	 */
+	setDisplay(display: "flex" | "grid" | "none"): void {
+		const v = display === "grid" ? 1 : display === "none" ? 2 : 0;
+		checkResult(ffi.tui_set_display(this.handle, v));
+	}
+
+	/**
+	 * Take this widget and its subtree out of layout (siblings reflow into
+	 * the gap) without destroying them or forgetting flex/grid mode.
+	 */
+	setDisplayNone(enabled: boolean): void {
+		checkResult(ffi.tui_set_display_none(this.handle, enabled ? 1 : 0));
+	}
+
+	/**
+	 * Grid column or row tracks: numbers are cells, strings may be "auto",
+	 * "Nfr" or "N%". Requires `setDisplay("grid")`.
+	 */
+	setGridTemplate(axis: "columns" | "rows", tracks: Array<string | number>): void {
+		const packed = packGridTracks(tracks.map(parseGridTrack));
+		checkResult(
+			ffi.tui_set_grid_template(this.handle, axis === "rows" ? 1 : 0, packed, tracks.length),
+		);
+	}
+
+	/**
+	 * Place this widget between 1-based grid lines ([start, end]); negative
+	 * lines count from the end and 0 means auto.
+	 */
+	setGridPlacement(column: [number, number], row: [number, number]): void {
 		checkResult(
-			ffi.tui_set_layout_edges(this.handle, 0, top, right, bottom, left),
+			ffi.tui_set_grid_placement(this.handle, column[0], column[1], row[0], row[1]),
 		);
 	}
 
-	setMargin(top: number, right: number, bottom: number, left: number): void {
+	/**
+	 * JSON describing this widget's requested vs resolved size from the last
+	 * layout pass, including which constraints (`active`) decided it.
+	 */
+	explainLayout(): string {
+		const len = ffi.tui_explain_layout_len(this.handle);
+		checkResult(len, "explainLayout:len");
+		const buf = Buffer.alloc(len);
+		const written = ffi.tui_explain_layout(this.handle, buf, len);
+		checkResult(written, "explainLayout");
+		return buf.toString("utf-8", 0, written);
+	}
+
+	/** Keep width / height at `ratio` when only one axis is sized. 0 clears it. */
+	setAspectRatio(ratio: number): void {
+		checkResult(ffi.tui_set_layout_aspect_ratio(this.handle, ratio));
+	}
+
+	setPadding(
+		top: number,
+		right: number,
+		bottom: number,
+		left: number,
+		unit: LengthUnit = "cells",
+	): void {
 		checkResult(
-			ffi.tui_set_layout_edges(this.handle, 1, top, right, bottom, left),
+			ffi.tui_set_layout_edges_unit(
+				this.handle,
+				0,
+				top,
+				right,
+				bottom,
+				left,
+				lengthUnitCode(unit),
+			),
+		);
+	}
+
+	setMargin(
+		top: number,
+		right: number,
+		bottom: number,
+		left: number,
+		unit: LengthUnit = "cells",
+	): void {
+		checkResult(
+			ffi.tui_set_layout_edges_unit(
+				this.handle,
+				1,
+				top,
+				right,
+				bottom,
+				left,
+				lengthUnitCode(unit),
+			),
 		);
 	}
 
@@ -102,9 +330,39 @@ export abstract class Widget {
 		);
 	}
 
+	/**
+	 * Offsets from the parent's edges for an absolutely positioned widget
+	 * (see `setPositionType`). "auto" leaves that side unconstrained.
+	 */
+	setInset(
+		top: number | "auto",
+		right: number | "auto",
+		bottom: number | "auto",
+		left: number | "auto",
+		unit: LengthUnit = "cells",
+	): void {
+		const side = (v: number | "auto") => (v === "auto" ? Number.NaN : v);
+		checkResult(
+			ffi.tui_set_layout_edges_unit(
+				this.handle,
+				2,
+				side(top),
+				side(right),
+				side(bottom),
+				side(left),
+				lengthUnitCode(unit),
+			),
+		);
+	}
+
+	/**
+	 * Layout overflow. "hidden" and "scroll" also clip painted children to
+	 * this widget's content area.
+	 */
 	setOverflow(mode: "visible" | "hidden" | "scroll"): void {
 		const v = mode === "hidden" ? 1 : mode === "scroll" ? 2 : 0;
 		checkResult(ffi.tui_set_layout_flex(this.handle, 7, v));
+		checkResult(ffi.tui_set_style_overflow(this.handle, v === 0 ? 0 : 1));
 	}
 
 	setFlexGrow(value: number): void {
@@ -119,8 +377,11 @@ export abstract class Widget {
 		checkResult(ffi.tui_set_layout_flex_factor(this.handle, 2, value));
 	}
 
-	setGap(rowGap: number, columnGap: number): void {
-		checkResult(ffi.tui_set_layout_gap(this.handle, rowGap, columnGap));
+	/** Gaps between children; "percent" is relative to this widget's size. */
+	setGap(rowGap: number, columnGap: number, unit: LengthUnit = "cells"): void {
+		checkResult(
+			ffi.tui_set_layout_gap_unit(this.handle, rowGap, columnGap, lengthUnitCode(unit)),
+		);
 	}
 
 	// --- Visual style ---
@@ -164,6 +425,180 @@ export abstract class Widget {
 		checkResult(ffi.tui_set_style_opacity(this.handle, value));
 	}
 
+	/** Multiply this widget's opacity into all of its descendants. */
+	setOpacityCascade(enabled: boolean): void {
+		checkResult(ffi.tui_set_style_opacity_cascade(this.handle, enabled ? 1 : 0));
+	}
+
+	/**
+	 * How opacity below 1 fades this widget: "blend" mixes RGB colors toward
+	 * the background, "dim" sets the terminal's dim attribute instead, and
+	 * "both" blends with a dim fallback for palette colors.
+	 */
+	setOpacityMode(mode: "blend" | "dim" | "both"): void {
+		const map: Record<string, number> = { blend: 0, dim: 1, both: 2 };
+		checkResult(ffi.tui_set_opacity_mode(this.handle, map[mode] ?? 0));
+	}
+
+	/**
+	 * Base direction for plain text (Input and plain-format Text). "auto"
+	 * picks per line from the first strong character; RTL lines start at
+	 * the right edge. A Text with any RTL line wraps all of its lines at any
+	 * character instead of between words.
+	 */
+	setTextDirection(direction: "auto" | "ltr" | "rtl"): void {
+		const map: Record<string, number> = { auto: 0, ltr: 1, rtl: 2 };
+		checkResult(ffi.tui_set_text_direction(this.handle, map[direction] ?? 0));
+	}
+
+	/** Blank rows between lines of a Text or TextArea. 0 (default) packs them. */
+	setLineSpacing(extraRows: number): void {
+		checkResult(ffi.tui_set_line_spacing(this.handle, extraRows), "setLineSpacing");
+	}
+
+	/**
+	 * Blank columns after each character of a plain Text or Input, without
+	 * touching the content.
+	 */
+	setLetterSpacing(cells: number): void {
+		checkResult(ffi.tui_set_letter_spacing(this.handle, cells), "setLetterSpacing");
+	}
+
+	/**
+	 * Give every character of a plain Text or Input the same advance so table
+	 * columns align; `true` means one cell. Wider characters show as "…".
+	 */
+	setMonospaceGrid(cells: boolean | number): void {
+		const v = cells === true ? 1 : cells === false ? 0 : cells;
+		checkResult(ffi.tui_set_monospace_grid(this.handle, v), "setMonospaceGrid");
+	}
+
+	/** Case transform for drawing a plain Text or Input; content is unchanged. */
+	setTextTransform(mode: "none" | "upper" | "lower" | "title"): void {
+		const map: Record<string, number> = { none: 0, upper: 1, lower: 2, title: 3 };
+		checkResult(ffi.tui_set_text_transform(this.handle, map[mode] ?? 0), "setTextTransform");
+	}
+
+	/**
+	 * Find a literal substring in Text/TextArea content. Ranges are grapheme
+	 * offsets, end-exclusive, suitable for `setHighlights`.
+	 */
+	findText(pattern: string, options: { caseInsensitive?: boolean } = {}): MatchRange[] {
+		const encoded = new TextEncoder().encode(pattern);
+		const flag = options.caseInsensitive ? 1 : 0;
+		let capacity = 64;
+		for (;;) {
+			const out = new Uint8Array(capacity * TUI_MATCH_RANGE_SIZE);
+			const total = ffi.tui_text_find(
+				this.handle,
+				Buffer.from(encoded),
+				encoded.length,
+				flag,
+				out,
+				capacity,
+			);
+			checkResult(total, "findText");
+			if (total <= capacity) {
+				return readMatchRanges(out, total);
+			}
+			capacity = total;
+		}
+	}
+
+	/**
+	 * Highlight grapheme ranges of Text/TextArea content with the search
+	 * background. Cleared automatically when the content changes; pass an
+	 * empty array to clear explicitly.
+	 */
+	setHighlights(ranges: MatchRange[]): void {
+		checkResult(
+			ffi.tui_text_set_highlights(this.handle, packMatchRanges(ranges), ranges.length),
+			"setHighlights",
+		);
+	}
+
+	/**
+	 * Draw raw cells (e.g. half-block image art) into this widget at
+	 * `origin + (x, y)` relative to its top-left. Later blits overwrite
+	 * earlier ones at the same position; cells outside the widget are
+	 * clipped.
+	 */
+	blitCells(cells: CellUpdate[], originX = 0, originY = 0): void {
+		checkResult(
+			ffi.tui_blit_cells(this.handle, packCellInits(cells), cells.length, originX, originY),
+			"blitCells",
+		);
+	}
+
+	/** Remove all cells drawn by `blitCells`. */
+	clearCells(): void {
+		checkResult(ffi.tui_clear_cells(this.handle), "clearCells");
+	}
+
+	/**
+	 * Replace the node's content, splicing only the part that changed.
+	 * Renders the same as a full replace but keeps cursor and selection
+	 * positions that precede the change.
+	 */
+	setContentDiffed(text: string): void {
+		const encoded = new TextEncoder().encode(text);
+		checkResult(
+			ffi.tui_set_content_diffed(this.handle, Buffer.from(encoded), encoded.length),
+			"setContentDiffed",
+		);
+	}
+
+	/**
+	 * Append text to the node's content without resending the existing
+	 * content. Intended for streaming logs and chat output.
+	 */
+	appendContent(text: string): void {
+		const encoded = new TextEncoder().encode(text);
+		checkResult(
+			ffi.tui_content_append(this.handle, Buffer.from(encoded), encoded.length),
+			"appendContent",
+		);
+	}
+
+	/**
+	 * Drop the oldest lines so at most `maxLines` remain.
+	 * @returns Number of lines removed
+	 */
+	truncateLines(maxLines: number): number {
+		const removed = ffi.tui_content_truncate_lines(this.handle, maxLines);
+		checkResult(removed, "truncateLines");
+		return removed;
+	}
+
+	/** Insert text before grapheme `index`, clamped to the content end. */
+	insertTextAt(index: number, text: string): void {
+		const encoded = new TextEncoder().encode(text);
+		checkResult(
+			ffi.tui_content_insert_at(this.handle, index, Buffer.from(encoded), encoded.length),
+			"insertTextAt",
+		);
+	}
+
+	/** Delete graphemes `start..end` (end-exclusive, clamped). */
+	deleteTextRange(start: number, end: number): void {
+		checkResult(ffi.tui_content_delete_range(this.handle, start, end), "deleteTextRange");
+	}
+
+	/** Replace graphemes `start..end` (end-exclusive, clamped) with `text`. */
+	replaceTextRange(start: number, end: number, text: string): void {
+		const encoded = new TextEncoder().encode(text);
+		checkResult(
+			ffi.tui_content_replace_range(
+				this.handle,
+				start,
+				end,
+				Buffer.from(encoded),
+				encoded.length,
+			),
+			"replaceTextRange",
+		);
+	}
+
 	// --- Animation (v1) ---
 
 	/**
@@ -171,91 +606,17 @@ export abstract class Widget {
 	 * @returns Animation handle (for cancellation)
 	 */
 	animate(options: {
-		property:
-			| "opacity"
-			| "fgColor"
-			| "bgColor"
-			| "borderColor"
-			| "positionX"
-			| "positionY";
+		property: AnimProperty;
 		target: number | string;
 		duration: number;
-		easing?:
-			| "linear"
-			| "easeIn"
-			| "easeOut"
-			| "easeInOut"
-			| "cubicIn"
-			| "cubicOut"
-			| "elastic"
-			| "bounce";
+		easing?: AnimEasing;
 		/** If true, the animation reverses and repeats indefinitely (oscillates). */
 		loop?: boolean;
 	}): number {
-		const propMap: Record<string, number> = {
-			opacity: 0,
-			fgColor: 1,
-			bgColor: 2,
-			borderColor: 3,
-			positionX: 4,
-			positionY: 5,
-		};
-		const easingMap: Record<string, number> = {
-			linear: 0,
-			easeIn: 1,
-			easeOut: 2,
-			easeInOut: 3,
-			cubicIn: 4,
-			cubicOut: 5,
-			elastic: 6,
-			bounce: 7,
-		};
-
-		const prop = propMap[options.property];
-		if (prop === undefined) {
-			throw new TypeError(
-				`animate: invalid property "${options.property}". ` +
-					`Expected one of: opacity, fgColor, bgColor, borderColor, positionX, positionY`,
-			);
-		}
-		let targetBits: number;
-
-		if (
-			options.property === "opacity" ||
-			options.property === "positionX" ||
-			options.property === "positionY"
-		) {
-			let numericValue: number;
-			if (typeof options.target === "number") {
-				numericValue = options.target;
-			} else {
-				numericValue = parseFloat(options.target);
-				if (isNaN(numericValue)) {
-					throw new TypeError(
-						`animate: ${options.property} target must be a number or numeric string, got "${options.target}"`,
-					);
-				}
-			}
-			const f32 = new Float32Array([numericValue]);
-			targetBits = new Uint32Array(f32.buffer)[0]!;
-		} else {
-			targetBits = parseColor(options.target);
-		}
-
-		const easingKey = options.easing ?? "linear";
-		const easing = easingMap[easingKey];
-		if (easing === undefined) {
-			throw new TypeError(
-				`animate: invalid easing "${easingKey}". ` +
-					`Expected one of: linear, easeIn, easeOut, easeInOut, cubicIn, cubicOut, elastic, bounce`,
-			);
-		}
-
-		if (!Number.isFinite(options.duration) || options.duration < 0) {
-			throw new TypeError(
-				`animate: duration must be a non-negative finite number, got ${options.duration}`,
-			);
-		}
+		const prop = animPropertyCode(options.property, "animate");
+		const targetBits = encodeAnimValue(options.property, options.target, "animate");
+		const easing = animEasingCode(options.easing ?? "linear", "animate");
+		checkAnimDuration(options.duration, "animate");
 
 		const handle = ffi.tui_animate(
 			this.handle,
@@ -273,6 +634,94 @@ export abstract class Widget {
 		return handle;
 	}
 
+	/**
+	 * Animate through multiple stops in one handle (e.g. fade in, hold, fade
+	 * out). Each stop's `time` is normalized to [0, 1] of `duration`; its
+	 * `easing` shapes the segment that ends at that stop. The current value
+	 * is the implicit start. Looping restarts from the first stop.
+	 */
+	animateKeyframes(options: {
+		property: AnimProperty;
+		stops: { time: number; value: number | string; easing?: AnimEasing }[];
+		duration: number;
+		loop?: boolean;
+	}): number {
+		const prop = animPropertyCode(options.property, "animateKeyframes");
+		checkAnimDuration(options.duration, "animateKeyframes");
+		const packed = packKeyframeStops(
+			options.stops.map((stop) => ({
+				time: stop.time,
+				value: encodeAnimValue(options.property, stop.value, "animateKeyframes"),
+				easing: animEasingCode(stop.easing ?? "linear", "animateKeyframes"),
+			})),
+		);
+
+		const handle = ffi.tui_animate_keyframes(
+			this.handle,
+			prop,
+			packed,
+			options.stops.length,
+			options.duration,
+		);
+		if (handle === 0) {
+			throw new Error("Failed to start keyframe animation");
+		}
+		if (options.loop) {
+			ffi.tui_set_animation_looping(handle);
+		}
+		return handle;
+	}
+
+	/**
+	 * Spring an f32 property (opacity, positionX, positionY) toward `target`
+	 * instead of using a fixed duration. Completes once the spring settles.
+	 * Defaults give a quick, lightly damped motion.
+	 */
+	animateSpring(options: {
+		property: "opacity" | "positionX" | "positionY";
+		target: number;
+		stiffness?: number;
+		damping?: number;
+	}): number {
+		const prop = animPropertyCode(options.property, "animateSpring");
+		const handle = ffi.tui_animate_spring(
+			this.handle,
+			prop,
+			encodeAnimValue(options.property, options.target, "animateSpring"),
+			options.stiffness ?? 170,
+			options.damping ?? 26,
+		);
+		if (handle === 0) {
+			throw new Error("Failed to start spring animation");
+		}
+		return handle;
+	}
+
+	/**
+	 * FLIP step 1: record the on-screen position of every descendant.
+	 * Call before mutating the tree.
+	 */
+	capturePositions(): number {
+		const result = ffi.tui_capture_positions(this.handle);
+		checkResult(result, "capturePositions");
+		return result;
+	}
+
+	/**
+	 * FLIP step 2: after mutating the tree, animate moved descendants from
+	 * their captured position to their new one. Returns the number moved.
+	 */
+	playPositionTransitions(duration: number, easing: AnimEasing = "easeOut"): number {
+		checkAnimDuration(duration, "playPositionTransitions");
+		const result = ffi.tui_play_position_transitions(
+			this.handle,
+			duration,
+			animEasingCode(easing, "playPositionTransitions"),
+		);
+		checkResult(result, "playPositionTransitions");
+		return result;
+	}
+
 	/**
 	 * Cancel an active animation. The property retains its current value.
 	 */
@@ -281,18 +730,45 @@ export abstract class Widget {
 	}
 
 	/**
-	 * Start a built-in spinner animation that cycles through braille frames.
+	 * Start a built-in spinner animation that cycles through braille frames,
+	 * or through `frames` (e.g. ["|", "/", "-", "\\"]) when given.
 	 * @returns Animation handle (for cancellation)
 	 */
-	spinner(options: { interval?: number } = {}): number {
+	spinner(options: { interval?: number; frames?: string[] } = {}): number {
 		const interval = options.interval ?? 80;
-		const handle = ffi.tui_start_spinner(this.handle, interval);
+		let handle: number;
+		if (options.frames) {
+			const encoded = Buffer.from(options.frames.join("\n"), "utf-8");
+			handle = ffi.tui_start_spinner_frames(this.handle, encoded, encoded.length, interval);
+		} else {
+			handle = ffi.tui_start_spinner(this.handle, interval);
+		}
 		if (handle === 0) {
 			throw new Error("Failed to start spinner animation");
 		}
 		return handle;
 	}
 
+	/**
+	 * Start a color spinner: the content stays put while the foreground
+	 * color cycles through `colors` until cancelled.
+	 * @returns Animation handle (for cancellation)
+	 */
+	colorSpinner(colors: (string | number)[], options: { interval?: number } = {}): number {
+		const interval = options.interval ?? 120;
+		const palette = Uint32Array.from(colors, (color) => parseColor(color));
+		const handle = ffi.tui_start_color_spinner(
+			this.handle,
+			palette,
+			palette.length,
+			interval,
+		);
+		if (handle === 0) {
+			throw new Error("Failed to start color spinner animation");
+		}
+		return handle;
+	}
+
 	/**
 	 * Start a built-in progress animation (opacity 0→1 over the given duration).
 	 * @returns Animation handle (for cancellation)
@@ -328,6 +804,36 @@ export abstract class Widget {
 		return handle;
 	}
 
+	/**
+	 * Reveal this Text's content a few characters at a time. The content
+	 * itself stays complete; only what is drawn grows.
+	 * @returns Animation handle (for cancellation)
+	 */
+	typewriter(options: { charsPerSec?: number } = {}): number {
+		const handle = ffi.tui_animate_typewriter(this.handle, options.charsPerSec ?? 30);
+		if (handle === 0) {
+			throw new Error("Failed to start typewriter animation");
+		}
+		return handle;
+	}
+
+	/**
+	 * Scroll this Text sideways, ticker-style, whenever it is too wide for
+	 * the node. `gap` blank cells separate repetitions.
+	 * @returns Animation handle (for cancellation)
+	 */
+	marquee(options: { speed?: number; gap?: number } = {}): number {
+		const handle = ffi.tui_animate_marquee(
+			this.handle,
+			options.speed ?? 8,
+			options.gap ?? 4,
+		);
+		if (handle === 0) {
+			throw new Error("Failed to start marquee animation");
+		}
+		return handle;
+	}
+
 	/**
 	 * Start a built-in pulse animation (opacity oscillates indefinitely).
 	 * @returns Animation handle (for cancellation)
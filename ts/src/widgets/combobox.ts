@@ -0,0 +1,70 @@
+import { ffi } from "../ffi";
+import { NodeType } from "../ffi/structs";
+import { checkResult } from "../errors";
+import { Widget } from "../widget";
+import { Buffer } from "buffer";
+
+export interface ComboBoxOptions {
+	options?: string[];
+	width?: string | number;
+	height?: string | number;
+	maxLength?: number;
+	fg?: string | number;
+	bg?: string | number;
+	border?: "none" | "single" | "double" | "rounded" | "bold";
+}
+
+/**
+ * Editable Select: a text line on the first row whose content filters the
+ * option list below it. Up/Down highlight an option; Enter submits either
+ * the highlighted option (copied into the text) or the typed text.
+ */
+export class ComboBox extends Widget {
+	constructor(options: ComboBoxOptions = {}) {
+		const handle = ffi.tui_create_node(NodeType.ComboBox);
+		if (handle === 0) throw new Error("Failed to create ComboBox node");
+		super(handle);
+
+		if (options.width) this.setWidth(options.width);
+		if (options.height) this.setHeight(options.height);
+		if (options.maxLength) this.setMaxLength(options.maxLength);
+		if (options.fg) this.setForeground(options.fg);
+		if (options.bg) this.setBackground(options.bg);
+		if (options.border) this.setBorderStyle(options.border);
+		if (options.options) {
+			for (const opt of options.options) {
+				this.addOption(opt);
+			}
+		}
+	}
+
+	getValue(): string {
+		const len = ffi.tui_get_content_len(this.handle);
+		checkResult(len);
+		if (len === 0) return "";
+
+		const buf = Buffer.alloc(len + 1);
+		const written = ffi.tui_get_content(this.handle, buf, len + 1);
+		checkResult(written);
+		return buf.toString("utf-8", 0, written);
+	}
+
+	setMaxLength(max: number): void {
+		checkResult(ffi.tui_input_set_max_len(this.handle, max));
+	}
+
+	addOption(text: string): void {
+		const encoded = new TextEncoder().encode(text);
+		const buf = Buffer.from(encoded);
+		checkResult(ffi.tui_select_add_option(this.handle, buf, encoded.length));
+	}
+
+	clearOptions(): void {
+		checkResult(ffi.tui_select_clear_options(this.handle));
+	}
+
+	/** Index of the highlighted option in the full list, or -1. */
+	getHighlighted(): number {
+		return ffi.tui_select_get_selected(this.handle);
+	}
+}
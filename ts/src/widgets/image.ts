@@ -0,0 +1,48 @@
+import { ffi } from "../ffi";
+import { NodeType } from "../ffi/structs";
+import { checkResult } from "../errors";
+import { Widget } from "../widget";
+import { Buffer } from "buffer";
+
+export interface ImageOptions {
+	/** Escape bytes (sixel, kitty graphics or iTerm2) sized for the widget. */
+	payload?: Uint8Array;
+	/** Shown in a bordered placeholder when images aren't supported. */
+	alt?: string;
+	width?: string | number;
+	height?: string | number;
+	fg?: string | number;
+	bg?: string | number;
+	border?: "none" | "single" | "double" | "rounded" | "bold";
+}
+
+/**
+ * Reserves a box for an inline image. The payload is written at the box
+ * when the terminal reports `inlineImages`; otherwise the alt text is
+ * drawn inside a border.
+ */
+export class Image extends Widget {
+	constructor(options: ImageOptions = {}) {
+		const handle = ffi.tui_create_node(NodeType.Image);
+		if (handle === 0) throw new Error("Failed to create Image node");
+		super(handle);
+
+		if (options.width) this.setWidth(options.width);
+		if (options.height) this.setHeight(options.height);
+		if (options.fg) this.setForeground(options.fg);
+		if (options.bg) this.setBackground(options.bg);
+		if (options.border) this.setBorderStyle(options.border);
+		if (options.alt !== undefined) this.setAlt(options.alt);
+		if (options.payload) this.setPayload(options.payload);
+	}
+
+	setPayload(payload: Uint8Array): void {
+		const buf = Buffer.from(payload);
+		checkResult(ffi.tui_image_set_payload(this.handle, buf, buf.length));
+	}
+
+	setAlt(text: string): void {
+		const buf = Buffer.from(text, "utf-8");
+		checkResult(ffi.tui_set_content(this.handle, buf, buf.length));
+	}
+}
@@ -9,6 +9,7 @@ export interface InputOptions {
 	height?: string | number;
 	maxLength?: number;
 	mask?: string;
+	direction?: "auto" | "ltr" | "rtl";
 	fg?: string | number;
 	bg?: string | number;
 	border?: "none" | "single" | "double" | "rounded" | "bold";
@@ -24,6 +25,7 @@ export class Input extends Widget {
 		if (options.height) this.setHeight(options.height);
 		if (options.maxLength) this.setMaxLength(options.maxLength);
 		if (options.mask) this.setMask(options.mask);
+		if (options.direction) this.setTextDirection(options.direction);
 		if (options.fg) this.setForeground(options.fg);
 		if (options.bg) this.setBackground(options.bg);
 		if (options.border) this.setBorderStyle(options.border);
@@ -46,10 +48,24 @@ export class Input extends Widget {
 		return result;
 	}
 
+	/** Empty the content and reset the cursor and selection. */
+	clear(): void {
+		checkResult(ffi.tui_input_clear(this.handle), "Input.clear");
+	}
+
 	setCursor(position: number): void {
 		checkResult(ffi.tui_input_set_cursor(this.handle, position));
 	}
 
+	/**
+	 * Key that submits this input: a `KeyCode` plus an exact `Modifier`
+	 * mask, e.g. `setSubmitKey(KeyCode.Enter, Modifier.Ctrl)`. Defaults to
+	 * plain Enter; an unbound Enter does nothing.
+	 */
+	setSubmitKey(code: number, modifiers: number = 0): void {
+		checkResult(ffi.tui_input_set_submit_key(this.handle, code, modifiers), "setSubmitKey");
+	}
+
 	setMaxLength(max: number): void {
 		checkResult(ffi.tui_input_set_max_len(this.handle, max));
 	}
@@ -62,4 +78,68 @@ export class Input extends Widget {
 	clearMask(): void {
 		checkResult(ffi.tui_input_set_mask(this.handle, 0));
 	}
+
+	/** Show the real content of a masked input without dropping the mask. */
+	setMaskRevealed(revealed: boolean): void {
+		checkResult(
+			ffi.tui_input_set_mask_revealed(this.handle, revealed ? 1 : 0),
+			"setMaskRevealed",
+		);
+	}
+
+	/**
+	 * Show an IME composition string underlined at the cursor without
+	 * changing the value. Typing the committed text replaces it.
+	 */
+	setComposition(text: string): void {
+		const encoded = Buffer.from(text, "utf-8");
+		checkResult(
+			ffi.tui_input_set_composition(this.handle, encoded, encoded.length),
+			"setComposition",
+		);
+	}
+
+	/** Drop a pending IME composition without committing it. */
+	clearComposition(): void {
+		checkResult(ffi.tui_input_clear_composition(this.handle), "clearComposition");
+	}
+
+	/**
+	 * Constrain typing to a mask such as "###-###-####": `#` is a digit,
+	 * `A` a letter, anything else a separator inserted automatically.
+	 * An empty pattern removes the constraint.
+	 */
+	setPattern(pattern: string): void {
+		const encoded = Buffer.from(pattern, "utf-8");
+		checkResult(
+			ffi.tui_input_set_pattern(this.handle, encoded, encoded.length),
+			"setPattern",
+		);
+	}
+
+	/**
+	 * Numeric mode: only digits, one decimal point and a leading sign are
+	 * accepted, and Up/Down step the value by `step` within `[min, max]`.
+	 */
+	setNumeric(min: number, max: number, step: number = 1): void {
+		checkResult(ffi.tui_input_set_numeric(this.handle, min, max, step), "setNumeric");
+	}
+
+	/** Return to free-text input. */
+	clearNumeric(): void {
+		checkResult(ffi.tui_input_set_numeric(this.handle, 0, 0, 0), "clearNumeric");
+	}
+
+	/** The content parsed as a number, or null when it is not one. */
+	getNumeric(): number | null {
+		const out = new Float64Array(1);
+		return ffi.tui_input_get_numeric(this.handle, out) === 0 ? out[0]! : null;
+	}
+
+	/** Whether the content completely fills the pattern (true without one). */
+	isValid(): boolean {
+		const result = ffi.tui_input_is_valid(this.handle);
+		checkResult(result, "isValid");
+		return result === 1;
+	}
 }
@@ -0,0 +1,37 @@
+import { ffi } from "../ffi";
+import { checkResult } from "../errors";
+import { Widget } from "../widget";
+import { Buffer } from "buffer";
+
+/**
+ * Popup context menu drawn above the widget tree. Not part of the layout:
+ * open it with `showAt` (e.g. on a right-click mouse event). While open it
+ * captures keys and clicks; choosing an item emits a "menuSelect" event
+ * whose `selectedIndex` is the item index. Escape or an outside click
+ * closes it silently.
+ */
+export class Menu extends Widget {
+	constructor(items: string[] = []) {
+		const handle = ffi.tui_menu_create();
+		if (handle === 0) throw new Error("Failed to create Menu");
+		super(handle);
+		for (const item of items) {
+			this.addItem(item);
+		}
+	}
+
+	addItem(label: string): void {
+		const encoded = new TextEncoder().encode(label);
+		const buf = Buffer.from(encoded);
+		checkResult(ffi.tui_menu_add_item(this.handle, buf, encoded.length));
+	}
+
+	showAt(x: number, y: number): void {
+		checkResult(ffi.tui_menu_show_at(this.handle, x, y));
+	}
+
+	/** Close whichever menu is open. */
+	static close(): void {
+		checkResult(ffi.tui_menu_close());
+	}
+}
@@ -38,4 +38,12 @@ export class ScrollBox extends Widget {
 	scrollBy(dx: number, dy: number): void {
 		checkResult(ffi.tui_scroll_by(this.handle, dx, dy));
 	}
+
+	/**
+	 * Keep the view pinned to the bottom as content grows. Following pauses
+	 * while scrolled up and resumes at the bottom.
+	 */
+	setAutoscroll(enabled: boolean): void {
+		checkResult(ffi.tui_set_autoscroll(this.handle, enabled ? 1 : 0), "ScrollBox.setAutoscroll");
+	}
 }
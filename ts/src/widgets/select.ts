@@ -67,4 +67,24 @@ export class Select extends Widget {
 	getSelected(): number {
 		return ffi.tui_select_get_selected(this.handle);
 	}
+
+	/**
+	 * How options wider than the widget are shown. "marquee" scrolls the
+	 * selected option one grapheme per render; other rows are clipped.
+	 */
+	setOptionOverflow(mode: "clip" | "ellipsis" | "marquee"): void {
+		const map: Record<string, number> = { clip: 0, ellipsis: 1, marquee: 2 };
+		checkResult(ffi.tui_select_set_option_overflow(this.handle, map[mode] ?? 0));
+	}
+
+	/**
+	 * Show only options matching `pattern` (case-insensitive substring;
+	 * `*` and `?` wildcards). An empty string shows every option again.
+	 * `getSelected()` keeps returning indices into the full option list.
+	 */
+	setFilter(pattern: string): void {
+		const encoded = new TextEncoder().encode(pattern);
+		const buf = Buffer.from(encoded);
+		checkResult(ffi.tui_select_set_filter(this.handle, buf, encoded.length));
+	}
 }
@@ -8,8 +8,9 @@ export interface TextOptions {
 	content?: string;
 	width?: string | number;
 	height?: string | number;
-	format?: "plain" | "markdown" | "code";
+	format?: "plain" | "markdown" | "code" | "ansi";
 	language?: string;
+	direction?: "auto" | "ltr" | "rtl";
 	fg?: string | number;
 	bg?: string | number;
 	bold?: boolean;
@@ -25,18 +26,21 @@ export class Text extends Widget {
 
 		if (options.width) this.setWidth(options.width);
 		if (options.height) this.setHeight(options.height);
-		if (options.content) this.setContent(options.content);
+		// Format first: ANSI content keeps its escapes only once it is set.
 		if (options.format) {
 			const formatMap: Record<string, number> = {
 				plain: 0,
 				markdown: 1,
 				code: 2,
+				ansi: 3,
 			};
 			checkResult(
 				ffi.tui_set_content_format(this.handle, formatMap[options.format] ?? 0),
 			);
 		}
+		if (options.content) this.setContent(options.content);
 		if (options.language) this.setCodeLanguage(options.language);
+		if (options.direction) this.setTextDirection(options.direction);
 		if (options.fg) this.setForeground(options.fg);
 		if (options.bg) this.setBackground(options.bg);
 		if (options.bold) this.setBold(true);
@@ -68,4 +72,21 @@ export class Text extends Widget {
 			ffi.tui_set_code_language(this.handle, buf, encoded.length),
 		);
 	}
+
+	/**
+	 * Banner-size text via DEC line attributes. Double height needs two
+	 * Text rows with the same content, one "top" and one "bottom". Shown at
+	 * normal size on terminals without `decLineAttributes`.
+	 */
+	setLineScale(
+		scale: "normal" | "double-width" | "double-height-top" | "double-height-bottom",
+	): void {
+		const map: Record<string, number> = {
+			normal: 0,
+			"double-width": 1,
+			"double-height-top": 2,
+			"double-height-bottom": 3,
+		};
+		checkResult(ffi.tui_set_line_scale(this.handle, map[scale] ?? 0));
+	}
 }
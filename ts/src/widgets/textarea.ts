@@ -44,6 +44,46 @@ export class TextArea extends Widget {
 		return buf.toString("utf-8", 0, written);
 	}
 
+	/** Logical line `row` without its newline. Throws when out of range. */
+	getLine(row: number): string {
+		const len = ffi.tui_textarea_get_line_len(this.handle, row);
+		checkResult(len, "TextArea.getLine");
+		if (len === 0) return "";
+
+		const buf = Buffer.alloc(len + 1);
+		const written = ffi.tui_textarea_get_line(this.handle, row, buf, len + 1);
+		checkResult(written, "TextArea.getLine");
+		return buf.toString("utf-8", 0, written);
+	}
+
+	/** Replace line `row`; undoable, and the cursor shifts with the edit. */
+	setLine(row: number, text: string): void {
+		const buf = Buffer.from(text, "utf-8");
+		checkResult(
+			ffi.tui_textarea_set_line(this.handle, row, buf, buf.length),
+			"TextArea.setLine",
+		);
+	}
+
+	/** Insert a line before `row`; `row` equal to the line count appends. */
+	insertLine(row: number, text: string): void {
+		const buf = Buffer.from(text, "utf-8");
+		checkResult(
+			ffi.tui_textarea_insert_line(this.handle, row, buf, buf.length),
+			"TextArea.insertLine",
+		);
+	}
+
+	/** Delete line `row` and its newline. */
+	deleteLine(row: number): void {
+		checkResult(ffi.tui_textarea_delete_line(this.handle, row), "TextArea.deleteLine");
+	}
+
+	/** Empty the content and reset cursor, viewport, selection and undo. */
+	clear(): void {
+		checkResult(ffi.tui_textarea_clear(this.handle), "TextArea.clear");
+	}
+
 	setCursor(row: number, col: number): void {
 		checkResult(
 			ffi.tui_textarea_set_cursor(this.handle, row, col),
@@ -152,4 +192,21 @@ export class TextArea extends Widget {
 			"TextArea.setHistoryLimit",
 		);
 	}
+
+	/**
+	 * Keep only the last `maxLines` lines, dropping older ones after every
+	 * change. A view scrolled to the bottom keeps following new lines.
+	 * Pass 0 for unlimited.
+	 */
+	setMaxLines(maxLines: number): void {
+		checkResult(ffi.tui_textarea_set_max_lines(this.handle, maxLines), "TextArea.setMaxLines");
+	}
+
+	/**
+	 * Keep the view pinned to the last line as content grows. Following
+	 * pauses while scrolled up and resumes at the bottom.
+	 */
+	setAutoscroll(enabled: boolean): void {
+		checkResult(ffi.tui_set_autoscroll(this.handle, enabled ? 1 : 0), "TextArea.setAutoscroll");
+	}
 }